@@ -0,0 +1,8 @@
+create table tempVoiceRoom
+(
+    channel_id bigint not null primary key,
+    guild_id   bigint not null,
+    owner_id   bigint not null
+);
+
+create index temp_voice_room_guild_index on tempVoiceRoom (guild_id);
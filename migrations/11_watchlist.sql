@@ -0,0 +1,2 @@
+-- Watching a user is kept as an entry in their history, it ends when they are unwatched
+alter type historyType add value 'watch';
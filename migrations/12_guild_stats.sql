@@ -0,0 +1,12 @@
+-- Daily counters per guild, the stats collector adds to the bucket of the current day
+create table guildStats
+(
+    guild_id         bigint not null,
+    day              date   not null,
+    messages         int    not null default 0,
+    joins            int    not null default 0,
+    leaves           int    not null default 0,
+    commands         int    not null default 0,
+    automod_triggers int    not null default 0,
+    primary key (guild_id, day)
+);
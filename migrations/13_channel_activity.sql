@@ -0,0 +1,9 @@
+-- Messages per channel per hour, next to the daily guild counters so activity reports can show where and when
+create table channelActivity
+(
+    guild_id   bigint    not null,
+    channel_id bigint    not null,
+    hour       timestamp not null,
+    messages   int       not null default 0,
+    primary key (guild_id, channel_id, hour)
+);
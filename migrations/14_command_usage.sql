@@ -0,0 +1,11 @@
+-- Command uses per channel per day, to see which commands a guild actually uses
+create table commandUsage
+(
+    guild_id   bigint not null,
+    channel_id bigint not null,
+    day        date   not null,
+    command    text   not null,
+    uses       int    not null default 0,
+    failures   int    not null default 0,
+    primary key (guild_id, day, command, channel_id)
+);
@@ -0,0 +1,8 @@
+-- Snapshots of channel settings and overwrites, to create the same channel again later
+create table channelTemplate
+(
+    guild_id bigint not null,
+    name     text   not null,
+    template jsonb  not null,
+    primary key (guild_id, name)
+);
@@ -0,0 +1,9 @@
+-- Pinned messages that have been copied to a pin archive channel, so they only get archived once
+create table pinArchive
+(
+    message_id         bigint not null primary key,
+    guild_id           bigint not null,
+    archive_message_id bigint not null
+);
+
+create index pinArchive_guild_id on pinArchive (guild_id);
@@ -0,0 +1,13 @@
+-- Suggestions members made, numbered per guild. The content only lives in the posted message
+create table suggestion
+(
+    guild_id   bigint not null,
+    id         int    not null,
+    author_id  bigint not null,
+    channel_id bigint not null,
+    message_id bigint,
+    status     text   not null default 'open',
+    primary key (guild_id, id)
+);
+
+create index suggestion_author on suggestion (author_id);
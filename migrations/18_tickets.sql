@@ -0,0 +1,16 @@
+-- Private channels members opened to talk to staff, numbered per guild
+create table ticket
+(
+    guild_id      bigint      not null,
+    id            int         not null,
+    owner_id      bigint      not null,
+    channel_id    bigint,
+    claimed_by    bigint,
+    opened_at     timestamptz not null default now(),
+    closed_at     timestamptz,
+    -- The archive the transcript was stored as
+    transcript_id bigint,
+    primary key (guild_id, id)
+);
+
+create index ticket_channel on ticket (channel_id);
@@ -0,0 +1,29 @@
+-- Events members RSVP to, numbered per guild
+create table event
+(
+    guild_id    bigint      not null,
+    id          int         not null,
+    channel_id  bigint      not null,
+    message_id  bigint,
+    creator_id  bigint      not null,
+    title       text        not null,
+    description text,
+    starts_at   timestamptz not null,
+    -- When the members that are going get pinged, cleared once they are
+    remind_at   timestamptz,
+    primary key (guild_id, id)
+);
+
+create index event_reminder on event (remind_at);
+
+create table eventrsvp
+(
+    guild_id     bigint      not null,
+    event_id     int         not null,
+    user_id      bigint      not null,
+    status       text        not null,
+    responded_at timestamptz not null default now(),
+    primary key (guild_id, event_id, user_id)
+);
+
+create index eventrsvp_user on eventrsvp (user_id);
@@ -0,0 +1,25 @@
+-- RSS and Atom feeds whose new entries get posted in a channel, numbered per guild
+create table feed
+(
+    guild_id         bigint      not null,
+    id               int         not null,
+    channel_id       bigint      not null,
+    url              text        not null,
+    title            text,
+    template         text        not null,
+    interval_minutes int         not null,
+    next_poll_at     timestamptz not null,
+    primary key (guild_id, id)
+);
+
+create index feed_next_poll on feed (next_poll_at);
+
+-- Entries that were already posted (or were there before the feed was added), so they aren't posted again
+create table feedentry
+(
+    guild_id bigint      not null,
+    feed_id  int         not null,
+    entry_id text        not null,
+    seen_at  timestamptz not null default now(),
+    primary key (guild_id, feed_id, entry_id)
+);
@@ -0,0 +1,14 @@
+-- GitHub repositories whose webhook deliveries get relayed into a channel
+create table githubrepo
+(
+    guild_id   bigint not null,
+    -- owner/name, lowercase
+    repository text   not null,
+    channel_id bigint not null,
+    events     text[] not null,
+    -- Deliveries need to be signed with this, so nobody else can post in the channel
+    secret     text   not null,
+    primary key (guild_id, repository)
+);
+
+create index githubrepo_repository on githubrepo (repository);
@@ -0,0 +1,12 @@
+-- Endpoints external systems can post JSON to, rendered with the template into a channel, numbered per guild
+create table webhookendpoint
+(
+    guild_id   bigint not null,
+    id         int    not null,
+    name       text   not null,
+    channel_id bigint not null,
+    template   text   not null,
+    -- Sha256 of the token in the url as hex, the token itself is only ever shown to whoever made the endpoint
+    token_hash text   not null unique,
+    primary key (guild_id, id)
+);
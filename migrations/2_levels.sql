@@ -0,0 +1,9 @@
+create table userLevel
+(
+    guild_id bigint not null,
+    user_id  bigint not null,
+    xp       bigint not null default 0,
+    primary key (guild_id, user_id)
+);
+
+create index user_level_guild_xp_index on userLevel (guild_id, xp desc);
@@ -0,0 +1,10 @@
+create table archive
+(
+    id                bigint      not null primary key,
+    guild_id          bigint      not null,
+    channel_id        bigint      not null,
+    encrypted_content bytea       not null,
+    created           timestamptz not null default now()
+);
+
+create index archive_guild_index on archive (guild_id);
\ No newline at end of file
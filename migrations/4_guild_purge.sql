@@ -0,0 +1,5 @@
+create table guildPurge
+(
+    guild_id bigint      not null primary key,
+    purge_at timestamptz not null
+);
\ No newline at end of file
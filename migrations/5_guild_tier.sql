@@ -0,0 +1,7 @@
+create table guildTier
+(
+    guild_id   bigint      not null primary key,
+    tier       int2        not null,
+    granted_by bigint      not null,
+    granted_at timestamptz not null default now()
+);
@@ -0,0 +1,7 @@
+create table blockedUser
+(
+    user_id    bigint      not null primary key,
+    reason     text,
+    blocked_by bigint      not null,
+    blocked_at timestamptz not null default now()
+);
@@ -0,0 +1,7 @@
+create table profileRevert
+(
+    guild_id  bigint      not null primary key,
+    profile   text        not null,
+    revert_at timestamptz not null,
+    snapshot  jsonb       not null
+);
\ No newline at end of file
@@ -0,0 +1,7 @@
+create table voiceActivity
+(
+    guild_id  bigint      not null,
+    user_id   bigint      not null,
+    last_seen timestamptz not null,
+    primary key (guild_id, user_id)
+);
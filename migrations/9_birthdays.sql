@@ -0,0 +1,11 @@
+create table birthday
+(
+    guild_id       bigint not null,
+    user_id        bigint not null,
+    month          int2   not null,
+    day            int2   not null,
+    -- The year it was last announced in, so it's only announced once a year
+    announced_year int4,
+    role_given     bool   not null default false,
+    primary key (guild_id, user_id)
+);
@@ -25,7 +25,7 @@ pub struct CachedGuild {
     pub verification_level: VerificationLevel,
     pub default_message_notifications: DefaultMessageNotificationLevel,
     pub roles: RwLock<HashMap<RoleId, Arc<CachedRole>>>,
-    pub emoji: Vec<Arc<CachedEmoji>>,
+    pub emoji: RwLock<Vec<Arc<CachedEmoji>>>,
     pub features: Vec<String>,
     //same as region, will cause issues when they add one
     pub unavailable: bool,
@@ -41,6 +41,8 @@ pub struct CachedGuild {
     pub premium_tier: PremiumTier,
     pub premium_subscription_count: u64,
     pub preferred_locale: String,
+    /// The voice channel of every member that is in one.
+    pub voice_states: RwLock<HashMap<UserId, ChannelId>>,
 
     //own fields
     pub complete: AtomicBool,
@@ -49,7 +51,7 @@ pub struct CachedGuild {
 
 impl CachedGuild {
     pub async fn from_guild(guild: Guild) -> Self {
-        let mut cached_guild = CachedGuild {
+        let cached_guild = CachedGuild {
             id: guild.id,
             name: guild.name,
             icon: guild.icon,
@@ -62,7 +64,7 @@ impl CachedGuild {
             verification_level: guild.verification_level,
             default_message_notifications: guild.default_message_notifications,
             roles: RwLock::new(HashMap::new()),
-            emoji: vec![],
+            emoji: RwLock::new(vec![]),
             features: guild.features,
             unavailable: false,
             members: RwLock::new(HashMap::new()),
@@ -74,6 +76,13 @@ impl CachedGuild {
             premium_tier: guild.premium_tier,
             premium_subscription_count: guild.premium_subscription_count.unwrap_or(0),
             preferred_locale: guild.preferred_locale,
+            voice_states: RwLock::new(
+                guild
+                    .voice_states
+                    .iter()
+                    .filter_map(|state| state.channel_id.map(|channel_id| (state.user_id, channel_id)))
+                    .collect(),
+            ),
             complete: AtomicBool::new(false),
             member_count: AtomicU64::new(0),
         };
@@ -98,16 +107,19 @@ impl CachedGuild {
         }
 
         //emoji
-        for emoji in guild.emojis {
-            cached_guild.emoji.push(Arc::new(CachedEmoji::from(emoji)));
+        {
+            let mut emoji = cached_guild.emoji.write().await;
+            for e in guild.emojis {
+                emoji.push(Arc::new(CachedEmoji::from(e)));
+            }
+            emoji.sort_by(|a, b| a.id.cmp(&b.id));
         }
 
-        cached_guild.emoji.sort_by(|a, b| a.id.cmp(&b.id));
         cached_guild
     }
 
     pub async fn defrost(cache: &Cache, cold_guild: ColdStorageGuild) -> Self {
-        let mut guild = CachedGuild {
+        let guild = CachedGuild {
             id: cold_guild.id,
             name: cold_guild.name,
             icon: cold_guild.icon,
@@ -120,7 +132,7 @@ impl CachedGuild {
             verification_level: cold_guild.verification_level,
             default_message_notifications: cold_guild.default_message_notifications,
             roles: RwLock::new(HashMap::new()),
-            emoji: vec![],
+            emoji: RwLock::new(vec![]),
             features: vec![],
             unavailable: false,
             members: RwLock::new(HashMap::new()),
@@ -132,6 +144,7 @@ impl CachedGuild {
             premium_tier: cold_guild.premium_tier,
             premium_subscription_count: cold_guild.premium_subscription_count,
             preferred_locale: cold_guild.preferred_locale,
+            voice_states: RwLock::new(cold_guild.voice_states.into_iter().collect()),
             complete: AtomicBool::new(true),
             member_count: AtomicU64::new(cold_guild.members.len() as u64),
         };
@@ -158,15 +171,19 @@ impl CachedGuild {
                 channels.insert(channel.get_id(), Arc::new(channel));
             }
         }
-        for emoji in cold_guild.emoji {
-            guild.emoji.push(Arc::new(emoji));
+
+        {
+            let mut emoji = guild.emoji.write().await;
+            for e in cold_guild.emoji {
+                emoji.push(Arc::new(e));
+            }
+            emoji.sort_by(|a, b| a.id.cmp(&b.id));
         }
-        guild.emoji.sort_by(|a, b| a.id.cmp(&b.id));
         guild
     }
 
     pub async fn update(&self, other: &PartialGuild) -> Self {
-        let mut guild = CachedGuild {
+        let guild = CachedGuild {
             id: other.id,
             name: other.name.clone(),
             icon: other.icon.clone(),
@@ -179,7 +196,7 @@ impl CachedGuild {
             verification_level: other.verification_level,
             default_message_notifications: other.default_message_notifications,
             roles: RwLock::new(HashMap::new()),
-            emoji: self.emoji.clone(),
+            emoji: RwLock::new(self.emoji.read().await.clone()),
             features: other.features.clone(),
             unavailable: false,
             members: RwLock::new(HashMap::new()),
@@ -191,6 +208,7 @@ impl CachedGuild {
             premium_tier: other.premium_tier,
             premium_subscription_count: other.premium_subscription_count.unwrap_or(0),
             preferred_locale: other.preferred_locale.clone(),
+            voice_states: RwLock::new(self.voice_states.read().await.clone()),
             complete: AtomicBool::new(self.complete.load(Ordering::SeqCst)),
             member_count: AtomicU64::new(self.member_count.load(Ordering::SeqCst)),
         };
@@ -216,8 +234,6 @@ impl CachedGuild {
             }
         }
 
-        guild.emoji.sort_by(|a, b| a.id.cmp(&b.id));
-
         guild
     }
 
@@ -242,6 +258,17 @@ impl CachedGuild {
     pub async fn get_member(&self, user_id: &UserId) -> Option<Arc<CachedMember>> {
         self.members.read().await.get(user_id).cloned()
     }
+
+    /// Everyone that is in the voice channel.
+    pub async fn voice_channel_members(&self, channel_id: ChannelId) -> Vec<UserId> {
+        self.voice_states
+            .read()
+            .await
+            .iter()
+            .filter(|(_, channel)| **channel == channel_id)
+            .map(|(user_id, _)| *user_id)
+            .collect()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -293,6 +320,8 @@ pub struct ColdStorageGuild {
     pub premium_subscription_count: u64,
     #[serde(rename = "w", default, skip_serializing_if = "is_default")]
     pub preferred_locale: String,
+    #[serde(rename = "x", default, skip_serializing_if = "is_default")]
+    pub voice_states: Vec<(UserId, ChannelId)>,
 }
 
 impl ColdStorageGuild {
@@ -322,6 +351,13 @@ impl ColdStorageGuild {
             premium_tier: guild.premium_tier,
             premium_subscription_count: guild.premium_subscription_count,
             preferred_locale: guild.preferred_locale.clone(),
+            voice_states: guild
+                .voice_states
+                .read()
+                .await
+                .iter()
+                .map(|(user_id, channel_id)| (*user_id, *channel_id))
+                .collect(),
         };
         {
             let mut roles = guild.roles.write().await;
@@ -331,7 +367,7 @@ impl ColdStorageGuild {
             roles.clear();
         }
 
-        for emoji in &guild.emoji {
+        for emoji in guild.emoji.read().await.iter() {
             csg.emoji.push(emoji.as_ref().clone());
         }
 
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use prometheus::IntGauge;
+use twilight_model::id::GuildId;
+
+use crate::core::BotStats;
+
+/// Where a guild is in its lifecycle on this cluster.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GuildState {
+    /// Listed in a ready event, but the guild create hasn't arrived yet.
+    Expected,
+    /// Cached, but the members are still being chunked.
+    Partial,
+    Loaded,
+    /// Unavailable due to a discord outage.
+    Outage,
+}
+
+/// Keeps track of the state of every guild on this cluster, and the guild counters along with it.
+#[derive(Debug, Default)]
+pub struct LoadingState {
+    guilds: HashMap<GuildId, GuildState>,
+}
+
+impl LoadingState {
+    pub fn get(&self, guild_id: GuildId) -> Option<GuildState> {
+        self.guilds.get(&guild_id).copied()
+    }
+
+    /// Moves a guild to a new state, or forgets about it when it's gone. Returns the state it was in before.
+    pub fn set(&mut self, guild_id: GuildId, state: Option<GuildState>, stats: &BotStats) -> Option<GuildState> {
+        let old = match state {
+            Some(state) => self.guilds.insert(guild_id, state),
+            None => self.guilds.remove(&guild_id),
+        };
+
+        if let Some(gauge) = old.and_then(|old| counter(old, stats)) {
+            gauge.dec();
+        }
+        if let Some(gauge) = state.and_then(|state| counter(state, stats)) {
+            gauge.inc();
+        }
+        old
+    }
+
+    pub fn count(&self, state: GuildState) -> usize {
+        self.guilds.values().filter(|s| **s == state).count()
+    }
+
+    /// If there are no more guilds on their way, guilds in an outage don't count as they might be gone for a while.
+    pub fn is_done(&self) -> bool {
+        !self
+            .guilds
+            .values()
+            .any(|state| matches!(state, GuildState::Expected | GuildState::Partial))
+    }
+
+    pub fn clear(&mut self, stats: &BotStats) {
+        let guilds: Vec<GuildId> = self.guilds.keys().copied().collect();
+        for guild_id in guilds {
+            self.set(guild_id, None, stats);
+        }
+    }
+}
+
+fn counter(state: GuildState, stats: &BotStats) -> Option<&IntGauge> {
+    match state {
+        GuildState::Expected => None,
+        GuildState::Partial => Some(&stats.guild_counts.partial),
+        GuildState::Loaded => Some(&stats.guild_counts.loaded),
+        GuildState::Outage => Some(&stats.guild_counts.outage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_counters_in_sync() {
+        let stats = BotStats::new(0);
+        let mut loading = LoadingState::default();
+        let guild = GuildId(1);
+
+        loading.set(guild, Some(GuildState::Expected), &stats);
+        assert!(!loading.is_done());
+        loading.set(guild, Some(GuildState::Partial), &stats);
+        assert_eq!(stats.guild_counts.partial.get(), 1);
+        loading.set(guild, Some(GuildState::Loaded), &stats);
+        assert_eq!(stats.guild_counts.partial.get(), 0);
+        assert_eq!(stats.guild_counts.loaded.get(), 1);
+        assert!(loading.is_done());
+
+        assert_eq!(
+            loading.set(guild, Some(GuildState::Outage), &stats),
+            Some(GuildState::Loaded)
+        );
+        assert_eq!(stats.guild_counts.loaded.get(), 0);
+        assert_eq!(stats.guild_counts.outage.get(), 1);
+        assert!(loading.is_done());
+
+        loading.set(guild, None, &stats);
+        assert_eq!(stats.guild_counts.outage.get(), 0);
+    }
+}
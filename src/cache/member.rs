@@ -24,6 +24,9 @@ pub struct CachedMember {
     pub server_deafened: bool,
     #[serde(rename = "m", default, skip_serializing_if = "is_default")]
     pub server_muted: bool,
+    /// Still has to pass membership screening.
+    #[serde(rename = "p", default, skip_serializing_if = "is_default")]
+    pub pending: bool,
 }
 
 impl CachedMember {
@@ -36,6 +39,7 @@ impl CachedMember {
             boosting_since: member.premium_since.clone(),
             server_deafened: member.deaf,
             server_muted: member.mute,
+            pending: member.pending,
         }
     }
 
@@ -48,6 +52,7 @@ impl CachedMember {
             boosting_since: member.premium_since.clone(),
             server_deafened: self.server_deafened,
             server_muted: self.server_muted,
+            pending: member.pending,
         }
     }
 
@@ -68,6 +73,7 @@ impl CachedMember {
             boosting_since: self.boosting_since.clone(),
             server_deafened: self.server_deafened,
             server_muted: self.server_muted,
+            pending: self.pending,
         }
     }
 }
@@ -8,13 +8,14 @@ use twilight_gateway::Event;
 use twilight_model::channel::{Channel, GuildChannel, PrivateChannel};
 use twilight_model::gateway::payload::RequestGuildMembers;
 use twilight_model::gateway::presence::{ActivityType, Status};
-use twilight_model::guild::GuildStatus;
-use twilight_model::id::{ChannelId, EmojiId, GuildId, UserId};
+use twilight_model::guild::{GuildStatus, Permissions};
+use twilight_model::id::{ChannelId, EmojiId, GuildId, RoleId, UserId};
 use twilight_model::user::User;
 
 mod channel;
 mod emoji;
 mod guild;
+mod loading_state;
 mod member;
 mod role;
 mod user;
@@ -22,10 +23,13 @@ mod user;
 pub use channel::CachedChannel;
 pub use emoji::CachedEmoji;
 pub use guild::{CachedGuild, ColdStorageGuild};
+pub use loading_state::{GuildState, LoadingState};
 pub use member::CachedMember;
 pub use role::CachedRole;
 pub use user::CachedUser;
 
+use crate::core::escalation::{self, PermissionGrant};
+use crate::core::guild_emoji;
 use crate::core::{BotContext, BotStats, ShardState};
 use crate::database::redis::Redis;
 use crate::error::{ColdResumeError, DatabaseError};
@@ -46,7 +50,7 @@ pub struct Cache {
     //is this even possible to get accurate across multiple clusters?
     pub filling: AtomicBool,
 
-    pub unavailable_guilds: RwLock<Vec<GuildId>>,
+    pub loading: RwLock<LoadingState>,
     pub expected: RwLock<Vec<GuildId>>,
 
     pub stats: Arc<BotStats>,
@@ -64,7 +68,7 @@ impl Cache {
             users: RwLock::new(HashMap::new()),
             emoji: RwLock::new(HashMap::new()),
             filling: AtomicBool::new(true),
-            unavailable_guilds: RwLock::new(vec![]),
+            loading: RwLock::new(LoadingState::default()),
             expected: RwLock::new(vec![]),
             stats,
             missing_per_shard: RwLock::new(HashMap::new()),
@@ -78,6 +82,7 @@ impl Cache {
         self.emoji.write().await.clear();
         self.filling.store(true, Ordering::SeqCst);
         self.private_channels.write().await.clear();
+        self.loading.write().await.clear(&self.stats);
     }
 
     pub async fn update(&self, shard_id: u64, event: &Event, ctx: Arc<BotContext>) {
@@ -96,6 +101,15 @@ impl Cache {
                         }
                     }
                 }
+
+                let mut loading = self.loading.write().await;
+                for guild in &ready.guilds {
+                    let guild_id = match guild {
+                        GuildStatus::Online(guild) => guild.id,
+                        GuildStatus::Offline(guild) => guild.id,
+                    };
+                    loading.set(guild_id, Some(GuildState::Expected), &self.stats);
+                }
             }
             Event::GuildCreate(e) => {
                 trace!("Received guild create event for {} ({})", e.name, e.id);
@@ -104,17 +118,6 @@ impl Cache {
                 }
                 let guild = CachedGuild::from_guild(e.0.clone()).await;
 
-                {
-                    //fine to always grab a write lock here, we update from the main loop itself
-                    let mut unavailable = self.unavailable_guilds.write().await;
-                    if let Some(index) = unavailable.iter().position(|id| *id == guild.id) {
-                        //guild is available again
-                        unavailable.remove(index);
-                        info!("Guild \"{}\", ``{}`` became available again", guild.name, guild.id);
-                        self.stats.guild_counts.outage.dec();
-                    }
-                }
-
                 {
                     let mut guild_channels = self.guild_channels.write().await;
                     let gc = guild.channels.read().await;
@@ -126,23 +129,25 @@ impl Cache {
 
                 {
                     let mut emoji_cache = self.emoji.write().await;
-                    for emoji in &guild.emoji {
-                        emoji_cache.insert(emoji.id, emoji.clone());
+                    let emoji = guild.emoji.read().await;
+                    for e in emoji.iter() {
+                        emoji_cache.insert(e.id, e.clone());
                     }
-                    self.stats.emoji_count.add(guild.emoji.len() as i64);
+                    self.stats.emoji_count.add(emoji.len() as i64);
                 }
 
                 self.stats.role_count.add(guild.roles.read().await.len() as i64);
 
-                //we usually don't need this mutable but acquire a write lock regardless to prevent potential deadlocks
-                let mut list = self.unavailable_guilds.write().await;
-                if let Some(index) = list.iter().position(|id| id.0 == guild.id.0) {
-                    list.remove(index);
+                let previous = self
+                    .loading
+                    .write()
+                    .await
+                    .set(guild.id, Some(GuildState::Partial), &self.stats);
+                if previous == Some(GuildState::Outage) {
                     gearbot_info!("Guild {}, ``{}`` is available again!", guild.name, guild.id);
                 }
 
                 self.guilds.write().await.insert(e.id, Arc::new(guild));
-                self.stats.guild_counts.partial.inc();
             }
             Event::GuildUpdate(update) => {
                 trace!("Receive guild update for {} ({})", update.name, update.id);
@@ -162,14 +167,55 @@ impl Cache {
                     }
                 }
             }
-            Event::GuildEmojisUpdate(_) => {}
+            Event::GuildEmojisUpdate(event) => match self.get_guild(&event.guild_id).await {
+                Some(guild) => {
+                    let mut new = event
+                        .emojis
+                        .iter()
+                        .map(|emoji| Arc::new(CachedEmoji::from(emoji.clone())))
+                        .collect::<Vec<_>>();
+                    new.sort_by(|a, b| a.id.cmp(&b.id));
+                    let old = std::mem::replace(&mut *guild.emoji.write().await, new.clone());
+
+                    {
+                        let mut emoji_cache = self.emoji.write().await;
+                        for e in &old {
+                            emoji_cache.remove(&e.id);
+                        }
+                        for e in &new {
+                            emoji_cache.insert(e.id, e.clone());
+                        }
+                    }
+                    self.stats.emoji_count.sub(old.len() as i64);
+                    self.stats.emoji_count.add(new.len() as i64);
+
+                    let changes = guild_emoji::emoji_changes(&old, &new);
+                    if !changes.is_empty() {
+                        let ctx = ctx.clone();
+                        let guild_id = event.guild_id;
+                        tokio::spawn(async move { ctx.log_emoji_changes(guild_id, changes).await });
+                    }
+                }
+                None => gearbot_warn!(
+                    "Received an emoji update for guild {} but no such guild exists in cache",
+                    event.guild_id
+                ),
+            },
             Event::GuildDelete(guild) => {
                 if let Some(cached_guild) = self.get_guild(&guild.id).await {
-                    if guild.unavailable {
-                        self.guild_unavailable(&cached_guild).await;
-                    }
                     self.nuke_guild_cache(&cached_guild).await
                 }
+                if guild.unavailable {
+                    self.guild_unavailable(guild.id).await;
+                } else {
+                    self.loading.write().await.set(guild.id, None, &self.stats);
+                }
+            }
+            Event::UnavailableGuild(guild) => {
+                if let Some(cached_guild) = self.get_guild(&guild.id).await {
+                    self.nuke_guild_cache(&cached_guild).await
+                }
+                self.guild_unavailable(guild.id).await;
             }
             Event::MemberChunk(chunk) => {
                 trace!(
@@ -206,7 +252,7 @@ impl Cache {
                                 "Finished processing all chunks for {} ({}). {:?} guilds to go!",
                                 guild.name,
                                 guild.id.0,
-                                self.stats.guild_counts.partial.get()
+                                self.loading.read().await.count(GuildState::Partial)
                             );
                             guild.complete.store(true, Ordering::SeqCst);
                             let shard_missing = self
@@ -240,10 +286,13 @@ impl Cache {
                                     });
                                 }
                             }
-                            self.stats.guild_counts.partial.dec();
-                            self.stats.guild_counts.loaded.inc();
-                            // if we where at 1 we are now at 0
-                            if self.stats.guild_counts.partial.get() == 0
+                            let done = {
+                                let mut loading = self.loading.write().await;
+                                loading.set(guild.id, Some(GuildState::Loaded), &self.stats);
+                                loading.is_done()
+                            };
+                            ctx.update_health().await;
+                            if done
                                 && self.filling.load(Ordering::Relaxed)
                                 && ctx
                                     .shard_states
@@ -375,6 +424,19 @@ impl Cache {
                 }
             }
 
+            Event::VoiceStateUpdate(update) => {
+                let state = &update.0;
+                if let Some(guild_id) = state.guild_id {
+                    if let Some(guild) = self.get_guild(&guild_id).await {
+                        let mut voice_states = guild.voice_states.write().await;
+                        match state.channel_id {
+                            Some(channel_id) => voice_states.insert(state.user_id, channel_id),
+                            None => voice_states.remove(&state.user_id),
+                        };
+                    }
+                }
+            }
+
             Event::MemberAdd(event) => {
                 debug!("{} joined {}", event.user.id, event.guild_id);
                 match self.get_guild(&event.guild_id).await {
@@ -425,11 +487,12 @@ impl Cache {
                         }
                         let mut members = guild.members.write().await;
                         if members.contains_key(&event.user.id) {
-                            let g = {
+                            let (g, old_roles) = {
                                 let member = members.get(&event.user.id).unwrap();
-                                Arc::new(member.update(&*event))
+                                (Arc::new(member.update(&*event)), member.roles.clone())
                             };
                             members.insert(event.user.id, g);
+                            spot_member_grant(&ctx, &guild, event.user.id, &old_roles, &event.roles).await;
                         } else if guild.complete.load(Ordering::SeqCst) {
                             warn!(
                                 "Received a member update for an unknown member {} in guild {}",
@@ -499,11 +562,21 @@ impl Cache {
 
             Event::RoleUpdate(event) => match self.get_guild(&event.guild_id).await {
                 Some(guild) => {
-                    guild
+                    let old = guild
                         .roles
                         .write()
                         .await
                         .insert(event.role.id, Arc::new(CachedRole::from_role(&event.role)));
+                    if let Some(old) = old {
+                        let granted = escalation::granted_to_role(old.permissions, event.role.permissions);
+                        if !granted.is_empty() {
+                            let grant = PermissionGrant::Role {
+                                role_id: event.role.id,
+                                old_permissions: old.permissions,
+                            };
+                            review_grant(&ctx, event.guild_id, grant, granted);
+                        }
+                    }
                 }
                 None => gearbot_warn!(
                     "Received a role update event for guild {} but no such guild exists in cache",
@@ -526,14 +599,20 @@ impl Cache {
         };
     }
 
-    async fn guild_unavailable(&self, guild: &Arc<CachedGuild>) {
-        info!(
-            "Guild \"{}\", ``{}`` became unavailable due to an outage",
-            guild.name, guild.id
-        );
-        self.stats.guild_counts.outage.inc();
-        let mut list = self.unavailable_guilds.write().await;
-        list.push(guild.id);
+    async fn guild_unavailable(&self, guild_id: GuildId) {
+        let previous = self
+            .loading
+            .write()
+            .await
+            .set(guild_id, Some(GuildState::Outage), &self.stats);
+        if previous != Some(GuildState::Outage) {
+            info!("Guild ``{}`` became unavailable due to an outage", guild_id);
+        }
+    }
+
+    /// Guilds in an outage are missing from the cache and shouldn't be acted upon until they come back.
+    pub async fn in_outage(&self, guild_id: GuildId) -> bool {
+        self.loading.read().await.get(guild_id) == Some(GuildState::Outage)
     }
 
     async fn nuke_guild_cache(&self, guild: &Arc<CachedGuild>) {
@@ -566,20 +645,15 @@ impl Cache {
 
         {
             let mut emoji_cache = self.emoji.write().await;
-            for emoji in &guild.emoji {
-                emoji_cache.remove(&emoji.id);
+            let emoji = guild.emoji.read().await;
+            for e in emoji.iter() {
+                emoji_cache.remove(&e.id);
             }
+            self.stats.emoji_count.sub(emoji.len() as i64);
         }
-        self.stats.emoji_count.sub(guild.emoji.len() as i64);
         self.stats.role_count.sub(guild.roles.read().await.len() as i64);
 
         self.guilds.write().await.remove(&guild.id);
-
-        if !guild.complete.load(Ordering::SeqCst) {
-            self.stats.guild_counts.partial.dec();
-        } else {
-            self.stats.guild_counts.loaded.dec();
-        }
     }
 
     pub async fn insert_private_channel(&self, private_channel: &PrivateChannel) -> Arc<CachedChannel> {
@@ -701,7 +775,7 @@ impl Cache {
         for guild in self.guilds.read().await.values() {
             count += guild.members.read().await.len()
                 + guild.channels.read().await.len()
-                + guild.emoji.len()
+                + guild.emoji.read().await.len()
                 + guild.roles.read().await.len();
             list.push(guild.id);
             if count > 100000 {
@@ -887,20 +961,24 @@ impl Cache {
             }
 
             {
-                let mut emoji = self.emoji.write().await;
-                for e in &guild.emoji {
-                    emoji.insert(e.id, e.clone());
+                let mut emoji_cache = self.emoji.write().await;
+                let emoji = guild.emoji.read().await;
+                for e in emoji.iter() {
+                    emoji_cache.insert(e.id, e.clone());
                 }
+                self.stats.emoji_count.add(emoji.len() as i64);
             }
-            self.stats.emoji_count.add(guild.emoji.len() as i64);
 
             self.stats
                 .user_counts
                 .total
                 .add(guild.members.read().await.len() as i64);
 
+            self.loading
+                .write()
+                .await
+                .set(guild.id, Some(GuildState::Loaded), &self.stats);
             self.guilds.write().await.insert(guild.id, Arc::new(guild));
-            self.stats.guild_counts.loaded.inc();
         }
 
         Ok(())
@@ -932,6 +1010,56 @@ fn update_user_with_user(old: Arc<CachedUser>, new: Arc<CachedUser>) -> CachedUs
     }
 }
 
+/// Hands grants of dangerous permissions off to be reviewed, this is the only place that still knows what was
+/// there before.
+fn review_grant(ctx: &Arc<BotContext>, guild_id: GuildId, grant: PermissionGrant, granted: Permissions) {
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = ctx.review_permission_grant(guild_id, grant, granted).await {
+            gearbot_error!("Failed to review a permission grant in {}: {}", guild_id, e);
+        }
+    });
+}
+
+async fn spot_member_grant(
+    ctx: &Arc<BotContext>,
+    guild: &CachedGuild,
+    user_id: UserId,
+    old_roles: &[RoleId],
+    new_roles: &[RoleId],
+) {
+    let added = new_roles
+        .iter()
+        .filter(|role_id| !old_roles.contains(role_id))
+        .copied()
+        .collect::<Vec<RoleId>>();
+    if added.is_empty() {
+        return;
+    }
+
+    // Everyone has the permissions of the everyone role, it shares its id with the guild
+    let mut before = match guild.get_role(&RoleId(guild.id.0)).await {
+        Some(role) => role.permissions,
+        None => Permissions::empty(),
+    };
+    for role_id in old_roles {
+        if let Some(role) = guild.get_role(role_id).await {
+            before |= role.permissions;
+        }
+    }
+    let mut added_permissions = vec![];
+    for role_id in added {
+        if let Some(role) = guild.get_role(&role_id).await {
+            added_permissions.push((role_id, role.permissions));
+        }
+    }
+
+    let (roles, granted) = escalation::granted_by_roles(before, &added_permissions);
+    if !granted.is_empty() {
+        review_grant(ctx, guild.id, PermissionGrant::Member { user_id, roles }, granted);
+    }
+}
+
 fn is_default<T: Default + PartialEq>(t: &T) -> bool {
     t == &T::default()
 }
@@ -0,0 +1,67 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::utils::Emoji;
+
+pub async fn backup_now(ctx: CommandContext) -> CommandResult {
+    match ctx.bot_context.create_backup().await {
+        Ok(info) => {
+            let infractions = match info.infractions {
+                Some(count) => format!(" and {} infractions", count),
+                None => String::new(),
+            };
+            ctx.reply_raw(format!(
+                "{} Backed up {} guild configs{} to ``{}`` ({} bytes)",
+                Emoji::Yes.for_chat(),
+                info.configs,
+                infractions,
+                info.file_name,
+                info.size
+            ))
+            .await?;
+        }
+        Err(e) => {
+            ctx.reply_raw(format!("{} {}", Emoji::No.for_chat(), e)).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn backup_list(ctx: CommandContext) -> CommandResult {
+    let reply = match ctx.bot_context.list_backups() {
+        Ok(backups) if backups.is_empty() => format!("{} There are no backups yet", Emoji::Info.for_chat()),
+        Ok(backups) => format!(
+            "{} Available backups, oldest first:\n``{}``",
+            Emoji::Info.for_chat(),
+            backups.join("``\n``")
+        ),
+        Err(e) => format!("{} {}", Emoji::No.for_chat(), e),
+    };
+    ctx.reply_raw(reply).await?;
+    Ok(())
+}
+
+pub async fn backup_restore(mut ctx: CommandContext) -> CommandResult {
+    let file_name = ctx.parser.get_next()?.to_string();
+    let include_infractions = ctx.parser.get_next().map_or(false, |arg| arg == "infractions");
+
+    let (configs, infractions) = match ctx.bot_context.restore_backup(&file_name, include_infractions).await {
+        Ok(restored) => restored,
+        Err(e) => {
+            ctx.reply_raw(format!("{} {}", Emoji::No.for_chat(), e)).await?;
+            return Ok(());
+        }
+    };
+
+    let cleared = ctx.bot_context.clear_config_caches().await?;
+    ctx.reply_raw(format!(
+        "{} Restored {} guild configs and {} infractions from ``{}`` ({}/{} clusters reloaded their configs)",
+        Emoji::Yes.for_chat(),
+        configs,
+        infractions,
+        file_name,
+        cleared,
+        ctx.bot_context.scheme_info.cluster_count
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::utils::Emoji;
+
+pub async fn blocklist_add(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+    let reason = ctx.parser.get_remaining();
+    let reason = if reason.is_empty() { None } else { Some(reason.as_str()) };
+
+    if ctx.bot_context.global_admins.contains(&user.id) {
+        ctx.reply_raw(format!("{} Bot admins can't be blocked", Emoji::No.for_chat()))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.bot_context
+        .block_user(user.id, reason, ctx.message.author.id)
+        .await?;
+    let synced = ctx.bot_context.sync_blocklist().await?;
+    ctx.reply_raw(format!(
+        "{} {} can no longer use the bot anywhere ({}/{} clusters confirmed)",
+        Emoji::Yes.for_chat(),
+        user.full_name_with_id(),
+        synced,
+        ctx.bot_context.scheme_info.cluster_count
+    ))
+    .await?;
+    Ok(())
+}
+
+pub async fn blocklist_remove(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+
+    if !ctx.bot_context.unblock_user(user.id, ctx.message.author.id).await? {
+        ctx.reply_raw(format!(
+            "{} {} isn't on the blocklist",
+            Emoji::No.for_chat(),
+            user.full_name_with_id()
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let synced = ctx.bot_context.sync_blocklist().await?;
+    ctx.reply_raw(format!(
+        "{} {} can use the bot again ({}/{} clusters confirmed)",
+        Emoji::Yes.for_chat(),
+        user.full_name_with_id(),
+        synced,
+        ctx.bot_context.scheme_info.cluster_count
+    ))
+    .await?;
+    Ok(())
+}
@@ -1,12 +1,11 @@
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 
-use log::info;
-use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder};
 use twilight_model::id::{GuildId, UserId};
 
 use crate::core::CommandContext;
 use crate::error::CommandResult;
+use crate::utils::embeds::SafeEmbed;
 
 pub async fn check_cache(ctx: CommandContext) -> CommandResult {
     let mut counts: HashMap<UserId, Vec<GuildId>> = HashMap::new();
@@ -58,83 +57,24 @@ pub async fn check_cache(ctx: CommandContext) -> CommandResult {
     if out.is_empty() {
         out = String::from("All user mutual counts are correct")
     }
-    if out.len() > 2000 {
-        info!("{}", out);
-        out = String::from("Too long, see console");
-    }
-    let e = EmbedBuilder::new()
-        .field(
-            EmbedFieldBuilder::new(
-                "Unique users metric",
-                ctx.bot_context.stats.user_counts.unique.get().to_string(),
-            )?
-            .build(),
-        )
-        .field(
-            EmbedFieldBuilder::new(
-                "Unique users in cache",
-                ctx.bot_context.cache.users.read().await.len().to_string(),
-            )?
-            .build(),
-        )
+    let e = SafeEmbed::new()
         .field(
-            EmbedFieldBuilder::new(
-                "Total users metric",
-                ctx.bot_context.stats.user_counts.total.get().to_string(),
-            )?
-            .build(),
+            "Unique users metric",
+            ctx.bot_context.stats.user_counts.unique.get().to_string(),
         )
-        /*
         .field(
-            EmbedFieldBuilder::new(
-                "Total users in cache",
-                ctx.bot_context
-                    .cache
-                    .guilds
-                    .read()
-                    .await
-                    .values()
-                    .map(|guild| guild.members.read().await.len())
-                    .sum::<usize>()
-                    .to_string(),
-            )?
-            .build(),
+            "Unique users in cache",
+            ctx.bot_context.cache.users.read().await.len().to_string(),
         )
         .field(
-            EmbedFieldBuilder::new(
-                "Members without properly cached users",
-                ctx.bot_context
-                    .cache
-                    .guilds
-                    .read()
-                    .await
-                    .values()
-                    .map(|guild| {
-                        guild
-                            .members
-                            .read()
-                            .await
-                            .values()
-                            .map(|member| {
-                                if ctx.bot_context.cache.users.read().await.contains_key(&member.user_id) {
-                                    0
-                                } else {
-                                    1
-                                }
-                            })
-                            .sum::<usize>()
-                    })
-                    .sum::<usize>()
-                    .to_string(),
-            )?
-            .build(),
+            "Total users metric",
+            ctx.bot_context.stats.user_counts.total.get().to_string(),
         )
-         */
-        .field(EmbedFieldBuilder::new("Users who think they have no mutuals", think_no_servers.to_string())?.build())
-        .field(EmbedFieldBuilder::new("Users without mutual servers", no_servers.to_string())?.build())
-        .build()?;
+        .field("Users who think they have no mutuals", think_no_servers.to_string())
+        .field("Users without mutual servers", no_servers.to_string());
 
-    ctx.reply_raw_with_embed(out, e).await?;
+    ctx.reply_long(out).await?;
+    ctx.reply_safe_embed(e).await?;
 
     Ok(())
 }
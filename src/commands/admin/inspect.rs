@@ -0,0 +1,226 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use twilight_model::id::GuildId;
+
+use crate::cache::CachedGuild;
+use crate::core::CommandContext;
+use crate::database::diagnostics::{Diagnostic, DIAGNOSTICS};
+use crate::error::{CommandError, CommandResult, DatabaseError, ParseError};
+use crate::utils::Emoji;
+
+pub async fn inspect_guild(mut ctx: CommandContext) -> CommandResult {
+    let guild = match target_guild(&mut ctx).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let mut roles: Vec<Value> = guild
+        .roles
+        .read()
+        .await
+        .values()
+        .map(|role| {
+            json!({
+                "id": role.id.0.to_string(),
+                "name": role.name,
+                "position": role.position,
+                "permissions": role.permissions.bits(),
+                "managed": role.managed,
+            })
+        })
+        .collect();
+    roles.sort_by_key(|role| -role["position"].as_i64().unwrap_or_default());
+
+    let dump = json!({
+        "id": guild.id.0.to_string(),
+        "name": guild.name,
+        "owner_id": guild.owner_id.0.to_string(),
+        "region": guild.region,
+        "preferred_locale": guild.preferred_locale,
+        "unavailable": guild.unavailable,
+        "complete": guild.complete.load(Ordering::SeqCst),
+        "member_count": guild.member_count.load(Ordering::Relaxed),
+        "cached_members": guild.members.read().await.len(),
+        "channels": guild.channels.read().await.len(),
+        "emoji": guild.emoji.read().await.len(),
+        "features": guild.features,
+        "premium_subscription_count": guild.premium_subscription_count,
+        "roles": roles,
+    });
+
+    send_dump(
+        &ctx,
+        format!("guild {}", guild.id),
+        format!("guild-{}", guild.id),
+        &dump,
+    )
+    .await
+}
+
+pub async fn inspect_member(mut ctx: CommandContext) -> CommandResult {
+    let guild = ctx.get_guild()?.clone();
+    let member = ctx.parser.get_member_or(ctx.message.get_author_as_member()?).await?;
+    let user = ctx.bot_context.cache.get_user(member.user_id).await;
+
+    let dump = json!({
+        "user": user.map(|user| json!({
+            "id": user.id.0.to_string(),
+            "username": user.username,
+            "discriminator": user.discriminator,
+            "avatar": user.avatar,
+            "bot": user.bot_user,
+            "system": user.system_user,
+            "public_flags": user.public_flags.map(|flags| flags.bits()),
+            "mutual_servers": user.mutual_servers.load(Ordering::SeqCst),
+        })),
+        "member": {
+            "guild_id": guild.id.0.to_string(),
+            "nickname": member.nickname,
+            "roles": member.roles.iter().map(|role| role.0.to_string()).collect::<Vec<_>>(),
+            "joined_at": member.joined_at,
+            "boosting_since": member.boosting_since,
+            "server_deafened": member.server_deafened,
+            "server_muted": member.server_muted,
+        },
+    });
+
+    send_dump(
+        &ctx,
+        format!("member {} of guild {}", member.user_id, guild.id),
+        format!("member-{}-{}", guild.id, member.user_id),
+        &dump,
+    )
+    .await
+}
+
+pub async fn inspect_config(mut ctx: CommandContext) -> CommandResult {
+    let guild = match target_guild(&mut ctx).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    // Go to the database rather than through the config cache, that would create a config for guilds that have none
+    let config = match ctx.bot_context.datastore.get_guild_config(guild.id.0).await? {
+        Some(config) => config,
+        None => {
+            ctx.reply_raw(format!(
+                "{} Guild {} has no config stored",
+                Emoji::No.for_chat(),
+                guild.id
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+    let dump = serde_json::to_value(&config).map_err(DatabaseError::Deserializing)?;
+
+    send_dump(
+        &ctx,
+        format!("config of guild {}", guild.id),
+        format!("config-{}", guild.id),
+        &dump,
+    )
+    .await
+}
+
+pub async fn inspect_permissions(mut ctx: CommandContext) -> CommandResult {
+    let guild = ctx.get_guild()?.clone();
+    let config = ctx.get_config()?;
+    let member = ctx.parser.get_member_or(ctx.message.get_author_as_member()?).await?;
+
+    let permissions = ctx.bot_context.get_permissions_for(&guild, &member, &config).await;
+    let discord_permissions = ctx.bot_context.get_guild_permissions_for_member(&member, &guild).await;
+
+    let dump = json!({
+        "guild_id": guild.id.0.to_string(),
+        "user_id": member.user_id.0.to_string(),
+        "gearbot": {
+            "bits": permissions.bits(),
+            "flags": format!("{:?}", permissions),
+        },
+        "discord": {
+            "bits": discord_permissions.bits(),
+            "flags": format!("{:?}", discord_permissions),
+        },
+    });
+
+    send_dump(
+        &ctx,
+        format!("permissions of {} in guild {}", member.user_id, guild.id),
+        format!("permissions-{}-{}", guild.id, member.user_id),
+        &dump,
+    )
+    .await
+}
+
+pub async fn inspect_query(mut ctx: CommandContext) -> CommandResult {
+    if !ctx.parser.has_next() {
+        let available = DIAGNOSTICS
+            .iter()
+            .map(|diagnostic| format!("`{}`: {}", diagnostic.name, diagnostic.description))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ctx.reply_raw(format!("{} Available queries:\n{}", Emoji::Info.for_chat(), available))
+            .await?;
+        return Ok(());
+    }
+
+    let name = ctx.parser.get_next()?.to_string();
+    let diagnostic = Diagnostic::find(&name).ok_or(ParseError::WrongArgumentType(String::from("query")))?;
+    let rows = ctx.bot_context.datastore.run_diagnostic(diagnostic).await?;
+
+    let dump = json!({
+        "query": diagnostic.name,
+        "description": diagnostic.description,
+        "rows": rows
+            .iter()
+            .map(|(label, value)| json!({ "label": label, "value": value }))
+            .collect::<Vec<_>>(),
+    });
+
+    send_dump(
+        &ctx,
+        format!("`{}` ({} rows)", diagnostic.name, rows.len()),
+        format!("query-{}", diagnostic.name),
+        &dump,
+    )
+    .await
+}
+
+/// Takes a guild id from the arguments, falling back to the guild the command is used in.
+///
+/// Only guilds in the cache of this cluster can be inspected, if it's not there this lets the user know.
+async fn target_guild(ctx: &mut CommandContext) -> Result<Option<Arc<CachedGuild>>, CommandError> {
+    if !ctx.parser.has_next() {
+        return Ok(Some(ctx.get_guild()?.clone()));
+    }
+
+    let id = ctx
+        .parser
+        .get_next()?
+        .parse::<u64>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("guild id")))?;
+    let guild = ctx.bot_context.cache.get_guild(&GuildId(id)).await;
+    if guild.is_none() {
+        ctx.reply_raw(format!(
+            "{} Guild {} is not in the cache of this cluster",
+            Emoji::No.for_chat(),
+            id
+        ))
+        .await?;
+    }
+    Ok(guild)
+}
+
+async fn send_dump(ctx: &CommandContext, description: String, file_name: String, dump: &Value) -> CommandResult {
+    let data = serde_json::to_vec_pretty(dump).map_err(DatabaseError::Deserializing)?;
+    ctx.reply_raw_with_file(
+        format!("{} Inspected {}", Emoji::Info.for_chat(), description),
+        format!("inspect-{}.json", file_name),
+        data,
+    )
+    .await?;
+    Ok(())
+}
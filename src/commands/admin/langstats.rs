@@ -0,0 +1,64 @@
+use unic_langid::LanguageIdentifier;
+
+use crate::core::CommandContext;
+use crate::error::{CommandResult, ParseError};
+use crate::utils::Emoji;
+
+pub async fn langstats(mut ctx: CommandContext) -> CommandResult {
+    let coverage = ctx.bot_context.translations.coverage();
+
+    if ctx.parser.has_next() {
+        let language: LanguageIdentifier = ctx
+            .parser
+            .get_next()?
+            .parse()
+            .map_err(|_| ParseError::WrongArgumentType(String::from("language")))?;
+
+        match coverage.iter().find(|c| c.language == language) {
+            Some(c) if c.missing.is_empty() => {
+                ctx.reply_raw(format!(
+                    "{} ``{}`` is fully translated",
+                    Emoji::Yes.for_chat(),
+                    language
+                ))
+                .await?;
+            }
+            Some(c) => {
+                let mut output = format!(
+                    "{} ``{}`` is missing {} of {} strings, these fall back to another language:\n",
+                    Emoji::Info.for_chat(),
+                    language,
+                    c.missing.len(),
+                    c.total
+                );
+                for key in &c.missing {
+                    output += &format!("``{}``\n", key);
+                }
+                ctx.reply_long(output).await?;
+            }
+            None => {
+                ctx.reply_raw(format!(
+                    "{} ``{}`` is not a loaded language",
+                    Emoji::No.for_chat(),
+                    language
+                ))
+                .await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let mut output = format!("{} Translation coverage:\n", Emoji::Info.for_chat());
+    for c in &coverage {
+        output += &format!(
+            "``{}``: {}/{} ({:.1}%)\n",
+            c.language,
+            c.translated,
+            c.total,
+            c.percentage()
+        );
+    }
+    ctx.reply_raw(output).await?;
+
+    Ok(())
+}
@@ -1,5 +1,17 @@
+pub use backup::{backup_list, backup_now, backup_restore};
+pub use blocklist::{blocklist_add, blocklist_remove};
 pub use check_cache::check_cache;
+pub use inspect::{inspect_config, inspect_guild, inspect_member, inspect_permissions, inspect_query};
+pub use langstats::langstats;
 pub use restart::restart;
+pub use tier::{tier_get, tier_grant, tier_revoke};
+pub use whereis::whereis;
 
+mod backup;
+mod blocklist;
 mod check_cache;
+mod inspect;
+mod langstats;
 mod restart;
+mod tier;
+mod whereis;
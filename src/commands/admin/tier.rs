@@ -0,0 +1,67 @@
+use twilight_model::id::GuildId;
+
+use crate::core::{CommandContext, Tier};
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::utils::Emoji;
+
+pub async fn tier_get(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = target_guild(&mut ctx)?;
+    let tier = ctx.bot_context.get_tier(guild_id).await?;
+
+    ctx.reply_raw(format!(
+        "{} Guild {} is on the {} tier",
+        Emoji::Info.for_chat(),
+        guild_id,
+        tier
+    ))
+    .await?;
+    Ok(())
+}
+
+pub async fn tier_grant(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_string();
+    let tier = Tier::from_name(&name).ok_or(ParseError::WrongArgumentType(String::from("tier")))?;
+    let guild_id = target_guild(&mut ctx)?;
+
+    ctx.bot_context
+        .grant_tier(guild_id, tier, ctx.message.author.id)
+        .await?;
+    ctx.reply_raw(format!(
+        "{} Guild {} is now on the {} tier",
+        Emoji::Yes.for_chat(),
+        guild_id,
+        tier
+    ))
+    .await?;
+    Ok(())
+}
+
+pub async fn tier_revoke(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = target_guild(&mut ctx)?;
+
+    ctx.bot_context
+        .grant_tier(guild_id, Tier::Free, ctx.message.author.id)
+        .await?;
+    ctx.reply_raw(format!(
+        "{} Guild {} is back on the {} tier",
+        Emoji::Yes.for_chat(),
+        guild_id,
+        Tier::Free
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Takes a guild id from the arguments, falling back to the guild the command is used in.
+fn target_guild(ctx: &mut CommandContext) -> Result<GuildId, CommandError> {
+    if ctx.parser.has_next() {
+        let id = ctx
+            .parser
+            .get_next()?
+            .parse::<u64>()
+            .map_err(|_| ParseError::WrongArgumentType(String::from("guild id")))?;
+        Ok(GuildId(id))
+    } else {
+        Ok(ctx.get_guild()?.id)
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use crate::core::CommandContext;
+use crate::database::redis::api_handlers::api_structs::{ReplyData, Request};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils;
+use crate::utils::embeds::SafeEmbed;
+
+const WHEREIS_EMBED_COLOR: u32 = 0x00_cea2;
+const CLUSTER_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn whereis(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+
+    let scheme_info = &ctx.bot_context.scheme_info;
+    let cluster_count = scheme_info.cluster_count;
+    let responses = ctx
+        .bot_context
+        .datastore
+        .cache_pool
+        .query_clusters(
+            scheme_info.cluster_id,
+            Request::MutualGuilds(user.id),
+            cluster_count,
+            CLUSTER_REPLY_TIMEOUT,
+        )
+        .await?;
+
+    let args = FluArgs::with_capacity(1)
+        .add("user", user.full_name_with_id())
+        .generate();
+    let mut embed = SafeEmbed::new()
+        .title(ctx.translate_with_args(GearBotString::WhereisTitle, &args))
+        .color(WHEREIS_EMBED_COLOR);
+
+    let mut total = 0;
+    for response in &responses {
+        let guilds = match &response.data {
+            ReplyData::MutualGuildList(guilds) => guilds,
+            _ => continue,
+        };
+        total += guilds.len();
+
+        if guilds.is_empty() {
+            continue;
+        }
+
+        let mut list = String::new();
+        for guild in guilds {
+            list += &format!(
+                "{}{} (``{}``)\n",
+                if guild.owned { ":crown: " } else { "" },
+                utils::clean(&guild.name, true, true, true, false),
+                guild.id
+            );
+        }
+
+        let args = FluArgs::with_capacity(2)
+            .add("cluster", response.cluster)
+            .add("latency", response.latency.as_millis())
+            .generate();
+        embed = embed.field(ctx.translate_with_args(GearBotString::WhereisCluster, &args), list);
+    }
+
+    let missing = (0..cluster_count)
+        .filter(|cluster| !responses.iter().any(|response| response.cluster == *cluster))
+        .map(|cluster| cluster.to_string())
+        .collect::<Vec<String>>();
+
+    let args = FluArgs::with_capacity(3)
+        .add("total", total)
+        .add("replied", responses.len())
+        .add("clusters", cluster_count)
+        .generate();
+    let mut description = ctx.translate_with_args(GearBotString::WhereisSummary, &args);
+    if !missing.is_empty() {
+        let args = FluArgs::with_capacity(1).add("missing", missing.join(", ")).generate();
+        description += "\n";
+        description += &ctx.translate_with_args(GearBotString::WhereisMissing, &args);
+    }
+
+    ctx.reply_safe_embed(embed.description(description)).await?;
+
+    Ok(())
+}
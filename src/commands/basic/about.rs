@@ -2,12 +2,12 @@ use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use chrono::Utc;
-use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder};
 
 use crate::core::CommandContext;
 use crate::error::{CommandResult, OtherFailure};
 use crate::translation::{FluArgs, GearBotString};
-use crate::utils::{self, Emoji};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::Emoji;
 
 const ABOUT_EMBED_COLOR: u32 = 0x00_cea2;
 
@@ -33,15 +33,37 @@ pub async fn about(ctx: CommandContext) -> CommandResult {
         .values()
         .map(|info| info.latency().average().unwrap_or_default().as_millis())
         .sum::<u128>()
-        / ctx.bot_context.scheme_info.shards_per_cluster as u128;
+        / ctx.bot_context.scheme_info.shard_count() as u128;
 
-    let args = FluArgs::with_capacity(14)
+    let (memory, threads) = match stats.collect_sys_info() {
+        Some(info) => (
+            format!(
+                "{} MB",
+                ctx.format_number(info.resident_memory as f64 / (1024.0 * 1024.0))
+            ),
+            info.threads.to_string(),
+        ),
+        None => (String::from("unknown"), String::from("unknown")),
+    };
+
+    let database_latency = match ctx.bot_context.datastore.ping().await {
+        Ok(latency) => format!("{}ms", latency.as_millis()),
+        Err(e) => {
+            log::warn!("Failed to probe the database latency: {}", e);
+            String::from("unavailable")
+        }
+    };
+
+    let args = FluArgs::with_capacity(23)
         .add("gearDiamond", Emoji::GearDiamond.for_chat())
         .add("gearGold", Emoji::GearGold.for_chat())
         .add("gearIron", Emoji::GearIron.for_chat())
         .add("cluster_id", ctx.bot_context.scheme_info.cluster_id)
-        .add("uptime", utils::age(ctx.bot_context.start_time, Utc::now(), 4))
-        .add("start_time", ctx.bot_context.start_time.to_rfc2822())
+        .add(
+            "uptime",
+            ctx.format_duration(Utc::now().signed_duration_since(stats.start_time).num_seconds(), 4),
+        )
+        .add("start_time", stats.start_time.timestamp())
         .add("version", stats.version)
         .add("shards", ctx.bot_context.scheme_info.total_shards)
         .add("average_latency", avg_latency)
@@ -53,32 +75,26 @@ pub async fn about(ctx: CommandContext) -> CommandResult {
         .add("user_messages", stats.message_counts.user_messages.get())
         .add("messages_send", stats.message_counts.own_messages.get())
         .add("commands_executed", stats.total_command_counts.load(Ordering::Relaxed))
+        .add("gearStone", Emoji::GearStone.for_chat())
+        .add("channels", stats.channel_count.get())
+        .add("roles", stats.role_count.get())
+        .add("emoji", stats.emoji_count.get())
+        .add("memory", memory)
+        .add("threads", threads)
+        .add("database_latency", database_latency)
         .generate();
 
     let description = ctx.translate_with_args(GearBotString::AboutDescription, &args);
 
-    let embed = EmbedBuilder::new()
-        .description(description)?
-        .color(ABOUT_EMBED_COLOR)?
+    let embed = SafeEmbed::new()
+        .description(description)
+        .color(ABOUT_EMBED_COLOR)
         .timestamp(Utc::now().to_rfc3339())
-        .field(
-            EmbedFieldBuilder::new("Support Server", "[Click Here](https://discord.gg/PfwZmgU)")?
-                .inline()
-                .build(),
-        )
-        .field(
-            EmbedFieldBuilder::new("Website", "[Click Here](https://gearbot.rocks)")?
-                .inline()
-                .build(),
-        )
-        .field(
-            EmbedFieldBuilder::new("GitHub", "[Click Here](https://github.com/gearbot/GearBot)")?
-                .inline()
-                .build(),
-        )
-        .build()?;
+        .inline_field("Support Server", "[Click Here](https://discord.gg/PfwZmgU)")
+        .inline_field("Website", "[Click Here](https://gearbot.rocks)")
+        .inline_field("GitHub", "[Click Here](https://github.com/gearbot/GearBot)");
 
-    ctx.reply_embed(embed).await?;
+    ctx.reply_safe_embed(embed).await?;
 
     Ok(())
 }
@@ -0,0 +1,98 @@
+use crate::core::birthdays::parse_birthday;
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Shows the birthday the user registered on this server.
+pub async fn birthday(ctx: CommandContext) -> CommandResult {
+    if !birthdays_enabled(&ctx).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let (key, args) = match ctx
+        .bot_context
+        .datastore
+        .get_birthday(guild_id, ctx.message.author.id)
+        .await?
+    {
+        Some((month, day)) => (
+            GearBotString::BirthdayShow,
+            FluArgs::with_capacity(2)
+                .add("emoji", Emoji::Info.for_chat())
+                .add("date", format_birthday(month, day)),
+        ),
+        None => (
+            GearBotString::BirthdayNone,
+            FluArgs::with_capacity(1).add("emoji", Emoji::Info.for_chat()),
+        ),
+    };
+    ctx.reply(key, args.generate()).await?;
+    Ok(())
+}
+
+pub async fn birthday_set(mut ctx: CommandContext) -> CommandResult {
+    if !birthdays_enabled(&ctx).await? {
+        return Ok(());
+    }
+
+    let input = ctx.parser.get_next()?.to_string();
+    let (month, day) = match parse_birthday(&input) {
+        Some(date) => date,
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("date", input)
+                .generate();
+            ctx.reply(GearBotString::BirthdayInvalidDate, args).await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    ctx.bot_context
+        .datastore
+        .set_birthday(guild_id, ctx.message.author.id, month, day)
+        .await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("date", format_birthday(month, day))
+        .generate();
+    ctx.reply(GearBotString::BirthdaySet, args).await?;
+    Ok(())
+}
+
+pub async fn birthday_remove(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let removed = ctx
+        .bot_context
+        .datastore
+        .remove_birthday(guild_id, ctx.message.author.id)
+        .await?;
+
+    let (key, emoji) = if removed {
+        (GearBotString::BirthdayRemoved, Emoji::Yes)
+    } else {
+        (GearBotString::BirthdayNone, Emoji::Info)
+    };
+    let args = FluArgs::with_capacity(1).add("emoji", emoji.for_chat()).generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+/// Replies that the module is off if it is.
+async fn birthdays_enabled(ctx: &CommandContext) -> Result<bool, CommandError> {
+    if ctx.get_config()?.birthdays.enabled {
+        return Ok(true);
+    }
+
+    let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+    ctx.reply(GearBotString::BirthdaysDisabled, args).await?;
+    Ok(false)
+}
+
+fn format_birthday(month: u32, day: u32) -> String {
+    format!("{:02}-{:02}", month, day)
+}
@@ -3,21 +3,10 @@ use crate::error::CommandResult;
 use crate::translation::{FluArgs, GearBotString};
 use crate::utils;
 
-pub async fn coinflip(ctx: CommandContext) -> CommandResult {
-    let string_len: usize = ctx.parser.parts.iter().map(String::len).sum::<usize>() + ctx.parser.parts.len();
+pub async fn coinflip(mut ctx: CommandContext) -> CommandResult {
+    let thing_todo = ctx.parser.get_remaining();
 
-    let thing_todo = ctx
-        .parser
-        .parts
-        .iter()
-        .skip(1)
-        .fold(String::with_capacity(string_len), |mut s, part| {
-            s += part;
-            s.push(' ');
-            s
-        });
-
-    let thing_todo = if !thing_todo.is_empty() {
+    let thing_todo = if !thing_todo.trim().is_empty() {
         //todo: couple links to invoking user having embed perms
         utils::clean(&thing_todo, false, true, false, false)
     } else {
@@ -0,0 +1,131 @@
+use chrono::Utc;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, MessageId};
+
+use crate::core::events::{self, EventDetails, RsvpStatus};
+use crate::core::{CommandContext, Reactor};
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::{self, TITLE_LIMIT};
+use crate::utils::Emoji;
+
+/// Announces an event in this channel: ``event create 2021-06-01 20:00 Movie night | Bring snacks``. The start is in
+/// the timezone of the guild, the description after the bar is optional.
+pub async fn event_create(mut ctx: CommandContext) -> CommandResult {
+    let date = ctx.parser.get_next()?.to_string();
+    let time = ctx.parser.get_next()?.to_string();
+    let (title, description) = events::split_details(&ctx.parser.get_remaining()).ok_or(ParseError::MissingArgument)?;
+
+    let config = ctx.get_config()?;
+    let now = Utc::now();
+    let starts_at = match events::parse_start(&date, &time, config.tz()) {
+        Some(starts_at) => starts_at,
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("timezone", config.tz().name())
+                .generate();
+            ctx.reply(GearBotString::EventInvalidTime, args).await?;
+            return Ok(());
+        }
+    };
+    if starts_at <= now {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::EventInPast, args).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let channel_id = ctx.message.channel.get_id();
+    let details = EventDetails {
+        title: embeds::truncate(&title, TITLE_LIMIT),
+        description,
+        starts_at,
+    };
+    let remind_at = config
+        .events
+        .reminder_at(starts_at, now)
+        .map(|remind_at| remind_at.timestamp());
+    let datastore = &ctx.bot_context.datastore;
+    let event = datastore
+        .create_event(guild_id, channel_id, ctx.message.author.id, &details, remind_at)
+        .await?;
+
+    let embed = ctx.bot_context.event_embed(&config, &event, &[])?;
+    let posted = match ctx.bot_context.http.create_message(channel_id).embed(embed)?.await {
+        Ok(posted) => posted,
+        Err(e) => {
+            datastore.remove_event(guild_id, event.id).await?;
+            return Err(e.into());
+        }
+    };
+    datastore.set_event_message(guild_id, event.id, posted.id).await?;
+
+    for status in RsvpStatus::ALL.iter() {
+        if let Err(e) = ctx
+            .bot_context
+            .http
+            .create_reaction(channel_id, posted.id, status.emoji().to_reaction())
+            .await
+        {
+            ctx.bot_context.track_http_error(&e).await;
+        }
+    }
+    Reactor::new_event_rsvp(guild_id, event.id, event.starts_at)
+        .save(&ctx.bot_context, posted.id)
+        .await?;
+    Ok(())
+}
+
+/// Removes the announcement along with the RSVPs, only whoever created the event and server managers can do this.
+pub async fn event_cancel(mut ctx: CommandContext) -> CommandResult {
+    let id = ctx
+        .parser
+        .get_next()?
+        .trim_start_matches('#')
+        .parse::<i32>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("event number")))?;
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let event = match datastore.get_event(guild_id, id).await? {
+        Some(event) => event,
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("id", id)
+                .generate();
+            ctx.reply(GearBotString::EventUnknown, args).await?;
+            return Ok(());
+        }
+    };
+    if event.creator_id as u64 != ctx.message.author.id.0
+        && !ctx.author_has_guild_permissions(Permissions::MANAGE_GUILD).await
+    {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::EventNotYours, args).await?;
+        return Ok(());
+    }
+
+    datastore.remove_event(guild_id, id).await?;
+    if let Some(message_id) = event.message_id {
+        let message_id = MessageId(message_id as u64);
+        datastore.cache_pool.delete(&format!("reactor:{}", message_id)).await?;
+        // Might have been removed by hand already
+        if let Err(e) = ctx
+            .bot_context
+            .http
+            .delete_message(ChannelId(event.channel_id as u64), message_id)
+            .await
+        {
+            ctx.bot_context.track_http_error(&e).await;
+        }
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::EventCancelled, args).await?;
+    Ok(())
+}
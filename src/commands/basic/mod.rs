@@ -1,13 +1,21 @@
 pub use about::about;
+pub use birthday::{birthday, birthday_remove, birthday_set};
 pub use coinflip::coinflip;
+pub use events::{event_cancel, event_create};
 pub use help::help;
+pub use mydata::{mydata_delete, mydata_export};
 pub use ping::ping;
 pub use quote::quote;
+pub use suggestions::{suggest, suggestion_approve, suggestion_consider, suggestion_deny};
 pub use uid::uid;
 
 mod about;
+mod birthday;
 mod coinflip;
+mod events;
 mod help;
+mod mydata;
 mod ping;
 mod quote;
+mod suggestions;
 mod uid;
@@ -0,0 +1,45 @@
+use crate::core::CommandContext;
+use crate::error::{CommandResult, DatabaseError};
+use crate::translation::{FluArgs, GearBotString};
+
+pub async fn mydata_export(ctx: CommandContext) -> CommandResult {
+    let user_id = ctx.message.author.id;
+    let export = ctx.bot_context.export_user_data(user_id).await?;
+    let file = serde_json::to_vec_pretty(&export).map_err(DatabaseError::Serializing)?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("infractions", export.infractions.len())
+        .add("levels", export.levels.len())
+        .add("messages", export.messages.len())
+        .generate();
+    ctx.reply_with_file(
+        GearBotString::MyDataExport,
+        args,
+        format!("gearbot-data-{}.json", user_id),
+        file,
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn mydata_delete(mut ctx: CommandContext) -> CommandResult {
+    // There is no way back from this, so make sure it wasn't typed on a whim
+    if ctx.parser.get_next().ok() != Some("confirm") {
+        ctx.reply(GearBotString::MyDataDeleteConfirm, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let deletion = ctx.bot_context.delete_user_data(ctx.message.author.id).await?;
+
+    let args = FluArgs::with_capacity(4)
+        .add("messages", deletion.messages)
+        .add("levels", deletion.levels)
+        .add("infractions", deletion.infractions)
+        .add("kept", deletion.kept_infractions)
+        .generate();
+    ctx.reply(GearBotString::MyDataDeleted, args).await?;
+
+    Ok(())
+}
@@ -3,6 +3,9 @@ use std::time::{Duration, Instant};
 use crate::core::CommandContext;
 use crate::error::CommandResult;
 use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+
+const PING_EMBED_COLOR: u32 = 0x00_cea2;
 
 pub async fn ping(ctx: CommandContext) -> CommandResult {
     let start = Instant::now();
@@ -11,23 +14,63 @@ pub async fn ping(ctx: CommandContext) -> CommandResult {
 
     let rest_time = start.elapsed().as_millis();
 
-    let cluster_info = ctx.get_cluster_info();
+    let database_time = match ctx.bot_context.datastore.ping().await {
+        Ok(latency) => format!("{} ms", latency.as_millis()),
+        Err(e) => {
+            log::warn!("Failed to probe the database latency: {}", e);
+            ctx.translate(GearBotString::PingUnavailable)
+        }
+    };
 
-    // This is 0 until we get a heartbeat
-    let ws_time_avg = cluster_info
-        .into_iter()
-        .filter_map(|(_, info)| info.latency().average())
-        .sum::<Duration>()
-        .as_millis();
+    let mut cluster_info = ctx.get_cluster_info().into_iter().collect::<Vec<_>>();
+    cluster_info.sort_by_key(|(shard, _)| *shard);
+
+    // These are 0 until we get a heartbeat
+    let mut shard_latencies = String::new();
+    for (shard, info) in &cluster_info {
+        let latency = info.latency().average().unwrap_or_default().as_millis();
+        let key = if *shard == ctx.shard {
+            GearBotString::PingCurrentShardLatency
+        } else {
+            GearBotString::PingShardLatency
+        };
 
-    let args = FluArgs::with_capacity(2)
-        .add("rest", rest_time)
-        .add("latency", ws_time_avg)
-        .generate();
+        let args = FluArgs::with_capacity(2)
+            .add("shard", *shard)
+            .add("latency", latency)
+            .generate();
+        shard_latencies += &ctx.translate_with_args(key, &args);
+        shard_latencies.push('\n');
+    }
+
+    // Shards without a heartbeat yet would drag the average down, they are left out of it entirely
+    let heartbeats: Vec<Duration> = cluster_info
+        .iter()
+        .filter_map(|(_, info)| info.latency().average())
+        .collect();
+    if !heartbeats.is_empty() {
+        let ws_time_avg = heartbeats.iter().sum::<Duration>().as_millis() / heartbeats.len() as u128;
+        let args = FluArgs::with_capacity(1).add("latency", ws_time_avg).generate();
+        shard_latencies += &ctx.translate_with_args(GearBotString::PingClusterAverage, &args);
+    }
 
-    let edited_msg = ctx.translate_with_args(GearBotString::PingPong, &args);
+    let embeds = SafeEmbed::new()
+        .title(ctx.translate(GearBotString::PingPong))
+        .color(PING_EMBED_COLOR)
+        .field(ctx.translate(GearBotString::PingGateway), shard_latencies)
+        .inline_field(ctx.translate(GearBotString::PingRest), format!("{} ms", rest_time))
+        .inline_field(ctx.translate(GearBotString::PingDatabase), database_time)
+        .build()?;
 
-    ctx.update_message(edited_msg, sent_msg.channel_id, sent_msg.id).await?;
+    // Big clusters can have more shards than fit in one embed, the rest goes in follow up messages
+    let mut embeds = embeds.into_iter();
+    if let Some(embed) = embeds.next() {
+        ctx.update_message_embed(embed, sent_msg.channel_id, sent_msg.id)
+            .await?;
+    }
+    for embed in embeds {
+        ctx.reply_embed(embed).await?;
+    }
 
     Ok(())
 }
@@ -1,29 +1,48 @@
-use twilight_model::id::MessageId;
-
 use crate::core::CommandContext;
-use crate::error::{CommandResult, ParseError};
+use crate::error::{CommandError, CommandResult, ParseError};
 use crate::translation::{FluArgs, GearBotString};
 use crate::utils;
+use crate::utils::embeds::SafeEmbed;
+
+const QUOTE_EMBED_COLOR: u32 = 0x00_cea2;
 
 pub async fn quote(mut ctx: CommandContext) -> CommandResult {
-    let msg_id = ctx
-        .parser
-        .get_next()?
-        .parse::<u64>()
-        .map_err(|_| ParseError::MissingArgument)?;
-
-    let guild_id = ctx.get_guild().id;
-
-    match ctx.bot_context.fetch_user_message(MessageId(msg_id), guild_id).await? {
-        Some(msg) => {
-            let message = utils::clean(&msg.content, true, true, false, false);
-            ctx.reply_raw(message).await?;
-        }
-        None => {
+    let message = match ctx.get_message().await {
+        Ok(message) => message,
+        Err(CommandError::ParseError(ParseError::UnknownMessage)) => {
             let args = FluArgs::with_capacity(0).generate();
             ctx.reply(GearBotString::QuoteNotFound, args).await?;
+            return Ok(());
         }
+        Err(e) => return Err(e),
+    };
+
+    let author = ctx.get_user(message.author).await?;
+
+    let footer_args = FluArgs::with_capacity(1)
+        .add("name", ctx.message.author.full_name())
+        .generate();
+
+    let mut embed = SafeEmbed::new()
+        .author(author.full_name(), Some(author.avatar_url()))
+        .description(message.content.clone())
+        .color(QUOTE_EMBED_COLOR)
+        .timestamp(utils::snowflake_timestamp(message.id.0).to_rfc3339())
+        .field(
+            ctx.translate(GearBotString::QuoteJumpLink),
+            format!(
+                "[{}]({})",
+                ctx.translate(GearBotString::QuoteJumpLink),
+                message.jump_link()
+            ),
+        )
+        .footer(ctx.translate_with_args(GearBotString::QuoteFooter, &footer_args));
+
+    if let Some(image) = message.attachments.iter().find(|attachment| attachment.width.is_some()) {
+        embed = embed.image(image.url.clone());
     }
 
+    ctx.reply_safe_embed(embed).await?;
+
     Ok(())
 }
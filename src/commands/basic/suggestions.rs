@@ -0,0 +1,240 @@
+use twilight_model::channel::embed::Embed;
+use twilight_model::id::{ChannelId, MessageId, UserId};
+
+use crate::cache::CachedUser;
+use crate::core::suggestions::SuggestionStatus;
+use crate::core::CommandContext;
+use crate::database::structures::StoredSuggestion;
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::Emoji;
+
+pub async fn suggest(mut ctx: CommandContext) -> CommandResult {
+    let content = ctx.parser.get_remaining();
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let config = ctx.get_config()?;
+    let channel_id = match config.suggestions.target() {
+        Some(channel_id) => channel_id,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::SuggestionsDisabled, args).await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let id = datastore
+        .create_suggestion(guild_id, ctx.message.author.id, channel_id)
+        .await?;
+    let embed = suggestion_embed(&ctx, id, &ctx.message.author, content, SuggestionStatus::Open, None)?;
+
+    let posted = match ctx.bot_context.http.create_message(channel_id).embed(embed)?.await {
+        Ok(posted) => posted,
+        Err(e) => {
+            ctx.bot_context.track_http_error(&e).await;
+            datastore.remove_suggestion(guild_id, id).await?;
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("channel", channel_id.to_string())
+                .generate();
+            ctx.reply(GearBotString::SuggestionFailed, args).await?;
+            return Ok(());
+        }
+    };
+    datastore.set_suggestion_message(guild_id, id, posted.id).await?;
+
+    for reaction in config.suggestions.vote_reactions() {
+        if let Err(e) = ctx
+            .bot_context
+            .http
+            .create_reaction(channel_id, posted.id, reaction)
+            .await
+        {
+            ctx.bot_context.track_http_error(&e).await;
+        }
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(GearBotString::SuggestionSent, args).await?;
+    Ok(())
+}
+
+pub async fn suggestion_approve(ctx: CommandContext) -> CommandResult {
+    mark_suggestion(ctx, SuggestionStatus::Approved).await
+}
+
+pub async fn suggestion_deny(ctx: CommandContext) -> CommandResult {
+    mark_suggestion(ctx, SuggestionStatus::Denied).await
+}
+
+pub async fn suggestion_consider(ctx: CommandContext) -> CommandResult {
+    mark_suggestion(ctx, SuggestionStatus::Considered).await
+}
+
+/// Updates the status of a suggestion and its embed, anything after the number is the reason.
+async fn mark_suggestion(mut ctx: CommandContext, status: SuggestionStatus) -> CommandResult {
+    let id = ctx
+        .parser
+        .get_next()?
+        .trim_start_matches('#')
+        .parse::<i32>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("suggestion number")))?;
+    let reason = ctx.parser.get_remaining();
+    let reason = Some(reason.trim()).filter(|reason| !reason.is_empty());
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let suggestion = match datastore.get_suggestion(guild_id, id).await? {
+        Some(suggestion) => suggestion,
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("id", id)
+                .generate();
+            ctx.reply(GearBotString::SuggestionUnknown, args).await?;
+            return Ok(());
+        }
+    };
+    datastore.set_suggestion_status(guild_id, id, status.as_str()).await?;
+
+    update_suggestion_message(&ctx, &suggestion, status, reason).await?;
+    if ctx.get_config()?.suggestions.notify_author {
+        notify_suggester(&ctx, &suggestion, status, reason).await?;
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .add("status", status_name(&ctx, status))
+        .generate();
+    ctx.reply(GearBotString::SuggestionUpdated, args).await?;
+    Ok(())
+}
+
+/// Rebuilds the posted embed with the new status. The content is taken from the embed itself, it isn't stored
+/// anywhere else.
+async fn update_suggestion_message(
+    ctx: &CommandContext,
+    suggestion: &StoredSuggestion,
+    status: SuggestionStatus,
+    reason: Option<&str>,
+) -> Result<(), CommandError> {
+    let message_id = match suggestion.message_id {
+        Some(message_id) => MessageId(message_id as u64),
+        None => return Ok(()),
+    };
+    let channel_id = ChannelId(suggestion.channel_id as u64);
+
+    let message = match ctx.bot_context.http.message(channel_id, message_id).await {
+        Ok(Some(message)) => message,
+        // Removed by hand, nothing left to update
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            ctx.bot_context.track_http_error(&e).await;
+            log::debug!(
+                "Failed to fetch suggestion message {} in {}: {}",
+                message_id,
+                channel_id,
+                e
+            );
+            return Ok(());
+        }
+    };
+    let content = message
+        .embeds
+        .first()
+        .and_then(|embed| embed.description.clone())
+        .unwrap_or_default();
+
+    let author = ctx.get_user(UserId(suggestion.author_id as u64)).await?;
+    let embed = suggestion_embed(ctx, suggestion.id, &author, &content, status, reason)?;
+    ctx.update_message_embed(embed, channel_id, message_id).await?;
+    Ok(())
+}
+
+/// Lets the member know what happened to their suggestion, they might not accept DMs from us.
+async fn notify_suggester(
+    ctx: &CommandContext,
+    suggestion: &StoredSuggestion,
+    status: SuggestionStatus,
+    reason: Option<&str>,
+) -> Result<(), CommandError> {
+    let args = FluArgs::with_capacity(4)
+        .add("emoji", Emoji::Info.for_chat())
+        .add("id", suggestion.id)
+        .add("guild", ctx.get_guild()?.name.clone())
+        .add("status", status_name(ctx, status))
+        .generate();
+    let mut content = ctx.translate_with_args(GearBotString::SuggestionStatusDm, &args);
+    if let Some(reason) = reason {
+        content += &format!(
+            "\n**{}:** {}",
+            ctx.translate(GearBotString::SuggestionReasonField),
+            reason
+        );
+    }
+
+    let channel = match ctx.get_dm_for_user(UserId(suggestion.author_id as u64)).await {
+        Ok(channel) => channel,
+        Err(e) => {
+            ctx.bot_context.track_http_error(&e).await;
+            return Ok(());
+        }
+    };
+    if let Err(e) = ctx
+        .bot_context
+        .http
+        .create_message(channel.get_id())
+        .content(content)?
+        .await
+    {
+        ctx.bot_context.track_http_error(&e).await;
+    }
+    Ok(())
+}
+
+fn suggestion_embed(
+    ctx: &CommandContext,
+    id: i32,
+    author: &CachedUser,
+    content: &str,
+    status: SuggestionStatus,
+    reason: Option<&str>,
+) -> Result<Embed, CommandError> {
+    let title_args = FluArgs::with_capacity(1).add("id", id).generate();
+    let mut embed = SafeEmbed::new()
+        .author(author.full_name(), Some(author.avatar_url()))
+        .title(ctx.translate_with_args(GearBotString::SuggestionTitle, &title_args))
+        .description(content)
+        .color(status.color())
+        .inline_field(
+            ctx.translate(GearBotString::SuggestionStatusField),
+            status_name(ctx, status),
+        );
+    if let Some(reason) = reason {
+        embed = embed.inline_field(ctx.translate(GearBotString::SuggestionReasonField), reason);
+    }
+
+    // Suggestions always fit in a single embed, and build always gives at least one
+    Ok(embed.build()?.remove(0))
+}
+
+fn status_name(ctx: &CommandContext, status: SuggestionStatus) -> String {
+    let key = match status {
+        SuggestionStatus::Open => GearBotString::SuggestionOpen,
+        SuggestionStatus::Approved => GearBotString::SuggestionApproved,
+        SuggestionStatus::Denied => GearBotString::SuggestionDenied,
+        SuggestionStatus::Considered => GearBotString::SuggestionConsidered,
+    };
+    ctx.translate(key)
+}
@@ -1,14 +1,89 @@
+use chrono::Utc;
+use twilight_model::id::{ChannelId, GuildId, RoleId, UserId};
+
 use crate::core::CommandContext;
 use crate::error::{CommandResult, ParseError};
-use crate::utils::matchers;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::{self, matchers};
+
+const UID_EMBED_COLOR: u32 = 0x00_cea2;
 
 pub async fn uid(mut ctx: CommandContext) -> CommandResult {
-    let user_id = {
+    let snowflake = {
         let msg = ctx.parser.get_next()?;
-        matchers::get_mention(msg).ok_or(ParseError::MissingArgument)?
+        matchers::get_snowflake(msg).ok_or_else(|| ParseError::WrongArgumentType(String::from("snowflake")))?
+    };
+
+    let created_at = utils::snowflake_timestamp(snowflake);
+    let parts = utils::snowflake_parts(snowflake);
+
+    let args = FluArgs::with_capacity(5)
+        .add("created", created_at.format("%A %d %B %Y (%T)").to_string())
+        .add(
+            "age",
+            ctx.format_duration(Utc::now().signed_duration_since(created_at).num_seconds(), 2),
+        )
+        .add("worker", parts.worker_id)
+        .add("process", parts.process_id)
+        .add("increment", parts.increment)
+        .generate();
+    let description = ctx.translate_with_args(GearBotString::UidDescription, &args);
+
+    // A single id can belong to multiple things at once, e.g. a guild and its @everyone role
+    let mut matches = vec![];
+    let cache = &ctx.bot_context.cache;
+
+    if let Some(user) = cache.get_user(UserId(snowflake)).await {
+        let args = FluArgs::with_capacity(1)
+            .add("name", clean_name(&user.full_name()))
+            .generate();
+        matches.push(ctx.translate_with_args(GearBotString::UidMatchUser, &args));
+    }
+
+    // Channels and guilds are only looked up in the current guild, the names of other guilds are none of the author's
+    // business
+    let guild = ctx.get_guild().ok();
+    let channel = match &guild {
+        Some(guild) => guild.channels.read().await.get(&ChannelId(snowflake)).cloned(),
+        None => None,
     };
+    if let Some(channel) = channel {
+        let args = FluArgs::with_capacity(1)
+            .add("name", clean_name(channel.get_name()))
+            .generate();
+        matches.push(ctx.translate_with_args(GearBotString::UidMatchChannel, &args));
+    }
 
-    ctx.reply_raw(user_id).await?;
+    if let Some(role) = ctx.get_role(&RoleId(snowflake)).await {
+        let args = FluArgs::with_capacity(1).add("name", clean_name(&role.name)).generate();
+        matches.push(ctx.translate_with_args(GearBotString::UidMatchRole, &args));
+    }
+
+    if let Some(guild) = guild.filter(|guild| guild.id == GuildId(snowflake)) {
+        let args = FluArgs::with_capacity(1)
+            .add("name", clean_name(&guild.name))
+            .generate();
+        matches.push(ctx.translate_with_args(GearBotString::UidMatchGuild, &args));
+    }
+
+    let matches = if matches.is_empty() {
+        ctx.translate(GearBotString::UidNoMatches)
+    } else {
+        matches.join("\n")
+    };
+
+    let embed = SafeEmbed::new()
+        .title(snowflake.to_string())
+        .description(description)
+        .color(UID_EMBED_COLOR)
+        .field(ctx.translate(GearBotString::UidMatchesHeader), matches);
+
+    ctx.reply_safe_embed(embed).await?;
 
     Ok(())
 }
+
+fn clean_name(name: &str) -> String {
+    utils::clean(name, true, true, true, false)
+}
@@ -0,0 +1,42 @@
+use rand::seq::SliceRandom;
+
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils;
+
+pub async fn choose(mut ctx: CommandContext) -> CommandResult {
+    let remaining = ctx.parser.get_remaining();
+
+    // Options can either be comma separated or regular (quoted) arguments
+    let options: Vec<String> = if remaining.contains(',') {
+        remaining
+            .split(',')
+            .map(str::trim)
+            .filter(|option| !option.is_empty())
+            .map(String::from)
+            .collect()
+    } else {
+        let mut options = vec![];
+        while ctx.parser.has_next() {
+            options.push(ctx.parser.get_next()?.to_string());
+        }
+        options
+    };
+
+    if options.len() < 2 {
+        let args = FluArgs::with_capacity(0).generate();
+        ctx.reply(GearBotString::FunChooseNotEnough, args).await?;
+        return Ok(());
+    }
+
+    let choice = options.choose(&mut rand::thread_rng()).cloned().unwrap_or_default();
+
+    let args = FluArgs::with_capacity(1)
+        .add("choice", utils::clean(&choice, false, true, false, false))
+        .generate();
+
+    ctx.reply(GearBotString::FunChooseResult, args).await?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use rand::seq::SliceRandom;
+
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+
+const ANSWERS: [GearBotString; 10] = [
+    GearBotString::FunEightBallCertain,
+    GearBotString::FunEightBallNoDoubt,
+    GearBotString::FunEightBallDefinitely,
+    GearBotString::FunEightBallOutlookGood,
+    GearBotString::FunEightBallHazy,
+    GearBotString::FunEightBallAskLater,
+    GearBotString::FunEightBallCannotPredict,
+    GearBotString::FunEightBallNo,
+    GearBotString::FunEightBallSourcesNo,
+    GearBotString::FunEightBallDoubtful,
+];
+
+pub async fn eightball(mut ctx: CommandContext) -> CommandResult {
+    if ctx.parser.get_remaining().trim().is_empty() {
+        let args = FluArgs::with_capacity(0).generate();
+        ctx.reply(GearBotString::FunEightBallNoQuestion, args).await?;
+        return Ok(());
+    }
+
+    let answer = *ANSWERS
+        .choose(&mut rand::thread_rng())
+        .unwrap_or(&GearBotString::FunEightBallHazy);
+
+    let args = FluArgs::with_capacity(0).generate();
+    ctx.reply(answer, args).await?;
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub use choose::choose;
+pub use eightball::eightball;
+pub use roll::roll;
+
+mod choose;
+mod eightball;
+mod roll;
@@ -0,0 +1,33 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::dice::DiceRoll;
+
+/// Past this many dice, only the total is shown to keep the reply readable.
+const MAX_SHOWN_ROLLS: usize = 20;
+
+pub async fn roll(mut ctx: CommandContext) -> CommandResult {
+    let dice = ctx.parser.get_dice_or(DiceRoll::default())?;
+    let outcome = dice.roll();
+
+    let rolls = if outcome.rolls.len() > MAX_SHOWN_ROLLS {
+        String::from("...")
+    } else {
+        outcome
+            .rolls
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let args = FluArgs::with_capacity(3)
+        .add("expression", dice.to_string())
+        .add("rolls", rolls)
+        .add("total", outcome.total)
+        .generate();
+
+    ctx.reply(GearBotString::FunRollResult, args).await?;
+
+    Ok(())
+}
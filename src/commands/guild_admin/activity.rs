@@ -0,0 +1,158 @@
+use chrono::TimeZone;
+use twilight_model::id::ChannelId;
+
+use crate::core::guild_stats::{self, ActivityDistribution, STATS_RETENTION_DAYS};
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::{self, Emoji};
+
+const ACTIVITY_EMBED_COLOR: u32 = 0x00_cea2;
+const DEFAULT_WINDOW_DAYS: u32 = 7;
+/// The quieter channels are only in the CSV export.
+const CHANNEL_LIMIT: usize = 15;
+const BAR_WIDTH: u64 = 20;
+
+/// Shows which channels were the most active and at what time of day over a window, ``csv`` also attaches the
+/// hourly counts of every channel.
+pub async fn activity_report(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let mut window_days = DEFAULT_WINDOW_DAYS;
+    let mut csv = false;
+    while ctx.parser.has_next() {
+        let argument = ctx.parser.get_next()?.to_string();
+        if argument.eq_ignore_ascii_case("csv") {
+            csv = true;
+            continue;
+        }
+        match utils::parse_duration(&argument).filter(|duration| duration.num_days() > 0) {
+            Some(duration) => window_days = (duration.num_days() as u32).min(STATS_RETENTION_DAYS),
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("window", argument)
+                    .generate();
+                ctx.reply(GearBotString::ActivityReportInvalidWindow, args).await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let window = ctx.format_duration(window_days as i64 * 86_400, 2);
+    let hours = ctx
+        .bot_context
+        .datastore
+        .get_channel_activity(guild_id, window_days)
+        .await?;
+    if hours.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .add("window", window)
+            .generate();
+        ctx.reply(GearBotString::ActivityReportEmpty, args).await?;
+        return Ok(());
+    }
+
+    let timezone = ctx.get_config()?.tz();
+    let distribution = ActivityDistribution::collect(
+        hours
+            .iter()
+            .map(|hour| (ChannelId(hour.channel_id as u64), hour.hour, hour.messages as u64)),
+        timezone,
+    );
+
+    let mut channels = String::new();
+    for (channel_id, messages) in distribution.channels.iter().take(CHANNEL_LIMIT) {
+        let args = FluArgs::with_capacity(3)
+            .add("channel", format!("<#{}>", channel_id))
+            .add("count", ctx.format_number(*messages as f64))
+            .add("share", guild_stats::share(*messages, distribution.total))
+            .generate();
+        channels += &ctx.translate_with_args(GearBotString::ActivityReportChannel, &args);
+        channels += "\n";
+    }
+    if distribution.channels.len() > CHANNEL_LIMIT {
+        let args = FluArgs::with_capacity(1)
+            .add("count", distribution.channels.len() - CHANNEL_LIMIT)
+            .generate();
+        channels += &ctx.translate_with_args(GearBotString::ActivityReportMoreChannels, &args);
+    }
+
+    let busiest = distribution.hours.iter().copied().max().unwrap_or(0);
+    let mut heatmap = String::from("```\n");
+    for (hour, messages) in distribution.hours.iter().enumerate() {
+        heatmap += &format!(
+            "{:02}:00 {:<width$} {}\n",
+            hour,
+            bar(*messages, busiest),
+            messages,
+            width = BAR_WIDTH as usize
+        );
+    }
+    heatmap += "```";
+
+    let title_args = FluArgs::with_capacity(1).add("window", window).generate();
+    let total_args = FluArgs::with_capacity(2)
+        .add("count", ctx.format_number(distribution.total as f64))
+        .add("channels", distribution.channels.len())
+        .generate();
+    let hours_args = FluArgs::with_capacity(1).add("timezone", timezone.name()).generate();
+    let embed = SafeEmbed::new()
+        .title(ctx.translate_with_args(GearBotString::ActivityReportTitle, &title_args))
+        .description(ctx.translate_with_args(GearBotString::ActivityReportTotal, &total_args))
+        .color(ACTIVITY_EMBED_COLOR)
+        .field(ctx.translate(GearBotString::ActivityReportChannels), channels)
+        .field(
+            ctx.translate_with_args(GearBotString::ActivityReportHours, &hours_args),
+            heatmap,
+        );
+    ctx.reply_safe_embed(embed).await?;
+
+    if csv {
+        let mut export = String::from("hour,channel_id,channel,messages\n");
+        for hour in &hours {
+            let channel_id = ChannelId(hour.channel_id as u64);
+            let name = match ctx.get_channel(channel_id).await {
+                Some(channel) => channel.get_name().to_string(),
+                None => String::new(),
+            };
+            export += &format!(
+                "{},{},{},{}\n",
+                timezone.timestamp(hour.hour, 0).format("%Y-%m-%d %H:%M"),
+                channel_id,
+                csv_field(&name),
+                hour.messages
+            );
+        }
+
+        let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+        ctx.reply_with_file(
+            GearBotString::ActivityReportExported,
+            args,
+            format!("activity_{}.csv", guild_id),
+            export.into_bytes(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// A bar relative to the busiest hour, hours with any messages at all always get a bit of one.
+fn bar(messages: u64, busiest: u64) -> String {
+    if busiest == 0 {
+        return String::new();
+    }
+    let length = (messages * BAR_WIDTH + busiest - 1) / busiest;
+    "█".repeat(length as usize)
+}
+
+/// Quotes a value when it would otherwise break the row.
+fn csv_field(value: &str) -> String {
+    if value.contains(|c| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,103 @@
+use crate::commands::ROOT_NODE;
+use crate::core::CommandContext;
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// How many aliases a single guild can have.
+const MAX_ALIASES: usize = 50;
+
+pub async fn alias_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if config.command_aliases.is_empty() {
+        ctx.reply(GearBotString::AliasListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut aliases: Vec<(&String, &String)> = config.command_aliases.iter().collect();
+    aliases.sort();
+
+    let mut reply = ctx.translate(GearBotString::AliasListHeader);
+    for (alias, command) in aliases {
+        let args = FluArgs::with_capacity(2)
+            .add("alias", alias.as_str())
+            .add("command", command.as_str())
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::AliasListEntry, &args);
+    }
+
+    ctx.reply_raw(reply).await?;
+    Ok(())
+}
+
+pub async fn alias_add(mut ctx: CommandContext) -> CommandResult {
+    let alias = ctx.parser.get_next()?.to_lowercase();
+    let command = ctx.parser.get_remaining();
+    if command.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+    let first = command.split_whitespace().next().unwrap_or_default().to_lowercase();
+
+    if ROOT_NODE.all_commands.contains_key(&alias) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("alias", alias)
+            .generate();
+        ctx.reply(GearBotString::AliasTaken, args).await?;
+        return Ok(());
+    }
+
+    if !ROOT_NODE.all_commands.contains_key(&first) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("command", first)
+            .generate();
+        ctx.reply(GearBotString::AliasUnknownCommand, args).await?;
+        return Ok(());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    if !config.command_aliases.contains_key(&alias) && config.command_aliases.len() >= MAX_ALIASES {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_ALIASES)
+            .generate();
+        ctx.reply(GearBotString::AliasLimit, args).await?;
+        return Ok(());
+    }
+
+    config.command_aliases.insert(alias.clone(), command.clone());
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("alias", alias)
+        .add("command", command)
+        .generate();
+    ctx.reply(GearBotString::AliasAdded, args).await?;
+    Ok(())
+}
+
+pub async fn alias_remove(mut ctx: CommandContext) -> CommandResult {
+    let alias = ctx.parser.get_next()?.to_lowercase();
+
+    let mut config = (*ctx.get_config()?).clone();
+    if config.command_aliases.remove(&alias).is_none() {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("alias", alias)
+            .generate();
+        ctx.reply(GearBotString::AliasUnknown, args).await?;
+        return Ok(());
+    }
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("alias", alias)
+        .generate();
+    ctx.reply(GearBotString::AliasRemoved, args).await?;
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, RoleId, UserId};
+
+use crate::cache::CachedChannel;
+use crate::core::auto_publish::PublishFilter;
+use crate::core::{CommandContext, GuildConfig};
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{matchers, Emoji};
+
+pub async fn auto_publish_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if config.auto_publish.channels.is_empty() {
+        ctx.reply(
+            GearBotString::AutoPublishListEmpty,
+            FluArgs::with_capacity(0).generate(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut channels: Vec<(&ChannelId, &PublishFilter)> = config.auto_publish.channels.iter().collect();
+    channels.sort_by_key(|(channel, _)| channel.0);
+
+    let mut reply = ctx.translate(GearBotString::AutoPublishListHeader);
+    for (channel, filter) in channels {
+        let args = FluArgs::with_capacity(2)
+            .add("channel", channel.to_string())
+            .add("who", describe_filter(&ctx, filter))
+            .generate();
+        let key = if filter.bots {
+            GearBotString::AutoPublishListEntryBots
+        } else {
+            GearBotString::AutoPublishListEntry
+        };
+        reply += "\n";
+        reply += &ctx.translate_with_args(key, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+/// Starts publishing everything that isn't posted by a bot, the filters can be narrowed down afterwards.
+pub async fn auto_publish_add(mut ctx: CommandContext) -> CommandResult {
+    let channel = ctx.parser.get_channel().await?;
+    let channel_id = channel.get_id();
+    if !matches!(*channel, CachedChannel::AnnouncementsChannel { .. }) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::AutoPublishNotAnnouncements, args).await?;
+        return Ok(());
+    }
+    // Publishing what others posted counts as managing their messages
+    if !ctx
+        .bot_has_permissions_in_channel(channel_id, Permissions::MANAGE_MESSAGES)
+        .await
+    {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::AutoPublishMissingPermissions, args).await?;
+        return Ok(());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    config.auto_publish.channels.entry(channel_id).or_default();
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(GearBotString::AutoPublishAdded, args).await?;
+    Ok(())
+}
+
+pub async fn auto_publish_remove(mut ctx: CommandContext) -> CommandResult {
+    let channel_id = ctx.parser.get_channel().await?.get_id();
+    let mut config = (*ctx.get_config()?).clone();
+    if config.auto_publish.channels.remove(&channel_id).is_none() {
+        return reply_not_configured(&ctx, channel_id).await;
+    }
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(GearBotString::AutoPublishRemoved, args).await?;
+    Ok(())
+}
+
+/// Only publishes the messages of these members, leaving them out removes the author filter again.
+pub async fn auto_publish_authors(mut ctx: CommandContext) -> CommandResult {
+    let channel_id = ctx.parser.get_channel().await?.get_id();
+    let mut authors: Vec<UserId> = vec![];
+    while ctx.parser.has_next() {
+        let user = ctx.parser.get_user().await?;
+        if !authors.contains(&user.id) {
+            authors.push(user.id);
+        }
+    }
+
+    update_filter(ctx, channel_id, |filter| filter.authors = authors).await
+}
+
+/// Only publishes the messages of members with one of these roles, leaving them out removes the role filter again.
+pub async fn auto_publish_roles(mut ctx: CommandContext) -> CommandResult {
+    let channel_id = ctx.parser.get_channel().await?.get_id();
+    let mut roles: Vec<RoleId> = vec![];
+    while ctx.parser.has_next() {
+        let input = ctx.parser.get_next()?.to_string();
+        let role = match matchers::get_snowflake(&input).map(RoleId) {
+            Some(role) if ctx.get_role(&role).await.is_some() => role,
+            _ => return Err(ParseError::WrongArgumentType(String::from("role")).into()),
+        };
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+    }
+
+    update_filter(ctx, channel_id, |filter| filter.roles = roles).await
+}
+
+/// Switches between publishing the messages of bots and webhooks that aren't listed as authors or not.
+pub async fn auto_publish_bots(mut ctx: CommandContext) -> CommandResult {
+    let channel_id = ctx.parser.get_channel().await?.get_id();
+    let mut config = (*ctx.get_config()?).clone();
+    let bots = match config.auto_publish.channels.get_mut(&channel_id) {
+        Some(filter) => {
+            filter.bots = !filter.bots;
+            filter.bots
+        }
+        None => return reply_not_configured(&ctx, channel_id).await,
+    };
+    ctx.set_config(config).await?;
+
+    let key = if bots {
+        GearBotString::AutoPublishBotsEnabled
+    } else {
+        GearBotString::AutoPublishBotsDisabled
+    };
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+async fn update_filter(
+    ctx: CommandContext,
+    channel_id: ChannelId,
+    update: impl FnOnce(&mut PublishFilter),
+) -> CommandResult {
+    let mut config: GuildConfig = (*ctx.get_config()?).clone();
+    let who = match config.auto_publish.channels.get_mut(&channel_id) {
+        Some(filter) => {
+            update(filter);
+            describe_filter(&ctx, filter)
+        }
+        None => return reply_not_configured(&ctx, channel_id).await,
+    };
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel_id.to_string())
+        .add("who", who)
+        .generate();
+    ctx.reply(GearBotString::AutoPublishFilterUpdated, args).await?;
+    Ok(())
+}
+
+/// The authors and roles whose messages get published, or everyone without any.
+fn describe_filter(ctx: &CommandContext, filter: &PublishFilter) -> String {
+    if filter.authors.is_empty() && filter.roles.is_empty() {
+        return ctx.translate(GearBotString::AutoPublishEveryone);
+    }
+    filter
+        .authors
+        .iter()
+        .map(|author| format!("<@{}>", author))
+        .chain(filter.roles.iter().map(|role| format!("<@&{}>", role)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+async fn reply_not_configured(ctx: &CommandContext, channel_id: ChannelId) -> Result<(), CommandError> {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(GearBotString::AutoPublishNotConfigured, args).await?;
+    Ok(())
+}
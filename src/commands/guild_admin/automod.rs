@@ -0,0 +1,173 @@
+use crate::core::automod::{AutomodRule, AutomodRuleError, AutomodRules, MAX_PATTERN_LENGTH, MAX_PATTERN_NESTING};
+use crate::core::CommandContext;
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn automod_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if config.automod.is_empty() {
+        ctx.reply(GearBotString::AutomodListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::AutomodListHeader);
+    for rule in config.automod.rules() {
+        let actions = rule
+            .actions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        let args = FluArgs::with_capacity(5)
+            .add("name", rule.name.as_str())
+            .add("conditions", rule.conditions.len())
+            .add("actions", actions)
+            .add("threshold", rule.threshold)
+            .add("window", rule.window)
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::AutomodListEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+pub async fn automod_show(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_remaining();
+    if name.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let config = ctx.get_config()?;
+    match config
+        .automod
+        .rules()
+        .iter()
+        .find(|rule| rule.name.eq_ignore_ascii_case(&name))
+    {
+        Some(rule) => {
+            let definition = serde_json::to_string_pretty(rule).unwrap_or_default();
+            ctx.reply_long(format!("```json\n{}```", definition)).await?;
+        }
+        None => unknown_rule(&ctx, name).await?,
+    }
+    Ok(())
+}
+
+pub async fn automod_add(mut ctx: CommandContext) -> CommandResult {
+    let definition = ctx.parser.get_remaining();
+    let definition = definition.trim().trim_start_matches("```json").trim_matches('`');
+    if definition.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let rule: AutomodRule = match serde_json::from_str(definition) {
+        Ok(rule) => rule,
+        Err(e) => return invalid_rule(&ctx, e.to_string()).await,
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    let mut rules = config.automod.rules().to_vec();
+    let replaced = rules
+        .iter()
+        .any(|existing| existing.name.eq_ignore_ascii_case(&rule.name));
+    rules.retain(|existing| !existing.name.eq_ignore_ascii_case(&rule.name));
+    let name = rule.name.clone();
+    rules.push(rule);
+
+    config.automod = match AutomodRules::try_compile(rules) {
+        Ok(rules) => rules,
+        Err(e) => return rule_error(&ctx, e).await,
+    };
+    ctx.set_config(config).await?;
+
+    let key = if replaced {
+        GearBotString::AutomodRuleUpdated
+    } else {
+        GearBotString::AutomodRuleAdded
+    };
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+pub async fn automod_remove(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_remaining();
+    if name.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    let mut rules = config.automod.rules().to_vec();
+    let before = rules.len();
+    rules.retain(|rule| !rule.name.eq_ignore_ascii_case(&name));
+    if rules.len() == before {
+        return unknown_rule(&ctx, name).await;
+    }
+
+    // Leaving out a rule can't make the others invalid
+    config.automod = AutomodRules::compile(rules);
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::AutomodRuleRemoved, args).await?;
+    Ok(())
+}
+
+async fn invalid_rule(ctx: &CommandContext, reason: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("reason", reason)
+        .generate();
+    ctx.reply(GearBotString::AutomodRuleInvalid, args).await?;
+    Ok(())
+}
+
+/// Patterns that are refused for being too expensive get their own explanation, they're valid after all.
+async fn rule_error(ctx: &CommandContext, error: AutomodRuleError) -> CommandResult {
+    let (key, args) = match error {
+        AutomodRuleError::PatternTooLong(_) => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("max", MAX_PATTERN_LENGTH)
+                .generate();
+            (GearBotString::AutomodPatternTooLong, args)
+        }
+        AutomodRuleError::PatternTooDeep(pattern) => {
+            let args = FluArgs::with_capacity(3)
+                .add("gearno", Emoji::No.for_chat())
+                .add("pattern", pattern)
+                .add("max", MAX_PATTERN_NESTING)
+                .generate();
+            (GearBotString::AutomodPatternTooDeep, args)
+        }
+        AutomodRuleError::PatternTooBig(pattern) => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("pattern", pattern)
+                .generate();
+            (GearBotString::AutomodPatternTooBig, args)
+        }
+        error => return invalid_rule(ctx, error.to_string()).await,
+    };
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+async fn unknown_rule(ctx: &CommandContext, name: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::AutomodRuleUnknown, args).await?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::core::autoresponder::{AutoResponse, AutoResponses};
+use crate::core::CommandContext;
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn autoresponse_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if config.autoresponses.is_empty() {
+        ctx.reply(
+            GearBotString::AutoResponseListEmpty,
+            FluArgs::with_capacity(0).generate(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::AutoResponseListHeader);
+    for response in config.autoresponses.responses() {
+        let args = FluArgs::with_capacity(3)
+            .add("name", response.name.as_str())
+            .add("trigger", response.trigger.to_string())
+            .add("cooldown", response.cooldown)
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::AutoResponseListEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+pub async fn autoresponse_show(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_remaining();
+    if name.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let config = ctx.get_config()?;
+    match config
+        .autoresponses
+        .responses()
+        .iter()
+        .find(|response| response.name.eq_ignore_ascii_case(&name))
+    {
+        Some(response) => {
+            let definition = serde_json::to_string_pretty(response).unwrap_or_default();
+            ctx.reply_long(format!("```json\n{}```", definition)).await?;
+        }
+        None => unknown_response(&ctx, name).await?,
+    }
+    Ok(())
+}
+
+pub async fn autoresponse_add(mut ctx: CommandContext) -> CommandResult {
+    let definition = ctx.parser.get_remaining();
+    let definition = definition.trim().trim_start_matches("```json").trim_matches('`');
+    if definition.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let response: AutoResponse = match serde_json::from_str(definition) {
+        Ok(response) => response,
+        Err(e) => return invalid_response(&ctx, e.to_string()).await,
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    let mut responses = config.autoresponses.responses().to_vec();
+    let replaced = responses
+        .iter()
+        .any(|existing| existing.name.eq_ignore_ascii_case(&response.name));
+    responses.retain(|existing| !existing.name.eq_ignore_ascii_case(&response.name));
+    let name = response.name.clone();
+    responses.push(response);
+
+    config.autoresponses = match AutoResponses::try_compile(responses) {
+        Ok(responses) => responses,
+        Err(e) => return invalid_response(&ctx, e.to_string()).await,
+    };
+    ctx.set_config(config).await?;
+
+    let key = if replaced {
+        GearBotString::AutoResponseUpdated
+    } else {
+        GearBotString::AutoResponseAdded
+    };
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+pub async fn autoresponse_remove(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_remaining();
+    if name.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    let mut responses = config.autoresponses.responses().to_vec();
+    let before = responses.len();
+    responses.retain(|response| !response.name.eq_ignore_ascii_case(&name));
+    if responses.len() == before {
+        return unknown_response(&ctx, name).await;
+    }
+
+    // Leaving out a response can't make the others invalid
+    config.autoresponses = AutoResponses::compile(responses);
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::AutoResponseRemoved, args).await?;
+    Ok(())
+}
+
+async fn invalid_response(ctx: &CommandContext, reason: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("reason", reason)
+        .generate();
+    ctx.reply(GearBotString::AutoResponseInvalid, args).await?;
+    Ok(())
+}
+
+async fn unknown_response(ctx: &CommandContext, name: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::AutoResponseUnknown, args).await?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn botban(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+
+    let refusal = if user.id == ctx.message.author.id {
+        Some(GearBotString::BotBanSelf)
+    } else if user.id == ctx.get_guild()?.owner_id {
+        Some(GearBotString::BotBanOwner)
+    } else {
+        None
+    };
+    if let Some(key) = refusal {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(key, args).await?;
+        return Ok(());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    if config.bot_banned_users.contains(&user.id) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("user", user.full_name_with_id())
+            .generate();
+        ctx.reply(GearBotString::BotBanAlreadyBanned, args).await?;
+        return Ok(());
+    }
+
+    config.bot_banned_users.push(user.id);
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("user", user.full_name_with_id())
+        .generate();
+    ctx.reply(GearBotString::BotBanAdded, args).await?;
+    Ok(())
+}
+
+pub async fn botunban(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+
+    let mut config = (*ctx.get_config()?).clone();
+    if !config.bot_banned_users.contains(&user.id) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("user", user.full_name_with_id())
+            .generate();
+        ctx.reply(GearBotString::BotBanNotBanned, args).await?;
+        return Ok(());
+    }
+
+    config.bot_banned_users.retain(|banned| *banned != user.id);
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("user", user.full_name_with_id())
+        .generate();
+    ctx.reply(GearBotString::BotBanRemoved, args).await?;
+    Ok(())
+}
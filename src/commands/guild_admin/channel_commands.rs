@@ -0,0 +1,193 @@
+use twilight_model::id::ChannelId;
+
+use crate::commands::meta::nodes::CommandGroup;
+use crate::commands::ROOT_NODE;
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Disabling this one would leave no way to turn anything back on in the channel.
+const LOCKED_COMMAND: &str = "commands";
+
+pub async fn commands_list(mut ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+
+    // Without a channel, give an overview of all channels that have restrictions
+    if !ctx.parser.has_next() {
+        let mut channels: Vec<(&ChannelId, &Vec<String>)> = config
+            .disabled_commands
+            .iter()
+            .filter(|(_, commands)| !commands.is_empty())
+            .collect();
+        if channels.is_empty() {
+            let args = FluArgs::with_capacity(0).generate();
+            ctx.reply(GearBotString::ChannelCommandsOverviewEmpty, args).await?;
+            return Ok(());
+        }
+        channels.sort_by_key(|(channel, _)| channel.0);
+
+        let mut reply = ctx.translate(GearBotString::ChannelCommandsOverviewHeader);
+        for (channel, commands) in channels {
+            let commands = commands
+                .iter()
+                .map(|command| format!("``{}``", command))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let args = FluArgs::with_capacity(2)
+                .add("channel", channel.to_string())
+                .add("commands", commands)
+                .generate();
+            reply += "\n";
+            reply += &ctx.translate_with_args(GearBotString::ChannelCommandsOverviewEntry, &args);
+        }
+
+        ctx.reply_long(reply).await?;
+        return Ok(());
+    }
+
+    let channel = ctx.parser.get_channel().await?.get_id();
+    let args = FluArgs::with_capacity(1).add("channel", channel.to_string()).generate();
+    let mut reply = ctx.translate_with_args(GearBotString::ChannelCommandsHeader, &args);
+    for command in ROOT_NODE
+        .command_list
+        .iter()
+        .filter(|command| command.group != CommandGroup::BotAdmin)
+    {
+        let emoji = if config.command_disabled_in(channel, &command.name) {
+            Emoji::No
+        } else {
+            Emoji::Yes
+        };
+        let args = FluArgs::with_capacity(2)
+            .add("emoji", emoji.for_chat())
+            .add("command", command.name.as_str())
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::ChannelCommandsEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+pub async fn commands_disable(ctx: CommandContext) -> CommandResult {
+    toggle_command(ctx, false).await
+}
+
+pub async fn commands_enable(ctx: CommandContext) -> CommandResult {
+    toggle_command(ctx, true).await
+}
+
+async fn toggle_command(mut ctx: CommandContext, enable: bool) -> CommandResult {
+    let command = match next_command(&mut ctx).await? {
+        Some(command) => command,
+        None => return Ok(()),
+    };
+
+    if !enable && command == LOCKED_COMMAND {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("command", command)
+            .generate();
+        ctx.reply(GearBotString::ChannelCommandLocked, args).await?;
+        return Ok(());
+    }
+
+    let channel = if ctx.parser.has_next() {
+        ctx.parser.get_channel().await?.get_id()
+    } else {
+        ctx.message.channel.get_id()
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    let disabled = config.disabled_commands.entry(channel).or_default();
+    if enable {
+        if !disabled.contains(&command) {
+            let args = FluArgs::with_capacity(3)
+                .add("gearno", Emoji::No.for_chat())
+                .add("command", command)
+                .add("channel", channel.to_string())
+                .generate();
+            ctx.reply(GearBotString::ChannelCommandNotDisabled, args).await?;
+            return Ok(());
+        }
+        disabled.retain(|name| *name != command);
+        if disabled.is_empty() {
+            config.disabled_commands.remove(&channel);
+        }
+    } else if !disabled.contains(&command) {
+        disabled.push(command.clone());
+        disabled.sort();
+    }
+    ctx.set_config(config).await?;
+
+    let (key, emoji) = if enable {
+        (GearBotString::ChannelCommandEnabled, Emoji::Yes)
+    } else {
+        (GearBotString::ChannelCommandDisabled, Emoji::No)
+    };
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", emoji.for_chat())
+        .add("command", command)
+        .add("channel", channel.to_string())
+        .generate();
+    ctx.reply(key, args).await?;
+
+    Ok(())
+}
+
+/// Sends the replies of a command to a channel of its own, or back to where it's used without a channel.
+pub async fn commands_output(mut ctx: CommandContext) -> CommandResult {
+    let command = match next_command(&mut ctx).await? {
+        Some(command) => command,
+        None => return Ok(()),
+    };
+    let channel = if ctx.parser.has_next() {
+        Some(ctx.parser.get_channel().await?.get_id())
+    } else {
+        None
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("command", command.clone())
+        .add(
+            "channel",
+            channel.map(|channel| channel.to_string()).unwrap_or_default(),
+        )
+        .generate();
+    let key = match channel {
+        Some(channel) => {
+            config.command_output_channels.insert(command, channel);
+            GearBotString::ChannelCommandOutputSet
+        }
+        None => {
+            config.command_output_channels.remove(&command);
+            GearBotString::ChannelCommandOutputCleared
+        }
+    };
+    ctx.set_config(config).await?;
+    ctx.reply(key, args).await?;
+
+    Ok(())
+}
+
+/// Reads the name of a top level command, aliases count as the command they belong to.
+///
+/// Tells the author and returns `None` if there is no such command.
+async fn next_command(ctx: &mut CommandContext) -> Result<Option<String>, CommandError> {
+    let input = ctx.parser.get_next()?.to_lowercase();
+    match ROOT_NODE.all_commands.get(&input) {
+        Some(node) => Ok(Some(node.name.clone())),
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("command", input)
+                .generate();
+            ctx.reply(GearBotString::ChannelCommandUnknown, args).await?;
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use twilight_model::id::ChannelId;
+
+use crate::cache::CachedChannel;
+use crate::core::channel_templates::{self, ChannelTemplate, MAX_CHANNEL_TEMPLATES};
+use crate::core::logpump::LogType;
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Creates a copy of a channel with the same settings and overwrites, optionally under another name.
+pub async fn channel_clone(mut ctx: CommandContext) -> CommandResult {
+    let source = ctx.parser.get_channel().await?;
+    let template = match ChannelTemplate::from_channel(&source) {
+        Some(template) => template,
+        None => return unsupported_channel(&ctx, &source).await,
+    };
+    let name = if ctx.parser.has_next() {
+        ctx.parser.get_remaining()
+    } else {
+        template.name.clone()
+    };
+
+    let channel = match create_channel(&ctx, &template, &name).await? {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+    ctx.log(
+        LogType::ChannelCloned {
+            channel,
+            source: source.get_id(),
+        },
+        Some(ctx.message.channel.get_id()),
+        ctx.message.author.id,
+    );
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel.to_string())
+        .add("source", source.get_id().to_string())
+        .generate();
+    ctx.reply(GearBotString::ChannelCloned, args).await?;
+    Ok(())
+}
+
+pub async fn channel_template_list(ctx: CommandContext) -> CommandResult {
+    let names = ctx
+        .bot_context
+        .datastore
+        .get_channel_template_names(ctx.get_guild()?.id)
+        .await?;
+    if names.is_empty() {
+        ctx.reply(
+            GearBotString::ChannelTemplateListEmpty,
+            FluArgs::with_capacity(0).generate(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::ChannelTemplateListHeader);
+    reply += "\n";
+    reply += &names
+        .iter()
+        .map(|name| format!("``{}``", name))
+        .collect::<Vec<String>>()
+        .join(", ");
+    ctx.reply_raw(reply).await?;
+    Ok(())
+}
+
+pub async fn channel_template_save(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+    if !channel_templates::is_valid_template_name(&name) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("name", name.replace("`", "ˋ"))
+            .generate();
+        ctx.reply(GearBotString::ChannelTemplateInvalidName, args).await?;
+        return Ok(());
+    }
+    let channel = ctx.parser.get_channel().await?;
+    let template = match ChannelTemplate::from_channel(&channel) {
+        Some(template) => template,
+        None => return unsupported_channel(&ctx, &channel).await,
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let names = datastore.get_channel_template_names(guild_id).await?;
+    if !names.contains(&name) && names.len() >= MAX_CHANNEL_TEMPLATES {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_CHANNEL_TEMPLATES)
+            .generate();
+        ctx.reply(GearBotString::ChannelTemplateLimitReached, args).await?;
+        return Ok(());
+    }
+    datastore.save_channel_template(guild_id, &name, &template).await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel.get_id().to_string())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::ChannelTemplateSaved, args).await?;
+    Ok(())
+}
+
+/// Creates a new channel from a template, named like the channel it was saved from unless another name is given.
+pub async fn channel_template_apply(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+    let template = match ctx
+        .bot_context
+        .datastore
+        .get_channel_template(ctx.get_guild()?.id, &name)
+        .await?
+    {
+        Some(template) => template,
+        None => return unknown_template(&ctx, name).await,
+    };
+    let channel_name = if ctx.parser.has_next() {
+        ctx.parser.get_remaining()
+    } else {
+        template.name.clone()
+    };
+
+    let channel = match create_channel(&ctx, &template, &channel_name).await? {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+    ctx.log(
+        LogType::ChannelTemplateApplied {
+            channel,
+            template: name.clone(),
+        },
+        Some(ctx.message.channel.get_id()),
+        ctx.message.author.id,
+    );
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("channel", channel.to_string())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::ChannelTemplateApplied, args).await?;
+    Ok(())
+}
+
+pub async fn channel_template_remove(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+    if !ctx
+        .bot_context
+        .datastore
+        .remove_channel_template(ctx.get_guild()?.id, &name)
+        .await?
+    {
+        return unknown_template(&ctx, name).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::ChannelTemplateRemoved, args).await?;
+    Ok(())
+}
+
+/// Creates the channel, replying why not when that didn't work.
+async fn create_channel(
+    ctx: &CommandContext,
+    template: &ChannelTemplate,
+    name: &str,
+) -> Result<Option<ChannelId>, CommandError> {
+    let reason = format!(
+        "Requested by {} ({})",
+        ctx.message.author.full_name(),
+        ctx.message.author.id
+    );
+    let created = ctx
+        .bot_context
+        .create_channel_from_template(ctx.get_guild()?.id, template, name, &reason)
+        .await
+        .unwrap_or_else(|e| {
+            log::debug!("Failed to create a channel from a template: {}", e);
+            None
+        });
+
+    if created.is_none() {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::ChannelCreateFailed, args).await?;
+    }
+    Ok(created)
+}
+
+async fn unsupported_channel(ctx: &CommandContext, channel: &Arc<CachedChannel>) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("channel", channel.get_id().to_string())
+        .generate();
+    ctx.reply(GearBotString::ChannelTemplateUnsupported, args).await?;
+    Ok(())
+}
+
+async fn unknown_template(ctx: &CommandContext, name: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("name", name.replace("`", "ˋ"))
+        .generate();
+    ctx.reply(GearBotString::ChannelTemplateUnknown, args).await?;
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use serde_json::Value;
+use twilight_model::channel::Attachment;
+use twilight_model::guild::Permissions;
+
+use crate::core::{CommandContext, ConfigProblem, GuildConfig};
+use crate::error::{CommandError, CommandResult, DatabaseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Way more than any real config needs, anything bigger is not worth downloading.
+const MAX_IMPORT_SIZE: u64 = 256 * 1024;
+/// The rest are only counted, so the reply stays within a single message.
+const LISTED_PROBLEMS: usize = 15;
+
+pub async fn config_export(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let exported = serde_json::to_vec_pretty(&*ctx.get_config()?).map_err(DatabaseError::Serializing)?;
+
+    let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+    ctx.reply_with_file(
+        GearBotString::ConfigExported,
+        args,
+        format!("config_{}.json", guild_id),
+        exported,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Replaces the config with the attached one, as long as it passes every check ``config validate`` does. Configs
+/// from other servers are refused until they no longer point to its channels and roles.
+pub async fn config_import(ctx: CommandContext) -> CommandResult {
+    let attachment = match ctx.message.attachments.first() {
+        Some(attachment) => attachment,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::ConfigImportNoFile, args).await?;
+            return Ok(());
+        }
+    };
+
+    let bytes = match download_config(&ctx, attachment).await? {
+        Some(bytes) => bytes,
+        None => return Ok(()),
+    };
+
+    let parsed = serde_json::from_slice::<Value>(&bytes)
+        .and_then(|raw| serde_json::from_value::<GuildConfig>(raw.clone()).map(|config| (raw, config)));
+    let (raw, config) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("reason", e.to_string())
+                .generate();
+            ctx.reply(GearBotString::ConfigImportInvalid, args).await?;
+            return Ok(());
+        }
+    };
+
+    // Broken rules and patterns are dropped while loading, they have to be caught in the raw config
+    let problems = all_problems(&ctx, &raw, &config).await?;
+    if !problems.is_empty() {
+        return reply_problems(&ctx, &problems).await;
+    }
+
+    apply_import(ctx, config).await
+}
+
+/// Runs every check a config can go through over a config without storing it: the attached file, the JSON after
+/// the command, or the current config when there's neither.
+pub async fn config_validate(mut ctx: CommandContext) -> CommandResult {
+    let raw = if let Some(attachment) = ctx.message.attachments.first() {
+        match download_config(&ctx, attachment).await? {
+            Some(bytes) => serde_json::from_slice::<Value>(&bytes),
+            None => return Ok(()),
+        }
+    } else if ctx.parser.has_next() {
+        let remaining = ctx.parser.get_remaining();
+        serde_json::from_str::<Value>(strip_code_block(&remaining))
+    } else {
+        serde_json::to_value(&*ctx.get_config()?)
+    };
+
+    let parsed = raw.and_then(|raw| serde_json::from_value::<GuildConfig>(raw.clone()).map(|config| (raw, config)));
+    let (raw, config) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("reason", e.to_string())
+                .generate();
+            ctx.reply(GearBotString::ConfigValidateInvalid, args).await?;
+            return Ok(());
+        }
+    };
+
+    let problems = all_problems(&ctx, &raw, &config).await?;
+    if problems.is_empty() {
+        let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+        ctx.reply(GearBotString::ConfigValidateValid, args).await?;
+        return Ok(());
+    }
+
+    reply_problems(&ctx, &problems).await
+}
+
+async fn all_problems(
+    ctx: &CommandContext,
+    raw: &Value,
+    config: &GuildConfig,
+) -> Result<Vec<ConfigProblem>, CommandError> {
+    let mut problems = config.problems();
+    problems.extend(GuildConfig::pattern_problems(raw));
+    problems.extend(guild_problems(ctx, config).await?);
+    Ok(problems)
+}
+
+async fn reply_problems(ctx: &CommandContext, problems: &[ConfigProblem]) -> CommandResult {
+    let mut listed = problems
+        .iter()
+        .take(LISTED_PROBLEMS)
+        .map(|problem| format!("- {}", problem))
+        .collect::<Vec<String>>()
+        .join("\n");
+    if problems.len() > LISTED_PROBLEMS {
+        let args = FluArgs::with_capacity(1)
+            .add("count", problems.len() - LISTED_PROBLEMS)
+            .generate();
+        listed += "\n";
+        listed += &ctx.translate_with_args(GearBotString::ConfigValidateMore, &args);
+    }
+    let args = FluArgs::with_capacity(3)
+        .add("gearno", Emoji::No.for_chat())
+        .add("count", problems.len())
+        .add("problems", listed)
+        .generate();
+    ctx.reply(GearBotString::ConfigValidateProblems, args).await?;
+    Ok(())
+}
+
+/// What the config points to that isn't on this server, and log channels the bot can't log in.
+async fn guild_problems(ctx: &CommandContext, config: &GuildConfig) -> Result<Vec<ConfigProblem>, CommandError> {
+    let guild = ctx.get_guild()?;
+    let mut problems = vec![];
+
+    {
+        let channels = guild.channels.read().await;
+        let mut missing = config
+            .referenced_channels()
+            .into_iter()
+            .filter(|channel| !channels.contains_key(channel))
+            .collect::<Vec<_>>();
+        missing.sort();
+        problems.extend(missing.into_iter().map(ConfigProblem::UnknownChannel));
+    }
+    {
+        let roles = guild.roles.read().await;
+        let mut missing = config
+            .referenced_roles()
+            .into_iter()
+            .filter(|role| !roles.contains_key(role))
+            .collect::<Vec<_>>();
+        missing.sort();
+        problems.extend(missing.into_iter().map(ConfigProblem::UnknownRole));
+    }
+
+    let mut log_channels = config.log_channels.iter().collect::<Vec<_>>();
+    log_channels.sort_by_key(|(channel_id, _)| **channel_id);
+    for (channel_id, log_config) in log_channels {
+        if !guild.channels.read().await.contains_key(channel_id) {
+            continue;
+        }
+        let mut required = Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES;
+        // An existing webhook keeps working, a new one can only be made with the permission
+        if log_config.uses_webhook()
+            && ctx
+                .bot_context
+                .datastore
+                .get_webhook_parts(*channel_id)
+                .await?
+                .is_none()
+        {
+            required |= Permissions::MANAGE_WEBHOOKS;
+        }
+        let granted = ctx
+            .bot_context
+            .get_channel_permissions_for(ctx.bot_context.bot_user.id, *channel_id)
+            .await;
+        if !granted.contains(required) {
+            problems.push(ConfigProblem::LogPermissions(*channel_id, required - granted));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Downloads an attached config, replying why not if it can't be used.
+async fn download_config(ctx: &CommandContext, attachment: &Attachment) -> Result<Option<Vec<u8>>, CommandError> {
+    if attachment.size > MAX_IMPORT_SIZE {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::ConfigImportTooBig, args).await?;
+        return Ok(None);
+    }
+
+    let downloaded = match reqwest::get(&attachment.url).await.and_then(|r| r.error_for_status()) {
+        Ok(response) => response.bytes().await,
+        Err(e) => Err(e),
+    };
+    match downloaded {
+        Ok(bytes) => Ok(Some(bytes.to_vec())),
+        Err(e) => {
+            log::warn!("Failed to download an imported config: {}", e);
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::ConfigImportDownloadFailed, args).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// JSON pasted in chat usually comes wrapped in a code block.
+fn strip_code_block(input: &str) -> &str {
+    let input = input.trim();
+    match input.strip_prefix("```") {
+        Some(inner) => {
+            let inner = inner.strip_suffix("```").unwrap_or(inner);
+            inner.strip_prefix("json").unwrap_or(inner).trim()
+        }
+        None => input,
+    }
+}
+
+async fn apply_import(ctx: CommandContext, mut config: GuildConfig) -> CommandResult {
+    // Same guarantee loading a config from the database gives
+    config.permission_groups.sort_by(|a, b| a.priority.cmp(&b.priority));
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+    ctx.reply(GearBotString::ConfigImported, args).await?;
+    Ok(())
+}
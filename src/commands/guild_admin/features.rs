@@ -0,0 +1,72 @@
+use crate::core::{CommandContext, GuildFeatures, FEATURE_NAMES};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn feature_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+
+    let mut reply = ctx.translate(GearBotString::FeatureListHeader);
+    for (name, feature) in FEATURE_NAMES.iter() {
+        let emoji = if config.features.contains(*feature) {
+            Emoji::Yes
+        } else {
+            Emoji::No
+        };
+        let args = FluArgs::with_capacity(2)
+            .add("emoji", emoji.for_chat())
+            .add("feature", *name)
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::FeatureListEntry, &args);
+    }
+
+    ctx.reply_raw(reply).await?;
+    Ok(())
+}
+
+pub async fn feature_enable(ctx: CommandContext) -> CommandResult {
+    toggle_feature(ctx, true).await
+}
+
+pub async fn feature_disable(ctx: CommandContext) -> CommandResult {
+    toggle_feature(ctx, false).await
+}
+
+async fn toggle_feature(mut ctx: CommandContext, enable: bool) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_string();
+    let feature = match GuildFeatures::from_name(&name) {
+        Some(feature) => feature,
+        None => {
+            let names = FEATURE_NAMES
+                .iter()
+                .map(|(name, _)| format!("``{}``", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let args = FluArgs::with_capacity(3)
+                .add("gearno", Emoji::No.for_chat())
+                .add("feature", name)
+                .add("features", names)
+                .generate();
+            ctx.reply(GearBotString::FeatureUnknown, args).await?;
+            return Ok(());
+        }
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    config.features.set(feature, enable);
+    ctx.set_config(config).await?;
+
+    let (key, emoji) = if enable {
+        (GearBotString::FeatureEnabledNow, Emoji::Yes)
+    } else {
+        (GearBotString::FeatureDisabledNow, Emoji::No)
+    };
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", emoji.for_chat())
+        .add("feature", name.to_lowercase())
+        .generate();
+    ctx.reply(key, args).await?;
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use twilight_model::guild::Permissions;
+
+use crate::cache::CachedChannel;
+use crate::core::feeds::{
+    DEFAULT_INTERVAL_MINUTES, DEFAULT_TEMPLATE, MAX_FEEDS, MAX_INTERVAL_MINUTES, MIN_INTERVAL_MINUTES, TEMPLATE_LIMIT,
+};
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult, FeedError, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn feed_list(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let feeds = ctx.bot_context.datastore.get_feeds(guild_id).await?;
+    if feeds.is_empty() {
+        ctx.reply(GearBotString::FeedListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::FeedListHeader);
+    for feed in feeds {
+        let args = FluArgs::with_capacity(5)
+            .add("id", feed.id)
+            .add("title", feed.title.unwrap_or_else(|| feed.url.clone()))
+            .add("url", feed.url)
+            .add("channel", feed.channel_id.to_string())
+            .add("minutes", feed.interval_minutes)
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::FeedListEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+/// Starts posting new entries of a feed: ``feed add #news https://example.com/feed.xml 60``. The interval is in
+/// minutes and optional, everything that's in the feed already is never posted.
+pub async fn feed_add(mut ctx: CommandContext) -> CommandResult {
+    let channel = ctx.parser.get_channel().await?;
+    let channel_id = channel.get_id();
+    let url = ctx
+        .parser
+        .get_next()?
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string();
+    let interval = if ctx.parser.has_next() {
+        parse_interval(&mut ctx)?
+    } else {
+        DEFAULT_INTERVAL_MINUTES
+    };
+
+    if !matches!(
+        *channel,
+        CachedChannel::TextChannel { .. } | CachedChannel::AnnouncementsChannel { .. }
+    ) || !ctx
+        .bot_has_permissions_in_channel(channel_id, Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS)
+        .await
+    {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::FeedCantPost, args).await?;
+        return Ok(());
+    }
+    if !interval_in_bounds(&ctx, interval).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    if datastore.get_feeds(guild_id).await?.len() >= MAX_FEEDS {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_FEEDS)
+            .generate();
+        ctx.reply(GearBotString::FeedLimit, args).await?;
+        return Ok(());
+    }
+
+    let fetched = match ctx.bot_context.feed_reader.fetch(&url).await {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            let key = match e {
+                FeedError::InvalidUrl => GearBotString::FeedInvalidUrl,
+                _ => GearBotString::FeedUnreadable,
+            };
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("url", url)
+                .generate();
+            ctx.reply(key, args).await?;
+            return Ok(());
+        }
+    };
+
+    let feed = datastore
+        .create_feed(
+            guild_id,
+            channel_id,
+            &url,
+            fetched.title.as_deref(),
+            DEFAULT_TEMPLATE,
+            interval,
+        )
+        .await?;
+    let existing: Vec<String> = fetched.entries.iter().map(|entry| entry.id.clone()).collect();
+    datastore.mark_feed_entries_seen(guild_id, feed.id, &existing).await?;
+
+    let args = FluArgs::with_capacity(5)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", feed.id)
+        .add("title", feed.title.unwrap_or(url))
+        .add("channel", channel_id.to_string())
+        .add("minutes", interval)
+        .generate();
+    ctx.reply(GearBotString::FeedAdded, args).await?;
+    Ok(())
+}
+
+pub async fn feed_remove(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx.bot_context.datastore.remove_feed(guild_id, id).await? {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::FeedRemoved, args).await?;
+    Ok(())
+}
+
+/// Sets what new entries say: ``feed template 1 **{title}** by {author}``. The placeholders are `{title}`, `{link}`,
+/// `{summary}`, `{author}` and `{feed}`, leaving the template out goes back to only the summary.
+pub async fn feed_template(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let template = match ctx.parser.get_remaining() {
+        template if template.is_empty() => DEFAULT_TEMPLATE.to_string(),
+        template => template,
+    };
+    if template.chars().count() > TEMPLATE_LIMIT {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", TEMPLATE_LIMIT)
+            .generate();
+        ctx.reply(GearBotString::FeedTemplateTooLong, args).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx
+        .bot_context
+        .datastore
+        .set_feed_template(guild_id, id, &template)
+        .await?
+    {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::FeedTemplateUpdated, args).await?;
+    Ok(())
+}
+
+/// Changes how many minutes there are between polls of a feed.
+pub async fn feed_interval(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let interval = parse_interval(&mut ctx)?;
+    if !interval_in_bounds(&ctx, interval).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx
+        .bot_context
+        .datastore
+        .set_feed_interval(guild_id, id, interval)
+        .await?
+    {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .add("minutes", interval)
+        .generate();
+    ctx.reply(GearBotString::FeedIntervalUpdated, args).await?;
+    Ok(())
+}
+
+/// Shows the latest entry of a feed the way it would be posted, to try out templates.
+pub async fn feed_preview(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let guild_id = ctx.get_guild()?.id;
+    let feed = match ctx.bot_context.datastore.get_feed(guild_id, id).await? {
+        Some(feed) => feed,
+        None => return reply_unknown(&ctx, id).await,
+    };
+
+    let entry = match ctx.bot_context.feed_reader.fetch(&feed.url).await {
+        Ok(mut fetched) if !fetched.entries.is_empty() => fetched.entries.remove(0),
+        _ => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("url", feed.url)
+                .generate();
+            ctx.reply(GearBotString::FeedUnreadable, args).await?;
+            return Ok(());
+        }
+    };
+
+    let embed = ctx.bot_context.feed_entry_embed(&feed, &entry)?;
+    ctx.reply_raw_with_embed(String::new(), embed).await?;
+    Ok(())
+}
+
+fn parse_id(ctx: &mut CommandContext) -> Result<i32, ParseError> {
+    ctx.parser
+        .get_next()?
+        .trim_start_matches('#')
+        .parse::<i32>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("feed number")))
+}
+
+fn parse_interval(ctx: &mut CommandContext) -> Result<i32, ParseError> {
+    ctx.parser
+        .get_next()?
+        .parse::<i32>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("minutes")))
+}
+
+/// Tells the author when the interval is out of bounds.
+async fn interval_in_bounds(ctx: &CommandContext, interval: i32) -> Result<bool, CommandError> {
+    if (MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&interval) {
+        return Ok(true);
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("gearno", Emoji::No.for_chat())
+        .add("min", MIN_INTERVAL_MINUTES)
+        .add("max", MAX_INTERVAL_MINUTES)
+        .generate();
+    ctx.reply(GearBotString::FeedInvalidInterval, args).await?;
+    Ok(false)
+}
+
+async fn reply_unknown(ctx: &CommandContext, id: i32) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::FeedUnknown, args).await?;
+    Ok(())
+}
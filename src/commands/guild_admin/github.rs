@@ -0,0 +1,305 @@
+use twilight_model::guild::Permissions;
+use twilight_model::id::ChannelId;
+
+use crate::cache::CachedChannel;
+use crate::core::github::{generate_secret, normalize_repository, GithubEvent, MAX_REPOSITORIES};
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn github_list(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let repos = ctx.bot_context.datastore.get_github_repos(guild_id).await?;
+    if repos.is_empty() {
+        ctx.reply(GearBotString::GithubListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::GithubListHeader);
+    for repo in repos {
+        let args = FluArgs::with_capacity(3)
+            .add("repository", repo.repository)
+            .add("channel", repo.channel_id.to_string())
+            .add("events", repo.events.join(", "))
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::GithubListEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+/// Starts relaying a repository: ``github add gearbot/gearbot #dev push release``. Leaving out the events relays
+/// all of them, the details to set up the webhook on GitHub are sent to the author in DMs.
+pub async fn github_add(mut ctx: CommandContext) -> CommandResult {
+    let url = match webhook_url(&ctx).await? {
+        Some(url) => url,
+        None => return Ok(()),
+    };
+    let repository = match parse_repository(&mut ctx).await? {
+        Some(repository) => repository,
+        None => return Ok(()),
+    };
+    let channel = ctx.parser.get_channel().await?;
+    let channel_id = channel.get_id();
+    let events = if ctx.parser.has_next() {
+        match parse_events(&mut ctx).await? {
+            Some(events) => events,
+            None => return Ok(()),
+        }
+    } else {
+        GithubEvent::ALL
+            .iter()
+            .map(|event| event.as_str().to_string())
+            .collect()
+    };
+
+    if !matches!(
+        *channel,
+        CachedChannel::TextChannel { .. } | CachedChannel::AnnouncementsChannel { .. }
+    ) || !ctx
+        .bot_has_permissions_in_channel(channel_id, Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS)
+        .await
+    {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::GithubCantPost, args).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let existing = datastore.get_github_repos(guild_id).await?;
+    if existing.iter().any(|repo| repo.repository == repository) {
+        return reply_with_repository(&ctx, GearBotString::GithubAlreadyFollowed, &repository).await;
+    }
+    if existing.len() >= MAX_REPOSITORIES {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_REPOSITORIES)
+            .generate();
+        ctx.reply(GearBotString::GithubLimit, args).await?;
+        return Ok(());
+    }
+
+    // Only stored once the author has the secret, a repository nobody can sign for is of no use
+    let secret = generate_secret();
+    if !send_setup(&ctx, &url, &repository, &secret, &events, channel_id).await? {
+        return reply_with_repository(&ctx, GearBotString::GithubDmFailed, &repository).await;
+    }
+    if !datastore
+        .add_github_repo(guild_id, &repository, channel_id, &events, &secret)
+        .await?
+    {
+        return reply_with_repository(&ctx, GearBotString::GithubAlreadyFollowed, &repository).await;
+    }
+
+    let args = FluArgs::with_capacity(4)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("repository", repository)
+        .add("channel", channel_id.to_string())
+        .add("events", events.join(", "))
+        .generate();
+    ctx.reply(GearBotString::GithubAdded, args).await?;
+    Ok(())
+}
+
+pub async fn github_remove(mut ctx: CommandContext) -> CommandResult {
+    let repository = match parse_repository(&mut ctx).await? {
+        Some(repository) => repository,
+        None => return Ok(()),
+    };
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx
+        .bot_context
+        .datastore
+        .remove_github_repo(guild_id, &repository)
+        .await?
+    {
+        return reply_with_repository(&ctx, GearBotString::GithubUnknownRepository, &repository).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("repository", repository)
+        .generate();
+    ctx.reply(GearBotString::GithubRemoved, args).await?;
+    Ok(())
+}
+
+/// Changes which events of a repository are relayed, the webhook on GitHub can keep sending the others.
+pub async fn github_events(mut ctx: CommandContext) -> CommandResult {
+    let repository = match parse_repository(&mut ctx).await? {
+        Some(repository) => repository,
+        None => return Ok(()),
+    };
+    let events = match parse_events(&mut ctx).await? {
+        Some(events) => events,
+        None => return Ok(()),
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx
+        .bot_context
+        .datastore
+        .set_github_repo_events(guild_id, &repository, &events)
+        .await?
+    {
+        return reply_with_repository(&ctx, GearBotString::GithubUnknownRepository, &repository).await;
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("repository", repository)
+        .add("events", events.join(", "))
+        .generate();
+    ctx.reply(GearBotString::GithubEventsUpdated, args).await?;
+    Ok(())
+}
+
+/// Replaces the secret of a repository, for when the old one leaked. Deliveries signed with the old one are refused
+/// from then on.
+pub async fn github_secret(mut ctx: CommandContext) -> CommandResult {
+    let url = match webhook_url(&ctx).await? {
+        Some(url) => url,
+        None => return Ok(()),
+    };
+    let repository = match parse_repository(&mut ctx).await? {
+        Some(repository) => repository,
+        None => return Ok(()),
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let repo = match datastore
+        .get_github_repos(guild_id)
+        .await?
+        .into_iter()
+        .find(|repo| repo.repository == repository)
+    {
+        Some(repo) => repo,
+        None => return reply_with_repository(&ctx, GearBotString::GithubUnknownRepository, &repository).await,
+    };
+
+    let secret = generate_secret();
+    let channel_id = ChannelId(repo.channel_id as u64);
+    if !send_setup(&ctx, &url, &repository, &secret, &repo.events, channel_id).await? {
+        return reply_with_repository(&ctx, GearBotString::GithubDmFailed, &repository).await;
+    }
+    if !datastore.set_github_repo_secret(guild_id, &repository, &secret).await? {
+        return reply_with_repository(&ctx, GearBotString::GithubUnknownRepository, &repository).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("repository", repository)
+        .generate();
+    ctx.reply(GearBotString::GithubSecretReset, args).await?;
+    Ok(())
+}
+
+/// Where deliveries need to go, telling the author it can't be used when the webhook server isn't running.
+async fn webhook_url(ctx: &CommandContext) -> Result<Option<String>, CommandError> {
+    match &ctx.bot_context.webhook_url {
+        Some(url) => Ok(Some(format!("{}/webhooks/github", url.trim_end_matches('/')))),
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::GithubNotEnabled, args).await?;
+            Ok(None)
+        }
+    }
+}
+
+async fn parse_repository(ctx: &mut CommandContext) -> Result<Option<String>, CommandError> {
+    let input = ctx.parser.get_next()?.to_string();
+    let name = input.trim_start_matches("https://github.com/").trim_end_matches('/');
+    match normalize_repository(name) {
+        Some(repository) => Ok(Some(repository)),
+        None => {
+            reply_with_repository(ctx, GearBotString::GithubInvalidRepository, name).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Reads all remaining arguments as event names, there has to be at least one.
+async fn parse_events(ctx: &mut CommandContext) -> Result<Option<Vec<String>>, CommandError> {
+    let mut events = vec![];
+    loop {
+        let name = ctx.parser.get_next()?.to_string();
+        match GithubEvent::from_str(&name) {
+            Some(event) => {
+                let event = event.as_str().to_string();
+                if !events.contains(&event) {
+                    events.push(event);
+                }
+            }
+            None => {
+                let all: Vec<&str> = GithubEvent::ALL.iter().map(|event| event.as_str()).collect();
+                let args = FluArgs::with_capacity(3)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("event", name)
+                    .add("events", all.join(", "))
+                    .generate();
+                ctx.reply(GearBotString::GithubUnknownEvent, args).await?;
+                return Ok(None);
+            }
+        }
+        if !ctx.parser.has_next() {
+            return Ok(Some(events));
+        }
+    }
+}
+
+/// DMs the author everything needed to set up the webhook on GitHub, returning if that worked.
+async fn send_setup(
+    ctx: &CommandContext,
+    url: &str,
+    repository: &str,
+    secret: &str,
+    events: &[String],
+    channel_id: ChannelId,
+) -> Result<bool, CommandError> {
+    let args = FluArgs::with_capacity(5)
+        .add("repository", repository)
+        .add("url", url)
+        .add("secret", secret)
+        .add("events", events.join(", "))
+        .add("channel", channel_id.to_string())
+        .generate();
+    let content = ctx.translate_with_args(GearBotString::GithubSetupDm, &args);
+
+    let channel = match ctx.get_dm_for_author().await {
+        Ok(channel) => channel,
+        Err(e) => {
+            ctx.bot_context.track_http_error(&e).await;
+            return Ok(false);
+        }
+    };
+    if let Err(e) = ctx
+        .bot_context
+        .http
+        .create_message(channel.get_id())
+        .content(content)?
+        .await
+    {
+        ctx.bot_context.track_http_error(&e).await;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+async fn reply_with_repository(ctx: &CommandContext, key: GearBotString, repository: &str) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("repository", repository)
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use chrono::Utc;
+
+use crate::core::logpump::LogType;
+use crate::core::{CommandContext, LogCategory};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, Emoji};
+
+/// Shows a sample log of every category, or just the one asked for, with the customizations of the guild applied.
+pub async fn log_style_preview(mut ctx: CommandContext) -> CommandResult {
+    let categories = if ctx.parser.has_next() {
+        let name = ctx.parser.get_next()?.to_string();
+        match LogCategory::from_name(&name) {
+            Some(category) => vec![category],
+            None => {
+                let names = LogCategory::ALL
+                    .iter()
+                    .map(|category| format!("``{:?}``", category).to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let args = FluArgs::with_capacity(3)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("category", name)
+                    .add("categories", names)
+                    .generate();
+                ctx.reply(GearBotString::LogStyleUnknownCategory, args).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        LogCategory::ALL.to_vec()
+    };
+
+    let config = ctx.get_config()?;
+    let channel = Some(ctx.message.channel.get_id());
+    let now = Utc::now();
+    for category in categories {
+        let sample = match category {
+            LogCategory::GENERAL => LogType::RaidModeChanged {
+                enabled: true,
+                automatic: false,
+            },
+            LogCategory::COMMANDS => LogType::CommandUsed {
+                command: String::from("logstyle preview"),
+            },
+            LogCategory::MESSAGES => LogType::MessageDeleted {
+                channel: ctx.message.channel.get_id(),
+                content: ctx.translate(GearBotString::LogStylePreviewContent),
+                attachments: vec![],
+            },
+            LogCategory::MEMBERS => LogType::ScreeningCompleted {
+                gate_role: None,
+                succeeded: true,
+            },
+            LogCategory::WATCHLIST => LogType::WatchedJoin {
+                created: utils::snowflake_timestamp(ctx.message.author.id.0).timestamp(),
+            },
+        };
+
+        let appearance = config.log_appearance.get(&category);
+        let text = sample.to_text(
+            &ctx.bot_context,
+            &config.language,
+            &ctx.message.author,
+            &channel,
+            Some(&now),
+            appearance,
+        );
+        let embed = sample.to_embed(
+            &ctx.bot_context,
+            &config.language,
+            &ctx.message.author,
+            &channel,
+            &now,
+            appearance,
+        )?;
+        ctx.reply_raw_with_embed(text, embed).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+pub use activity::*;
+pub use aliases::*;
+pub use auto_publish::*;
+pub use automod::*;
+pub use autoresponses::*;
+pub use bot_bans::*;
+pub use channel_commands::*;
+pub use channel_templates::*;
+pub use config_transfer::*;
+pub use features::*;
+pub use feeds::*;
+pub use github::*;
+pub use log_style::*;
+pub use profiles::*;
+pub use stats::*;
+pub use timezone::*;
+pub use usage::*;
+pub use webhooks::*;
+
+mod activity;
+mod aliases;
+mod auto_publish;
+mod automod;
+mod autoresponses;
+mod bot_bans;
+mod channel_commands;
+mod channel_templates;
+mod config_transfer;
+mod features;
+mod feeds;
+mod github;
+mod log_style;
+mod profiles;
+mod stats;
+mod timezone;
+mod usage;
+mod webhooks;
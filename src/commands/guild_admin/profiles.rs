@@ -0,0 +1,210 @@
+use chrono::Utc;
+
+use crate::core::profiles::ConfigProfile;
+use crate::core::CommandContext;
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, Emoji};
+
+/// How many profiles a single guild can have.
+const MAX_PROFILES: usize = 10;
+
+pub async fn profile_list(ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if config.profiles.is_empty() {
+        ctx.reply(GearBotString::ProfileListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut profiles: Vec<(&String, &ConfigProfile)> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| *name);
+
+    let mut reply = ctx.translate(GearBotString::ProfileListHeader);
+    for (name, profile) in profiles {
+        let args = FluArgs::with_capacity(2)
+            .add("name", name.as_str())
+            .add("sections", section_list(profile))
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::ProfileListEntry, &args);
+    }
+
+    if let Some(pending) = ctx
+        .bot_context
+        .datastore
+        .get_profile_revert(ctx.get_guild()?.id)
+        .await?
+    {
+        let args = FluArgs::with_capacity(2)
+            .add("name", pending.profile)
+            .add("at", pending.revert_at.to_string())
+            .generate();
+        reply += "\n\n";
+        reply += &ctx.translate_with_args(GearBotString::ProfileListPending, &args);
+    }
+
+    ctx.reply_raw(reply).await?;
+    Ok(())
+}
+
+pub async fn profile_save(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+    let sections = ctx.parser.get_remaining();
+    let sections: Vec<&str> = sections.split_whitespace().collect();
+    if sections.is_empty() {
+        return Err(ParseError::MissingArgument.into());
+    }
+
+    let mut config = (*ctx.get_config()?).clone();
+    if !config.profiles.contains_key(&name) && config.profiles.len() >= MAX_PROFILES {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_PROFILES)
+            .generate();
+        ctx.reply(GearBotString::ProfileLimitReached, args).await?;
+        return Ok(());
+    }
+
+    let profile = match ConfigProfile::capture(&config, &sections) {
+        Ok(profile) => profile,
+        Err(e) => return invalid_profile(&ctx, e.to_string()).await,
+    };
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name.clone())
+        .add("sections", section_list(&profile))
+        .generate();
+    config.profiles.insert(name, profile);
+    ctx.set_config(config).await?;
+
+    ctx.reply(GearBotString::ProfileSaved, args).await?;
+    Ok(())
+}
+
+pub async fn profile_delete(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+
+    let mut config = (*ctx.get_config()?).clone();
+    if config.profiles.remove(&name).is_none() {
+        return unknown_profile(&ctx, name).await;
+    }
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::ProfileDeleted, args).await?;
+    Ok(())
+}
+
+/// Applies a profile, optionally only for a while before putting back what it changed.
+pub async fn profile_apply(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_lowercase();
+    let duration = if ctx.parser.has_next() {
+        let input = ctx.parser.get_next()?.to_string();
+        match utils::parse_duration(&input).and_then(|duration| duration.to_std().ok()) {
+            Some(duration) => Some(duration),
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("duration", input)
+                    .generate();
+                ctx.reply(GearBotString::ProfileInvalidDuration, args).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        None
+    };
+
+    let guild_id = ctx.get_guild()?.id;
+    let config = ctx.get_config()?;
+    let profile = match config.profiles.get(&name) {
+        Some(profile) => profile,
+        None => return unknown_profile(&ctx, name).await,
+    };
+    let applied = match profile.apply_to(&config) {
+        Ok(applied) => applied,
+        Err(e) => return invalid_profile(&ctx, e.to_string()).await,
+    };
+
+    if let Some(duration) = duration {
+        let sections: Vec<&String> = profile.sections.keys().collect();
+        let current = match ConfigProfile::capture(&config, &sections) {
+            Ok(current) => current,
+            Err(e) => return invalid_profile(&ctx, e.to_string()).await,
+        };
+        // An earlier revert still knows what things looked like before any profile was applied
+        let snapshot = match ctx.bot_context.datastore.get_profile_revert(guild_id).await? {
+            Some(mut pending) => {
+                pending.snapshot.fill_from(current);
+                pending.snapshot
+            }
+            None => current,
+        };
+        ctx.bot_context
+            .datastore
+            .schedule_profile_revert(guild_id, &name, duration, &snapshot)
+            .await?;
+    }
+    ctx.set_config(applied).await?;
+
+    let (key, args) = match duration {
+        Some(duration) => {
+            let at = Utc::now().timestamp() + duration.as_secs() as i64;
+            let args = FluArgs::with_capacity(3).add("at", at.to_string());
+            (GearBotString::ProfileAppliedUntil, args)
+        }
+        None => (GearBotString::ProfileApplied, FluArgs::with_capacity(2)),
+    };
+    let args = args.add("emoji", Emoji::Yes.for_chat()).add("name", name).generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+pub async fn profile_revert(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    match ctx.bot_context.revert_profile(guild_id).await? {
+        Some(name) => {
+            let args = FluArgs::with_capacity(2)
+                .add("emoji", Emoji::Yes.for_chat())
+                .add("name", name)
+                .generate();
+            ctx.reply(GearBotString::ProfileReverted, args).await?;
+        }
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::ProfileNothingToRevert, args).await?;
+        }
+    }
+    Ok(())
+}
+
+fn section_list(profile: &ConfigProfile) -> String {
+    profile
+        .sections
+        .keys()
+        .map(|section| format!("``{}``", section))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+async fn invalid_profile(ctx: &CommandContext, reason: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("reason", reason)
+        .generate();
+    ctx.reply(GearBotString::ProfileInvalid, args).await?;
+    Ok(())
+}
+
+async fn unknown_profile(ctx: &CommandContext, name: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("name", name)
+        .generate();
+    ctx.reply(GearBotString::ProfileUnknown, args).await?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use chrono::{Duration, Utc};
+
+use crate::core::guild_stats::trend;
+use crate::core::CommandContext;
+use crate::database::structures::GuildStatsDay;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// How far back the daily overview goes, the trends compare the last week with the one before.
+const STATS_DAYS: u32 = 14;
+
+/// The counted statistics, with how they're called.
+const COLUMNS: [(GearBotString, fn(&GuildStatsDay) -> i32); 5] = [
+    (GearBotString::StatsGuildMessages, |day| day.messages),
+    (GearBotString::StatsGuildJoins, |day| day.joins),
+    (GearBotString::StatsGuildLeaves, |day| day.leaves),
+    (GearBotString::StatsGuildCommands, |day| day.commands),
+    (GearBotString::StatsGuildAutomod, |day| day.automod_triggers),
+];
+
+/// Shows the activity of the last week compared to the one before, and the counts of every day.
+pub async fn stats_guild(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let days = ctx.bot_context.datastore.get_guild_stats(guild_id, STATS_DAYS).await?;
+    if days.is_empty() {
+        let args = FluArgs::with_capacity(1)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .generate();
+        ctx.reply(GearBotString::StatsGuildEmpty, args).await?;
+        return Ok(());
+    }
+
+    let week_start = (Utc::now() - Duration::days(7)).timestamp();
+    let (this_week, last_week): (Vec<&GuildStatsDay>, Vec<&GuildStatsDay>) =
+        days.iter().partition(|day| day.day > week_start);
+
+    let args = FluArgs::with_capacity(1)
+        .add("gearinfo", Emoji::Info.for_chat())
+        .generate();
+    let mut content = ctx.translate_with_args(GearBotString::StatsGuildHeader, &args);
+    for (name, count) in &COLUMNS {
+        let current = this_week.iter().map(|day| count(day) as u64).sum::<u64>();
+        let previous = last_week.iter().map(|day| count(day) as u64).sum::<u64>();
+        let trend = match trend(current, previous) {
+            Some(trend) => ctx.translate_with_args(
+                GearBotString::StatsGuildTrend,
+                &FluArgs::with_capacity(1)
+                    .add("trend", format!("{:+}", trend))
+                    .generate(),
+            ),
+            None => String::new(),
+        };
+        let args = FluArgs::with_capacity(3)
+            .add("name", ctx.translate(*name))
+            .add("count", ctx.format_number(current as f64))
+            .add("trend", trend)
+            .generate();
+        content += "\n";
+        content += &ctx.translate_with_args(GearBotString::StatsGuildLine, &args);
+    }
+
+    let names = COLUMNS
+        .iter()
+        .map(|(name, _)| ctx.translate(*name))
+        .collect::<Vec<String>>();
+    let date_width = days
+        .iter()
+        .map(|day| ctx.format_date(day.day).chars().count())
+        .max()
+        .unwrap_or(0);
+    content += "\n\n";
+    content += &ctx.translate(GearBotString::StatsGuildDaily);
+    content += &format!("\n```\n{:width$}", "", width = date_width);
+    for name in &names {
+        content += &format!("  {}", name);
+    }
+    for day in &days {
+        content += &format!("\n{:width$}", ctx.format_date(day.day), width = date_width);
+        for ((_, count), name) in COLUMNS.iter().zip(&names) {
+            content += &format!("  {:>width$}", count(day), width = name.chars().count());
+        }
+    }
+    content += "\n```";
+
+    ctx.reply_long(content).await?;
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn config_timezone(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_string();
+    let timezone = match name.parse::<Tz>() {
+        Ok(timezone) => timezone,
+        Err(_) => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("timezone", name)
+                .generate();
+            ctx.reply(GearBotString::TimezoneUnknown, args).await?;
+            return Ok(());
+        }
+    };
+
+    let mut config = (*ctx.get_config()?).clone();
+    config.timezone = timezone.name().to_string();
+    ctx.set_config(config).await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("timezone", timezone.name())
+        .add("time", Utc::now().with_timezone(&timezone).format("%H:%M").to_string())
+        .generate();
+    ctx.reply(GearBotString::TimezoneSet, args).await?;
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::commands::meta::nodes::CommandGroup;
+use crate::commands::ROOT_NODE;
+use crate::core::guild_stats::{self, STATS_RETENTION_DAYS};
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, Emoji};
+
+const DEFAULT_WINDOW_DAYS: u32 = 30;
+/// The less used ones are still in the API.
+const COMMAND_LIMIT: usize = 20;
+
+/// Shows which commands were used on the server over a window, and which ones weren't used at all.
+pub async fn usage(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let mut window_days = DEFAULT_WINDOW_DAYS;
+    if ctx.parser.has_next() {
+        let argument = ctx.parser.get_next()?.to_string();
+        match utils::parse_duration(&argument).filter(|duration| duration.num_days() > 0) {
+            Some(duration) => window_days = (duration.num_days() as u32).min(STATS_RETENTION_DAYS),
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("window", argument)
+                    .generate();
+                ctx.reply(GearBotString::UsageInvalidWindow, args).await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let window = ctx.format_duration(window_days as i64 * 86_400, 2);
+    let usage = ctx
+        .bot_context
+        .datastore
+        .get_command_usage(guild_id, window_days)
+        .await?;
+    if usage.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .add("window", window)
+            .generate();
+        ctx.reply(GearBotString::UsageEmpty, args).await?;
+        return Ok(());
+    }
+
+    let total = usage.iter().map(|command| command.uses as u64).sum::<u64>();
+    let args = FluArgs::with_capacity(3)
+        .add("gearinfo", Emoji::Info.for_chat())
+        .add("window", window)
+        .add("total", total)
+        .generate();
+    let mut content = ctx.translate_with_args(GearBotString::UsageHeader, &args);
+    for command in usage.iter().take(COMMAND_LIMIT) {
+        let args = FluArgs::with_capacity(4)
+            .add("command", command.command.clone())
+            .add("uses", ctx.format_number(command.uses as f64))
+            .add("share", guild_stats::share(command.uses as u64, total))
+            .add("channels", command.channels)
+            .generate();
+        content += "\n";
+        content += &ctx.translate_with_args(GearBotString::UsageLine, &args);
+        if command.failures > 0 {
+            let args = FluArgs::with_capacity(1).add("failures", command.failures).generate();
+            content += &ctx.translate_with_args(GearBotString::UsageFailures, &args);
+        }
+    }
+    if usage.len() > COMMAND_LIMIT {
+        let args = FluArgs::with_capacity(1)
+            .add("count", usage.len() - COMMAND_LIMIT)
+            .generate();
+        content += "\n";
+        content += &ctx.translate_with_args(GearBotString::UsageMore, &args);
+    }
+
+    // Only the top level, a command counts as used when any of its subcommands was
+    let unused = ROOT_NODE
+        .command_list
+        .iter()
+        .filter(|node| node.group != CommandGroup::BotAdmin)
+        .map(|node| node.name.as_str())
+        .filter(|name| {
+            !usage
+                .iter()
+                .any(|command| command.command.split(' ').next() == Some(*name))
+        })
+        .collect::<Vec<&str>>();
+    if !unused.is_empty() {
+        let commands = unused
+            .iter()
+            .map(|name| format!("``{}``", name))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let args = FluArgs::with_capacity(1).add("commands", commands).generate();
+        content += "\n\n";
+        content += &ctx.translate_with_args(GearBotString::UsageUnused, &args);
+    }
+
+    ctx.reply_long(content).await?;
+    Ok(())
+}
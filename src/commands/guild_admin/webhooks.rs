@@ -0,0 +1,260 @@
+use twilight_model::guild::Permissions;
+use twilight_model::id::ChannelId;
+
+use crate::cache::CachedChannel;
+use crate::core::github::generate_secret;
+use crate::core::webhook_server::CUSTOM_PATH;
+use crate::core::webhooks::{hash_token, DEFAULT_TEMPLATE, MAX_ENDPOINTS, TEMPLATE_LIMIT};
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn webhook_list(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let endpoints = ctx.bot_context.datastore.get_webhook_endpoints(guild_id).await?;
+    if endpoints.is_empty() {
+        ctx.reply(GearBotString::WebhookListEmpty, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let mut reply = ctx.translate(GearBotString::WebhookListHeader);
+    for endpoint in endpoints {
+        let args = FluArgs::with_capacity(3)
+            .add("id", endpoint.id)
+            .add("name", endpoint.name)
+            .add("channel", endpoint.channel_id.to_string())
+            .generate();
+        reply += "\n";
+        reply += &ctx.translate_with_args(GearBotString::WebhookListEntry, &args);
+    }
+
+    ctx.reply_long(reply).await?;
+    Ok(())
+}
+
+/// Creates an endpoint: ``webhook add #deploys ci Build {build.number}: {status}``. The template is optional and
+/// fills in `{placeholders}` with the values at those paths in the JSON that is posted, the url is sent to the
+/// author in DMs.
+pub async fn webhook_add(mut ctx: CommandContext) -> CommandResult {
+    if !webhooks_enabled(&ctx).await? {
+        return Ok(());
+    }
+    let channel = ctx.parser.get_channel().await?;
+    let channel_id = channel.get_id();
+    let name = ctx.parser.get_next()?.to_string();
+    let template = match ctx.parser.get_remaining() {
+        template if template.is_empty() => DEFAULT_TEMPLATE.to_string(),
+        template => template,
+    };
+
+    if !matches!(
+        *channel,
+        CachedChannel::TextChannel { .. } | CachedChannel::AnnouncementsChannel { .. }
+    ) || !ctx
+        .bot_has_permissions_in_channel(channel_id, Permissions::SEND_MESSAGES)
+        .await
+    {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::WebhookCantPost, args).await?;
+        return Ok(());
+    }
+    if !template_fits(&ctx, &template).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    if datastore.get_webhook_endpoints(guild_id).await?.len() >= MAX_ENDPOINTS {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("max", MAX_ENDPOINTS)
+            .generate();
+        ctx.reply(GearBotString::WebhookLimit, args).await?;
+        return Ok(());
+    }
+
+    // Only created once the author has the url, an endpoint nobody can post to is of no use
+    let token = generate_secret();
+    if !send_url(&ctx, &name, channel_id, &token).await? {
+        return reply_dm_failed(&ctx).await;
+    }
+    let endpoint = datastore
+        .create_webhook_endpoint(guild_id, &name, channel_id, &template, &hash_token(&token))
+        .await?;
+
+    let args = FluArgs::with_capacity(4)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", endpoint.id)
+        .add("name", endpoint.name)
+        .add("channel", channel_id.to_string())
+        .generate();
+    ctx.reply(GearBotString::WebhookAdded, args).await?;
+    Ok(())
+}
+
+pub async fn webhook_remove(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx.bot_context.datastore.remove_webhook_endpoint(guild_id, id).await? {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::WebhookRemoved, args).await?;
+    Ok(())
+}
+
+/// Sets what deliveries of an endpoint turn into, leaving the template out goes back to posting their `content`.
+pub async fn webhook_template(mut ctx: CommandContext) -> CommandResult {
+    let id = parse_id(&mut ctx)?;
+    let template = match ctx.parser.get_remaining() {
+        template if template.is_empty() => DEFAULT_TEMPLATE.to_string(),
+        template => template,
+    };
+    if !template_fits(&ctx, &template).await? {
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    if !ctx
+        .bot_context
+        .datastore
+        .set_webhook_endpoint_template(guild_id, id, &template)
+        .await?
+    {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::WebhookTemplateUpdated, args).await?;
+    Ok(())
+}
+
+/// Replaces the url of an endpoint, for when the old one leaked. The old url stops working right away.
+pub async fn webhook_token(mut ctx: CommandContext) -> CommandResult {
+    if !webhooks_enabled(&ctx).await? {
+        return Ok(());
+    }
+    let id = parse_id(&mut ctx)?;
+
+    let guild_id = ctx.get_guild()?.id;
+    let datastore = &ctx.bot_context.datastore;
+    let endpoint = match datastore
+        .get_webhook_endpoints(guild_id)
+        .await?
+        .into_iter()
+        .find(|endpoint| endpoint.id == id)
+    {
+        Some(endpoint) => endpoint,
+        None => return reply_unknown(&ctx, id).await,
+    };
+
+    let token = generate_secret();
+    let channel_id = ChannelId(endpoint.channel_id as u64);
+    if !send_url(&ctx, &endpoint.name, channel_id, &token).await? {
+        return reply_dm_failed(&ctx).await;
+    }
+    if !datastore
+        .set_webhook_endpoint_token(guild_id, id, &hash_token(&token))
+        .await?
+    {
+        return reply_unknown(&ctx, id).await;
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::WebhookTokenReset, args).await?;
+    Ok(())
+}
+
+/// Tells the author endpoints can't be used when the webhook server isn't running.
+async fn webhooks_enabled(ctx: &CommandContext) -> Result<bool, CommandError> {
+    if ctx.bot_context.webhook_url.is_some() {
+        return Ok(true);
+    }
+
+    let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+    ctx.reply(GearBotString::WebhookNotEnabled, args).await?;
+    Ok(false)
+}
+
+async fn template_fits(ctx: &CommandContext, template: &str) -> Result<bool, CommandError> {
+    if template.chars().count() <= TEMPLATE_LIMIT {
+        return Ok(true);
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("max", TEMPLATE_LIMIT)
+        .generate();
+    ctx.reply(GearBotString::WebhookTemplateTooLong, args).await?;
+    Ok(false)
+}
+
+/// DMs the author the url to post to, returning if that worked.
+async fn send_url(ctx: &CommandContext, name: &str, channel_id: ChannelId, token: &str) -> Result<bool, CommandError> {
+    let base = ctx.bot_context.webhook_url.as_deref().unwrap_or_default();
+    let url = format!("{}{}{}", base.trim_end_matches('/'), CUSTOM_PATH, token);
+    let args = FluArgs::with_capacity(3)
+        .add("name", name)
+        .add("channel", channel_id.to_string())
+        .add("url", url)
+        .generate();
+    let content = ctx.translate_with_args(GearBotString::WebhookSetupDm, &args);
+
+    let channel = match ctx.get_dm_for_author().await {
+        Ok(channel) => channel,
+        Err(e) => {
+            ctx.bot_context.track_http_error(&e).await;
+            return Ok(false);
+        }
+    };
+    if let Err(e) = ctx
+        .bot_context
+        .http
+        .create_message(channel.get_id())
+        .content(content)?
+        .await
+    {
+        ctx.bot_context.track_http_error(&e).await;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+fn parse_id(ctx: &mut CommandContext) -> Result<i32, ParseError> {
+    ctx.parser
+        .get_next()?
+        .trim_start_matches('#')
+        .parse::<i32>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("webhook number")))
+}
+
+async fn reply_unknown(ctx: &CommandContext, id: i32) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("id", id)
+        .generate();
+    ctx.reply(GearBotString::WebhookUnknown, args).await?;
+    Ok(())
+}
+
+async fn reply_dm_failed(ctx: &CommandContext) -> CommandResult {
+    let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+    ctx.reply(GearBotString::WebhookDmFailed, args).await?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use twilight_model::id::UserId;
+
+use crate::core::{levels, CommandContext, GuildFeatures};
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+
+const LEADERBOARD_EMBED_COLOR: u32 = 0x00_cea2;
+const PAGE_SIZE: u32 = 10;
+
+pub async fn leaderboard(mut ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if !config.features.contains(GuildFeatures::LEVELING) || !config.levels.enabled {
+        let args = FluArgs::with_capacity(0).generate();
+        ctx.reply(GearBotString::LevelsDisabled, args).await?;
+        return Ok(());
+    }
+
+    let page = if ctx.parser.has_next() {
+        ctx.parser
+            .get_next()?
+            .parse::<u32>()
+            .map_err(|_| ParseError::WrongArgumentType(String::from("page number")))?
+            .max(1)
+    } else {
+        1
+    };
+
+    let guild = ctx.get_guild()?.clone();
+    let datastore = &ctx.bot_context.datastore;
+
+    let total = datastore.get_leaderboard_size(guild.id).await?;
+    let pages = ((total + PAGE_SIZE as u64 - 1) / PAGE_SIZE as u64).max(1) as u32;
+    let page = page.min(pages);
+
+    let entries = datastore
+        .get_leaderboard(guild.id, PAGE_SIZE, (page - 1) * PAGE_SIZE)
+        .await?;
+
+    if entries.is_empty() {
+        let args = FluArgs::with_capacity(0).generate();
+        ctx.reply(GearBotString::LevelsLeaderboardEmpty, args).await?;
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let user_id = UserId(entry.user_id as u64);
+        let name = match ctx.bot_context.get_user(user_id).await {
+            Ok(user) => user.full_name(),
+            Err(_) => user_id.to_string(),
+        };
+
+        let args = FluArgs::with_capacity(4)
+            .add("position", (page - 1) * PAGE_SIZE + index as u32 + 1)
+            .add("name", name)
+            .add("level", levels::level_for_xp(entry.xp as u64))
+            .add("xp", entry.xp)
+            .generate();
+
+        description += &ctx.translate_with_args(GearBotString::LevelsLeaderboardEntry, &args);
+        description += "\n";
+    }
+
+    let header_args = FluArgs::with_capacity(3)
+        .add("guild_name", guild.name.clone())
+        .add("page", page)
+        .add("pages", pages)
+        .generate();
+
+    let embed = SafeEmbed::new()
+        .title(ctx.translate_with_args(GearBotString::LevelsLeaderboardHeader, &header_args))
+        .description(description)
+        .color(LEADERBOARD_EMBED_COLOR);
+
+    ctx.reply_safe_embed(embed).await?;
+
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub use leaderboard::leaderboard;
+pub use rank::rank;
+
+mod leaderboard;
+mod rank;
@@ -0,0 +1,45 @@
+use crate::core::{levels, CommandContext, GuildFeatures};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+
+const RANK_EMBED_COLOR: u32 = 0x00_cea2;
+
+pub async fn rank(mut ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if !config.features.contains(GuildFeatures::LEVELING) || !config.levels.enabled {
+        let args = FluArgs::with_capacity(0).generate();
+        ctx.reply(GearBotString::LevelsDisabled, args).await?;
+        return Ok(());
+    }
+
+    let user = ctx.parser.get_user_or(ctx.message.author.clone()).await?;
+    let guild_id = ctx.get_guild()?.id;
+
+    match ctx.bot_context.datastore.get_rank(guild_id, user.id).await? {
+        Some((xp, rank)) => {
+            let (level, progress, needed) = levels::level_progress(xp);
+
+            let args = FluArgs::with_capacity(5)
+                .add("rank", rank)
+                .add("level", level)
+                .add("progress", progress)
+                .add("needed", needed)
+                .add("total", xp)
+                .generate();
+
+            let embed = SafeEmbed::new()
+                .author(user.full_name(), Some(user.avatar_url()))
+                .description(ctx.translate_with_args(GearBotString::LevelsRank, &args))
+                .color(RANK_EMBED_COLOR);
+
+            ctx.reply_safe_embed(embed).await?;
+        }
+        None => {
+            let args = FluArgs::with_capacity(1).add("userid", user.id.to_string()).generate();
+            ctx.reply(GearBotString::LevelsNoXp, args).await?;
+        }
+    }
+
+    Ok(())
+}
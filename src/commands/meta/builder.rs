@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use twilight_model::guild::Permissions;
+
+use super::nodes::{CommandGroup, CommandHandler, CommandNode, GearBotPermissions, RootNode};
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+
+/// Starts the definition of a command, see [`RootBuilder`] for how they are put together.
+pub fn command(name: &str, command_permission: GearBotPermissions) -> CommandBuilder {
+    CommandBuilder {
+        name: name.to_string(),
+        aliases: vec![],
+        handler: None,
+        bot_permissions: Permissions::empty(),
+        command_permission,
+        supports_dry_run: false,
+        dm_only: false,
+        requires_confirmation: false,
+        subcommands: vec![],
+    }
+}
+
+pub struct CommandBuilder {
+    name: String,
+    aliases: Vec<String>,
+    handler: Option<CommandHandler>,
+    bot_permissions: Permissions,
+    command_permission: GearBotPermissions,
+    supports_dry_run: bool,
+    dm_only: bool,
+    requires_confirmation: bool,
+    subcommands: Vec<CommandBuilder>,
+}
+
+impl CommandBuilder {
+    pub fn handler<F, R>(mut self, handler: F) -> Self
+    where
+        F: Fn(CommandContext) -> R + Send + Sync + 'static,
+        R: Future<Output = CommandResult> + Send + 'static,
+    {
+        self.handler = Some(Box::new(move |ctx| Box::pin(handler(ctx))));
+        self
+    }
+
+    pub fn aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+        self
+    }
+
+    /// Permissions the bot needs in the channel to be able to run this command.
+    pub fn bot_permissions(mut self, permissions: Permissions) -> Self {
+        self.bot_permissions = permissions;
+        self
+    }
+
+    /// The command makes changes that are hard to revert, it will accept the `--dry-run`/`-n` flag.
+    pub fn destructive(mut self) -> Self {
+        self.supports_dry_run = true;
+        self
+    }
+
+    /// The command deals with personal data, it can only be used in DMs.
+    pub fn dm_only(mut self) -> Self {
+        self.dm_only = true;
+        self
+    }
+
+    /// The command does something drastic, the author has to confirm it before it runs. Dry runs don't ask.
+    pub fn requires_confirmation(mut self) -> Self {
+        self.requires_confirmation = true;
+        self
+    }
+
+    pub fn subcommand(mut self, subcommand: CommandBuilder) -> Self {
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    /// Builds the node, subcommands end up in the same group as their parent.
+    ///
+    /// Panics when two subcommands share a name or alias, this is a mistake in the command definitions.
+    fn build(self, group: &CommandGroup) -> Arc<CommandNode> {
+        let mut sub_nodes = HashMap::new();
+        let mut node_list = vec![];
+        for subcommand in self.subcommands {
+            let node = subcommand.build(group);
+            for name in std::iter::once(&node.name).chain(&node.aliases) {
+                if sub_nodes.insert(name.clone(), node.clone()).is_some() {
+                    panic!(
+                        "Tried to register subcommand {} under {} but a subcommand is already registered under this name",
+                        name, self.name
+                    );
+                }
+            }
+            node_list.push(node);
+        }
+
+        Arc::new(CommandNode {
+            name: self.name,
+            supports_dry_run: self.supports_dry_run,
+            dm_only: self.dm_only,
+            requires_confirmation: self.requires_confirmation,
+            handler: self.handler,
+            sub_nodes,
+            node_list,
+            // The prompt is answered with reactions, those have to be added first
+            bot_permissions: if self.requires_confirmation {
+                self.bot_permissions | Permissions::ADD_REACTIONS
+            } else {
+                self.bot_permissions
+            },
+            command_permission: self.command_permission,
+            group: group.clone(),
+            aliases: self.aliases,
+        })
+    }
+}
+
+/// Puts the full command tree together from the commands of every group.
+#[derive(Default)]
+pub struct RootBuilder {
+    groups: Vec<(CommandGroup, Vec<CommandBuilder>)>,
+}
+
+impl RootBuilder {
+    pub fn group(mut self, group: CommandGroup, commands: Vec<CommandBuilder>) -> Self {
+        self.groups.push((group, commands));
+        self
+    }
+
+    /// Panics when two commands share a name or alias, this is a mistake in the command definitions.
+    pub fn build(self) -> RootNode {
+        let mut all_commands = HashMap::new();
+        let mut command_list = vec![];
+        let mut by_group = HashMap::new();
+        let mut groups = vec![];
+
+        for (group, commands) in self.groups {
+            let mut nodes = vec![];
+            for command in commands {
+                let node = command.build(&group);
+                for name in std::iter::once(&node.name).chain(&node.aliases) {
+                    if all_commands.insert(name.clone(), node.clone()).is_some() {
+                        panic!(
+                            "Tried to register command {} but another command was already registered with that name!",
+                            name
+                        );
+                    }
+                }
+                nodes.push(node);
+            }
+            nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+            command_list.extend(nodes.iter().cloned());
+            by_group.insert(group.clone(), nodes);
+            groups.push(group);
+        }
+
+        command_list.sort_by(|a: &Arc<CommandNode>, b| a.name.cmp(&b.name));
+        log::info!("Loaded {} commands in {} groups", command_list.len(), by_group.len());
+
+        RootNode {
+            all_commands,
+            command_list,
+            by_group,
+            groups,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn nothing(_ctx: CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    #[test]
+    fn subcommands_inherit_the_group() {
+        let root = RootBuilder::default()
+            .group(
+                CommandGroup::GuildAdmin,
+                vec![command("config", GearBotPermissions::CONFIG_COMMAND)
+                    .aliases(&["c"])
+                    .subcommand(command("get", GearBotPermissions::READ_CONFIG).handler(nothing))],
+            )
+            .build();
+
+        assert!(root.all_commands.contains_key("c"));
+        let config = &root.all_commands["config"];
+        assert!(config.handler.is_none());
+        assert!(config.sub_nodes["get"].group == CommandGroup::GuildAdmin);
+        assert_eq!(root.by_group[&CommandGroup::GuildAdmin].len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_aliases_are_rejected() {
+        RootBuilder::default()
+            .group(
+                CommandGroup::Basic,
+                vec![
+                    command("ping", GearBotPermissions::PING_COMMAND).handler(nothing),
+                    command("pong", GearBotPermissions::PING_COMMAND)
+                        .aliases(&["ping"])
+                        .handler(nothing),
+                ],
+            )
+            .build();
+    }
+}
@@ -70,6 +70,48 @@ bitflags! {
         const MISC_GROUP            = 0x004_000;
         const EMOJI_COMMAND         = 0x008_000;
         const EMOJI_LIST_COMMAND    = 0x010_000;
+        const RANK_COMMAND          = 0x020_000;
+        const LEADERBOARD_COMMAND   = 0x040_000;
+        const SNIPE_COMMAND         = 0x080_000;
+        const EDIT_SNIPE_COMMAND    = 0x100_000;
+        const ROLL_COMMAND          = 0x200_000;
+        const CHOOSE_COMMAND        = 0x400_000;
+        const EIGHTBALL_COMMAND     = 0x800_000;
+        const UNDO_COMMAND          = 0x1_000_000;
+        const MY_DATA_COMMAND       = 0x2_000_000;
+        const FEATURE_COMMAND       = 0x4_000_000;
+        const ALIAS_COMMAND         = 0x8_000_000;
+        const COMMANDS_COMMAND      = 0x10_000_000;
+        const BOTBAN_COMMAND        = 0x20_000_000;
+        const SEARCH_COMMAND        = 0x40_000_000;
+        const ARCHIVE_COMMAND       = 0x80_000_000;
+        const AUTOMOD_COMMAND       = 0x100_000_000;
+        const HEAT_COMMAND          = 0x200_000_000;
+        const JOIN_GATE_COMMAND     = 0x400_000_000;
+        const RAID_MODE_COMMAND     = 0x800_000_000;
+        const PROFILE_COMMAND       = 0x1_000_000_000;
+        const PRUNE_COMMAND         = 0x2_000_000_000;
+        const BIRTHDAY_COMMAND      = 0x4_000_000_000;
+        const WATCH_COMMAND         = 0x8_000_000_000;
+        const AUDIT_COMMAND         = 0x10_000_000_000;
+        const LOG_STYLE_COMMAND     = 0x20_000_000_000;
+        const STATS_COMMAND         = 0x40_000_000_000;
+        const ACTIVITY_COMMAND      = 0x80_000_000_000;
+        const MODSTATS_COMMAND      = 0x100_000_000_000;
+        const USAGE_COMMAND         = 0x200_000_000_000;
+        const ROLE_COMMAND          = 0x400_000_000_000;
+        const EMOJI_MANAGE_COMMAND  = 0x800_000_000_000;
+        const CHANNEL_COMMAND       = 0x1_000_000_000_000;
+        const AUTORESPONSE_COMMAND  = 0x2_000_000_000_000;
+        const SUGGEST_COMMAND       = 0x4_000_000_000_000;
+        const SUGGESTION_COMMAND    = 0x8_000_000_000_000;
+        const TICKET_COMMAND        = 0x10_000_000_000_000;
+        const TICKET_MANAGE_COMMAND = 0x20_000_000_000_000;
+        const EVENT_COMMAND         = 0x40_000_000_000_000;
+        const AUTOPUBLISH_COMMAND   = 0x80_000_000_000_000;
+        const FEED_COMMAND          = 0x100_000_000_000_000;
+        const GITHUB_COMMAND        = 0x200_000_000_000_000;
+        const WEBHOOK_COMMAND       = 0x400_000_000_000_000;
     }
 }
 
@@ -90,6 +132,12 @@ impl Serialize for GearBotPermissions {
 
 pub struct CommandNode {
     pub name: String,
+    /// If set, the parser will strip out `--dry-run`/`-n` and tell the handler to only report what it would do.
+    pub supports_dry_run: bool,
+    /// Only usable in DMs, anything else only works inside guilds.
+    pub dm_only: bool,
+    /// The author has to confirm they really want to run it before the handler is called.
+    pub requires_confirmation: bool,
     pub handler: Option<CommandHandler>,
     pub sub_nodes: HashMap<String, Arc<CommandNode>>,
     pub node_list: Vec<Arc<CommandNode>>,
@@ -1,14 +1,24 @@
+use std::sync::Arc;
+
+use twilight_http::request::AuditLogReason;
+use twilight_model::id::EmojiId;
+use url::Url;
+
+use crate::cache::CachedEmoji;
+use crate::core::guild_emoji::{self, MAX_EMOJI_SIZE};
+use crate::core::public_fetch;
 use crate::core::reactors::gen_emoji_page;
 use crate::core::{CommandContext, Reactor};
-use crate::error::CommandResult;
-use crate::utils::Emoji;
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{matchers, Emoji};
 
 pub async fn emoji_list(ctx: CommandContext) -> CommandResult {
-    let guild = ctx.get_guild();
+    let guild = ctx.get_guild()?;
     let guild_config = &ctx.get_config()?;
 
     let reactor = Reactor::new_emoji_list();
-    let pages = guild.emoji.len() as u8 + 1;
+    let pages = guild.emoji.read().await.len() as u8 + 1;
     let page = gen_emoji_page(0, pages, guild, guild_config, &ctx.bot_context).await?;
 
     let message = ctx.reply_embed(page).await?;
@@ -30,3 +40,228 @@ pub async fn emoji_list(ctx: CommandContext) -> CommandResult {
 pub async fn emoji_info(_ctx: CommandContext) -> CommandResult {
     Ok(())
 }
+
+/// Uploads a new emoji from a link or an attached image.
+pub async fn emoji_add(mut ctx: CommandContext) -> CommandResult {
+    let name = ctx.parser.get_next()?.to_string();
+    let url = if ctx.parser.has_next() {
+        ctx.parser.get_next()?.to_string()
+    } else {
+        match ctx.message.attachments.first() {
+            Some(attachment) => attachment.url.clone(),
+            None => {
+                let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+                ctx.reply(GearBotString::EmojiNoImage, args).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    upload_emoji(&ctx, name, &url).await
+}
+
+/// Copies a custom emoji from another server, given directly or as the first one in a linked message.
+pub async fn emoji_steal(mut ctx: CommandContext) -> CommandResult {
+    let direct = ctx.parser.peek().map_or(false, |input| matchers::contains_emote(input));
+    let content = if direct {
+        ctx.parser.get_next()?.to_string()
+    } else {
+        ctx.get_message().await?.content
+    };
+    let stolen = match matchers::get_emoji_parts(&content).into_iter().next() {
+        Some(stolen) => stolen,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::EmojiNothingToSteal, args).await?;
+            return Ok(());
+        }
+    };
+    let name = if ctx.parser.has_next() {
+        ctx.parser.get_next()?.to_string()
+    } else {
+        stolen.name.clone()
+    };
+
+    let url = format!(
+        "https://cdn.discordapp.com/emojis/{}.{}",
+        stolen.id,
+        if stolen.animated { "gif" } else { "png" }
+    );
+    upload_emoji(&ctx, name, &url).await
+}
+
+pub async fn emoji_remove(mut ctx: CommandContext) -> CommandResult {
+    let emoji = match find_emoji(&mut ctx).await? {
+        Some(emoji) => emoji,
+        None => return Ok(()),
+    };
+
+    ctx.bot_context
+        .http
+        .delete_emoji(ctx.get_guild()?.id, emoji.id)
+        .reason(audit_reason(&ctx))?
+        .await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("emoji_name", emoji.name.clone())
+        .generate();
+    ctx.reply(GearBotString::EmojiRemoved, args).await?;
+    Ok(())
+}
+
+pub async fn emoji_rename(mut ctx: CommandContext) -> CommandResult {
+    let emoji = match find_emoji(&mut ctx).await? {
+        Some(emoji) => emoji,
+        None => return Ok(()),
+    };
+    let name = ctx.parser.get_next()?.to_string();
+    if !guild_emoji::is_valid_emoji_name(&name) {
+        return reply_invalid_name(&ctx, name).await;
+    }
+
+    ctx.bot_context
+        .http
+        .update_emoji(ctx.get_guild()?.id, emoji.id)
+        .name(name.clone())
+        .reason(audit_reason(&ctx))?
+        .await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("before", emoji.name.clone())
+        .add("after", name)
+        .generate();
+    ctx.reply(GearBotString::EmojiRenamed, args).await?;
+    Ok(())
+}
+
+/// Downloads the image and turns it into an emoji, as long as it fits and there is a free slot for it.
+async fn upload_emoji(ctx: &CommandContext, name: String, url: &str) -> CommandResult {
+    if !guild_emoji::is_valid_emoji_name(&name) {
+        return reply_invalid_name(ctx, name).await;
+    }
+
+    let url = match Url::parse(url).ok().filter(public_fetch::is_allowed_url) {
+        Some(url) => url,
+        None => return reply_download_failed(ctx).await,
+    };
+    let request = ctx.bot_context.emoji_client.get(url.clone()).send();
+    let response = match request.await.and_then(|r| r.error_for_status()) {
+        Ok(response) => response,
+        Err(e) => {
+            log::debug!("Failed to download an emoji image from {}: {}", url, e);
+            return reply_download_failed(ctx).await;
+        }
+    };
+    // Stops as soon as it's too big, there's no point in downloading the rest
+    let data = match public_fetch::read_limited(response, MAX_EMOJI_SIZE).await {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::EmojiTooBig, args).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            log::debug!("Failed to download an emoji image from {}: {}", url, e);
+            return reply_download_failed(ctx).await;
+        }
+    };
+    let (image, animated) = match guild_emoji::emoji_data_uri(&data) {
+        Some(image) => image,
+        None => return reply_download_failed(ctx).await,
+    };
+
+    let guild = ctx.get_guild()?;
+    if guild_emoji::free_emoji_slots(&guild.emoji.read().await, guild.premium_tier, animated) == 0 {
+        let args = FluArgs::with_capacity(3)
+            .add("gearno", Emoji::No.for_chat())
+            .add("animated", if animated { "yes" } else { "no" })
+            .add("slots", guild_emoji::emoji_slots(guild.premium_tier))
+            .generate();
+        ctx.reply(GearBotString::EmojiNoSlots, args).await?;
+        return Ok(());
+    }
+
+    let emoji = ctx
+        .bot_context
+        .http
+        .create_emoji(guild.id, name, image)
+        .reason(audit_reason(ctx))?
+        .await?;
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add(
+            "new_emoji",
+            format!(
+                "<{}:{}:{}>",
+                if emoji.animated { "a" } else { "" },
+                emoji.name,
+                emoji.id
+            ),
+        )
+        .add("emoji_name", emoji.name)
+        .generate();
+    ctx.reply(GearBotString::EmojiAdded, args).await?;
+    Ok(())
+}
+
+/// Finds the emoji of this guild the next argument points to, by name or as the emoji itself. Replies why not
+/// when there is no such emoji or it can't be changed.
+async fn find_emoji(ctx: &mut CommandContext) -> Result<Option<Arc<CachedEmoji>>, CommandError> {
+    let input = ctx.parser.get_next()?.to_string();
+    let id = matchers::get_emoji_parts(&input).first().map(|emoji| EmojiId(emoji.id));
+    let found = ctx
+        .get_guild()?
+        .emoji
+        .read()
+        .await
+        .iter()
+        .find(|emoji| Some(emoji.id) == id || emoji.name == input)
+        .cloned();
+
+    match found {
+        Some(emoji) if emoji.managed => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("emoji_name", emoji.name.clone())
+                .generate();
+            ctx.reply(GearBotString::EmojiManaged, args).await?;
+            Ok(None)
+        }
+        Some(emoji) => Ok(Some(emoji)),
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("emoji", input.replace("`", "ˋ"))
+                .generate();
+            ctx.reply(GearBotString::EmojiNotFound, args).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Shows up in the audit log so it's clear who asked for the change.
+fn audit_reason(ctx: &CommandContext) -> String {
+    format!(
+        "Requested by {} ({})",
+        ctx.message.author.full_name(),
+        ctx.message.author.id
+    )
+}
+
+async fn reply_invalid_name(ctx: &CommandContext, name: String) -> CommandResult {
+    let args = FluArgs::with_capacity(2)
+        .add("gearno", Emoji::No.for_chat())
+        .add("emoji_name", name.replace("`", "ˋ"))
+        .generate();
+    ctx.reply(GearBotString::EmojiInvalidName, args).await?;
+    Ok(())
+}
+
+async fn reply_download_failed(ctx: &CommandContext) -> CommandResult {
+    let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+    ctx.reply(GearBotString::EmojiDownloadFailed, args).await?;
+    Ok(())
+}
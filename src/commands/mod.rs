@@ -1,175 +1,463 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-
 use lazy_static::lazy_static;
 use twilight_model::guild::Permissions;
 
-use crate::commands::meta::nodes::{CommandGroup, CommandNode, GearBotPermissions, RootNode};
-use crate::{
-    command, command_with_aliases, command_with_subcommands, command_with_subcommands_and_aliases,
-    command_with_subcommands_and_handler_and_aliases,
-};
+use crate::commands::meta::builder::{command, RootBuilder};
+use crate::commands::meta::nodes::{CommandGroup, GearBotPermissions, RootNode};
 
 mod admin;
 mod basic;
 mod debug;
+mod fun;
+mod guild_admin;
+mod levels;
 pub mod meta;
 mod misc;
 mod moderation;
 
 lazy_static! {
-    pub static ref ROOT_NODE: RootNode = {
-        let mut commandlist = vec![
-            command!(
-                "about",
-                basic::about,
-                Permissions::EMBED_LINKS,
-                GearBotPermissions::ABOUT_COMMAND,
-                CommandGroup::Basic
-            ),
-            command!(
-                "coinflip",
-                basic::coinflip,
-                Permissions::empty(),
-                GearBotPermissions::COINFLIP_COMMAND,
-                CommandGroup::Basic
-            ),
-            command!(
-                "ping",
-                basic::ping,
-                Permissions::empty(),
-                GearBotPermissions::PING_COMMAND,
-                CommandGroup::Basic
-            ),
-            command!(
-                "quote",
-                basic::quote,
-                Permissions::EMBED_LINKS,
-                GearBotPermissions::QUOTE_COMMAND,
-                CommandGroup::Basic
-            ),
-            command!(
-                "uid",
-                basic::uid,
-                Permissions::empty(),
-                GearBotPermissions::UID_COMMAND,
-                CommandGroup::Basic
-            ),
-            command_with_subcommands_and_aliases!(
-                "config",
-                vec![String::from("c")],
-                Permissions::empty(),
-                GearBotPermissions::CONFIG_COMMAND, // has it's own to not cascade to write by mistake
-                CommandGroup::GuildAdmin,
-                command_with_subcommands_and_handler_and_aliases!(
-                    "get",
-                    vec![String::from("g")],
-                    debug::get_config,
-                    Permissions::empty(),
-                    GearBotPermissions::READ_CONFIG,
-                    CommandGroup::GuildAdmin,
-                    command_with_aliases!(
-                        "pretty",
-                        vec![String::from("p")],
-                        debug::get_config_pretty,
-                        Permissions::empty(),
-                        GearBotPermissions::READ_CONFIG,
-                        CommandGroup::GuildAdmin
+    pub static ref ROOT_NODE: RootNode = RootBuilder::default()
+        .group(
+            CommandGroup::Basic,
+            vec![
+                command("about", GearBotPermissions::ABOUT_COMMAND)
+                    .handler(basic::about)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("birthday", GearBotPermissions::BIRTHDAY_COMMAND)
+                    .handler(basic::birthday)
+                    .subcommand(command("set", GearBotPermissions::BIRTHDAY_COMMAND).handler(basic::birthday_set))
+                    .subcommand(command("remove", GearBotPermissions::BIRTHDAY_COMMAND).handler(basic::birthday_remove)),
+                command("coinflip", GearBotPermissions::COINFLIP_COMMAND).handler(basic::coinflip),
+                command("event", GearBotPermissions::EVENT_COMMAND)
+                    .subcommand(
+                        command("create", GearBotPermissions::EVENT_COMMAND)
+                            .handler(basic::event_create)
+                            .bot_permissions(
+                                Permissions::EMBED_LINKS | Permissions::ADD_REACTIONS | Permissions::MANAGE_MESSAGES,
+                            ),
+                    )
+                    .subcommand(command("cancel", GearBotPermissions::EVENT_COMMAND).handler(basic::event_cancel)),
+                command("8ball", GearBotPermissions::EIGHTBALL_COMMAND)
+                    .aliases(&["eightball"])
+                    .handler(fun::eightball),
+                command("choose", GearBotPermissions::CHOOSE_COMMAND).handler(fun::choose),
+                command("roll", GearBotPermissions::ROLL_COMMAND).handler(fun::roll),
+                command("mydata", GearBotPermissions::MY_DATA_COMMAND)
+                    .subcommand(
+                        command("export", GearBotPermissions::MY_DATA_COMMAND)
+                            .handler(basic::mydata_export)
+                            .bot_permissions(Permissions::ATTACH_FILES)
+                            .dm_only(),
+                    )
+                    .subcommand(
+                        command("delete", GearBotPermissions::MY_DATA_COMMAND)
+                            .handler(basic::mydata_delete)
+                            .dm_only(),
+                    ),
+                command("ping", GearBotPermissions::PING_COMMAND).handler(basic::ping),
+                command("quote", GearBotPermissions::QUOTE_COMMAND)
+                    .handler(basic::quote)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("suggest", GearBotPermissions::SUGGEST_COMMAND)
+                    .handler(basic::suggest)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("ticket", GearBotPermissions::TICKET_COMMAND)
+                    .subcommand(command("open", GearBotPermissions::TICKET_COMMAND).handler(moderation::ticket_open))
+                    .subcommand(
+                        command("close", GearBotPermissions::TICKET_COMMAND)
+                            .handler(moderation::ticket_close)
+                            .bot_permissions(Permissions::MANAGE_CHANNELS | Permissions::READ_MESSAGE_HISTORY),
+                    )
+                    .subcommand(
+                        command("claim", GearBotPermissions::TICKET_MANAGE_COMMAND).handler(moderation::ticket_claim),
+                    )
+                    .subcommand(
+                        command("transcript", GearBotPermissions::TICKET_MANAGE_COMMAND)
+                            .handler(moderation::ticket_transcript)
+                            .bot_permissions(Permissions::READ_MESSAGE_HISTORY | Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("panel", GearBotPermissions::TICKET_MANAGE_COMMAND)
+                            .handler(moderation::ticket_panel)
+                            .bot_permissions(Permissions::ADD_REACTIONS),
+                    ),
+                command("uid", GearBotPermissions::UID_COMMAND).handler(basic::uid),
+                command("rank", GearBotPermissions::RANK_COMMAND)
+                    .handler(levels::rank)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("leaderboard", GearBotPermissions::LEADERBOARD_COMMAND)
+                    .aliases(&["lb"])
+                    .handler(levels::leaderboard)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+            ],
+        )
+        .group(
+            CommandGroup::Moderation,
+            vec![
+                command("archive", GearBotPermissions::ARCHIVE_COMMAND)
+                    .subcommand(
+                        command("channel", GearBotPermissions::ARCHIVE_COMMAND)
+                            .handler(moderation::archive_channel)
+                            .bot_permissions(Permissions::READ_MESSAGE_HISTORY | Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("get", GearBotPermissions::ARCHIVE_COMMAND)
+                            .handler(moderation::archive_get)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    ),
+                command("audit", GearBotPermissions::AUDIT_COMMAND).subcommand(
+                    command("integrations", GearBotPermissions::AUDIT_COMMAND)
+                        .handler(moderation::audit_integrations)
+                        .bot_permissions(
+                            Permissions::MANAGE_WEBHOOKS
+                                | Permissions::MANAGE_GUILD
+                                | Permissions::VIEW_AUDIT_LOG
+                                | Permissions::ATTACH_FILES,
+                        ),
+                ),
+                command("heat", GearBotPermissions::HEAT_COMMAND)
+                    .handler(moderation::heat)
+                    .subcommand(command("reset", GearBotPermissions::HEAT_COMMAND).handler(moderation::heat_reset)),
+                command("joingate", GearBotPermissions::JOIN_GATE_COMMAND).subcommand(
+                    command("verify", GearBotPermissions::JOIN_GATE_COMMAND)
+                        .handler(moderation::join_gate_verify)
+                        .bot_permissions(Permissions::MANAGE_ROLES),
+                ),
+                command("modstats", GearBotPermissions::MODSTATS_COMMAND)
+                    .handler(moderation::mod_stats)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("prune", GearBotPermissions::PRUNE_COMMAND)
+                    .subcommand(
+                        command("preview", GearBotPermissions::PRUNE_COMMAND)
+                            .handler(moderation::prune_preview)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("execute", GearBotPermissions::PRUNE_COMMAND)
+                            .handler(moderation::prune_execute)
+                            .destructive()
+                            .requires_confirmation()
+                            .bot_permissions(
+                                Permissions::KICK_MEMBERS | Permissions::MANAGE_ROLES | Permissions::ATTACH_FILES,
+                            ),
+                    ),
+                command("raidmode", GearBotPermissions::RAID_MODE_COMMAND)
+                    .handler(moderation::raid_mode)
+                    .subcommand(
+                        command("on", GearBotPermissions::RAID_MODE_COMMAND)
+                            .handler(moderation::raid_mode_on)
+                            .bot_permissions(
+                                Permissions::MANAGE_GUILD | Permissions::MANAGE_ROLES | Permissions::MANAGE_CHANNELS,
+                            ),
+                    )
+                    .subcommand(
+                        command("off", GearBotPermissions::RAID_MODE_COMMAND)
+                            .handler(moderation::raid_mode_off)
+                            .bot_permissions(
+                                Permissions::MANAGE_GUILD | Permissions::MANAGE_ROLES | Permissions::MANAGE_CHANNELS,
+                            ),
                     )
+                    .subcommand(command("auto", GearBotPermissions::RAID_MODE_COMMAND).handler(moderation::raid_mode_auto)),
+                command("role", GearBotPermissions::ROLE_COMMAND)
+                    .subcommand(
+                        command("addall", GearBotPermissions::ROLE_COMMAND)
+                            .handler(moderation::role_add_all)
+                            .destructive()
+                            .requires_confirmation()
+                            .bot_permissions(Permissions::MANAGE_ROLES),
+                    )
+                    .subcommand(
+                        command("removeall", GearBotPermissions::ROLE_COMMAND)
+                            .handler(moderation::role_remove_all)
+                            .destructive()
+                            .requires_confirmation()
+                            .bot_permissions(Permissions::MANAGE_ROLES),
+                    )
+                    .subcommand(command("cancel", GearBotPermissions::ROLE_COMMAND).handler(moderation::role_cancel))
+                    .subcommand(
+                        command("audit", GearBotPermissions::ROLE_COMMAND)
+                            .handler(moderation::role_audit)
+                            .subcommand(
+                                command("fix", GearBotPermissions::ROLE_COMMAND)
+                                    .handler(moderation::role_audit_fix)
+                                    .destructive()
+                                    .requires_confirmation()
+                                    .bot_permissions(Permissions::MANAGE_ROLES),
+                            ),
+                    ),
+                command("search", GearBotPermissions::SEARCH_COMMAND)
+                    .handler(moderation::search)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("snipe", GearBotPermissions::SNIPE_COMMAND)
+                    .handler(moderation::snipe)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("editsnipe", GearBotPermissions::EDIT_SNIPE_COMMAND)
+                    .handler(moderation::edit_snipe)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("suggestion", GearBotPermissions::SUGGESTION_COMMAND)
+                    .subcommand(
+                        command("approve", GearBotPermissions::SUGGESTION_COMMAND).handler(basic::suggestion_approve),
+                    )
+                    .subcommand(command("deny", GearBotPermissions::SUGGESTION_COMMAND).handler(basic::suggestion_deny))
+                    .subcommand(
+                        command("consider", GearBotPermissions::SUGGESTION_COMMAND).handler(basic::suggestion_consider),
+                    ),
+                command("undo", GearBotPermissions::UNDO_COMMAND)
+                    .handler(moderation::undo)
+                    .bot_permissions(Permissions::MANAGE_ROLES),
+                command("unwatch", GearBotPermissions::WATCH_COMMAND).handler(moderation::unwatch),
+                command("userinfo", GearBotPermissions::USERINFO_COMMAND)
+                    .handler(moderation::userinfo)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("watch", GearBotPermissions::WATCH_COMMAND).handler(moderation::watch),
+            ],
+        )
+        .group(
+            CommandGroup::GuildAdmin,
+            vec![
+                // has it's own permission to not cascade to write by mistake
+                command("config", GearBotPermissions::CONFIG_COMMAND)
+                    .aliases(&["c"])
+                    .subcommand(
+                        command("get", GearBotPermissions::READ_CONFIG)
+                            .aliases(&["g"])
+                            .handler(debug::get_config)
+                            .subcommand(
+                                command("pretty", GearBotPermissions::READ_CONFIG)
+                                    .aliases(&["p"])
+                                    .handler(debug::get_config_pretty),
+                            ),
+                    )
+                    .subcommand(command("set", GearBotPermissions::WRITE_CONFIG).handler(debug::set_config))
+                    .subcommand(command("reset", GearBotPermissions::WRITE_CONFIG).handler(debug::reset_config))
+                    .subcommand(
+                        command("export", GearBotPermissions::READ_CONFIG)
+                            .handler(guild_admin::config_export)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(command("import", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::config_import))
+                    .subcommand(
+                        command("validate", GearBotPermissions::READ_CONFIG).handler(guild_admin::config_validate),
+                    )
+                    .subcommand(
+                        command("timezone", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::config_timezone),
+                    ),
+                // like config, so reading doesn't cascade into writing
+                command("feature", GearBotPermissions::FEATURE_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::feature_list))
+                    .subcommand(
+                        command("enable", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feature_enable),
+                    )
+                    .subcommand(
+                        command("disable", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feature_disable),
+                    ),
+                command("stats", GearBotPermissions::STATS_COMMAND)
+                    .subcommand(command("guild", GearBotPermissions::STATS_COMMAND).handler(guild_admin::stats_guild)),
+                command("activity", GearBotPermissions::ACTIVITY_COMMAND).subcommand(
+                    command("report", GearBotPermissions::ACTIVITY_COMMAND)
+                        .handler(guild_admin::activity_report)
+                        .bot_permissions(Permissions::EMBED_LINKS | Permissions::ATTACH_FILES),
                 ),
-                command!(
-                    "set",
-                    debug::set_config,
-                    Permissions::empty(),
-                    GearBotPermissions::WRITE_CONFIG,
-                    CommandGroup::GuildAdmin
+                command("usage", GearBotPermissions::USAGE_COMMAND).handler(guild_admin::usage),
+                command("logstyle", GearBotPermissions::LOG_STYLE_COMMAND).subcommand(
+                    command("preview", GearBotPermissions::READ_CONFIG)
+                        .handler(guild_admin::log_style_preview)
+                        .bot_permissions(Permissions::EMBED_LINKS),
                 ),
-                command!(
-                "reset",
-                debug::reset_config,
-                Permissions::empty(),
-                GearBotPermissions::WRITE_CONFIG,
-                CommandGroup::GuildAdmin
+                command("alias", GearBotPermissions::ALIAS_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::alias_list))
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::alias_add))
+                    .subcommand(command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::alias_remove)),
+                command("autopublish", GearBotPermissions::AUTOPUBLISH_COMMAND)
+                    .subcommand(
+                        command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::auto_publish_list),
+                    )
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::auto_publish_add))
+                    .subcommand(
+                        command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::auto_publish_remove),
+                    )
+                    .subcommand(
+                        command("authors", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::auto_publish_authors),
+                    )
+                    .subcommand(
+                        command("roles", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::auto_publish_roles),
+                    )
+                    .subcommand(
+                        command("bots", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::auto_publish_bots),
+                    ),
+                command("automod", GearBotPermissions::AUTOMOD_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::automod_list))
+                    .subcommand(command("show", GearBotPermissions::READ_CONFIG).handler(guild_admin::automod_show))
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::automod_add))
+                    .subcommand(command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::automod_remove)),
+                command("autoresponse", GearBotPermissions::AUTORESPONSE_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::autoresponse_list))
+                    .subcommand(command("show", GearBotPermissions::READ_CONFIG).handler(guild_admin::autoresponse_show))
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::autoresponse_add))
+                    .subcommand(
+                        command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::autoresponse_remove),
+                    ),
+                command("feed", GearBotPermissions::FEED_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::feed_list))
+                    .subcommand(
+                        command("preview", GearBotPermissions::READ_CONFIG)
+                            .handler(guild_admin::feed_preview)
+                            .bot_permissions(Permissions::EMBED_LINKS),
+                    )
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feed_add))
+                    .subcommand(command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feed_remove))
+                    .subcommand(
+                        command("template", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feed_template),
+                    )
+                    .subcommand(
+                        command("interval", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::feed_interval),
+                    ),
+                command("github", GearBotPermissions::GITHUB_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::github_list))
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::github_add))
+                    .subcommand(command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::github_remove))
+                    .subcommand(command("events", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::github_events))
+                    .subcommand(command("secret", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::github_secret)),
+                command("webhook", GearBotPermissions::WEBHOOK_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::webhook_list))
+                    .subcommand(command("add", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::webhook_add))
+                    .subcommand(
+                        command("remove", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::webhook_remove),
+                    )
+                    .subcommand(
+                        command("template", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::webhook_template),
+                    )
+                    .subcommand(command("token", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::webhook_token)),
+                command("profile", GearBotPermissions::PROFILE_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::profile_list))
+                    .subcommand(command("save", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::profile_save))
+                    .subcommand(command("delete", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::profile_delete))
+                    .subcommand(command("apply", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::profile_apply))
+                    .subcommand(command("revert", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::profile_revert)),
+                command("botban", GearBotPermissions::BOTBAN_COMMAND).handler(guild_admin::botban),
+                command("botunban", GearBotPermissions::BOTBAN_COMMAND).handler(guild_admin::botunban),
+                command("commands", GearBotPermissions::COMMANDS_COMMAND)
+                    .subcommand(command("list", GearBotPermissions::READ_CONFIG).handler(guild_admin::commands_list))
+                    .subcommand(
+                        command("disable", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::commands_disable),
+                    )
+                    .subcommand(
+                        command("enable", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::commands_enable),
+                    )
+                    .subcommand(
+                        command("output", GearBotPermissions::WRITE_CONFIG).handler(guild_admin::commands_output),
+                    ),
+                command("channel", GearBotPermissions::CHANNEL_COMMAND)
+                    .subcommand(
+                        command("clone", GearBotPermissions::CHANNEL_COMMAND)
+                            .handler(guild_admin::channel_clone)
+                            .bot_permissions(Permissions::MANAGE_CHANNELS),
+                    )
+                    .subcommand(
+                        command("template", GearBotPermissions::CHANNEL_COMMAND)
+                            .subcommand(
+                                command("list", GearBotPermissions::CHANNEL_COMMAND)
+                                    .handler(guild_admin::channel_template_list),
+                            )
+                            .subcommand(
+                                command("save", GearBotPermissions::CHANNEL_COMMAND)
+                                    .handler(guild_admin::channel_template_save),
+                            )
+                            .subcommand(
+                                command("apply", GearBotPermissions::CHANNEL_COMMAND)
+                                    .handler(guild_admin::channel_template_apply)
+                                    .bot_permissions(Permissions::MANAGE_CHANNELS),
+                            )
+                            .subcommand(
+                                command("remove", GearBotPermissions::CHANNEL_COMMAND)
+                                    .aliases(&["delete"])
+                                    .handler(guild_admin::channel_template_remove),
+                            ),
+                    ),
+            ],
+        )
+        .group(
+            CommandGroup::Misc,
+            vec![command("emoji", GearBotPermissions::EMOJI_COMMAND)
+                .subcommand(
+                    command("list", GearBotPermissions::EMOJI_LIST_COMMAND)
+                        .handler(misc::emoji_list)
+                        .bot_permissions(Permissions::EMBED_LINKS),
                 )
-            ),
-            command!(
-                "userinfo",
-                moderation::userinfo,
-                Permissions::EMBED_LINKS,
-                GearBotPermissions::USERINFO_COMMAND,
-                CommandGroup::Moderation
-            ),
-            command_with_subcommands!(
-                "check",
-                GearBotPermissions::BOT_ADMIN,
-                CommandGroup::BotAdmin,
-                command!(
-                    "cache",
-                    admin::check_cache,
-                    Permissions::EMBED_LINKS,
-                    GearBotPermissions::BOT_ADMIN,
-                    CommandGroup::BotAdmin
+                .subcommand(
+                    command("add", GearBotPermissions::EMOJI_MANAGE_COMMAND)
+                        .handler(misc::emoji_add)
+                        .bot_permissions(Permissions::MANAGE_EMOJIS),
                 )
-            ),
-            command!(
-                "redis_test",
-                admin::restart,
-                Permissions::empty(),
-                GearBotPermissions::BOT_ADMIN,
-                CommandGroup::BotAdmin
-            ),
-            command!(
-            "perms",
-            debug::get_perms,
-            Permissions::empty(),
-            GearBotPermissions::BOT_ADMIN,
-            CommandGroup::BotAdmin
-            ),
-            command!("test", debug::test, Permissions::empty(), GearBotPermissions::BOT_ADMIN, CommandGroup::BotAdmin),
-            command_with_subcommands!("emoji", GearBotPermissions::EMOJI_COMMAND, CommandGroup::Misc, command!("list", misc::emoji_list, Permissions::EMBED_LINKS, GearBotPermissions::EMOJI_LIST_COMMAND, CommandGroup::Misc))
-        ];
-
-        let mut all_commands = HashMap::new();
-        let mut command_list = vec![];
-        let mut by_group = HashMap::new();
-
-        commandlist.sort_by(|a, b| a.name.cmp(&b.name));
-
-        for command in commandlist {
-            command_list.push(command.clone());
-            if all_commands.contains_key(&*command.name) {
-                panic!(format!(
-                    "Tried to register command name {} but another command was already registered with that name!",
-                    command.name
-                ))
-            }
-            all_commands.insert(command.name.clone(), command.clone());
-
-            for a in &command.aliases {
-                if all_commands.contains_key(a) {
-                    panic!(format!(
-                        "Tried to register command alias {} but another command was already registered with that name!",
-                        a
-                    ))
-                }
-                all_commands.insert(a.clone(), command.clone());
-            }
-
-            let mut list = match by_group.remove(&command.group) {
-                Some(list) => list,
-                None => vec![],
-            };
-            list.push(command.clone());
-            by_group.insert(command.group.clone(), list);
-        }
-
-        log::info!("Loaded {} commands in {} groups", command_list.len(), by_group.len());
-
-        RootNode {
-            all_commands,
-            command_list,
-            by_group,
-            groups: vec![CommandGroup::Basic, CommandGroup::Moderation, CommandGroup::GuildAdmin]
-        }
-    };
+                .subcommand(
+                    command("remove", GearBotPermissions::EMOJI_MANAGE_COMMAND)
+                        .aliases(&["delete"])
+                        .handler(misc::emoji_remove)
+                        .bot_permissions(Permissions::MANAGE_EMOJIS),
+                )
+                .subcommand(
+                    command("rename", GearBotPermissions::EMOJI_MANAGE_COMMAND)
+                        .handler(misc::emoji_rename)
+                        .bot_permissions(Permissions::MANAGE_EMOJIS),
+                )
+                .subcommand(
+                    command("steal", GearBotPermissions::EMOJI_MANAGE_COMMAND)
+                        .handler(misc::emoji_steal)
+                        .bot_permissions(Permissions::MANAGE_EMOJIS),
+                )],
+        )
+        .group(
+            CommandGroup::BotAdmin,
+            vec![
+                command("backup", GearBotPermissions::BOT_ADMIN)
+                    .subcommand(command("now", GearBotPermissions::BOT_ADMIN).handler(admin::backup_now))
+                    .subcommand(command("list", GearBotPermissions::BOT_ADMIN).handler(admin::backup_list))
+                    .subcommand(command("restore", GearBotPermissions::BOT_ADMIN).handler(admin::backup_restore)),
+                command("blocklist", GearBotPermissions::BOT_ADMIN)
+                    .subcommand(command("add", GearBotPermissions::BOT_ADMIN).handler(admin::blocklist_add))
+                    .subcommand(command("remove", GearBotPermissions::BOT_ADMIN).handler(admin::blocklist_remove)),
+                command("check", GearBotPermissions::BOT_ADMIN).subcommand(
+                    command("cache", GearBotPermissions::BOT_ADMIN)
+                        .handler(admin::check_cache)
+                        .bot_permissions(Permissions::EMBED_LINKS),
+                ),
+                command("inspect", GearBotPermissions::BOT_ADMIN)
+                    .subcommand(
+                        command("guild", GearBotPermissions::BOT_ADMIN)
+                            .handler(admin::inspect_guild)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("member", GearBotPermissions::BOT_ADMIN)
+                            .handler(admin::inspect_member)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("config", GearBotPermissions::BOT_ADMIN)
+                            .handler(admin::inspect_config)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("permissions", GearBotPermissions::BOT_ADMIN)
+                            .handler(admin::inspect_permissions)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    )
+                    .subcommand(
+                        command("query", GearBotPermissions::BOT_ADMIN)
+                            .handler(admin::inspect_query)
+                            .bot_permissions(Permissions::ATTACH_FILES),
+                    ),
+                command("langstats", GearBotPermissions::BOT_ADMIN).handler(admin::langstats),
+                command("tier", GearBotPermissions::BOT_ADMIN)
+                    .subcommand(command("get", GearBotPermissions::BOT_ADMIN).handler(admin::tier_get))
+                    .subcommand(command("grant", GearBotPermissions::BOT_ADMIN).handler(admin::tier_grant))
+                    .subcommand(command("revoke", GearBotPermissions::BOT_ADMIN).handler(admin::tier_revoke)),
+                command("whereis", GearBotPermissions::BOT_ADMIN)
+                    .handler(admin::whereis)
+                    .bot_permissions(Permissions::EMBED_LINKS),
+                command("redis_test", GearBotPermissions::BOT_ADMIN).handler(admin::restart),
+                command("perms", GearBotPermissions::BOT_ADMIN).handler(debug::get_perms),
+                command("test", GearBotPermissions::BOT_ADMIN).handler(debug::test),
+            ],
+        )
+        .build();
 }
@@ -0,0 +1,231 @@
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, MessageId};
+
+use crate::core::CommandContext;
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::transcript::{self, ExportedMessage};
+use crate::utils::Emoji;
+
+/// The most messages discord hands out per request.
+const PAGE_LIMIT: u64 = 100;
+/// Upper bound on messages in a single export, to not spend minutes paging through a channel.
+pub(super) const MAX_MESSAGES: usize = 1000;
+
+pub(super) enum Selection {
+    /// This many of the most recent messages.
+    Latest(usize),
+    /// Everything from the first message up to and including the last one.
+    Range(MessageId, MessageId),
+}
+
+enum Format {
+    Html,
+    Json,
+}
+
+pub async fn archive_channel(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let selection = parse_selection(ctx.parser.get_next()?)?;
+
+    let mut format = Format::Html;
+    let mut store = false;
+    while ctx.parser.has_next() {
+        match ctx.parser.get_next()?.to_lowercase().as_str() {
+            "html" => format = Format::Html,
+            "json" => format = Format::Json,
+            "store" => store = true,
+            _ => return Err(ParseError::WrongArgumentType(String::from("html, json or store")).into()),
+        }
+    }
+
+    let channel = ctx.message.channel.clone();
+    let channel_id = channel.get_id();
+    // Leave the command itself out, it doesn't belong in the export
+    let messages = fetch_messages(&ctx, channel_id, &selection, ctx.message.id).await?;
+    if messages.is_empty() {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::ArchiveNothing, args).await?;
+        return Ok(());
+    }
+
+    let exported = export_messages(&ctx, &messages).await;
+
+    let channel_name = channel.get_name();
+    let (content, extension) = match format {
+        Format::Html => (transcript::render_html(channel_name, channel_id, &exported), "html"),
+        Format::Json => (transcript::render_json(channel_name, channel_id, &exported), "json"),
+    };
+
+    if store {
+        // The command message is never part of the export, so it can't clash with a bulk delete archive
+        let archive_id = ctx.message.id.0;
+        ctx.bot_context
+            .datastore
+            .insert_archive(archive_id, guild_id, channel_id, &content)
+            .await?;
+        let args = FluArgs::with_capacity(4)
+            .add("emoji", Emoji::Yes.for_chat())
+            .add("count", exported.len())
+            .add("channel", channel_id.to_string())
+            .add("id", archive_id.to_string())
+            .generate();
+        ctx.reply(GearBotString::ArchiveStored, args).await?;
+    } else {
+        let args = FluArgs::with_capacity(3)
+            .add("emoji", Emoji::Yes.for_chat())
+            .add("count", exported.len())
+            .add("channel", channel_id.to_string())
+            .generate();
+        ctx.reply_with_file(
+            GearBotString::ArchiveCreated,
+            args,
+            format!("archive_{}.{}", channel_id, extension),
+            content.into_bytes(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn archive_get(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let archive_id = ctx
+        .parser
+        .get_next()?
+        .parse::<u64>()
+        .map_err(|_| ParseError::WrongArgumentType(String::from("archive id")))?;
+
+    let content = match ctx.bot_context.datastore.get_archive(archive_id, guild_id).await? {
+        Some(content) => content,
+        None => {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("id", archive_id.to_string())
+                .generate();
+            ctx.reply(GearBotString::ArchiveUnknown, args).await?;
+            return Ok(());
+        }
+    };
+
+    // Bulk delete transcripts are stored as plain text next to the exports
+    let extension = if content.starts_with("<!DOCTYPE html>") {
+        "html"
+    } else if content.starts_with('{') {
+        "json"
+    } else {
+        "txt"
+    };
+    let args = FluArgs::with_capacity(1).add("id", archive_id.to_string()).generate();
+    ctx.reply_with_file(
+        GearBotString::ArchiveRetrieved,
+        args,
+        format!("archive_{}.{}", archive_id, extension),
+        content.into_bytes(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn parse_selection(input: &str) -> Result<Selection, ParseError> {
+    let invalid = || ParseError::WrongArgumentType(String::from("message count or range of message ids"));
+    match input.split_once('-') {
+        Some((first, last)) => {
+            let first = first.parse::<u64>().map_err(|_| invalid())?;
+            let last = last.parse::<u64>().map_err(|_| invalid())?;
+            Ok(Selection::Range(MessageId(first.min(last)), MessageId(first.max(last))))
+        }
+        None => {
+            let count = input.parse::<usize>().map_err(|_| invalid())?;
+            if count == 0 {
+                return Err(invalid());
+            }
+            Ok(Selection::Latest(count.min(MAX_MESSAGES)))
+        }
+    }
+}
+
+/// Pages through the channel history, returning the selected messages from oldest to newest.
+pub(super) async fn fetch_messages(
+    ctx: &CommandContext,
+    channel_id: ChannelId,
+    selection: &Selection,
+    command_id: MessageId,
+) -> Result<Vec<Message>, CommandError> {
+    let http = &ctx.bot_context.http;
+    let mut messages: Vec<Message> = vec![];
+
+    match selection {
+        Selection::Latest(count) => {
+            let mut cursor = command_id;
+            while messages.len() < *count {
+                // A limit of 100 is always accepted
+                let page = http
+                    .channel_messages(channel_id)
+                    .before(cursor)
+                    .limit(PAGE_LIMIT)
+                    .unwrap()
+                    .await?;
+                let done = (page.len() as u64) < PAGE_LIMIT;
+                match page.iter().map(|message| message.id).min() {
+                    Some(oldest) => cursor = oldest,
+                    None => break,
+                }
+                messages.extend(page);
+                if done {
+                    break;
+                }
+            }
+            messages.sort_by_key(|message| message.id);
+            let excess = messages.len().saturating_sub(*count);
+            messages.drain(..excess);
+        }
+        Selection::Range(first, last) => {
+            // Start right before the first one so it's included
+            let mut cursor = MessageId(first.0.saturating_sub(1));
+            while messages.len() < MAX_MESSAGES {
+                let page = http
+                    .channel_messages(channel_id)
+                    .after(cursor)
+                    .limit(PAGE_LIMIT)
+                    .unwrap()
+                    .await?;
+                let done = (page.len() as u64) < PAGE_LIMIT;
+                match page.iter().map(|message| message.id).max() {
+                    Some(newest) => cursor = newest,
+                    None => break,
+                }
+                messages.extend(
+                    page.into_iter()
+                        .filter(|message| message.id <= *last && message.id != command_id),
+                );
+                if done || cursor >= *last {
+                    break;
+                }
+            }
+            messages.sort_by_key(|message| message.id);
+            messages.truncate(MAX_MESSAGES);
+        }
+    }
+
+    Ok(messages)
+}
+
+pub(super) async fn export_messages(ctx: &CommandContext, messages: &[Message]) -> Vec<ExportedMessage> {
+    let mut exported = Vec::with_capacity(messages.len());
+    for message in messages {
+        let author = ctx.bot_context.cache.get_or_insert_user(&message.author).await;
+        exported.push(ExportedMessage {
+            id: message.id,
+            author_id: message.author.id,
+            author: author.full_name(),
+            content: message.content.clone(),
+            timestamp: message.timestamp.clone(),
+            attachments: message.attachments.iter().map(|a| a.url.clone()).collect(),
+            embeds: message.embeds.len(),
+        });
+    }
+    exported
+}
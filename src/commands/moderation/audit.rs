@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use twilight_model::id::UserId;
+
+use crate::core::{AuditedEntry, AuditedWebhook, CommandContext};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Lists everything that can act in the server without being a regular member, flagging webhooks in channels
+/// they're not expected in.
+pub async fn audit_integrations(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let config = ctx.get_config()?;
+    let audit = ctx.bot_context.audit_integrations(guild_id, &config).await?;
+    if audit.webhooks.is_empty() && audit.bots.is_empty() && audit.integrations.is_empty() {
+        let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+        ctx.reply(GearBotString::AuditNothing, args).await?;
+        return Ok(());
+    }
+
+    let flagged = audit.webhooks.iter().filter(|webhook| webhook.unexpected).count();
+    let args = FluArgs::with_capacity(2)
+        .add("gearwarn", Emoji::Warn.for_chat())
+        .add("flagged", flagged)
+        .generate();
+    let mut content = ctx.translate_with_args(GearBotString::AuditIntegrationsHeader, &args);
+
+    // The flagged ones first, those are what this is about
+    let mut webhooks = audit.webhooks;
+    webhooks.sort_by_key(|webhook| !webhook.unexpected);
+    for webhook in &webhooks {
+        content += "\n";
+        content += &webhook_line(&ctx, webhook);
+    }
+    for bot in &audit.bots {
+        content += "\n";
+        content += &entry_line(&ctx, GearBotString::AuditBot, bot);
+    }
+    for integration in &audit.integrations {
+        content += "\n";
+        content += &entry_line(&ctx, GearBotString::AuditIntegration, integration);
+    }
+
+    ctx.reply_long(content).await?;
+    Ok(())
+}
+
+fn webhook_line(ctx: &CommandContext, webhook: &AuditedWebhook) -> String {
+    let flag = if webhook.unexpected {
+        format!("{} ", Emoji::Warn.for_chat())
+    } else {
+        String::new()
+    };
+    let args = FluArgs::with_capacity(6)
+        .add("flag", flag)
+        .add("name", webhook.entry.name.clone())
+        .add("id", webhook.entry.id.to_string())
+        .add("channel_id", webhook.channel_id.to_string())
+        .add("created", created(ctx, webhook.entry.created_at))
+        .add("creator", creator(ctx, webhook.entry.created_by))
+        .generate();
+    ctx.translate_with_args(GearBotString::AuditWebhook, &args)
+}
+
+fn entry_line(ctx: &CommandContext, key: GearBotString, entry: &AuditedEntry) -> String {
+    let args = FluArgs::with_capacity(5)
+        .add("name", entry.name.clone())
+        .add("id", entry.id.to_string())
+        .add("kind", entry.kind.clone())
+        .add("created", created(ctx, entry.created_at))
+        .add("creator", creator(ctx, entry.created_by))
+        .generate();
+    ctx.translate_with_args(key, &args)
+}
+
+fn created(ctx: &CommandContext, created_at: Option<DateTime<Utc>>) -> String {
+    match created_at {
+        Some(created_at) => format!("<t:{}:R>", created_at.timestamp()),
+        None => ctx.translate(GearBotString::AuditUnknown),
+    }
+}
+
+fn creator(ctx: &CommandContext, created_by: Option<UserId>) -> String {
+    match created_by {
+        Some(user_id) => format!("<@{}>", user_id),
+        None => ctx.translate(GearBotString::AuditUnknown),
+    }
+}
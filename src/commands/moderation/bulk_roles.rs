@@ -0,0 +1,261 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use twilight_model::id::{GuildId, RoleId, UserId};
+
+use crate::core::bulk_roles::{bulk_role_targets, BulkRoleMode, MemberFilter, BULK_ROLE_CHUNK_SIZE};
+use crate::core::{BulkRoleJob, CommandContext, ModAction, RoleMutation};
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, matchers, Emoji};
+
+/// Gives the role to every member matching the filters.
+pub async fn role_add_all(ctx: CommandContext) -> CommandResult {
+    bulk_role(ctx, BulkRoleMode::Add).await
+}
+
+/// Takes the role from every member matching the filters.
+pub async fn role_remove_all(ctx: CommandContext) -> CommandResult {
+    bulk_role(ctx, BulkRoleMode::Remove).await
+}
+
+/// Stops the bulk role change that is running on the server, what was already changed stays that way.
+pub async fn role_cancel(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let job = match ctx.bot_context.cancel_bulk_role_job(guild_id).await {
+        Some(job) => job,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::BulkRoleNotRunning, args).await?;
+            return Ok(());
+        }
+    };
+
+    let moderator = match ctx.bot_context.cache.get_user(job.moderator).await {
+        Some(user) => user.full_name(),
+        None => job.moderator.to_string(),
+    };
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("moderator", moderator)
+        .generate();
+    ctx.reply(GearBotString::BulkRoleCancelRequested, args).await?;
+    Ok(())
+}
+
+async fn bulk_role(mut ctx: CommandContext, mode: BulkRoleMode) -> CommandResult {
+    let guild = ctx.get_guild()?.clone();
+    let role_input = ctx.parser.get_next()?.to_string();
+    let role = matchers::get_snowflake(&role_input)
+        .map(RoleId)
+        .ok_or_else(|| ParseError::WrongArgumentType(String::from("role")))?;
+    let mutation = match mode {
+        BulkRoleMode::Add => RoleMutation::Add,
+        BulkRoleMode::Remove => RoleMutation::Remove,
+    };
+    ctx.bot_context
+        .check_role_mutation(guild.id, role, mutation, Some(ctx.message.author.id))
+        .await?;
+
+    let mut filter = MemberFilter::default();
+    while ctx.parser.has_next() {
+        let argument = ctx.parser.get_next()?.to_string();
+        let parsed = match argument.to_lowercase().as_str() {
+            "bots" => {
+                filter.bots = Some(true);
+                true
+            }
+            "humans" => {
+                filter.bots = Some(false);
+                true
+            }
+            _ => match argument.split_once(':') {
+                Some((name, value)) if name.eq_ignore_ascii_case("has") => {
+                    match matchers::get_snowflake(value).map(RoleId) {
+                        Some(has) if guild.get_role(&has).await.is_some() => {
+                            filter.has_roles.push(has);
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                Some((name, value)) if name.eq_ignore_ascii_case("before") => {
+                    // Either a date or how long ago
+                    let before = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                        Ok(date) => Some(DateTime::from_utc(date.and_hms(0, 0, 0), Utc)),
+                        Err(_) => utils::parse_duration(value).map(|age| Utc::now() - age),
+                    };
+                    filter.joined_before = before;
+                    before.is_some()
+                }
+                _ => false,
+            },
+        };
+
+        if !parsed {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("filter", argument)
+                .generate();
+            ctx.reply(GearBotString::BulkRoleInvalidFilter, args).await?;
+            return Ok(());
+        }
+    }
+
+    let role_name = match guild.get_role(&role).await {
+        Some(role) => role.name.clone(),
+        None => role.to_string(),
+    };
+    let members = guild.members.read().await.values().cloned().collect::<Vec<_>>();
+    let mut candidates = Vec::with_capacity(members.len());
+    for member in &members {
+        let bot = ctx
+            .bot_context
+            .cache
+            .get_user(member.user_id)
+            .await
+            .map_or(false, |user| user.bot_user);
+        candidates.push((member.as_ref(), bot));
+    }
+    let targets = bulk_role_targets(candidates, &filter, role, mode);
+
+    if targets.is_empty() {
+        let args = FluArgs::with_capacity(3)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .add("mode", mode_name(mode))
+            .add("role", role_name)
+            .generate();
+        ctx.reply(GearBotString::BulkRoleNothing, args).await?;
+        return Ok(());
+    }
+    if ctx.is_dry_run() {
+        let args = FluArgs::with_capacity(3)
+            .add("mode", mode_name(mode))
+            .add("role", role_name)
+            .add("count", targets.len())
+            .generate();
+        ctx.reply_dry_run(GearBotString::BulkRoleDryRun, args).await?;
+        return Ok(());
+    }
+
+    let job = match ctx
+        .bot_context
+        .start_bulk_role_job(guild.id, ctx.message.author.id, role, mode)
+        .await
+    {
+        Some(job) => job,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::BulkRoleAlreadyRunning, args).await?;
+            return Ok(());
+        }
+    };
+
+    // The job has to be cleared no matter how it ends, or the server can't start another one
+    let result = run_bulk_role(&ctx, guild.id, &job, &targets, &role_name).await;
+    ctx.bot_context.finish_bulk_role_job(guild.id).await;
+    result
+}
+
+/// Works through the members a chunk at a time, updating the progress after every chunk.
+async fn run_bulk_role(
+    ctx: &CommandContext,
+    guild_id: GuildId,
+    job: &BulkRoleJob,
+    targets: &[UserId],
+    role_name: &str,
+) -> CommandResult {
+    let mode = mode_name(job.mode);
+    let progress_text = |done: usize, failed: usize| {
+        let args = FluArgs::with_capacity(6)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .add("mode", mode)
+            .add("role", role_name.to_string())
+            .add("total", targets.len())
+            .add("done", done)
+            .add("failed", failed)
+            .generate();
+        ctx.translate_with_args(GearBotString::BulkRoleProgress, &args)
+    };
+    let progress = ctx.reply_raw(progress_text(0, 0)).await?;
+
+    let mut changed = Vec::with_capacity(targets.len());
+    let mut failed = 0;
+    for chunk in targets.chunks(BULK_ROLE_CHUNK_SIZE) {
+        if job.is_cancelled() {
+            break;
+        }
+
+        for user_id in chunk {
+            // Members that left in the meantime can't be changed anymore
+            if ctx.bot_context.cache.get_member(&guild_id, user_id).await.is_none() {
+                failed += 1;
+                continue;
+            }
+
+            let http = &ctx.bot_context.http;
+            let result = match job.mode {
+                BulkRoleMode::Add => http
+                    .add_guild_member_role(guild_id, *user_id, job.role)
+                    .await
+                    .map(|_| ()),
+                BulkRoleMode::Remove => http
+                    .remove_guild_member_role(guild_id, *user_id, job.role)
+                    .await
+                    .map(|_| ()),
+            };
+            match result {
+                Ok(()) => changed.push(*user_id),
+                Err(e) => {
+                    ctx.bot_context.track_http_error(&e).await;
+                    log::debug!("Failed to change the roles of {} in guild {}: {}", user_id, guild_id, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        // Progress is only informative, failing to show it shouldn't stop the work
+        let _ = ctx
+            .update_message(progress_text(changed.len(), failed), progress.channel_id, progress.id)
+            .await;
+    }
+
+    // Also when cancelled, what was changed up to then can still be undone
+    let done = changed.len();
+    if done > 0 {
+        let action = match job.mode {
+            BulkRoleMode::Add => ModAction::RolesAdded {
+                users: changed,
+                role: job.role,
+            },
+            BulkRoleMode::Remove => ModAction::RolesRemoved {
+                users: changed,
+                role: job.role,
+            },
+        };
+        ctx.journal(action).await?;
+    }
+
+    let (key, emoji) = if job.is_cancelled() && done + failed < targets.len() {
+        (GearBotString::BulkRoleCancelled, Emoji::Warn)
+    } else {
+        (GearBotString::BulkRoleFinished, Emoji::Yes)
+    };
+    let args = FluArgs::with_capacity(7)
+        .add("emoji", emoji.for_chat())
+        .add("gearwarn", Emoji::Warn.for_chat())
+        .add("mode", mode)
+        .add("role", role_name.to_string())
+        .add("total", targets.len())
+        .add("done", done)
+        .add("failed", failed)
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+/// How the mode is called in the translations.
+fn mode_name(mode: BulkRoleMode) -> &'static str {
+    match mode {
+        BulkRoleMode::Add => "add",
+        BulkRoleMode::Remove => "remove",
+    }
+}
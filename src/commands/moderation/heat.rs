@@ -0,0 +1,41 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn heat(mut ctx: CommandContext) -> CommandResult {
+    let config = ctx.get_config()?;
+    if !config.heat.enabled {
+        return heat_disabled(&ctx).await;
+    }
+
+    let user = ctx.parser.get_user_or(ctx.message.author.clone()).await?;
+    let guild_id = ctx.get_guild()?.id;
+    let heat = ctx.bot_context.get_heat(guild_id, user.id, &config.heat).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("user", user.id.to_string())
+        .add("heat", format!("{:.1}", heat))
+        .generate();
+    ctx.reply(GearBotString::HeatCurrent, args).await?;
+    Ok(())
+}
+
+pub async fn heat_reset(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+    let guild_id = ctx.get_guild()?.id;
+    ctx.bot_context.reset_heat(guild_id, user.id).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("user", user.id.to_string())
+        .generate();
+    ctx.reply(GearBotString::HeatReset, args).await?;
+    Ok(())
+}
+
+async fn heat_disabled(ctx: &CommandContext) -> CommandResult {
+    let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+    ctx.reply(GearBotString::HeatDisabled, args).await?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::core::{CommandContext, ModAction};
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Lets a member through the join gate, taking away the probation and verification roles and dropping any challenge
+/// they still had open.
+pub async fn join_gate_verify(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+    let member = ctx
+        .get_member(&user.id)
+        .await
+        .ok_or(ParseError::MemberNotFoundById(user.id.0))?;
+    let guild_id = ctx.get_guild()?.id;
+    let config = ctx.get_config()?;
+
+    let gate_roles = [config.join_gate.probation_role, config.join_gate.verification_role];
+    let held = gate_roles
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|role| member.roles.contains(role))
+        .collect::<Vec<_>>();
+    if held.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("user", user.id.to_string())
+            .generate();
+        ctx.reply(GearBotString::JoinGateNotGated, args).await?;
+        return Ok(());
+    }
+
+    for role in held {
+        ctx.bot_context
+            .remove_member_role(guild_id, user.id, role, Some(ctx.message.author.id))
+            .await?;
+        ctx.journal(ModAction::RoleRemoved { user: user.id, role }).await?;
+    }
+    ctx.bot_context.forget_challenge(guild_id, user.id).await?;
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("user", user.id.to_string())
+        .generate();
+    ctx.reply(GearBotString::JoinGateVerified, args).await?;
+    Ok(())
+}
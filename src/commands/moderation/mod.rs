@@ -1,3 +1,31 @@
+pub use archive::{archive_channel, archive_get};
+pub use audit::audit_integrations;
+pub use bulk_roles::{role_add_all, role_cancel, role_remove_all};
+pub use heat::{heat, heat_reset};
+pub use join_gate::join_gate_verify;
+pub use mod_stats::mod_stats;
+pub use prune::{prune_execute, prune_preview};
+pub use raid_mode::{raid_mode, raid_mode_auto, raid_mode_off, raid_mode_on};
+pub use role_audit::{role_audit, role_audit_fix};
+pub use search::search;
+pub use snipe::{edit_snipe, snipe};
+pub use tickets::{ticket_claim, ticket_close, ticket_open, ticket_panel, ticket_transcript};
+pub use undo::undo;
 pub use userinfo::userinfo;
+pub use watch::{unwatch, watch};
 
+mod archive;
+mod audit;
+mod bulk_roles;
+mod heat;
+mod join_gate;
+mod mod_stats;
+mod prune;
+mod raid_mode;
+mod role_audit;
+mod search;
+mod snipe;
+mod tickets;
+mod undo;
 mod userinfo;
+mod watch;
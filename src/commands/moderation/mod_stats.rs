@@ -0,0 +1,89 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::{self, Emoji};
+
+const MOD_STATS_EMBED_COLOR: u32 = 0x00_cea2;
+const DEFAULT_PERIOD_DAYS: u32 = 30;
+
+/// Shows how many infractions every moderator gave out over a period and of what type, so the work can be spread
+/// more evenly.
+pub async fn mod_stats(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let period_days = if ctx.parser.has_next() {
+        let input = ctx.parser.get_next()?.to_string();
+        match utils::parse_duration(&input).filter(|duration| duration.num_days() > 0) {
+            Some(duration) => duration.num_days() as u32,
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("period", input)
+                    .generate();
+                ctx.reply(GearBotString::ModStatsInvalidPeriod, args).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        DEFAULT_PERIOD_DAYS
+    };
+
+    let period = ctx.format_duration(period_days as i64 * 86_400, 2);
+    let workloads = ctx
+        .bot_context
+        .datastore
+        .get_moderator_workload(guild_id, period_days)
+        .await?;
+    if workloads.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .add("period", period)
+            .generate();
+        ctx.reply(GearBotString::ModStatsNothing, args).await?;
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    for workload in &workloads {
+        let name = match ctx.bot_context.get_user(workload.mod_id).await {
+            Ok(user) => user.full_name(),
+            Err(_) => workload.mod_id.to_string(),
+        };
+        let kinds = workload
+            .kinds
+            .iter()
+            .map(|(kind, count)| {
+                let args = FluArgs::with_capacity(2)
+                    .add("kind", kind.clone())
+                    .add("count", *count)
+                    .generate();
+                ctx.translate_with_args(GearBotString::ModStatsKind, &args)
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let args = FluArgs::with_capacity(4)
+            .add("name", name)
+            .add("id", workload.mod_id.to_string())
+            .add("total", workload.total)
+            .add("kinds", kinds)
+            .generate();
+        description += &ctx.translate_with_args(GearBotString::ModStatsModerator, &args);
+        description += "\n";
+    }
+
+    let total = workloads.iter().map(|workload| workload.total).sum::<u64>();
+    let title_args = FluArgs::with_capacity(1).add("period", period).generate();
+    let footer_args = FluArgs::with_capacity(2)
+        .add("total", total)
+        .add("moderators", workloads.len())
+        .generate();
+    let embed = SafeEmbed::new()
+        .title(ctx.translate_with_args(GearBotString::ModStatsTitle, &title_args))
+        .description(description)
+        .footer(ctx.translate_with_args(GearBotString::ModStatsFooter, &footer_args))
+        .color(MOD_STATS_EMBED_COLOR);
+    ctx.reply_safe_embed(embed).await?;
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use twilight_model::id::UserId;
+
+use crate::core::prune::PruneAction;
+use crate::core::{CommandContext, ModAction, RoleMutation, PRUNE_PREVIEW_TTL};
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, Emoji};
+
+/// Lists the members without activity in the window, this is what ``prune execute`` acts on.
+pub async fn prune_preview(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let window_days = if ctx.parser.has_next() {
+        let input = ctx.parser.get_next()?.to_string();
+        match utils::parse_duration(&input).filter(|duration| duration.num_days() > 0) {
+            Some(duration) => duration.num_days() as u32,
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("window", input)
+                    .generate();
+                ctx.reply(GearBotString::PruneInvalidWindow, args).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        ctx.get_config()?.prune.window_days
+    };
+
+    let preview = ctx
+        .bot_context
+        .preview_prune(guild_id, ctx.message.author.id, window_days)
+        .await?;
+    let window = ctx.format_duration(window_days as i64 * 86_400, 2);
+    if preview.members.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("emoji", Emoji::Yes.for_chat())
+            .add("window", window)
+            .generate();
+        ctx.reply(GearBotString::PruneNothing, args).await?;
+        return Ok(());
+    }
+
+    let export = export_members(&ctx, &preview.members).await;
+    let args = FluArgs::with_capacity(4)
+        .add("emoji", Emoji::Info.for_chat())
+        .add("count", preview.members.len())
+        .add("window", window)
+        .add("minutes", PRUNE_PREVIEW_TTL / 60)
+        .generate();
+    ctx.reply_with_file(
+        GearBotString::PrunePreview,
+        args,
+        format!("prune_preview_{}.txt", guild_id),
+        export.into_bytes(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Kicks or roles the members of the last preview of the moderator. Dry runs leave the preview to act on.
+pub async fn prune_execute(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let action = match ctx.parser.get_next()?.to_lowercase().as_str() {
+        "kick" => PruneAction::Kick,
+        "role" => match ctx.get_config()?.prune.role {
+            Some(role) => {
+                ctx.bot_context
+                    .check_role_mutation(guild_id, role, RoleMutation::Add, Some(ctx.message.author.id))
+                    .await?;
+                PruneAction::Role(role)
+            }
+            None => {
+                let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+                ctx.reply(GearBotString::PruneNoRole, args).await?;
+                return Ok(());
+            }
+        },
+        _ => return Err(ParseError::WrongArgumentType(String::from("kick or role")).into()),
+    };
+
+    let moderator = ctx.message.author.id;
+    let preview = if ctx.is_dry_run() {
+        ctx.bot_context.get_prune_preview(guild_id, moderator).await?
+    } else {
+        ctx.bot_context.take_prune_preview(guild_id, moderator).await?
+    };
+    let preview = match preview {
+        Some(preview) => preview,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::PruneNoPreview, args).await?;
+            return Ok(());
+        }
+    };
+    if ctx.is_dry_run() {
+        let args = FluArgs::with_capacity(2)
+            .add("action", action_name(action))
+            .add("count", preview.members.len())
+            .generate();
+        ctx.reply_dry_run(GearBotString::PruneDryRun, args).await?;
+        return Ok(());
+    }
+
+    let outcome = ctx
+        .bot_context
+        .execute_prune(guild_id, moderator, &preview, action)
+        .await?;
+    // Kicks can't be taken back, roles can
+    if let PruneAction::Role(role) = action {
+        if !outcome.pruned.is_empty() {
+            ctx.journal(ModAction::RolesAdded {
+                users: outcome.pruned.clone(),
+                role,
+            })
+            .await?;
+        }
+    }
+
+    let mut export = String::from("Pruned:\n");
+    export += &export_members(&ctx, &outcome.pruned).await;
+    if !outcome.failed.is_empty() {
+        export += "\nFailed:\n";
+        export += &export_members(&ctx, &outcome.failed).await;
+    }
+    let args = FluArgs::with_capacity(4)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("action", action_name(action))
+        .add("pruned", outcome.pruned.len())
+        .add("total", preview.members.len())
+        .generate();
+    ctx.reply_with_file(
+        GearBotString::PruneExecuted,
+        args,
+        format!("prune_{}.txt", guild_id),
+        export.into_bytes(),
+    )
+    .await?;
+    Ok(())
+}
+
+fn action_name(action: PruneAction) -> &'static str {
+    match action {
+        PruneAction::Kick => "kick",
+        PruneAction::Role(_) => "role",
+    }
+}
+
+/// One member per line, with their name if we still know it.
+async fn export_members(ctx: &CommandContext, members: &[UserId]) -> String {
+    let mut export = String::new();
+    for user_id in members {
+        match ctx.bot_context.cache.get_user(*user_id).await {
+            Some(user) => export += &format!("{} {}\n", user_id, user.full_name()),
+            None => export += &format!("{}\n", user_id),
+        }
+    }
+    export
+}
@@ -0,0 +1,119 @@
+use twilight_model::id::RoleId;
+
+use crate::core::{CommandContext, ModAction};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn raid_mode(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let (key, args) = match ctx.bot_context.get_raid_mode(guild_id).await? {
+        Some(state) => {
+            let args = FluArgs::with_capacity(2)
+                .add("since", state.since.to_string())
+                .add("automatic", state.automatic.to_string());
+            (GearBotString::RaidModeStatusOn, args)
+        }
+        None => {
+            let config = ctx.get_config()?;
+            let args = FluArgs::with_capacity(1).add("auto", config.raid_mode.auto.to_string());
+            (GearBotString::RaidModeStatusOff, args)
+        }
+    };
+
+    ctx.reply(key, args.generate()).await?;
+    Ok(())
+}
+
+pub async fn raid_mode_on(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let state = match ctx
+        .bot_context
+        .enable_raid_mode(guild_id, Some(ctx.message.author.id))
+        .await?
+    {
+        Some(state) => state,
+        None => {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            ctx.reply(GearBotString::RaidModeAlreadyOn, args).await?;
+            return Ok(());
+        }
+    };
+
+    // Undo unlocks the channels all at once, ``raidmode off`` puts back the rest of the lockdown
+    if !state.restricted_channels.is_empty() {
+        ctx.journal(ModAction::ChannelsLocked {
+            role: RoleId(guild_id.0),
+            channels: state.restricted_channels.clone(),
+        })
+        .await?;
+    }
+
+    let mut changes = vec![];
+    if state.previous_verification_level.is_some() {
+        changes.push(ctx.translate(GearBotString::RaidModeVerificationRaised));
+    }
+    if state.previous_everyone_permissions.is_some() {
+        changes.push(ctx.translate(GearBotString::RaidModeInvitesPaused));
+    }
+    if !state.restricted_channels.is_empty() {
+        let args = FluArgs::with_capacity(1)
+            .add("count", state.restricted_channels.len())
+            .generate();
+        changes.push(ctx.translate_with_args(GearBotString::RaidModeChannelsRestricted, &args));
+    }
+    let changes = if changes.is_empty() {
+        ctx.translate(GearBotString::RaidModeNoChanges)
+    } else {
+        changes.join(", ")
+    };
+
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("changes", changes)
+        .generate();
+    ctx.reply(GearBotString::RaidModeOn, args).await?;
+    Ok(())
+}
+
+pub async fn raid_mode_off(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let disabled = ctx
+        .bot_context
+        .disable_raid_mode(guild_id, ctx.message.author.id)
+        .await?;
+
+    let (key, args) = match disabled {
+        Some(_) => (
+            GearBotString::RaidModeOff,
+            FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()),
+        ),
+        None => (
+            GearBotString::RaidModeAlreadyOff,
+            FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()),
+        ),
+    };
+    ctx.reply(key, args.generate()).await?;
+    Ok(())
+}
+
+/// Switches the raid detector on or off.
+pub async fn raid_mode_auto(ctx: CommandContext) -> CommandResult {
+    let mut config = (*ctx.get_config()?).clone();
+    config.raid_mode.auto = !config.raid_mode.auto;
+    let raid_mode = config.raid_mode.clone();
+    ctx.set_config(config).await?;
+
+    let (key, args) = if raid_mode.auto {
+        let args = FluArgs::with_capacity(3)
+            .add("emoji", Emoji::Yes.for_chat())
+            .add("threshold", raid_mode.join_threshold)
+            .add("window", raid_mode.join_window);
+        (GearBotString::RaidModeAutoOn, args)
+    } else {
+        let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat());
+        (GearBotString::RaidModeAutoOff, args)
+    };
+    ctx.reply(key, args.generate()).await?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use twilight_model::id::RoleId;
+
+use crate::core::role_audit::{self, RoleAudit};
+use crate::core::{CommandContext, RoleMutation};
+use crate::error::{CommandError, CommandResult};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+/// Reports the roles that could use some cleaning up, with what to do about them.
+pub async fn role_audit(ctx: CommandContext) -> CommandResult {
+    let (audit, names) = audit_guild_roles(&ctx).await?;
+    if audit.is_clean() {
+        let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+        ctx.reply(GearBotString::RoleAuditClean, args).await?;
+        return Ok(());
+    }
+
+    let args = FluArgs::with_capacity(2)
+        .add("gearinfo", Emoji::Info.for_chat())
+        .add("roles", names.len())
+        .generate();
+    let mut content = ctx.translate_with_args(GearBotString::RoleAuditHeader, &args);
+    if !audit.empty.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("count", audit.empty.len())
+            .add("roles", role_list(&names, &audit.empty))
+            .generate();
+        content += "\n\n";
+        content += &ctx.translate_with_args(GearBotString::RoleAuditEmpty, &args);
+    }
+    if !audit.duplicate_permissions.is_empty() {
+        let args = FluArgs::with_capacity(1)
+            .add("count", audit.duplicate_permissions.len())
+            .generate();
+        content += "\n\n";
+        content += &ctx.translate_with_args(GearBotString::RoleAuditDuplicates, &args);
+        for group in &audit.duplicate_permissions {
+            content += "\n- ";
+            content += &role_list(&names, group);
+        }
+    }
+    if !audit.hoisted.is_empty() {
+        let args = FluArgs::with_capacity(2)
+            .add("count", audit.hoisted.len())
+            .add("roles", role_list(&names, &audit.hoisted))
+            .generate();
+        content += "\n\n";
+        content += &ctx.translate_with_args(GearBotString::RoleAuditHoisted, &args);
+    }
+    if !audit.above_bot.is_empty() {
+        let args = FluArgs::with_capacity(1).add("count", audit.above_bot.len()).generate();
+        content += "\n\n";
+        content += &ctx.translate_with_args(GearBotString::RoleAuditAboveBot, &args);
+        for (role, members) in &audit.above_bot {
+            let args = FluArgs::with_capacity(2)
+                .add("role", role_name(&names, role))
+                .add("members", *members)
+                .generate();
+            content += "\n- ";
+            content += &ctx.translate_with_args(GearBotString::RoleAuditHolders, &args);
+        }
+    }
+
+    ctx.reply_long(content).await?;
+    Ok(())
+}
+
+/// Deletes the empty roles the audit found, as far as the role guard lets us touch them.
+pub async fn role_audit_fix(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let (audit, names) = audit_guild_roles(&ctx).await?;
+
+    let mut deletable = vec![];
+    for role in audit.empty {
+        if ctx
+            .bot_context
+            .check_role_mutation(guild_id, role, RoleMutation::Remove, Some(ctx.message.author.id))
+            .await
+            .is_ok()
+        {
+            deletable.push(role);
+        }
+    }
+    if deletable.is_empty() {
+        let args = FluArgs::with_capacity(1)
+            .add("gearinfo", Emoji::Info.for_chat())
+            .generate();
+        ctx.reply(GearBotString::RoleAuditFixNothing, args).await?;
+        return Ok(());
+    }
+
+    if ctx.is_dry_run() {
+        let args = FluArgs::with_capacity(2)
+            .add("count", deletable.len())
+            .add("roles", role_list(&names, &deletable))
+            .generate();
+        ctx.reply_dry_run(GearBotString::RoleAuditFixDryRun, args).await?;
+        return Ok(());
+    }
+
+    let mut deleted = 0;
+    for role in &deletable {
+        match ctx.bot_context.http.delete_role(guild_id, *role).await {
+            Ok(_) => deleted += 1,
+            Err(e) => {
+                ctx.bot_context.track_http_error(&e).await;
+                log::debug!("Failed to delete role {} in guild {}: {}", role, guild_id, e);
+            }
+        }
+    }
+
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("deleted", deleted)
+        .add("total", deletable.len())
+        .generate();
+    ctx.reply(GearBotString::RoleAuditFixed, args).await?;
+    Ok(())
+}
+
+/// Audits the roles of the guild of the command, along with the names of all roles.
+async fn audit_guild_roles(ctx: &CommandContext) -> Result<(RoleAudit, HashMap<RoleId, String>), CommandError> {
+    let guild = ctx.get_guild()?;
+    let top_position = ctx.bot_context.get_role_guard(guild.id).await.top_position();
+
+    let roles = guild.roles.read().await.values().cloned().collect::<Vec<_>>();
+    let members = guild.members.read().await.values().cloned().collect::<Vec<_>>();
+    let audit = role_audit::audit_roles(
+        guild.id,
+        roles.iter().map(|role| role.as_ref()),
+        members.iter().map(|member| member.as_ref()),
+        top_position,
+    );
+    let names = roles
+        .iter()
+        .filter(|role| role.id.0 != guild.id.0)
+        .map(|role| (role.id, role.name.clone()))
+        .collect();
+
+    Ok((audit, names))
+}
+
+fn role_name(names: &HashMap<RoleId, String>, role: &RoleId) -> String {
+    match names.get(role) {
+        Some(name) => format!("``{}``", name),
+        None => format!("``{}``", role),
+    }
+}
+
+fn role_list(names: &HashMap<RoleId, String>, roles: &[RoleId]) -> String {
+    roles
+        .iter()
+        .map(|role| role_name(names, role))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -0,0 +1,147 @@
+use chrono::Utc;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, MessageId, UserId};
+
+use crate::core::CommandContext;
+use crate::database::structures::MessageSearch;
+use crate::error::{CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::{self, SafeEmbed};
+use crate::utils::{self, matchers, Emoji};
+
+const SEARCH_EMBED_COLOR: u32 = 0x00_cea2;
+const PAGE_SIZE: usize = 10;
+/// How many of the most recent messages matching the filters get decrypted to look for the text.
+const SCAN_LIMIT: u32 = 2000;
+const PREVIEW_LENGTH: usize = 300;
+
+pub async fn search(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let mut search = MessageSearch::default();
+    let mut page = 1;
+    let mut words = vec![];
+
+    while ctx.parser.has_next() {
+        let argument = ctx.parser.get_next()?.to_string();
+        let (filter, value) = match argument.split_once(':') {
+            Some((filter, value)) if is_filter(filter) => (filter.to_lowercase(), value.to_string()),
+            // Anything that isn't a filter is part of the text to look for
+            _ => {
+                words.push(argument);
+                continue;
+            }
+        };
+
+        let parsed = match filter.as_str() {
+            "from" => matchers::get_snowflake(&value).map(|id| search.author = Some(UserId(id))),
+            "in" => match matchers::get_snowflake(&value) {
+                Some(id) => match ctx.get_channel(ChannelId(id)).await {
+                    Some(channel) if channel.get_guild_id() == Some(guild_id) => {
+                        search.channel = Some(channel.get_id());
+                        Some(())
+                    }
+                    _ => return Err(ParseError::UnknownChannel(id).into()),
+                },
+                None => None,
+            },
+            "after" => utils::parse_duration(&value)
+                .map(|age| search.after = Some(MessageId(utils::snowflake_at(Utc::now() - age)))),
+            "before" => utils::parse_duration(&value)
+                .map(|age| search.before = Some(MessageId(utils::snowflake_at(Utc::now() - age)))),
+            "page" => value.parse::<usize>().ok().map(|number| page = number.max(1)),
+            _ => None,
+        };
+
+        if parsed.is_none() {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("filter", argument)
+                .generate();
+            ctx.reply(GearBotString::SearchInvalidFilter, args).await?;
+            return Ok(());
+        }
+    }
+
+    let text = words.join(" ").to_lowercase();
+    let config = ctx.get_config()?;
+    let messages = ctx
+        .bot_context
+        .datastore
+        .search_messages(guild_id, &search, SCAN_LIMIT)
+        .await?;
+    let scanned = messages.len();
+
+    // Only show what the moderator could have read in the channel themselves
+    let mut matches = vec![];
+    for (id, message) in messages {
+        if config.message_logs.ignored_users.contains(&message.author.0)
+            || !message.content.to_lowercase().contains(&text)
+            || !ctx
+                .has_channel_permissions(
+                    ctx.message.author.id,
+                    message.channel,
+                    Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+                )
+                .await
+        {
+            continue;
+        }
+        matches.push((id, message));
+    }
+
+    if matches.is_empty() {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::SearchNothing, args).await?;
+        return Ok(());
+    }
+
+    let pages = (matches.len() + PAGE_SIZE - 1) / PAGE_SIZE;
+    let page = page.min(pages);
+
+    let mut description = String::new();
+    for (id, message) in matches.iter().skip((page - 1) * PAGE_SIZE).take(PAGE_SIZE) {
+        let author = match ctx.get_user(message.author).await {
+            Ok(user) => user.full_name_with_id(),
+            Err(_) => message.author.to_string(),
+        };
+        let content = utils::clean(&message.content, true, true, true, true);
+        let args = FluArgs::with_capacity(5)
+            .add("author", author)
+            .add("channel", message.channel.to_string())
+            .add(
+                "time",
+                utils::snowflake_timestamp(id.0).format("%Y-%m-%d %H:%M").to_string(),
+            )
+            .add(
+                "link",
+                format!("https://discord.com/channels/{}/{}/{}", guild_id, message.channel, id),
+            )
+            .add("content", embeds::truncate(&content, PREVIEW_LENGTH))
+            .generate();
+        description += &ctx.translate_with_args(GearBotString::SearchEntry, &args);
+        description += "\n\n";
+    }
+
+    let header_args = FluArgs::with_capacity(2)
+        .add("page", page)
+        .add("pages", pages)
+        .generate();
+    let mut embed = SafeEmbed::new()
+        .title(ctx.translate_with_args(GearBotString::SearchHeader, &header_args))
+        .description(description)
+        .color(SEARCH_EMBED_COLOR);
+    if scanned as u32 == SCAN_LIMIT {
+        let args = FluArgs::with_capacity(1).add("limit", SCAN_LIMIT).generate();
+        embed = embed.footer(ctx.translate_with_args(GearBotString::SearchScanLimit, &args));
+    }
+
+    ctx.reply_safe_embed(embed).await?;
+    Ok(())
+}
+
+fn is_filter(name: &str) -> bool {
+    matches!(
+        name.to_lowercase().as_str(),
+        "from" | "in" | "after" | "before" | "page"
+    )
+}
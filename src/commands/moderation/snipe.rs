@@ -0,0 +1,71 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::core::{CommandContext, GuildFeatures, SnipedMessage};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
+use crate::utils::Emoji;
+
+const SNIPE_EMBED_COLOR: u32 = 0x00_cea2;
+
+pub async fn snipe(ctx: CommandContext) -> CommandResult {
+    send_snipe(ctx, false).await
+}
+
+pub async fn edit_snipe(ctx: CommandContext) -> CommandResult {
+    send_snipe(ctx, true).await
+}
+
+async fn send_snipe(ctx: CommandContext, edit: bool) -> CommandResult {
+    let config = ctx.get_config()?;
+    if !config.features.contains(GuildFeatures::SNIPES) {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("feature", "snipes")
+            .generate();
+        ctx.reply(GearBotString::FeatureDisabled, args).await?;
+        return Ok(());
+    }
+
+    let channel_id = ctx.message.channel.get_id();
+    let snipe = match ctx.bot_context.get_snipe(channel_id, edit).await? {
+        // in case it got excluded after we stored it
+        Some(snipe) if !ctx.bot_context.is_excluded_from_snipe(snipe.id).await? => snipe,
+        _ => {
+            let key = if edit {
+                GearBotString::EditSnipeNothing
+            } else {
+                GearBotString::SnipeNothing
+            };
+            ctx.reply(key, FluArgs::with_capacity(0).generate()).await?;
+            return Ok(());
+        }
+    };
+
+    if config.message_logs.ignored_users.contains(&snipe.author.0) {
+        ctx.reply(GearBotString::SnipeNothing, FluArgs::with_capacity(0).generate())
+            .await?;
+        return Ok(());
+    }
+
+    let user = ctx.get_user(snipe.author).await?;
+    let mut embed = SafeEmbed::new()
+        .author(user.full_name_with_id(), Some(user.avatar_url()))
+        .color(SNIPE_EMBED_COLOR)
+        .timestamp(timestamp(&snipe));
+
+    embed = match &snipe.previous_content {
+        Some(previous) => embed
+            .field(ctx.translate(GearBotString::SnipeBefore), previous.clone())
+            .field(ctx.translate(GearBotString::SnipeAfter), snipe.content.clone()),
+        None => embed.description(snipe.content.clone()),
+    };
+
+    ctx.reply_safe_embed(embed).await?;
+
+    Ok(())
+}
+
+fn timestamp(snipe: &SnipedMessage) -> String {
+    DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(snipe.timestamp, 0), Utc).to_rfc3339()
+}
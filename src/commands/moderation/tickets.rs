@@ -0,0 +1,221 @@
+use twilight_model::guild::Permissions;
+
+use super::archive::{export_messages, fetch_messages, Selection, MAX_MESSAGES};
+use crate::core::tickets::{self, TicketOutcome};
+use crate::core::{CommandContext, GuildConfig};
+use crate::database::structures::StoredTicket;
+use crate::error::{CommandError, CommandResult, ParseError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::transcript;
+use crate::utils::Emoji;
+
+pub async fn ticket_open(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let outcome = ctx.bot_context.open_ticket(guild_id, ctx.message.author.id).await?;
+
+    let (key, args) = match outcome {
+        TicketOutcome::Opened(channel_id) => (
+            GearBotString::TicketOpened,
+            FluArgs::with_capacity(2)
+                .add("emoji", Emoji::Yes.for_chat())
+                .add("channel", channel_id.to_string()),
+        ),
+        TicketOutcome::AtLimit(channel_id) => (
+            GearBotString::TicketAtLimit,
+            FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("channel", channel_id.to_string()),
+        ),
+        TicketOutcome::Disabled => (
+            GearBotString::TicketsDisabled,
+            FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()),
+        ),
+        TicketOutcome::Failed => (
+            GearBotString::TicketFailed,
+            FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()),
+        ),
+    };
+    ctx.reply(key, args.generate()).await?;
+    Ok(())
+}
+
+/// Closes the ticket the command is used in, storing a transcript first if the guild wants those. Only the member
+/// who opened it and staff can do this.
+pub async fn ticket_close(ctx: CommandContext) -> CommandResult {
+    let ticket = match current_ticket(&ctx).await? {
+        Some(ticket) => ticket,
+        None => return Ok(()),
+    };
+    let config = ctx.get_config()?;
+    if ticket.owner_id as u64 != ctx.message.author.id.0 && !is_ticket_staff(&ctx, &config).await {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::TicketNotYours, args).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    let channel = ctx.message.channel.clone();
+    let channel_id = channel.get_id();
+    let mut transcript_id = None;
+    if config.tickets.transcripts {
+        let messages = fetch_messages(&ctx, channel_id, &Selection::Latest(MAX_MESSAGES), ctx.message.id).await?;
+        if !messages.is_empty() {
+            let exported = export_messages(&ctx, &messages).await;
+            let content = transcript::render_html(channel.get_name(), channel_id, &exported);
+            // The close command is never part of the transcript, so it can't clash with another archive
+            let archive_id = ctx.message.id.0;
+            ctx.bot_context
+                .datastore
+                .insert_archive(archive_id, guild_id, channel_id, &content)
+                .await?;
+            transcript_id = Some(archive_id);
+        }
+    }
+
+    ctx.bot_context
+        .datastore
+        .close_ticket(guild_id, ticket.id, transcript_id)
+        .await?;
+    ctx.bot_context.http.delete_channel(channel_id).await?;
+    Ok(())
+}
+
+pub async fn ticket_claim(ctx: CommandContext) -> CommandResult {
+    let ticket = match current_ticket(&ctx).await? {
+        Some(ticket) => ticket,
+        None => return Ok(()),
+    };
+
+    let channel_id = ctx.message.channel.get_id();
+    if let Some(claimed_by) = ticket.claimed_by {
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("user", claimed_by.to_string())
+            .generate();
+        ctx.reply(GearBotString::TicketAlreadyClaimed, args).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.get_guild()?.id;
+    ctx.bot_context
+        .datastore
+        .claim_ticket(guild_id, ticket.id, ctx.message.author.id)
+        .await?;
+
+    // Announced in the ticket itself, so the member knows who they're talking to
+    let args = FluArgs::with_capacity(2)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("user", ctx.message.author.id.to_string())
+        .generate();
+    ctx.send_message(channel_id, GearBotString::TicketClaimed, args).await?;
+    Ok(())
+}
+
+/// Hands out the stored transcript of a closed ticket by number, or a fresh one of the ticket the command is used in.
+pub async fn ticket_transcript(mut ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    if ctx.parser.has_next() {
+        let id = ctx
+            .parser
+            .get_next()?
+            .trim_start_matches('#')
+            .parse::<i32>()
+            .map_err(|_| ParseError::WrongArgumentType(String::from("ticket number")))?;
+
+        let ticket = match ctx.bot_context.datastore.get_ticket(guild_id, id).await? {
+            Some(ticket) => ticket,
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("id", id)
+                    .generate();
+                ctx.reply(GearBotString::TicketUnknown, args).await?;
+                return Ok(());
+            }
+        };
+        let content = match ticket.transcript_id {
+            Some(archive_id) => {
+                ctx.bot_context
+                    .datastore
+                    .get_archive(archive_id as u64, guild_id)
+                    .await?
+            }
+            None => None,
+        };
+        match content {
+            Some(content) => send_transcript(&ctx, id, content).await?,
+            None => {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearno", Emoji::No.for_chat())
+                    .add("id", id)
+                    .generate();
+                ctx.reply(GearBotString::TicketNoTranscript, args).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let ticket = match current_ticket(&ctx).await? {
+        Some(ticket) => ticket,
+        None => return Ok(()),
+    };
+    let channel = ctx.message.channel.clone();
+    let channel_id = channel.get_id();
+    let messages = fetch_messages(&ctx, channel_id, &Selection::Latest(MAX_MESSAGES), ctx.message.id).await?;
+    let exported = export_messages(&ctx, &messages).await;
+    let content = transcript::render_html(channel.get_name(), channel_id, &exported);
+    send_transcript(&ctx, ticket.id, content).await
+}
+
+/// Posts the message members can react to to open a ticket in this channel, replacing the previous one.
+pub async fn ticket_panel(ctx: CommandContext) -> CommandResult {
+    let args = FluArgs::with_capacity(1).add("emoji", Emoji::Yes.for_chat()).generate();
+    let panel = ctx
+        .send_message(ctx.message.channel.get_id(), GearBotString::TicketPanel, args)
+        .await?;
+    ctx.bot_context
+        .http
+        .create_reaction(panel.channel_id, panel.id, Emoji::Yes.to_reaction())
+        .await?;
+
+    let mut config = (*ctx.get_config()?).clone();
+    config.tickets.panel = Some(panel.id);
+    ctx.set_config(config).await?;
+    Ok(())
+}
+
+/// The ticket the command is used in, replying that it isn't one when it's not.
+async fn current_ticket(ctx: &CommandContext) -> Result<Option<StoredTicket>, CommandError> {
+    let ticket = ctx
+        .bot_context
+        .datastore
+        .get_channel_ticket(ctx.message.channel.get_id())
+        .await?;
+    if ticket.is_none() {
+        let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+        ctx.reply(GearBotString::TicketNotATicket, args).await?;
+    }
+    Ok(ticket)
+}
+
+/// Staff are the configured roles, and anyone who can manage the channels anyways.
+async fn is_ticket_staff(ctx: &CommandContext, config: &GuildConfig) -> bool {
+    if let Ok(member) = ctx.get_author_as_member() {
+        if config.tickets.is_staff(&member.roles) {
+            return true;
+        }
+    }
+    ctx.author_has_guild_permissions(Permissions::MANAGE_CHANNELS).await
+}
+
+async fn send_transcript(ctx: &CommandContext, id: i32, content: String) -> CommandResult {
+    let args = FluArgs::with_capacity(1).add("id", id).generate();
+    ctx.reply_with_file(
+        GearBotString::TicketTranscript,
+        args,
+        format!("{}.html", tickets::channel_name(id)),
+        content.into_bytes(),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::core::{CommandContext, ModAction, RoleMutation, UNDO_WINDOW};
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+
+pub async fn undo(ctx: CommandContext) -> CommandResult {
+    let guild_id = ctx.get_guild()?.id;
+    let bot_context = &ctx.bot_context;
+    let http = &bot_context.http;
+
+    let entry = match bot_context.latest_mod_action(guild_id, ctx.message.author.id).await? {
+        Some(entry) => entry,
+        None => {
+            let args = FluArgs::with_capacity(1).add("minutes", UNDO_WINDOW / 60).generate();
+            ctx.reply(GearBotString::UndoNothing, args).await?;
+            return Ok(());
+        }
+    };
+
+    let (key, args) = match entry.action.clone() {
+        ModAction::RoleRemoved { user, role } => {
+            bot_context
+                .add_member_role(guild_id, user, role, Some(ctx.message.author.id))
+                .await?;
+            let args = FluArgs::with_capacity(2)
+                .add("user", user.to_string())
+                .add("role", role.to_string());
+            (GearBotString::UndoRoleRemoved, args)
+        }
+        ModAction::RolesAdded { users, role } => {
+            bot_context
+                .check_role_mutation(guild_id, role, RoleMutation::Remove, Some(ctx.message.author.id))
+                .await?;
+            let mut undone = 0;
+            for user in &users {
+                match http.remove_guild_member_role(guild_id, *user, role).await {
+                    Ok(_) => undone += 1,
+                    Err(e) => bot_context.track_http_error(&e).await,
+                }
+            }
+            let args = FluArgs::with_capacity(3)
+                .add("role", role.to_string())
+                .add("undone", undone)
+                .add("total", users.len());
+            (GearBotString::UndoRolesAdded, args)
+        }
+        ModAction::RolesRemoved { users, role } => {
+            bot_context
+                .check_role_mutation(guild_id, role, RoleMutation::Add, Some(ctx.message.author.id))
+                .await?;
+            let mut undone = 0;
+            for user in &users {
+                match http.add_guild_member_role(guild_id, *user, role).await {
+                    Ok(_) => undone += 1,
+                    Err(e) => bot_context.track_http_error(&e).await,
+                }
+            }
+            let args = FluArgs::with_capacity(3)
+                .add("role", role.to_string())
+                .add("undone", undone)
+                .add("total", users.len());
+            (GearBotString::UndoRolesRemoved, args)
+        }
+        ModAction::ChannelsLocked { role, channels } => {
+            let mut undone = 0;
+            for (channel, previous) in &channels {
+                let result = match previous {
+                    Some((allow, deny)) => http
+                        .update_channel_permission(*channel, *allow, *deny)
+                        .role(role)
+                        .await
+                        .map(|_| ()),
+                    None => http.delete_channel_permission(*channel, role.0).await.map(|_| ()),
+                };
+                match result {
+                    Ok(_) => undone += 1,
+                    Err(e) => bot_context.track_http_error(&e).await,
+                }
+            }
+            let args = FluArgs::with_capacity(2)
+                .add("undone", undone)
+                .add("total", channels.len());
+            (GearBotString::UndoChannelsLocked, args)
+        }
+    };
+    // Only now it's undone, a failure above leaves it in the journal to try again
+    bot_context
+        .forget_mod_action(guild_id, ctx.message.author.id, &entry)
+        .await?;
+
+    ctx.reply(key, args.generate()).await?;
+
+    Ok(())
+}
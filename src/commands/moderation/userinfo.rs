@@ -1,11 +1,11 @@
 use chrono::{DateTime, Utc};
-use twilight_embed_builder::{EmbedAuthorBuilder, EmbedBuilder, ImageSource};
 use twilight_model::guild::Permissions;
 use twilight_model::user::UserFlags;
 
 use crate::core::CommandContext;
 use crate::error::CommandResult;
 use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::SafeEmbed;
 use crate::utils::{self, Emoji};
 
 const USER_INFO_COLOR: u32 = 0x00_cea2;
@@ -16,21 +16,15 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
     //set some things that are the same regardless
     let mut content = "".to_string();
 
-    let mut builder = EmbedBuilder::new();
-    let mut author_builder = EmbedAuthorBuilder::new().name(format!("{}#{}", user.username, user.discriminator))?;
-
-    if let Some(avatar) = user.avatar.as_ref() {
+    let icon_url = user.avatar.as_ref().map(|avatar| {
         let extension = if avatar.starts_with("a_") { "gif" } else { "png" };
-
-        author_builder = author_builder.icon_url(ImageSource::url(format!(
+        format!(
             "https://cdn.discordapp.com/avatars/{}/{}.{}",
-            user.id,
-            user.avatar.as_ref().unwrap(),
-            extension
-        ))?);
-    }
+            user.id, avatar, extension
+        )
+    });
 
-    builder = builder.author(author_builder.build());
+    let mut embed = SafeEmbed::new().author(format!("{}#{}", user.username, user.discriminator), icon_url);
 
     //add badges
     let flags = match user.public_flags {
@@ -100,7 +94,7 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
         "\n**User id**: {}\n**Account created on**: {}\n**Account Age**: {}\n\n",
         user.id,
         created_at.format("%A %d %B %Y (%T)"),
-        utils::age(created_at, Utc::now(), 2)
+        ctx.format_duration(Utc::now().signed_duration_since(created_at).num_seconds(), 2)
     );
 
     let cached_member = ctx.get_member(&user.id).await;
@@ -111,7 +105,7 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
                 Some(role) => ctx.get_role(role).await.unwrap().color,
                 None => USER_INFO_COLOR,
             };
-            builder = builder.color(color)?;
+            embed = embed.color(color);
 
             let (joined, ago) = match &member.joined_at {
                 Some(joined) => {
@@ -119,7 +113,7 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
                         DateTime::from_utc(DateTime::parse_from_str(joined, "%FT%T%.f%z").unwrap().naive_utc(), Utc);
                     (
                         joined.format("%A %d %B %Y (%T)").to_string(),
-                        utils::age(joined, Utc::now(), 2),
+                        ctx.format_duration(Utc::now().signed_duration_since(joined).num_seconds(), 2),
                     )
                 }
                 None => ("Unknown".to_string(), "Unknown".to_string()),
@@ -154,7 +148,7 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
             }
         }
         None => {
-            builder = builder.color(USER_INFO_COLOR)?;
+            embed = embed.color(USER_INFO_COLOR);
         }
     }
 
@@ -169,11 +163,25 @@ pub async fn userinfo(mut ctx: CommandContext) -> CommandResult {
         )
     }
 
-    builder = builder.description(content)?;
+    if ctx.get_config()?.reputation_network {
+        let guild_id = ctx.get_guild()?.id;
+        let reputation = ctx
+            .bot_context
+            .datastore
+            .get_network_reputation(user.id, guild_id)
+            .await?;
+        let args = FluArgs::with_capacity(2)
+            .add("bans", reputation.bans)
+            .add("infractions", reputation.infractions)
+            .generate();
+        content += &ctx.translate_with_args(GearBotString::UserinfoNetworkReputation, &args);
+    }
+
+    embed = embed.description(content);
 
     let args = FluArgs::with_capacity(1).add("userid", user.id.to_string()).generate();
 
-    ctx.reply_with_embed(GearBotString::UserinfoHeader, args, builder.build()?)
+    ctx.reply_with_safe_embed(GearBotString::UserinfoHeader, args, embed)
         .await?;
 
     Ok(())
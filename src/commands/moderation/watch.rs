@@ -0,0 +1,45 @@
+use crate::core::CommandContext;
+use crate::error::CommandResult;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+pub async fn watch(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+    let guild_id = ctx.get_guild()?.id;
+    let added = ctx
+        .bot_context
+        .watch_user(guild_id, user.id, ctx.message.author.id)
+        .await?;
+
+    let key = if added {
+        GearBotString::WatchAdded
+    } else {
+        GearBotString::WatchAlready
+    };
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("gearno", Emoji::No.for_chat())
+        .add("user", user.id.to_string())
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
+
+pub async fn unwatch(mut ctx: CommandContext) -> CommandResult {
+    let user = ctx.parser.get_user().await?;
+    let guild_id = ctx.get_guild()?.id;
+    let removed = ctx.bot_context.unwatch_user(guild_id, user.id).await?;
+
+    let key = if removed {
+        GearBotString::WatchRemoved
+    } else {
+        GearBotString::WatchNotWatched
+    };
+    let args = FluArgs::with_capacity(3)
+        .add("emoji", Emoji::Yes.for_chat())
+        .add("gearno", Emoji::No.for_chat())
+        .add("user", user.id.to_string())
+        .generate();
+    ctx.reply(key, args).await?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::audit_log::AuditLogEvent;
+use twilight_model::id::UserId;
+
+/// Stops compromised or malicious admins that start tearing the server down, by counting destructive actions per
+/// executor and acting on them once they do too many too fast.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AntiNukeConfig {
+    pub enabled: bool,
+    /// How long actions keep counting, in seconds.
+    pub window: u32,
+    /// How many of each action in the window is too many, 0 to not count that action.
+    pub channel_deletes: u32,
+    pub role_deletes: u32,
+    pub bans: u32,
+    pub webhook_creates: u32,
+    pub response: AntiNukeResponse,
+    /// Never counted, the owner and the bot itself never are either.
+    pub exempt_users: Vec<UserId>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AntiNukeResponse {
+    /// Takes away every role we can, so they lose their permissions but a mistake is easy to undo.
+    StripRoles,
+    Ban,
+}
+
+/// The destructive actions that get counted.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NukeAction {
+    ChannelDelete,
+    RoleDelete,
+    Ban,
+    WebhookCreate,
+}
+
+impl Default for AntiNukeConfig {
+    fn default() -> Self {
+        AntiNukeConfig {
+            enabled: false,
+            window: 60,
+            channel_deletes: 3,
+            role_deletes: 3,
+            bans: 5,
+            webhook_creates: 3,
+            response: AntiNukeResponse::StripRoles,
+            exempt_users: vec![],
+        }
+    }
+}
+
+impl AntiNukeConfig {
+    /// How many of the action it takes to act, `None` if it's not counted.
+    pub fn threshold(&self, action: NukeAction) -> Option<u32> {
+        let threshold = match action {
+            NukeAction::ChannelDelete => self.channel_deletes,
+            NukeAction::RoleDelete => self.role_deletes,
+            NukeAction::Ban => self.bans,
+            NukeAction::WebhookCreate => self.webhook_creates,
+        };
+        Some(threshold).filter(|threshold| *threshold > 0)
+    }
+}
+
+impl NukeAction {
+    /// The audit log entries that tell who did it.
+    pub fn audit_event(self) -> AuditLogEvent {
+        match self {
+            NukeAction::ChannelDelete => AuditLogEvent::ChannelDelete,
+            NukeAction::RoleDelete => AuditLogEvent::RoleDelete,
+            NukeAction::Ban => AuditLogEvent::MemberBanAdd,
+            NukeAction::WebhookCreate => AuditLogEvent::WebhookCreate,
+        }
+    }
+}
+
+impl fmt::Display for NukeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NukeAction::ChannelDelete => "channel deletions",
+            NukeAction::RoleDelete => "role deletions",
+            NukeAction::Ban => "bans",
+            NukeAction::WebhookCreate => "webhook creations",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_thresholds_turn_counting_off() {
+        let config = AntiNukeConfig {
+            bans: 0,
+            ..AntiNukeConfig::default()
+        };
+
+        assert_eq!(config.threshold(NukeAction::ChannelDelete), Some(3));
+        assert_eq!(config.threshold(NukeAction::Ban), None);
+    }
+}
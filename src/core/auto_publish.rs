@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{ChannelId, RoleId, UserId};
+
+/// How many messages discord lets us publish per announcement channel every hour.
+pub const PUBLISH_LIMIT: u32 = 10;
+const PUBLISH_WINDOW: i64 = 60 * 60;
+
+/// Announcement channels whose messages get published to the channels following them as soon as they're posted.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct AutoPublishConfig {
+    pub channels: HashMap<ChannelId, PublishFilter>,
+}
+
+/// Whose messages get published. Without authors or roles everyone's are, otherwise only the messages of the listed
+/// authors and members with one of the roles.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct PublishFilter {
+    pub authors: Vec<UserId>,
+    pub roles: Vec<RoleId>,
+    /// Also publish the messages of bots and webhooks that aren't listed as authors.
+    pub bots: bool,
+}
+
+impl PublishFilter {
+    pub fn allows(&self, author: UserId, roles: &[RoleId], bot: bool) -> bool {
+        if self.authors.contains(&author) {
+            return true;
+        }
+        if bot && !self.bots {
+            return false;
+        }
+        if self.authors.is_empty() && self.roles.is_empty() {
+            return true;
+        }
+        roles.iter().any(|role| self.roles.contains(role))
+    }
+}
+
+/// Counts how many messages were published in the channel during the current hour.
+pub fn publish_count_key(channel_id: ChannelId, now: i64) -> String {
+    format!("auto_publish:{}:{}", channel_id, now / PUBLISH_WINDOW)
+}
+
+/// Seconds until the current hour is over and the count starts over.
+pub fn window_remaining(now: i64) -> u32 {
+    (PUBLISH_WINDOW - now % PUBLISH_WINDOW) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_but_bots_without_filters() {
+        let filter = PublishFilter::default();
+
+        assert!(filter.allows(UserId(1), &[], false));
+        assert!(!filter.allows(UserId(1), &[], true));
+        assert!(PublishFilter {
+            bots: true,
+            ..PublishFilter::default()
+        }
+        .allows(UserId(1), &[], true));
+    }
+
+    #[test]
+    fn filters_on_authors_and_roles() {
+        let filter = PublishFilter {
+            authors: vec![UserId(1)],
+            roles: vec![RoleId(5)],
+            bots: false,
+        };
+
+        assert!(filter.allows(UserId(2), &[RoleId(4), RoleId(5)], false));
+        assert!(!filter.allows(UserId(2), &[RoleId(4)], false));
+        // Listed authors are in even when they are a bot
+        assert!(filter.allows(UserId(1), &[], true));
+        assert!(!filter.allows(UserId(3), &[RoleId(5)], true));
+    }
+
+    #[test]
+    fn counts_per_hour() {
+        assert_eq!(
+            publish_count_key(ChannelId(1), 7200),
+            publish_count_key(ChannelId(1), 10799)
+        );
+        assert_ne!(
+            publish_count_key(ChannelId(1), 7200),
+            publish_count_key(ChannelId(1), 10800)
+        );
+        assert_eq!(window_remaining(7200), 3600);
+        assert_eq!(window_remaining(10799), 1);
+    }
+}
@@ -0,0 +1,579 @@
+use std::fmt;
+
+use regex::{Error as RegexError, RegexSet, RegexSetBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use twilight_model::id::{ChannelId, RoleId};
+
+use crate::core::text_folding;
+
+/// Upper bound on the compiled size of all patterns of a guild together, the set runs on every message.
+const RULE_PATTERNS_SIZE_LIMIT: usize = 1 << 18;
+/// Upper bound on the compiled size of a single pattern, so one rule can't take up the room of all the others.
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+/// Memory the matcher of a guild can keep around to speed up matching, it slows down instead of growing past this.
+const RULE_PATTERNS_CACHE_LIMIT: usize = 1 << 20;
+/// In characters, anything longer is very unlikely to be written by hand.
+pub const MAX_PATTERN_LENGTH: usize = 500;
+/// How deep groups can be nested in a pattern.
+pub const MAX_PATTERN_NESTING: usize = 10;
+/// How many rules a guild can have.
+pub const MAX_RULES: usize = 25;
+
+/// An automod rule the way admins write it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AutomodRule {
+    pub name: String,
+    /// All of these have to match for the rule to trigger.
+    pub conditions: Vec<AutomodCondition>,
+    /// Can be left empty when the rule only adds heat.
+    #[serde(default)]
+    pub actions: Vec<AutomodAction>,
+    /// Heat added to the author every time the rule triggers, see [`HeatConfig`].
+    ///
+    /// [`HeatConfig`]: crate::core::heat::HeatConfig
+    #[serde(default)]
+    pub heat: u32,
+    /// How many times the rule has to trigger for the same user before the actions are taken.
+    #[serde(default = "default_threshold")]
+    pub threshold: u32,
+    /// Seconds after the last trigger before the count for the threshold starts over.
+    #[serde(default = "default_window")]
+    pub window: u32,
+    /// Members with any of these roles are never affected.
+    #[serde(default)]
+    pub exempt_roles: Vec<RoleId>,
+}
+
+fn default_threshold() -> u32 {
+    1
+}
+
+fn default_window() -> u32 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomodCondition {
+    /// The content matches a regex.
+    Content { pattern: String },
+    /// The account of the author is less than this many hours old.
+    AccountAge { max_hours: u64 },
+    /// The author joined the server less than this many hours ago.
+    MemberAge { max_hours: u64 },
+    /// Sent in one of these channels.
+    InChannel { channels: Vec<ChannelId> },
+    /// Sent anywhere but these channels.
+    NotInChannel { channels: Vec<ChannelId> },
+    /// Has an attachment with one of these extensions, or any attachment at all if none are listed.
+    Attachment {
+        #[serde(default)]
+        extensions: Vec<String>,
+    },
+}
+
+/// Ordered from least to most severe.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomodAction {
+    Delete,
+    Warn,
+    Kick,
+    Ban,
+}
+
+impl fmt::Display for AutomodAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AutomodAction::Delete => "delete",
+            AutomodAction::Warn => "warn",
+            AutomodAction::Kick => "kick",
+            AutomodAction::Ban => "ban",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Holds back severe automod actions until a moderator approves them, instead of taking them right away.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AutomodReviewConfig {
+    /// Where held back actions are posted for review, nothing is held back without one.
+    pub channel: Option<ChannelId>,
+    /// The least severe action that needs approval.
+    pub min_action: AutomodAction,
+    /// Minutes an action waits for a decision before it's dropped.
+    pub expiry_minutes: u32,
+}
+
+impl Default for AutomodReviewConfig {
+    fn default() -> Self {
+        AutomodReviewConfig {
+            channel: None,
+            min_action: AutomodAction::Kick,
+            expiry_minutes: 60 * 24,
+        }
+    }
+}
+
+impl AutomodReviewConfig {
+    /// Deletes never wait, the point of those is getting the message out of sight right away.
+    pub fn holds_back(&self, action: AutomodAction) -> bool {
+        self.channel.is_some() && action != AutomodAction::Delete && action >= self.min_action
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AutomodRuleError {
+    NoName,
+    NoConditions,
+    NoActions,
+    InvalidPattern(String),
+    /// The pattern is longer than [`MAX_PATTERN_LENGTH`].
+    PatternTooLong(String),
+    /// The pattern nests groups deeper than [`MAX_PATTERN_NESTING`].
+    PatternTooDeep(String),
+    /// The pattern compiles to more than a single pattern is allowed to, usually from large repetitions.
+    PatternTooBig(String),
+    TooMany,
+    TooBig,
+}
+
+impl fmt::Display for AutomodRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomodRuleError::NoName => write!(f, "the rule needs a name"),
+            AutomodRuleError::NoConditions => write!(f, "the rule needs at least one condition"),
+            AutomodRuleError::NoActions => write!(f, "the rule needs at least one action or some heat"),
+            AutomodRuleError::InvalidPattern(e) => write!(f, "invalid pattern: {}", e),
+            AutomodRuleError::PatternTooLong(pattern) => write!(
+                f,
+                "the pattern ``{}`` is longer than {} characters",
+                pattern, MAX_PATTERN_LENGTH
+            ),
+            AutomodRuleError::PatternTooDeep(pattern) => write!(
+                f,
+                "the pattern ``{}`` nests groups deeper than {} levels",
+                pattern, MAX_PATTERN_NESTING
+            ),
+            AutomodRuleError::PatternTooBig(pattern) => {
+                write!(f, "the pattern ``{}`` is too complex to run on every message", pattern)
+            }
+            AutomodRuleError::TooMany => write!(f, "a server can have at most {} rules", MAX_RULES),
+            AutomodRuleError::TooBig => write!(f, "the patterns of all rules together are too big"),
+        }
+    }
+}
+
+impl AutomodRule {
+    /// Checks the rule can be compiled, the patterns are checked on their own here.
+    pub fn check(&self) -> Result<(), AutomodRuleError> {
+        if self.name.trim().is_empty() {
+            return Err(AutomodRuleError::NoName);
+        }
+        if self.conditions.is_empty() {
+            return Err(AutomodRuleError::NoConditions);
+        }
+        if self.actions.is_empty() && self.heat == 0 {
+            return Err(AutomodRuleError::NoActions);
+        }
+        for pattern in self.patterns() {
+            check_pattern(pattern)?;
+        }
+        Ok(())
+    }
+
+    /// If the rule looks at the content at all, the others only depend on who sent it where.
+    pub fn checks_content(&self) -> bool {
+        self.patterns().next().is_some()
+    }
+
+    fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.conditions.iter().filter_map(|condition| match condition {
+            AutomodCondition::Content { pattern } => Some(pattern.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// Refuses patterns that would be too expensive to run on every message.
+///
+/// The regex engine never backtracks, so matching time only grows with the length of the message. What's left to
+/// limit is how big a pattern gets once compiled, that's what long patterns, deep nesting and large repetitions
+/// blow up.
+pub(crate) fn check_pattern(pattern: &str) -> Result<(), AutomodRuleError> {
+    if pattern.chars().count() > MAX_PATTERN_LENGTH {
+        return Err(AutomodRuleError::PatternTooLong(pattern.to_string()));
+    }
+    if nesting_depth(pattern) > MAX_PATTERN_NESTING {
+        return Err(AutomodRuleError::PatternTooDeep(pattern.to_string()));
+    }
+
+    match RegexSetBuilder::new(&[pattern])
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .nest_limit(MAX_PATTERN_NESTING as u32 * 4)
+        .build()
+    {
+        Ok(_) => Ok(()),
+        Err(RegexError::CompiledTooBig(_)) => Err(AutomodRuleError::PatternTooBig(pattern.to_string())),
+        Err(e) => Err(AutomodRuleError::InvalidPattern(e.to_string())),
+    }
+}
+
+/// How deep the groups of a pattern are nested, brackets inside character classes don't count.
+fn nesting_depth(pattern: &str) -> usize {
+    let mut chars = pattern.chars();
+    let mut depth = 0usize;
+    let mut deepest = 0;
+    let mut class = 0usize;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => class += 1,
+            ']' if class > 0 => class -= 1,
+            '(' if class == 0 => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            ')' if class == 0 => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Everything about a message the conditions look at.
+pub struct AutomodSubject<'a> {
+    pub content: &'a str,
+    pub channel: ChannelId,
+    pub roles: &'a [RoleId],
+    /// How old the account is, in hours.
+    pub account_age: u64,
+    /// How long ago they joined, in hours, if we know.
+    pub member_age: Option<u64>,
+    pub attachments: Vec<&'a str>,
+}
+
+/// The rules of a guild, compiled when the config is loaded and stored as their definitions.
+///
+/// The content patterns of all rules share a single set, so every message is scanned at most once no matter
+/// how many rules there are, and not at all if the other conditions already rule everything out.
+#[derive(Debug, Clone)]
+pub struct AutomodRules {
+    rules: Vec<AutomodRule>,
+    patterns: RegexSet,
+    /// For every rule, where its patterns are in the set.
+    rule_patterns: Vec<Vec<usize>>,
+}
+
+impl Default for AutomodRules {
+    fn default() -> Self {
+        AutomodRules {
+            rules: vec![],
+            patterns: RegexSet::empty(),
+            rule_patterns: vec![],
+        }
+    }
+}
+
+impl AutomodRules {
+    /// Compiles the rules, leaving out the broken ones.
+    pub fn compile(rules: Vec<AutomodRule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter(|rule| match rule.check() {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Ignoring invalid automod rule {:?}: {}", rule.name, e);
+                    false
+                }
+            })
+            .take(MAX_RULES)
+            .collect();
+
+        AutomodRules::build(rules).unwrap_or_else(|e| {
+            log::warn!("Ignoring all automod rules: {}", e);
+            AutomodRules::default()
+        })
+    }
+
+    /// Compiles the rules, refusing if any of them is broken.
+    pub fn try_compile(rules: Vec<AutomodRule>) -> Result<Self, AutomodRuleError> {
+        if rules.len() > MAX_RULES {
+            return Err(AutomodRuleError::TooMany);
+        }
+        for rule in &rules {
+            rule.check()?;
+        }
+
+        AutomodRules::build(rules)
+    }
+
+    fn build(rules: Vec<AutomodRule>) -> Result<Self, AutomodRuleError> {
+        let mut sources = vec![];
+        let mut rule_patterns = Vec::with_capacity(rules.len());
+        for rule in &rules {
+            let start = sources.len();
+            sources.extend(rule.patterns());
+            rule_patterns.push((start..sources.len()).collect());
+        }
+
+        // Every pattern is fine on its own at this point, but together they can still be too big
+        let patterns = RegexSetBuilder::new(&sources)
+            .size_limit(RULE_PATTERNS_SIZE_LIMIT)
+            .dfa_size_limit(RULE_PATTERNS_CACHE_LIMIT)
+            .build()
+            .map_err(|_| AutomodRuleError::TooBig)?;
+
+        Ok(AutomodRules {
+            rules,
+            patterns,
+            rule_patterns,
+        })
+    }
+
+    pub fn rules(&self) -> &[AutomodRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// All channels the conditions point to.
+    pub fn referenced_channels(&self) -> impl Iterator<Item = ChannelId> + '_ {
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.conditions.iter())
+            .filter_map(|condition| match condition {
+                AutomodCondition::InChannel { channels } | AutomodCondition::NotInChannel { channels } => {
+                    Some(channels)
+                }
+                _ => None,
+            })
+            .flatten()
+            .copied()
+    }
+
+    /// Finds the rules the message breaks.
+    pub fn matching(&self, subject: &AutomodSubject) -> Vec<&AutomodRule> {
+        let candidates: Vec<usize> = (0..self.rules.len())
+            .filter(|index| self.matches_without_content(&self.rules[*index], subject))
+            .collect();
+
+        if candidates.iter().all(|index| self.rule_patterns[*index].is_empty()) {
+            return candidates.into_iter().map(|index| &self.rules[index]).collect();
+        }
+
+        // Patterns can match either the message as it was sent or what it looks like, so lookalikes don't get
+        // around them while patterns about the special characters themselves keep working
+        let mut matched = vec![false; self.patterns.len()];
+        let folded = text_folding::fold(subject.content);
+        let mut contents = vec![subject.content];
+        if folded != subject.content {
+            contents.push(&folded);
+        }
+        for content in contents {
+            for pattern in self.patterns.matches(content).iter() {
+                matched[pattern] = true;
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|index| self.rule_patterns[*index].iter().all(|pattern| matched[*pattern]))
+            .map(|index| &self.rules[index])
+            .collect()
+    }
+
+    fn matches_without_content(&self, rule: &AutomodRule, subject: &AutomodSubject) -> bool {
+        if subject.roles.iter().any(|role| rule.exempt_roles.contains(role)) {
+            return false;
+        }
+
+        rule.conditions.iter().all(|condition| match condition {
+            AutomodCondition::Content { .. } => true,
+            AutomodCondition::AccountAge { max_hours } => subject.account_age < *max_hours,
+            AutomodCondition::MemberAge { max_hours } => subject.member_age.map_or(false, |age| age < *max_hours),
+            AutomodCondition::InChannel { channels } => channels.contains(&subject.channel),
+            AutomodCondition::NotInChannel { channels } => !channels.contains(&subject.channel),
+            AutomodCondition::Attachment { extensions } => subject.attachments.iter().any(|name| {
+                extensions.is_empty()
+                    || extensions.iter().any(|extension| {
+                        let extension = extension.trim_start_matches('.');
+                        name.rsplit_once('.')
+                            .map_or(false, |(_, actual)| actual.eq_ignore_ascii_case(extension))
+                    })
+            }),
+        })
+    }
+}
+
+impl Serialize for AutomodRules {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.rules.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AutomodRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(AutomodRules::compile(Vec::<AutomodRule>::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: &str) -> AutomodRules {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn subject(content: &str) -> AutomodSubject<'_> {
+        AutomodSubject {
+            content,
+            channel: ChannelId(1),
+            roles: &[],
+            account_age: 24 * 365,
+            member_age: Some(24 * 30),
+            attachments: vec![],
+        }
+    }
+
+    fn names<'a>(matched: Vec<&'a AutomodRule>) -> Vec<&'a str> {
+        matched.into_iter().map(|rule| rule.name.as_str()).collect()
+    }
+
+    #[test]
+    fn combines_conditions() {
+        let rules = rules(
+            r#"[
+                {"name": "invites", "conditions": [{"type": "content", "pattern": "discord\\.gg/"}], "actions": ["delete"]},
+                {
+                    "name": "new links",
+                    "conditions": [{"type": "content", "pattern": "https?://"}, {"type": "account_age", "max_hours": 24}],
+                    "actions": ["delete", "warn"]
+                }
+            ]"#,
+        );
+
+        assert_eq!(names(rules.matching(&subject("join discord.gg/abc"))), vec!["invites"]);
+        assert!(rules.matching(&subject("https://example.com")).is_empty());
+
+        let mut new_account = subject("https://discord.gg/abc");
+        new_account.account_age = 2;
+        assert_eq!(names(rules.matching(&new_account)), vec!["invites", "new links"]);
+    }
+
+    #[test]
+    fn checks_channels_attachments_and_exemptions() {
+        let rules = rules(
+            r#"[{
+                "name": "no executables",
+                "conditions": [{"type": "attachment", "extensions": [".exe", "scr"]}, {"type": "not_in_channel", "channels": [2]}],
+                "actions": ["delete"],
+                "exempt_roles": [5]
+            }]"#,
+        );
+
+        let mut message = subject("");
+        message.attachments = vec!["totally_a_game.EXE"];
+        assert_eq!(rules.matching(&message).len(), 1);
+
+        message.channel = ChannelId(2);
+        assert!(rules.matching(&message).is_empty());
+
+        message.channel = ChannelId(1);
+        message.roles = &[RoleId(5)];
+        assert!(rules.matching(&message).is_empty());
+
+        message.roles = &[];
+        message.attachments = vec!["cat.png"];
+        assert!(rules.matching(&message).is_empty());
+    }
+
+    #[test]
+    fn review_holds_back_severe_actions() {
+        let mut review = AutomodReviewConfig::default();
+        assert!(!review.holds_back(AutomodAction::Ban));
+
+        review.channel = Some(ChannelId(3));
+        assert!(review.holds_back(AutomodAction::Ban));
+        assert!(review.holds_back(AutomodAction::Kick));
+        assert!(!review.holds_back(AutomodAction::Warn));
+        assert!(!review.holds_back(AutomodAction::Delete));
+
+        review.min_action = AutomodAction::Delete;
+        assert!(review.holds_back(AutomodAction::Warn));
+        assert!(!review.holds_back(AutomodAction::Delete));
+    }
+
+    #[test]
+    fn matches_lookalikes() {
+        let rules = rules(
+            r#"[
+                {"name": "invites", "conditions": [{"type": "content", "pattern": "discord\\.gg/"}], "actions": ["delete"]},
+                {"name": "fancy", "conditions": [{"type": "content", "pattern": "ｆａｎｃｙ"}], "actions": ["delete"]}
+            ]"#,
+        );
+
+        assert_eq!(
+            names(rules.matching(&subject("dіscоrd\u{200B}.gg/abc"))),
+            vec!["invites"]
+        );
+        assert_eq!(names(rules.matching(&subject("ｆａｎｃｙ"))), vec!["fancy"]);
+    }
+
+    #[test]
+    fn leaves_out_broken_rules() {
+        let rules = rules(
+            r#"[
+                {"name": "broken", "conditions": [{"type": "content", "pattern": "(unclosed"}], "actions": ["delete"]},
+                {"name": "nothing to do", "conditions": [{"type": "member_age", "max_hours": 1}], "actions": []},
+                {"name": "heat only", "conditions": [{"type": "member_age", "max_hours": 1}], "heat": 5},
+                {"name": "fine", "conditions": [{"type": "content", "pattern": "bad"}], "actions": ["ban"]}
+            ]"#,
+        );
+
+        assert_eq!(rules.rules().len(), 2);
+        assert_eq!(names(rules.matching(&subject("bad words"))), vec!["fine"]);
+        assert_eq!(
+            serde_json::to_value(&rules).unwrap()[1]["threshold"],
+            serde_json::json!(1)
+        );
+    }
+
+    #[test]
+    fn refuses_expensive_patterns() {
+        assert_eq!(check_pattern("discord\\.gg/[a-z]+"), Ok(()));
+        assert_eq!(check_pattern("[(]{3}((a)|(b))"), Ok(()));
+
+        let long = "a".repeat(MAX_PATTERN_LENGTH + 1);
+        assert_eq!(
+            check_pattern(&long),
+            Err(AutomodRuleError::PatternTooLong(long.clone()))
+        );
+
+        let deep = format!(
+            "{}a{}",
+            "(".repeat(MAX_PATTERN_NESTING + 1),
+            ")".repeat(MAX_PATTERN_NESTING + 1)
+        );
+        assert_eq!(
+            check_pattern(&deep),
+            Err(AutomodRuleError::PatternTooDeep(deep.clone()))
+        );
+
+        let repeated = "(\\w{50}){50}";
+        assert_eq!(
+            check_pattern(repeated),
+            Err(AutomodRuleError::PatternTooBig(repeated.to_string()))
+        );
+        assert!(matches!(
+            check_pattern("(unclosed"),
+            Err(AutomodRuleError::InvalidPattern(_))
+        ));
+    }
+}
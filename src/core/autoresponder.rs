@@ -0,0 +1,334 @@
+use std::fmt;
+
+use regex::{RegexSet, RegexSetBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use twilight_model::id::{ChannelId, UserId};
+
+use crate::core::automod::{self, AutomodRuleError};
+
+/// Upper bound on the compiled size of all patterns of a guild together, like the automod ones they run on every
+/// message.
+const RESPONSE_PATTERNS_SIZE_LIMIT: usize = 1 << 18;
+/// Memory the matcher of a guild can keep around to speed up matching.
+const RESPONSE_PATTERNS_CACHE_LIMIT: usize = 1 << 20;
+/// How many autoresponses a guild can have.
+pub const MAX_AUTORESPONSES: usize = 50;
+/// In characters, so a response always fits in a single message.
+pub const MAX_RESPONSE_LENGTH: usize = 2000;
+
+/// A response the bot sends when a message matches its trigger.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AutoResponse {
+    pub name: String,
+    pub trigger: AutoResponseTrigger,
+    /// ``{user}`` is replaced with a mention of whoever triggered it.
+    pub response: String,
+    /// Seconds before it responds in the same channel again.
+    #[serde(default = "default_cooldown")]
+    pub cooldown: u32,
+    /// Only responds in these channels, or in all of them if none are listed.
+    #[serde(default)]
+    pub channels: Vec<ChannelId>,
+    /// Never responds in these channels.
+    #[serde(default)]
+    pub ignored_channels: Vec<ChannelId>,
+}
+
+fn default_cooldown() -> u32 {
+    30
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutoResponseTrigger {
+    /// The whole message is this text, ignoring case and surrounding whitespace.
+    Exact { text: String },
+    /// The message has this text somewhere in it, ignoring case.
+    Contains { text: String },
+    /// The message matches a regex, with the same limits as automod patterns.
+    Regex { pattern: String },
+}
+
+impl fmt::Display for AutoResponseTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutoResponseTrigger::Exact { text } => write!(f, "exactly ``{}``", text),
+            AutoResponseTrigger::Contains { text } => write!(f, "contains ``{}``", text),
+            AutoResponseTrigger::Regex { pattern } => write!(f, "matches ``{}``", pattern),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AutoResponseError {
+    NoName,
+    EmptyTrigger,
+    EmptyResponse,
+    ResponseTooLong,
+    Pattern(AutomodRuleError),
+    TooMany,
+    TooBig,
+}
+
+impl fmt::Display for AutoResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutoResponseError::NoName => write!(f, "the autoresponse needs a name"),
+            AutoResponseError::EmptyTrigger => write!(f, "the trigger can't be empty"),
+            AutoResponseError::EmptyResponse => write!(f, "the response can't be empty"),
+            AutoResponseError::ResponseTooLong => {
+                write!(f, "the response is longer than {} characters", MAX_RESPONSE_LENGTH)
+            }
+            AutoResponseError::Pattern(e) => e.fmt(f),
+            AutoResponseError::TooMany => write!(f, "a server can have at most {} autoresponses", MAX_AUTORESPONSES),
+            AutoResponseError::TooBig => write!(f, "the patterns of all autoresponses together are too big"),
+        }
+    }
+}
+
+impl AutoResponse {
+    pub fn check(&self) -> Result<(), AutoResponseError> {
+        if self.name.trim().is_empty() {
+            return Err(AutoResponseError::NoName);
+        }
+        let trigger = match &self.trigger {
+            AutoResponseTrigger::Exact { text } | AutoResponseTrigger::Contains { text } => text,
+            AutoResponseTrigger::Regex { pattern } => pattern,
+        };
+        if trigger.trim().is_empty() {
+            return Err(AutoResponseError::EmptyTrigger);
+        }
+        if self.response.trim().is_empty() {
+            return Err(AutoResponseError::EmptyResponse);
+        }
+        if self.response.chars().count() > MAX_RESPONSE_LENGTH {
+            return Err(AutoResponseError::ResponseTooLong);
+        }
+        if let AutoResponseTrigger::Regex { pattern } = &self.trigger {
+            automod::check_pattern(pattern).map_err(AutoResponseError::Pattern)?;
+        }
+        Ok(())
+    }
+
+    /// The message to send for a trigger by this user.
+    pub fn render(&self, user_id: UserId) -> String {
+        self.response.replace("{user}", &format!("<@{}>", user_id))
+    }
+
+    fn responds_in(&self, channel: ChannelId) -> bool {
+        (self.channels.is_empty() || self.channels.contains(&channel)) && !self.ignored_channels.contains(&channel)
+    }
+}
+
+/// The autoresponses of a guild, compiled when the config is loaded and stored as their definitions.
+///
+/// Like the automod rules the regex triggers share a single set, so a message is only scanned once.
+#[derive(Debug, Clone)]
+pub struct AutoResponses {
+    responses: Vec<AutoResponse>,
+    patterns: RegexSet,
+    /// For every response, where its pattern is in the set if it has one.
+    response_patterns: Vec<Option<usize>>,
+}
+
+impl Default for AutoResponses {
+    fn default() -> Self {
+        AutoResponses {
+            responses: vec![],
+            patterns: RegexSet::empty(),
+            response_patterns: vec![],
+        }
+    }
+}
+
+impl AutoResponses {
+    /// Compiles the autoresponses, leaving out the broken ones.
+    pub fn compile(responses: Vec<AutoResponse>) -> Self {
+        let responses = responses
+            .into_iter()
+            .filter(|response| match response.check() {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Ignoring invalid autoresponse {:?}: {}", response.name, e);
+                    false
+                }
+            })
+            .take(MAX_AUTORESPONSES)
+            .collect();
+
+        AutoResponses::build(responses).unwrap_or_else(|e| {
+            log::warn!("Ignoring all autoresponses: {}", e);
+            AutoResponses::default()
+        })
+    }
+
+    /// Compiles the autoresponses, refusing if any of them is broken.
+    pub fn try_compile(responses: Vec<AutoResponse>) -> Result<Self, AutoResponseError> {
+        if responses.len() > MAX_AUTORESPONSES {
+            return Err(AutoResponseError::TooMany);
+        }
+        for response in &responses {
+            response.check()?;
+        }
+
+        AutoResponses::build(responses)
+    }
+
+    fn build(responses: Vec<AutoResponse>) -> Result<Self, AutoResponseError> {
+        let mut sources = vec![];
+        let response_patterns = responses
+            .iter()
+            .map(|response| match &response.trigger {
+                AutoResponseTrigger::Regex { pattern } => {
+                    sources.push(pattern.as_str());
+                    Some(sources.len() - 1)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let patterns = RegexSetBuilder::new(&sources)
+            .size_limit(RESPONSE_PATTERNS_SIZE_LIMIT)
+            .dfa_size_limit(RESPONSE_PATTERNS_CACHE_LIMIT)
+            .build()
+            .map_err(|_| AutoResponseError::TooBig)?;
+
+        Ok(AutoResponses {
+            responses,
+            patterns,
+            response_patterns,
+        })
+    }
+
+    pub fn responses(&self) -> &[AutoResponse] {
+        &self.responses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// All channels the autoresponses point to.
+    pub fn referenced_channels(&self) -> impl Iterator<Item = ChannelId> + '_ {
+        self.responses
+            .iter()
+            .flat_map(|response| response.channels.iter().chain(response.ignored_channels.iter()))
+            .copied()
+    }
+
+    /// The responses that trigger on the message, in the order they were defined.
+    pub fn matching(&self, content: &str, channel: ChannelId) -> Vec<&AutoResponse> {
+        let candidates: Vec<usize> = (0..self.responses.len())
+            .filter(|index| self.responses[*index].responds_in(channel))
+            .collect();
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        let lowercase = content.to_lowercase();
+        let matched: Vec<usize> = if candidates.iter().any(|index| self.response_patterns[*index].is_some()) {
+            self.patterns.matches(content).into_iter().collect()
+        } else {
+            vec![]
+        };
+
+        candidates
+            .into_iter()
+            .filter(|index| match &self.responses[*index].trigger {
+                AutoResponseTrigger::Exact { text } => lowercase.trim() == text.trim().to_lowercase(),
+                AutoResponseTrigger::Contains { text } => lowercase.contains(&text.to_lowercase()),
+                AutoResponseTrigger::Regex { .. } => {
+                    self.response_patterns[*index].map_or(false, |pattern| matched.contains(&pattern))
+                }
+            })
+            .map(|index| &self.responses[index])
+            .collect()
+    }
+}
+
+impl Serialize for AutoResponses {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.responses.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AutoResponses {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(AutoResponses::compile(Vec::<AutoResponse>::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn responses(json: &str) -> AutoResponses {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn names<'a>(matched: Vec<&'a AutoResponse>) -> Vec<&'a str> {
+        matched.into_iter().map(|response| response.name.as_str()).collect()
+    }
+
+    #[test]
+    fn matches_triggers() {
+        let responses = responses(
+            r#"[
+                {"name": "hi", "trigger": {"type": "exact", "text": "Hello"}, "response": "Hi {user}!"},
+                {"name": "rules", "trigger": {"type": "contains", "text": "the RULES"}, "response": "Read <#1>"},
+                {"name": "version", "trigger": {"type": "regex", "pattern": "(?i)which version"}, "response": "2.0"}
+            ]"#,
+        );
+
+        assert_eq!(names(responses.matching("  hello ", ChannelId(1))), vec!["hi"]);
+        assert!(responses.matching("hello there", ChannelId(1)).is_empty());
+        assert_eq!(
+            names(responses.matching("where are the rules? Which version is this?", ChannelId(1))),
+            vec!["rules", "version"]
+        );
+        assert_eq!(responses.responses()[0].render(UserId(5)), "Hi <@5>!");
+        assert_eq!(responses.responses()[0].cooldown, 30);
+    }
+
+    #[test]
+    fn respects_channels() {
+        let responses = responses(
+            r#"[
+                {"name": "help", "trigger": {"type": "contains", "text": "help"}, "response": "Ask in <#2>", "channels": [1, 3], "ignored_channels": [3]}
+            ]"#,
+        );
+
+        assert_eq!(responses.matching("help", ChannelId(1)).len(), 1);
+        assert!(responses.matching("help", ChannelId(2)).is_empty());
+        assert!(responses.matching("help", ChannelId(3)).is_empty());
+    }
+
+    #[test]
+    fn leaves_out_broken_responses() {
+        let responses = responses(
+            r#"[
+                {"name": "broken", "trigger": {"type": "regex", "pattern": "(unclosed"}, "response": "nope"},
+                {"name": "silent", "trigger": {"type": "exact", "text": "hi"}, "response": " "},
+                {"name": "fine", "trigger": {"type": "regex", "pattern": "fi+ne"}, "response": "yes"}
+            ]"#,
+        );
+
+        assert_eq!(responses.responses().len(), 1);
+        assert_eq!(names(responses.matching("fiiine", ChannelId(1))), vec!["fine"]);
+
+        let repeated = AutoResponse {
+            name: String::from("big"),
+            trigger: AutoResponseTrigger::Regex {
+                pattern: String::from("(\\w{50}){50}"),
+            },
+            response: String::from("too big"),
+            cooldown: 0,
+            channels: vec![],
+            ignored_channels: vec![],
+        };
+        assert!(matches!(
+            repeated.check(),
+            Err(AutoResponseError::Pattern(AutomodRuleError::PatternTooBig(_)))
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{ChannelId, RoleId, UserId};
+
+/// Announcements of the birthdays (or anniversaries) members registered, opt-in per guild.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BirthdayConfig {
+    pub enabled: bool,
+    /// Where the announcements go, without one only the role is handed out.
+    pub channel: Option<ChannelId>,
+    /// ``{user}`` is replaced with a mention of the member.
+    pub message: String,
+    /// Given for the day, and taken away again once it's over.
+    pub role: Option<RoleId>,
+}
+
+impl Default for BirthdayConfig {
+    fn default() -> Self {
+        BirthdayConfig {
+            enabled: false,
+            channel: None,
+            message: String::from(":tada: Happy birthday {user}!"),
+            role: None,
+        }
+    }
+}
+
+impl BirthdayConfig {
+    pub fn announcement(&self, user_id: UserId) -> String {
+        self.message.replace("{user}", &format!("<@{}>", user_id))
+    }
+}
+
+/// Parses a date as month and day, like ``12-31``. A year in front (``1990-12-31``) is accepted and ignored.
+pub fn parse_birthday(input: &str) -> Option<(u32, u32)> {
+    let parts: Vec<&str> = input.trim().split('-').collect();
+    let (month, day) = match parts.as_slice() {
+        [month, day] | [_, month, day] => (month.parse().ok()?, day.parse().ok()?),
+        _ => return None,
+    };
+
+    // 2000 is a leap year, so the 29th of february is valid
+    NaiveDate::from_ymd_opt(2000, month, day).map(|_| (month, day))
+}
+
+/// The date it is in the timezone, days start and end at midnight there.
+pub fn local_date(timezone: Tz, now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&timezone).naive_local().date()
+}
+
+/// If the birthday is celebrated on this day, the 29th of february is celebrated on the 28th in other years.
+pub fn celebrated_on(month: u32, day: u32, date: NaiveDate) -> bool {
+    if month == 2 && day == 29 && NaiveDate::from_ymd_opt(date.year(), 2, 29).is_none() {
+        return date.month() == 2 && date.day() == 28;
+    }
+    date.month() == month && date.day() == day
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates_with_and_without_year() {
+        assert_eq!(parse_birthday("12-31"), Some((12, 31)));
+        assert_eq!(parse_birthday("1990-03-04"), Some((3, 4)));
+        assert_eq!(parse_birthday("02-29"), Some((2, 29)));
+        assert_eq!(parse_birthday("02-30"), None);
+        assert_eq!(parse_birthday("13-01"), None);
+        assert_eq!(parse_birthday("tomorrow"), None);
+    }
+
+    #[test]
+    fn leap_day_birthdays_move_to_the_28th() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert!(celebrated_on(3, 4, date(2021, 3, 4)));
+        assert!(!celebrated_on(3, 4, date(2021, 3, 5)));
+        assert!(celebrated_on(2, 29, date(2021, 2, 28)));
+        assert!(!celebrated_on(2, 29, date(2020, 2, 28)));
+        assert!(celebrated_on(2, 29, date(2020, 2, 29)));
+    }
+
+    #[test]
+    fn the_day_follows_the_timezone() {
+        let now = DateTime::parse_from_rfc3339("2021-03-04T23:30:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(local_date(Tz::UTC, now), NaiveDate::from_ymd_opt(2021, 3, 4).unwrap());
+        assert_eq!(
+            local_date(Tz::Europe__Brussels, now),
+            NaiveDate::from_ymd_opt(2021, 3, 5).unwrap()
+        );
+        assert_eq!(
+            local_date(Tz::America__New_York, now),
+            NaiveDate::from_ymd_opt(2021, 3, 4).unwrap()
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use std::fs;
+use std::net::{IpAddr, SocketAddr};
 use std::{collections::HashMap, convert::TryFrom};
 
 use serde::Deserialize;
+use twilight_model::gateway::Intents;
 use twilight_model::id::{EmojiId, WebhookId};
 use twilight_util::link::webhook::parse as parse_webhook;
 
@@ -16,7 +18,241 @@ pub struct BotConfig {
     pub database: Database,
     pub emoji: HashMap<String, String>,
     pub global_admins: Vec<u64>,
+    pub rest_proxy: Option<RestProxy>,
+    /// Deprecated: the old way to set a proxy, only used when there is no `rest_proxy` section.
     pub proxy_url: Option<String>,
+    pub attachment_mirror: Option<AttachmentMirror>,
+    pub moderation_api: Option<ModerationApi>,
+    pub ocr_api: Option<OcrApi>,
+    pub toxicity_api: Option<ToxicityApi>,
+    pub backups: Option<Backups>,
+    pub error_tracking: Option<ErrorTracking>,
+    pub tracing: Option<Tracing>,
+    /// How many days data is kept after leaving a guild, 30 if not set.
+    pub data_retention_days: Option<u32>,
+    #[serde(default)]
+    pub sharding: Sharding,
+    #[serde(default)]
+    pub intents: IntentToggles,
+    #[serde(default)]
+    pub metrics: Metrics,
+    pub webhooks: Option<WebhookServer>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct Sharding {
+    /// Shards across all clusters, uses the amount discord recommends if not set.
+    pub total_shards: Option<u64>,
+    /// Which cluster this process is, can be overridden by the first command line argument.
+    pub cluster_id: u64,
+    pub cluster_count: u64,
+}
+
+impl Default for Sharding {
+    fn default() -> Self {
+        Sharding {
+            total_shards: None,
+            cluster_id: 0,
+            cluster_count: 1,
+        }
+    }
+}
+
+/// Gateway intents of optional features, turning one off saves on events but breaks the feature.
+///
+/// Guilds, members and guild messages are always needed and can't be turned off.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct IntentToggles {
+    pub bans: bool,
+    pub emoji: bool,
+    pub invites: bool,
+    pub voice_states: bool,
+    /// Reactions on guild messages, used for paginated replies like the emoji list.
+    pub reactions: bool,
+    /// Commands and reactions in DMs.
+    pub direct_messages: bool,
+}
+
+impl Default for IntentToggles {
+    fn default() -> Self {
+        IntentToggles {
+            bans: true,
+            emoji: true,
+            invites: true,
+            voice_states: true,
+            reactions: true,
+            direct_messages: true,
+        }
+    }
+}
+
+impl IntentToggles {
+    pub fn intents(&self) -> Intents {
+        let mut intents = Intents::GUILDS | Intents::GUILD_MEMBERS | Intents::GUILD_MESSAGES;
+        intents.set(Intents::GUILD_BANS, self.bans);
+        intents.set(Intents::GUILD_EMOJIS, self.emoji);
+        intents.set(Intents::GUILD_INVITES, self.invites);
+        intents.set(Intents::GUILD_VOICE_STATES, self.voice_states);
+        intents.set(Intents::GUILD_MESSAGE_REACTIONS, self.reactions);
+        intents.set(
+            Intents::DIRECT_MESSAGES | Intents::DIRECT_MESSAGE_REACTIONS,
+            self.direct_messages,
+        );
+        intents
+    }
+}
+
+/// Where the prometheus metrics and the health check are served, and who gets to see them.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct Metrics {
+    pub bind: SocketAddr,
+    /// When set, requests need to send this as a bearer token in the authorization header. `/healthz` doesn't, so
+    /// liveness probes work without it.
+    pub token: Option<String>,
+    /// Addresses that are allowed to connect, anyone can when this is empty.
+    pub allowed_ips: Vec<IpAddr>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            bind: SocketAddr::from(([127, 0, 0, 1], 9091)),
+            token: None,
+            allowed_ips: vec![],
+        }
+    }
+}
+
+/// Receives webhooks of other services, so they can be relayed into guild channels.
+#[derive(Deserialize, Debug)]
+pub struct WebhookServer {
+    pub bind: SocketAddr,
+    /// Where the server can be reached from the internet, like `https://gearbot.example.com`.
+    pub public_url: String,
+}
+
+/// An http proxy that handles the ratelimits, so all clusters share the same ratelimit budget.
+#[derive(Deserialize, Debug)]
+pub struct RestProxy {
+    /// Address of the proxy, like `localhost:3000`.
+    pub url: String,
+    /// Talk plain http to the proxy instead of https.
+    #[serde(default = "enabled")]
+    pub use_http: bool,
+    /// Talk to discord directly (with our own ratelimiter) if the proxy can't be reached on startup.
+    #[serde(default)]
+    pub fallback_to_direct: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AttachmentMirror {
+    /// A private channel that attachments of deleted messages get re-uploaded to.
+    pub channel: u64,
+}
+
+/// An external service that labels images, attachments are sent to it for guilds that have image policies.
+#[derive(Deserialize, Debug)]
+pub struct ModerationApi {
+    /// Gets a POST with the url of every image, and answers with the labels it found.
+    pub endpoint: String,
+    /// Sent as bearer token when set.
+    pub token: Option<String>,
+    /// Images that take longer than this are left alone.
+    #[serde(default = "default_moderation_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_moderation_timeout() -> u64 {
+    10
+}
+
+/// An external service that reads the text in images, so automod rules also apply to text posted as a picture.
+#[derive(Deserialize, Debug)]
+pub struct OcrApi {
+    /// Gets a POST with the url of every image, and answers with the text in it.
+    pub endpoint: String,
+    /// Sent as bearer token when set.
+    pub token: Option<String>,
+    #[serde(default = "default_moderation_timeout")]
+    pub timeout_seconds: u64,
+}
+
+/// An external service that scores how toxic messages are, guilds decide how much heat that adds.
+#[derive(Deserialize, Debug)]
+pub struct ToxicityApi {
+    /// Gets a POST with the content of every message, and answers with a score per attribute.
+    pub endpoint: String,
+    /// Sent as bearer token when set.
+    pub token: Option<String>,
+    #[serde(default = "default_moderation_timeout")]
+    pub timeout_seconds: u64,
+}
+
+/// Periodic snapshots of all guild configs, compressed and encrypted with the main encryption key.
+#[derive(Deserialize, Debug)]
+pub struct Backups {
+    /// Folder the backup files are written to, it's created if it doesn't exist.
+    pub directory: String,
+    /// Hours between two backups, 24 if not set.
+    #[serde(default = "default_backup_interval")]
+    pub interval_hours: u64,
+    /// Also back up all infractions, this can get big for larger bots.
+    #[serde(default)]
+    pub include_infractions: bool,
+    /// How many backup files to keep around, the oldest ones are removed first. 7 if not set.
+    #[serde(default = "default_backups_kept")]
+    pub keep: usize,
+}
+
+fn default_backup_interval() -> u64 {
+    24
+}
+
+fn default_backups_kept() -> usize {
+    7
+}
+
+/// Sends panics and failures to a Sentry compatible error tracker, so they come with context instead of only
+/// showing up as a bump in the error counters.
+#[derive(Deserialize, Debug)]
+pub struct ErrorTracking {
+    /// The DSN of the project, like ``https://key@sentry.example.com/42``.
+    pub dsn: String,
+    /// Shows up as the environment of every event, ``production`` if not set.
+    #[serde(default = "default_environment")]
+    pub environment: String,
+}
+
+fn default_environment() -> String {
+    String::from("production")
+}
+
+/// Where the tracing spans are exported to, so slow events and commands can be broken down.
+#[derive(Deserialize, Debug)]
+pub struct Tracing {
+    /// The OTLP (grpc) endpoint of the collector, like ``http://localhost:4317``.
+    pub endpoint: String,
+    /// ``gearbot`` if not set.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// The part of the traces that gets exported, between 0 and 1. All of them if not set.
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+}
+
+fn default_service_name() -> String {
+    String::from("gearbot")
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
 }
 
 #[derive(Deserialize, Debug)]
@@ -28,6 +264,23 @@ pub struct Tokens {
 pub struct Logging {
     pub important_logs: WebhookComponents,
     pub info_logs: WebhookComponents,
+    /// How lines are written to the log files and stderr, the webhooks always get plain text.
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per line, for log collectors like Loki or Elasticsearch.
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Text
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -62,7 +315,17 @@ impl BotConfig {
         let config_file = fs::read_to_string(filename).map_err(|_| StartupError::NoConfig)?;
         match toml::from_str::<BotConfig>(&config_file) {
             Err(_) => Err(StartupError::InvalidConfig),
-            Ok(c) => {
+            Ok(mut c) => {
+                if let Some(url) = c.proxy_url.take() {
+                    println!("proxy_url is deprecated, use a [rest_proxy] section instead");
+                    if c.rest_proxy.is_none() {
+                        c.rest_proxy = Some(RestProxy {
+                            url,
+                            use_http: true,
+                            fallback_to_direct: false,
+                        });
+                    }
+                }
                 let mut override_map: HashMap<String, EmojiOverride> = HashMap::with_capacity(c.emoji.len());
                 let mut id_map: HashMap<String, u64> = HashMap::with_capacity(c.emoji.len());
 
@@ -0,0 +1,150 @@
+use chrono::{Duration, Utc};
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::anti_nuke::{AntiNukeResponse, NukeAction};
+use crate::core::logpump::{LogData, LogType};
+use crate::error::{DatabaseError, EventHandlerError};
+use crate::utils;
+
+/// Audit log entries older than this are about something else.
+const ATTRIBUTION_WINDOW_SECONDS: i64 = 30;
+
+fn nuke_counter_key(guild_id: GuildId, executor: UserId, action: NukeAction) -> String {
+    format!("anti_nuke:{}:{}:{:?}", guild_id, executor, action)
+}
+
+/// Marks an audit log entry as counted, webhook updates don't say which webhook they are about so the same entry can
+/// turn up more than once.
+fn counted_entry_key(entry_id: u64) -> String {
+    format!("anti_nuke_entry:{}", entry_id)
+}
+
+impl BotContext {
+    /// Counts a destructive action against whoever did it, and acts on them once they cross the threshold.
+    ///
+    /// `target` is what the action was done to, webhook creations don't have one.
+    pub async fn track_nuke_action(
+        &self,
+        guild_id: GuildId,
+        action: NukeAction,
+        target: Option<u64>,
+    ) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        let anti_nuke = &config.anti_nuke;
+        let threshold = match anti_nuke.threshold(action) {
+            Some(threshold) if anti_nuke.enabled => threshold,
+            _ => return Ok(()),
+        };
+
+        let redis_cache = &self.datastore.cache_pool;
+        let mut executor = None;
+        for (entry_id, user_id) in self.find_nuke_entries(guild_id, action, target).await {
+            let counted = counted_entry_key(entry_id);
+            if redis_cache.get::<bool>(&counted).await?.is_none() {
+                redis_cache
+                    .set(&counted, &true, Some(ATTRIBUTION_WINDOW_SECONDS as u32))
+                    .await?;
+                executor = Some(user_id);
+                break;
+            }
+        }
+        let executor = match executor {
+            Some(executor) => executor,
+            None => return Ok(()),
+        };
+        let owner = self.cache.get_guild(&guild_id).await.map(|guild| guild.owner_id);
+        if executor == self.bot_user.id || Some(executor) == owner || anti_nuke.exempt_users.contains(&executor) {
+            return Ok(());
+        }
+
+        // The window starts at the first action, spacing them out doesn't keep it open
+        let key = nuke_counter_key(guild_id, executor, action);
+        let count = redis_cache.count_hit(&key, anti_nuke.window.max(1)).await?;
+        if count < threshold as isize {
+            return Ok(());
+        }
+        redis_cache.delete(&key).await.map_err(DatabaseError::from)?;
+
+        let succeeded = match anti_nuke.response {
+            AntiNukeResponse::StripRoles => self.strip_roles(guild_id, executor).await,
+            AntiNukeResponse::Ban => match self.http.create_ban(guild_id, executor).await {
+                Ok(_) => {
+                    self.datastore
+                        .insert_infraction(guild_id, executor, self.bot_user.id, "ban")
+                        .await?;
+                    true
+                }
+                Err(e) => {
+                    self.track_http_error(&e).await;
+                    log::debug!("Failed to ban nuking {} in {}: {}", executor, guild_id, e);
+                    false
+                }
+            },
+        };
+
+        self.log(LogData {
+            log_type: LogType::NukeDetected {
+                action,
+                count,
+                response: anti_nuke.response,
+                succeeded,
+            },
+            guild: guild_id,
+            source_channel: None,
+            source_user: executor,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    /// The recent audit log entries that could be about the action, newest first, with who did them.
+    async fn find_nuke_entries(
+        &self,
+        guild_id: GuildId,
+        action: NukeAction,
+        target: Option<u64>,
+    ) -> Vec<(u64, UserId)> {
+        let request = match self
+            .http
+            .audit_log(guild_id)
+            .action_type(action.audit_event())
+            .limit(10)
+        {
+            Ok(request) => request,
+            Err(_) => return vec![],
+        };
+        let audit_log = match request.await {
+            Ok(Some(audit_log)) => audit_log,
+            _ => return vec![],
+        };
+
+        let cutoff = Utc::now() - Duration::seconds(ATTRIBUTION_WINDOW_SECONDS);
+        let target = target.map(|target| target.to_string());
+        audit_log
+            .entries
+            .iter()
+            .filter(|entry| utils::snowflake_timestamp(entry.id.0) > cutoff)
+            .filter(|entry| target.is_none() || entry.target_id.as_ref().map(|id| id.to_string()) == target)
+            .filter_map(|entry| Some((entry.id.0, entry.user_id?)))
+            .collect()
+    }
+
+    /// Takes away every role of the member the role guard lets us, returns if they're all gone.
+    async fn strip_roles(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        let roles = match self.cache.get_member(&guild_id, &user_id).await {
+            Some(member) => member.roles.clone(),
+            None => return false,
+        };
+
+        let mut stripped = true;
+        for role_id in roles {
+            if let Err(e) = self.remove_member_role(guild_id, user_id, role_id, None).await {
+                log::debug!("Failed to strip {} from {} in {}: {}", role_id, user_id, guild_id, e);
+                stripped = false;
+            }
+        }
+        stripped
+    }
+}
@@ -0,0 +1,103 @@
+use twilight_model::id::{ChannelId, GuildId, MessageId};
+
+use super::BotContext;
+use crate::database::structures::StoredAttachment;
+
+/// Discord refuses uploads bigger than this for bots without boosted guilds.
+const MAX_MIRROR_SIZE: usize = 8 * 1024 * 1024;
+/// Discord only allows this many files per message.
+const MAX_FILES_PER_MESSAGE: usize = 10;
+
+/// Re-uploads attachments of deleted messages to a private channel, so logs can link to files that still exist.
+pub struct AttachmentMirror {
+    channel: ChannelId,
+    client: reqwest::Client,
+}
+
+impl AttachmentMirror {
+    pub fn new(channel: ChannelId) -> Self {
+        AttachmentMirror {
+            channel,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl BotContext {
+    /// Describes the attachments of a deleted message for logging.
+    ///
+    /// When mirroring is requested and configured, every attachment that could still be downloaded gets
+    /// re-uploaded and is described by its new link. Anything else falls back to only the filename, since
+    /// the original links stop working once the message is gone.
+    pub async fn describe_deleted_attachments(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        attachments: &[StoredAttachment],
+        mirror: bool,
+    ) -> Vec<String> {
+        let mut descriptions: Vec<String> = attachments.iter().map(|a| a.filename.clone()).collect();
+
+        let mirror_config = match &self.attachment_mirror {
+            // Uploading is a lot of requests for something logs can do without while we're being rate limited
+            Some(mirror_config) if mirror && !self.rate_limits.is_slowed_down() => mirror_config,
+            _ => return descriptions,
+        };
+
+        let mut files = vec![];
+        for (index, attachment) in attachments.iter().enumerate().take(MAX_FILES_PER_MESSAGE) {
+            let url = format!(
+                "https://cdn.discordapp.com/attachments/{}/{}/{}",
+                channel_id, attachment.id, attachment.filename
+            );
+
+            match download(&mirror_config.client, &url).await {
+                Ok(Some(data)) => files.push((index, attachment.filename.clone(), data)),
+                Ok(None) => log::debug!("Not mirroring attachment {}, it is too big", attachment.id),
+                Err(e) => log::warn!("Failed to download attachment {} for mirroring: {}", attachment.id, e),
+            }
+        }
+
+        if files.is_empty() {
+            return descriptions;
+        }
+
+        let mut request = self
+            .http
+            .create_message(mirror_config.channel)
+            .content(format!("Attachments of message {} in guild {}", message_id, guild_id))
+            .unwrap();
+        for (_, name, data) in &files {
+            request = request.attachment(name.clone(), data.clone());
+        }
+
+        match request.await {
+            // Uploaded attachments come back in the same order they were send in.
+            Ok(mirrored) => {
+                for ((index, name, _), uploaded) in files.iter().zip(mirrored.attachments.iter()) {
+                    descriptions[*index] = format!("{}: <{}>", name, uploaded.url);
+                }
+            }
+            Err(e) => log::warn!("Failed to upload mirrored attachments of message {}: {}", message_id, e),
+        }
+
+        descriptions
+    }
+}
+
+/// Downloads a file, unless it's too big to be uploaded again.
+async fn download(client: &reqwest::Client, url: &str) -> Result<Option<Vec<u8>>, reqwest::Error> {
+    let response = client.get(url).send().await?.error_for_status()?;
+
+    if response.content_length().unwrap_or(0) as usize > MAX_MIRROR_SIZE {
+        return Ok(None);
+    }
+
+    let data = response.bytes().await?;
+    if data.len() > MAX_MIRROR_SIZE {
+        return Ok(None);
+    }
+
+    Ok(Some(data.to_vec()))
+}
@@ -0,0 +1,85 @@
+use chrono::Utc;
+use twilight_model::channel::message::{MessageFlags, MessageType};
+use twilight_model::channel::Message;
+
+use super::rate_limits::is_rate_limited;
+use super::BotContext;
+use crate::cache::CachedChannel;
+use crate::core::auto_publish::{publish_count_key, window_remaining, PUBLISH_LIMIT};
+use crate::error::EventHandlerError;
+
+impl BotContext {
+    /// Publishes new messages in the announcement channels the guild wants that for, if the author passes the filter.
+    ///
+    /// Discord only allows a handful of publishes per channel every hour. Those are counted so we don't keep asking
+    /// once the limit is reached, anything posted after that stays unpublished.
+    pub async fn auto_publish(&self, message: &Message) -> Result<(), EventHandlerError> {
+        let guild_id = match message.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(()),
+        };
+        // System messages can't be published, and copies from channels we follow already were
+        if !matches!(message.kind, MessageType::Regular | MessageType::Reply)
+            || message.flags.map_or(false, |flags| {
+                flags.intersects(MessageFlags::CROSSPOSTED | MessageFlags::IS_CROSSPOST)
+            })
+        {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let filter = match config.auto_publish.channels.get(&message.channel_id) {
+            Some(filter) => filter,
+            None => return Ok(()),
+        };
+        // The channel could have been turned back into a regular one
+        match self.cache.get_channel(message.channel_id).await {
+            Some(channel) if matches!(*channel, CachedChannel::AnnouncementsChannel { .. }) => {}
+            _ => return Ok(()),
+        }
+
+        let roles = match &message.member {
+            Some(member) => member.roles.clone(),
+            None => match self.cache.get_member(&guild_id, &message.author.id).await {
+                Some(member) => member.roles.clone(),
+                None => vec![],
+            },
+        };
+        let bot = message.author.bot || message.webhook_id.is_some();
+        if !filter.allows(message.author.id, &roles, bot) {
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp();
+        let key = publish_count_key(message.channel_id, now);
+        let published = self.datastore.cache_pool.get::<u32>(&key).await?.unwrap_or(0);
+        if published >= PUBLISH_LIMIT {
+            log::debug!(
+                "Not publishing {} in {}, the channel reached the hourly limit",
+                message.id,
+                message.channel_id
+            );
+            return Ok(());
+        }
+
+        let published = match self.http.crosspost_message(message.channel_id, message.id).await {
+            Ok(_) => published + 1,
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to publish {} in {}: {}", message.id, message.channel_id, e);
+                // Discord keeps its own count, trust that one over ours
+                if is_rate_limited(&e) {
+                    PUBLISH_LIMIT
+                } else {
+                    return Ok(());
+                }
+            }
+        };
+        self.datastore
+            .cache_pool
+            .set(&key, &published, Some(window_remaining(now)))
+            .await?;
+
+        Ok(())
+    }
+}
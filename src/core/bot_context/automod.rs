@@ -0,0 +1,374 @@
+use chrono::{DateTime, Utc};
+use twilight_model::channel::Message;
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::automod::{AutomodAction, AutomodRule, AutomodSubject};
+use crate::core::guild_stats::GuildStat;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::reactors::{review_pending_key, AutomodReviewReactor};
+use crate::core::{GuildConfig, GuildFeatures, Reactor};
+use crate::error::{DatabaseError, EventHandlerError};
+use crate::utils::{self, Emoji};
+
+impl BotContext {
+    /// Checks a new message against the automod rules of the guild, and acts on the rules it breaks.
+    pub async fn run_automod(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        if !config.features.contains(GuildFeatures::AUTOMOD) || config.automod.is_empty() {
+            return Ok(());
+        }
+
+        let attachments = message
+            .attachments
+            .iter()
+            .map(|attachment| attachment.filename.as_str())
+            .collect();
+        self.check_automod(message, guild_id, &config, &message.content, attachments, None)
+            .await
+    }
+
+    /// Checks content that came with a message against the rules, and acts on the message for the rules it breaks.
+    ///
+    /// When the content isn't what the message says, like text found in an image, `source` says where it's from in
+    /// the logs.
+    pub(super) async fn check_automod(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        config: &GuildConfig,
+        content: &str,
+        attachments: Vec<&str>,
+        source: Option<&str>,
+    ) -> Result<(), EventHandlerError> {
+        let member = self.cache.get_member(&guild_id, &message.author.id).await;
+        let member_age = member
+            .as_ref()
+            .and_then(|member| member.joined_at.as_ref())
+            .and_then(|joined| DateTime::parse_from_str(joined, "%FT%T%.f%z").ok())
+            .map(|joined| hours_since(joined.with_timezone(&Utc)));
+        let roles = member.as_ref().map(|member| member.roles.as_slice()).unwrap_or(&[]);
+
+        let subject = AutomodSubject {
+            content,
+            channel: message.channel_id,
+            roles,
+            account_age: hours_since(utils::snowflake_timestamp(message.author.id.0)),
+            member_age,
+            attachments,
+        };
+
+        let mut deleted = false;
+        let mut heat = 0;
+        // Content from elsewhere is only for the rules about content, the others already saw the message itself
+        let rules = config
+            .automod
+            .matching(&subject)
+            .into_iter()
+            .filter(|rule| source.is_none() || rule.checks_content());
+        for rule in rules {
+            heat += rule.heat;
+            let actions = if self.automod_threshold_reached(guild_id, message, rule).await? {
+                rule.actions.as_slice()
+            } else {
+                &[]
+            };
+
+            let mut taken = Vec::with_capacity(actions.len());
+            for action in actions {
+                if config.automod_review.holds_back(*action) {
+                    if self
+                        .queue_automod_review(*action, message, guild_id, &rule.name, config)
+                        .await
+                    {
+                        taken.push(format!("{} (awaiting review)", action));
+                    }
+                } else if self
+                    .try_automod_action(*action, message, guild_id, &rule.name, &mut deleted)
+                    .await
+                {
+                    taken.push(action.to_string());
+                }
+            }
+
+            let rule_name = match source {
+                Some(source) => format!("{} ({})", rule.name, source),
+                None => rule.name.clone(),
+            };
+            self.log(LogData {
+                log_type: LogType::AutomodTriggered {
+                    rule: rule_name,
+                    channel: message.channel_id,
+                    content: content.to_string(),
+                    actions: taken,
+                },
+                guild: guild_id,
+                source_channel: Some(message.channel_id),
+                source_user: message.author.id,
+                timestamp: Utc::now(),
+            });
+            self.count_guild_stat(guild_id, GuildStat::AutomodTrigger).await;
+        }
+
+        self.apply_heat(message, guild_id, config, heat, &mut deleted).await
+    }
+
+    /// Adds heat for a message to its author, and takes the action of the step it crossed if any.
+    pub(super) async fn apply_heat(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        config: &GuildConfig,
+        heat: u32,
+        deleted: &mut bool,
+    ) -> Result<(), EventHandlerError> {
+        if heat == 0 || !config.heat.enabled {
+            return Ok(());
+        }
+
+        let (before, after) = self.add_heat(guild_id, message.author.id, heat, &config.heat).await?;
+        if let Some(step) = config.heat.escalation(before, after) {
+            let held_back = config.automod_review.holds_back(step.action);
+            let taken = if held_back {
+                self.queue_automod_review(step.action, message, guild_id, "heat", config)
+                    .await
+            } else {
+                self.try_automod_action(step.action, message, guild_id, "heat", deleted)
+                    .await
+            };
+            self.log(LogData {
+                log_type: LogType::HeatEscalated {
+                    channel: message.channel_id,
+                    heat: after.round() as u32,
+                    threshold: step.threshold,
+                    action: if held_back {
+                        format!("{} (awaiting review)", step.action)
+                    } else {
+                        step.action.to_string()
+                    },
+                    succeeded: taken,
+                },
+                guild: guild_id,
+                source_channel: Some(message.channel_id),
+                source_user: message.author.id,
+                timestamp: Utc::now(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Takes an action, returning if it worked. Failures are only logged, the other actions still get their turn.
+    pub(super) async fn try_automod_action(
+        &self,
+        action: AutomodAction,
+        message: &Message,
+        guild_id: GuildId,
+        reason: &str,
+        deleted: &mut bool,
+    ) -> bool {
+        // Multiple rules can want the same message gone
+        if action == AutomodAction::Delete && *deleted {
+            return false;
+        }
+
+        match self.take_automod_action(action, message, guild_id).await {
+            Ok(()) => {
+                *deleted |= action == AutomodAction::Delete;
+                true
+            }
+            Err(e) => {
+                if let EventHandlerError::Twilight(http_error) = &e {
+                    self.track_http_error(http_error).await;
+                }
+                log::warn!(
+                    "Failed to {} {} for {:?} in {}: {}",
+                    action,
+                    message.author.id,
+                    reason,
+                    guild_id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Posts the action in the review channel for a moderator to decide on, returning if it's waiting for review.
+    ///
+    /// Failures are only logged, like for actions that are taken right away.
+    pub(super) async fn queue_automod_review(
+        &self,
+        action: AutomodAction,
+        message: &Message,
+        guild_id: GuildId,
+        reason: &str,
+        config: &GuildConfig,
+    ) -> bool {
+        let channel_id = match config.automod_review.channel {
+            Some(channel_id) => channel_id,
+            None => return false,
+        };
+        let redis_cache = &self.datastore.cache_pool;
+        let pending_key = review_pending_key(guild_id, message.author.id, action);
+        // Repeat offences while the first one is still waiting don't need a review of their own
+        match redis_cache.get::<u64>(&pending_key).await {
+            Ok(Some(_)) => return true,
+            Ok(None) => {}
+            Err(e) => {
+                log::warn!("Failed to check for pending automod reviews in {}: {}", guild_id, e);
+                return false;
+            }
+        }
+
+        let expires_in = config.automod_review.expiry_minutes.max(1) * 60;
+        let review = AutomodReviewReactor::new(
+            guild_id,
+            message.author.id,
+            action,
+            reason,
+            message.channel_id,
+            &message.content,
+            expires_in,
+        );
+        let request = match self
+            .http
+            .create_message(channel_id)
+            .content(review.prompt_text(self, &config.language))
+        {
+            Ok(request) => request,
+            Err(e) => {
+                log::warn!(
+                    "Failed to build the automod review for {} in {}: {}",
+                    reason,
+                    guild_id,
+                    e
+                );
+                return false;
+            }
+        };
+        let prompt = match request.await {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::warn!(
+                    "Failed to post the automod review for {} in {}: {}",
+                    reason,
+                    guild_id,
+                    e
+                );
+                return false;
+            }
+        };
+
+        let reactor = Reactor::AutomodReview(review);
+        if let Err(e) = reactor.save(self, prompt.id).await {
+            log::warn!(
+                "Failed to store the automod review for {} in {}: {}",
+                reason,
+                guild_id,
+                e
+            );
+            return false;
+        }
+        if let Err(e) = redis_cache.set(&pending_key, &prompt.id.0, Some(expires_in)).await {
+            log::warn!(
+                "Failed to mark the automod review for {} in {}: {}",
+                reason,
+                guild_id,
+                e
+            );
+        }
+        for emoji in &[Emoji::Yes, Emoji::No] {
+            if let Err(e) = self
+                .http
+                .create_reaction(channel_id, prompt.id, emoji.to_reaction())
+                .await
+            {
+                self.track_http_error(&e).await;
+            }
+        }
+
+        true
+    }
+
+    /// Counts the trigger towards the threshold of the rule, returning if it has been reached.
+    ///
+    /// Reaching it starts the count over.
+    async fn automod_threshold_reached(
+        &self,
+        guild_id: GuildId,
+        message: &Message,
+        rule: &AutomodRule,
+    ) -> Result<bool, DatabaseError> {
+        if rule.threshold <= 1 {
+            return Ok(true);
+        }
+
+        // The window starts at the first trigger, later ones don't push it back
+        let redis_cache = &self.datastore.cache_pool;
+        let key = format!("automod:{}:{}:{}", guild_id, rule.name, message.author.id);
+        let count = redis_cache.count_hit(&key, rule.window.max(1)).await?;
+        if count >= rule.threshold as isize {
+            redis_cache.delete(&key).await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn take_automod_action(
+        &self,
+        action: AutomodAction,
+        message: &Message,
+        guild_id: GuildId,
+    ) -> Result<(), EventHandlerError> {
+        match action {
+            AutomodAction::Delete => {
+                self.exclude_from_snipe(message.id).await?;
+                self.http.delete_message(message.channel_id, message.id).await?;
+                Ok(())
+            }
+            _ => {
+                self.punish_for_automod(action, guild_id, message.author.id, self.bot_user.id)
+                    .await
+            }
+        }
+    }
+
+    /// Takes an action against the member, the moderator is who the infraction is on. Deleting does nothing here,
+    /// that only applies to a message.
+    pub async fn punish_for_automod(
+        &self,
+        action: AutomodAction,
+        guild_id: GuildId,
+        user_id: UserId,
+        moderator: UserId,
+    ) -> Result<(), EventHandlerError> {
+        match action {
+            AutomodAction::Delete => {}
+            AutomodAction::Warn => {
+                self.datastore
+                    .insert_infraction(guild_id, user_id, moderator, "warning")
+                    .await?;
+            }
+            AutomodAction::Kick => {
+                self.http.remove_guild_member(guild_id, user_id).await?;
+                self.datastore
+                    .insert_infraction(guild_id, user_id, moderator, "kick")
+                    .await?;
+            }
+            AutomodAction::Ban => {
+                self.http.create_ban(guild_id, user_id).await?;
+                self.datastore
+                    .insert_infraction(guild_id, user_id, moderator, "ban")
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn hours_since(time: DateTime<Utc>) -> u64 {
+    (Utc::now() - time).num_hours().max(0) as u64
+}
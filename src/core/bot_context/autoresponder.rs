@@ -0,0 +1,60 @@
+use twilight_model::channel::Message;
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::error::EventHandlerError;
+
+impl BotContext {
+    /// Sends the first autoresponse the message triggers that isn't on cooldown in the channel.
+    ///
+    /// Runs after automod, messages it deleted and commands don't get a response.
+    pub async fn run_autoresponder(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        if config.autoresponses.is_empty()
+            || message.content.trim().is_empty()
+            || config.matching_prefix(&message.content).is_some()
+        {
+            return Ok(());
+        }
+
+        let matching = config.autoresponses.matching(&message.content, message.channel_id);
+        if matching.is_empty() || self.is_excluded_from_snipe(message.id).await? {
+            return Ok(());
+        }
+
+        let redis_cache = &self.datastore.cache_pool;
+        for response in matching {
+            let key = format!("autoresponse:{}:{}:{}", guild_id, response.name, message.channel_id);
+            if redis_cache.get::<bool>(&key).await?.is_some() {
+                continue;
+            }
+            if response.cooldown > 0 {
+                redis_cache.set(&key, &true, Some(response.cooldown)).await?;
+            }
+
+            let request = match self
+                .http
+                .create_message(message.channel_id)
+                .content(response.render(message.author.id))
+            {
+                Ok(request) => request,
+                Err(e) => {
+                    log::debug!(
+                        "Autoresponse {:?} in {} is not a valid message: {}",
+                        response.name,
+                        guild_id,
+                        e
+                    );
+                    return Ok(());
+                }
+            };
+            if let Err(e) = request.await {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to send autoresponse {:?} in {}: {}", response.name, guild_id, e);
+            }
+            return Ok(());
+        }
+
+        Ok(())
+    }
+}
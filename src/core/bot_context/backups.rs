@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::BotContext;
+use crate::database::redis::api_handlers::api_structs::Request;
+use crate::error::{ApiCommunicaionError, BackupError};
+use crate::{gearbot_error, gearbot_info};
+
+const BACKUP_PREFIX: &str = "gearbot-backup-";
+const BACKUP_EXTENSION: &str = ".bin";
+const CACHE_CLEAR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What ended up in a backup file.
+pub struct BackupInfo {
+    pub file_name: String,
+    pub configs: usize,
+    pub infractions: Option<usize>,
+    pub size: u64,
+}
+
+impl BotContext {
+    /// Periodically writes a backup if they are configured.
+    pub async fn run_backups(&self) {
+        let settings = match &self.backups {
+            Some(settings) if self.is_primary_cluster() => settings,
+            _ => return,
+        };
+
+        let interval = Duration::from_secs(settings.interval_hours.max(1) * 60 * 60);
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match self.create_backup().await {
+                Ok(info) => gearbot_info!(
+                    "Backed up {} guild configs to ``{}`` ({} bytes)",
+                    info.configs,
+                    info.file_name,
+                    info.size
+                ),
+                Err(e) => gearbot_error!("Failed to make a backup: {}", e),
+            }
+        }
+    }
+
+    /// Writes a new backup file and removes the ones that are too old to keep.
+    pub async fn create_backup(&self) -> Result<BackupInfo, BackupError> {
+        let settings = self.backups.as_ref().ok_or(BackupError::NotConfigured)?;
+        let directory = Path::new(&settings.directory);
+        fs::create_dir_all(directory)?;
+
+        let snapshot = self
+            .datastore
+            .create_backup_snapshot(settings.include_infractions)
+            .await?;
+        let encoded = self.datastore.encode_backup(&snapshot)?;
+
+        let file_name = format!("{}{}{}", BACKUP_PREFIX, snapshot.created_at, BACKUP_EXTENSION);
+        fs::write(directory.join(&file_name), &encoded)?;
+
+        let backups = self.list_backups()?;
+        if backups.len() > settings.keep {
+            for old in &backups[..backups.len() - settings.keep] {
+                if let Err(e) = fs::remove_file(directory.join(old)) {
+                    log::warn!("Failed to remove old backup {}: {}", old, e);
+                }
+            }
+        }
+
+        Ok(BackupInfo {
+            file_name,
+            configs: snapshot.configs.len(),
+            infractions: snapshot.infractions.map(|infractions| infractions.len()),
+            size: encoded.len() as u64,
+        })
+    }
+
+    /// Lists the names of the backup files, oldest first.
+    pub fn list_backups(&self) -> Result<Vec<String>, BackupError> {
+        let settings = self.backups.as_ref().ok_or(BackupError::NotConfigured)?;
+        let entries = match fs::read_dir(&settings.directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut backups = vec![];
+        for entry in entries {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name.starts_with(BACKUP_PREFIX) && name.ends_with(BACKUP_EXTENSION) {
+                backups.push(name);
+            }
+        }
+        // The timestamps all have the same amount of digits, so sorting by name sorts by age
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Restores the configs from a backup file, and the missing infractions if asked to.
+    ///
+    /// Returns how many configs and infractions were restored.
+    pub async fn restore_backup(
+        &self,
+        file_name: &str,
+        include_infractions: bool,
+    ) -> Result<(usize, usize), BackupError> {
+        let bytes = fs::read(self.backup_path(file_name)?)?;
+        let snapshot = self.datastore.decode_backup(&bytes)?;
+        let restored = self
+            .datastore
+            .restore_backup_snapshot(&snapshot, include_infractions)
+            .await?;
+
+        self.clear_cached_configs().await;
+        gearbot_info!(
+            "Restored {} guild configs and {} infractions from ``{}``",
+            restored.0,
+            restored.1,
+            file_name
+        );
+        Ok(restored)
+    }
+
+    /// Drops all cached guild configs on every cluster so they get loaded again, returning how many clusters confirmed.
+    pub async fn clear_config_caches(&self) -> Result<usize, ApiCommunicaionError> {
+        let responses = self
+            .datastore
+            .cache_pool
+            .query_clusters(
+                self.scheme_info.cluster_id,
+                Request::ClearConfigCache,
+                self.scheme_info.cluster_count,
+                CACHE_CLEAR_TIMEOUT,
+            )
+            .await?;
+        Ok(responses.len())
+    }
+
+    /// Only accepts names of backup files, so nothing outside the backup directory can be read.
+    fn backup_path(&self, file_name: &str) -> Result<PathBuf, BackupError> {
+        let settings = self.backups.as_ref().ok_or(BackupError::NotConfigured)?;
+        if !file_name.starts_with(BACKUP_PREFIX)
+            || !file_name.ends_with(BACKUP_EXTENSION)
+            || file_name.contains(|c| c == '/' || c == '\\')
+        {
+            return Err(BackupError::Io(std::io::ErrorKind::NotFound.into()));
+        }
+        Ok(Path::new(&settings.directory).join(file_name))
+    }
+}
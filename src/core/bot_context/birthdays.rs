@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use chrono::{Datelike, Utc};
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::core::birthdays::{celebrated_on, local_date};
+use crate::error::DatabaseError;
+use crate::gearbot_error;
+
+/// How often the birthdays are checked, announcements go out at most this long after the day starts.
+const BIRTHDAY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+impl BotContext {
+    /// Periodically announces the birthdays of the day, and takes the birthday role back once the day is over.
+    ///
+    /// Every cluster only handles its own guilds, the announcements need their members.
+    pub async fn run_birthday_announcer(&self) {
+        loop {
+            tokio::time::sleep(BIRTHDAY_INTERVAL).await;
+
+            let guilds = match self.datastore.get_birthday_guilds().await {
+                Ok(guilds) => guilds,
+                Err(e) => {
+                    gearbot_error!("Failed to fetch the guilds with birthdays: {}", e);
+                    continue;
+                }
+            };
+
+            for guild_id in guilds {
+                if self.cache.get_guild(&guild_id).await.is_none() {
+                    continue;
+                }
+                if let Err(e) = self.announce_birthdays(guild_id).await {
+                    gearbot_error!("Failed to announce the birthdays of guild {}: {}", guild_id, e);
+                }
+            }
+        }
+    }
+
+    async fn announce_birthdays(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        let config = self.get_config(guild_id).await?;
+        let birthdays = &config.birthdays;
+        let today = local_date(config.tz(), Utc::now());
+
+        for birthday in self.datastore.get_birthdays(guild_id).await? {
+            let user_id = UserId(birthday.user_id as u64);
+            // Turning the module off still cleans up the roles that were handed out
+            let celebrating = birthdays.enabled && celebrated_on(birthday.month as u32, birthday.day as u32, today);
+
+            if !celebrating {
+                if birthday.role_given {
+                    if let Some(role) = birthdays.role {
+                        if let Err(e) = self.remove_member_role(guild_id, user_id, role, None).await {
+                            log::debug!(
+                                "Failed to take the birthday role from {} in {}: {}",
+                                user_id,
+                                guild_id,
+                                e
+                            );
+                        }
+                    }
+                    self.datastore.mark_birthday_role_removed(guild_id, user_id).await?;
+                }
+                continue;
+            }
+
+            if birthday.announced_year == Some(today.year())
+                || self.cache.get_member(&guild_id, &user_id).await.is_none()
+            {
+                continue;
+            }
+
+            if let Some(channel) = birthdays.channel {
+                self.send_birthday_announcement(channel, birthdays.announcement(user_id))
+                    .await;
+            }
+            let role_given = match birthdays.role {
+                Some(role) => match self.add_member_role(guild_id, user_id, role, None).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        log::debug!("Failed to give the birthday role to {} in {}: {}", user_id, guild_id, e);
+                        false
+                    }
+                },
+                None => false,
+            };
+            self.datastore
+                .mark_birthday_announced(guild_id, user_id, today.year(), role_given)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_birthday_announcement(&self, channel: ChannelId, announcement: String) {
+        let request = match self.http.create_message(channel).content(announcement) {
+            Ok(request) => request,
+            Err(e) => {
+                log::debug!("Birthday announcement for {} is not a valid message: {}", channel, e);
+                return;
+            }
+        };
+        if let Err(e) = request.await {
+            self.track_http_error(&e).await;
+            log::debug!("Failed to announce a birthday in {}: {}", channel, e);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use twilight_model::id::UserId;
+
+use super::BotContext;
+use crate::database::redis::api_handlers::api_structs::Request;
+use crate::error::{ApiCommunicaionError, DatabaseError};
+
+const RELOAD_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl BotContext {
+    /// Checks the global blocklist, this happens for every command so it only looks at what's in memory.
+    pub async fn is_blocked(&self, user_id: UserId) -> bool {
+        self.blocked_users.read().await.contains(&user_id)
+    }
+
+    /// Reloads the blocklist from the database, returning how many users are on it.
+    pub async fn reload_blocklist(&self) -> Result<usize, DatabaseError> {
+        let blocked = self
+            .datastore
+            .get_blocked_users()
+            .await?
+            .into_iter()
+            .collect::<HashSet<UserId>>();
+        let count = blocked.len();
+        *self.blocked_users.write().await = blocked;
+        Ok(count)
+    }
+
+    /// Blocks the user on this cluster, use [`sync_blocklist`](Self::sync_blocklist) to get the others to pick it up.
+    pub async fn block_user(
+        &self,
+        user_id: UserId,
+        reason: Option<&str>,
+        blocked_by: UserId,
+    ) -> Result<(), DatabaseError> {
+        self.datastore.block_user(user_id, reason, blocked_by).await?;
+        self.blocked_users.write().await.insert(user_id);
+        log::info!("{} was blocked from using the bot by {}", user_id, blocked_by);
+        Ok(())
+    }
+
+    /// Lifts the block on this cluster, returning if they were blocked to begin with.
+    pub async fn unblock_user(&self, user_id: UserId, unblocked_by: UserId) -> Result<bool, DatabaseError> {
+        let was_blocked = self.datastore.unblock_user(user_id).await?;
+        self.blocked_users.write().await.remove(&user_id);
+        if was_blocked {
+            log::info!("{} was unblocked by {}", user_id, unblocked_by);
+        }
+        Ok(was_blocked)
+    }
+
+    /// Tells every cluster to reload the blocklist, returning how many of them confirmed they did.
+    pub async fn sync_blocklist(&self) -> Result<usize, ApiCommunicaionError> {
+        let responses = self
+            .datastore
+            .cache_pool
+            .query_clusters(
+                self.scheme_info.cluster_id,
+                Request::ReloadBlocklist,
+                self.scheme_info.cluster_count,
+                RELOAD_REPLY_TIMEOUT,
+            )
+            .await?;
+        Ok(responses.len())
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use twilight_model::id::{GuildId, RoleId, UserId};
+
+use super::BotContext;
+use crate::core::bulk_roles::BulkRoleMode;
+
+/// A bulk role change that is running, there can only be one per guild at a time.
+pub struct BulkRoleJob {
+    pub moderator: UserId,
+    pub role: RoleId,
+    pub mode: BulkRoleMode,
+    cancelled: AtomicBool,
+}
+
+impl BulkRoleJob {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl BotContext {
+    /// Registers a new bulk role change for the guild, `None` if another one is still running.
+    pub async fn start_bulk_role_job(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+        role: RoleId,
+        mode: BulkRoleMode,
+    ) -> Option<Arc<BulkRoleJob>> {
+        let mut jobs = self.bulk_role_jobs.write().await;
+        if jobs.contains_key(&guild_id) {
+            return None;
+        }
+
+        let job = Arc::new(BulkRoleJob {
+            moderator,
+            role,
+            mode,
+            cancelled: AtomicBool::new(false),
+        });
+        jobs.insert(guild_id, job.clone());
+        Some(job)
+    }
+
+    pub async fn finish_bulk_role_job(&self, guild_id: GuildId) {
+        self.bulk_role_jobs.write().await.remove(&guild_id);
+    }
+
+    /// Asks the running bulk role change of the guild to stop after the current chunk, returning the job if there
+    /// was one.
+    pub async fn cancel_bulk_role_job(&self, guild_id: GuildId) -> Option<Arc<BulkRoleJob>> {
+        let job = self.bulk_role_jobs.read().await.get(&guild_id).cloned();
+        if let Some(job) = &job {
+            job.cancelled.store(true, Ordering::Relaxed);
+        }
+        job
+    }
+}
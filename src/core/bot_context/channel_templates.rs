@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use twilight_http::request::guild::create_guild_channel::CreateGuildChannel;
+use twilight_http::request::AuditLogReason;
+use twilight_model::channel::permission_overwrite::PermissionOverwrite;
+use twilight_model::id::{ChannelId, GuildId, RoleId};
+
+use super::rate_limits::is_rate_limited;
+use super::BotContext;
+use crate::core::channel_templates::ChannelTemplate;
+
+/// How often creating a channel gets tried while discord keeps telling us to slow down.
+const CREATE_ATTEMPTS: u32 = 3;
+/// How long to wait before trying again, this grows with every attempt.
+const CREATE_BACKOFF: Duration = Duration::from_secs(5);
+
+impl BotContext {
+    /// Creates a channel from a template, returning `None` if the template can't be turned into a valid request.
+    ///
+    /// Under rate limit pressure this waits a bit first so it doesn't add to the pile, and being rate limited
+    /// itself backs off and tries again.
+    pub async fn create_channel_from_template(
+        &self,
+        guild_id: GuildId,
+        template: &ChannelTemplate,
+        name: &str,
+        reason: &str,
+    ) -> Result<Option<ChannelId>, twilight_http::Error> {
+        let guild = self.cache.get_guild(&guild_id).await;
+        let (overwrites, parent_id) = match &guild {
+            Some(guild) => {
+                let roles = guild.roles.read().await;
+                let channels = guild.channels.read().await;
+                (
+                    template.usable_overwrites(|role_id: RoleId| roles.contains_key(&role_id)),
+                    template.parent_id.filter(|parent_id| channels.contains_key(parent_id)),
+                )
+            }
+            None => (template.permission_overwrites.clone(), template.parent_id),
+        };
+
+        let mut attempt = 1;
+        loop {
+            if self.rate_limits.is_slowed_down() {
+                tokio::time::sleep(CREATE_BACKOFF).await;
+            }
+            let request = match self.build_channel_request(guild_id, template, name, reason, &overwrites, parent_id) {
+                Some(request) => request,
+                None => return Ok(None),
+            };
+
+            match request.await {
+                Ok(channel) => return Ok(Some(channel.id())),
+                Err(e) => {
+                    self.track_http_error(&e).await;
+                    if !is_rate_limited(&e) || attempt >= CREATE_ATTEMPTS {
+                        return Err(e);
+                    }
+                    log::debug!(
+                        "Got rate limited creating a channel in {}, trying again in {}s",
+                        guild_id,
+                        (CREATE_BACKOFF * attempt).as_secs()
+                    );
+                    tokio::time::sleep(CREATE_BACKOFF * attempt).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn build_channel_request(
+        &self,
+        guild_id: GuildId,
+        template: &ChannelTemplate,
+        name: &str,
+        reason: &str,
+        overwrites: &[PermissionOverwrite],
+        parent_id: Option<ChannelId>,
+    ) -> Option<CreateGuildChannel<'_>> {
+        let mut request = match self.http.create_guild_channel(guild_id, name) {
+            Ok(request) => request
+                .kind(template.kind)
+                .nsfw(template.nsfw)
+                .position(template.position.max(0) as u64)
+                .permission_overwrites(overwrites.to_vec()),
+            Err(e) => {
+                log::debug!("Not creating a channel in {}, the name is invalid: {}", guild_id, e);
+                return None;
+            }
+        };
+        if let Some(parent_id) = parent_id {
+            request = request.parent_id(parent_id);
+        }
+        if let Some(topic) = &template.topic {
+            request = request.topic(topic.clone()).ok()?;
+        }
+        if let Some(slowmode) = template.slowmode.filter(|slowmode| *slowmode > 0) {
+            request = request.rate_limit_per_user(slowmode).ok()?;
+        }
+        if let Some(bitrate) = template.bitrate {
+            request = request.bitrate(bitrate);
+        }
+        if let Some(user_limit) = template.user_limit.filter(|user_limit| *user_limit > 0) {
+            request = request.user_limit(user_limit);
+        }
+
+        request.reason(reason).ok()
+    }
+}
@@ -30,6 +30,8 @@ impl BotContext {
         let redis_cache = &self.datastore.cache_pool;
 
         let resume_data = self.cluster.down_resumable();
+        // Nothing left to count, what was counted has to survive the restart
+        self.flush_guild_stats().await;
         let (guild_chunks, user_chunks) = self.cache.prepare_cold_resume(&redis_cache).await;
 
         // prepare resume data
@@ -42,7 +44,7 @@ impl BotContext {
             resume_data: map,
             total_shards: self.scheme_info.total_shards,
             guild_chunks,
-            shard_count: self.scheme_info.shards_per_cluster,
+            shard_count: self.scheme_info.shard_count(),
             user_chunks,
         };
 
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+use twilight_model::channel::Reaction;
+use twilight_model::id::{MessageId, UserId};
+
+use super::BotContext;
+use crate::utils::Emoji;
+
+/// A yes/no prompt that is waiting for the user it asked.
+pub struct PendingConfirmation {
+    user_id: UserId,
+    answer: oneshot::Sender<bool>,
+}
+
+impl BotContext {
+    /// Waits for the user to react to the prompt with yes or no, not answering in time counts as a no.
+    pub async fn await_confirmation(&self, prompt: MessageId, user_id: UserId, time_limit: Duration) -> bool {
+        let (answer, receiver) = oneshot::channel();
+        self.confirmations
+            .write()
+            .await
+            .insert(prompt, PendingConfirmation { user_id, answer });
+
+        match timeout(time_limit, receiver).await {
+            Ok(Ok(confirmed)) => confirmed,
+            _ => {
+                self.confirmations.write().await.remove(&prompt);
+                false
+            }
+        }
+    }
+
+    /// Answers the prompt the reaction was added to, if it's a yes or no from the user the prompt is waiting for.
+    ///
+    /// Returns if the reaction was an answer, those don't need any further processing.
+    pub async fn answer_confirmation(&self, reaction: &Reaction) -> bool {
+        let confirmed = if Emoji::Yes.matches(&reaction.emoji) {
+            true
+        } else if Emoji::No.matches(&reaction.emoji) {
+            false
+        } else {
+            return false;
+        };
+
+        let mut confirmations = self.confirmations.write().await;
+        let for_this_user = confirmations
+            .get(&reaction.message_id)
+            .map_or(false, |pending| pending.user_id == reaction.user_id);
+        if !for_this_user {
+            return false;
+        }
+
+        if let Some(pending) = confirmations.remove(&reaction.message_id) {
+            // The prompt might have just timed out, nothing left to tell then
+            let _ = pending.answer.send(confirmed);
+        }
+        true
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::Utc;
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, GuildId, MessageId, UserId};
+
+use super::BotContext;
+use crate::core::automod::AutomodAction;
+use crate::core::content_policy::{self, ContentPolicyConfig, ContentViolation};
+use crate::core::guild_stats::GuildStat;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::{GuildConfig, GuildFeatures};
+use crate::error::{DatabaseError, EventHandlerError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::Emoji;
+
+impl BotContext {
+    /// Checks the attachments and embeds of a new message against the content policy of its channel.
+    pub async fn run_content_policy(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        if message.attachments.is_empty() && message.embeds.is_empty() {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let policies = &config.content_policy;
+        if !config.features.contains(GuildFeatures::AUTOMOD) || !policies.is_enabled() {
+            return Ok(());
+        }
+        let policy = policies.policy_for(message.channel_id);
+        if self
+            .is_exempt_from_content_policy(guild_id, message.author.id, policies)
+            .await
+        {
+            return Ok(());
+        }
+
+        let violation = if policy.require_spoilers
+            && message
+                .attachments
+                .iter()
+                .any(|attachment| !content_policy::is_spoiler(&attachment.filename))
+        {
+            Some(ContentViolation::MissingSpoiler)
+        } else if policy.block_nsfw_embeds && self.has_nsfw_embed(message, policies).await {
+            Some(ContentViolation::NsfwEmbed)
+        } else {
+            None
+        };
+
+        match violation {
+            Some(violation) => self.enforce_content_policy(message, guild_id, &config, violation).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Links usually only get their embeds after the message was sent, so updates that add embeds are checked
+    /// again. Updates don't have the whole message, it's fetched only when the channel blocks nsfw embeds.
+    pub async fn run_embed_content_policy(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        guild_id: GuildId,
+    ) -> Result<(), EventHandlerError> {
+        if self.image_classifier.is_none() {
+            return Ok(());
+        }
+        let config = self.get_config(guild_id).await?;
+        let policies = &config.content_policy;
+        if !config.features.contains(GuildFeatures::AUTOMOD)
+            || !policies.policy_for(channel_id).block_nsfw_embeds
+            || self.is_excluded_from_snipe(message_id).await?
+        {
+            return Ok(());
+        }
+
+        let message = match self.http.message(channel_id, message_id).await {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!(
+                    "Failed to fetch {} to check its embeds in {}: {}",
+                    message_id,
+                    guild_id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+        if message.author.bot
+            || self
+                .is_exempt_from_content_policy(guild_id, message.author.id, policies)
+                .await
+        {
+            return Ok(());
+        }
+
+        if self.has_nsfw_embed(&message, policies).await {
+            self.enforce_content_policy(&message, guild_id, &config, ContentViolation::NsfwEmbed)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn is_exempt_from_content_policy(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        policies: &ContentPolicyConfig,
+    ) -> bool {
+        match self.cache.get_member(&guild_id, &user_id).await {
+            Some(member) => member.roles.iter().any(|role| policies.exempt_roles.contains(role)),
+            None => false,
+        }
+    }
+
+    /// Nsfw channels can have whatever embeds they want, and without a classifier there is no telling.
+    async fn has_nsfw_embed(&self, message: &Message, policies: &ContentPolicyConfig) -> bool {
+        let classifier = match &self.image_classifier {
+            Some(classifier) => classifier,
+            None => return false,
+        };
+        let images = content_policy::embed_images(&message.embeds);
+        if images.is_empty() {
+            return false;
+        }
+        if let Some(channel) = self.cache.get_channel(message.channel_id).await {
+            if channel.is_nsfw() {
+                return false;
+            }
+        }
+
+        for image in images {
+            match classifier.classify(image).await {
+                Ok(labels) if content_policy::is_nsfw(&labels, policies.nsfw_confidence) => return true,
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to classify an embed image in {}: {}", message.channel_id, e),
+            }
+        }
+        false
+    }
+
+    /// Removes the message, tells the author why and escalates if they keep doing it.
+    async fn enforce_content_policy(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        config: &GuildConfig,
+        violation: ContentViolation,
+    ) -> Result<(), EventHandlerError> {
+        let policies = &config.content_policy;
+        let reason = violation.to_string();
+        let mut deleted = false;
+        let mut taken = vec![];
+        if self
+            .try_automod_action(AutomodAction::Delete, message, guild_id, &reason, &mut deleted)
+            .await
+        {
+            taken.push(AutomodAction::Delete.to_string());
+        }
+
+        if policies.notify_author {
+            self.notify_content_violation(message, guild_id, config, violation)
+                .await;
+        }
+
+        if self
+            .content_policy_escalation_reached(guild_id, message.author.id, policies)
+            .await?
+        {
+            let action = policies.escalation_action;
+            if config.automod_review.holds_back(action) {
+                if self
+                    .queue_automod_review(action, message, guild_id, &reason, config)
+                    .await
+                {
+                    taken.push(format!("{} (awaiting review)", action));
+                }
+            } else if self
+                .try_automod_action(action, message, guild_id, &reason, &mut deleted)
+                .await
+            {
+                taken.push(action.to_string());
+            }
+        }
+
+        self.log(LogData {
+            log_type: LogType::AutomodTriggered {
+                rule: reason,
+                channel: message.channel_id,
+                content: message.content.clone(),
+                actions: taken,
+            },
+            guild: guild_id,
+            source_channel: Some(message.channel_id),
+            source_user: message.author.id,
+            timestamp: Utc::now(),
+        });
+        self.count_guild_stat(guild_id, GuildStat::AutomodTrigger).await;
+
+        Ok(())
+    }
+
+    /// DMs the author what was wrong with their message, they might not accept DMs from us.
+    async fn notify_content_violation(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        config: &GuildConfig,
+        violation: ContentViolation,
+    ) {
+        let key = match violation {
+            ContentViolation::MissingSpoiler => GearBotString::ContentPolicySpoilerDm,
+            ContentViolation::NsfwEmbed => GearBotString::ContentPolicyNsfwDm,
+        };
+        let args = FluArgs::with_capacity(3)
+            .add("gearno", Emoji::No.for_chat())
+            .add("guild", self.guild_name(guild_id).await)
+            .add("channel", message.channel_id.to_string())
+            .generate();
+        let content = self.translate_with_args(&config.language, key, &args);
+
+        let channel = match self.get_dm_for_user(message.author.id).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                self.track_http_error(&e).await;
+                return;
+            }
+        };
+        let request = match self.http.create_message(channel.get_id()).content(content) {
+            Ok(request) => request,
+            Err(e) => {
+                log::debug!("Content policy notice for {} is not a valid message: {}", guild_id, e);
+                return;
+            }
+        };
+        if let Err(e) = request.await {
+            self.track_http_error(&e).await;
+        }
+    }
+
+    /// Counts the violation, returning if the author reached the escalation threshold. Reaching it starts the
+    /// count over.
+    async fn content_policy_escalation_reached(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        policies: &ContentPolicyConfig,
+    ) -> Result<bool, DatabaseError> {
+        if policies.escalate_after == 0 {
+            return Ok(false);
+        }
+
+        // The window starts at the first violation, later ones don't push it back
+        let redis_cache = &self.datastore.cache_pool;
+        let key = format!("content_policy:{}:{}", guild_id, user_id);
+        let count = redis_cache.count_hit(&key, policies.escalation_window.max(1)).await?;
+        if count >= policies.escalate_after as isize {
+            redis_cache.delete(&key).await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -87,6 +87,11 @@ impl BotContext {
         Ok(())
     }
 
+    /// Forgets all cached configs, they get loaded from the database again when needed.
+    pub async fn clear_cached_configs(&self) {
+        self.configs.write().await.clear();
+    }
+
     pub async fn fetch_user_message(
         &self,
         message_id: MessageId,
@@ -95,6 +100,14 @@ impl BotContext {
         self.datastore.get_full_message(message_id, guild_id).await
     }
 
+    pub async fn fetch_user_messages(
+        &self,
+        message_ids: &[MessageId],
+        guild_id: GuildId,
+    ) -> Result<Vec<(MessageId, UserMessage)>, DatabaseError> {
+        self.datastore.get_full_messages(message_ids, guild_id).await
+    }
+
     pub async fn insert_message(&self, message: &Message, guild_id: GuildId) -> Result<(), DatabaseError> {
         // All guilds need to have a config before anything can happen thanks to encryption.
         let _ = self.get_config(guild_id).await?;
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::error::DatabaseError;
+use crate::{gearbot_error, gearbot_info};
+
+/// How long data is kept after leaving a guild, unless configured otherwise.
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+/// How often to check for guilds that are due to be purged.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+impl BotContext {
+    /// Schedules the data of a guild we left to be removed once the retention period is over.
+    pub async fn schedule_guild_purge(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        self.datastore
+            .schedule_guild_purge(guild_id, self.data_retention)
+            .await?;
+        log::info!(
+            "Left guild {}, its data will be removed in {} days",
+            guild_id,
+            self.data_retention.as_secs() / (60 * 60 * 24)
+        );
+        Ok(())
+    }
+
+    /// Keeps the data of a guild we got added back to.
+    pub async fn cancel_guild_purge(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        if self.datastore.cancel_guild_purge(guild_id).await? {
+            log::info!("Rejoined guild {}, cancelled the removal of its data", guild_id);
+        }
+        Ok(())
+    }
+
+    /// Periodically removes the data of guilds whose retention period ran out.
+    pub async fn run_guild_purges(&self) {
+        if !self.is_primary_cluster() {
+            return;
+        }
+
+        loop {
+            tokio::time::sleep(PURGE_INTERVAL).await;
+
+            let due = match self.datastore.get_due_guild_purges().await {
+                Ok(due) => due,
+                Err(e) => {
+                    gearbot_error!("Failed to fetch the guilds due for a data purge: {}", e);
+                    continue;
+                }
+            };
+            self.stats.scheduler_backlog.guild_purges.set(due.len() as i64);
+
+            for guild_id in due {
+                match self.datastore.purge_guild_data(guild_id).await {
+                    Ok(()) => {
+                        self.configs.write().await.remove(&guild_id);
+                        gearbot_info!("Removed all data of guild {}", guild_id);
+                    }
+                    Err(e) => gearbot_error!("Failed to remove the data of guild {}: {}", guild_id, e),
+                }
+            }
+        }
+    }
+}
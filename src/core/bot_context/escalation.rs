@@ -0,0 +1,146 @@
+use chrono::{Duration, Utc};
+use twilight_model::guild::audit_log::AuditLogEvent;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::escalation::PermissionGrant;
+use crate::core::logpump::{LogData, LogType};
+use crate::error::EventHandlerError;
+use crate::utils;
+
+/// Audit log entries older than this are about an earlier change of the same role or member.
+const ATTRIBUTION_WINDOW_SECONDS: i64 = 60;
+/// Audit log entries often show up a moment after the change itself, so we look again once after this.
+const ATTRIBUTION_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+
+impl BotContext {
+    /// Logs who handed out dangerous permissions, and takes them away again if that wasn't a trusted admin and the
+    /// guild wants that. Grants we can't find the author of are never taken away.
+    pub async fn review_permission_grant(
+        &self,
+        guild_id: GuildId,
+        grant: PermissionGrant,
+        granted: Permissions,
+    ) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        let escalation = &config.escalation;
+        if !escalation.enabled {
+            return Ok(());
+        }
+
+        let executor = match self.find_grant_executor(guild_id, &grant).await {
+            Some(executor) => Some(executor),
+            None => {
+                tokio::time::sleep(ATTRIBUTION_RETRY_DELAY).await;
+                self.find_grant_executor(guild_id, &grant).await
+            }
+        };
+        // Changes we make ourselves were asked for by someone that was allowed to
+        if executor == Some(self.bot_user.id) {
+            return Ok(());
+        }
+        let owner = self.cache.get_guild(&guild_id).await.map(|guild| guild.owner_id);
+        let untrusted = executor.map_or(false, |executor| {
+            Some(executor) != owner && !escalation.trusted_admins.contains(&executor)
+        });
+
+        let reverted = if escalation.revert && untrusted {
+            Some(self.revert_grant(guild_id, &grant).await)
+        } else {
+            None
+        };
+
+        let (log_type, source_user) = match grant {
+            PermissionGrant::Role { role_id, .. } => (
+                LogType::RolePermissionsGranted {
+                    role: role_id,
+                    permissions: granted,
+                    executor,
+                    reverted,
+                },
+                executor.unwrap_or(self.bot_user.id),
+            ),
+            PermissionGrant::Member { user_id, roles } => (
+                LogType::MemberPermissionsGranted {
+                    roles,
+                    permissions: granted,
+                    executor,
+                    reverted,
+                },
+                user_id,
+            ),
+        };
+        self.log(LogData {
+            log_type,
+            guild: guild_id,
+            source_channel: None,
+            source_user,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    /// Who made the change according to the audit log, if we can read it and it's in there already.
+    async fn find_grant_executor(&self, guild_id: GuildId, grant: &PermissionGrant) -> Option<UserId> {
+        let (event, target) = match grant {
+            PermissionGrant::Role { role_id, .. } => (AuditLogEvent::RoleUpdate, role_id.to_string()),
+            PermissionGrant::Member { user_id, .. } => (AuditLogEvent::MemberRoleUpdate, user_id.to_string()),
+        };
+        let audit_log = self
+            .http
+            .audit_log(guild_id)
+            .action_type(event)
+            .limit(10)
+            .ok()?
+            .await
+            .ok()??;
+
+        let cutoff = Utc::now() - Duration::seconds(ATTRIBUTION_WINDOW_SECONDS);
+        audit_log
+            .entries
+            .iter()
+            .find(|entry| entry.target_id.as_ref().map(|id| id.to_string()) == Some(target.clone()))
+            .filter(|entry| utils::snowflake_timestamp(entry.id.0) > cutoff)
+            .and_then(|entry| entry.user_id)
+    }
+
+    /// Puts the old permissions back or takes the roles away again, returning if that worked.
+    async fn revert_grant(&self, guild_id: GuildId, grant: &PermissionGrant) -> bool {
+        match grant {
+            PermissionGrant::Role {
+                role_id,
+                old_permissions,
+            } => match self
+                .http
+                .update_role(guild_id, *role_id)
+                .permissions(*old_permissions)
+                .await
+            {
+                Ok(_) => true,
+                Err(e) => {
+                    self.track_http_error(&e).await;
+                    log::debug!("Failed to revert the permissions of {} in {}: {}", role_id, guild_id, e);
+                    false
+                }
+            },
+            PermissionGrant::Member { user_id, roles } => {
+                let mut reverted = true;
+                for role_id in roles {
+                    if let Err(e) = self.remove_member_role(guild_id, *user_id, *role_id, None).await {
+                        log::debug!(
+                            "Failed to take {} away from {} in {}: {}",
+                            role_id,
+                            user_id,
+                            guild_id,
+                            e
+                        );
+                        reverted = false;
+                    }
+                }
+                reverted
+            }
+        }
+    }
+}
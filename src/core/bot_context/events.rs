@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use twilight_model::channel::embed::Embed;
+use twilight_model::channel::message::allowed_mentions::AllowedMentionsBuilder;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::core::events::{mention_chunks, RsvpStatus};
+use crate::core::GuildConfig;
+use crate::database::structures::{StoredEvent, StoredRsvp};
+use crate::error::{DatabaseError, MessageError};
+use crate::gearbot_error;
+use crate::translation::{format_zoned_datetime, FluArgs, GearBotString};
+use crate::utils::embeds::{SafeEmbed, FIELD_VALUE_LIMIT};
+use crate::utils::Emoji;
+
+/// How often is checked for reminders that are due, they go out at most this long after they should.
+const EVENT_REMINDER_INTERVAL: Duration = Duration::from_secs(60);
+
+const MESSAGE_LIMIT: usize = 2000;
+
+impl BotContext {
+    /// The announcement of an event, with the start in the timezone of the guild and who is coming.
+    pub fn event_embed(
+        &self,
+        config: &GuildConfig,
+        event: &StoredEvent,
+        rsvps: &[StoredRsvp],
+    ) -> Result<Embed, MessageError> {
+        let lang = &config.language;
+        let start = format!(
+            "{} (<t:{}:R>)",
+            format_zoned_datetime(config.tz().timestamp(event.starts_at, 0), lang),
+            event.starts_at
+        );
+        let mut embed = SafeEmbed::new()
+            .title(event.title.clone())
+            .field(self.translate(lang, GearBotString::EventStartsField), start);
+        if let Some(description) = &event.description {
+            embed = embed.description(description.clone());
+        }
+
+        for status in RsvpStatus::ALL.iter() {
+            let users: Vec<UserId> = rsvps
+                .iter()
+                .filter(|rsvp| RsvpStatus::from_str(&rsvp.status) == Some(*status))
+                .map(|rsvp| UserId(rsvp.user_id as u64))
+                .collect();
+            let key = match status {
+                RsvpStatus::Going => GearBotString::EventGoingField,
+                RsvpStatus::Maybe => GearBotString::EventMaybeField,
+                RsvpStatus::Declined => GearBotString::EventDeclinedField,
+            };
+            let name = self.translate_with_args(
+                lang,
+                key,
+                &FluArgs::with_capacity(1).add("count", users.len()).generate(),
+            );
+            // The count in the name stays right when not everyone fits
+            let value = match mention_chunks(&users, FIELD_VALUE_LIMIT).into_iter().next() {
+                Some(mentions) => mentions,
+                None => self.translate(lang, GearBotString::EventNobody),
+            };
+            embed = embed.inline_field(name, value);
+        }
+
+        let footer = self.translate_with_args(
+            lang,
+            GearBotString::EventFooter,
+            &FluArgs::with_capacity(1).add("id", event.id).generate(),
+        );
+        // Announcements always fit in a single embed, and build always gives at least one
+        Ok(embed.footer(footer).build()?.remove(0))
+    }
+
+    /// Periodically pings the members that are coming to events that are about to start.
+    ///
+    /// Every cluster only handles its own guilds, like the birthday announcer.
+    pub async fn run_event_reminders(&self) {
+        loop {
+            tokio::time::sleep(EVENT_REMINDER_INTERVAL).await;
+
+            let events = match self.datastore.get_due_event_reminders().await {
+                Ok(events) => events,
+                Err(e) => {
+                    gearbot_error!("Failed to fetch the due event reminders: {}", e);
+                    continue;
+                }
+            };
+
+            for event in events {
+                let guild_id = GuildId(event.guild_id as u64);
+                if self.cache.get_guild(&guild_id).await.is_none() {
+                    continue;
+                }
+                if let Err(e) = self.send_event_reminder(guild_id, &event).await {
+                    gearbot_error!(
+                        "Failed to send the reminder for event {} of guild {}: {}",
+                        event.id,
+                        guild_id,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Pings the members in the channel the event was announced in. The reminder is cleared first so one that fails
+    /// to send doesn't ping everyone again on the next run.
+    async fn send_event_reminder(&self, guild_id: GuildId, event: &StoredEvent) -> Result<(), DatabaseError> {
+        self.datastore.clear_event_reminder(guild_id, event.id).await?;
+        // Missed while we were offline, too late to remind anyone now
+        if event.starts_at <= Utc::now().timestamp() {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let users: Vec<UserId> = self
+            .datastore
+            .get_rsvps(guild_id, event.id)
+            .await?
+            .iter()
+            .filter(|rsvp| RsvpStatus::from_str(&rsvp.status).map_or(false, |status| config.events.reminds(status)))
+            .map(|rsvp| UserId(rsvp.user_id as u64))
+            .collect();
+        if users.is_empty() {
+            return Ok(());
+        }
+
+        let args = FluArgs::with_capacity(3)
+            .add("emoji", Emoji::Info.for_chat())
+            .add("title", event.title.clone())
+            .add("start", format!("<t:{}:R>", event.starts_at))
+            .generate();
+        let header = self.translate_with_args(&config.language, GearBotString::EventReminder, &args);
+        let channel_id = ChannelId(event.channel_id as u64);
+        // Titles are short enough that this leaves plenty of room for the mentions
+        let limit = MESSAGE_LIMIT - header.len() - 1;
+        let mut header = Some(header);
+        for mentions in mention_chunks(&users, limit) {
+            // Nothing gets pinged by default, and there is a limit on how many users can be allowed at once
+            let pinged: Vec<UserId> = users
+                .iter()
+                .copied()
+                .filter(|user| mentions.contains(&format!("<@{}>", user)))
+                .collect();
+            let content = match header.take() {
+                Some(header) => format!("{}\n{}", header, mentions),
+                None => mentions,
+            };
+            let request = match self.http.create_message(channel_id).content(content) {
+                Ok(request) => request.allowed_mentions(AllowedMentionsBuilder::new().user_ids(pinged).build()),
+                Err(e) => {
+                    log::debug!(
+                        "Reminder for event {} in {} is not a valid message: {}",
+                        event.id,
+                        guild_id,
+                        e
+                    );
+                    return Ok(());
+                }
+            };
+            if let Err(e) = request.await {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to remind about event {} in {}: {}", event.id, guild_id, e);
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use twilight_model::channel::embed::Embed;
+use twilight_model::id::{ChannelId, GuildId};
+use url::Url;
+
+use super::BotContext;
+use crate::core::feeds::{FeedEntry, MAX_POSTS_PER_POLL};
+use crate::database::structures::StoredFeed;
+use crate::error::{DatabaseError, MessageError};
+use crate::gearbot_error;
+use crate::utils::embeds::SafeEmbed;
+
+/// How often is checked for feeds that are due, every feed has its own interval on top of this.
+const FEED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+impl BotContext {
+    /// A new entry of a feed, with the template of the feed as description.
+    pub fn feed_entry_embed(&self, feed: &StoredFeed, entry: &FeedEntry) -> Result<Embed, MessageError> {
+        let feed_title = feed.title.as_deref().unwrap_or(&feed.url);
+        let mut embed = SafeEmbed::new().footer(feed_title);
+        if let Some(title) = entry.title.as_ref().or_else(|| entry.link.as_ref()) {
+            embed = embed.title(title.clone());
+        }
+        // Discord refuses the whole embed over a link it doesn't like
+        if let Some(link) = &entry.link {
+            if Url::parse(link).map_or(false, |url| matches!(url.scheme(), "http" | "https")) {
+                embed = embed.url(link.clone());
+            }
+        }
+        let description = entry.render(&feed.template, feed_title);
+        if !description.is_empty() {
+            embed = embed.description(description);
+        }
+        if let Some(published) = entry.published {
+            embed = embed.timestamp(published.to_rfc3339());
+        }
+
+        // Templates and summaries are limited so entries fit in one embed, and build always gives at least one
+        Ok(embed.build()?.remove(0))
+    }
+
+    /// Periodically polls the feeds that are due and posts whatever is new in them.
+    ///
+    /// Every cluster only handles its own guilds, like the birthday announcer.
+    pub async fn run_feed_poller(&self) {
+        loop {
+            tokio::time::sleep(FEED_POLL_INTERVAL).await;
+
+            let feeds = match self.datastore.get_due_feeds().await {
+                Ok(feeds) => feeds,
+                Err(e) => {
+                    gearbot_error!("Failed to fetch the due feeds: {}", e);
+                    continue;
+                }
+            };
+
+            for feed in feeds {
+                let guild_id = GuildId(feed.guild_id as u64);
+                if self.cache.get_guild(&guild_id).await.is_none() {
+                    continue;
+                }
+                if let Err(e) = self.poll_feed(guild_id, &feed).await {
+                    gearbot_error!("Failed to poll feed {} of guild {}: {}", feed.id, guild_id, e);
+                }
+            }
+        }
+    }
+
+    /// Posts the entries that weren't seen before, oldest first. They are marked as seen before posting, an entry
+    /// that fails to post is skipped instead of being tried again on every poll.
+    async fn poll_feed(&self, guild_id: GuildId, feed: &StoredFeed) -> Result<(), DatabaseError> {
+        // Scheduled first, feeds that are slow or broken are only tried again once their interval passed
+        self.datastore.schedule_feed_poll(guild_id, feed.id).await?;
+
+        let fetched = match self.feed_reader.fetch(&feed.url).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                log::debug!("Failed to poll feed {} of {}: {}", feed.id, guild_id, e);
+                return Ok(());
+            }
+        };
+
+        let current: Vec<String> = fetched.entries.iter().map(|entry| entry.id.clone()).collect();
+        let seen: HashSet<String> = self
+            .datastore
+            .get_seen_feed_entries(guild_id, feed.id, &current)
+            .await?
+            .into_iter()
+            .collect();
+        self.datastore.prune_feed_entries(guild_id, feed.id, &current).await?;
+
+        let mut new_ids = HashSet::new();
+        let new: Vec<&FeedEntry> = fetched
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| !seen.contains(&entry.id) && new_ids.insert(entry.id.clone()))
+            .collect();
+        if new.is_empty() {
+            return Ok(());
+        }
+        let new_ids: Vec<String> = new_ids.into_iter().collect();
+        self.datastore
+            .mark_feed_entries_seen(guild_id, feed.id, &new_ids)
+            .await?;
+
+        let channel_id = ChannelId(feed.channel_id as u64);
+        let skipped = new.len().saturating_sub(MAX_POSTS_PER_POLL);
+        for entry in new.into_iter().skip(skipped) {
+            let embed = match self.feed_entry_embed(feed, entry) {
+                Ok(embed) => embed,
+                Err(e) => {
+                    log::debug!(
+                        "Entry {} of feed {} in {} is not a valid embed: {}",
+                        entry.id,
+                        feed.id,
+                        guild_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let request = match self.http.create_message(channel_id).embed(embed) {
+                Ok(request) => request,
+                Err(e) => {
+                    log::debug!(
+                        "Entry {} of feed {} in {} is not a valid message: {}",
+                        entry.id,
+                        feed.id,
+                        guild_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            if let Err(e) = request.await {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to post feed {} in {}: {}", feed.id, guild_id, e);
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use twilight_model::channel::embed::Embed;
+use twilight_model::id::{ChannelId, GuildId};
+use unic_langid::LanguageIdentifier;
+
+use super::BotContext;
+use crate::core::github::{
+    normalize_repository, verify_signature, DeliveryHeader, GithubDelivery, GithubEvent, BODY_LIMIT, MAX_LISTED_COMMITS,
+};
+use crate::core::webhook_server::Delivery;
+use crate::error::{DatabaseError, MessageError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::{truncate, SafeEmbed};
+
+const PUSH_COLOR: u32 = 0x00_2188ff;
+const OPENED_COLOR: u32 = 0x00_28a745;
+const CLOSED_COLOR: u32 = 0x00_cb2431;
+
+impl BotContext {
+    /// Relays a delivery from GitHub to every guild that follows the repository, if it is signed with their secret.
+    ///
+    /// Posting doesn't go through the gateway, so this cluster handles the guilds of all clusters.
+    pub async fn receive_github_webhook(
+        &self,
+        event: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> Result<Delivery, DatabaseError> {
+        let header: DeliveryHeader = match serde_json::from_slice(body) {
+            Ok(header) => header,
+            Err(_) => return Ok(Delivery::Malformed),
+        };
+        let repository = match header
+            .repository
+            .and_then(|repository| normalize_repository(&repository.full_name))
+        {
+            Some(repository) => repository,
+            None => return Ok(Delivery::Ignored),
+        };
+
+        let subscriptions: Vec<_> = self
+            .datastore
+            .get_github_subscriptions(&repository)
+            .await?
+            .into_iter()
+            .filter(|subscription| verify_signature(&subscription.secret, body, signature))
+            .collect();
+        if subscriptions.is_empty() {
+            return Ok(Delivery::Unauthorized);
+        }
+
+        // Sent when the webhook is created, to see if it works
+        if event == "ping" {
+            return Ok(Delivery::Relayed);
+        }
+        let event = match GithubEvent::from_str(event) {
+            Some(event) => event,
+            None => return Ok(Delivery::Ignored),
+        };
+        let delivery = match GithubDelivery::parse(event, body) {
+            Ok(Some(delivery)) => delivery,
+            Ok(None) => return Ok(Delivery::Ignored),
+            Err(_) => return Ok(Delivery::Malformed),
+        };
+
+        let mut relayed = false;
+        for subscription in subscriptions {
+            if !subscription.events.iter().any(|name| name == event.as_str()) {
+                continue;
+            }
+
+            let guild_id = GuildId(subscription.guild_id as u64);
+            let language = if self.cache.get_guild(&guild_id).await.is_some() {
+                self.get_config(guild_id).await?.language.clone()
+            } else {
+                // Not ours, not worth keeping the config of around
+                match self.datastore.get_guild_config(guild_id.0).await? {
+                    Some(config) => config.language,
+                    None => continue,
+                }
+            };
+
+            let embed = match self.github_embed(&language, &repository, &delivery) {
+                Ok(embed) => embed,
+                Err(e) => {
+                    log::debug!(
+                        "GitHub delivery for {} in {} is not a valid embed: {}",
+                        repository,
+                        guild_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let request = match self
+                .http
+                .create_message(ChannelId(subscription.channel_id as u64))
+                .embed(embed)
+            {
+                Ok(request) => request,
+                Err(e) => {
+                    log::debug!(
+                        "GitHub delivery for {} in {} is not a valid message: {}",
+                        repository,
+                        guild_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            match request.await {
+                Ok(_) => relayed = true,
+                Err(e) => {
+                    self.track_http_error(&e).await;
+                    log::debug!(
+                        "Failed to relay GitHub delivery for {} in {}: {}",
+                        repository,
+                        guild_id,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(if relayed { Delivery::Relayed } else { Delivery::Ignored })
+    }
+
+    fn github_embed(
+        &self,
+        language: &LanguageIdentifier,
+        repository: &str,
+        delivery: &GithubDelivery,
+    ) -> Result<Embed, MessageError> {
+        let mut embed = match delivery {
+            GithubDelivery::Push(push) => {
+                let args = FluArgs::with_capacity(3)
+                    .add("repository", repository.to_string())
+                    .add("branch", push.branch().to_string())
+                    .add("count", push.commits.len())
+                    .generate();
+                let mut description = push.commit_lines().join("\n");
+                if push.commits.len() > MAX_LISTED_COMMITS {
+                    let args = FluArgs::with_capacity(1)
+                        .add("count", push.commits.len() - MAX_LISTED_COMMITS)
+                        .generate();
+                    description += "\n";
+                    description += &self.translate_with_args(language, GearBotString::GithubPushMore, &args);
+                }
+                SafeEmbed::new()
+                    .title(self.translate_with_args(language, GearBotString::GithubPushTitle, &args))
+                    .url(push.compare.clone())
+                    .description(description)
+                    .color(PUSH_COLOR)
+            }
+            GithubDelivery::Release(release) => {
+                let name = release.release.name.clone().filter(|name| !name.is_empty());
+                let args = FluArgs::with_capacity(2)
+                    .add("repository", repository.to_string())
+                    .add("name", name.unwrap_or_else(|| release.release.tag_name.clone()))
+                    .generate();
+                let mut embed = SafeEmbed::new()
+                    .title(self.translate_with_args(language, GearBotString::GithubReleaseTitle, &args))
+                    .url(release.release.html_url.clone())
+                    .color(OPENED_COLOR);
+                if let Some(body) = release.release.body.as_deref().filter(|body| !body.is_empty()) {
+                    embed = embed.description(truncate(body, BODY_LIMIT));
+                }
+                embed
+            }
+            GithubDelivery::Issues(issues) => {
+                let (key, color) = match issues.action.as_str() {
+                    "closed" => (GearBotString::GithubIssueClosed, CLOSED_COLOR),
+                    "reopened" => (GearBotString::GithubIssueReopened, OPENED_COLOR),
+                    _ => (GearBotString::GithubIssueOpened, OPENED_COLOR),
+                };
+                let args = FluArgs::with_capacity(3)
+                    .add("repository", repository.to_string())
+                    .add("number", issues.issue.number)
+                    .add("title", issues.issue.title.clone())
+                    .generate();
+                let mut embed = SafeEmbed::new()
+                    .title(self.translate_with_args(language, key, &args))
+                    .url(issues.issue.html_url.clone())
+                    .color(color);
+                // Closing doesn't change the description, no need to repeat it
+                if issues.action != "closed" {
+                    if let Some(body) = issues.issue.body.as_deref().filter(|body| !body.is_empty()) {
+                        embed = embed.description(truncate(body, BODY_LIMIT));
+                    }
+                }
+                embed
+            }
+        };
+        if let Some(sender) = delivery.sender() {
+            embed = embed.author(sender.login.clone(), sender.avatar_url.clone());
+        }
+
+        // Bodies are truncated and commits limited so it all fits in one embed, and build always gives at least one
+        Ok(embed.build()?.remove(0))
+    }
+}
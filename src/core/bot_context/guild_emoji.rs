@@ -0,0 +1,53 @@
+use chrono::{Duration, Utc};
+use twilight_model::guild::audit_log::AuditLogEvent;
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::guild_emoji::EmojiChange;
+use crate::core::logpump::{LogData, LogType};
+use crate::utils;
+
+/// Audit log entries older than this are about an earlier change of the same emoji.
+const ATTRIBUTION_WINDOW_SECONDS: i64 = 60;
+
+impl BotContext {
+    /// Logs changes to the emoji of a guild, as done by whoever the audit log says did them.
+    pub async fn log_emoji_changes(&self, guild_id: GuildId, changes: Vec<EmojiChange>) {
+        for change in changes {
+            let executor = self.find_emoji_executor(guild_id, &change).await;
+            self.log(LogData {
+                log_type: LogType::EmojiChanged { change },
+                guild: guild_id,
+                source_channel: None,
+                source_user: executor.unwrap_or(self.bot_user.id),
+                timestamp: Utc::now(),
+            });
+        }
+    }
+
+    /// Who made the change according to the audit log, if we can read it and it's in there already.
+    async fn find_emoji_executor(&self, guild_id: GuildId, change: &EmojiChange) -> Option<UserId> {
+        let event = match change {
+            EmojiChange::Created { .. } => AuditLogEvent::EmojiCreate,
+            EmojiChange::Deleted { .. } => AuditLogEvent::EmojiDelete,
+            EmojiChange::Renamed { .. } => AuditLogEvent::EmojiUpdate,
+        };
+        let audit_log = self
+            .http
+            .audit_log(guild_id)
+            .action_type(event)
+            .limit(10)
+            .ok()?
+            .await
+            .ok()??;
+
+        let cutoff = Utc::now() - Duration::seconds(ATTRIBUTION_WINDOW_SECONDS);
+        let target = change.id().to_string();
+        audit_log
+            .entries
+            .iter()
+            .find(|entry| entry.target_id.as_ref() == Some(&target))
+            .filter(|entry| utils::snowflake_timestamp(entry.id.0) > cutoff)
+            .and_then(|entry| entry.user_id)
+    }
+}
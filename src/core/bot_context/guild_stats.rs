@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Utc;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::BotContext;
+use crate::core::guild_stats::{self, GuildStat};
+use crate::gearbot_error;
+
+/// How often the counts are written to the database, a restart without a cold resume loses at most this much.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+impl BotContext {
+    pub async fn count_guild_stat(&self, guild_id: GuildId, stat: GuildStat) {
+        self.guild_stats.lock().await.entry(guild_id).or_default().add(stat);
+    }
+
+    /// Counts a message towards the activity of its channel during the current hour.
+    pub async fn count_channel_message(&self, guild_id: GuildId, channel_id: ChannelId) {
+        let hour = guild_stats::hour_bucket(Utc::now().timestamp());
+        let mut activity = self.channel_activity.lock().await;
+        let count = activity.entry((guild_id, channel_id, hour)).or_insert(0);
+        *count = count.saturating_add(1);
+    }
+
+    /// Counts a use of a command, by its full name like ``config validate``.
+    pub async fn count_command_use(&self, guild_id: GuildId, channel_id: ChannelId, command: &str, succeeded: bool) {
+        let mut usage = self.command_usage.lock().await;
+        usage
+            .entry((guild_id, channel_id, command.to_string()))
+            .or_default()
+            .add(succeeded);
+    }
+
+    /// Periodically writes the counts to the database, every cluster writes the ones of its own guilds.
+    pub async fn run_stats_collector(&self) {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            self.flush_guild_stats().await;
+        }
+    }
+
+    /// Adds everything counted since the last flush to the buckets of today, and of the hours the messages were
+    /// sent in.
+    pub async fn flush_guild_stats(&self) {
+        let counts = std::mem::replace(&mut *self.guild_stats.lock().await, HashMap::new());
+        for (guild_id, counts) in counts {
+            if let Err(e) = self.datastore.add_guild_stats(guild_id, &counts).await {
+                gearbot_error!("Failed to write the statistics of guild {}: {}", guild_id, e);
+            }
+        }
+
+        let activity = std::mem::replace(&mut *self.channel_activity.lock().await, HashMap::new());
+        for ((guild_id, channel_id, hour), messages) in activity {
+            if let Err(e) = self
+                .datastore
+                .add_channel_activity(guild_id, channel_id, hour, messages)
+                .await
+            {
+                gearbot_error!(
+                    "Failed to write the activity of channel {} in {}: {}",
+                    channel_id,
+                    guild_id,
+                    e
+                );
+            }
+        }
+
+        let usage = std::mem::replace(&mut *self.command_usage.lock().await, HashMap::new());
+        for ((guild_id, channel_id, command), counts) in usage {
+            if let Err(e) = self
+                .datastore
+                .add_command_usage(guild_id, channel_id, &command, counts)
+                .await
+            {
+                gearbot_error!("Failed to write the uses of {} in {}: {}", command, guild_id, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use super::{BotContext, ShardState};
+use crate::cache::GuildState;
+
+/// How often the database connection gets checked.
+const DATABASE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Where the cluster is in its lifecycle.
+///
+/// Once ready it never goes back to starting or loading, losing a shard or the database afterwards makes it degraded
+/// until that recovers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    /// Not all shards are connected yet.
+    Starting,
+    /// All shards are up but guilds are still being cached.
+    Loading,
+    Ready,
+    Degraded,
+}
+
+impl HealthState {
+    /// Works out the next state from what the components look like right now.
+    pub fn next(self, shards_ready: bool, database: bool, loaded: bool) -> HealthState {
+        let up = shards_ready && database && loaded;
+        match self {
+            HealthState::Ready | HealthState::Degraded if up => HealthState::Ready,
+            HealthState::Ready | HealthState::Degraded => HealthState::Degraded,
+            _ if up => HealthState::Ready,
+            _ if shards_ready => HealthState::Loading,
+            _ => HealthState::Starting,
+        }
+    }
+
+    /// Commands are held off until the cluster has been ready once, after that degraded is still good enough.
+    pub fn accepts_commands(self) -> bool {
+        matches!(self, HealthState::Ready | HealthState::Degraded)
+    }
+}
+
+pub struct Health {
+    state: RwLock<HealthState>,
+    database: AtomicBool,
+}
+
+impl Health {
+    pub fn new() -> Self {
+        Health {
+            state: RwLock::new(HealthState::Starting),
+            database: AtomicBool::new(true),
+        }
+    }
+
+    pub async fn state(&self) -> HealthState {
+        *self.state.read().await
+    }
+}
+
+/// A snapshot of the parts the bot can't function without.
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub state: HealthState,
+    pub shards_ready: usize,
+    pub shards_total: usize,
+    pub database: bool,
+    pub guilds_loaded: usize,
+    pub guilds_loading: usize,
+    pub guilds_in_outage: usize,
+}
+
+impl HealthReport {
+    /// If everything the cluster needs is up, regardless of the state it's in.
+    pub fn is_healthy(&self) -> bool {
+        self.database && self.shards_ready == self.shards_total
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.state == HealthState::Ready
+    }
+}
+
+impl BotContext {
+    /// Re-evaluates the health state, should be called whenever one of the components changes.
+    pub async fn update_health(&self) -> HealthReport {
+        let shards = self.shard_states.read().await;
+        let shards_ready = shards.values().filter(|state| **state == ShardState::Ready).count();
+        let shards_total = shards.len();
+        drop(shards);
+
+        let database = self.health.database.load(Ordering::SeqCst);
+        let loading = self.cache.loading.read().await;
+        let guilds_loaded = loading.count(GuildState::Loaded);
+        let guilds_loading = loading.count(GuildState::Expected) + loading.count(GuildState::Partial);
+        let guilds_in_outage = loading.count(GuildState::Outage);
+        let loaded = loading.is_done();
+        drop(loading);
+
+        let mut state = self.health.state.write().await;
+        let next = state.next(shards_ready == shards_total, database, loaded);
+        if next != *state {
+            log::info!("Cluster health went from {:?} to {:?}", *state, next);
+            *state = next;
+        }
+
+        HealthReport {
+            state: next,
+            shards_ready,
+            shards_total,
+            database,
+            guilds_loaded,
+            guilds_loading,
+            guilds_in_outage,
+        }
+    }
+
+    /// Keeps checking if the database is still reachable, and if the rate limit pressure has gone down.
+    pub async fn run_health_checks(&self) {
+        loop {
+            let reachable = match self.datastore.ping().await {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Health check failed to reach the database: {}", e);
+                    false
+                }
+            };
+            self.health.database.store(reachable, Ordering::SeqCst);
+            self.update_health().await;
+            self.update_rate_limit_pressure().await;
+
+            tokio::time::sleep(DATABASE_CHECK_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HealthState;
+
+    #[test]
+    fn goes_through_startup() {
+        let state = HealthState::Starting.next(false, true, false);
+        assert_eq!(state, HealthState::Starting);
+        let state = state.next(true, true, false);
+        assert_eq!(state, HealthState::Loading);
+        let state = state.next(true, true, true);
+        assert_eq!(state, HealthState::Ready);
+    }
+
+    #[test]
+    fn degrades_instead_of_restarting() {
+        let state = HealthState::Ready.next(false, true, true);
+        assert_eq!(state, HealthState::Degraded);
+        assert!(state.accepts_commands());
+        assert_eq!(state.next(true, true, true), HealthState::Ready);
+        assert!(!HealthState::Loading.accepts_commands());
+    }
+}
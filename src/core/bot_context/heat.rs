@@ -0,0 +1,54 @@
+use chrono::Utc;
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::heat::{HeatConfig, HeatState};
+use crate::error::DatabaseError;
+
+fn heat_key(guild_id: GuildId, user_id: UserId) -> String {
+    format!("heat:{}:{}", guild_id, user_id)
+}
+
+impl BotContext {
+    /// The current heat of a user, with the decay since it last changed taken into account.
+    pub async fn get_heat(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        config: &HeatConfig,
+    ) -> Result<f64, DatabaseError> {
+        let state: Option<HeatState> = self.datastore.cache_pool.get(&heat_key(guild_id, user_id)).await?;
+        Ok(state.map_or(0.0, |state| {
+            state.current(Utc::now().timestamp(), config.decay_per_minute)
+        }))
+    }
+
+    /// Adds heat to a user, returning their heat from before and after.
+    pub async fn add_heat(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        amount: u32,
+        config: &HeatConfig,
+    ) -> Result<(f64, f64), DatabaseError> {
+        let before = self.get_heat(guild_id, user_id, config).await?;
+        let state = HeatState {
+            heat: before + amount as f64,
+            updated: Utc::now().timestamp(),
+        };
+
+        // No need to keep it around once it's all gone
+        let expiry = state.seconds_left(config.decay_per_minute);
+        self.datastore
+            .cache_pool
+            .set(&heat_key(guild_id, user_id), &state, expiry)
+            .await?;
+
+        Ok((before, state.heat))
+    }
+
+    pub async fn reset_heat(&self, guild_id: GuildId, user_id: UserId) -> Result<(), DatabaseError> {
+        self.datastore.cache_pool.delete(&heat_key(guild_id, user_id)).await?;
+        Ok(())
+    }
+}
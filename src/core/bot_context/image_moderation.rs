@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+use twilight_model::channel::Message;
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::core::guild_stats::GuildStat;
+use crate::core::image_moderation;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::GuildFeatures;
+use crate::error::EventHandlerError;
+
+/// Images after these in a message aren't read, every one of them is a call to the OCR service.
+const MAX_SCANNED_IMAGES: usize = 4;
+
+impl BotContext {
+    /// Sends the images of a new message to the moderation API, and acts on the labels the guild has a policy for.
+    ///
+    /// The actions are the same as the automod rules have, and go through review the same way.
+    pub async fn run_image_moderation(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        let classifier = match &self.image_classifier {
+            Some(classifier) => classifier,
+            None => return Ok(()),
+        };
+        let images = message
+            .attachments
+            .iter()
+            .filter(|attachment| image_moderation::is_image(&attachment.filename))
+            .collect::<Vec<_>>();
+        if images.is_empty() {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let policies = &config.image_moderation;
+        if !config.features.contains(GuildFeatures::AUTOMOD) || !policies.is_enabled() {
+            return Ok(());
+        }
+        if let Some(member) = self.cache.get_member(&guild_id, &message.author.id).await {
+            if member.roles.iter().any(|role| policies.exempt_roles.contains(role)) {
+                return Ok(());
+            }
+        }
+
+        let mut deleted = false;
+        // Every action is only taken once per message, no matter how many images or labels call for it
+        let mut handled = BTreeSet::new();
+        for image in images {
+            let labels = match classifier.classify(&image.url).await {
+                Ok(labels) => labels,
+                Err(e) => {
+                    log::warn!("Failed to classify an image in {}: {}", guild_id, e);
+                    continue;
+                }
+            };
+
+            for (policy, label) in policies.triggered(&labels) {
+                let reason = format!("image: {} ({:.0}%)", label.name, label.confidence * 100.0);
+                let mut taken = vec![];
+                for action in policy.actions.iter().filter(|action| handled.insert(**action)) {
+                    if config.automod_review.holds_back(*action) {
+                        if self
+                            .queue_automod_review(*action, message, guild_id, &reason, &config)
+                            .await
+                        {
+                            taken.push(format!("{} (awaiting review)", action));
+                        }
+                    } else if self
+                        .try_automod_action(*action, message, guild_id, &reason, &mut deleted)
+                        .await
+                    {
+                        taken.push(action.to_string());
+                    }
+                }
+
+                self.log(LogData {
+                    log_type: LogType::AutomodTriggered {
+                        rule: reason,
+                        channel: message.channel_id,
+                        content: image.url.clone(),
+                        actions: taken,
+                    },
+                    guild: guild_id,
+                    source_channel: Some(message.channel_id),
+                    source_user: message.author.id,
+                    timestamp: Utc::now(),
+                });
+                self.count_guild_stat(guild_id, GuildStat::AutomodTrigger).await;
+            }
+
+            // No point in looking at the rest once the message is gone
+            if deleted {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the text in the images of a new message, and checks it against the automod rules like it was sent
+    /// as a message.
+    pub async fn run_image_text_automod(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        let extractor = match &self.text_extractor {
+            Some(extractor) => extractor,
+            None => return Ok(()),
+        };
+        let images = message
+            .attachments
+            .iter()
+            .filter(|attachment| image_moderation::is_image(&attachment.filename))
+            .take(MAX_SCANNED_IMAGES)
+            .collect::<Vec<_>>();
+        if images.is_empty() {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        if !config.features.contains(GuildFeatures::AUTOMOD)
+            || !config.image_moderation.scan_text
+            || config.automod.is_empty()
+        {
+            return Ok(());
+        }
+
+        for image in images {
+            let text = match extractor.extract(&image.url).await {
+                Ok(text) => text,
+                Err(e) => {
+                    log::warn!("Failed to read the text of an image in {}: {}", guild_id, e);
+                    continue;
+                }
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            self.check_automod(message, guild_id, &config, &text, vec![], Some("text in image"))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
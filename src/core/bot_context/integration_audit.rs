@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use twilight_model::guild::audit_log::AuditLogEvent;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::core::GuildConfig;
+use crate::error::CommandError;
+use crate::utils;
+
+/// Who did something according to the audit log, and when.
+type AuditTrail = HashMap<String, (Option<UserId>, DateTime<Utc>)>;
+
+/// Everything that can act in a guild without being a regular member, for hunting down stale or planted access.
+pub struct IntegrationAudit {
+    pub webhooks: Vec<AuditedWebhook>,
+    pub bots: Vec<AuditedEntry>,
+    pub integrations: Vec<AuditedEntry>,
+}
+
+pub struct AuditedWebhook {
+    pub entry: AuditedEntry,
+    pub channel_id: ChannelId,
+    /// Not in a log channel or any of the channels webhooks are expected in.
+    pub unexpected: bool,
+}
+
+pub struct AuditedEntry {
+    pub id: u64,
+    pub name: String,
+    /// What kind of integration it is, like ``twitch`` or ``discord``. Empty for webhooks and bots.
+    pub kind: String,
+    pub created_by: Option<UserId>,
+    /// When it was added, unknown for bots that were added too long ago to still be in the audit log.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl BotContext {
+    /// Collects the webhooks, bots and integrations of a guild, filling in who added them from the audit log.
+    pub async fn audit_integrations(
+        &self,
+        guild_id: GuildId,
+        config: &GuildConfig,
+    ) -> Result<IntegrationAudit, CommandError> {
+        let webhooks = self
+            .http
+            .guild_webhooks(guild_id)
+            .await?
+            .into_iter()
+            .map(|webhook| AuditedWebhook {
+                entry: AuditedEntry {
+                    id: webhook.id.0,
+                    name: webhook.name.unwrap_or_default(),
+                    kind: String::new(),
+                    created_by: webhook.user.map(|user| user.id),
+                    created_at: Some(utils::snowflake_timestamp(webhook.id.0)),
+                },
+                channel_id: webhook.channel_id,
+                unexpected: !config.log_channels.contains_key(&webhook.channel_id)
+                    && !config.expected_webhook_channels.contains(&webhook.channel_id),
+            })
+            .collect();
+
+        let bot_trail = self.audit_trail(guild_id, AuditLogEvent::BotAdd).await;
+        let mut bots = vec![];
+        if let Some(guild) = self.cache.get_guild(&guild_id).await {
+            let members = guild.members.read().await.keys().copied().collect::<Vec<UserId>>();
+            for user_id in members {
+                let user = match self.cache.get_user(user_id).await {
+                    Some(user) if user.bot_user => user,
+                    _ => continue,
+                };
+                let added = bot_trail.get(&user_id.to_string());
+                bots.push(AuditedEntry {
+                    id: user_id.0,
+                    name: user.full_name(),
+                    kind: String::new(),
+                    created_by: added.and_then(|(by, _)| *by),
+                    created_at: added.map(|(_, at)| *at),
+                });
+            }
+        }
+
+        let integration_trail = self.audit_trail(guild_id, AuditLogEvent::IntegrationCreate).await;
+        let integrations = self
+            .http
+            .guild_integrations(guild_id)
+            .await?
+            .into_iter()
+            .map(|integration| {
+                let added = integration_trail.get(&integration.id.to_string());
+                AuditedEntry {
+                    id: integration.id.0,
+                    name: integration.name,
+                    kind: integration.kind,
+                    created_by: added
+                        .and_then(|(by, _)| *by)
+                        .or_else(|| integration.user.map(|user| user.id)),
+                    created_at: Some(
+                        added
+                            .map(|(_, at)| *at)
+                            .unwrap_or_else(|| utils::snowflake_timestamp(integration.id.0)),
+                    ),
+                }
+            })
+            .collect();
+
+        Ok(IntegrationAudit {
+            webhooks,
+            bots,
+            integrations,
+        })
+    }
+
+    /// The targets of recent audit log entries of this kind, empty if we can't read the audit log.
+    async fn audit_trail(&self, guild_id: GuildId, event: AuditLogEvent) -> AuditTrail {
+        let audit_log = match self.http.audit_log(guild_id).action_type(event).limit(100) {
+            Ok(request) => match request.await {
+                Ok(Some(audit_log)) => audit_log,
+                _ => return HashMap::new(),
+            },
+            Err(_) => return HashMap::new(),
+        };
+
+        audit_log
+            .entries
+            .into_iter()
+            // The audit log is newest first, the latest entry should win when something got added more than once
+            .rev()
+            .filter_map(|entry| {
+                let target = entry.target_id.as_ref()?.to_string();
+                Some((target, (entry.user_id, utils::snowflake_timestamp(entry.id.0))))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,412 @@
+use chrono::Utc;
+use rand::Rng;
+use twilight_model::guild::Member;
+use twilight_model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+
+use super::automod::hours_since;
+use super::BotContext;
+use crate::core::join_gate::{
+    ChallengeLevel, JoinGateAction, JoinGateReason, PendingCaptcha, PendingScreening, RiskTier,
+};
+use crate::core::logpump::{LogData, LogType};
+use crate::core::Reactor;
+use crate::error::{DatabaseError, EventHandlerError, RoleGuardError};
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{self, Emoji};
+
+/// Discord gives guilds with membership screening this feature.
+const SCREENING_FEATURE: &str = "MEMBER_VERIFICATION_GATE_ENABLED";
+/// How long to remember members that haven't completed screening yet, in seconds.
+const SCREENING_TTL: u32 = 30 * 24 * 60 * 60;
+
+/// How long challenges stay open, in seconds. Moderators can still let members through by hand after that.
+const CHALLENGE_TTL: u32 = 24 * 60 * 60;
+const CAPTCHA_LENGTH: usize = 6;
+
+fn captcha_key(user_id: UserId) -> String {
+    format!("captcha:{}", user_id)
+}
+
+fn screening_key(guild_id: GuildId, user_id: UserId) -> String {
+    format!("screening:{}:{}", guild_id, user_id)
+}
+
+impl BotContext {
+    /// Checks a member that just joined against the join gate of the guild, acts on them if they get flagged and
+    /// challenges them if their risk tiers ask for it.
+    pub async fn run_join_gate(&self, member: &Member) -> Result<(), EventHandlerError> {
+        let guild_id = member.guild_id;
+        let config = self.get_config(guild_id).await?;
+        let gate = &config.join_gate;
+        let raid_mode = self.raid_mode_active(guild_id).await?;
+        if !gate.enabled && !raid_mode {
+            return Ok(());
+        }
+
+        let account_age = hours_since(utils::snowflake_timestamp(member.user.id.0));
+        let network_bans = if config.reputation_network && gate.network_bans > 0 {
+            self.datastore
+                .get_network_reputation(member.user.id, guild_id)
+                .await?
+                .bans
+        } else {
+            0
+        };
+        let reasons = gate.check(account_age, member.user.avatar.is_some(), network_bans);
+        let mut tiers = vec![];
+        if reasons
+            .iter()
+            .any(|reason| matches!(reason, JoinGateReason::NewAccount { .. }))
+        {
+            tiers.push(RiskTier::NewAccount);
+        }
+        if self.is_watched(guild_id, member.user.id).await? {
+            tiers.push(RiskTier::Watched);
+        }
+        if raid_mode {
+            tiers.push(RiskTier::RaidMode);
+        }
+
+        let action = if reasons.is_empty() {
+            None
+        } else {
+            Some(match (raid_mode, gate.action) {
+                (true, _) => config.raid_mode.join_gate_action,
+                (false, JoinGateAction::RaidKick) => JoinGateAction::Log,
+                (false, action) => action,
+            })
+        };
+        let challenge = gate.challenge(&tiers, action);
+
+        let mut held_role = None;
+        if let Some(action) = action {
+            // Roles don't do anything before screening is done, so they are handed out once it is
+            let after_screening = member.pending && gate.role(action).is_some();
+            let succeeded = match action {
+                // The verification role comes with the challenge
+                JoinGateAction::Log | JoinGateAction::Verification => true,
+                JoinGateAction::Probation if after_screening => {
+                    held_role = gate.probation_role;
+                    true
+                }
+                JoinGateAction::Probation => match gate.probation_role {
+                    Some(role) => match self.add_member_role(guild_id, member.user.id, role, None).await {
+                        Ok(()) => true,
+                        Err(RoleGuardError::Twilight(e)) => return Err(e.into()),
+                        Err(e) => {
+                            log::debug!("Failed to gate {} in guild {}: {}", member.user.id, guild_id, e);
+                            false
+                        }
+                    },
+                    None => false,
+                },
+                JoinGateAction::RaidKick => {
+                    self.http.remove_guild_member(guild_id, member.user.id).await?;
+                    self.datastore
+                        .insert_infraction(guild_id, member.user.id, self.bot_user.id, "kick")
+                        .await?;
+                    true
+                }
+            };
+
+            self.log(LogData {
+                log_type: LogType::JoinGateFlagged {
+                    reasons,
+                    action,
+                    succeeded,
+                    after_screening,
+                },
+                guild: guild_id,
+                source_channel: None,
+                source_user: member.user.id,
+                timestamp: Utc::now(),
+            });
+
+            if action == JoinGateAction::RaidKick {
+                return Ok(());
+            }
+        }
+
+        if member.pending && (held_role.is_some() || challenge != ChallengeLevel::None) {
+            self.track_screening(guild_id, member.user.id, held_role, challenge)
+                .await?;
+        } else {
+            self.issue_challenge(guild_id, member.user.id, challenge).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remembers a member that still has to pass membership screening, along with the join gate role and
+    /// challenge they're waiting on.
+    pub async fn track_screening(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        gate_role: Option<RoleId>,
+        challenge: ChallengeLevel,
+    ) -> Result<(), DatabaseError> {
+        self.datastore
+            .cache_pool
+            .set(
+                &screening_key(guild_id, user_id),
+                &PendingScreening { gate_role, challenge },
+                Some(SCREENING_TTL),
+            )
+            .await
+    }
+
+    /// Hands out the held back join gate role and starts the challenge once a member is through screening, and logs
+    /// that they are.
+    pub async fn complete_screening(&self, guild_id: GuildId, user_id: UserId) -> Result<(), EventHandlerError> {
+        let screened_guild = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild.features.iter().any(|feature| feature == SCREENING_FEATURE),
+            None => false,
+        };
+        if !screened_guild {
+            return Ok(());
+        }
+
+        let key = screening_key(guild_id, user_id);
+        let pending = match self.datastore.cache_pool.get::<PendingScreening>(&key).await? {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+        self.datastore
+            .cache_pool
+            .delete(&key)
+            .await
+            .map_err(DatabaseError::from)?;
+
+        let succeeded = match pending.gate_role {
+            Some(role) => match self.add_member_role(guild_id, user_id, role, None).await {
+                Ok(()) => true,
+                Err(RoleGuardError::Twilight(e)) => return Err(e.into()),
+                Err(e) => {
+                    log::debug!("Failed to gate {} in guild {}: {}", user_id, guild_id, e);
+                    false
+                }
+            },
+            None => true,
+        };
+
+        self.log(LogData {
+            log_type: LogType::ScreeningCompleted {
+                gate_role: pending.gate_role,
+                succeeded,
+            },
+            guild: guild_id,
+            source_channel: None,
+            source_user: user_id,
+            timestamp: Utc::now(),
+        });
+
+        self.issue_challenge(guild_id, user_id, pending.challenge).await
+    }
+
+    /// Gives a new member the verification role and DMs them the challenge to get rid of it again.
+    pub async fn issue_challenge(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        level: ChallengeLevel,
+    ) -> Result<(), EventHandlerError> {
+        if level == ChallengeLevel::None {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let gated = match config.join_gate.verification_role {
+            Some(role) => match self.add_member_role(guild_id, user_id, role, None).await {
+                Ok(()) => true,
+                Err(RoleGuardError::Twilight(e)) => return Err(e.into()),
+                Err(e) => {
+                    log::debug!("Failed to challenge {} in guild {}: {}", user_id, guild_id, e);
+                    false
+                }
+            },
+            None => false,
+        };
+
+        let guild = self.guild_name(guild_id).await;
+        let prompted = match level {
+            // Moderators let them through with the verify command
+            ChallengeLevel::None | ChallengeLevel::Manual => true,
+            ChallengeLevel::Reaction => {
+                let prompt = self.translate_with_args(
+                    &config.language,
+                    GearBotString::ChallengeReactionPrompt,
+                    &FluArgs::with_capacity(2)
+                        .add("gearyes", Emoji::Yes.for_chat())
+                        .add("guild", guild)
+                        .generate(),
+                );
+                match self.send_challenge(user_id, prompt).await {
+                    Some((channel_id, message_id)) => {
+                        Reactor::new_join_challenge(guild_id, user_id)
+                            .save(self, message_id)
+                            .await?;
+                        if let Err(e) = self
+                            .http
+                            .create_reaction(channel_id, message_id, Emoji::Yes.to_reaction())
+                            .await
+                        {
+                            self.track_http_error(&e).await;
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            }
+            ChallengeLevel::Captcha => {
+                let code = generate_captcha();
+                let prompt = self.translate_with_args(
+                    &config.language,
+                    GearBotString::ChallengeCaptchaPrompt,
+                    &FluArgs::with_capacity(2)
+                        .add("guild", guild)
+                        .add("code", code.clone())
+                        .generate(),
+                );
+                let prompted = self.send_challenge(user_id, prompt).await.is_some();
+                if prompted {
+                    self.datastore
+                        .cache_pool
+                        .set(
+                            &captcha_key(user_id),
+                            &PendingCaptcha { guild_id, code },
+                            Some(CHALLENGE_TTL),
+                        )
+                        .await?;
+                }
+                prompted
+            }
+        };
+
+        self.log(LogData {
+            log_type: LogType::ChallengeIssued {
+                level,
+                succeeded: gated && prompted,
+            },
+            guild: guild_id,
+            source_channel: None,
+            source_user: user_id,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    /// Checks a DM against the captcha the author still has to solve, if they have one.
+    pub async fn answer_captcha(&self, user_id: UserId, answer: &str) -> Result<(), EventHandlerError> {
+        let key = captcha_key(user_id);
+        let pending = match self.datastore.cache_pool.get::<PendingCaptcha>(&key).await? {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+
+        if answer.trim().eq_ignore_ascii_case(&pending.code) {
+            self.pass_challenge(pending.guild_id, user_id, ChallengeLevel::Captcha)
+                .await?;
+            return Ok(());
+        }
+
+        let config = self.get_config(pending.guild_id).await?;
+        let reply = self.translate_with_args(
+            &config.language,
+            GearBotString::ChallengeCaptchaWrong,
+            &FluArgs::with_capacity(3)
+                .add("gearno", Emoji::No.for_chat())
+                .add("code", pending.code)
+                .add("guild", self.guild_name(pending.guild_id).await)
+                .generate(),
+        );
+        self.send_challenge(user_id, reply).await;
+        Ok(())
+    }
+
+    /// Takes the verification role away from a member that passed their challenge, and lets them know they're in.
+    pub async fn pass_challenge(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        level: ChallengeLevel,
+    ) -> Result<(), DatabaseError> {
+        self.forget_challenge(guild_id, user_id).await?;
+
+        let config = self.get_config(guild_id).await?;
+        if let Some(role) = config.join_gate.verification_role {
+            if let Err(e) = self.remove_member_role(guild_id, user_id, role, None).await {
+                log::debug!("Failed to let {} through in guild {}: {}", user_id, guild_id, e);
+            }
+        }
+
+        let message = self.translate_with_args(
+            &config.language,
+            GearBotString::ChallengePassedDm,
+            &FluArgs::with_capacity(2)
+                .add("gearyes", Emoji::Yes.for_chat())
+                .add("guild", self.guild_name(guild_id).await)
+                .generate(),
+        );
+        self.send_challenge(user_id, message).await;
+
+        self.log(LogData {
+            log_type: LogType::ChallengePassed { level },
+            guild: guild_id,
+            source_channel: None,
+            source_user: user_id,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    /// Drops the captcha a member still had open for the guild, for when a moderator lets them through.
+    pub async fn forget_challenge(&self, guild_id: GuildId, user_id: UserId) -> Result<(), DatabaseError> {
+        let key = captcha_key(user_id);
+        let pending = self.datastore.cache_pool.get::<PendingCaptcha>(&key).await?;
+        if pending.map_or(false, |pending| pending.guild_id == guild_id) {
+            self.datastore
+                .cache_pool
+                .delete(&key)
+                .await
+                .map_err(DatabaseError::from)?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn guild_name(&self, guild_id: GuildId) -> String {
+        match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild.name.clone(),
+            None => guild_id.to_string(),
+        }
+    }
+
+    /// DMs a challenge to a member, they might not accept DMs from us.
+    async fn send_challenge(&self, user_id: UserId, content: String) -> Option<(ChannelId, MessageId)> {
+        let channel_id = match self.get_dm_for_user(user_id).await {
+            Ok(channel) => channel.get_id(),
+            Err(e) => {
+                self.track_http_error(&e).await;
+                return None;
+            }
+        };
+        match self.http.create_message(channel_id).content(content).ok()?.await {
+            Ok(message) => Some((channel_id, message.id)),
+            Err(e) => {
+                self.track_http_error(&e).await;
+                None
+            }
+        }
+    }
+}
+
+/// Leaves out the characters that are easy to mix up.
+fn generate_captcha() -> String {
+    const CHARACTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    let mut rng = rand::thread_rng();
+    (0..CAPTCHA_LENGTH)
+        .map(|_| CHARACTERS[rng.gen_range(0..CHARACTERS.len())] as char)
+        .collect()
+}
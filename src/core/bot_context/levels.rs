@@ -0,0 +1,93 @@
+use rand::Rng;
+use twilight_model::channel::Message;
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::core::{levels, GuildFeatures};
+use crate::error::{EventHandlerError, RoleGuardError};
+
+impl BotContext {
+    /// Awards xp for a message if the guild has leveling enabled and the author isn't on cooldown.
+    ///
+    /// Handles handing out (and optionally taking away) role rewards when the author reaches a new level.
+    pub async fn award_message_xp(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        let level_config = &config.levels;
+
+        if !config.features.contains(GuildFeatures::LEVELING)
+            || !level_config.enabled
+            || level_config.ignored_channels.contains(&message.channel_id)
+        {
+            return Ok(());
+        }
+
+        let member = match self.cache.get_member(&guild_id, &message.author.id).await {
+            Some(member) => member,
+            None => return Ok(()),
+        };
+
+        if member
+            .roles
+            .iter()
+            .any(|role| level_config.ignored_roles.contains(role))
+        {
+            return Ok(());
+        }
+
+        let cooldown_key = format!("xp_cooldown:{}:{}", guild_id, message.author.id);
+        let redis_cache = &self.datastore.cache_pool;
+        if redis_cache.get::<bool>(&cooldown_key).await?.is_some() {
+            return Ok(());
+        }
+        redis_cache
+            .set(&cooldown_key, &true, Some(level_config.cooldown.max(1)))
+            .await?;
+
+        let amount = if level_config.min_xp >= level_config.max_xp {
+            level_config.min_xp
+        } else {
+            rand::thread_rng().gen_range(level_config.min_xp..=level_config.max_xp)
+        };
+
+        let total = self.datastore.add_xp(guild_id, message.author.id, amount).await?;
+        let old_level = levels::level_for_xp(total - amount as u64);
+        let new_level = levels::level_for_xp(total);
+
+        if new_level == old_level || level_config.role_rewards.is_empty() {
+            return Ok(());
+        }
+
+        let highest_reward = level_config
+            .role_rewards
+            .iter()
+            .filter(|reward| reward.level <= new_level)
+            .max_by_key(|reward| reward.level);
+
+        for reward in &level_config.role_rewards {
+            let earned = if level_config.stack_rewards {
+                reward.level <= new_level
+            } else {
+                highest_reward.map_or(false, |highest| highest.role == reward.role)
+            };
+
+            let result = if earned && !member.roles.contains(&reward.role) {
+                self.add_member_role(guild_id, message.author.id, reward.role, None)
+                    .await
+            } else if !earned && reward.level <= new_level && member.roles.contains(&reward.role) {
+                self.remove_member_role(guild_id, message.author.id, reward.role, None)
+                    .await
+            } else {
+                Ok(())
+            };
+
+            match result {
+                Err(RoleGuardError::Twilight(e)) => return Err(e.into()),
+                // A misconfigured reward shouldn't stop the others from being handed out
+                Err(e) => log::debug!("Skipped level reward {} in guild {}: {}", reward.role, guild_id, e),
+                Ok(()) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
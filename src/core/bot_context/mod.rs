@@ -3,34 +3,95 @@ use twilight_gateway::Cluster;
 use twilight_http::Client as HttpClient;
 use twilight_model::{
     channel::Message,
-    id::{GuildId, UserId},
+    id::{ChannelId, GuildId, MessageId, UserId},
     user::CurrentUser,
 };
 
+mod anti_nuke;
+mod attachment_mirror;
+mod auto_publish;
+mod automod;
+mod autoresponder;
+mod backups;
+mod birthdays;
+mod blocklist;
+mod bulk_roles;
+mod channel_templates;
 mod cold_resume;
+mod confirmations;
+mod content_policy;
 mod data_access;
+mod data_purge;
+mod escalation;
+mod events;
+mod feeds;
+mod github;
+mod guild_emoji;
+mod guild_stats;
+mod health;
+mod heat;
+mod image_moderation;
+mod integration_audit;
+mod join_gate;
+mod levels;
 mod logpump;
+mod mod_journal;
+mod onboarding;
 mod permissions;
+mod pin_archive;
+mod profiles;
+mod prune;
+mod raid_mode;
+mod rate_limits;
+mod retention;
+mod role_guard;
+mod snipes;
 mod stats;
+mod sys_info;
+mod temp_voice;
+mod tickets;
+mod tiers;
+mod toxicity;
+mod user_data;
+mod watchlist;
+mod webhooks;
 
 pub mod status;
 
-pub use stats::BotStats;
+pub use attachment_mirror::AttachmentMirror;
+pub use bulk_roles::BulkRoleJob;
+pub use health::{Health, HealthReport, HealthState};
+pub use integration_audit::{AuditedEntry, AuditedWebhook, IntegrationAudit};
+pub use mod_journal::{JournalEntry, ModAction, UNDO_WINDOW};
+pub use prune::PRUNE_PREVIEW_TTL;
+pub use rate_limits::RateLimitMonitor;
+pub use role_guard::{RoleGuard, RoleMutation};
+pub use snipes::SnipedMessage;
+pub use stats::{BotStats, TaskGuard};
+pub use sys_info::SysInfo;
 
 use crate::cache::Cache;
+use crate::core::feeds::FeedReader;
+use crate::core::guild_stats::{CommandUseCounts, GuildStatCounts};
+use crate::core::image_moderation::{ImageClassifier, TextExtractor};
 use crate::core::logpump::LogData;
+use crate::core::toxicity::MessageClassifier;
+use crate::core::Backups;
 use crate::core::GuildConfig;
 use crate::database::redis::api_handlers::api_structs::{RawTeamMembers, TeamInfo, TeamMember};
 use crate::database::DataStorage;
 use crate::translation::{GearBotString, Translations};
 use crate::SchemeInfo;
+use confirmations::PendingConfirmation;
 use fluent_bundle::FluentArgs;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use unic_langid::LanguageIdentifier;
+use watchlist::WatchedUser;
 
 #[derive(PartialEq, Debug)]
 pub enum ShardState {
@@ -57,8 +118,39 @@ pub struct BotContext {
     pub translations: Translations,
     pub scheme_info: SchemeInfo,
     pub shard_states: RwLock<HashMap<u64, ShardState>>,
+    pub health: Health,
+    pub rate_limits: RateLimitMonitor,
     pub start_time: DateTime<Utc>,
     pub global_admins: Vec<UserId>,
+    /// Users that aren't allowed to use the bot anywhere, loaded from the database on startup.
+    blocked_users: RwLock<HashSet<UserId>>,
+    /// Yes/no prompts of commands that are waiting for an answer, by prompt message.
+    confirmations: RwLock<HashMap<MessageId, PendingConfirmation>>,
+    /// Who is watched in each guild, loaded from the database the first time a guild needs it.
+    watchlists: RwLock<HashMap<GuildId, HashMap<UserId, WatchedUser>>>,
+    /// Bulk role changes that are still running, so they can be cancelled.
+    bulk_role_jobs: RwLock<HashMap<GuildId, Arc<BulkRoleJob>>>,
+    /// Statistics counted since they were last written to the database.
+    guild_stats: Mutex<HashMap<GuildId, GuildStatCounts>>,
+    /// Messages per channel per hour (by the start of the hour) that weren't written to the database yet.
+    channel_activity: Mutex<HashMap<(GuildId, ChannelId, i64), u32>>,
+    /// Uses of every command per channel that weren't written to the database yet.
+    command_usage: Mutex<HashMap<(GuildId, ChannelId, String), CommandUseCounts>>,
+    pub attachment_mirror: Option<AttachmentMirror>,
+    /// Labels image attachments for guilds with image policies, when a moderation API is configured.
+    pub image_classifier: Option<Box<dyn ImageClassifier>>,
+    /// Reads the text in image attachments for guilds that want it scanned, when an OCR API is configured.
+    pub text_extractor: Option<Box<dyn TextExtractor>>,
+    /// Scores messages for guilds that give toxicity heat, when a toxicity API is configured.
+    pub message_classifier: Option<Box<dyn MessageClassifier>>,
+    pub backups: Option<Backups>,
+    pub feed_reader: FeedReader,
+    /// Downloads the images of emoji that are added from a url.
+    pub emoji_client: reqwest::Client,
+    /// Where the webhook server can be reached, when it is running.
+    pub webhook_url: Option<String>,
+    /// How long data is kept around after leaving a guild.
+    pub data_retention: Duration,
     team_info: RawTeamMembers,
     logpump_sender: UnboundedSender<LogData>,
 }
@@ -70,24 +162,33 @@ impl BotContext {
         datastore: DataStorage,
         translations: Translations,
         global_admins: Vec<u64>,
+        attachment_mirror: Option<u64>,
+        image_classifier: Option<Box<dyn ImageClassifier>>,
+        text_extractor: Option<Box<dyn TextExtractor>>,
+        message_classifier: Option<Box<dyn MessageClassifier>>,
+        backups: Option<Backups>,
+        data_retention_days: Option<u32>,
+        webhook_url: Option<String>,
         stats: Arc<BotStats>,
         logpump_sender: UnboundedSender<LogData>,
     ) -> Self {
         let scheme_info = bot_core.2;
-        let mut shard_states = HashMap::with_capacity(scheme_info.shards_per_cluster as usize);
-        for i in scheme_info.cluster_id * scheme_info.shards_per_cluster
-            ..scheme_info.cluster_id * scheme_info.shards_per_cluster + scheme_info.shards_per_cluster
-        {
+        let mut shard_states = HashMap::with_capacity(scheme_info.shard_count() as usize);
+        for i in scheme_info.shard_range() {
             shard_states.insert(i, ShardState::PendingCreation);
             bot_core.0.missing_per_shard.write().await.insert(i, AtomicU64::new(0));
         }
 
         let global_admins = global_admins.into_iter().map(UserId).collect();
+        let attachment_mirror = attachment_mirror.map(|channel| AttachmentMirror::new(ChannelId(channel)));
+        let data_retention_days = data_retention_days.unwrap_or(data_purge::DEFAULT_RETENTION_DAYS);
 
-        stats.shard_counts.pending.set(scheme_info.shards_per_cluster as i64);
+        stats.shard_counts.pending.set(scheme_info.shard_count() as i64);
 
         let team_info: RawTeamMembers =
             toml::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/team.toml"))).unwrap();
+        let feed_reader = FeedReader::new().expect("Failed to set up the feed reader");
+        let emoji_client = crate::core::guild_emoji::download_client().expect("Failed to set up the emoji downloader");
 
         BotContext {
             cache: bot_core.0,
@@ -102,8 +203,26 @@ impl BotContext {
             translations,
             scheme_info,
             shard_states: RwLock::new(shard_states),
+            health: Health::new(),
+            rate_limits: RateLimitMonitor::new(),
             start_time: Utc::now(),
             global_admins,
+            blocked_users: RwLock::new(HashSet::new()),
+            confirmations: RwLock::new(HashMap::new()),
+            watchlists: RwLock::new(HashMap::new()),
+            bulk_role_jobs: RwLock::new(HashMap::new()),
+            guild_stats: Mutex::new(HashMap::new()),
+            channel_activity: Mutex::new(HashMap::new()),
+            command_usage: Mutex::new(HashMap::new()),
+            attachment_mirror,
+            image_classifier,
+            text_extractor,
+            message_classifier,
+            backups,
+            feed_reader,
+            emoji_client,
+            webhook_url,
+            data_retention: Duration::from_secs(data_retention_days as u64 * 60 * 60 * 24),
             team_info,
             logpump_sender,
         }
@@ -114,6 +233,13 @@ impl BotContext {
         self.bot_user.id == other.author.id
     }
 
+    /// Returns if this cluster runs the background jobs that work on every guild at once, like backups and purges.
+    ///
+    /// All clusters share the same database, so only the first cluster runs those.
+    pub fn is_primary_cluster(&self) -> bool {
+        self.scheme_info.cluster_id == 0
+    }
+
     pub fn translate(&self, language: &LanguageIdentifier, key: GearBotString) -> String {
         self.translations.get_text_plain(language, key).to_string()
     }
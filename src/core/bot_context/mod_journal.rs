@@ -0,0 +1,152 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, GuildId, RoleId, UserId};
+
+use super::BotContext;
+use crate::error::DatabaseError;
+
+/// How long after an action it can still be undone, in seconds.
+pub const UNDO_WINDOW: u32 = 60 * 15;
+/// How many actions are remembered per moderator.
+const JOURNAL_SIZE: usize = 10;
+
+/// A moderation action that can be reverted.
+///
+/// Bans are deliberately not part of this: unbanning doesn't restore the roles, nickname or
+/// messages that were lost, so an "undo" would be misleading. Those need a manual unban.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ModAction {
+    RoleRemoved {
+        user: UserId,
+        role: RoleId,
+    },
+    /// A role given to a batch of members at once, like ``role addall`` does.
+    RolesAdded {
+        users: Vec<UserId>,
+        role: RoleId,
+    },
+    RolesRemoved {
+        users: Vec<UserId>,
+        role: RoleId,
+    },
+    /// Channels locked for a role in one go, like raid mode does.
+    ChannelsLocked {
+        role: RoleId,
+        /// Every locked channel with the overwrite that was in place before the lock, if there was one.
+        channels: Vec<(ChannelId, Option<(Permissions, Permissions)>)>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub action: ModAction,
+    pub timestamp: i64,
+}
+
+fn journal_key(guild_id: GuildId, moderator: UserId) -> String {
+    format!("mod_journal:{}:{}", guild_id, moderator)
+}
+
+impl BotContext {
+    /// Records a reversible action so the moderator can undo it later.
+    pub async fn record_mod_action(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+        action: ModAction,
+    ) -> Result<(), DatabaseError> {
+        let entry = JournalEntry {
+            action,
+            timestamp: Utc::now().timestamp(),
+        };
+
+        self.datastore
+            .cache_pool
+            .push_capped(&journal_key(guild_id, moderator), &entry, JOURNAL_SIZE, UNDO_WINDOW)
+            .await
+    }
+
+    /// The most recent action of this moderator, if it's still recent enough to undo.
+    ///
+    /// It stays in the journal until it's forgotten, so an undo that fails can be tried again.
+    pub async fn latest_mod_action(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+    ) -> Result<Option<JournalEntry>, DatabaseError> {
+        let entry: Option<JournalEntry> = self
+            .datastore
+            .cache_pool
+            .list_front(&journal_key(guild_id, moderator))
+            .await?;
+
+        Ok(entry.filter(|entry| is_undoable(entry, Utc::now().timestamp())))
+    }
+
+    /// Takes an action out of the journal once it has been undone.
+    pub async fn forget_mod_action(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+        entry: &JournalEntry,
+    ) -> Result<(), DatabaseError> {
+        self.datastore
+            .cache_pool
+            .list_remove(&journal_key(guild_id, moderator), entry)
+            .await
+    }
+}
+
+fn is_undoable(entry: &JournalEntry, now: i64) -> bool {
+    now - entry.timestamp <= UNDO_WINDOW as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_actions_can_be_undone() {
+        let entry = JournalEntry {
+            action: ModAction::ChannelsLocked {
+                role: RoleId(1),
+                channels: vec![
+                    (ChannelId(2), Some((Permissions::VIEW_CHANNEL, Permissions::empty()))),
+                    (ChannelId(3), None),
+                ],
+            },
+            timestamp: 1000,
+        };
+
+        // The journal lives in redis, so it has to make it through there in one piece
+        let entry: JournalEntry = serde_json::from_str(&serde_json::to_string(&entry).unwrap()).unwrap();
+        assert!(is_undoable(&entry, 1000 + UNDO_WINDOW as i64));
+        match entry.action {
+            ModAction::ChannelsLocked { role, channels } => {
+                assert_eq!(role, RoleId(1));
+                assert_eq!(
+                    channels,
+                    vec![
+                        (ChannelId(2), Some((Permissions::VIEW_CHANNEL, Permissions::empty()))),
+                        (ChannelId(3), None),
+                    ]
+                );
+            }
+            other => panic!("expected the channel lock back, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn old_actions_are_not_undone() {
+        let entry = JournalEntry {
+            action: ModAction::RolesAdded {
+                users: vec![UserId(3), UserId(4)],
+                role: RoleId(5),
+            },
+            timestamp: 1000,
+        };
+
+        assert!(!is_undoable(&entry, 1001 + UNDO_WINDOW as i64));
+    }
+}
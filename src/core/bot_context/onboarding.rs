@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use twilight_model::guild::audit_log::AuditLogEvent;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::cache::{CachedChannel, CachedGuild};
+use crate::core::guild_config::{LogCategory, LogChannelConfig};
+use crate::core::GuildConfig;
+use crate::error::EventHandlerError;
+use crate::translation::{FluArgs, GearBotString};
+use crate::{gearbot_error, gearbot_info};
+
+/// Channel names that are likely meant for logs, best match first.
+const LOG_CHANNEL_NAMES: &[&str] = &["mod-logs", "modlogs", "mod-log", "bot-logs", "logs", "log"];
+
+impl BotContext {
+    /// Sets up a guild the bot has never been in before.
+    ///
+    /// Guilds that already have a config are left alone, this includes guilds that added the bot while it was offline
+    /// and got a config from an earlier visit.
+    pub async fn onboard_guild(&self, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        if self.datastore.get_guild_config(guild_id.0).await?.is_some() {
+            return Ok(());
+        }
+
+        let guild = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild,
+            None => return Err(EventHandlerError::UnknownGuild(guild_id)),
+        };
+
+        // This creates the config along with the encryption key for the guild.
+        self.get_config(guild_id).await?;
+
+        let mut config = GuildConfig::default();
+        let log_channel = self.find_log_channel(&guild).await;
+        if let Some(channel_id) = log_channel {
+            config.log_channels.insert(
+                channel_id,
+                LogChannelConfig {
+                    categories: vec![
+                        LogCategory::GENERAL,
+                        LogCategory::COMMANDS,
+                        LogCategory::MESSAGES,
+                        LogCategory::MEMBERS,
+                    ],
+                    disabled_keys: vec![],
+                    style: config.log_style,
+                    filters: vec![],
+                    timestamps: true,
+                    webhook: false,
+                    webhook_identities: HashMap::new(),
+                },
+            );
+        }
+        let language = config.language.clone();
+        let prefix = config.prefix.clone();
+        self.set_config(guild_id, config).await?;
+
+        gearbot_info!("Joined a new guild: {} ({})", guild.name, guild_id);
+        if let Err(e) = self
+            .datastore
+            .cache_pool
+            .announce_new_guild(self.scheme_info.cluster_id, &guild)
+            .await
+        {
+            gearbot_error!("Failed to announce new guild {} to the other clusters: {}", guild_id, e);
+        }
+
+        let inviter = match self.find_inviter(guild_id).await {
+            Some(inviter) => inviter,
+            None => return Ok(()),
+        };
+
+        let log_channel = match log_channel {
+            Some(channel_id) => format!("<#{}>", channel_id),
+            None => self.translate(&language, GearBotString::OnboardingNoLogChannel),
+        };
+        let message = self.translate_with_args(
+            &language,
+            GearBotString::OnboardingWelcome,
+            &FluArgs::with_capacity(3)
+                .add("guild", guild.name.clone())
+                .add("prefix", prefix)
+                .add("log_channel", log_channel)
+                .generate(),
+        );
+
+        // They might not accept DMs from us, nothing we can do about that.
+        if let Ok(channel) = self.get_dm_for_user(inviter).await {
+            let _ = self
+                .http
+                .create_message(channel.get_id())
+                .content(message)
+                .unwrap()
+                .await;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_dm_for_user(&self, user_id: UserId) -> Result<Arc<CachedChannel>, twilight_http::Error> {
+        match self.cache.get_dm_channel_for(user_id).await {
+            Some(channel) => Ok(channel),
+            None => {
+                let channel = self.http.create_private_channel(user_id).await?;
+                Ok(self.cache.insert_private_channel(&channel).await)
+            }
+        }
+    }
+
+    /// Looks for a text channel that seems meant for logs and that we can actually send to.
+    async fn find_log_channel(&self, guild: &CachedGuild) -> Option<ChannelId> {
+        let mut candidates: Vec<(usize, ChannelId)> = guild
+            .channels
+            .read()
+            .await
+            .values()
+            .filter(|channel| matches!(channel.as_ref(), CachedChannel::TextChannel { .. }))
+            .filter_map(|channel| {
+                LOG_CHANNEL_NAMES
+                    .iter()
+                    .position(|name| channel.get_name().eq_ignore_ascii_case(name))
+                    .map(|rank| (rank, channel.get_id()))
+            })
+            .collect();
+        candidates.sort();
+
+        for (_, channel_id) in candidates {
+            let permissions = self.get_channel_permissions_for(self.bot_user.id, channel_id).await;
+            if permissions.contains(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES) {
+                return Some(channel_id);
+            }
+        }
+
+        None
+    }
+
+    /// Finds out who added the bot through the audit log, this needs the view audit log permission.
+    async fn find_inviter(&self, guild_id: GuildId) -> Option<UserId> {
+        let audit_log = self
+            .http
+            .audit_log(guild_id)
+            .action_type(AuditLogEvent::BotAdd)
+            .limit(10)
+            .ok()?
+            .await
+            .ok()??;
+
+        let bot_id = self.bot_user.id.to_string();
+        audit_log
+            .entries
+            .iter()
+            .find(|entry| entry.target_id.as_ref().map(|id| id.to_string()) == Some(bot_id.clone()))
+            .and_then(|entry| entry.user_id)
+    }
+}
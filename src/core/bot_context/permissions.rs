@@ -146,6 +146,20 @@ impl BotContext {
         permissions
     }
 
+    /// Calculates permissions outside of guilds, where only the lowest permission group of the config applies.
+    pub fn get_dm_permissions(&self, user_id: &UserId, config: &GuildConfig) -> GearBotPermissions {
+        let mut permissions = GearBotPermissions::empty();
+        let mut not_negated_denies = GearBotPermissions::empty();
+
+        if let Some(group) = config.permission_groups.first() {
+            apply(&mut permissions, &mut not_negated_denies, group);
+        }
+        cascade_groups(&mut permissions, &not_negated_denies);
+
+        self.apply_admin_perms(user_id, &mut permissions);
+        permissions
+    }
+
     pub fn apply_admin_perms(&self, user_id: &UserId, permissions: &mut GearBotPermissions) {
         if self.global_admins.contains(user_id) {
             permissions.insert(GearBotPermissions::BOT_ADMIN);
@@ -0,0 +1,120 @@
+use chrono::Utc;
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::BotContext;
+use crate::core::pin_archive::{self, PinArchiveChannel, MAX_PINS};
+use crate::core::GuildConfig;
+use crate::error::{DatabaseError, EventHandlerError};
+use crate::translation::{FluArgs, GearBotString};
+
+impl BotContext {
+    /// Copies a newly pinned message to the pin archive of its channel, and unpins the oldest pin to make room
+    /// for the next one when the channel is full and asks for that.
+    pub async fn archive_pins(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        last_pin_timestamp: Option<&str>,
+    ) -> Result<(), EventHandlerError> {
+        // Unpinning also sends an update, those don't have anything new to archive
+        if !pin_archive::is_new_pin(last_pin_timestamp, Utc::now()) {
+            return Ok(());
+        }
+        let config = self.get_config(guild_id).await?;
+        let settings = match config.pin_archive.settings_for(channel_id) {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+
+        let pins = match self.http.pins(channel_id).await {
+            Ok(pins) => pins,
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to get the pins of {} in {}: {}", channel_id, guild_id, e);
+                return Ok(());
+            }
+        };
+
+        // The most recent pin comes first
+        if let Some(newest) = pins.first() {
+            if !self.datastore.is_pin_archived(newest.id).await? {
+                self.archive_pin(guild_id, &config, settings, newest).await?;
+            }
+        }
+
+        if settings.unpin_oldest && pins.len() >= MAX_PINS {
+            if let Some(oldest) = pins.last() {
+                // Don't lose it if it was pinned before the archive was set up
+                if !self.datastore.is_pin_archived(oldest.id).await? {
+                    self.archive_pin(guild_id, &config, settings, oldest).await?;
+                }
+                if let Err(e) = self.http.delete_pin(channel_id, oldest.id).await {
+                    self.track_http_error(&e).await;
+                    log::debug!("Failed to unpin {} in {}: {}", oldest.id, guild_id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Posts the copy of a pin in the archive. Failing to do so is only logged, it gets another chance with the
+    /// next pin update.
+    async fn archive_pin(
+        &self,
+        guild_id: GuildId,
+        config: &GuildConfig,
+        settings: &PinArchiveChannel,
+        message: &Message,
+    ) -> Result<(), DatabaseError> {
+        let channel_name = match self.cache.get_channel(message.channel_id).await {
+            Some(channel) => channel.get_name().to_string(),
+            None => message.channel_id.to_string(),
+        };
+        let jump = self.translate(&config.language, GearBotString::PinArchiveJump);
+        let footer = self.translate_with_args(
+            &config.language,
+            GearBotString::PinArchiveFooter,
+            &FluArgs::with_capacity(1).add("channel", channel_name).generate(),
+        );
+
+        let embed = match pin_archive::archive_embed(guild_id, message, &jump, footer) {
+            Ok(embed) => embed,
+            Err(e) => {
+                log::warn!(
+                    "Failed to build the archived copy of {} in {}: {}",
+                    message.id,
+                    guild_id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+        let request = match self.http.create_message(settings.archive).embed(embed) {
+            Ok(request) => request,
+            Err(e) => {
+                log::debug!(
+                    "Archived copy of {} in {} is not a valid message: {}",
+                    message.id,
+                    guild_id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        match request.await {
+            Ok(archived) => {
+                self.datastore
+                    .mark_pin_archived(guild_id, message.id, archived.id)
+                    .await
+            }
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to archive {} in {}: {}", message.id, guild_id, e);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::error::DatabaseError;
+use crate::gearbot_error;
+
+/// How often to check for profiles that are due to be reverted.
+const REVERT_INTERVAL: Duration = Duration::from_secs(60);
+
+impl BotContext {
+    /// Puts back what the pending profile changed, returning the name of the profile if there was one.
+    pub async fn revert_profile(&self, guild_id: GuildId) -> Result<Option<String>, DatabaseError> {
+        let pending = match self.datastore.get_profile_revert(guild_id).await? {
+            Some(pending) => pending,
+            None => return Ok(None),
+        };
+
+        let config = self.get_config(guild_id).await?;
+        match pending.snapshot.apply_to(&config) {
+            Ok(config) => self.set_config(guild_id, config).await?,
+            // Nothing sensible left to put back, the revert is dropped either way
+            Err(e) => gearbot_error!(
+                "Failed to revert profile {} in guild {}: {}",
+                pending.profile,
+                guild_id,
+                e
+            ),
+        }
+        self.datastore.cancel_profile_revert(guild_id).await?;
+
+        Ok(Some(pending.profile))
+    }
+
+    /// Periodically reverts profiles that were applied for a limited time.
+    ///
+    /// Every cluster only handles its own guilds, so the cached configs of other clusters never go stale.
+    pub async fn run_profile_reverts(&self) {
+        loop {
+            tokio::time::sleep(REVERT_INTERVAL).await;
+
+            let due = match self.datastore.get_due_profile_reverts().await {
+                Ok(due) => due,
+                Err(e) => {
+                    gearbot_error!("Failed to fetch the profiles due to be reverted: {}", e);
+                    continue;
+                }
+            };
+            self.stats.scheduler_backlog.profile_reverts.set(due.len() as i64);
+
+            for guild_id in due {
+                if self.cache.get_guild(&guild_id).await.is_none() {
+                    continue;
+                }
+                if let Err(e) = self.revert_profile(guild_id).await {
+                    gearbot_error!("Failed to revert the profile of guild {}: {}", guild_id, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::Utc;
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::prune::{inactive_members, PruneAction, PrunePreview};
+use crate::error::DatabaseError;
+
+/// How long a preview can be acted on, in seconds.
+pub const PRUNE_PREVIEW_TTL: u32 = 60 * 10;
+
+fn prune_preview_key(guild_id: GuildId) -> String {
+    format!("prune_preview:{}", guild_id)
+}
+
+/// The members of a preview that were and weren't pruned.
+#[derive(Debug, Default)]
+pub struct PruneOutcome {
+    pub pruned: Vec<UserId>,
+    /// Members that left in the meantime or that we failed to kick or role.
+    pub failed: Vec<UserId>,
+}
+
+impl BotContext {
+    /// Looks up the members without messages or voice activity in the window, and keeps the list around for the
+    /// moderator to act on.
+    pub async fn preview_prune(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+        window_days: u32,
+    ) -> Result<PrunePreview, DatabaseError> {
+        let config = self.get_config(guild_id).await?;
+        let active = self.datastore.get_active_users(guild_id, window_days).await?;
+
+        let members = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild.members.read().await.values().cloned().collect(),
+            None => vec![],
+        };
+        let mut humans = Vec::with_capacity(members.len());
+        for member in members {
+            let bot = self
+                .cache
+                .get_user(member.user_id)
+                .await
+                .map_or(false, |user| user.bot_user);
+            if !bot {
+                humans.push(member);
+            }
+        }
+
+        let preview = PrunePreview {
+            moderator,
+            window_days,
+            members: inactive_members(
+                humans.iter().map(|member| member.as_ref()),
+                &active,
+                &config.prune,
+                window_days,
+                Utc::now(),
+            ),
+        };
+        self.datastore
+            .cache_pool
+            .set(&prune_preview_key(guild_id), &preview, Some(PRUNE_PREVIEW_TTL))
+            .await?;
+
+        Ok(preview)
+    }
+
+    /// The pending preview of the guild, if the moderator is the one that made it. It stays around to act on.
+    pub async fn get_prune_preview(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+    ) -> Result<Option<PrunePreview>, DatabaseError> {
+        let preview = self
+            .datastore
+            .cache_pool
+            .get::<PrunePreview>(&prune_preview_key(guild_id))
+            .await?
+            .filter(|preview| preview.moderator == moderator);
+
+        Ok(preview)
+    }
+
+    /// Takes the pending preview of the guild, if the moderator is the one that made it.
+    pub async fn take_prune_preview(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+    ) -> Result<Option<PrunePreview>, DatabaseError> {
+        let preview = self.get_prune_preview(guild_id, moderator).await?;
+        if preview.is_some() {
+            self.datastore.cache_pool.delete(&prune_preview_key(guild_id)).await?;
+        }
+
+        Ok(preview)
+    }
+
+    /// Kicks or roles the members of the preview, the role has to be checked with the role guard beforehand.
+    pub async fn execute_prune(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+        preview: &PrunePreview,
+        action: PruneAction,
+    ) -> Result<PruneOutcome, DatabaseError> {
+        let mut outcome = PruneOutcome::default();
+
+        for user_id in &preview.members {
+            if self.cache.get_member(&guild_id, user_id).await.is_none() {
+                outcome.failed.push(*user_id);
+                continue;
+            }
+
+            let result = match action {
+                PruneAction::Kick => self.http.remove_guild_member(guild_id, *user_id).await.map(|_| ()),
+                PruneAction::Role(role) => self
+                    .http
+                    .add_guild_member_role(guild_id, *user_id, role)
+                    .await
+                    .map(|_| ()),
+            };
+
+            match result {
+                Ok(()) => {
+                    if action == PruneAction::Kick {
+                        self.datastore
+                            .insert_infraction(guild_id, *user_id, moderator, "kick")
+                            .await?;
+                    }
+                    outcome.pruned.push(*user_id);
+                }
+                Err(e) => {
+                    self.track_http_error(&e).await;
+                    log::debug!("Failed to prune {} in guild {}: {}", user_id, guild_id, e);
+                    outcome.failed.push(*user_id);
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
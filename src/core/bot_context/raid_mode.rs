@@ -0,0 +1,193 @@
+use chrono::Utc;
+use twilight_model::channel::permission_overwrite::PermissionOverwriteType;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{GuildId, RoleId, UserId};
+
+use super::BotContext;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::raid_mode::{raised_verification_level, RaidModeState};
+use crate::error::DatabaseError;
+
+fn raid_mode_key(guild_id: GuildId) -> String {
+    format!("raid_mode:{}", guild_id)
+}
+
+impl BotContext {
+    pub async fn get_raid_mode(&self, guild_id: GuildId) -> Result<Option<RaidModeState>, DatabaseError> {
+        self.datastore.cache_pool.get(&raid_mode_key(guild_id)).await
+    }
+
+    /// If raid mode is currently on for the guild.
+    pub async fn raid_mode_active(&self, guild_id: GuildId) -> Result<bool, DatabaseError> {
+        Ok(self.get_raid_mode(guild_id).await?.is_some())
+    }
+
+    /// Applies the lockdown of the guild, `None` as moderator means the raid detector did it.
+    ///
+    /// Parts of the lockdown that can't be applied are skipped. Returns `None` if raid mode was already on.
+    pub async fn enable_raid_mode(
+        &self,
+        guild_id: GuildId,
+        moderator: Option<UserId>,
+    ) -> Result<Option<RaidModeState>, DatabaseError> {
+        if self.raid_mode_active(guild_id).await? {
+            return Ok(None);
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let lockdown = &config.raid_mode;
+        let everyone = RoleId(guild_id.0);
+        let mut state = RaidModeState {
+            automatic: moderator.is_none(),
+            since: Utc::now().timestamp(),
+            previous_verification_level: None,
+            previous_everyone_permissions: None,
+            restricted_channels: vec![],
+        };
+
+        if let Some(guild) = self.cache.get_guild(&guild_id).await {
+            let current = guild.verification_level;
+            if let Some(level) = lockdown
+                .verification_level
+                .and_then(|target| raised_verification_level(current, target))
+            {
+                match self.http.update_guild(guild_id).verification_level(level).await {
+                    Ok(_) => state.previous_verification_level = Some(current),
+                    Err(e) => {
+                        self.raid_mode_step_failed(guild_id, "raise the verification level", e)
+                            .await
+                    }
+                }
+            }
+
+            let everyone_permissions = guild.get_role(&everyone).await.map(|role| role.permissions);
+            if let Some(permissions) = everyone_permissions.filter(|_| lockdown.pause_invites) {
+                if permissions.contains(Permissions::CREATE_INVITE) {
+                    let paused = permissions - Permissions::CREATE_INVITE;
+                    match self.http.update_role(guild_id, everyone).permissions(paused).await {
+                        Ok(_) => state.previous_everyone_permissions = Some(permissions),
+                        Err(e) => self.raid_mode_step_failed(guild_id, "pause invites", e).await,
+                    }
+                }
+            }
+        }
+
+        for channel_id in &lockdown.restricted_channels {
+            let channel = match self.cache.get_channel(*channel_id).await {
+                Some(channel) => channel,
+                None => continue,
+            };
+            let previous = channel
+                .get_permission_overrides()
+                .iter()
+                .find(|overwrite| overwrite.kind == PermissionOverwriteType::Role(everyone))
+                .map(|overwrite| (overwrite.allow, overwrite.deny));
+            let (allow, deny) = previous.unwrap_or_else(|| (Permissions::empty(), Permissions::empty()));
+
+            match self
+                .http
+                .update_channel_permission(
+                    *channel_id,
+                    allow - Permissions::SEND_MESSAGES,
+                    deny | Permissions::SEND_MESSAGES,
+                )
+                .role(everyone)
+                .await
+            {
+                Ok(_) => state.restricted_channels.push((*channel_id, previous)),
+                Err(e) => self.raid_mode_step_failed(guild_id, "restrict a channel", e).await,
+            }
+        }
+
+        self.datastore
+            .cache_pool
+            .set(&raid_mode_key(guild_id), &state, None)
+            .await?;
+        self.log_raid_mode(guild_id, moderator, true);
+
+        Ok(Some(state))
+    }
+
+    /// Puts back everything raid mode changed. Returns `None` if raid mode wasn't on.
+    pub async fn disable_raid_mode(
+        &self,
+        guild_id: GuildId,
+        moderator: UserId,
+    ) -> Result<Option<RaidModeState>, DatabaseError> {
+        let state = match self.get_raid_mode(guild_id).await? {
+            Some(state) => state,
+            None => return Ok(None),
+        };
+        let everyone = RoleId(guild_id.0);
+
+        if let Some(level) = state.previous_verification_level {
+            if let Err(e) = self.http.update_guild(guild_id).verification_level(level).await {
+                self.raid_mode_step_failed(guild_id, "restore the verification level", e)
+                    .await;
+            }
+        }
+
+        if let Some(permissions) = state.previous_everyone_permissions {
+            if let Err(e) = self.http.update_role(guild_id, everyone).permissions(permissions).await {
+                self.raid_mode_step_failed(guild_id, "resume invites", e).await;
+            }
+        }
+
+        for (channel_id, previous) in &state.restricted_channels {
+            let result = match previous {
+                Some((allow, deny)) => {
+                    self.http
+                        .update_channel_permission(*channel_id, *allow, *deny)
+                        .role(everyone)
+                        .await
+                }
+                None => self.http.delete_channel_permission(*channel_id, everyone.0).await,
+            };
+            if let Err(e) = result {
+                self.raid_mode_step_failed(guild_id, "unrestrict a channel", e).await;
+            }
+        }
+
+        self.datastore.cache_pool.delete(&raid_mode_key(guild_id)).await?;
+        self.log_raid_mode(guild_id, Some(moderator), false);
+
+        Ok(Some(state))
+    }
+
+    /// Counts a join towards the raid detector, turning raid mode on when there are too many at once.
+    pub async fn track_join_for_raids(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        let config = self.get_config(guild_id).await?;
+        if !config.raid_mode.auto || self.raid_mode_active(guild_id).await? {
+            return Ok(());
+        }
+
+        // The window starts at the first join, later joins don't push it back
+        let redis_cache = &self.datastore.cache_pool;
+        let key = format!("raid_joins:{}", guild_id);
+        let joins = redis_cache.count_hit(&key, config.raid_mode.join_window.max(1)).await?;
+        if joins >= config.raid_mode.join_threshold as isize {
+            redis_cache.delete(&key).await?;
+            self.enable_raid_mode(guild_id, None).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn raid_mode_step_failed(&self, guild_id: GuildId, step: &str, error: twilight_http::Error) {
+        self.track_http_error(&error).await;
+        log::warn!("Raid mode failed to {} in {}: {}", step, guild_id, error);
+    }
+
+    fn log_raid_mode(&self, guild_id: GuildId, moderator: Option<UserId>, enabled: bool) {
+        self.log(LogData {
+            log_type: LogType::RaidModeChanged {
+                enabled,
+                automatic: moderator.is_none(),
+            },
+            guild: guild_id,
+            source_channel: None,
+            source_user: moderator.unwrap_or(self.bot_user.id),
+            timestamp: Utc::now(),
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use hyper::StatusCode;
+use tokio::sync::Mutex;
+use twilight_http::Error;
+
+use super::BotContext;
+
+/// How far back rate limit hits count towards the pressure.
+const PRESSURE_WINDOW: Duration = Duration::from_secs(60);
+/// This many hits within the window starts the slowdown.
+const SLOWDOWN_THRESHOLD: usize = 10;
+/// The slowdown only ends once the hits within the window drop to this, so it doesn't flap on and off.
+const RECOVERY_THRESHOLD: usize = 2;
+
+/// Keeps track of how often discord has been telling us to slow down.
+///
+/// Under pressure, features nobody is waiting on (attachment mirroring, command suggestions) are paused so
+/// the requests that matter (commands, moderation, logging) get the room instead.
+pub struct RateLimitMonitor {
+    hits: Mutex<VecDeque<Instant>>,
+    slowed_down: AtomicBool,
+}
+
+impl RateLimitMonitor {
+    pub fn new() -> Self {
+        RateLimitMonitor {
+            hits: Mutex::new(VecDeque::new()),
+            slowed_down: AtomicBool::new(false),
+        }
+    }
+
+    /// If non-essential features should hold off on making requests right now.
+    pub fn is_slowed_down(&self) -> bool {
+        self.slowed_down.load(Ordering::SeqCst)
+    }
+}
+
+/// Works out if the slowdown should be active, given if it is right now and how many recent hits there were.
+pub fn should_slow_down(slowed_down: bool, recent_hits: usize) -> bool {
+    if slowed_down {
+        recent_hits > RECOVERY_THRESHOLD
+    } else {
+        recent_hits >= SLOWDOWN_THRESHOLD
+    }
+}
+
+/// If discord rejected the request because we are sending too many.
+pub fn is_rate_limited(error: &Error) -> bool {
+    matches!(error, Error::Response { status, .. } if *status == StatusCode::TOO_MANY_REQUESTS)
+}
+
+impl BotContext {
+    /// Records the outcome of a request to the discord api, anything that isn't a rate limit is ignored.
+    pub async fn track_http_error(&self, error: &Error) {
+        if !is_rate_limited(error) {
+            return;
+        }
+
+        self.stats.rate_limit_stats.hits.inc();
+        self.rate_limits.hits.lock().await.push_back(Instant::now());
+        self.update_rate_limit_pressure().await;
+    }
+
+    /// Drops hits that fell out of the window and switches the slowdown on or off when needed.
+    ///
+    /// Gets called on every hit, and periodically with the health checks to notice when the pressure is gone.
+    pub async fn update_rate_limit_pressure(&self) -> usize {
+        let mut hits = self.rate_limits.hits.lock().await;
+        while hits.front().map_or(false, |hit| hit.elapsed() > PRESSURE_WINDOW) {
+            hits.pop_front();
+        }
+        let recent = hits.len();
+        drop(hits);
+
+        let was_slowed_down = self.rate_limits.is_slowed_down();
+        let slow_down = should_slow_down(was_slowed_down, recent);
+        if slow_down != was_slowed_down {
+            self.rate_limits.slowed_down.store(slow_down, Ordering::SeqCst);
+            self.stats.rate_limit_stats.slowed_down.set(slow_down as i64);
+            if slow_down {
+                log::warn!(
+                    "Got rate limited {} times in the last {} seconds, pausing non-essential features",
+                    recent,
+                    PRESSURE_WINDOW.as_secs()
+                );
+            } else {
+                log::info!("Rate limit pressure is gone, resuming non-essential features");
+            }
+        }
+        recent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::should_slow_down;
+
+    #[test]
+    fn slows_down_under_pressure() {
+        assert!(!should_slow_down(false, 3));
+        assert!(should_slow_down(false, 10));
+    }
+
+    #[test]
+    fn only_recovers_once_pressure_is_low() {
+        assert!(should_slow_down(true, 5));
+        assert!(!should_slow_down(true, 2));
+    }
+}
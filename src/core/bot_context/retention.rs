@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use super::BotContext;
+use crate::core::guild_stats::STATS_RETENTION_DAYS;
+use crate::gearbot_error;
+
+/// How often expired archives and attachments get cleaned up.
+const JANITOR_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+impl BotContext {
+    /// Periodically removes archives and attachments that are past the retention period of their guild, and guild
+    /// statistics that are too old to matter.
+    pub async fn run_retention_janitor(&self) {
+        if !self.is_primary_cluster() {
+            return;
+        }
+
+        let stats = &self.stats.janitor_stats;
+        loop {
+            tokio::time::sleep(JANITOR_INTERVAL).await;
+
+            match self.datastore.prune_expired_archives().await {
+                Ok(pruned) => {
+                    stats.pruned_archives.inc_by(pruned.rows);
+                    stats.reclaimed_archive_bytes.inc_by(pruned.bytes);
+                    if pruned.rows > 0 {
+                        log::info!("Removed {} expired archives ({} bytes)", pruned.rows, pruned.bytes);
+                    }
+                }
+                Err(e) => gearbot_error!("Failed to remove expired archives: {}", e),
+            }
+
+            match self.datastore.prune_expired_attachments().await {
+                Ok(pruned) => {
+                    stats.pruned_attachments.inc_by(pruned.rows);
+                    stats.reclaimed_attachment_bytes.inc_by(pruned.bytes);
+                    if pruned.rows > 0 {
+                        log::info!("Removed {} expired attachments ({} bytes)", pruned.rows, pruned.bytes);
+                    }
+                }
+                Err(e) => gearbot_error!("Failed to remove expired attachments: {}", e),
+            }
+
+            if let Err(e) = self.datastore.prune_guild_stats(STATS_RETENTION_DAYS).await {
+                gearbot_error!("Failed to remove old guild statistics: {}", e);
+            }
+            if let Err(e) = self.datastore.prune_channel_activity(STATS_RETENTION_DAYS).await {
+                gearbot_error!("Failed to remove old channel activity: {}", e);
+            }
+            if let Err(e) = self.datastore.prune_command_usage(STATS_RETENTION_DAYS).await {
+                gearbot_error!("Failed to remove old command usage: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use twilight_model::guild::Permissions;
+use twilight_model::id::{GuildId, RoleId, UserId};
+
+use super::BotContext;
+use crate::cache::CachedRole;
+use crate::error::RoleGuardError;
+
+/// Roles with any of these can do real damage, so we never hand them out ourselves.
+const ELEVATED_PERMISSIONS: Permissions = Permissions::from_bits_truncate(
+    Permissions::ADMINISTRATOR.bits()
+        | Permissions::MANAGE_GUILD.bits()
+        | Permissions::MANAGE_ROLES.bits()
+        | Permissions::MANAGE_CHANNELS.bits()
+        | Permissions::MANAGE_WEBHOOKS.bits()
+        | Permissions::BAN_MEMBERS.bits()
+        | Permissions::KICK_MEMBERS.bits()
+        | Permissions::MANAGE_MESSAGES.bits()
+        | Permissions::MENTION_EVERYONE.bits(),
+);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoleMutation {
+    Add,
+    Remove,
+}
+
+/// Where the bot stands in a guild, every role we add or remove has to get past this first.
+///
+/// When a moderator asked for the change, it also has to be below their own highest role so we can't be used to
+/// reach past it.
+pub struct RoleGuard {
+    guild_id: GuildId,
+    top_position: i64,
+    permissions: Permissions,
+}
+
+impl RoleGuard {
+    /// `actor_top` is the position of the highest role of the moderator asking for this, see
+    /// [`BotContext::actor_top_position`]. Changes nobody asked for and changes by the owner don't have one.
+    pub fn check(
+        &self,
+        role: &CachedRole,
+        mutation: RoleMutation,
+        actor_top: Option<i64>,
+    ) -> Result<(), RoleGuardError> {
+        if !self.permissions.contains(Permissions::MANAGE_ROLES) {
+            return Err(RoleGuardError::MissingManageRoles);
+        }
+        if role.id.0 == self.guild_id.0 {
+            return Err(RoleGuardError::Everyone);
+        }
+        // Integration and booster roles are handed out by discord itself
+        if role.managed {
+            return Err(RoleGuardError::Managed(role.id));
+        }
+        if role.position >= self.top_position {
+            return Err(RoleGuardError::AboveBot(role.id));
+        }
+        if actor_top.map_or(false, |top| role.position >= top) {
+            return Err(RoleGuardError::AboveActor(role.id));
+        }
+        // Taking power away is fine, handing it out automatically is not
+        if mutation == RoleMutation::Add && role.permissions.intersects(ELEVATED_PERMISSIONS) {
+            return Err(RoleGuardError::Elevated(role.id));
+        }
+
+        Ok(())
+    }
+
+    /// The position of the highest role of the bot, it can't touch roles at or above it.
+    pub fn top_position(&self) -> i64 {
+        self.top_position
+    }
+}
+
+impl BotContext {
+    pub async fn get_role_guard(&self, guild_id: GuildId) -> RoleGuard {
+        RoleGuard {
+            guild_id,
+            top_position: self.top_role_position(guild_id, self.bot_user.id).await,
+            permissions: self.get_guild_permissions_for(&guild_id, &self.bot_user.id).await,
+        }
+    }
+
+    /// The position a moderator can't reach through us, they can only change roles below their own highest role.
+    /// The owner can reach everything.
+    pub async fn actor_top_position(&self, guild_id: GuildId, actor: UserId) -> Option<i64> {
+        let owner = self.cache.get_guild(&guild_id).await.map(|guild| guild.owner_id);
+        if owner == Some(actor) {
+            None
+        } else {
+            Some(self.top_role_position(guild_id, actor).await)
+        }
+    }
+
+    async fn top_role_position(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+        let guild = self.cache.get_guild(&guild_id).await;
+        let member = self.cache.get_member(&guild_id, &user_id).await;
+
+        let mut top_position = 0;
+        if let (Some(guild), Some(member)) = (guild, member) {
+            for role_id in &member.roles {
+                if let Some(role) = guild.get_role(role_id).await {
+                    top_position = top_position.max(role.position);
+                }
+            }
+        }
+        top_position
+    }
+
+    /// Checks if we can safely add or remove a role, without actually doing it. The actor is the moderator that
+    /// asked for it, if any.
+    pub async fn check_role_mutation(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        mutation: RoleMutation,
+        actor: Option<UserId>,
+    ) -> Result<(), RoleGuardError> {
+        let role = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild.get_role(&role_id).await,
+            None => None,
+        };
+        let role = role.ok_or(RoleGuardError::UnknownRole(role_id))?;
+        let actor_top = match actor {
+            Some(actor) => self.actor_top_position(guild_id, actor).await,
+            None => None,
+        };
+
+        self.get_role_guard(guild_id).await.check(&role, mutation, actor_top)
+    }
+
+    pub async fn add_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+        actor: Option<UserId>,
+    ) -> Result<(), RoleGuardError> {
+        self.check_role_mutation(guild_id, role_id, RoleMutation::Add, actor)
+            .await?;
+        self.http.add_guild_member_role(guild_id, user_id, role_id).await?;
+        Ok(())
+    }
+
+    pub async fn remove_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+        actor: Option<UserId>,
+    ) -> Result<(), RoleGuardError> {
+        self.check_role_mutation(guild_id, role_id, RoleMutation::Remove, actor)
+            .await?;
+        self.http.remove_guild_member_role(guild_id, user_id, role_id).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures::cached_role;
+
+    fn guard() -> RoleGuard {
+        RoleGuard {
+            guild_id: GuildId(1),
+            top_position: 10,
+            permissions: Permissions::MANAGE_ROLES,
+        }
+    }
+
+    #[test]
+    fn allows_harmless_roles_below_the_bot() {
+        let muted = cached_role(2, 5, Permissions::empty());
+        assert!(guard().check(&muted, RoleMutation::Add, None).is_ok());
+        assert!(guard().check(&muted, RoleMutation::Remove, None).is_ok());
+    }
+
+    #[test]
+    fn refuses_unsafe_roles() {
+        let guard = guard();
+        assert!(matches!(
+            guard.check(&cached_role(2, 10, Permissions::empty()), RoleMutation::Add, None),
+            Err(RoleGuardError::AboveBot(_))
+        ));
+        assert!(matches!(
+            guard.check(&cached_role(1, 0, Permissions::empty()), RoleMutation::Add, None),
+            Err(RoleGuardError::Everyone)
+        ));
+
+        let mut managed = cached_role(2, 5, Permissions::empty());
+        managed.managed = true;
+        assert!(matches!(
+            guard.check(&managed, RoleMutation::Remove, None),
+            Err(RoleGuardError::Managed(_))
+        ));
+
+        let moderator = cached_role(2, 5, Permissions::BAN_MEMBERS);
+        assert!(matches!(
+            guard.check(&moderator, RoleMutation::Add, None),
+            Err(RoleGuardError::Elevated(_))
+        ));
+        assert!(guard.check(&moderator, RoleMutation::Remove, None).is_ok());
+    }
+
+    #[test]
+    fn refuses_roles_out_of_reach_of_the_actor() {
+        let guard = guard();
+        let role = cached_role(2, 5, Permissions::empty());
+        assert!(guard.check(&role, RoleMutation::Add, Some(6)).is_ok());
+        assert!(matches!(
+            guard.check(&role, RoleMutation::Remove, Some(5)),
+            Err(RoleGuardError::AboveActor(_))
+        ));
+    }
+
+    #[test]
+    fn needs_manage_roles() {
+        let guard = RoleGuard {
+            permissions: Permissions::empty(),
+            ..guard()
+        };
+        assert!(matches!(
+            guard.check(&cached_role(2, 5, Permissions::empty()), RoleMutation::Add, None),
+            Err(RoleGuardError::MissingManageRoles)
+        ));
+    }
+}
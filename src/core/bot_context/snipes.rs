@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{ChannelId, MessageId, UserId};
+
+use super::BotContext;
+use crate::error::DatabaseError;
+
+/// How long deleted and edited messages stay snipeable, in seconds.
+const SNIPE_DURATION: u32 = 60 * 10;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SnipedMessage {
+    pub id: MessageId,
+    pub author: UserId,
+    pub content: String,
+    /// Only present for edits, this is what the message said before it got edited.
+    pub previous_content: Option<String>,
+    pub timestamp: i64,
+}
+
+impl BotContext {
+    pub async fn store_snipe(&self, channel_id: ChannelId, snipe: &SnipedMessage) -> Result<(), DatabaseError> {
+        let key = if snipe.previous_content.is_some() {
+            format!("editsnipe:{}", channel_id)
+        } else {
+            format!("snipe:{}", channel_id)
+        };
+
+        self.datastore.cache_pool.set(&key, snipe, Some(SNIPE_DURATION)).await
+    }
+
+    pub async fn get_snipe(&self, channel_id: ChannelId, edit: bool) -> Result<Option<SnipedMessage>, DatabaseError> {
+        let key = if edit {
+            format!("editsnipe:{}", channel_id)
+        } else {
+            format!("snipe:{}", channel_id)
+        };
+
+        self.datastore.cache_pool.get(&key).await
+    }
+
+    /// Marks a message as never to be sniped, used when we remove content ourselves (automod, censoring, ...).
+    ///
+    /// This needs to happen *before* the message is actually deleted to avoid racing with the delete event.
+    pub async fn exclude_from_snipe(&self, message_id: MessageId) -> Result<(), DatabaseError> {
+        self.datastore
+            .cache_pool
+            .set(&format!("snipe_exclude:{}", message_id), &true, Some(SNIPE_DURATION))
+            .await
+    }
+
+    pub async fn is_excluded_from_snipe(&self, message_id: MessageId) -> Result<bool, DatabaseError> {
+        Ok(self
+            .datastore
+            .cache_pool
+            .get::<bool>(&format!("snipe_exclude:{}", message_id))
+            .await?
+            .is_some())
+    }
+
+    /// Remembers the latest content of an edited message, so consecutive edits show the right previous version.
+    pub async fn store_edited_content(&self, message_id: MessageId, content: &str) -> Result<(), DatabaseError> {
+        self.datastore
+            .cache_pool
+            .set(
+                &format!("edited_content:{}", message_id),
+                &content,
+                Some(SNIPE_DURATION),
+            )
+            .await
+    }
+
+    pub async fn get_edited_content(&self, message_id: MessageId) -> Result<Option<String>, DatabaseError> {
+        self.datastore
+            .cache_pool
+            .get(&format!("edited_content:{}", message_id))
+            .await
+    }
+}
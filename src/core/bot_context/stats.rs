@@ -1,11 +1,15 @@
 use std::sync::atomic::AtomicU64;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use twilight_model::channel::Message;
+use twilight_model::id::GuildId;
 
+use super::sys_info::SysInfo;
 use super::{BotContext, ShardState};
 use crate::GIT_VERSION;
-use prometheus::{Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
+use prometheus::{Gauge, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
 
 use crate::core::guild_config::{LogCategory, LogStyle};
 use log::info;
@@ -80,15 +84,76 @@ pub struct ShardStats {
     pub disconnected: IntGauge,
 }
 
+/// How often the runtime gets probed for how long it takes to get to a new task.
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct LogpumpStats {
     pub active_pumps: IntGauge,
     pub pending_logs: IntGauge,
+    pub dropped_logs: IntCounter,
+    /// Logs handed to pumps that didn't finish yet, per guild. Guilds are only in here while they have any, so
+    /// the amount of labels doesn't grow with the amount of guilds that ever logged something.
+    queued_logs: IntGaugeVec,
+    queued_per_guild: Mutex<HashMap<GuildId, i64>>,
     pub embed: LogTypeCounters,
     pub text: LogTypeCounters,
 }
 
 pub struct LogTypeCounters {
     pub general: IntCounter,
+    pub commands: IntCounter,
+    pub messages: IntCounter,
+    pub members: IntCounter,
+    pub watchlist: IntCounter,
+}
+
+pub struct RateLimitStats {
+    pub hits: IntCounter,
+    pub slowed_down: IntGauge,
+}
+
+pub struct JanitorStats {
+    pub pruned_archives: IntCounter,
+    pub pruned_attachments: IntCounter,
+    pub reclaimed_archive_bytes: IntCounter,
+    pub reclaimed_attachment_bytes: IntCounter,
+}
+
+pub struct RuntimeStats {
+    /// Gateway events that are still being handled.
+    pub event_tasks: IntGauge,
+    /// Pumps that were spawned, including the ones waiting for another pump of their channel to finish.
+    pub pump_tasks: IntGauge,
+    /// How long a freshly spawned task waited before it got to run. Tokio doesn't expose its worker states in
+    /// this version, workers that are blocked show up as this going up instead.
+    pub scheduling_delay: Gauge,
+}
+
+pub struct SchedulerStats {
+    pub guild_purges: IntGauge,
+    pub profile_reverts: IntGauge,
+}
+
+/// Keeps a task counted for as long as it's alive, also when it panics.
+pub struct TaskGuard(IntGauge);
+
+impl TaskGuard {
+    pub fn new(gauge: &IntGauge) -> Self {
+        gauge.inc();
+        TaskGuard(gauge.clone())
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+pub struct ProcessStats {
+    pub resident_memory: IntGauge,
+    pub virtual_memory: IntGauge,
+    pub threads: IntGauge,
 }
 
 pub struct BotStats {
@@ -106,6 +171,11 @@ pub struct BotStats {
     pub command_counts: IntCounterVec,
     pub total_command_counts: AtomicU64,
     pub logpump_stats: LogpumpStats,
+    pub process_stats: ProcessStats,
+    pub runtime_stats: RuntimeStats,
+    pub scheduler_backlog: SchedulerStats,
+    pub rate_limit_stats: RateLimitStats,
+    pub janitor_stats: JanitorStats,
 }
 
 impl BotStats {
@@ -122,7 +192,17 @@ impl BotStats {
         let command_counts = IntCounterVec::new(Opts::new("commands", "Executed commands"), &["name"]).unwrap();
         let active_pumps = IntGauge::with_opts(Opts::new("active_pumps", "Active logpumps")).unwrap();
         let pending_logs = IntGauge::with_opts(Opts::new("pending_logs", "Pending log messages")).unwrap();
+        let dropped_logs = IntCounter::with_opts(Opts::new("dropped_logs", "Log messages that could not be delivered")).unwrap();
+        let queued_logs = IntGaugeVec::new(Opts::new("queued_logs", "Log messages waiting on a pump per guild"), &["guild"]).unwrap();
         let pumped_logs = IntCounterVec::new(Opts::new("pumped_logs", "Successfully send logs"), &["type", "category"]).unwrap();
+        let process_counter = IntGaugeVec::new(Opts::new("process", "Resource usage of the process"), &["resource"]).unwrap();
+        let runtime_tasks = IntGaugeVec::new(Opts::new("runtime_tasks", "Tasks that are alive"), &["kind"]).unwrap();
+        let scheduling_delay = Gauge::with_opts(Opts::new("runtime_scheduling_delay_seconds", "How long the last probe task waited to be polled")).unwrap();
+        let scheduler_backlog = IntGaugeVec::new(Opts::new("scheduler_backlog", "Scheduled jobs that were due on the last run"), &["kind"]).unwrap();
+        let rate_limit_hits = IntCounter::with_opts(Opts::new("rate_limit_hits", "Requests discord rejected with a 429")).unwrap();
+        let rate_limit_slowdown = IntGauge::with_opts(Opts::new("rate_limit_slowdown", "If non-essential features are paused because of rate limits")).unwrap();
+        let pruned_rows = IntCounterVec::new(Opts::new("pruned_rows", "Rows removed because their retention period ran out"), &["kind"]).unwrap();
+        let reclaimed_bytes = IntCounterVec::new(Opts::new("reclaimed_bytes", "Space freed by removing expired rows"), &["kind"]).unwrap();
 
         let mut static_labels = HashMap::new();
         static_labels.insert(String::from("cluster"), cluster_id.to_string());
@@ -138,7 +218,17 @@ impl BotStats {
         registry.register(Box::new(command_counts.clone())).unwrap();
         registry.register(Box::new(active_pumps.clone())).unwrap();
         registry.register(Box::new(pending_logs.clone())).unwrap();
+        registry.register(Box::new(dropped_logs.clone())).unwrap();
+        registry.register(Box::new(queued_logs.clone())).unwrap();
         registry.register(Box::new(pumped_logs.clone())).unwrap();
+        registry.register(Box::new(process_counter.clone())).unwrap();
+        registry.register(Box::new(runtime_tasks.clone())).unwrap();
+        registry.register(Box::new(scheduling_delay.clone())).unwrap();
+        registry.register(Box::new(scheduler_backlog.clone())).unwrap();
+        registry.register(Box::new(rate_limit_hits.clone())).unwrap();
+        registry.register(Box::new(rate_limit_slowdown.clone())).unwrap();
+        registry.register(Box::new(pruned_rows.clone())).unwrap();
+        registry.register(Box::new(reclaimed_bytes.clone())).unwrap();
 
         BotStats {
             registry,
@@ -215,13 +305,48 @@ impl BotStats {
             logpump_stats: LogpumpStats {
                 active_pumps,
                 pending_logs,
+                dropped_logs,
+                queued_logs,
+                queued_per_guild: Mutex::new(HashMap::new()),
                 embed: LogTypeCounters {
-                    general: pumped_logs.get_metric_with_label_values(&["embed", "general"]).unwrap()
+                    general: pumped_logs.get_metric_with_label_values(&["embed", "general"]).unwrap(),
+                    commands: pumped_logs.get_metric_with_label_values(&["embed", "commands"]).unwrap(),
+                    messages: pumped_logs.get_metric_with_label_values(&["embed", "messages"]).unwrap(),
+                    members: pumped_logs.get_metric_with_label_values(&["embed", "members"]).unwrap(),
+                    watchlist: pumped_logs.get_metric_with_label_values(&["embed", "watchlist"]).unwrap(),
                 },
                 text: LogTypeCounters {
-                    general: pumped_logs.get_metric_with_label_values(&["text", "general"]).unwrap()
+                    general: pumped_logs.get_metric_with_label_values(&["text", "general"]).unwrap(),
+                    commands: pumped_logs.get_metric_with_label_values(&["text", "commands"]).unwrap(),
+                    messages: pumped_logs.get_metric_with_label_values(&["text", "messages"]).unwrap(),
+                    members: pumped_logs.get_metric_with_label_values(&["text", "members"]).unwrap(),
+                    watchlist: pumped_logs.get_metric_with_label_values(&["text", "watchlist"]).unwrap(),
                 }
             },
+            process_stats: ProcessStats {
+                resident_memory: process_counter.get_metric_with_label_values(&["resident_memory"]).unwrap(),
+                virtual_memory: process_counter.get_metric_with_label_values(&["virtual_memory"]).unwrap(),
+                threads: process_counter.get_metric_with_label_values(&["threads"]).unwrap(),
+            },
+            runtime_stats: RuntimeStats {
+                event_tasks: runtime_tasks.get_metric_with_label_values(&["events"]).unwrap(),
+                pump_tasks: runtime_tasks.get_metric_with_label_values(&["pumps"]).unwrap(),
+                scheduling_delay,
+            },
+            scheduler_backlog: SchedulerStats {
+                guild_purges: scheduler_backlog.get_metric_with_label_values(&["guild_purge"]).unwrap(),
+                profile_reverts: scheduler_backlog.get_metric_with_label_values(&["profile_revert"]).unwrap(),
+            },
+            rate_limit_stats: RateLimitStats {
+                hits: rate_limit_hits,
+                slowed_down: rate_limit_slowdown,
+            },
+            janitor_stats: JanitorStats {
+                pruned_archives: pruned_rows.get_metric_with_label_values(&["archive"]).unwrap(),
+                pruned_attachments: pruned_rows.get_metric_with_label_values(&["attachment"]).unwrap(),
+                reclaimed_archive_bytes: reclaimed_bytes.get_metric_with_label_values(&["archive"]).unwrap(),
+                reclaimed_attachment_bytes: reclaimed_bytes.get_metric_with_label_values(&["attachment"]).unwrap(),
+            },
         }
     }
 
@@ -241,10 +366,64 @@ impl BotStats {
         }
     }
 
+    /// Collects fresh system information and updates the process metrics with it.
+    pub fn collect_sys_info(&self) -> Option<SysInfo> {
+        let info = SysInfo::collect()?;
+        self.process_stats.resident_memory.set(info.resident_memory as i64);
+        self.process_stats.virtual_memory.set(info.virtual_memory as i64);
+        self.process_stats.threads.set(info.threads as i64);
+        Some(info)
+    }
+
+    /// Periodically spawns an empty task to see how long it takes the runtime to get to it.
+    pub async fn run_runtime_probe(&self) {
+        loop {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+
+            let spawned = Instant::now();
+            if let Ok(delay) = tokio::spawn(async move { spawned.elapsed() }).await {
+                self.runtime_stats.scheduling_delay.set(delay.as_secs_f64());
+            }
+        }
+    }
+
+    /// Counts logs that were handed to a pump for the guild.
+    pub fn logs_queued(&self, guild_id: GuildId, count: usize) {
+        self.update_queued_logs(guild_id, count as i64);
+    }
+
+    /// Stops counting logs of the guild once their pump is done with them, delivered or not.
+    pub fn logs_unqueued(&self, guild_id: GuildId, count: usize) {
+        self.update_queued_logs(guild_id, -(count as i64));
+    }
+
+    fn update_queued_logs(&self, guild_id: GuildId, change: i64) {
+        let stats = &self.logpump_stats;
+        let mut per_guild = stats.queued_per_guild.lock().unwrap();
+        let queued = per_guild.entry(guild_id).or_insert(0);
+        *queued += change;
+
+        let label = guild_id.to_string();
+        if *queued > 0 {
+            stats.queued_logs.with_label_values(&[label.as_str()]).set(*queued);
+        } else {
+            per_guild.remove(&guild_id);
+            let _ = stats.queued_logs.remove_label_values(&[label.as_str()]);
+        }
+    }
+
     pub async fn logpump_logged(&self, log_style: &LogStyle, category: &LogCategory) {
         match (log_style, category) {
             (LogStyle::Text, LogCategory::GENERAL) => self.logpump_stats.text.general.inc(),
             (LogStyle::Embed, LogCategory::GENERAL) => self.logpump_stats.embed.general.inc(),
+            (LogStyle::Text, LogCategory::COMMANDS) => self.logpump_stats.text.commands.inc(),
+            (LogStyle::Embed, LogCategory::COMMANDS) => self.logpump_stats.embed.commands.inc(),
+            (LogStyle::Text, LogCategory::MESSAGES) => self.logpump_stats.text.messages.inc(),
+            (LogStyle::Embed, LogCategory::MESSAGES) => self.logpump_stats.embed.messages.inc(),
+            (LogStyle::Text, LogCategory::MEMBERS) => self.logpump_stats.text.members.inc(),
+            (LogStyle::Embed, LogCategory::MEMBERS) => self.logpump_stats.embed.members.inc(),
+            (LogStyle::Text, LogCategory::WATCHLIST) => self.logpump_stats.text.watchlist.inc(),
+            (LogStyle::Embed, LogCategory::WATCHLIST) => self.logpump_stats.embed.watchlist.inc(),
         }
     }
 }
@@ -310,6 +489,9 @@ impl BotContext {
         info!("Shard {} is now {:?}", shard, new_state);
         self.get_state_metric(&new_state).inc();
         shards.insert(shard, new_state);
+        drop(shards);
+
+        self.update_health().await;
     }
 
     fn get_state_metric(&self, state: &ShardState) -> &IntGauge {
@@ -325,9 +507,3 @@ impl BotContext {
         }
     }
 }
-
-#[derive(Debug)]
-pub struct LoadingState {
-    to_load: u32,
-    loaded: u32,
-}
@@ -13,9 +13,7 @@ impl BotContext {
         activity_type: ActivityType,
         message: String,
     ) -> Result<(), EventHandlerError> {
-        for shard_id in self.scheme_info.cluster_id * self.scheme_info.shards_per_cluster
-            ..self.scheme_info.cluster_id * self.scheme_info.shards_per_cluster + self.scheme_info.shards_per_cluster
-        {
+        for shard_id in self.scheme_info.shard_range() {
             self.set_shard_activity(shard_id, status, activity_type, message.clone())
                 .await?;
         }
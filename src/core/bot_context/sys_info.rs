@@ -0,0 +1,66 @@
+use std::fs;
+
+/// A snapshot of the resources used by this process.
+///
+/// Collected from procfs, so this is only available on Linux hosts.
+#[derive(Debug, PartialEq)]
+pub struct SysInfo {
+    /// Resident set size in bytes
+    pub resident_memory: u64,
+    /// Virtual memory size in bytes
+    pub virtual_memory: u64,
+    pub threads: u64,
+}
+
+impl SysInfo {
+    pub fn collect() -> Option<Self> {
+        let status = fs::read_to_string("/proc/self/status").ok()?;
+        parse_proc_status(&status)
+    }
+}
+
+fn parse_proc_status(status: &str) -> Option<SysInfo> {
+    let mut resident_memory = None;
+    let mut virtual_memory = None;
+    let mut threads = None;
+
+    for line in status.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("VmRSS:") => resident_memory = parts.next().and_then(|kb| kb.parse::<u64>().ok()).map(|kb| kb * 1024),
+            Some("VmSize:") => virtual_memory = parts.next().and_then(|kb| kb.parse::<u64>().ok()).map(|kb| kb * 1024),
+            Some("Threads:") => threads = parts.next().and_then(|count| count.parse().ok()),
+            _ => {}
+        }
+    }
+
+    Some(SysInfo {
+        resident_memory: resident_memory?,
+        virtual_memory: virtual_memory?,
+        threads: threads.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_status() {
+        let status = "Name:\tgearbot\nVmPeak:\t  300000 kB\nVmSize:\t  250000 kB\nVmRSS:\t   51200 kB\nThreads:\t14\n";
+
+        assert_eq!(
+            parse_proc_status(status),
+            Some(SysInfo {
+                resident_memory: 51200 * 1024,
+                virtual_memory: 250000 * 1024,
+                threads: 14,
+            })
+        );
+    }
+
+    #[test]
+    fn missing_memory_is_none() {
+        assert_eq!(parse_proc_status("Name:\tgearbot\nThreads:\t14\n"), None);
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{Duration, Utc};
+use twilight_model::channel::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType};
+use twilight_model::channel::ChannelType;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::cache::CachedChannel;
+use crate::core::temp_voice::TempVoiceConfig;
+use crate::error::EventHandlerError;
+use crate::utils;
+
+/// Rooms younger than this are never cleaned up, the member they are for might not have been moved in yet.
+const ROOM_GRACE_SECONDS: i64 = 30;
+
+impl BotContext {
+    /// Gives members that joined a hub a room of their own, and removes the rooms that are empty now.
+    pub async fn update_temp_voice(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_id: Option<ChannelId>,
+    ) -> Result<(), EventHandlerError> {
+        let config = self.get_config(guild_id).await?;
+        let temp_voice = &config.temp_voice;
+        if temp_voice.hubs.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(hub) = channel_id.filter(|channel| temp_voice.hubs.contains(channel)) {
+            self.create_temp_room(guild_id, user_id, hub, temp_voice).await?;
+        }
+
+        let guild = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild,
+            None => return Ok(()),
+        };
+        let grace_cutoff = utils::snowflake_at(Utc::now() - Duration::seconds(ROOM_GRACE_SECONDS));
+        for room in self.datastore.get_temp_rooms(guild_id).await? {
+            if room.0 > grace_cutoff || !guild.voice_channel_members(room).await.is_empty() {
+                continue;
+            }
+
+            // Failing to delete it (already gone or no permission) shouldn't keep it tracked forever
+            if let Err(e) = self.http.delete_channel(room).await {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to delete temporary room {} in {}: {}", room, guild_id, e);
+            }
+            self.datastore.remove_temp_room(room).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_temp_room(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        hub: ChannelId,
+        config: &TempVoiceConfig,
+    ) -> Result<(), EventHandlerError> {
+        let parent_id = match self.cache.get_channel(hub).await.as_deref() {
+            Some(CachedChannel::VoiceChannel { parent_id, .. }) => *parent_id,
+            _ => None,
+        };
+        let nickname = self
+            .cache
+            .get_member(&guild_id, &user_id)
+            .await
+            .and_then(|member| member.nickname.clone());
+        let display_name = match nickname {
+            Some(nickname) => nickname,
+            None => match self.cache.get_user(user_id).await {
+                Some(user) => user.username.clone(),
+                None => user_id.to_string(),
+            },
+        };
+
+        let owner_overwrite = PermissionOverwrite {
+            allow: config.owner_permissions,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(user_id),
+        };
+        let mut request = match self
+            .http
+            .create_guild_channel(guild_id, config.room_name(&display_name))
+        {
+            Ok(request) => request
+                .kind(ChannelType::GuildVoice)
+                .permission_overwrites(vec![owner_overwrite]),
+            Err(e) => {
+                log::debug!(
+                    "Not creating a temporary room in {}, the name is invalid: {}",
+                    guild_id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+        if let Some(parent_id) = parent_id {
+            request = request.parent_id(parent_id);
+        }
+        if config.user_limit > 0 {
+            request = request.user_limit(config.user_limit);
+        }
+
+        let room = match request.await {
+            Ok(channel) => channel.id(),
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to create a temporary room in {}: {}", guild_id, e);
+                return Ok(());
+            }
+        };
+        self.datastore.insert_temp_room(room, guild_id, user_id).await?;
+
+        // If they left the hub already the room stays empty and gets cleaned up with the next voice update
+        if let Err(e) = self.http.update_guild_member(guild_id, user_id).channel_id(room).await {
+            self.track_http_error(&e).await;
+            log::debug!(
+                "Failed to move {} into their temporary room in {}: {}",
+                user_id,
+                guild_id,
+                e
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use twilight_model::channel::{ChannelType, Reaction};
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::BotContext;
+use crate::core::tickets::{self, TicketOutcome};
+use crate::error::{DatabaseError, EventHandlerError};
+use crate::utils::Emoji;
+
+impl BotContext {
+    /// Opens a private ticket channel for the member, unless they have too many open already.
+    pub async fn open_ticket(&self, guild_id: GuildId, user_id: UserId) -> Result<TicketOutcome, DatabaseError> {
+        let config = self.get_config(guild_id).await?;
+        let ticket_config = &config.tickets;
+        if !ticket_config.enabled {
+            return Ok(TicketOutcome::Disabled);
+        }
+
+        let open = self.datastore.get_open_tickets(guild_id, user_id).await?;
+        if ticket_config.max_open > 0 && open.len() >= ticket_config.max_open as usize {
+            if let Some(channel_id) = open.iter().find_map(|ticket| ticket.channel_id) {
+                return Ok(TicketOutcome::AtLimit(ChannelId(channel_id as u64)));
+            }
+        }
+
+        // Roles that are gone would get the whole request rejected
+        let staff_roles = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => {
+                let roles = guild.roles.read().await;
+                ticket_config
+                    .staff_roles
+                    .iter()
+                    .copied()
+                    .filter(|role| roles.contains_key(role))
+                    .collect()
+            }
+            None => ticket_config.staff_roles.clone(),
+        };
+        let overwrites = tickets::overwrites(guild_id, user_id, self.bot_user.id, &staff_roles);
+
+        let id = self.datastore.create_ticket(guild_id, user_id).await?;
+        let mut request = match self.http.create_guild_channel(guild_id, tickets::channel_name(id)) {
+            Ok(request) => request.kind(ChannelType::GuildText).permission_overwrites(overwrites),
+            Err(e) => {
+                log::debug!("Not creating ticket {} in {}, the name is invalid: {}", id, guild_id, e);
+                self.datastore.remove_ticket(guild_id, id).await?;
+                return Ok(TicketOutcome::Failed);
+            }
+        };
+        if let Some(category) = ticket_config.category {
+            request = request.parent_id(category);
+        }
+
+        let channel_id = match request.await {
+            Ok(channel) => channel.id(),
+            Err(e) => {
+                self.track_http_error(&e).await;
+                log::debug!("Failed to create the channel for ticket {} in {}: {}", id, guild_id, e);
+                self.datastore.remove_ticket(guild_id, id).await?;
+                return Ok(TicketOutcome::Failed);
+            }
+        };
+        self.datastore.set_ticket_channel(guild_id, id, channel_id).await?;
+
+        if !ticket_config.welcome.is_empty() {
+            match self
+                .http
+                .create_message(channel_id)
+                .content(ticket_config.welcome_message(user_id))
+            {
+                Ok(request) => {
+                    if let Err(e) = request.await {
+                        self.track_http_error(&e).await;
+                    }
+                }
+                Err(e) => log::debug!("Ticket welcome in {} is not a valid message: {}", guild_id, e),
+            }
+        }
+
+        Ok(TicketOutcome::Opened(channel_id))
+    }
+
+    /// Opens a ticket for members that react to the ticket panel. The reaction is taken away again so the panel
+    /// stays clean, the ticket itself is where they find out how it went.
+    pub async fn open_ticket_from_panel(&self, reaction: &Reaction) -> Result<(), EventHandlerError> {
+        let guild_id = match reaction.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(()),
+        };
+        if reaction.user_id == self.bot_user.id || !Emoji::Yes.matches(&reaction.emoji) {
+            return Ok(());
+        }
+        let config = self.get_config(guild_id).await?;
+        if config.tickets.panel != Some(reaction.message_id) {
+            return Ok(());
+        }
+
+        if let Err(e) = self
+            .http
+            .delete_reaction(
+                reaction.channel_id,
+                reaction.message_id,
+                Emoji::Yes.to_reaction(),
+                reaction.user_id,
+            )
+            .await
+        {
+            self.track_http_error(&e).await;
+        }
+
+        if let TicketOutcome::Failed = self.open_ticket(guild_id, reaction.user_id).await? {
+            log::debug!(
+                "Failed to open a ticket from the panel for {} in {}",
+                reaction.user_id,
+                guild_id
+            );
+        }
+        Ok(())
+    }
+}
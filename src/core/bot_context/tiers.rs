@@ -0,0 +1,38 @@
+use twilight_model::id::{GuildId, UserId};
+
+use super::BotContext;
+use crate::core::Tier;
+use crate::error::DatabaseError;
+
+/// Tiers rarely change, but are checked by every command with tiered limits.
+const TIER_CACHE_DURATION: u32 = 10 * 60;
+
+fn tier_key(guild_id: GuildId) -> String {
+    format!("guild_tier:{}", guild_id)
+}
+
+impl BotContext {
+    pub async fn get_tier(&self, guild_id: GuildId) -> Result<Tier, DatabaseError> {
+        let key = tier_key(guild_id);
+        let redis_cache = &self.datastore.cache_pool;
+        if let Some(tier) = redis_cache.get(&key).await? {
+            return Ok(tier);
+        }
+
+        let tier = self.datastore.get_guild_tier(guild_id).await?;
+        redis_cache.set(&key, &tier, Some(TIER_CACHE_DURATION)).await?;
+        Ok(tier)
+    }
+
+    pub async fn grant_tier(&self, guild_id: GuildId, tier: Tier, granted_by: UserId) -> Result<(), DatabaseError> {
+        if tier == Tier::Free {
+            self.datastore.remove_guild_tier(guild_id).await?;
+        } else {
+            self.datastore.set_guild_tier(guild_id, tier, granted_by).await?;
+        }
+        // All clusters share the cache, so this takes effect everywhere right away
+        self.datastore.cache_pool.delete(&tier_key(guild_id)).await?;
+        log::info!("Guild {} was set to the {} tier by {}", guild_id, tier, granted_by);
+        Ok(())
+    }
+}
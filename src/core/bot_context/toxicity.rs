@@ -0,0 +1,82 @@
+use chrono::Utc;
+use twilight_model::channel::Message;
+use twilight_model::id::GuildId;
+
+use super::BotContext;
+use crate::core::guild_stats::GuildStat;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::toxicity::{KeywordClassifier, MessageClassifier};
+use crate::core::GuildFeatures;
+use crate::error::EventHandlerError;
+
+impl BotContext {
+    /// Scores a new message and gives its author heat for it, by the weights the guild set for every attribute.
+    ///
+    /// Goes through the toxicity API when there is one, or the keywords of the guild if it can't be used.
+    pub async fn run_toxicity_check(&self, message: &Message, guild_id: GuildId) -> Result<(), EventHandlerError> {
+        if message.content.trim().is_empty() {
+            return Ok(());
+        }
+
+        let config = self.get_config(guild_id).await?;
+        let toxicity = &config.toxicity;
+        if !config.features.contains(GuildFeatures::AUTOMOD) || !config.heat.enabled || !toxicity.is_enabled() {
+            return Ok(());
+        }
+        if let Some(member) = self.cache.get_member(&guild_id, &message.author.id).await {
+            if member.roles.iter().any(|role| toxicity.exempt_roles.contains(role)) {
+                return Ok(());
+            }
+        }
+
+        let scores = match &self.message_classifier {
+            Some(classifier) => match classifier.score(&message.content).await {
+                Ok(scores) => Some(scores),
+                Err(e) => {
+                    log::warn!(
+                        "Failed to score a message in {}, using keywords instead: {}",
+                        guild_id,
+                        e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+        let scores = match scores {
+            Some(scores) => scores,
+            // Matching keywords can't fail
+            None => KeywordClassifier::new(&toxicity.keywords)
+                .score(&message.content)
+                .await
+                .unwrap_or_default(),
+        };
+
+        let (heat, counted) = toxicity.heat_for(&scores);
+        if heat == 0 {
+            return Ok(());
+        }
+
+        let rule = counted
+            .iter()
+            .map(|score| format!("{} {:.0}%", score.attribute, score.score * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.log(LogData {
+            log_type: LogType::AutomodTriggered {
+                rule: format!("toxicity: {}", rule),
+                channel: message.channel_id,
+                content: message.content.clone(),
+                actions: vec![format!("+{} heat", heat)],
+            },
+            guild: guild_id,
+            source_channel: Some(message.channel_id),
+            source_user: message.author.id,
+            timestamp: Utc::now(),
+        });
+        self.count_guild_stat(guild_id, GuildStat::AutomodTrigger).await;
+
+        let mut deleted = false;
+        self.apply_heat(message, guild_id, &config, heat, &mut deleted).await
+    }
+}
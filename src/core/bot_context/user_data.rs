@@ -0,0 +1,28 @@
+use twilight_model::id::UserId;
+
+use super::BotContext;
+use crate::database::user_data::{UserDataDeletion, UserDataExport};
+use crate::error::DatabaseError;
+
+impl BotContext {
+    pub async fn export_user_data(&self, user_id: UserId) -> Result<UserDataExport, DatabaseError> {
+        self.datastore.get_user_data(user_id).await
+    }
+
+    /// Removes everything we have on a user, except for the infractions their guilds want to hang on to.
+    pub async fn delete_user_data(&self, user_id: UserId) -> Result<UserDataDeletion, DatabaseError> {
+        let mut retention = vec![];
+        for guild_id in self.datastore.get_infraction_guilds(user_id).await? {
+            let config = self.get_config(guild_id).await?;
+            retention.push((guild_id, config.infraction_retention_days));
+        }
+
+        let deletion = self.datastore.delete_user_data(user_id, &retention).await?;
+        log::info!(
+            "Deleted the data of user {} on request ({} infractions kept)",
+            user_id,
+            deletion.kept_infractions
+        );
+        Ok(deletion)
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+use twilight_model::user::User;
+
+use super::BotContext;
+use crate::core::logpump::{LogData, LogType};
+use crate::error::DatabaseError;
+use crate::utils;
+
+/// What we last saw of a watched user, gateway updates don't tell what actually changed.
+pub struct WatchedUser {
+    name: Option<String>,
+    voice: Option<ChannelId>,
+}
+
+impl BotContext {
+    /// Starts watching a user, returns `false` if they were already being watched.
+    pub async fn watch_user(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        moderator: UserId,
+    ) -> Result<bool, DatabaseError> {
+        if !self.datastore.start_watch(guild_id, user_id, moderator).await? {
+            return Ok(false);
+        }
+
+        let watched = self.current_state(guild_id, user_id).await;
+        if let Some(watchlist) = self.watchlists.write().await.get_mut(&guild_id) {
+            watchlist.insert(user_id, watched);
+        }
+        Ok(true)
+    }
+
+    /// Stops watching a user, returns `false` if they weren't being watched.
+    pub async fn unwatch_user(&self, guild_id: GuildId, user_id: UserId) -> Result<bool, DatabaseError> {
+        if let Some(watchlist) = self.watchlists.write().await.get_mut(&guild_id) {
+            watchlist.remove(&user_id);
+        }
+        self.datastore.end_watch(guild_id, user_id).await
+    }
+
+    pub async fn watch_message(&self, message: &Message, guild_id: GuildId) -> Result<(), DatabaseError> {
+        if !self.is_watched(guild_id, message.author.id).await? {
+            return Ok(());
+        }
+
+        let mut content = message.content.clone();
+        for attachment in &message.attachments {
+            content += &format!("\n{}", attachment.url);
+        }
+        self.log_watched(
+            guild_id,
+            message.author.id,
+            Some(message.channel_id),
+            LogType::WatchedMessage {
+                channel: message.channel_id,
+                content,
+                link: format!(
+                    "https://discord.com/channels/{}/{}/{}",
+                    guild_id, message.channel_id, message.id
+                ),
+            },
+        );
+        Ok(())
+    }
+
+    pub async fn watch_join(&self, guild_id: GuildId, user_id: UserId) -> Result<(), DatabaseError> {
+        if !self.is_watched(guild_id, user_id).await? {
+            return Ok(());
+        }
+
+        let created = utils::snowflake_timestamp(user_id.0).timestamp();
+        self.log_watched(guild_id, user_id, None, LogType::WatchedJoin { created });
+        Ok(())
+    }
+
+    /// Logs watched users moving between voice channels, other voice updates (like muting) are ignored.
+    pub async fn watch_voice(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel: Option<ChannelId>,
+    ) -> Result<(), DatabaseError> {
+        self.load_watchlist(guild_id).await?;
+        let changed = match self
+            .watchlists
+            .write()
+            .await
+            .get_mut(&guild_id)
+            .and_then(|watchlist| watchlist.get_mut(&user_id))
+        {
+            Some(watched) if watched.voice != channel => {
+                watched.voice = channel;
+                true
+            }
+            _ => false,
+        };
+
+        if changed {
+            self.log_watched(guild_id, user_id, channel, LogType::WatchedVoice { channel });
+        }
+        Ok(())
+    }
+
+    /// Logs watched users changing their username or nickname.
+    pub async fn watch_name(
+        &self,
+        guild_id: GuildId,
+        user: &User,
+        nickname: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        self.load_watchlist(guild_id).await?;
+        let user_id = user.id;
+        let name = watched_name(&user.name, &user.discriminator, nickname);
+        let before = match self
+            .watchlists
+            .write()
+            .await
+            .get_mut(&guild_id)
+            .and_then(|watchlist| watchlist.get_mut(&user_id))
+        {
+            Some(watched) if watched.name.as_ref() != Some(&name) => watched.name.replace(name.clone()),
+            _ => return Ok(()),
+        };
+
+        // Without a name to compare to there is no telling if it changed
+        if let Some(before) = before {
+            self.log_watched(
+                guild_id,
+                user_id,
+                None,
+                LogType::WatchedNameChange { before, after: name },
+            );
+        }
+        Ok(())
+    }
+
+    /// If the user is on the watchlist of the guild, loading it first if needed.
+    pub async fn is_watched(&self, guild_id: GuildId, user_id: UserId) -> Result<bool, DatabaseError> {
+        self.load_watchlist(guild_id).await?;
+        Ok(self
+            .watchlists
+            .read()
+            .await
+            .get(&guild_id)
+            .map_or(false, |watchlist| watchlist.contains_key(&user_id)))
+    }
+
+    /// Loads who is watched in the guild the first time it's needed.
+    async fn load_watchlist(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        if self.watchlists.read().await.contains_key(&guild_id) {
+            return Ok(());
+        }
+
+        let users = self.datastore.get_watched_users(guild_id).await?;
+        let mut watchlist = HashMap::with_capacity(users.len());
+        for user_id in users {
+            watchlist.insert(user_id, self.current_state(guild_id, user_id).await);
+        }
+        self.watchlists.write().await.entry(guild_id).or_insert(watchlist);
+        Ok(())
+    }
+
+    async fn current_state(&self, guild_id: GuildId, user_id: UserId) -> WatchedUser {
+        let member = self.cache.get_member(&guild_id, &user_id).await;
+        let name = match self.cache.get_user(user_id).await {
+            Some(user) => Some(watched_name(
+                &user.username,
+                &user.discriminator,
+                member.as_ref().and_then(|member| member.nickname.as_deref()),
+            )),
+            None => None,
+        };
+        let voice = match self.cache.get_guild(&guild_id).await {
+            Some(guild) => guild.voice_states.read().await.get(&user_id).copied(),
+            None => None,
+        };
+
+        WatchedUser { name, voice }
+    }
+
+    fn log_watched(&self, guild_id: GuildId, user_id: UserId, source_channel: Option<ChannelId>, log_type: LogType) {
+        self.log(LogData {
+            log_type,
+            guild: guild_id,
+            source_channel,
+            source_user: user_id,
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+/// The name a watched user goes by, changes to it are logged.
+fn watched_name(username: &str, discriminator: &str, nickname: Option<&str>) -> String {
+    match nickname {
+        Some(nickname) => format!("{}#{} ({})", username, discriminator, nickname),
+        None => format!("{}#{}", username, discriminator),
+    }
+}
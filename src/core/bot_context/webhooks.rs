@@ -0,0 +1,69 @@
+use twilight_model::id::ChannelId;
+
+use super::BotContext;
+use crate::core::webhook_server::Delivery;
+use crate::core::webhooks::{hash_token, render, RATE_LIMIT, RATE_LIMIT_WINDOW_SECONDS};
+use crate::error::DatabaseError;
+
+impl BotContext {
+    /// Posts a delivery to the channel of the endpoint the token belongs to, rendered with its template.
+    ///
+    /// Like GitHub deliveries, this works for the guilds of all clusters. The rate limit is kept in redis so it
+    /// holds no matter which cluster gets the deliveries.
+    pub async fn receive_custom_webhook(&self, token: &str, body: &[u8]) -> Result<Delivery, DatabaseError> {
+        let endpoint = match self.datastore.get_webhook_endpoint_by_token(&hash_token(token)).await? {
+            Some(endpoint) => endpoint,
+            None => return Ok(Delivery::Unauthorized),
+        };
+
+        let key = format!("webhook_hits:{}:{}", endpoint.guild_id, endpoint.id);
+        if self
+            .datastore
+            .cache_pool
+            .count_hit(&key, RATE_LIMIT_WINDOW_SECONDS)
+            .await?
+            > RATE_LIMIT
+        {
+            return Ok(Delivery::RateLimited);
+        }
+
+        let payload = match serde_json::from_slice(body) {
+            Ok(payload) => payload,
+            Err(_) => return Ok(Delivery::Malformed),
+        };
+        let content = render(&endpoint.template, &payload);
+        if content.is_empty() {
+            return Ok(Delivery::Ignored);
+        }
+
+        // Nobody gets pinged, the http client doesn't allow any mentions unless told otherwise
+        let request = match self
+            .http
+            .create_message(ChannelId(endpoint.channel_id as u64))
+            .content(content)
+        {
+            Ok(request) => request,
+            Err(e) => {
+                log::debug!(
+                    "Delivery for webhook {} in {} is not a valid message: {}",
+                    endpoint.id,
+                    endpoint.guild_id,
+                    e
+                );
+                return Ok(Delivery::Malformed);
+            }
+        };
+        if let Err(e) = request.await {
+            self.track_http_error(&e).await;
+            log::debug!(
+                "Failed to relay a delivery for webhook {} in {}: {}",
+                endpoint.id,
+                endpoint.guild_id,
+                e
+            );
+            return Ok(Delivery::Ignored);
+        }
+
+        Ok(Delivery::Relayed)
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use twilight_model::id::{RoleId, UserId};
+
+use crate::cache::CachedMember;
+
+/// How many members get their role changed between two progress updates, and two checks for cancellation.
+pub const BULK_ROLE_CHUNK_SIZE: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BulkRoleMode {
+    Add,
+    Remove,
+}
+
+/// Which members a bulk role change applies to, members have to match all of it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemberFilter {
+    /// Roles the member needs to have, all of them.
+    pub has_roles: Vec<RoleId>,
+    /// Only members that joined before this.
+    pub joined_before: Option<DateTime<Utc>>,
+    /// Only bots when `Some(true)`, only humans when `Some(false)`.
+    pub bots: Option<bool>,
+}
+
+impl MemberFilter {
+    pub fn matches(&self, member: &CachedMember, bot: bool) -> bool {
+        if self.bots.map_or(false, |bots| bots != bot) {
+            return false;
+        }
+        if !self.has_roles.iter().all(|role| member.roles.contains(role)) {
+            return false;
+        }
+        match self.joined_before {
+            Some(before) => member
+                .joined_at
+                .as_ref()
+                .and_then(|joined| DateTime::parse_from_str(joined, "%FT%T%.f%z").ok())
+                .map_or(false, |joined| joined.with_timezone(&Utc) < before),
+            None => true,
+        }
+    }
+}
+
+/// The members the role change would actually do something for, the ones matching the filter that don't already
+/// have (or lack) the role.
+pub fn bulk_role_targets<'a>(
+    members: impl IntoIterator<Item = (&'a CachedMember, bool)>,
+    filter: &MemberFilter,
+    role: RoleId,
+    mode: BulkRoleMode,
+) -> Vec<UserId> {
+    let mut targets: Vec<UserId> = members
+        .into_iter()
+        .filter(|(member, bot)| filter.matches(member, *bot))
+        .filter(|(member, _)| member.roles.contains(&role) == (mode == BulkRoleMode::Remove))
+        .map(|(member, _)| member.user_id)
+        .collect();
+
+    targets.sort_unstable();
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures::cached_member;
+
+    #[test]
+    fn filters_members() {
+        let early = cached_member(1, "2020-01-01T00:00:00.000000+00:00", &[10]);
+        let late = cached_member(2, "2021-06-01T00:00:00.000000+00:00", &[10, 20]);
+        let bot = cached_member(3, "2020-01-01T00:00:00.000000+00:00", &[]);
+        let members = vec![(&early, false), (&late, false), (&bot, true)];
+
+        let everyone = MemberFilter::default();
+        assert_eq!(
+            bulk_role_targets(members.clone(), &everyone, RoleId(20), BulkRoleMode::Add),
+            vec![UserId(1), UserId(3)]
+        );
+        assert_eq!(
+            bulk_role_targets(members.clone(), &everyone, RoleId(20), BulkRoleMode::Remove),
+            vec![UserId(2)]
+        );
+
+        let filter = MemberFilter {
+            has_roles: vec![RoleId(10)],
+            joined_before: Some(
+                DateTime::parse_from_rfc3339("2021-01-01T00:00:00+00:00")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            bots: Some(false),
+        };
+        assert_eq!(
+            bulk_role_targets(members.clone(), &filter, RoleId(30), BulkRoleMode::Add),
+            vec![UserId(1)]
+        );
+
+        let bots = MemberFilter {
+            bots: Some(true),
+            ..MemberFilter::default()
+        };
+        assert_eq!(
+            bulk_role_targets(members, &bots, RoleId(30), BulkRoleMode::Add),
+            vec![UserId(3)]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType};
+use twilight_model::channel::ChannelType;
+use twilight_model::id::{ChannelId, RoleId};
+
+use crate::cache::CachedChannel;
+
+/// How many templates a single guild can have.
+pub const MAX_CHANNEL_TEMPLATES: usize = 25;
+
+/// The settings and overwrites of a channel, everything needed to create it again.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ChannelTemplate {
+    pub kind: ChannelType,
+    pub name: String,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub nsfw: bool,
+    #[serde(default)]
+    pub slowmode: Option<u64>,
+    #[serde(default)]
+    pub bitrate: Option<u64>,
+    #[serde(default)]
+    pub user_limit: Option<u64>,
+    #[serde(default)]
+    pub parent_id: Option<ChannelId>,
+    pub position: i64,
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+}
+
+impl ChannelTemplate {
+    /// Snapshots a guild channel, `None` for DMs and store channels as those can't be created.
+    pub fn from_channel(channel: &CachedChannel) -> Option<Self> {
+        let template = match channel {
+            CachedChannel::TextChannel {
+                position,
+                permission_overrides,
+                name,
+                topic,
+                nsfw,
+                slowmode,
+                parent_id,
+                ..
+            } => ChannelTemplate {
+                kind: ChannelType::GuildText,
+                name: name.clone(),
+                topic: topic.clone(),
+                nsfw: *nsfw,
+                slowmode: *slowmode,
+                bitrate: None,
+                user_limit: None,
+                parent_id: *parent_id,
+                position: *position,
+                permission_overwrites: permission_overrides.clone(),
+            },
+            CachedChannel::VoiceChannel {
+                position,
+                permission_overrides,
+                name,
+                bitrate,
+                user_limit,
+                parent_id,
+                ..
+            } => ChannelTemplate {
+                kind: ChannelType::GuildVoice,
+                name: name.clone(),
+                topic: None,
+                nsfw: false,
+                slowmode: None,
+                bitrate: Some(*bitrate),
+                user_limit: *user_limit,
+                parent_id: *parent_id,
+                position: *position,
+                permission_overwrites: permission_overrides.clone(),
+            },
+            CachedChannel::Category {
+                position,
+                permission_overrides,
+                name,
+                ..
+            } => ChannelTemplate::bare(ChannelType::GuildCategory, name, *position, None, permission_overrides),
+            CachedChannel::AnnouncementsChannel {
+                position,
+                permission_overrides,
+                name,
+                parent_id,
+                ..
+            } => ChannelTemplate::bare(
+                ChannelType::GuildNews,
+                name,
+                *position,
+                *parent_id,
+                permission_overrides,
+            ),
+            CachedChannel::StageChannel {
+                position,
+                permission_overrides,
+                name,
+                parent_id,
+                ..
+            } => ChannelTemplate::bare(
+                ChannelType::GuildStageVoice,
+                name,
+                *position,
+                *parent_id,
+                permission_overrides,
+            ),
+            CachedChannel::StoreChannel { .. } | CachedChannel::DM { .. } | CachedChannel::GroupDM { .. } => {
+                return None
+            }
+        };
+
+        Some(template)
+    }
+
+    fn bare(
+        kind: ChannelType,
+        name: &str,
+        position: i64,
+        parent_id: Option<ChannelId>,
+        permission_overwrites: &[PermissionOverwrite],
+    ) -> Self {
+        ChannelTemplate {
+            kind,
+            name: name.to_string(),
+            topic: None,
+            nsfw: false,
+            slowmode: None,
+            bitrate: None,
+            user_limit: None,
+            parent_id,
+            position,
+            permission_overwrites: permission_overwrites.to_vec(),
+        }
+    }
+
+    /// The overwrites that can still be used, discord refuses the whole channel if one of them is for a role that
+    /// doesn't exist anymore.
+    pub fn usable_overwrites(&self, role_exists: impl Fn(RoleId) -> bool) -> Vec<PermissionOverwrite> {
+        self.permission_overwrites
+            .iter()
+            .filter(|overwrite| match overwrite.kind {
+                PermissionOverwriteType::Role(role_id) => role_exists(role_id),
+                PermissionOverwriteType::Member(_) => true,
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Template names are used as a single argument, so 1 to 32 letters, numbers, dashes and underscores.
+pub fn is_valid_template_name(name: &str) -> bool {
+    (1..=32).contains(&name.chars().count()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twilight_model::guild::Permissions;
+    use twilight_model::id::{GuildId, UserId};
+
+    #[test]
+    fn snapshots_channels_and_drops_missing_roles() {
+        let channel = CachedChannel::TextChannel {
+            id: ChannelId(10),
+            guild_id: GuildId(1),
+            position: 3,
+            permission_overrides: vec![
+                PermissionOverwrite {
+                    allow: Permissions::empty(),
+                    deny: Permissions::SEND_MESSAGES,
+                    kind: PermissionOverwriteType::Role(RoleId(1)),
+                },
+                PermissionOverwrite {
+                    allow: Permissions::SEND_MESSAGES,
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Role(RoleId(2)),
+                },
+                PermissionOverwrite {
+                    allow: Permissions::MANAGE_MESSAGES,
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Member(UserId(3)),
+                },
+            ],
+            name: String::from("general"),
+            topic: Some(String::from("Talk here")),
+            nsfw: false,
+            slowmode: Some(5),
+            parent_id: Some(ChannelId(4)),
+        };
+
+        let template = ChannelTemplate::from_channel(&channel).unwrap();
+        assert_eq!(template.kind, ChannelType::GuildText);
+        assert_eq!(template.topic.as_deref(), Some("Talk here"));
+        assert_eq!(template.slowmode, Some(5));
+        assert_eq!(template.parent_id, Some(ChannelId(4)));
+
+        let usable = template.usable_overwrites(|role_id| role_id != RoleId(2));
+        assert_eq!(usable.len(), 2);
+        assert!(usable
+            .iter()
+            .all(|overwrite| overwrite.kind != PermissionOverwriteType::Role(RoleId(2))));
+
+        let json = serde_json::to_value(&template).unwrap();
+        assert_eq!(serde_json::from_value::<ChannelTemplate>(json).unwrap(), template);
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(is_valid_template_name("daily-reset_2"));
+        assert!(!is_valid_template_name(""));
+        assert!(!is_valid_template_name("two words"));
+        assert!(!is_valid_template_name(&"a".repeat(33)));
+    }
+}
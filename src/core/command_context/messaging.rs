@@ -1,14 +1,24 @@
+use std::time::Duration;
+
 use fluent_bundle::FluentArgs;
 use twilight_model::{
     channel::{embed::Embed, Message},
     id::{ChannelId, MessageId},
 };
 
-use crate::translation::GearBotString;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::embeds::{self, SafeEmbed, MAX_EMBEDS};
+use crate::utils::Emoji;
 
 use super::CommandContext;
 use crate::error::CommandError;
 
+const MESSAGE_LIMIT: usize = 2000;
+/// Replies that would take more messages than this get attached as a file instead.
+const MAX_MESSAGES: usize = 3;
+/// How long a confirmation prompt waits for an answer before giving up.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
 impl CommandContext {
     pub async fn send_message(
         &self,
@@ -81,34 +91,159 @@ impl CommandContext {
         Ok(updated_message_handle)
     }
 
+    pub async fn update_message_embed(
+        &self,
+        embed: Embed,
+        channel_id: ChannelId,
+        msg_id: MessageId,
+    ) -> Result<Message, CommandError> {
+        let updated_message_handle = self
+            .bot_context
+            .http
+            .update_message(channel_id, msg_id)
+            .content(None)?
+            .embed(embed)?
+            .await?;
+
+        Ok(updated_message_handle)
+    }
+
     pub async fn reply(&self, key: GearBotString, args: FluentArgs<'_>) -> Result<Message, CommandError> {
         let translated = self.translate_with_args(key, &args);
         let sent_msg_handle = self
             .bot_context
             .http
-            .create_message(self.message.channel.get_id())
+            .create_message(self.reply_channel())
             .content(translated)?
             .await?;
 
         Ok(sent_msg_handle)
     }
 
+    /// Replies with what a destructive command would have done, making it clear nothing actually happened.
+    pub async fn reply_dry_run(&self, key: GearBotString, args: FluentArgs<'_>) -> Result<Message, CommandError> {
+        let translated = format!(
+            "{}\n{}",
+            self.translate(GearBotString::DryRunNotice),
+            self.translate_with_args(key, &args)
+        );
+        self.reply_raw(translated).await
+    }
+
+    /// Asks the author to confirm with a reaction before going ahead, returns if they did.
+    ///
+    /// Saying no or not answering in time tells them nothing was changed.
+    pub async fn confirm(&self, key: GearBotString, args: FluentArgs<'_>) -> Result<bool, CommandError> {
+        let instructions = FluArgs::with_capacity(3)
+            .add("gearyes", Emoji::Yes.for_chat())
+            .add("gearno", Emoji::No.for_chat())
+            .add("seconds", CONFIRMATION_TIMEOUT.as_secs())
+            .generate();
+        let prompt = format!(
+            "{}\n{}",
+            self.translate_with_args(key, &args),
+            self.translate_with_args(GearBotString::ConfirmationInstructions, &instructions)
+        );
+        let message = self.reply_raw(prompt).await?;
+
+        let http = &self.bot_context.http;
+        http.create_reaction(message.channel_id, message.id, Emoji::Yes.to_reaction())
+            .await?;
+        http.create_reaction(message.channel_id, message.id, Emoji::No.to_reaction())
+            .await?;
+
+        let confirmed = self
+            .bot_context
+            .await_confirmation(message.id, self.message.author.id, CONFIRMATION_TIMEOUT)
+            .await;
+        if !confirmed {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            self.reply(GearBotString::ConfirmationCancelled, args).await?;
+        }
+
+        Ok(confirmed)
+    }
+
     pub async fn reply_raw<T: std::fmt::Display>(&self, message: T) -> Result<Message, CommandError> {
         let sent_msg_handle = self
             .bot_context
             .http
-            .create_message(self.message.channel.get_id())
+            .create_message(self.reply_channel())
             .content(message.to_string())?
             .await?;
 
         Ok(sent_msg_handle)
     }
 
+    /// Replies with content that might be over the message limit, splitting it over multiple messages.
+    ///
+    /// Returns the first message that was sent.
+    pub async fn reply_long(&self, content: impl Into<String>) -> Result<Message, CommandError> {
+        let content = content.into();
+        let chunks = embeds::split(&content, MESSAGE_LIMIT);
+        if chunks.len() > MAX_MESSAGES {
+            let args = FluArgs::with_capacity(0).generate();
+            return self
+                .reply_with_file(
+                    GearBotString::EmbedOverflow,
+                    args,
+                    String::from("response.txt"),
+                    content.into_bytes(),
+                )
+                .await;
+        }
+
+        let mut chunks = chunks.into_iter();
+        // split always gives at least one chunk
+        let sent = self.reply_raw(chunks.next().unwrap()).await?;
+        for chunk in chunks {
+            self.reply_raw(chunk).await?;
+        }
+
+        Ok(sent)
+    }
+
+    pub async fn reply_with_file(
+        &self,
+        key: GearBotString,
+        args: FluentArgs<'_>,
+        file_name: String,
+        file: Vec<u8>,
+    ) -> Result<Message, CommandError> {
+        let translated = self.translate_with_args(key, &args);
+        let sent_handle = self
+            .bot_context
+            .http
+            .create_message(self.reply_channel())
+            .content(translated)?
+            .attachment(file_name, file)
+            .await?;
+
+        Ok(sent_handle)
+    }
+
+    pub async fn reply_raw_with_file<T: std::fmt::Display>(
+        &self,
+        message: T,
+        file_name: String,
+        file: Vec<u8>,
+    ) -> Result<Message, CommandError> {
+        let sent_handle = self
+            .bot_context
+            .http
+            .create_message(self.reply_channel())
+            .content(message.to_string())?
+            .attachment(file_name, file)
+            .await?;
+
+        Ok(sent_handle)
+    }
+
     pub async fn reply_embed(&self, embed: Embed) -> Result<Message, CommandError> {
         let sent_embed_handle = self
             .bot_context
             .http
-            .create_message(self.message.channel.get_id())
+            .create_message(self.reply_channel())
             .embed(embed)?
             .await?;
 
@@ -125,7 +260,7 @@ impl CommandContext {
         let sent_handle = self
             .bot_context
             .http
-            .create_message(self.message.channel.get_id())
+            .create_message(self.reply_channel())
             .content(translated)?
             .embed(embed)?
             .await?;
@@ -141,11 +276,68 @@ impl CommandContext {
         let sent_handle = self
             .bot_context
             .http
-            .create_message(self.message.channel.get_id())
+            .create_message(self.reply_channel())
             .content(message)?
             .embed(embed)?
             .await?;
 
         Ok(sent_handle)
     }
+
+    /// Replies with an embed that might not fit in a single message.
+    ///
+    /// Content that needs too many embeds is attached as a text file instead of flooding the channel.
+    pub async fn reply_safe_embed(&self, embed: SafeEmbed) -> Result<Message, CommandError> {
+        self.send_safe_embed(None, embed).await
+    }
+
+    pub async fn reply_with_safe_embed(
+        &self,
+        key: GearBotString,
+        args: FluentArgs<'_>,
+        embed: SafeEmbed,
+    ) -> Result<Message, CommandError> {
+        let translated = self.translate_with_args(key, &args);
+        self.send_safe_embed(Some(translated), embed).await
+    }
+
+    pub async fn reply_raw_with_safe_embed(
+        &self,
+        message: impl Into<String>,
+        embed: SafeEmbed,
+    ) -> Result<Message, CommandError> {
+        self.send_safe_embed(Some(message.into()), embed).await
+    }
+
+    async fn send_safe_embed(&self, content: Option<String>, embed: SafeEmbed) -> Result<Message, CommandError> {
+        let embeds = embed.build()?;
+        if embeds.len() > MAX_EMBEDS {
+            let mut text = embed.to_text();
+            if let Some(content) = content {
+                text = format!("{}\n\n{}", content, text);
+            }
+            let args = FluArgs::with_capacity(0).generate();
+            return self
+                .reply_with_file(
+                    GearBotString::EmbedOverflow,
+                    args,
+                    String::from("response.txt"),
+                    text.into_bytes(),
+                )
+                .await;
+        }
+
+        let mut embeds = embeds.into_iter();
+        // build always gives at least one embed
+        let first = embeds.next().unwrap();
+        let sent = match content {
+            Some(content) => self.reply_raw_with_embed(content, first).await?,
+            None => self.reply_embed(first).await?,
+        };
+        for embed in embeds {
+            self.reply_embed(embed).await?;
+        }
+
+        Ok(sent)
+    }
 }
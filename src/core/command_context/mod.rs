@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::Utc;
 use fluent_bundle::FluentArgs;
 use twilight_gateway::shard::Information;
 use twilight_model::channel::embed::Embed;
@@ -10,18 +11,21 @@ use twilight_model::{id::MessageId, user::CurrentUser};
 
 use super::bot_context::BotContext;
 use super::logpump::{LogData, LogType};
-use super::GuildConfig;
+use super::tiers::{Tier, TierLimits};
+use super::{GuildConfig, ModAction};
 use crate::cache::{CachedChannel, CachedGuild, CachedMember, CachedUser};
 use crate::commands::meta::nodes::GearBotPermissions;
 use crate::error::{CommandError, OtherFailure};
 use crate::parser::Parser;
-use crate::translation::GearBotString;
+use crate::translation::{format_date, format_number, GearBotString};
 use twilight_model::id::{ChannelId, UserId};
 
 mod messaging;
 mod object_fetcher;
 mod permissions;
 
+pub use object_fetcher::FetchedMessage;
+
 pub struct CommandMessage {
     pub id: MessageId,
     pub content: String,
@@ -51,10 +55,12 @@ pub struct CommandContext {
     pub bot_context: Arc<BotContext>,
     config: Arc<GuildConfig>,
     pub message: CommandMessage,
-    guild: Arc<CachedGuild>,
+    guild: Option<Arc<CachedGuild>>,
     pub shard: u64,
     pub parser: Parser,
     pub permissions: GearBotPermissions,
+    /// Where replies go when the guild wants them somewhere else than where the command was used.
+    output_channel: Option<ChannelId>,
 }
 
 impl CommandContext {
@@ -62,7 +68,7 @@ impl CommandContext {
         ctx: Arc<BotContext>,
         config: Arc<GuildConfig>,
         message: CommandMessage,
-        guild: Arc<CachedGuild>,
+        guild: Option<Arc<CachedGuild>>,
         shard: u64,
         parser: Parser,
         permissions: GearBotPermissions,
@@ -75,9 +81,20 @@ impl CommandContext {
             shard,
             parser,
             permissions,
+            output_channel: None,
         }
     }
 
+    /// Sends all replies from here on to another channel.
+    pub fn redirect_output(&mut self, channel_id: ChannelId) {
+        self.output_channel = Some(channel_id);
+    }
+
+    /// The channel replies go to.
+    pub fn reply_channel(&self) -> ChannelId {
+        self.output_channel.unwrap_or_else(|| self.message.channel.get_id())
+    }
+
     pub fn get_cluster_info(&self) -> HashMap<u64, Information> {
         self.bot_context.cluster.info()
     }
@@ -102,10 +119,25 @@ impl CommandContext {
             .replace("\\n", "\n")
     }
 
+    pub fn format_number(&self, value: f64) -> String {
+        format_number(value, &self.config.language)
+    }
+
+    pub fn format_date(&self, timestamp: i64) -> String {
+        format_date(timestamp, &self.config.language)
+    }
+
+    /// Writes out a duration in seconds in the language of the guild.
+    pub fn format_duration(&self, seconds: i64, max_parts: usize) -> String {
+        self.bot_context
+            .translations
+            .format_duration(&self.config.language, seconds, max_parts)
+    }
+
     pub async fn set_config(&self, new_config: GuildConfig) -> Result<(), CommandError> {
         // This updates it both in the DB and handles our element guard
         self.bot_context
-            .set_config(self.guild.id, new_config)
+            .set_config(self.get_guild()?.id, new_config)
             .await
             .map_err(|e| CommandError::OtherFailure(OtherFailure::DatabaseError(e)))
     }
@@ -118,17 +150,52 @@ impl CommandContext {
         }
     }
 
-    pub fn get_guild(&self) -> &Arc<CachedGuild> {
-        &self.guild
+    /// Remembers a reversible action taken by the invoking moderator, so they can `undo` it.
+    pub async fn journal(&self, action: ModAction) -> Result<(), CommandError> {
+        self.bot_context
+            .record_mod_action(self.get_guild()?.id, self.message.author.id, action)
+            .await?;
+        Ok(())
+    }
+
+    /// If this is a dry run, destructive commands should only report what they would have done.
+    pub fn is_dry_run(&self) -> bool {
+        self.parser.dry_run
+    }
+
+    /// Makes sure the guild is on at least the given tier, erroring out of the command if it isn't.
+    pub async fn require_tier(&self, tier: Tier) -> Result<(), CommandError> {
+        let guild_tier = self.bot_context.get_tier(self.get_guild()?.id).await?;
+        if guild_tier < tier {
+            Err(CommandError::MissingTier(tier))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The limits that apply to the guild this command is used in.
+    pub async fn get_tier_limits(&self) -> Result<TierLimits, CommandError> {
+        Ok(self.bot_context.get_tier(self.get_guild()?.id).await?.limits())
     }
 
+    pub fn get_guild(&self) -> Result<&Arc<CachedGuild>, CommandError> {
+        self.guild.as_ref().ok_or(CommandError::NoDM)
+    }
+
+    /// Sends a log to the guild this command was used in, logs from DMs have nowhere to go and are dropped.
     pub fn log(&self, log_type: LogType, source_channel: Option<ChannelId>, source_user: UserId) {
+        let guild = match &self.guild {
+            Some(guild) => guild,
+            None => return,
+        };
+
         log::debug!("Logging {:?}", log_type);
         self.bot_context.log(LogData {
             log_type,
-            guild: self.guild.id,
+            guild: guild.id,
             source_channel,
             source_user,
+            timestamp: Utc::now(),
         });
     }
 }
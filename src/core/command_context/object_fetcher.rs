@@ -1,14 +1,36 @@
 use std::sync::Arc;
 
 use twilight_model::{
-    channel::Message,
+    channel::Attachment,
     guild::{Ban, Permissions},
-    id::{ChannelId, MessageId, RoleId, UserId},
+    id::{ChannelId, GuildId, MessageId, RoleId, UserId},
 };
 
 use super::CommandContext;
 use crate::cache::{CachedChannel, CachedMember, CachedRole, CachedUser};
 use crate::error::{CommandError, OtherFailure, ParseError};
+use crate::utils::matchers;
+
+/// A message retrieved by a command, either from the archive or from the api.
+#[derive(Debug)]
+pub struct FetchedMessage {
+    pub id: MessageId,
+    pub author: UserId,
+    pub content: String,
+    pub channel_id: ChannelId,
+    pub guild_id: GuildId,
+    /// Attachment information is not archived, so this is empty when the message came from the archive.
+    pub attachments: Vec<Attachment>,
+}
+
+impl FetchedMessage {
+    pub fn jump_link(&self) -> String {
+        format!(
+            "https://discord.com/channels/{}/{}/{}",
+            self.guild_id, self.channel_id, self.id
+        )
+    }
+}
 
 impl CommandContext {
     pub async fn get_user(&self, user_id: UserId) -> Result<Arc<CachedUser>, CommandError> {
@@ -19,7 +41,8 @@ impl CommandContext {
     }
 
     pub async fn get_member(&self, user_id: &UserId) -> Option<Arc<CachedMember>> {
-        self.bot_context.cache.get_member(&self.guild.id, user_id).await
+        let guild = self.guild.as_ref()?;
+        self.bot_context.cache.get_member(&guild.id, user_id).await
     }
 
     pub async fn get_channel(&self, channel_id: ChannelId) -> Option<Arc<CachedChannel>> {
@@ -27,14 +50,14 @@ impl CommandContext {
     }
 
     pub async fn get_role(&self, role_id: &RoleId) -> Option<Arc<CachedRole>> {
-        match self.guild.get_role(role_id).await {
+        match self.guild.as_ref()?.get_role(role_id).await {
             Some(guard) => Some(guard),
             None => None,
         }
     }
 
     pub async fn get_ban(&self, user_id: UserId) -> Result<Option<Ban>, CommandError> {
-        Ok(self.bot_context.http.ban(self.guild.id, user_id).await?)
+        Ok(self.bot_context.http.ban(self.get_guild()?.id, user_id).await?)
     }
 
     pub async fn get_dm_for_author(&self) -> Result<Arc<CachedChannel>, twilight_http::Error> {
@@ -43,65 +66,88 @@ impl CommandContext {
 
     //TODO: still usable with api v8 since we do not get a channel create event?
     pub async fn get_dm_for_user(&self, user_id: UserId) -> Result<Arc<CachedChannel>, twilight_http::Error> {
-        match self.bot_context.cache.get_dm_channel_for(user_id).await {
-            Some(channel) => Ok(channel),
-            None => {
-                let channel = self.bot_context.http.create_private_channel(user_id).await?;
-                Ok(self.bot_context.cache.insert_private_channel(&channel).await)
-            }
-        }
+        self.bot_context.get_dm_for_user(user_id).await
     }
 
-    pub async fn get_message(&mut self) -> Result<Message, CommandError> {
+    /// Parses the next argument as either a message jump link or a message id (in the current channel) and fetches it.
+    ///
+    /// The archive is checked first, only going to the api if we don't have it stored. Both the bot and the author
+    /// need to be able to read the history of the channel the message is in.
+    pub async fn get_message(&mut self) -> Result<FetchedMessage, CommandError> {
         let input = self.parser.get_next()?;
 
-        let user_id = self.message.author.id;
-        let message_id = input.parse::<u64>().map_err(|_| CommandError::NoDM)?;
-        let channel_id = self.message.channel.get_id();
+        let (channel_id, message_id) = match matchers::get_jump_link_parts(input) {
+            Some((channel_id, message_id)) => (ChannelId(channel_id), MessageId(message_id)),
+            None => {
+                let message_id = input
+                    .parse::<u64>()
+                    .map_err(|_| ParseError::WrongArgumentType(String::from("message link or id")))?;
+                (self.message.channel.get_id(), MessageId(message_id))
+            }
+        };
 
         let channel = match self.bot_context.cache.get_channel(channel_id).await {
-            Some(ch) => ch,
-            None => return Err(CommandError::ParseError(ParseError::UnknownChannel(channel_id.0))),
+            Some(channel) => channel,
+            None => return Err(ParseError::UnknownChannel(channel_id.0).into()),
         };
 
-        if let CachedChannel::TextChannel { name, id, .. } = &*channel {
-            let bot_has_access = self
-                .bot_has_channel_permissions(Permissions::VIEW_CHANNEL & Permissions::READ_MESSAGE_HISTORY)
-                .await;
+        let guild_id = match channel.get_guild_id() {
+            Some(guild_id) => guild_id,
+            None => return Err(ParseError::UnknownChannel(channel_id.0).into()),
+        };
 
-            // Verify that the bot has access
-            if !bot_has_access {
-                return Err(CommandError::ParseError(ParseError::NoChannelAccessBot(name.clone())));
-            }
+        let required = Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY;
 
-            let user_has_access = self
-                .has_channel_permissions(
-                    user_id,
-                    *id,
-                    Permissions::VIEW_CHANNEL & Permissions::READ_MESSAGE_HISTORY,
-                )
-                .await;
-
-            // Verify that the user has access
-            if !user_has_access {
-                return Err(ParseError::NoChannelAccessUser(name.clone()).into());
-            }
+        if !self
+            .has_channel_permissions(self.message.author.id, channel_id, required)
+            .await
+        {
+            return Err(ParseError::NoChannelAccessUser(channel.get_name().to_string()).into());
+        }
+
+        if channel.is_nsfw() && !self.message.channel.is_nsfw() {
+            return Err(ParseError::NSFW.into());
+        }
+
+        // The archive only knows the guild, a message from another channel is one the permission check didn't cover
+        if let Some(stored) = self
+            .bot_context
+            .fetch_user_message(message_id, guild_id)
+            .await?
+            .filter(|stored| stored.channel == channel_id)
+        {
+            return Ok(FetchedMessage {
+                id: message_id,
+                author: stored.author,
+                content: stored.content,
+                channel_id: stored.channel,
+                guild_id: stored.guild,
+                attachments: vec![],
+            });
+        }
+
+        if !self.bot_has_permissions_in_channel(channel_id, required).await {
+            return Err(ParseError::NoChannelAccessBot(channel.get_name().to_string()).into());
+        }
 
-            // All good, fetch the message from the api instead of cache to make sure it's not only up to date but still actually exists
-            let result = self.bot_context.http.message(*id, MessageId(message_id)).await;
-
-            match result {
-                Ok(message) => Ok(message.unwrap()),
-                Err(error) => {
-                    if error.to_string().contains("status: 404") {
-                        Err(CommandError::ParseError(ParseError::UnknownMessage))
-                    } else {
-                        Err(CommandError::OtherFailure(OtherFailure::TwilightHttp(error)))
-                    }
+        // Not archived, go fetch it from the api to make sure it actually still exists
+        match self.bot_context.http.message(channel_id, message_id).await {
+            Ok(Some(message)) => Ok(FetchedMessage {
+                id: message.id,
+                author: message.author.id,
+                content: message.content,
+                channel_id,
+                guild_id,
+                attachments: message.attachments,
+            }),
+            Ok(None) => Err(ParseError::UnknownMessage.into()),
+            Err(error) => {
+                if error.to_string().contains("status: 404") {
+                    Err(CommandError::ParseError(ParseError::UnknownMessage))
+                } else {
+                    Err(CommandError::OtherFailure(OtherFailure::TwilightHttp(error)))
                 }
             }
-        } else {
-            unreachable!()
         }
     }
 }
@@ -16,14 +16,15 @@ impl CommandContext {
     }
 
     pub async fn get_guild_permissions_for(&self, user_id: &UserId) -> Permissions {
-        self.bot_context
-            .get_guild_permissions_for(&self.guild.id, user_id)
-            .await
+        match &self.guild {
+            Some(guild) => self.bot_context.get_guild_permissions_for(&guild.id, user_id).await,
+            None => Permissions::empty(),
+        }
     }
 
+    /// What the bot can do in the channel replies go to.
     pub async fn get_bot_channel_permissions(&self) -> Permissions {
-        self.get_bot_permissions_for_channel(self.message.channel.get_id())
-            .await
+        self.get_bot_permissions_for_channel(self.reply_channel()).await
     }
 
     pub async fn get_bot_permissions_for_channel(&self, channel_id: ChannelId) -> Permissions {
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::embed::Embed;
+use twilight_model::id::{ChannelId, RoleId};
+
+use super::automod::AutomodAction;
+use super::image_moderation::ImageLabel;
+
+/// Discord marks attachments as spoilers by putting this in front of the file name.
+const SPOILER_PREFIX: &str = "SPOILER_";
+/// The label the image classifier gives to nsfw content.
+const NSFW_LABEL: &str = "nsfw";
+
+/// What has to be true about the messages in a channel.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct ChannelPolicy {
+    /// Every attachment has to be marked as a spoiler.
+    pub require_spoilers: bool,
+    /// Embeds with images the classifier labels nsfw are removed, unless the channel itself is marked nsfw.
+    pub block_nsfw_embeds: bool,
+}
+
+impl ChannelPolicy {
+    fn is_enabled(&self) -> bool {
+        self.require_spoilers || self.block_nsfw_embeds
+    }
+}
+
+/// Content rules for channels, enforced like automod: the message is deleted, the author gets told why and
+/// repeat offenders get acted on. Needs the automod feature, and a moderation API for the nsfw checks.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ContentPolicyConfig {
+    /// Policies of specific channels, the others follow the default one.
+    pub channels: HashMap<ChannelId, ChannelPolicy>,
+    pub default: ChannelPolicy,
+    /// How sure the classifier has to be before an embed counts as nsfw, from 0 to 1.
+    pub nsfw_confidence: f32,
+    /// DM the author why their message was removed.
+    pub notify_author: bool,
+    /// How many violations it takes before the escalation action is taken, never if 0.
+    pub escalate_after: u32,
+    /// Seconds after the last violation before the count starts over.
+    pub escalation_window: u32,
+    pub escalation_action: AutomodAction,
+    /// Members with any of these roles can post whatever they want.
+    pub exempt_roles: Vec<RoleId>,
+}
+
+impl Default for ContentPolicyConfig {
+    fn default() -> Self {
+        ContentPolicyConfig {
+            channels: HashMap::new(),
+            default: ChannelPolicy::default(),
+            nsfw_confidence: 0.8,
+            notify_author: true,
+            escalate_after: 0,
+            escalation_window: 60 * 60,
+            escalation_action: AutomodAction::Warn,
+            exempt_roles: vec![],
+        }
+    }
+}
+
+impl ContentPolicyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_enabled() || self.channels.values().any(ChannelPolicy::is_enabled)
+    }
+
+    pub fn policy_for(&self, channel: ChannelId) -> ChannelPolicy {
+        self.channels.get(&channel).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentViolation {
+    MissingSpoiler,
+    NsfwEmbed,
+}
+
+impl fmt::Display for ContentViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentViolation::MissingSpoiler => f.write_str("content policy: attachment without spoiler"),
+            ContentViolation::NsfwEmbed => f.write_str("content policy: nsfw embed"),
+        }
+    }
+}
+
+pub fn is_spoiler(filename: &str) -> bool {
+    filename.starts_with(SPOILER_PREFIX)
+}
+
+/// The images of embeds that can be sent to the classifier.
+pub fn embed_images(embeds: &[Embed]) -> Vec<&str> {
+    embeds
+        .iter()
+        .flat_map(|embed| {
+            embed
+                .image
+                .iter()
+                .filter_map(|image| image.url.as_deref())
+                .chain(embed.thumbnail.iter().filter_map(|thumbnail| thumbnail.url.as_deref()))
+        })
+        .collect()
+}
+
+/// If the labels of an image say it's nsfw, sure enough to act on it.
+pub fn is_nsfw(labels: &[ImageLabel], min_confidence: f32) -> bool {
+    labels
+        .iter()
+        .any(|label| label.name.eq_ignore_ascii_case(NSFW_LABEL) && label.confidence >= min_confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_policy_of_the_channel() {
+        let mut config: ContentPolicyConfig = serde_json::from_str(
+            r#"{"channels": {"5": {"require_spoilers": true}}, "default": {"block_nsfw_embeds": true}}"#,
+        )
+        .unwrap();
+        assert!(config.is_enabled());
+        assert!(config.notify_author);
+
+        let spoilers = config.policy_for(ChannelId(5));
+        assert!(spoilers.require_spoilers && !spoilers.block_nsfw_embeds);
+        assert!(config.policy_for(ChannelId(6)).block_nsfw_embeds);
+
+        config.default = ChannelPolicy::default();
+        config.channels.clear();
+        assert!(!config.is_enabled());
+    }
+
+    #[test]
+    fn recognizes_spoilers_and_nsfw_labels() {
+        assert!(is_spoiler("SPOILER_cat.png"));
+        assert!(!is_spoiler("cat.png"));
+        assert!(!is_spoiler("spoiler_cat.png"));
+
+        let labels = vec![
+            ImageLabel {
+                name: String::from("gore"),
+                confidence: 0.99,
+            },
+            ImageLabel {
+                name: String::from("nsfw"),
+                confidence: 0.7,
+            },
+        ];
+        assert!(is_nsfw(&labels, 0.6));
+        assert!(!is_nsfw(&labels, 0.8));
+    }
+}
@@ -0,0 +1,199 @@
+use std::panic;
+
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+use serde_json::{json, Map, Value};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use twilight_model::id::GuildId;
+use url::Url;
+use uuid::Uuid;
+
+use super::bot_config::BotConfig;
+use crate::error::StartupError;
+use crate::{GIT_VERSION, VERSION};
+
+static TRACKER: OnceCell<ErrorTracker> = OnceCell::new();
+
+/// Where a failure happened, everything that's known ends up as a tag of the event.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorTags {
+    pub shard: Option<u64>,
+    pub guild: Option<GuildId>,
+    /// The full command, like ``archive channel``.
+    pub command: Option<String>,
+    /// The gateway event that was being handled.
+    pub event: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ErrorLevel {
+    Error,
+    /// Panics, something is broken badly enough that it took a task down with it.
+    Fatal,
+}
+
+/// Where events go and how to authenticate, taken apart from the DSN.
+#[derive(Debug, Clone, PartialEq)]
+struct Endpoint {
+    store_url: String,
+    key: String,
+}
+
+struct ErrorTracker {
+    environment: String,
+    cluster_id: u64,
+    events: UnboundedSender<Value>,
+}
+
+/// Starts sending events to the tracker and hooks into panics, does nothing when error tracking isn't configured.
+///
+/// Has to be called from inside the runtime, the events are sent from a background task.
+pub fn initialize(config: &BotConfig, cluster_id: u64) -> Result<(), StartupError> {
+    let config = match &config.error_tracking {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let endpoint = parse_dsn(&config.dsn).ok_or(StartupError::InvalidConfig)?;
+
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Value>();
+    let tracker = ErrorTracker {
+        environment: config.environment.clone(),
+        cluster_id,
+        events: sender,
+    };
+    if TRACKER.set(tracker).is_err() {
+        log::warn!("Error tracking was attempted to be initialized a second time");
+        return Ok(());
+    }
+
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let auth = format!(
+            "Sentry sentry_version=7, sentry_client=gearbot/{}, sentry_key={}",
+            VERSION, endpoint.key
+        );
+        while let Some(event) = receiver.recv().await {
+            let result = client
+                .post(&endpoint.store_url)
+                .header("X-Sentry-Auth", &auth)
+                .header("Content-Type", "application/json")
+                .body(event.to_string())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            // Not through the error macros, this would just report itself again when the tracker is down
+            if let Err(e) = result {
+                log::warn!("Failed to send an event to the error tracker: {}", e);
+            }
+        }
+    });
+
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("Box<dyn Any>"));
+        let message = match info.location() {
+            Some(location) => format!("Panicked at {}:{}: {}", location.file(), location.line(), payload),
+            None => format!("Panicked: {}", payload),
+        };
+        capture(ErrorLevel::Fatal, message, ErrorTags::default());
+
+        previous_hook(info);
+    }));
+
+    Ok(())
+}
+
+/// Reports a failure to the error tracker, if there is one.
+pub fn capture(level: ErrorLevel, message: impl Into<String>, tags: ErrorTags) {
+    let tracker = match TRACKER.get() {
+        Some(tracker) => tracker,
+        None => return,
+    };
+
+    let mut tag_values = Map::new();
+    tag_values.insert(String::from("cluster"), json!(tracker.cluster_id.to_string()));
+    if let Some(shard) = tags.shard {
+        tag_values.insert(String::from("shard"), json!(shard.to_string()));
+    }
+    if let Some(guild) = tags.guild {
+        tag_values.insert(String::from("guild"), json!(guild.to_string()));
+    }
+    if let Some(command) = tags.command {
+        tag_values.insert(String::from("command"), json!(command));
+    }
+    if let Some(event) = tags.event {
+        tag_values.insert(String::from("event"), json!(event));
+    }
+
+    let level = match level {
+        ErrorLevel::Error => "error",
+        ErrorLevel::Fatal => "fatal",
+    };
+    let event = json!({
+        "event_id": Uuid::new_v4().simple().to_string(),
+        "timestamp": Utc::now().to_rfc3339(),
+        "platform": "other",
+        "logger": "gearbot",
+        "level": level,
+        "release": GIT_VERSION,
+        "environment": tracker.environment,
+        "server_name": format!("cluster-{}", tracker.cluster_id),
+        "message": { "formatted": message.into() },
+        "tags": tag_values,
+    });
+    // Only fails when the sending task is gone, then there's nowhere left to report it either
+    let _ = tracker.events.send(event);
+}
+
+/// Takes a DSN like ``https://key@host/path/42`` apart into the store endpoint of project 42 and the key.
+fn parse_dsn(dsn: &str) -> Option<Endpoint> {
+    let url = Url::parse(dsn).ok()?;
+    let key = url.username();
+    let host = url.host_str()?;
+    let mut path = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+    let project = path.pop()?;
+    if key.is_empty() || project.parse::<u64>().is_err() {
+        return None;
+    }
+
+    let port = url.port().map(|port| format!(":{}", port)).unwrap_or_default();
+    let prefix = path.iter().map(|segment| format!("/{}", segment)).collect::<String>();
+    Some(Endpoint {
+        store_url: format!("{}://{}{}{}/api/{}/store/", url.scheme(), host, port, prefix, project),
+        key: key.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dsns() {
+        assert_eq!(
+            parse_dsn("https://abc123@sentry.example.com/42"),
+            Some(Endpoint {
+                store_url: String::from("https://sentry.example.com/api/42/store/"),
+                key: String::from("abc123"),
+            })
+        );
+        assert_eq!(
+            parse_dsn("http://abc123@localhost:9000/tracking/7"),
+            Some(Endpoint {
+                store_url: String::from("http://localhost:9000/tracking/api/7/store/"),
+                key: String::from("abc123"),
+            })
+        );
+        assert_eq!(parse_dsn("https://sentry.example.com/42"), None);
+        assert_eq!(parse_dsn("https://abc123@sentry.example.com/"), None);
+        assert_eq!(parse_dsn("not a dsn"), None);
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::Permissions;
+use twilight_model::id::{RoleId, UserId};
+
+/// Anyone getting one of these can take over or spam the whole server, so every grant of them gets looked at.
+pub const DANGEROUS_PERMISSIONS: Permissions = Permissions::from_bits_truncate(
+    Permissions::ADMINISTRATOR.bits() | Permissions::MANAGE_GUILD.bits() | Permissions::MENTION_EVERYONE.bits(),
+);
+
+/// Watches roles and members gaining dangerous permissions.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct EscalationConfig {
+    pub enabled: bool,
+    /// Undoes grants by anyone that isn't the owner or a trusted admin. Grants the audit log doesn't tell us the
+    /// author of are only logged, they could just as well be from the owner.
+    pub revert: bool,
+    /// Admins that can hand out dangerous permissions without them getting reverted, the owner always can.
+    pub trusted_admins: Vec<UserId>,
+}
+
+/// Dangerous permissions that were handed out, spotted while updating the cache as that's the only place that still
+/// knows what was there before.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PermissionGrant {
+    Role {
+        role_id: RoleId,
+        /// What to put back when reverting.
+        old_permissions: Permissions,
+    },
+    Member {
+        user_id: UserId,
+        /// The roles that were added and hand out the permissions.
+        roles: Vec<RoleId>,
+    },
+}
+
+/// The dangerous permissions a role got that it didn't have before.
+pub fn granted_to_role(before: Permissions, after: Permissions) -> Permissions {
+    after & !before & DANGEROUS_PERMISSIONS
+}
+
+/// The dangerous permissions a member gets from newly added roles that they didn't have yet, and the roles that
+/// hand them out.
+pub fn granted_by_roles(before: Permissions, added: &[(RoleId, Permissions)]) -> (Vec<RoleId>, Permissions) {
+    // Administrators can do everything already
+    if before.contains(Permissions::ADMINISTRATOR) {
+        return (vec![], Permissions::empty());
+    }
+
+    let mut roles = vec![];
+    let mut granted = Permissions::empty();
+    for (role_id, permissions) in added {
+        let new = *permissions & !before & DANGEROUS_PERMISSIONS;
+        if !new.is_empty() {
+            roles.push(*role_id);
+            granted |= new;
+        }
+    }
+    (roles, granted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_looks_at_new_dangerous_permissions() {
+        assert_eq!(
+            granted_to_role(Permissions::MENTION_EVERYONE, Permissions::all()),
+            Permissions::ADMINISTRATOR | Permissions::MANAGE_GUILD
+        );
+        assert_eq!(
+            granted_to_role(Permissions::empty(), Permissions::BAN_MEMBERS),
+            Permissions::empty()
+        );
+
+        let added = [
+            (RoleId(1), Permissions::SEND_MESSAGES),
+            (RoleId(2), Permissions::MANAGE_GUILD | Permissions::MENTION_EVERYONE),
+        ];
+        assert_eq!(
+            granted_by_roles(Permissions::MENTION_EVERYONE, &added),
+            (vec![RoleId(2)], Permissions::MANAGE_GUILD)
+        );
+        assert_eq!(
+            granted_by_roles(Permissions::ADMINISTRATOR, &added),
+            (vec![], Permissions::empty())
+        );
+    }
+}
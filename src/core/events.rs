@@ -0,0 +1,227 @@
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use twilight_model::id::UserId;
+
+use crate::utils::Emoji;
+
+/// How start times are written in ``event create``, in the timezone of the guild.
+pub const START_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Events members RSVP to by reacting to their announcement.
+///
+/// Buttons would be the nicer way to RSVP, but those need message components, which twilight 0.4 doesn't have (see
+/// the handlers).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EventConfig {
+    /// How many minutes before the start the members that are going get pinged, 0 to not remind them.
+    pub reminder_minutes: u32,
+    /// Also ping the members that said they might come.
+    pub remind_maybe: bool,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        EventConfig {
+            reminder_minutes: 15,
+            remind_maybe: false,
+        }
+    }
+}
+
+impl EventConfig {
+    /// When the reminder for an event starting at this time goes out, events that start sooner than that don't
+    /// get one.
+    pub fn reminder_at(&self, starts_at: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.reminder_minutes == 0 {
+            return None;
+        }
+        Some(starts_at - Duration::minutes(self.reminder_minutes as i64)).filter(|remind_at| *remind_at > now)
+    }
+
+    pub fn reminds(&self, status: RsvpStatus) -> bool {
+        match status {
+            RsvpStatus::Going => true,
+            RsvpStatus::Maybe => self.remind_maybe,
+            RsvpStatus::Declined => false,
+        }
+    }
+}
+
+/// What is announced about an event, as given to ``event create``.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDetails {
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+}
+
+/// Reads the start of an event in the timezone of the guild. Times that don't exist there (skipped by daylight saving
+/// time) are rejected, ones that happen twice are the first time.
+pub fn parse_start(date: &str, time: &str, timezone: Tz) -> Option<DateTime<Utc>> {
+    let local = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), START_FORMAT).ok()?;
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+}
+
+/// Splits ``title | description``, the description is optional.
+pub fn split_details(input: &str) -> Option<(String, Option<String>)> {
+    let (title, description) = match input.split_once('|') {
+        Some((title, description)) => (title.trim(), Some(description.trim())),
+        None => (input.trim(), None),
+    };
+    if title.is_empty() {
+        return None;
+    }
+    Some((
+        title.to_string(),
+        description
+            .filter(|description| !description.is_empty())
+            .map(String::from),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RsvpStatus {
+    Going,
+    Maybe,
+    Declined,
+}
+
+impl RsvpStatus {
+    pub const ALL: [RsvpStatus; 3] = [RsvpStatus::Going, RsvpStatus::Maybe, RsvpStatus::Declined];
+
+    /// How the status is stored.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RsvpStatus::Going => "going",
+            RsvpStatus::Maybe => "maybe",
+            RsvpStatus::Declined => "declined",
+        }
+    }
+
+    pub fn from_str(status: &str) -> Option<Self> {
+        match status {
+            "going" => Some(RsvpStatus::Going),
+            "maybe" => Some(RsvpStatus::Maybe),
+            "declined" => Some(RsvpStatus::Declined),
+            _ => None,
+        }
+    }
+
+    /// The reaction members pick this status with.
+    pub fn emoji(&self) -> Emoji {
+        match self {
+            RsvpStatus::Going => Emoji::Yes,
+            RsvpStatus::Maybe => Emoji::Maybe,
+            RsvpStatus::Declined => Emoji::No,
+        }
+    }
+
+    pub fn from_emoji(emoji: &Emoji) -> Option<Self> {
+        match emoji {
+            Emoji::Yes => Some(RsvpStatus::Going),
+            Emoji::Maybe => Some(RsvpStatus::Maybe),
+            Emoji::No => Some(RsvpStatus::Declined),
+            _ => None,
+        }
+    }
+}
+
+/// Puts the mentions in as few messages as fit within the limit.
+pub fn mention_chunks(users: &[UserId], limit: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+    for user in users {
+        let mention = format!("<@{}>", user);
+        if !current.is_empty() && current.len() + mention.len() + 1 > limit {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current += &mention;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_are_read_in_the_guild_timezone() {
+        let start = parse_start("2021-06-01", "20:00", chrono_tz::Europe::Brussels).unwrap();
+        assert_eq!(start, Utc.ymd(2021, 6, 1).and_hms(18, 0, 0));
+        assert_eq!(
+            parse_start("2021-06-01", "20:00", Tz::UTC),
+            Some(Utc.ymd(2021, 6, 1).and_hms(20, 0, 0))
+        );
+
+        assert_eq!(parse_start("2021-06-01", "8pm", Tz::UTC), None);
+        assert_eq!(parse_start("06/01/2021", "20:00", Tz::UTC), None);
+        // The clocks jump from 2:00 to 3:00 that night
+        assert_eq!(parse_start("2021-03-28", "02:30", chrono_tz::Europe::Brussels), None);
+    }
+
+    #[test]
+    fn details_split_on_the_first_bar() {
+        assert_eq!(
+            split_details(" Movie night | Bring snacks | and drinks"),
+            Some((
+                String::from("Movie night"),
+                Some(String::from("Bring snacks | and drinks"))
+            ))
+        );
+        assert_eq!(
+            split_details("Movie night |"),
+            Some((String::from("Movie night"), None))
+        );
+        assert_eq!(split_details(" | Bring snacks"), None);
+    }
+
+    #[test]
+    fn reminders_skip_events_that_start_too_soon() {
+        let config = EventConfig::default();
+        let now = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+
+        assert_eq!(
+            config.reminder_at(now + Duration::hours(1), now),
+            Some(now + Duration::minutes(45))
+        );
+        assert_eq!(config.reminder_at(now + Duration::minutes(10), now), None);
+
+        let disabled = EventConfig {
+            reminder_minutes: 0,
+            ..EventConfig::default()
+        };
+        assert_eq!(disabled.reminder_at(now + Duration::hours(1), now), None);
+        assert!(config.reminds(RsvpStatus::Going));
+        assert!(!config.reminds(RsvpStatus::Maybe));
+    }
+
+    #[test]
+    fn statuses_round_trip() {
+        for status in RsvpStatus::ALL.iter() {
+            assert_eq!(RsvpStatus::from_str(status.as_str()), Some(*status));
+            assert_eq!(RsvpStatus::from_emoji(&status.emoji()), Some(*status));
+        }
+        assert_eq!(RsvpStatus::from_str("unknown"), None);
+    }
+
+    #[test]
+    fn mentions_are_chunked() {
+        let users = [UserId(1), UserId(22), UserId(333)];
+
+        assert_eq!(mention_chunks(&users, 2000), vec!["<@1> <@22> <@333>"]);
+        assert_eq!(mention_chunks(&users, 11), vec!["<@1> <@22>", "<@333>"]);
+        assert!(mention_chunks(&[], 2000).is_empty());
+    }
+}
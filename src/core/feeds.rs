@@ -0,0 +1,389 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::core::public_fetch::{self, is_allowed_url};
+use crate::error::FeedError;
+use crate::utils::embeds;
+
+/// How many feeds a single guild can have.
+pub const MAX_FEEDS: usize = 10;
+pub const MIN_INTERVAL_MINUTES: i32 = 5;
+pub const MAX_INTERVAL_MINUTES: i32 = 24 * 60;
+pub const DEFAULT_INTERVAL_MINUTES: i32 = 30;
+/// Posted as the description of every new entry, unless the guild sets its own.
+pub const DEFAULT_TEMPLATE: &str = "{summary}";
+pub const TEMPLATE_LIMIT: usize = 1000;
+
+/// At most this many new entries are posted per poll, a feed that suddenly shows its whole history shouldn't flood
+/// the channel.
+pub const MAX_POSTS_PER_POLL: usize = 5;
+
+const SUMMARY_LIMIT: usize = 500;
+const MAX_FEED_SIZE: usize = 2 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+lazy_static! {
+    static ref CDATA: Regex = Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    static ref LINE_BREAK: Regex = Regex::new(r"(?i)<br\s*/?>|</p>|</li>|</h\d>").unwrap();
+    static ref HTML_TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref BLANK_LINES: Regex = Regex::new(r"\n\s*\n(\s*\n)+").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub title: Option<String>,
+    /// In the order the feed lists them, which is usually newest first.
+    pub entries: Vec<FeedEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    /// Whatever identifies the entry best: its guid or id, otherwise its link or title.
+    pub id: String,
+    pub title: Option<String>,
+    pub link: Option<String>,
+    /// Plain text, without the html feeds tend to put in there.
+    pub summary: Option<String>,
+    pub author: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+}
+
+impl FeedEntry {
+    /// Fills in the placeholders of a template: `{title}`, `{link}`, `{summary}`, `{author}` and `{feed}`.
+    pub fn render(&self, template: &str, feed_title: &str) -> String {
+        let summary = match &self.summary {
+            Some(summary) => embeds::truncate(summary, SUMMARY_LIMIT),
+            None => String::new(),
+        };
+        template
+            .replace("{title}", self.title.as_deref().unwrap_or_default())
+            .replace("{link}", self.link.as_deref().unwrap_or_default())
+            .replace("{author}", self.author.as_deref().unwrap_or_default())
+            .replace("{feed}", feed_title)
+            // Last so anything in the summary that looks like a placeholder stays as it is
+            .replace("{summary}", &summary)
+            .trim()
+            .to_string()
+    }
+}
+
+/// Reads an RSS or Atom feed, `None` if it's neither.
+///
+/// This is far from a complete XML parser, but feeds are simple enough that finding the elements by name works for
+/// everything that is out there in practice.
+pub fn parse_feed(xml: &str) -> Option<Feed> {
+    let (items, atom) = match elements(xml, "item") {
+        items if !items.is_empty() => (items, false),
+        _ => (elements(xml, "entry"), true),
+    };
+    // An empty feed is still a feed, as long as it looks like one
+    if items.is_empty() && !(xml.contains("<rss") || xml.contains("<feed") || xml.contains("<rdf:RDF")) {
+        return None;
+    }
+
+    let head = match xml.find(if atom { "<entry" } else { "<item" }) {
+        Some(end) => &xml[..end],
+        None => xml,
+    };
+    let title = element(head, "title")
+        .map(|(_, content)| text(content))
+        .filter(|title| !title.is_empty());
+
+    let entries = items
+        .into_iter()
+        .filter_map(|(_, content)| parse_entry(content, atom))
+        .collect();
+
+    Some(Feed { title, entries })
+}
+
+fn parse_entry(xml: &str, atom: bool) -> Option<FeedEntry> {
+    let title = first_text(xml, &["title"]);
+    let link = if atom {
+        // Entries can link to all kinds of things, the alternate link is the entry itself
+        elements(xml, "link")
+            .into_iter()
+            .filter(|(tag, _)| matches!(attribute(tag, "rel").as_deref(), None | Some("alternate")))
+            .find_map(|(tag, _)| attribute(tag, "href"))
+    } else {
+        first_text(xml, &["link"])
+    };
+    let summary = first_text(xml, &["description", "summary", "content:encoded", "content"])
+        .map(|summary| plain_text(&summary))
+        .filter(|summary| !summary.is_empty());
+    let author = element(xml, "author")
+        .map(|(_, content)| match element(content, "name") {
+            Some((_, name)) => text(name),
+            None => text(content),
+        })
+        .filter(|author| !author.is_empty())
+        .or_else(|| first_text(xml, &["dc:creator"]));
+    let published = first_text(xml, &["pubDate", "published", "updated", "dc:date"]).and_then(|date| parse_date(&date));
+    let id = first_text(xml, &["guid", "id"])
+        .or_else(|| link.clone())
+        .or_else(|| title.clone())?;
+
+    Some(FeedEntry {
+        id,
+        title,
+        link,
+        summary,
+        author,
+        published,
+    })
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .or_else(|_| DateTime::parse_from_rfc2822(date))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// The text of the first of these elements that has any.
+fn first_text(xml: &str, names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| element(xml, name))
+        .map(|(_, content)| text(content))
+        .find(|text| !text.is_empty())
+}
+
+fn element<'a>(xml: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+    next_element(xml, name).map(|(tag, content, _)| (tag, content))
+}
+
+/// All elements with this name, as their opening tag and content.
+fn elements<'a>(mut xml: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+    let mut found = vec![];
+    while let Some((tag, content, end)) = next_element(xml, name) {
+        found.push((tag, content));
+        xml = &xml[end..];
+    }
+    found
+}
+
+/// Finds the next element with this name, giving its opening tag, its content and where it ends.
+fn next_element<'a>(xml: &'a str, name: &str) -> Option<(&'a str, &'a str, usize)> {
+    let open = format!("<{}", name);
+    let mut offset = 0;
+    loop {
+        let start = offset + xml[offset..].find(&open)?;
+        let after_name = start + open.len();
+        // Don't mistake <linkedin> for a <link>
+        if !matches!(
+            xml[after_name..].chars().next(),
+            Some('>' | '/' | ' ' | '\t' | '\r' | '\n')
+        ) {
+            offset = after_name;
+            continue;
+        }
+
+        let tag_end = after_name + xml[after_name..].find('>')? + 1;
+        let tag = &xml[start..tag_end];
+        if tag.ends_with("/>") {
+            return Some((tag, "", tag_end));
+        }
+
+        let close = format!("</{}>", name);
+        let content_end = tag_end + xml[tag_end..].find(&close)?;
+        return Some((tag, &xml[tag_end..content_end], content_end + close.len()));
+    }
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    ATTRIBUTE
+        .captures_iter(tag)
+        .find(|captures| &captures[1] == name)
+        .and_then(|captures| captures.get(2).or_else(|| captures.get(3)))
+        .map(|value| decode_entities(value.as_str()))
+}
+
+/// The content of an element as text, unwrapping CDATA sections and decoding entities everywhere else.
+fn text(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for captures in CDATA.captures_iter(content) {
+        let section = captures.get(0).unwrap();
+        out += &decode_entities(&content[last..section.start()]);
+        out += &captures[1];
+        last = section.end();
+    }
+    out += &decode_entities(&content[last..]);
+    out.trim().to_string()
+}
+
+/// Turns the html of a summary into plain text.
+fn plain_text(html: &str) -> String {
+    let text = LINE_BREAK.replace_all(html, "\n");
+    let text = HTML_TAG.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let text = text.lines().map(str::trim).collect::<Vec<&str>>().join("\n");
+    BLANK_LINES.replace_all(&text, "\n\n").trim().to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out += &rest[..start];
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|decimal| decimal.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+            decoded.map(|decoded| (decoded, end))
+        });
+        match decoded {
+            Some((decoded, end)) => {
+                out.push(decoded);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out += rest;
+    out
+}
+
+/// Downloads and parses feeds.
+pub struct FeedReader {
+    client: reqwest::Client,
+}
+
+impl FeedReader {
+    pub fn new() -> Result<Self, FeedError> {
+        let client = public_fetch::client_builder(FETCH_TIMEOUT)
+            .user_agent(concat!("GearBot/", env!("CARGO_PKG_VERSION"), " (feed reader)"))
+            .build()?;
+
+        Ok(FeedReader { client })
+    }
+
+    pub async fn fetch(&self, url: &str) -> Result<Feed, FeedError> {
+        let url = Url::parse(url).map_err(|_| FeedError::InvalidUrl)?;
+        if !is_allowed_url(&url) {
+            return Err(FeedError::InvalidUrl);
+        }
+
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let body = public_fetch::read_limited(response, MAX_FEED_SIZE)
+            .await?
+            .ok_or(FeedError::TooLarge)?;
+
+        parse_feed(&String::from_utf8_lossy(&body)).ok_or(FeedError::NotAFeed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rss() {
+        let feed = parse_feed(
+            r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+            <channel>
+                <title>Gear &amp; Co</title>
+                <link>https://example.com</link>
+                <item>
+                    <title><![CDATA[Release <2.0>]]></title>
+                    <link>https://example.com/2</link>
+                    <description>&lt;p&gt;Now with &lt;b&gt;more&lt;/b&gt; gears&lt;/p&gt;&lt;p&gt;Enjoy&lt;/p&gt;</description>
+                    <dc:creator>Gear</dc:creator>
+                    <pubDate>Tue, 01 Jun 2021 20:00:00 +0200</pubDate>
+                    <guid isPermaLink="false">post-2</guid>
+                </item>
+                <item>
+                    <title>Release 1.0</title>
+                    <link>https://example.com/1</link>
+                </item>
+            </channel>
+            </rss>"#,
+        )
+        .unwrap();
+
+        assert_eq!(feed.title.as_deref(), Some("Gear & Co"));
+        assert_eq!(feed.entries.len(), 2);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "post-2");
+        assert_eq!(entry.title.as_deref(), Some("Release <2.0>"));
+        assert_eq!(entry.link.as_deref(), Some("https://example.com/2"));
+        assert_eq!(entry.summary.as_deref(), Some("Now with more gears\nEnjoy"));
+        assert_eq!(entry.author.as_deref(), Some("Gear"));
+        assert_eq!(entry.published.unwrap().to_rfc3339(), "2021-06-01T18:00:00+00:00");
+        // Without a guid the link identifies the entry
+        assert_eq!(feed.entries[1].id, "https://example.com/1");
+    }
+
+    #[test]
+    fn parses_atom() {
+        let feed = parse_feed(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">
+                <title type="text">Gear log</title>
+                <link rel="self" href="https://example.com/feed.xml"/>
+                <entry>
+                    <title>Atom entry</title>
+                    <link rel="enclosure" href="https://example.com/file.mp3"/>
+                    <link href="https://example.com/entry?a=1&amp;b=2"/>
+                    <id>urn:uuid:1</id>
+                    <updated>2021-06-01T18:00:00Z</updated>
+                    <author><name>Gear</name><email>gear@example.com</email></author>
+                    <summary type="html">Short &amp;amp; sweet</summary>
+                </entry>
+            </feed>"#,
+        )
+        .unwrap();
+
+        assert_eq!(feed.title.as_deref(), Some("Gear log"));
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "urn:uuid:1");
+        assert_eq!(entry.link.as_deref(), Some("https://example.com/entry?a=1&b=2"));
+        assert_eq!(entry.author.as_deref(), Some("Gear"));
+        assert_eq!(entry.summary.as_deref(), Some("Short & sweet"));
+        assert!(entry.published.is_some());
+
+        assert_eq!(parse_feed("<html><body>Not a feed</body></html>"), None);
+        assert_eq!(parse_feed("<rss><channel></channel></rss>").unwrap().entries, vec![]);
+    }
+
+    #[test]
+    fn renders_templates() {
+        let entry = FeedEntry {
+            id: String::from("1"),
+            title: Some(String::from("Release")),
+            link: Some(String::from("https://example.com/1")),
+            summary: Some(String::from("Mentions {title}")),
+            author: None,
+            published: None,
+        };
+
+        assert_eq!(
+            entry.render("**{feed}**: {title} by {author}\n{summary}\n<{link}>", "Gear log"),
+            "**Gear log**: Release by \nMentions {title}\n<https://example.com/1>"
+        );
+        assert_eq!(entry.render(DEFAULT_TEMPLATE, "Gear log"), "Mentions {title}");
+    }
+}
@@ -0,0 +1,317 @@
+use hmac::{Hmac, Mac, NewMac};
+use lazy_static::lazy_static;
+use rand::{thread_rng, RngCore};
+use regex::Regex;
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// How many repositories a single guild can follow.
+pub const MAX_REPOSITORIES: usize = 10;
+/// Commits listed for a single push, the rest is only counted.
+pub const MAX_LISTED_COMMITS: usize = 10;
+/// How much of release notes and issue descriptions is shown, the embed links to the rest.
+pub const BODY_LIMIT: usize = 500;
+
+lazy_static! {
+    static ref REPOSITORY: Regex = Regex::new(r"^[A-Za-z0-9_.-]+/[A-Za-z0-9_.-]+$").unwrap();
+}
+
+/// The kinds of deliveries that can be relayed, guilds pick which ones they want per repository.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GithubEvent {
+    Push,
+    Release,
+    Issues,
+}
+
+impl GithubEvent {
+    pub const ALL: [GithubEvent; 3] = [GithubEvent::Push, GithubEvent::Release, GithubEvent::Issues];
+
+    /// The name GitHub uses for it, in the `X-GitHub-Event` header and the webhook settings.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GithubEvent::Push => "push",
+            GithubEvent::Release => "release",
+            GithubEvent::Issues => "issues",
+        }
+    }
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        GithubEvent::ALL
+            .iter()
+            .copied()
+            .find(|event| event.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Repositories are matched case insensitively, like GitHub does.
+pub fn normalize_repository(name: &str) -> Option<String> {
+    if REPOSITORY.is_match(name) {
+        Some(name.to_lowercase())
+    } else {
+        None
+    }
+}
+
+/// A new secret to sign deliveries with, 32 random bytes as hex.
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 32];
+    thread_rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+/// Checks the `X-Hub-Signature-256` header, which is `sha256=` followed by the HMAC of the body as hex.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let expected = match signature.strip_prefix("sha256=").and_then(|hex| hex::decode(hex).ok()) {
+        Some(expected) => expected,
+        None => return false,
+    };
+    // HMAC takes keys of any length, this can't fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC rejected a key");
+    mac.update(body);
+    // Compares in constant time, so the signature can't be guessed byte by byte
+    mac.verify(&expected).is_ok()
+}
+
+/// A delivery worth relaying, read according to the event it is for.
+#[derive(Debug)]
+pub enum GithubDelivery {
+    Push(PushDelivery),
+    Release(ReleaseDelivery),
+    Issues(IssuesDelivery),
+}
+
+impl GithubDelivery {
+    /// Reads the body of a delivery, `None` for the ones that aren't worth a message, like deleted branches and
+    /// edited issues.
+    pub fn parse(event: GithubEvent, body: &[u8]) -> Result<Option<Self>, serde_json::Error> {
+        let delivery = match event {
+            GithubEvent::Push => {
+                let push: PushDelivery = serde_json::from_slice(body)?;
+                if push.deleted || push.commits.is_empty() {
+                    return Ok(None);
+                }
+                GithubDelivery::Push(push)
+            }
+            GithubEvent::Release => {
+                let release: ReleaseDelivery = serde_json::from_slice(body)?;
+                if release.action != "published" {
+                    return Ok(None);
+                }
+                GithubDelivery::Release(release)
+            }
+            GithubEvent::Issues => {
+                let issues: IssuesDelivery = serde_json::from_slice(body)?;
+                if !matches!(issues.action.as_str(), "opened" | "closed" | "reopened") {
+                    return Ok(None);
+                }
+                GithubDelivery::Issues(issues)
+            }
+        };
+
+        Ok(Some(delivery))
+    }
+
+    pub fn sender(&self) -> Option<&Sender> {
+        match self {
+            GithubDelivery::Push(push) => push.sender.as_ref(),
+            GithubDelivery::Release(release) => release.sender.as_ref(),
+            GithubDelivery::Issues(issues) => issues.sender.as_ref(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Repository {
+    pub full_name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Sender {
+    pub login: String,
+    pub avatar_url: Option<String>,
+}
+
+/// The parts every delivery has, to find out who it is for before trusting anything else in it.
+#[derive(Deserialize, Debug)]
+pub struct DeliveryHeader {
+    /// Missing for events that are about an organization rather than a repository.
+    pub repository: Option<Repository>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PushDelivery {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// Set when the push removed the branch.
+    #[serde(default)]
+    pub deleted: bool,
+    pub compare: String,
+    pub commits: Vec<PushedCommit>,
+    pub repository: Repository,
+    pub sender: Option<Sender>,
+}
+
+impl PushDelivery {
+    /// The branch or tag that was pushed to, without the `refs/heads/` in front.
+    pub fn branch(&self) -> &str {
+        self.git_ref
+            .strip_prefix("refs/heads/")
+            .or_else(|| self.git_ref.strip_prefix("refs/tags/"))
+            .unwrap_or(&self.git_ref)
+    }
+
+    /// A line per commit, with only the first line of the message.
+    pub fn commit_lines(&self) -> Vec<String> {
+        self.commits
+            .iter()
+            .take(MAX_LISTED_COMMITS)
+            .map(|commit| {
+                format!(
+                    "[`{}`]({}) {} - {}",
+                    commit.id.get(..7).unwrap_or(&commit.id),
+                    commit.url,
+                    commit.message.lines().next().unwrap_or_default(),
+                    commit.author.name
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PushedCommit {
+    pub id: String,
+    pub message: String,
+    pub url: String,
+    pub author: CommitAuthor,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommitAuthor {
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReleaseDelivery {
+    pub action: String,
+    pub release: Release,
+    pub repository: Repository,
+    pub sender: Option<Sender>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Release {
+    pub name: Option<String>,
+    pub tag_name: String,
+    pub html_url: String,
+    pub body: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IssuesDelivery {
+    pub action: String,
+    pub issue: Issue,
+    pub repository: Repository,
+    pub sender: Option<Sender>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub body: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_signatures() {
+        // The example from the GitHub documentation on validating deliveries
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+        assert!(verify_signature(
+            "It's a Secret to Everybody",
+            b"Hello, World!",
+            signature
+        ));
+        assert!(!verify_signature(
+            "It's a Secret to Everybody",
+            b"Hello, World?",
+            signature
+        ));
+        assert!(!verify_signature("Another secret", b"Hello, World!", signature));
+        assert!(!verify_signature(
+            "It's a Secret to Everybody",
+            b"Hello, World!",
+            "sha1=757107ea"
+        ));
+        assert!(!verify_signature(
+            "It's a Secret to Everybody",
+            b"Hello, World!",
+            "sha256=nothex"
+        ));
+    }
+
+    #[test]
+    fn reads_repositories_and_events() {
+        assert_eq!(
+            normalize_repository("GearBot/GearBot"),
+            Some(String::from("gearbot/gearbot"))
+        );
+        assert_eq!(normalize_repository("gearbot"), None);
+        assert_eq!(normalize_repository("https://github.com/gearbot/gearbot"), None);
+        assert_eq!(GithubEvent::from_str("Release"), Some(GithubEvent::Release));
+        assert_eq!(GithubEvent::from_str("pull_request"), None);
+        assert_eq!(generate_secret().len(), 64);
+    }
+
+    #[test]
+    fn skips_uninteresting_deliveries() {
+        let issue = |action: &str| {
+            format!(
+                r#"{{
+                    "action": "{}",
+                    "issue": {{"number": 7, "title": "Gears", "html_url": "https://github.com/g/g/issues/7"}},
+                    "repository": {{"full_name": "gearbot/gearbot"}}
+                }}"#,
+                action
+            )
+        };
+
+        let opened = GithubDelivery::parse(GithubEvent::Issues, issue("opened").as_bytes()).unwrap();
+        assert!(matches!(opened, Some(GithubDelivery::Issues(_))));
+        let edited = GithubDelivery::parse(GithubEvent::Issues, issue("edited").as_bytes()).unwrap();
+        assert!(edited.is_none());
+        assert!(GithubDelivery::parse(GithubEvent::Release, issue("opened").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn summarizes_pushes() {
+        let push: PushDelivery = serde_json::from_str(
+            r#"{
+                "ref": "refs/heads/main",
+                "compare": "https://github.com/gearbot/gearbot/compare/a...b",
+                "commits": [{
+                    "id": "0123456789abcdef",
+                    "message": "Fix the gears\n\nThey were stuck",
+                    "url": "https://github.com/gearbot/gearbot/commit/0123456789abcdef",
+                    "author": {"name": "Gear"}
+                }],
+                "repository": {"full_name": "gearbot/gearbot"},
+                "sender": {"login": "gear", "avatar_url": null}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(push.branch(), "main");
+        assert!(!push.deleted);
+        assert_eq!(
+            push.commit_lines(),
+            vec!["[`0123456`](https://github.com/gearbot/gearbot/commit/0123456789abcdef) Fix the gears - Gear"]
+        );
+    }
+}
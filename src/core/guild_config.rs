@@ -1,24 +1,176 @@
-use serde::{Deserialize, Serialize};
+use bitflags::bitflags;
+use chrono_tz::Tz;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use twilight_model::guild::Permissions;
 use twilight_model::id::{ChannelId, RoleId, UserId};
 use unic_langid::LanguageIdentifier;
 
 use crate::commands::meta::nodes::GearBotPermissions;
+use crate::core::anti_nuke::AntiNukeConfig;
+use crate::core::auto_publish::AutoPublishConfig;
+use crate::core::automod::{AutomodReviewConfig, AutomodRule, AutomodRules};
+use crate::core::autoresponder::{AutoResponse, AutoResponses};
+use crate::core::birthdays::BirthdayConfig;
+use crate::core::content_policy::ContentPolicyConfig;
+use crate::core::escalation::EscalationConfig;
+use crate::core::events::EventConfig;
+use crate::core::heat::HeatConfig;
+use crate::core::image_moderation::ImageModerationConfig;
+use crate::core::join_gate::JoinGateConfig;
 use crate::core::logpump::{DataLessLogType, LogFilter};
+use crate::core::pin_archive::PinArchiveConfig;
+use crate::core::profiles::ConfigProfile;
+use crate::core::prune::PruneConfig;
+use crate::core::raid_mode::RaidModeConfig;
+use crate::core::suggestions::SuggestionConfig;
+use crate::core::temp_voice::TempVoiceConfig;
+use crate::core::tickets::TicketConfig;
+use crate::core::toxicity::ToxicityConfig;
 use crate::translation::DEFAULT_LANG;
-use std::collections::HashMap;
+use crate::utils::matchers;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GuildConfig {
     pub prefix: String,
+    /// Prefixes that are accepted next to the main one.
+    #[serde(default)]
+    pub extra_prefixes: Vec<String>,
     pub log_style: LogStyle,
     pub message_logs: MessageLogs,
     pub language: LanguageIdentifier,
+    /// Name of the timezone the guild lives in, like ``Europe/Brussels``.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
     pub permission_groups: Vec<PermissionGroup>,
     pub log_channels: HashMap<ChannelId, LogChannelConfig>,
+    /// How the logs of each category look, in every log channel they go to.
+    #[serde(default)]
+    pub log_appearance: HashMap<LogCategory, LogAppearance>,
+    #[serde(default)]
+    pub levels: LevelConfig,
+    /// Infractions and notes younger than this many days survive a user asking for their data to be deleted.
+    ///
+    /// Without a limit moderation records are never removed on request.
+    #[serde(default)]
+    pub infraction_retention_days: Option<u32>,
+    /// Archived transcripts older than this many days get removed, without a limit they are kept.
+    #[serde(default)]
+    pub archive_retention_days: Option<u32>,
+    /// Stored attachments of logged messages older than this many days get removed, without a limit they are kept.
+    #[serde(default)]
+    pub attachment_retention_days: Option<u32>,
+    #[serde(default = "GuildFeatures::all")]
+    pub features: GuildFeatures,
+    #[serde(default)]
+    pub command_correction: CommandCorrection,
+    /// Shortcuts of the guild itself, the first word of a command gets swapped out for what it stands for.
+    #[serde(default)]
+    pub command_aliases: HashMap<String, String>,
+    /// Commands that can't be used in a channel, by the name of their top level command.
+    #[serde(default)]
+    pub disabled_commands: HashMap<ChannelId, Vec<String>>,
+    /// Commands that always reply in another channel, by the name of their top level command.
+    #[serde(default)]
+    pub command_output_channels: HashMap<String, ChannelId>,
+    /// Channels webhooks are expected in next to the log channels, ``audit integrations`` flags the ones elsewhere.
+    #[serde(default)]
+    pub expected_webhook_channels: Vec<ChannelId>,
+    /// Users that can't use any commands in this guild.
+    #[serde(default)]
+    pub bot_banned_users: Vec<UserId>,
+    #[serde(default)]
+    pub automod: AutomodRules,
+    #[serde(default)]
+    pub automod_review: AutomodReviewConfig,
+    #[serde(default)]
+    pub autoresponses: AutoResponses,
+    #[serde(default)]
+    pub image_moderation: ImageModerationConfig,
+    #[serde(default)]
+    pub content_policy: ContentPolicyConfig,
+    #[serde(default)]
+    pub pin_archive: PinArchiveConfig,
+    #[serde(default)]
+    pub toxicity: ToxicityConfig,
+    #[serde(default)]
+    pub heat: HeatConfig,
+    #[serde(default)]
+    pub join_gate: JoinGateConfig,
+    #[serde(default)]
+    pub raid_mode: RaidModeConfig,
+    #[serde(default)]
+    pub escalation: EscalationConfig,
+    #[serde(default)]
+    pub anti_nuke: AntiNukeConfig,
+    #[serde(default)]
+    pub prune: PruneConfig,
+    #[serde(default)]
+    pub birthdays: BirthdayConfig,
+    #[serde(default)]
+    pub suggestions: SuggestionConfig,
+    #[serde(default)]
+    pub tickets: TicketConfig,
+    #[serde(default)]
+    pub temp_voice: TempVoiceConfig,
+    #[serde(default)]
+    pub events: EventConfig,
+    #[serde(default)]
+    pub auto_publish: AutoPublishConfig,
+    /// Shares how many infractions users have here with the other guilds in the reputation network, and lets this
+    /// guild look up theirs in return. Only counts are shared, never what happened or who did it.
+    #[serde(default)]
+    pub reputation_network: bool,
+    /// Named snapshots of parts of this config, by lowercase name.
+    #[serde(default)]
+    pub profiles: HashMap<String, ConfigProfile>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+bitflags! {
+    /// Subsystems that can be turned off as a whole, a disabled one skips all of its work for the guild.
+    pub struct GuildFeatures: u64 {
+        const LOGGING       = 0x01;
+        const MESSAGE_LOGS  = 0x02;
+        const LEVELING      = 0x04;
+        const SNIPES        = 0x08;
+        const AUTOMOD       = 0x10;
+    }
+}
+
+/// How the features are called in commands.
+pub const FEATURE_NAMES: [(&str, GuildFeatures); 5] = [
+    ("logging", GuildFeatures::LOGGING),
+    ("message_logs", GuildFeatures::MESSAGE_LOGS),
+    ("leveling", GuildFeatures::LEVELING),
+    ("snipes", GuildFeatures::SNIPES),
+    ("automod", GuildFeatures::AUTOMOD),
+];
+
+impl GuildFeatures {
+    pub fn from_name(name: &str) -> Option<Self> {
+        FEATURE_NAMES
+            .iter()
+            .find(|(feature_name, _)| feature_name.eq_ignore_ascii_case(name))
+            .map(|(_, feature)| *feature)
+    }
+}
+
+impl<'de> Deserialize<'de> for GuildFeatures {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_bits_truncate(u64::deserialize(deserializer)?))
+    }
+}
+
+impl Serialize for GuildFeatures {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PermissionGroup {
     pub priority: u8,
     pub name: String,
@@ -30,12 +182,92 @@ pub struct PermissionGroup {
     pub users: Vec<UserId>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct MessageLogs {
     pub enabled: bool,
     pub ignored_users: Vec<u64>,
     pub ignored_channels: Vec<u64>,
     pub ignore_bots: bool,
+    #[serde(default)]
+    pub ignored_roles: Vec<u64>,
+    /// Messages matching any of these are never logged.
+    #[serde(default)]
+    pub ignored_patterns: IgnoredPatterns,
+    /// Re-upload attachments of deleted messages so the logs can include them.
+    #[serde(default)]
+    pub mirror_attachments: bool,
+}
+
+impl MessageLogs {
+    /// Checks if a message should be kept out of the message logs.
+    pub fn ignores(&self, author: UserId, is_bot: bool, channel: ChannelId, roles: &[RoleId], content: &str) -> bool {
+        !self.enabled
+            || (self.ignore_bots && is_bot)
+            || self.ignored_users.contains(&author.0)
+            || self.ignored_channels.contains(&channel.0)
+            || roles.iter().any(|role| self.ignored_roles.contains(&role.0))
+            || self.ignored_patterns.matches(content)
+    }
+}
+
+/// Upper bound on the compiled size of a single pattern, to keep huge regexes out of the message hot path.
+const IGNORED_PATTERN_SIZE_LIMIT: usize = 1 << 16;
+
+/// Regex patterns that get compiled once when the config is loaded, and are stored as their source.
+#[derive(Debug, Default, Clone)]
+pub struct IgnoredPatterns(Vec<Regex>);
+
+impl IgnoredPatterns {
+    pub fn matches(&self, content: &str) -> bool {
+        self.0.iter().any(|pattern| pattern.is_match(content))
+    }
+
+    fn compile(source: &str) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(source).size_limit(IGNORED_PATTERN_SIZE_LIMIT).build()
+    }
+}
+
+impl Serialize for IgnoredPatterns {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|pattern| pattern.as_str()))
+    }
+}
+
+impl<'de> Deserialize<'de> for IgnoredPatterns {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sources = Vec::<String>::deserialize(deserializer)?;
+
+        // A single broken pattern shouldn't make the entire config unusable, so it gets left out instead.
+        let patterns = sources
+            .iter()
+            .filter_map(|source| match IgnoredPatterns::compile(source) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    log::warn!("Ignoring invalid message log pattern {:?}: {}", source, e);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(IgnoredPatterns(patterns))
+    }
+}
+
+/// What to do with commands that don't exist.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum CommandCorrection {
+    /// Stay silent, like for any other message.
+    Off,
+    /// Reply with the commands that are closest to what was typed.
+    Suggest,
+    /// Run the command anyways when it's a single typo away from one that can't do any damage, suggest otherwise.
+    AutoCorrect,
+}
+
+impl Default for CommandCorrection {
+    fn default() -> Self {
+        CommandCorrection::Suggest
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -44,32 +276,393 @@ pub enum LogStyle {
     Embed,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq, Hash)]
 pub enum LogCategory {
     GENERAL,
+    COMMANDS,
+    MESSAGES,
+    MEMBERS,
+    /// Everything watched users do, see the `watch` command.
+    WATCHLIST,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 5] = [
+        LogCategory::GENERAL,
+        LogCategory::COMMANDS,
+        LogCategory::MESSAGES,
+        LogCategory::MEMBERS,
+        LogCategory::WATCHLIST,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|category| format!("{:?}", category).eq_ignore_ascii_case(name))
+            .cloned()
+    }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LogChannelConfig {
     pub categories: Vec<LogCategory>,
     pub disabled_keys: Vec<DataLessLogType>,
     pub style: LogStyle,
     pub filters: Vec<LogFilter>,
     pub timestamps: bool,
+    /// Delivers text logs through a webhook as well, embed logs always use one.
+    #[serde(default)]
+    pub webhook: bool,
+    /// How the webhook shows up for each category, categories without one use the defaults of the webhook.
+    #[serde(default)]
+    pub webhook_identities: HashMap<LogCategory, WebhookIdentity>,
+}
+
+impl LogChannelConfig {
+    pub fn uses_webhook(&self) -> bool {
+        self.webhook || self.style == LogStyle::Embed
+    }
+}
+
+/// The name and avatar a log webhook sends with.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct WebhookIdentity {
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Customizations on top of the log style, anything left out keeps the default look.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct LogAppearance {
+    /// Embed color, as a number like ``0x5865F2``. Discord reads 0 as no color, so that can't be used.
+    pub color: Option<u32>,
+    /// Used instead of the emoji of the log type. Custom emoji also replace the footer icon of embeds.
+    pub emoji: Option<String>,
+    /// Embed parts to leave out, text logs don't have these.
+    pub hidden_fields: Vec<LogField>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogField {
+    Author,
+    Thumbnail,
+    Footer,
+    Timestamp,
+}
+
+impl LogAppearance {
+    pub fn shows(&self, field: LogField) -> bool {
+        !self.hidden_fields.contains(&field)
+    }
+
+    /// The image of the custom emoji, unicode emoji have none.
+    pub fn icon_url(&self) -> Option<String> {
+        let emoji = matchers::get_emoji_parts(self.emoji.as_deref()?).pop()?;
+        let extension = if emoji.animated { "gif" } else { "png" };
+        Some(format!("https://cdn.discordapp.com/emojis/{}.{}", emoji.id, extension))
+    }
+
+    fn valid_emoji(emoji: &str) -> bool {
+        let custom = matchers::get_emoji_parts(emoji);
+        if custom.len() == 1 {
+            return emoji.starts_with('<') && emoji.ends_with('>');
+        }
+        // Unicode emoji, some of them are built out of a couple of codepoints
+        !emoji.is_empty() && emoji.chars().count() <= 8 && !emoji.chars().any(|c| c.is_ascii() || c.is_whitespace())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct LevelConfig {
+    pub enabled: bool,
+    pub min_xp: u32,
+    pub max_xp: u32,
+    /// Time in seconds a user has to wait before they can earn xp again.
+    pub cooldown: u32,
+    pub ignored_channels: Vec<ChannelId>,
+    pub ignored_roles: Vec<RoleId>,
+    pub role_rewards: Vec<LevelReward>,
+    /// If disabled, only the reward of the highest level reached is kept.
+    pub stack_rewards: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LevelReward {
+    pub level: u32,
+    pub role: RoleId,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        LevelConfig {
+            enabled: false,
+            min_xp: 15,
+            max_xp: 25,
+            cooldown: 60,
+            ignored_channels: vec![],
+            ignored_roles: vec![],
+            role_rewards: vec![],
+            stack_rewards: true,
+        }
+    }
+}
+
+impl GuildConfig {
+    /// The timezone of the guild, UTC if the configured one is unknown.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// Finds the configured prefix the message starts with.
+    ///
+    /// If multiple match, the longest one wins so `!!` isn't mistaken for `!`.
+    pub fn matching_prefix(&self, content: &str) -> Option<&str> {
+        std::iter::once(&self.prefix)
+            .chain(self.extra_prefixes.iter())
+            .map(String::as_str)
+            .filter(|prefix| !prefix.is_empty() && content.starts_with(prefix))
+            .max_by_key(|prefix| prefix.len())
+    }
+
+    pub fn command_disabled_in(&self, channel: ChannelId, command: &str) -> bool {
+        self.disabled_commands
+            .get(&channel)
+            .map_or(false, |commands| commands.iter().any(|disabled| disabled == command))
+    }
+
+    /// Looks for values the commands would never set, for configs that are written by hand.
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+
+        if self.prefix.is_empty() {
+            problems.push(ConfigProblem::EmptyPrefix);
+        }
+
+        let mut priorities = HashSet::new();
+        for group in &self.permission_groups {
+            if !priorities.insert(group.priority) {
+                problems.push(ConfigProblem::DuplicatePriority(group.priority));
+            }
+        }
+
+        if self.levels.min_xp > self.levels.max_xp {
+            problems.push(ConfigProblem::XpRange {
+                min: self.levels.min_xp,
+                max: self.levels.max_xp,
+            });
+        }
+
+        if self.timezone.parse::<Tz>().is_err() {
+            problems.push(ConfigProblem::UnknownTimezone(self.timezone.clone()));
+        }
+
+        for category in &LogCategory::ALL {
+            let appearance = match self.log_appearance.get(category) {
+                Some(appearance) => appearance,
+                None => continue,
+            };
+            if let Some(color) = appearance.color.filter(|color| *color == 0 || *color > 0xFF_FF_FF) {
+                problems.push(ConfigProblem::LogColor(category.clone(), color));
+            }
+            if let Some(emoji) = appearance
+                .emoji
+                .as_ref()
+                .filter(|emoji| !LogAppearance::valid_emoji(emoji))
+            {
+                problems.push(ConfigProblem::LogEmoji(category.clone(), emoji.clone()));
+            }
+        }
+
+        problems
+    }
+
+    /// Looks for the patterns and automod rules loading the config would quietly leave out, so only the JSON it
+    /// was loaded from still has them.
+    pub fn pattern_problems(raw: &Value) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+
+        let sources = raw["message_logs"]["ignored_patterns"].as_array().into_iter().flatten();
+        for source in sources.filter_map(Value::as_str) {
+            if let Err(e) = IgnoredPatterns::compile(source) {
+                problems.push(ConfigProblem::InvalidPattern(source.to_string(), e.to_string()));
+            }
+        }
+
+        if let Ok(rules) = serde_json::from_value::<Vec<AutomodRule>>(raw["automod"].clone()) {
+            let mut broken = false;
+            for rule in &rules {
+                if let Err(e) = rule.check() {
+                    problems.push(ConfigProblem::InvalidRule(rule.name.clone(), e.to_string()));
+                    broken = true;
+                }
+            }
+            // The limits only apply to all rules together
+            if !broken {
+                if let Err(e) = AutomodRules::try_compile(rules) {
+                    problems.push(ConfigProblem::InvalidRules(e.to_string()));
+                }
+            }
+        }
+
+        if let Ok(responses) = serde_json::from_value::<Vec<AutoResponse>>(raw["autoresponses"].clone()) {
+            let mut broken = false;
+            for response in &responses {
+                if let Err(e) = response.check() {
+                    problems.push(ConfigProblem::InvalidAutoResponse(response.name.clone(), e.to_string()));
+                    broken = true;
+                }
+            }
+            if !broken {
+                if let Err(e) = AutoResponses::try_compile(responses) {
+                    problems.push(ConfigProblem::InvalidAutoResponses(e.to_string()));
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// All channels the config points to.
+    pub fn referenced_channels(&self) -> HashSet<ChannelId> {
+        self.log_channels
+            .keys()
+            .chain(self.levels.ignored_channels.iter())
+            .chain(self.disabled_commands.keys())
+            .chain(self.command_output_channels.values())
+            .chain(self.expected_webhook_channels.iter())
+            .chain(self.raid_mode.restricted_channels.iter())
+            .chain(self.automod_review.channel.iter())
+            .chain(self.birthdays.channel.iter())
+            .chain(self.suggestions.channel.iter())
+            .chain(self.tickets.category.iter())
+            .chain(self.temp_voice.hubs.iter())
+            .chain(self.content_policy.channels.keys())
+            .chain(self.auto_publish.channels.keys())
+            .copied()
+            .chain(self.message_logs.ignored_channels.iter().map(|id| ChannelId(*id)))
+            .chain(self.automod.referenced_channels())
+            .chain(self.autoresponses.referenced_channels())
+            .chain(self.pin_archive.referenced_channels())
+            .collect()
+    }
+
+    /// All roles the config points to.
+    pub fn referenced_roles(&self) -> HashSet<RoleId> {
+        self.permission_groups
+            .iter()
+            .flat_map(|group| group.roles.iter())
+            .chain(self.levels.ignored_roles.iter())
+            .chain(self.levels.role_rewards.iter().map(|reward| &reward.role))
+            .copied()
+            .chain(self.message_logs.ignored_roles.iter().map(|id| RoleId(*id)))
+            .chain(
+                self.automod
+                    .rules()
+                    .iter()
+                    .flat_map(|rule| rule.exempt_roles.iter().copied()),
+            )
+            .chain(self.join_gate.probation_role)
+            .chain(self.join_gate.verification_role)
+            .chain(self.prune.exempt_roles.iter().copied())
+            .chain(self.image_moderation.exempt_roles.iter().copied())
+            .chain(self.content_policy.exempt_roles.iter().copied())
+            .chain(self.toxicity.exempt_roles.iter().copied())
+            .chain(self.tickets.staff_roles.iter().copied())
+            .chain(
+                self.auto_publish
+                    .channels
+                    .values()
+                    .flat_map(|filter| filter.roles.iter().copied()),
+            )
+            .chain(self.prune.role)
+            .chain(self.birthdays.role)
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigProblem {
+    EmptyPrefix,
+    DuplicatePriority(u8),
+    XpRange {
+        min: u32,
+        max: u32,
+    },
+    UnknownTimezone(String),
+    LogColor(LogCategory, u32),
+    LogEmoji(LogCategory, String),
+    InvalidPattern(String, String),
+    InvalidRule(String, String),
+    InvalidRules(String),
+    InvalidAutoResponse(String, String),
+    InvalidAutoResponses(String),
+    UnknownChannel(ChannelId),
+    UnknownRole(RoleId),
+    /// A log channel the bot is missing these permissions in.
+    LogPermissions(ChannelId, Permissions),
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigProblem::EmptyPrefix => write!(f, "the prefix is empty"),
+            ConfigProblem::DuplicatePriority(priority) => {
+                write!(f, "multiple permission groups have priority {}", priority)
+            }
+            ConfigProblem::XpRange { min, max } => {
+                write!(f, "the minimum xp ({}) is higher than the maximum xp ({})", min, max)
+            }
+            ConfigProblem::UnknownTimezone(name) => write!(f, "there is no timezone called ``{}``", name),
+            ConfigProblem::LogColor(category, color) => {
+                write!(f, "the {:?} log color ``{:#X}`` is not a valid color", category, color)
+            }
+            ConfigProblem::LogEmoji(category, emoji) => {
+                write!(f, "the {:?} log emoji ``{}`` is not a single emoji", category, emoji)
+            }
+            ConfigProblem::InvalidPattern(pattern, e) => {
+                write!(f, "the message log pattern ``{}`` is invalid: {}", pattern, e)
+            }
+            ConfigProblem::InvalidRule(name, e) => write!(f, "the automod rule ``{}`` is invalid: {}", name, e),
+            ConfigProblem::InvalidRules(e) => write!(f, "the automod rules can't be used: {}", e),
+            ConfigProblem::InvalidAutoResponse(name, e) => {
+                write!(f, "the autoresponse ``{}`` is invalid: {}", name, e)
+            }
+            ConfigProblem::InvalidAutoResponses(e) => write!(f, "the autoresponses can't be used: {}", e),
+            ConfigProblem::UnknownChannel(channel) => write!(f, "there is no channel with id {}", channel),
+            ConfigProblem::UnknownRole(role) => write!(f, "there is no role with id {}", role),
+            ConfigProblem::LogPermissions(channel, missing) => {
+                write!(f, "I'm missing {:?} to log in <#{}>", missing, channel)
+            }
+        }
+    }
+}
+
+fn default_timezone() -> String {
+    String::from("UTC")
 }
 
 impl Default for GuildConfig {
     fn default() -> Self {
         GuildConfig {
             prefix: "!".to_string(),
+            extra_prefixes: vec![],
             log_style: LogStyle::Text,
+            log_appearance: HashMap::new(),
             message_logs: MessageLogs {
                 enabled: false,
                 ignored_users: vec![],
                 ignored_channels: vec![],
                 ignore_bots: true,
+                ignored_roles: vec![],
+                ignored_patterns: IgnoredPatterns::default(),
+                mirror_attachments: false,
             },
             language: DEFAULT_LANG,
+            timezone: default_timezone(),
             permission_groups: vec![
                 PermissionGroup {
                     priority: 0,
@@ -109,6 +702,209 @@ impl Default for GuildConfig {
                 },
             ],
             log_channels: HashMap::new(),
+            levels: LevelConfig::default(),
+            infraction_retention_days: None,
+            archive_retention_days: None,
+            attachment_retention_days: None,
+            features: GuildFeatures::all(),
+            command_correction: CommandCorrection::default(),
+            command_aliases: HashMap::new(),
+            disabled_commands: HashMap::new(),
+            command_output_channels: HashMap::new(),
+            expected_webhook_channels: vec![],
+            bot_banned_users: vec![],
+            automod: AutomodRules::default(),
+            automod_review: AutomodReviewConfig::default(),
+            autoresponses: AutoResponses::default(),
+            image_moderation: ImageModerationConfig::default(),
+            content_policy: ContentPolicyConfig::default(),
+            pin_archive: PinArchiveConfig::default(),
+            toxicity: ToxicityConfig::default(),
+            heat: HeatConfig::default(),
+            join_gate: JoinGateConfig::default(),
+            raid_mode: RaidModeConfig::default(),
+            escalation: EscalationConfig::default(),
+            anti_nuke: AntiNukeConfig::default(),
+            prune: PruneConfig::default(),
+            birthdays: BirthdayConfig::default(),
+            suggestions: SuggestionConfig::default(),
+            tickets: TicketConfig::default(),
+            temp_voice: TempVoiceConfig::default(),
+            events: EventConfig::default(),
+            auto_publish: AutoPublishConfig::default(),
+            reputation_network: false,
+            profiles: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_main_and_extra_prefixes() {
+        let mut config = GuildConfig::default();
+        config.extra_prefixes = vec![String::from("gb "), String::from("!!")];
+
+        assert_eq!(config.matching_prefix("!ping"), Some("!"));
+        assert_eq!(config.matching_prefix("gb ping"), Some("gb "));
+        assert_eq!(config.matching_prefix("!!ping"), Some("!!"));
+        assert_eq!(config.matching_prefix("?ping"), None);
+    }
+
+    #[test]
+    fn ignores_empty_prefixes() {
+        let mut config = GuildConfig::default();
+        config.extra_prefixes = vec![String::new()];
+
+        assert_eq!(config.matching_prefix("ping"), None);
+    }
+
+    #[test]
+    fn message_logs_ignore_roles_and_patterns() {
+        let config: MessageLogs = serde_json::from_str(
+            r#"{
+                "enabled": true,
+                "ignored_users": [],
+                "ignored_channels": [3],
+                "ignore_bots": true,
+                "ignored_roles": [10],
+                "ignored_patterns": ["^!", "(unclosed"]
+            }"#,
+        )
+        .unwrap();
+
+        let logged = |roles: &[RoleId], content: &str| !config.ignores(UserId(1), false, ChannelId(2), roles, content);
+
+        assert!(logged(&[RoleId(11)], "hello"));
+        assert!(!logged(&[RoleId(11), RoleId(10)], "hello"));
+        assert!(!logged(&[], "!command"));
+        assert!(!config.ignores(UserId(1), false, ChannelId(2), &[], "(unclosed"));
+        assert!(config.ignores(UserId(1), false, ChannelId(3), &[], "hello"));
+        assert!(config.ignores(UserId(1), true, ChannelId(2), &[], "hello"));
+    }
+
+    #[test]
+    fn ignored_patterns_roundtrip_as_source() {
+        let patterns: IgnoredPatterns = serde_json::from_str(r#"["^!", "(unclosed"]"#).unwrap();
+        assert_eq!(serde_json::to_string(&patterns).unwrap(), r#"["^!"]"#);
+    }
+
+    #[test]
+    fn features_default_to_all_and_match_names() {
+        let mut config = serde_json::to_value(GuildConfig::default()).unwrap();
+        config.as_object_mut().unwrap().remove("features");
+        let config: GuildConfig = serde_json::from_value(config).unwrap();
+
+        assert_eq!(config.features, GuildFeatures::all());
+        assert_eq!(GuildFeatures::from_name("Leveling"), Some(GuildFeatures::LEVELING));
+        assert_eq!(GuildFeatures::from_name("starboard"), None);
+    }
+
+    #[test]
+    fn commands_are_disabled_per_channel() {
+        let mut config = GuildConfig::default();
+        config
+            .disabled_commands
+            .insert(ChannelId(1), vec![String::from("ping")]);
+
+        assert!(config.command_disabled_in(ChannelId(1), "ping"));
+        assert!(!config.command_disabled_in(ChannelId(1), "about"));
+        assert!(!config.command_disabled_in(ChannelId(2), "ping"));
+    }
+
+    #[test]
+    fn finds_problems_in_hand_written_configs() {
+        assert!(GuildConfig::default().problems().is_empty());
+
+        let mut config = GuildConfig::default();
+        config.prefix = String::new();
+        config.permission_groups[1].priority = 0;
+        config.levels.min_xp = 30;
+        config.timezone = String::from("Mars/Olympus_Mons");
+        assert_eq!(
+            config.problems(),
+            vec![
+                ConfigProblem::EmptyPrefix,
+                ConfigProblem::DuplicatePriority(0),
+                ConfigProblem::XpRange { min: 30, max: 25 },
+                ConfigProblem::UnknownTimezone(String::from("Mars/Olympus_Mons"))
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_log_appearance() {
+        let mut config = GuildConfig::default();
+        config.log_appearance.insert(
+            LogCategory::GENERAL,
+            LogAppearance {
+                color: Some(0x1_00_00_00),
+                emoji: Some(String::from("🔨")),
+                hidden_fields: vec![],
+            },
+        );
+        config.log_appearance.insert(
+            LogCategory::MEMBERS,
+            LogAppearance {
+                emoji: Some(String::from("hammer")),
+                ..LogAppearance::default()
+            },
+        );
+        config.log_appearance.insert(
+            LogCategory::WATCHLIST,
+            LogAppearance {
+                color: Some(0x58_65_F2),
+                emoji: Some(String::from("<a:eyes:123456789012345678>")),
+                hidden_fields: vec![LogField::Thumbnail],
+            },
+        );
+
+        assert_eq!(
+            config.problems(),
+            vec![
+                ConfigProblem::LogColor(LogCategory::GENERAL, 0x1_00_00_00),
+                ConfigProblem::LogEmoji(LogCategory::MEMBERS, String::from("hammer"))
+            ]
+        );
+
+        let watchlist = &config.log_appearance[&LogCategory::WATCHLIST];
+        assert!(!watchlist.shows(LogField::Thumbnail));
+        assert_eq!(
+            watchlist.icon_url().as_deref(),
+            Some("https://cdn.discordapp.com/emojis/123456789012345678.gif")
+        );
+        assert_eq!(config.log_appearance[&LogCategory::GENERAL].icon_url(), None);
+    }
+
+    #[test]
+    fn bot_bans_default_to_nobody() {
+        let mut config = serde_json::to_value(GuildConfig::default()).unwrap();
+        config.as_object_mut().unwrap().remove("bot_banned_users");
+        let config: GuildConfig = serde_json::from_value(config).unwrap();
+
+        assert!(config.bot_banned_users.is_empty());
+    }
+
+    #[test]
+    fn finds_patterns_that_would_be_left_out() {
+        let mut raw = serde_json::to_value(GuildConfig::default()).unwrap();
+        assert!(GuildConfig::pattern_problems(&raw).is_empty());
+
+        raw["message_logs"]["ignored_patterns"] = serde_json::json!(["spoiler", "(unclosed"]);
+        raw["automod"] = serde_json::json!([
+            {"name": "invites", "conditions": [{"type": "content", "pattern": "discord\\.gg/"}], "actions": ["delete"]},
+            {"name": "broken", "conditions": [{"type": "content", "pattern": "[a-"}], "actions": ["delete"]}
+        ]);
+        raw["autoresponses"] = serde_json::json!([
+            {"name": "hi", "trigger": {"type": "exact", "text": "hi"}, "response": "hello"},
+            {"name": "bad", "trigger": {"type": "regex", "pattern": "(a"}, "response": "b"}
+        ]);
+        let problems = GuildConfig::pattern_problems(&raw);
+        assert_eq!(problems.len(), 3);
+        assert!(matches!(&problems[0], ConfigProblem::InvalidPattern(pattern, _) if pattern == "(unclosed"));
+        assert!(matches!(&problems[1], ConfigProblem::InvalidRule(name, _) if name == "broken"));
+        assert!(matches!(&problems[2], ConfigProblem::InvalidAutoResponse(name, _) if name == "bad"));
+    }
+}
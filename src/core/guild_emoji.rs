@@ -0,0 +1,189 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::PremiumTier;
+use twilight_model::id::EmojiId;
+
+use crate::cache::CachedEmoji;
+use crate::core::public_fetch;
+
+/// Discord refuses emoji images bigger than this.
+pub const MAX_EMOJI_SIZE: usize = 256 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The client emoji images are downloaded with, it only goes to the public internet.
+pub fn download_client() -> Result<reqwest::Client, reqwest::Error> {
+    public_fetch::client_builder(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("GearBot/", env!("CARGO_PKG_VERSION"), " (emoji)"))
+        .build()
+}
+
+/// How many emoji a guild can have at its boost tier. Static and animated emoji each get this many slots.
+pub fn emoji_slots(tier: PremiumTier) -> usize {
+    match tier {
+        PremiumTier::None => 50,
+        PremiumTier::Tier1 => 100,
+        PremiumTier::Tier2 => 150,
+        PremiumTier::Tier3 => 250,
+    }
+}
+
+/// How many slots of the kind are still free. Emoji from integrations don't take up any.
+pub fn free_emoji_slots(emoji: &[Arc<CachedEmoji>], tier: PremiumTier, animated: bool) -> usize {
+    let used = emoji
+        .iter()
+        .filter(|emoji| !emoji.managed && emoji.animated == animated)
+        .count();
+    emoji_slots(tier).saturating_sub(used)
+}
+
+/// Emoji names are 2 to 32 characters, only letters, numbers and underscores.
+pub fn is_valid_emoji_name(name: &str) -> bool {
+    (2..=32).contains(&name.chars().count()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The kind of image by its first bytes, only the ones emoji can be made of. Returns the mime type and if
+/// it's animated.
+pub fn emoji_image_type(data: &[u8]) -> Option<(&'static str, bool)> {
+    if data.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some(("image/png", false))
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("image/jpeg", false))
+    } else if data.starts_with(b"GIF8") {
+        Some(("image/gif", true))
+    } else {
+        None
+    }
+}
+
+/// The image as the data URI discord wants for uploads, along with if it's animated. `None` if it's not an image
+/// emoji can be made of.
+pub fn emoji_data_uri(data: &[u8]) -> Option<(String, bool)> {
+    let (mime, animated) = emoji_image_type(data)?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    Some((format!("data:{};base64,{}", mime, encoded), animated))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EmojiChange {
+    Created { id: EmojiId, name: String, animated: bool },
+    Deleted { id: EmojiId, name: String },
+    Renamed { id: EmojiId, before: String, after: String },
+}
+
+impl EmojiChange {
+    pub fn id(&self) -> EmojiId {
+        match self {
+            EmojiChange::Created { id, .. } | EmojiChange::Deleted { id, .. } | EmojiChange::Renamed { id, .. } => *id,
+        }
+    }
+}
+
+/// What changed between two versions of the emoji of a guild. Only names are compared, discord sends all emoji
+/// again when anything about one of them changes.
+pub fn emoji_changes(old: &[Arc<CachedEmoji>], new: &[Arc<CachedEmoji>]) -> Vec<EmojiChange> {
+    let mut changes = vec![];
+    for emoji in new {
+        match old.iter().find(|old| old.id == emoji.id) {
+            Some(old) if old.name != emoji.name => changes.push(EmojiChange::Renamed {
+                id: emoji.id,
+                before: old.name.clone(),
+                after: emoji.name.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(EmojiChange::Created {
+                id: emoji.id,
+                name: emoji.name.clone(),
+                animated: emoji.animated,
+            }),
+        }
+    }
+    for emoji in old {
+        if !new.iter().any(|new| new.id == emoji.id) {
+            changes.push(EmojiChange::Deleted {
+                id: emoji.id,
+                name: emoji.name.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emoji(id: u64, name: &str, animated: bool, managed: bool) -> Arc<CachedEmoji> {
+        Arc::new(CachedEmoji {
+            id: EmojiId(id),
+            name: String::from(name),
+            roles: vec![],
+            created_by: None,
+            requires_colons: true,
+            managed,
+            animated,
+            available: true,
+        })
+    }
+
+    #[test]
+    fn counts_slots_and_changes() {
+        let old = vec![
+            emoji(1, "gear", false, false),
+            emoji(2, "spin", true, false),
+            emoji(3, "twitch", false, true),
+        ];
+        assert_eq!(free_emoji_slots(&old, PremiumTier::None, false), 49);
+        assert_eq!(free_emoji_slots(&old, PremiumTier::Tier1, true), 99);
+
+        let new = vec![
+            emoji(1, "gears", false, false),
+            emoji(3, "twitch", false, true),
+            emoji(4, "wave", true, false),
+        ];
+        assert_eq!(
+            emoji_changes(&old, &new),
+            vec![
+                EmojiChange::Renamed {
+                    id: EmojiId(1),
+                    before: String::from("gear"),
+                    after: String::from("gears"),
+                },
+                EmojiChange::Created {
+                    id: EmojiId(4),
+                    name: String::from("wave"),
+                    animated: true,
+                },
+                EmojiChange::Deleted {
+                    id: EmojiId(2),
+                    name: String::from("spin"),
+                },
+            ]
+        );
+        assert!(emoji_changes(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn validates_names_and_images() {
+        assert!(is_valid_emoji_name("gear_bot2"));
+        assert!(!is_valid_emoji_name("g"));
+        assert!(!is_valid_emoji_name("gear-bot"));
+        assert!(!is_valid_emoji_name(&"a".repeat(33)));
+
+        assert_eq!(emoji_image_type(b"GIF89a..."), Some(("image/gif", true)));
+        assert_eq!(
+            emoji_image_type(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A]),
+            Some(("image/png", false))
+        );
+        assert_eq!(emoji_image_type(b"<html>"), None);
+
+        assert_eq!(
+            emoji_data_uri(b"GIF89a"),
+            Some((String::from("data:image/gif;base64,R0lGODlh"), true))
+        );
+        assert_eq!(emoji_data_uri(b"<html>"), None);
+    }
+}
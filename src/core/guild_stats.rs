@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Timelike};
+use chrono_tz::Tz;
+use twilight_model::id::ChannelId;
+
+/// Guild statistics are kept for a year, long enough to compare with the same time last year.
+pub const STATS_RETENTION_DAYS: u32 = 366;
+
+/// The things that are counted per guild for the statistics.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GuildStat {
+    Message,
+    Join,
+    Leave,
+    Command,
+    AutomodTrigger,
+}
+
+/// Counts of a guild that weren't written to the database yet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct GuildStatCounts {
+    pub messages: u32,
+    pub joins: u32,
+    pub leaves: u32,
+    pub commands: u32,
+    pub automod_triggers: u32,
+}
+
+impl GuildStatCounts {
+    pub fn add(&mut self, stat: GuildStat) {
+        let count = match stat {
+            GuildStat::Message => &mut self.messages,
+            GuildStat::Join => &mut self.joins,
+            GuildStat::Leave => &mut self.leaves,
+            GuildStat::Command => &mut self.commands,
+            GuildStat::AutomodTrigger => &mut self.automod_triggers,
+        };
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Uses of a command in a channel that weren't written to the database yet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct CommandUseCounts {
+    pub uses: u32,
+    /// Uses that ended in an error, counted in the uses as well.
+    pub failures: u32,
+}
+
+impl CommandUseCounts {
+    pub fn add(&mut self, succeeded: bool) {
+        self.uses = self.uses.saturating_add(1);
+        if !succeeded {
+            self.failures = self.failures.saturating_add(1);
+        }
+    }
+}
+
+/// How much a count went up or down compared to the period before, in percent. `None` when there was nothing
+/// before to compare with.
+pub fn trend(current: u64, previous: u64) -> Option<i64> {
+    if previous == 0 {
+        return None;
+    }
+    Some((current as i64 - previous as i64) * 100 / previous as i64)
+}
+
+/// The start of the hour a timestamp falls in, messages are counted per channel per hour.
+pub fn hour_bucket(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(3600)
+}
+
+/// Where and when messages were sent over a period.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActivityDistribution {
+    /// Messages per channel, most active first.
+    pub channels: Vec<(ChannelId, u64)>,
+    /// Messages per hour of the day, in the timezone of the guild.
+    pub hours: [u64; 24],
+    pub total: u64,
+}
+
+impl ActivityDistribution {
+    /// Adds up the hourly counts of every channel, `hour` being the start of the hour as a unix timestamp.
+    pub fn collect(counts: impl IntoIterator<Item = (ChannelId, i64, u64)>, timezone: Tz) -> Self {
+        let mut channels = HashMap::new();
+        let mut hours = [0; 24];
+        let mut total = 0;
+        for (channel_id, hour, messages) in counts {
+            *channels.entry(channel_id).or_insert(0) += messages;
+            hours[timezone.timestamp(hour, 0).hour() as usize] += messages;
+            total += messages;
+        }
+
+        let mut channels = channels.into_iter().collect::<Vec<(ChannelId, u64)>>();
+        channels.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then(a_id.cmp(b_id)));
+        ActivityDistribution { channels, hours, total }
+    }
+}
+
+/// How much of the total a count is, in whole percent.
+pub fn share(count: u64, total: u64) -> u64 {
+    if total == 0 {
+        return 0;
+    }
+    count * 100 / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_and_compares_periods() {
+        let mut counts = GuildStatCounts::default();
+        counts.add(GuildStat::Message);
+        counts.add(GuildStat::Message);
+        counts.add(GuildStat::Leave);
+        assert_eq!(
+            counts,
+            GuildStatCounts {
+                messages: 2,
+                leaves: 1,
+                ..GuildStatCounts::default()
+            }
+        );
+
+        let mut uses = CommandUseCounts::default();
+        uses.add(true);
+        uses.add(false);
+        assert_eq!(uses, CommandUseCounts { uses: 2, failures: 1 });
+
+        assert_eq!(trend(150, 100), Some(50));
+        assert_eq!(trend(25, 100), Some(-75));
+        assert_eq!(trend(10, 0), None);
+    }
+
+    #[test]
+    fn distributes_activity_over_channels_and_hours() {
+        // 2021-01-01 10:00 and 23:00 UTC
+        let morning = 1_609_495_200;
+        let night = 1_609_542_000;
+        assert_eq!(hour_bucket(morning + 1_234), morning);
+
+        let counts = vec![
+            (ChannelId(1), morning, 5),
+            (ChannelId(2), morning, 10),
+            (ChannelId(1), night, 1),
+        ];
+        let distribution = ActivityDistribution::collect(counts, chrono_tz::Europe::Brussels);
+        assert_eq!(distribution.channels, vec![(ChannelId(2), 10), (ChannelId(1), 6)]);
+        assert_eq!(distribution.hours[11], 15);
+        // Past midnight in Brussels
+        assert_eq!(distribution.hours[0], 1);
+        assert_eq!(distribution.total, 16);
+        assert_eq!(share(6, 16), 37);
+        assert_eq!(share(1, 0), 0);
+    }
+}
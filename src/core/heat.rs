@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::automod::AutomodAction;
+
+/// Escalation based on how much trouble someone caused lately, instead of on single rule violations.
+///
+/// Automod rules add their heat to the author, which cools down over time. Crossing a step takes its action.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HeatConfig {
+    pub enabled: bool,
+    /// How much heat goes away every minute.
+    pub decay_per_minute: f64,
+    pub steps: Vec<HeatStep>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct HeatStep {
+    pub threshold: u32,
+    pub action: AutomodAction,
+}
+
+impl Default for HeatConfig {
+    fn default() -> Self {
+        HeatConfig {
+            enabled: false,
+            decay_per_minute: 1.0,
+            steps: vec![],
+        }
+    }
+}
+
+impl HeatConfig {
+    /// The action of the highest step that was crossed going from one heat to the other, if any.
+    pub fn escalation(&self, before: f64, after: f64) -> Option<&HeatStep> {
+        self.steps
+            .iter()
+            .filter(|step| before < step.threshold as f64 && after >= step.threshold as f64)
+            .max_by_key(|step| step.threshold)
+    }
+}
+
+/// The heat of a user, as of the last time it changed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct HeatState {
+    pub heat: f64,
+    /// Unix timestamp.
+    pub updated: i64,
+}
+
+impl HeatState {
+    /// What is left of the heat at this time.
+    pub fn current(&self, now: i64, decay_per_minute: f64) -> f64 {
+        let minutes = (now - self.updated).max(0) as f64 / 60.0;
+        (self.heat - minutes * decay_per_minute.max(0.0)).max(0.0)
+    }
+
+    /// Seconds until all heat is gone, `None` if it never decays.
+    pub fn seconds_left(&self, decay_per_minute: f64) -> Option<u32> {
+        if decay_per_minute <= 0.0 {
+            return None;
+        }
+        Some((self.heat / decay_per_minute * 60.0).ceil().max(1.0) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heat_decays_over_time() {
+        let state = HeatState {
+            heat: 10.0,
+            updated: 1000,
+        };
+
+        assert_eq!(state.current(1000, 2.0), 10.0);
+        assert_eq!(state.current(1090, 2.0), 7.0);
+        assert_eq!(state.current(2000, 2.0), 0.0);
+        assert_eq!(state.current(2000, 0.0), 10.0);
+        assert_eq!(state.seconds_left(2.0), Some(300));
+        assert_eq!(state.seconds_left(0.0), None);
+    }
+
+    #[test]
+    fn escalates_to_the_highest_crossed_step() {
+        let config = HeatConfig {
+            enabled: true,
+            decay_per_minute: 1.0,
+            steps: vec![
+                HeatStep {
+                    threshold: 10,
+                    action: AutomodAction::Warn,
+                },
+                HeatStep {
+                    threshold: 20,
+                    action: AutomodAction::Kick,
+                },
+                HeatStep {
+                    threshold: 30,
+                    action: AutomodAction::Ban,
+                },
+            ],
+        };
+
+        let action = |before, after| config.escalation(before, after).map(|step| step.action);
+        assert_eq!(action(0.0, 5.0), None);
+        assert_eq!(action(5.0, 12.0), Some(AutomodAction::Warn));
+        assert_eq!(action(12.0, 15.0), None);
+        assert_eq!(action(5.0, 25.0), Some(AutomodAction::Kick));
+        assert_eq!(action(0.0, 40.0), Some(AutomodAction::Ban));
+    }
+}
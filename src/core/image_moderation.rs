@@ -0,0 +1,233 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use twilight_model::id::RoleId;
+
+use super::automod::AutomodAction;
+use super::bot_config::{ModerationApi, OcrApi};
+use crate::error::ClassifierError;
+
+/// Extensions of attachments that get sent for labeling.
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "webp"];
+
+pub type ClassifyResult<'a> = Pin<Box<dyn Future<Output = Result<Vec<ImageLabel>, ClassifierError>> + Send + 'a>>;
+pub type ExtractResult<'a> = Pin<Box<dyn Future<Output = Result<String, ClassifierError>> + Send + 'a>>;
+
+/// Something that can tell what's in an image, like nsfw content or gore.
+pub trait ImageClassifier: Send + Sync {
+    /// Labels the image behind the url, leaving out labels that don't apply at all.
+    fn classify<'a>(&'a self, url: &'a str) -> ClassifyResult<'a>;
+}
+
+/// Something that can read the text in an image.
+pub trait TextExtractor: Send + Sync {
+    /// The text in the image behind the url, empty if there is none.
+    fn extract<'a>(&'a self, url: &'a str) -> ExtractResult<'a>;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ImageLabel {
+    /// Lowercase, like ``nsfw`` or ``gore``.
+    pub name: String,
+    /// How sure the classifier is, from 0 to 1.
+    pub confidence: f32,
+}
+
+/// Talks to a moderation API over HTTP, see [`ModerationApi`] for what it expects.
+pub struct HttpClassifier {
+    client: reqwest::Client,
+    endpoint: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClassifyResponse {
+    labels: Vec<ImageLabel>,
+}
+
+impl HttpClassifier {
+    pub fn new(config: &ModerationApi) -> Result<Self, ClassifierError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+
+        Ok(HttpClassifier {
+            client,
+            endpoint: config.endpoint.clone(),
+            token: config.token.clone(),
+        })
+    }
+}
+
+impl ImageClassifier for HttpClassifier {
+    fn classify<'a>(&'a self, url: &'a str) -> ClassifyResult<'a> {
+        Box::pin(async move {
+            let body = serde_json::json!({ "url": url }).to_string();
+            let mut request = self
+                .client
+                .post(&self.endpoint)
+                .header("Content-Type", "application/json")
+                .body(body);
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await?.error_for_status()?.bytes().await?;
+            let response: ClassifyResponse =
+                serde_json::from_slice(&response).map_err(ClassifierError::InvalidResponse)?;
+
+            Ok(response
+                .labels
+                .into_iter()
+                .map(|label| ImageLabel {
+                    name: label.name.to_lowercase(),
+                    confidence: label.confidence,
+                })
+                .collect())
+        })
+    }
+}
+
+/// Reads images through an OCR service over HTTP, see [`OcrApi`] for what it expects.
+pub struct HttpTextExtractor {
+    client: reqwest::Client,
+    endpoint: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExtractResponse {
+    text: String,
+}
+
+impl HttpTextExtractor {
+    pub fn new(config: &OcrApi) -> Result<Self, ClassifierError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+
+        Ok(HttpTextExtractor {
+            client,
+            endpoint: config.endpoint.clone(),
+            token: config.token.clone(),
+        })
+    }
+}
+
+impl TextExtractor for HttpTextExtractor {
+    fn extract<'a>(&'a self, url: &'a str) -> ExtractResult<'a> {
+        Box::pin(async move {
+            let body = serde_json::json!({ "url": url }).to_string();
+            let mut request = self
+                .client
+                .post(&self.endpoint)
+                .header("Content-Type", "application/json")
+                .body(body);
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await?.error_for_status()?.bytes().await?;
+            let response: ExtractResponse =
+                serde_json::from_slice(&response).map_err(ClassifierError::InvalidResponse)?;
+
+            Ok(response.text)
+        })
+    }
+}
+
+/// What a guild wants done about images with certain labels. Needs the automod feature and a moderation API to
+/// be configured for the bot.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ImageModerationConfig {
+    pub policies: Vec<ImagePolicy>,
+    /// Members with any of these roles can post whatever they want.
+    pub exempt_roles: Vec<RoleId>,
+    /// Runs the text in images through the automod rules as well. Every image costs a call to the OCR service, so
+    /// this is off unless the guild asks for it.
+    pub scan_text: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ImagePolicy {
+    pub label: String,
+    /// How sure the classifier has to be before acting, from 0 to 1.
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+    pub actions: Vec<AutomodAction>,
+}
+
+fn default_min_confidence() -> f32 {
+    0.8
+}
+
+impl ImageModerationConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.policies.is_empty()
+    }
+
+    /// The policies that apply to an image with these labels, with the label that triggered them.
+    pub fn triggered<'a>(&'a self, labels: &'a [ImageLabel]) -> Vec<(&'a ImagePolicy, &'a ImageLabel)> {
+        self.policies
+            .iter()
+            .filter_map(|policy| {
+                labels
+                    .iter()
+                    .find(|label| {
+                        label.name.eq_ignore_ascii_case(&policy.label) && label.confidence >= policy.min_confidence
+                    })
+                    .map(|label| (policy, label))
+            })
+            .collect()
+    }
+}
+
+/// If an attachment is an image the classifier can look at, going by its name.
+pub fn is_image(filename: &str) -> bool {
+    filename.rsplit_once('.').map_or(false, |(_, extension)| {
+        IMAGE_EXTENSIONS
+            .iter()
+            .any(|image| extension.eq_ignore_ascii_case(image))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(name: &str, confidence: f32) -> ImageLabel {
+        ImageLabel {
+            name: String::from(name),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn applies_policies_above_their_confidence() {
+        let config: ImageModerationConfig = serde_json::from_str(
+            r#"{"policies": [
+                {"label": "nsfw", "actions": ["delete"]},
+                {"label": "gore", "min_confidence": 0.5, "actions": ["delete", "warn"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let labels = vec![label("nsfw", 0.6), label("gore", 0.7)];
+        let triggered = config.triggered(&labels);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].0.label, "gore");
+        assert_eq!(triggered[0].1, &labels[1]);
+
+        assert!(config.triggered(&[label("suggestive", 0.99)]).is_empty());
+        assert_eq!(config.triggered(&[label("NSFW", 0.95)]).len(), 1);
+    }
+
+    #[test]
+    fn recognizes_images() {
+        assert!(is_image("cat.PNG"));
+        assert!(is_image("photo.final.jpeg"));
+        assert!(!is_image("notes.txt"));
+        assert!(!is_image("png"));
+    }
+}
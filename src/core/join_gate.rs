@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{GuildId, RoleId};
+
+/// Checks done on everyone that joins, to catch throwaway accounts before they can do anything.
+///
+/// During raid mode the gate is active even when it's not enabled.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct JoinGateConfig {
+    pub enabled: bool,
+    /// Accounts younger than this get flagged, 0 to not look at the account age.
+    pub min_account_age_days: u32,
+    pub require_avatar: bool,
+    /// Members banned from at least this many other guilds of the reputation network get flagged, 0 to not look
+    /// at that. Only works when the guild is part of the network itself.
+    pub network_bans: u64,
+    /// What happens to members that get flagged.
+    pub action: JoinGateAction,
+    pub probation_role: Option<RoleId>,
+    /// Handed out for the verification action and to members that get challenged, until they pass.
+    pub verification_role: Option<RoleId>,
+    /// Which challenge members in each risk tier get, whether they got flagged or not.
+    pub challenges: ChallengePolicy,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinGateAction {
+    Log,
+    Probation,
+    Verification,
+    /// Kicks while raid mode is on, only logs otherwise.
+    RaidKick,
+}
+
+/// What a new member has to do to lose the verification role again, from least to most effort.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeLevel {
+    None,
+    /// React to a DM from the bot.
+    Reaction,
+    /// Send back the code from a DM from the bot.
+    Captcha,
+    /// Wait for a moderator to let them through with ``joingate verify``.
+    Manual,
+}
+
+/// The things that make a new member risky enough to challenge.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RiskTier {
+    /// Younger than the minimum account age of the join gate.
+    NewAccount,
+    Watched,
+    RaidMode,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ChallengePolicy {
+    pub new_account: ChallengeLevel,
+    pub watched: ChallengeLevel,
+    pub raid_mode: ChallengeLevel,
+}
+
+/// A member that still has to pass membership screening, kept until they do.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PendingScreening {
+    /// The join gate role they get once they're through, Discord doesn't let roles do anything before that.
+    pub gate_role: Option<RoleId>,
+    /// The challenge that starts once they're through, DMs about a guild they can't see yet only confuse people.
+    #[serde(default)]
+    pub challenge: ChallengeLevel,
+}
+
+/// A captcha that was DMed to a new member. DMs don't say which guild they're about, so only the latest one of
+/// each member is kept.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PendingCaptcha {
+    pub guild_id: GuildId,
+    pub code: String,
+}
+
+/// Why someone got flagged.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum JoinGateReason {
+    NewAccount { age_days: u64 },
+    NoAvatar,
+    NetworkBans { guilds: u64 },
+}
+
+impl Default for JoinGateConfig {
+    fn default() -> Self {
+        JoinGateConfig {
+            enabled: false,
+            min_account_age_days: 7,
+            require_avatar: false,
+            network_bans: 0,
+            action: JoinGateAction::Log,
+            probation_role: None,
+            verification_role: None,
+            challenges: ChallengePolicy::default(),
+        }
+    }
+}
+
+impl Default for ChallengeLevel {
+    fn default() -> Self {
+        ChallengeLevel::None
+    }
+}
+
+impl JoinGateConfig {
+    /// The reasons to flag a new member, empty if they can pass.
+    pub fn check(&self, account_age_hours: u64, has_avatar: bool, network_bans: u64) -> Vec<JoinGateReason> {
+        let mut reasons = vec![];
+        if account_age_hours < self.min_account_age_days as u64 * 24 {
+            reasons.push(JoinGateReason::NewAccount {
+                age_days: account_age_hours / 24,
+            });
+        }
+        if self.require_avatar && !has_avatar {
+            reasons.push(JoinGateReason::NoAvatar);
+        }
+        if self.network_bans > 0 && network_bans >= self.network_bans {
+            reasons.push(JoinGateReason::NetworkBans { guilds: network_bans });
+        }
+        reasons
+    }
+
+    /// The role the action hands out, if any.
+    pub fn role(&self, action: JoinGateAction) -> Option<RoleId> {
+        match action {
+            JoinGateAction::Probation => self.probation_role,
+            JoinGateAction::Verification => self.verification_role,
+            JoinGateAction::Log | JoinGateAction::RaidKick => None,
+        }
+    }
+
+    /// The challenge for a new member in these risk tiers. Members that got flagged with the verification action
+    /// always have to be let through by hand.
+    pub fn challenge(&self, tiers: &[RiskTier], flagged_action: Option<JoinGateAction>) -> ChallengeLevel {
+        let level = self.challenges.level_for(tiers);
+        if flagged_action == Some(JoinGateAction::Verification) {
+            level.max(ChallengeLevel::Manual)
+        } else {
+            level
+        }
+    }
+}
+
+impl ChallengePolicy {
+    /// The hardest challenge any of the tiers asks for.
+    pub fn level_for(&self, tiers: &[RiskTier]) -> ChallengeLevel {
+        tiers
+            .iter()
+            .map(|tier| match tier {
+                RiskTier::NewAccount => self.new_account,
+                RiskTier::Watched => self.watched,
+                RiskTier::RaidMode => self.raid_mode,
+            })
+            .max()
+            .unwrap_or(ChallengeLevel::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_new_accounts_and_missing_avatars() {
+        let config = JoinGateConfig {
+            enabled: true,
+            min_account_age_days: 3,
+            require_avatar: true,
+            ..JoinGateConfig::default()
+        };
+
+        assert_eq!(config.check(24 * 5, true, 3), vec![]);
+        assert_eq!(
+            config.check(30, false, 0),
+            vec![JoinGateReason::NewAccount { age_days: 1 }, JoinGateReason::NoAvatar]
+        );
+
+        let config = JoinGateConfig {
+            min_account_age_days: 0,
+            ..config
+        };
+        assert_eq!(config.check(0, true, 0), vec![]);
+    }
+
+    #[test]
+    fn flags_members_banned_across_the_network() {
+        let config = JoinGateConfig {
+            enabled: true,
+            min_account_age_days: 0,
+            network_bans: 2,
+            ..JoinGateConfig::default()
+        };
+
+        assert_eq!(config.check(0, true, 1), vec![]);
+        assert_eq!(
+            config.check(0, true, 3),
+            vec![JoinGateReason::NetworkBans { guilds: 3 }]
+        );
+
+        let config = JoinGateConfig {
+            network_bans: 0,
+            ..config
+        };
+        assert_eq!(config.check(0, true, 10), vec![]);
+    }
+
+    #[test]
+    fn picks_the_hardest_challenge() {
+        let config = JoinGateConfig {
+            challenges: ChallengePolicy {
+                new_account: ChallengeLevel::Reaction,
+                watched: ChallengeLevel::Captcha,
+                raid_mode: ChallengeLevel::None,
+            },
+            ..JoinGateConfig::default()
+        };
+
+        assert_eq!(config.challenge(&[], None), ChallengeLevel::None);
+        assert_eq!(config.challenge(&[RiskTier::RaidMode], None), ChallengeLevel::None);
+        assert_eq!(
+            config.challenge(
+                &[RiskTier::NewAccount, RiskTier::Watched],
+                Some(JoinGateAction::Probation)
+            ),
+            ChallengeLevel::Captcha
+        );
+        assert_eq!(
+            config.challenge(&[RiskTier::NewAccount], Some(JoinGateAction::Verification)),
+            ChallengeLevel::Manual
+        );
+    }
+}
@@ -0,0 +1,50 @@
+/// Amount of xp needed to go from `level` to the next one.
+pub fn xp_for_next_level(level: u32) -> u64 {
+    let level = level as u64;
+    5 * level * level + 50 * level + 100
+}
+
+/// Total amount of xp needed to reach `level` starting from nothing.
+pub fn total_xp_for_level(level: u32) -> u64 {
+    (0..level).map(xp_for_next_level).sum()
+}
+
+/// Calculates the level for a total amount of xp.
+///
+/// Returns `(level, xp into the current level, xp needed for the next level)`.
+pub fn level_progress(xp: u64) -> (u32, u64, u64) {
+    let mut level = 0;
+    let mut remaining = xp;
+    loop {
+        let needed = xp_for_next_level(level);
+        if remaining < needed {
+            return (level, remaining, needed);
+        }
+        remaining -= needed;
+        level += 1;
+    }
+}
+
+pub fn level_for_xp(xp: u64) -> u32 {
+    level_progress(xp).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_thresholds_work() {
+        assert_eq!(level_for_xp(0), 0);
+        assert_eq!(level_for_xp(99), 0);
+        assert_eq!(level_for_xp(100), 1);
+        assert_eq!(level_for_xp(total_xp_for_level(10)), 10);
+        assert_eq!(level_for_xp(total_xp_for_level(10) - 1), 9);
+    }
+
+    #[test]
+    fn level_progress_works() {
+        assert_eq!(level_progress(0), (0, 0, 100));
+        assert_eq!(level_progress(150), (1, 50, 155));
+    }
+}
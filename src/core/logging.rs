@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::future::Future;
 use std::io;
 use std::sync::Arc;
 
@@ -6,10 +7,12 @@ use flexi_logger::writers::LogWriter;
 use flexi_logger::{colored_opt_format, Age, Cleanup, Criterion, DeferredNow, Duplicate, Logger, LoggerHandle, Naming};
 use log::{Level, LevelFilter, Record};
 use once_cell::sync::OnceCell;
+use serde_json::{json, Value};
 use twilight_http::Client as HttpClient;
+use twilight_model::id::GuildId;
 use twilight_model::user::CurrentUser;
 
-use super::bot_config::{BotConfig, WebhookComponents};
+use super::bot_config::{BotConfig, LogFormat, WebhookComponents};
 use crate::error::StartupError;
 use crate::gearbot_error;
 use crate::utils::Emoji;
@@ -19,6 +22,24 @@ use std::time::Duration;
 static LOGGER_HANDLE: OnceCell<LoggerHandle> = OnceCell::new();
 type LogQueue = Arc<RwLock<VecDeque<String>>>;
 
+tokio::task_local! {
+    static LOG_CONTEXT: LogContext;
+}
+
+/// What the bot was busy with, added to every line logged in the meantime when logging as JSON.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogContext {
+    pub shard: Option<u64>,
+    pub guild: Option<GuildId>,
+    /// The full command, like ``archive channel``.
+    pub command: Option<String>,
+}
+
+/// Runs the future with the context attached to everything it logs, an outer context is replaced until it's done.
+pub async fn with_context<F: Future>(context: LogContext, future: F) -> F::Output {
+    LOG_CONTEXT.scope(context, future).await
+}
+
 struct WebhookLogger {
     queue: LogQueue,
 }
@@ -64,12 +85,16 @@ pub fn initialize(http: HttpClient, config: &BotConfig, user: CurrentUser) -> Re
         queue: info_queue.clone(),
     });
 
+    let logger = Logger::with_env_or_str("info")
+        .duplicate_to_stderr(Duplicate::Debug)
+        .log_to_file()
+        .directory("logs");
+    let logger = match config.logging.format {
+        LogFormat::Text => logger.format(colored_opt_format),
+        LogFormat::Json => logger.format_for_files(json_format).format_for_stderr(json_format),
+    };
     let log_init_status = LOGGER_HANDLE.set(
-        Logger::with_env_or_str("info")
-            .duplicate_to_stderr(Duplicate::Debug)
-            .log_to_file()
-            .directory("logs")
-            .format(colored_opt_format)
+        logger
             .o_timestamp(true)
             .rotate(
                 Criterion::Age(Age::Day),
@@ -160,6 +185,34 @@ async fn send_webhook(
     Ok(())
 }
 
+fn json_format(w: &mut dyn io::Write, now: &mut DeferredNow, record: &Record) -> Result<(), io::Error> {
+    // Lines logged outside of any task, like during startup, don't have a context
+    let context = LOG_CONTEXT.try_with(|context| context.clone()).unwrap_or_default();
+    write!(w, "{}", json_line(&now.now().to_rfc3339(), record, &context))
+}
+
+/// A log line as a JSON object, the context only adds what it knows about.
+fn json_line(timestamp: &str, record: &Record, context: &LogContext) -> Value {
+    let mut line = json!({
+        "timestamp": timestamp,
+        "level": record.level().as_str(),
+        "message": record.args().to_string(),
+        "module": record.module_path(),
+        "file": record.file(),
+        "line": record.line(),
+    });
+    if let Some(shard) = context.shard {
+        line["shard"] = json!(shard);
+    }
+    if let Some(guild) = context.guild {
+        line["guild"] = json!(guild.to_string());
+    }
+    if let Some(command) = &context.command {
+        line["command"] = json!(command);
+    }
+    line
+}
+
 fn get_emoji(level: Level) -> Emoji {
     match level {
         Level::Error => Emoji::No,
@@ -199,3 +252,33 @@ pub mod macros {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines_include_the_context() {
+        let context = LogContext {
+            shard: Some(3),
+            guild: Some(GuildId(42)),
+            command: None,
+        };
+        let line = json_line(
+            "2021-01-01T10:00:00+00:00",
+            &Record::builder()
+                .args(format_args!("Shard {} is down", 3))
+                .level(Level::Warn)
+                .module_path(Some("gearbot::core"))
+                .build(),
+            &context,
+        );
+
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["message"], "Shard 3 is down");
+        assert_eq!(line["module"], "gearbot::core");
+        assert_eq!(line["shard"], 3);
+        assert_eq!(line["guild"], "42");
+        assert!(line.get("command").is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::core::logpump::log_type::LogType;
+use chrono::{DateTime, Utc};
 use twilight_model::id::{ChannelId, GuildId, UserId};
 
 #[derive(Debug)]
@@ -7,4 +8,6 @@ pub struct LogData {
     pub guild: GuildId,
     pub source_channel: Option<ChannelId>,
     pub source_user: UserId,
+    /// When the event happened, logs can take a while to be send if there is a big backlog.
+    pub timestamp: DateTime<Utc>,
 }
@@ -2,7 +2,7 @@ use super::DataLessLogType;
 use serde::{Deserialize, Serialize};
 use twilight_model::id::{ChannelId, UserId};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LogFilter {
     log_types: Vec<DataLessLogType>,
     source_channels: Vec<ChannelId>,
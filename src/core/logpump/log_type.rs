@@ -1,30 +1,619 @@
 use crate::cache::CachedUser;
-use crate::core::guild_config::{LogCategory, LogStyle};
+use crate::core::anti_nuke::{AntiNukeResponse, NukeAction};
+use crate::core::guild_config::{LogAppearance, LogCategory, LogField};
+use crate::core::guild_emoji::EmojiChange;
+use crate::core::join_gate::{ChallengeLevel, JoinGateAction, JoinGateReason};
 use crate::core::BotContext;
 use crate::error::MessageError;
 use crate::translation::{FluArgs, GearBotString};
-use crate::utils::Emoji;
+use crate::utils::{self, Emoji};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use twilight_embed_builder::{EmbedAuthorBuilder, EmbedBuilder, EmbedFooterBuilder, ImageSource};
 use twilight_model::channel::embed::Embed;
-use twilight_model::id::ChannelId;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, RoleId, UserId};
 use unic_langid::LanguageIdentifier;
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
 pub enum LogType {
-    CommandUsed { command: String },
+    CommandUsed {
+        command: String,
+    },
+    ModCommandUsed {
+        command: String,
+        arguments: String,
+        target: Option<UserId>,
+        outcome: CommandOutcome,
+    },
+    MessageDeleted {
+        channel: ChannelId,
+        content: String,
+        /// Descriptions of the attachments, links to mirrored copies where possible.
+        attachments: Vec<String>,
+    },
+    MessagesBulkDeleted {
+        channel: ChannelId,
+        count: u64,
+        recovered: u64,
+        /// A transcript of the recovered messages, if there were any.
+        transcript: Option<String>,
+    },
+    AutomodTriggered {
+        rule: String,
+        channel: ChannelId,
+        content: String,
+        /// The actions that were taken, empty if the threshold wasn't reached yet.
+        actions: Vec<String>,
+    },
+    HeatEscalated {
+        channel: ChannelId,
+        heat: u32,
+        threshold: u32,
+        action: String,
+        /// If the action could actually be taken.
+        succeeded: bool,
+    },
+    JoinGateFlagged {
+        reasons: Vec<JoinGateReason>,
+        /// The action that was actually used, kicks turn into logs outside of raid mode.
+        action: JoinGateAction,
+        succeeded: bool,
+        /// The role of the action is held back until the member is through membership screening.
+        #[serde(default)]
+        after_screening: bool,
+    },
+    RaidModeChanged {
+        enabled: bool,
+        /// Done by the raid detector.
+        automatic: bool,
+    },
+    ScreeningCompleted {
+        /// The join gate role that was held back until now.
+        gate_role: Option<RoleId>,
+        succeeded: bool,
+    },
+    ChallengeIssued {
+        level: ChallengeLevel,
+        /// Both the verification role and the DM for the challenge got through.
+        succeeded: bool,
+    },
+    ChallengePassed {
+        level: ChallengeLevel,
+    },
+    WatchedMessage {
+        channel: ChannelId,
+        content: String,
+        /// Jump link to the message.
+        link: String,
+    },
+    WatchedJoin {
+        /// When their account was created, as a unix timestamp.
+        created: i64,
+    },
+    /// Moved to another voice channel, or left voice when there is none.
+    WatchedVoice {
+        channel: Option<ChannelId>,
+    },
+    WatchedNameChange {
+        before: String,
+        after: String,
+    },
+    /// A role got dangerous permissions, logged as done by whoever did it.
+    RolePermissionsGranted {
+        role: RoleId,
+        permissions: Permissions,
+        /// Who did it according to the audit log.
+        executor: Option<UserId>,
+        /// If it was reverted, when that was tried.
+        reverted: Option<bool>,
+    },
+    /// A member got dangerous permissions through new roles.
+    MemberPermissionsGranted {
+        roles: Vec<RoleId>,
+        permissions: Permissions,
+        executor: Option<UserId>,
+        reverted: Option<bool>,
+    },
+    /// Someone crossed an anti-nuke threshold and got acted on.
+    NukeDetected {
+        action: NukeAction,
+        /// How many they did within the window.
+        count: u32,
+        response: AntiNukeResponse,
+        succeeded: bool,
+    },
+    /// An emoji of the guild was added, renamed or removed, logged as done by whoever did it.
+    EmojiChanged {
+        change: EmojiChange,
+    },
+    /// A channel was created as a copy of another one.
+    ChannelCloned {
+        channel: ChannelId,
+        source: ChannelId,
+    },
+    /// A channel was created from a saved channel template.
+    ChannelTemplateApplied {
+        channel: ChannelId,
+        template: String,
+    },
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub enum DataLessLogType {
     CommandUsed,
+    ModCommandUsed,
+    MessageDeleted,
+    MessagesBulkDeleted,
+    AutomodTriggered,
+    HeatEscalated,
+    JoinGateFlagged,
+    RaidModeChanged,
+    ScreeningCompleted,
+    ChallengeIssued,
+    ChallengePassed,
+    WatchedMessage,
+    WatchedJoin,
+    WatchedVoice,
+    WatchedNameChange,
+    RolePermissionsGranted,
+    MemberPermissionsGranted,
+    NukeDetected,
+    EmojiChanged,
+    ChannelCloned,
+    ChannelTemplateApplied,
+}
+
+/// A file that gets uploaded along with a log.
+pub struct LogFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+pub enum CommandOutcome {
+    Succeeded,
+    Failed(String),
+    MissingPermissions,
+}
+
+impl CommandOutcome {
+    fn translate(&self, ctx: &Arc<BotContext>, lang: &LanguageIdentifier) -> String {
+        match self {
+            CommandOutcome::Succeeded => ctx.translate(lang, GearBotString::ModCommandSucceeded),
+            CommandOutcome::Failed(error) => ctx.translate_with_args(
+                lang,
+                GearBotString::ModCommandFailed,
+                &FluArgs::with_capacity(1).add("error", error.clone()).generate(),
+            ),
+            CommandOutcome::MissingPermissions => ctx.translate(lang, GearBotString::ModCommandDenied),
+        }
+    }
 }
 
 impl LogType {
     pub fn get_category(&self) -> LogCategory {
         match self {
-            LogType::CommandUsed { .. } => LogCategory::GENERAL,
+            LogType::CommandUsed { .. } | LogType::JoinGateFlagged { .. } | LogType::RaidModeChanged { .. } => {
+                LogCategory::GENERAL
+            }
+            LogType::ModCommandUsed { .. } | LogType::AutomodTriggered { .. } | LogType::HeatEscalated { .. } => {
+                LogCategory::COMMANDS
+            }
+            LogType::MessageDeleted { .. } | LogType::MessagesBulkDeleted { .. } => LogCategory::MESSAGES,
+            LogType::RolePermissionsGranted { .. } | LogType::NukeDetected { .. } | LogType::EmojiChanged { .. } => {
+                LogCategory::GENERAL
+            }
+            LogType::ChannelCloned { .. } | LogType::ChannelTemplateApplied { .. } => LogCategory::GENERAL,
+            LogType::ScreeningCompleted { .. }
+            | LogType::ChallengeIssued { .. }
+            | LogType::ChallengePassed { .. }
+            | LogType::MemberPermissionsGranted { .. } => LogCategory::MEMBERS,
+            LogType::WatchedMessage { .. }
+            | LogType::WatchedJoin { .. }
+            | LogType::WatchedVoice { .. }
+            | LogType::WatchedNameChange { .. } => LogCategory::WATCHLIST,
+        }
+    }
+
+    pub fn file(&self) -> Option<LogFile> {
+        match self {
+            LogType::MessagesBulkDeleted {
+                channel,
+                transcript: Some(transcript),
+                ..
+            } => Some(LogFile {
+                name: format!("deleted_messages_{}.txt", channel),
+                data: transcript.clone().into_bytes(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Collects everything needed to render this log, regardless of the style it will be send in.
+    fn template(&self, ctx: &Arc<BotContext>, lang: &LanguageIdentifier, channel: &Option<ChannelId>) -> LogTemplate {
+        match self {
+            LogType::CommandUsed { command } => {
+                let mut command = command.replace("`", "ˋ");
+                super::truncate(&mut command, 1800);
+                LogTemplate {
+                    key: GearBotString::CommandUsed,
+                    footer: GearBotString::CommandUsedFooter,
+                    // we always have a channel for command executions
+                    args: FluArgs::with_capacity(5)
+                        .add("channel_id", channel.unwrap().to_string())
+                        .add("command", command),
+                }
+            }
+            LogType::ModCommandUsed {
+                command,
+                arguments,
+                target,
+                outcome,
+            } => {
+                let mut arguments = arguments.replace("`", "ˋ");
+                super::truncate(&mut arguments, 1000);
+                let arguments = if arguments.is_empty() {
+                    ctx.translate(lang, GearBotString::ModCommandNoArguments)
+                } else {
+                    format!("``{}``", arguments)
+                };
+
+                LogTemplate {
+                    key: GearBotString::ModCommandUsed,
+                    footer: GearBotString::ModCommandUsedFooter,
+                    args: FluArgs::with_capacity(8)
+                        .add("command", command.clone())
+                        .add("channel_id", channel.unwrap().to_string())
+                        .add("target", target_mention(ctx, lang, target))
+                        .add("arguments", arguments)
+                        .add("outcome", outcome.translate(ctx, lang)),
+                }
+            }
+            LogType::MessageDeleted {
+                channel,
+                content,
+                attachments,
+            } => {
+                let mut content = content.clone();
+                super::truncate(&mut content, 1500);
+                let attachments = if attachments.is_empty() {
+                    String::new()
+                } else {
+                    ctx.translate_with_args(
+                        lang,
+                        GearBotString::MessageDeletedAttachments,
+                        &FluArgs::with_capacity(2)
+                            .add("count", attachments.len())
+                            .add("links", attachments.join("\n"))
+                            .generate(),
+                    )
+                };
+
+                LogTemplate {
+                    key: GearBotString::MessageDeleted,
+                    footer: GearBotString::MessageDeletedFooter,
+                    args: FluArgs::with_capacity(6)
+                        .add("channel_id", channel.to_string())
+                        .add("content", content)
+                        .add("attachments", attachments),
+                }
+            }
+            LogType::MessagesBulkDeleted {
+                channel,
+                count,
+                recovered,
+                ..
+            } => LogTemplate {
+                key: GearBotString::MessagesBulkDeleted,
+                footer: GearBotString::MessagesBulkDeletedFooter,
+                args: FluArgs::with_capacity(6)
+                    .add("channel_id", channel.to_string())
+                    .add("count", *count)
+                    .add("recovered", *recovered),
+            },
+            LogType::AutomodTriggered {
+                rule,
+                channel,
+                content,
+                actions,
+            } => {
+                let mut content = content.replace("`", "ˋ");
+                super::truncate(&mut content, 1500);
+                let actions = if actions.is_empty() {
+                    ctx.translate(lang, GearBotString::AutomodNoActions)
+                } else {
+                    actions.join(", ")
+                };
+
+                LogTemplate {
+                    key: GearBotString::AutomodTriggered,
+                    footer: GearBotString::AutomodTriggeredFooter,
+                    args: FluArgs::with_capacity(7)
+                        .add("rule", rule.clone())
+                        .add("channel_id", channel.to_string())
+                        .add("content", content)
+                        .add("actions", actions),
+                }
+            }
+            LogType::HeatEscalated {
+                channel,
+                heat,
+                threshold,
+                action,
+                succeeded,
+            } => {
+                let action = if *succeeded {
+                    action.clone()
+                } else {
+                    ctx.translate_with_args(
+                        lang,
+                        GearBotString::LogActionFailed,
+                        &FluArgs::with_capacity(1).add("action", action.clone()).generate(),
+                    )
+                };
+
+                LogTemplate {
+                    key: GearBotString::HeatEscalated,
+                    footer: GearBotString::HeatEscalatedFooter,
+                    args: FluArgs::with_capacity(7)
+                        .add("channel_id", channel.to_string())
+                        .add("heat", *heat)
+                        .add("threshold", *threshold)
+                        .add("action", action),
+                }
+            }
+            LogType::JoinGateFlagged {
+                reasons,
+                action,
+                succeeded,
+                after_screening,
+            } => {
+                let reasons = reasons
+                    .iter()
+                    .map(|reason| match reason {
+                        JoinGateReason::NewAccount { age_days } => ctx.translate_with_args(
+                            lang,
+                            GearBotString::JoinGateNewAccount,
+                            &FluArgs::with_capacity(1).add("days", *age_days).generate(),
+                        ),
+                        JoinGateReason::NoAvatar => ctx.translate(lang, GearBotString::JoinGateNoAvatar),
+                        JoinGateReason::NetworkBans { guilds } => ctx.translate_with_args(
+                            lang,
+                            GearBotString::JoinGateNetworkBans,
+                            &FluArgs::with_capacity(1).add("guilds", *guilds).generate(),
+                        ),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let key = match action {
+                    JoinGateAction::Log => GearBotString::JoinGateActionLog,
+                    JoinGateAction::Probation => GearBotString::JoinGateActionProbation,
+                    JoinGateAction::Verification => GearBotString::JoinGateActionVerification,
+                    JoinGateAction::RaidKick => GearBotString::JoinGateActionKick,
+                };
+                let mut action = ctx.translate(lang, key);
+                if *after_screening {
+                    action = ctx.translate_with_args(
+                        lang,
+                        GearBotString::JoinGateAfterScreening,
+                        &FluArgs::with_capacity(1).add("action", action).generate(),
+                    );
+                }
+                if !succeeded {
+                    action = ctx.translate_with_args(
+                        lang,
+                        GearBotString::LogActionFailed,
+                        &FluArgs::with_capacity(1).add("action", action).generate(),
+                    );
+                }
+
+                LogTemplate {
+                    key: GearBotString::JoinGateFlagged,
+                    footer: GearBotString::JoinGateFlaggedFooter,
+                    args: FluArgs::with_capacity(5).add("reasons", reasons).add("action", action),
+                }
+            }
+            LogType::RaidModeChanged { enabled, automatic } => LogTemplate {
+                key: match (enabled, automatic) {
+                    (true, true) => GearBotString::RaidModeDetected,
+                    (true, false) => GearBotString::RaidModeEnabledLog,
+                    (false, _) => GearBotString::RaidModeDisabledLog,
+                },
+                footer: GearBotString::RaidModeFooter,
+                args: FluArgs::with_capacity(3),
+            },
+            LogType::ScreeningCompleted { gate_role, succeeded } => match gate_role {
+                Some(role_id) => {
+                    let mut role = format!("<@&{}>", role_id);
+                    if !succeeded {
+                        role = ctx.translate_with_args(
+                            lang,
+                            GearBotString::LogActionFailed,
+                            &FluArgs::with_capacity(1).add("action", role).generate(),
+                        );
+                    }
+
+                    LogTemplate {
+                        key: GearBotString::ScreeningCompletedRole,
+                        footer: GearBotString::ScreeningFooter,
+                        args: FluArgs::with_capacity(4).add("role", role),
+                    }
+                }
+                None => LogTemplate {
+                    key: GearBotString::ScreeningCompleted,
+                    footer: GearBotString::ScreeningFooter,
+                    args: FluArgs::with_capacity(3),
+                },
+            },
+            LogType::ChallengeIssued { level, succeeded } => {
+                let mut challenge = challenge_name(ctx, lang, *level);
+                if !succeeded {
+                    challenge = ctx.translate_with_args(
+                        lang,
+                        GearBotString::LogActionFailed,
+                        &FluArgs::with_capacity(1).add("action", challenge).generate(),
+                    );
+                }
+
+                LogTemplate {
+                    key: GearBotString::ChallengeIssued,
+                    footer: GearBotString::ChallengeFooter,
+                    args: FluArgs::with_capacity(4).add("challenge", challenge),
+                }
+            }
+            LogType::ChallengePassed { level } => LogTemplate {
+                key: GearBotString::ChallengePassed,
+                footer: GearBotString::ChallengeFooter,
+                args: FluArgs::with_capacity(4).add("challenge", challenge_name(ctx, lang, *level)),
+            },
+            LogType::WatchedMessage { channel, content, link } => {
+                let mut content = content.replace("`", "ˋ");
+                super::truncate(&mut content, 1500);
+
+                LogTemplate {
+                    key: GearBotString::WatchedMessage,
+                    footer: GearBotString::WatchlistFooter,
+                    args: FluArgs::with_capacity(6)
+                        .add("channel_id", channel.to_string())
+                        .add("content", content)
+                        .add("link", link.clone()),
+                }
+            }
+            LogType::WatchedJoin { created } => LogTemplate {
+                key: GearBotString::WatchedJoin,
+                footer: GearBotString::WatchlistFooter,
+                args: FluArgs::with_capacity(4).add("created", created.to_string()),
+            },
+            LogType::WatchedVoice { channel } => match channel {
+                Some(channel) => LogTemplate {
+                    key: GearBotString::WatchedVoiceJoined,
+                    footer: GearBotString::WatchlistFooter,
+                    args: FluArgs::with_capacity(4).add("channel_id", channel.to_string()),
+                },
+                None => LogTemplate {
+                    key: GearBotString::WatchedVoiceLeft,
+                    footer: GearBotString::WatchlistFooter,
+                    args: FluArgs::with_capacity(3),
+                },
+            },
+            LogType::RolePermissionsGranted {
+                role,
+                permissions,
+                executor,
+                reverted,
+            } => LogTemplate {
+                key: GearBotString::RolePermissionsGranted,
+                footer: GearBotString::PermissionsGrantedFooter,
+                args: FluArgs::with_capacity(7)
+                    .add("role_id", role.to_string())
+                    .add("permissions", utils::permission_names(*permissions).join(", "))
+                    .add("executor", grant_executor(ctx, lang, executor))
+                    .add("outcome", grant_outcome(reverted)),
+            },
+            LogType::MemberPermissionsGranted {
+                roles,
+                permissions,
+                executor,
+                reverted,
+            } => LogTemplate {
+                key: GearBotString::MemberPermissionsGranted,
+                footer: GearBotString::PermissionsGrantedFooter,
+                args: FluArgs::with_capacity(7)
+                    .add(
+                        "roles",
+                        roles
+                            .iter()
+                            .map(|role| format!("<@&{}>", role))
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    )
+                    .add("permissions", utils::permission_names(*permissions).join(", "))
+                    .add("executor", grant_executor(ctx, lang, executor))
+                    .add("outcome", grant_outcome(reverted)),
+            },
+            LogType::NukeDetected {
+                action,
+                count,
+                response,
+                succeeded,
+            } => {
+                let response = ctx.translate(
+                    lang,
+                    match response {
+                        AntiNukeResponse::StripRoles => GearBotString::NukeRolesStripped,
+                        AntiNukeResponse::Ban => GearBotString::NukeBanned,
+                    },
+                );
+                let response = if *succeeded {
+                    response
+                } else {
+                    ctx.translate_with_args(
+                        lang,
+                        GearBotString::LogActionFailed,
+                        &FluArgs::with_capacity(1).add("action", response).generate(),
+                    )
+                };
+
+                LogTemplate {
+                    key: GearBotString::NukeDetected,
+                    footer: GearBotString::NukeDetectedFooter,
+                    args: FluArgs::with_capacity(6)
+                        .add("action", action.to_string())
+                        .add("count", *count)
+                        .add("response", response),
+                }
+            }
+            LogType::WatchedNameChange { before, after } => LogTemplate {
+                key: GearBotString::WatchedNameChange,
+                footer: GearBotString::WatchlistFooter,
+                args: FluArgs::with_capacity(5)
+                    .add("before", before.replace("`", "ˋ"))
+                    .add("after", after.replace("`", "ˋ")),
+            },
+            LogType::EmojiChanged { change } => match change {
+                EmojiChange::Created { id, name, animated } => LogTemplate {
+                    key: GearBotString::EmojiCreatedLog,
+                    footer: GearBotString::EmojiLogFooter,
+                    args: FluArgs::with_capacity(6)
+                        .add(
+                            "emoji",
+                            format!("<{}:{}:{}>", if *animated { "a" } else { "" }, name, id),
+                        )
+                        .add("emoji_name", name.clone())
+                        .add("emoji_id", id.to_string()),
+                },
+                EmojiChange::Deleted { id, name } => LogTemplate {
+                    key: GearBotString::EmojiDeletedLog,
+                    footer: GearBotString::EmojiLogFooter,
+                    args: FluArgs::with_capacity(5)
+                        .add("emoji_name", name.clone())
+                        .add("emoji_id", id.to_string()),
+                },
+                EmojiChange::Renamed { id, before, after } => LogTemplate {
+                    key: GearBotString::EmojiRenamedLog,
+                    footer: GearBotString::EmojiLogFooter,
+                    args: FluArgs::with_capacity(6)
+                        .add("before", before.clone())
+                        .add("after", after.clone())
+                        .add("emoji_id", id.to_string()),
+                },
+            },
+            LogType::ChannelCloned { channel, source } => LogTemplate {
+                key: GearBotString::ChannelClonedLog,
+                footer: GearBotString::ChannelLogFooter,
+                args: FluArgs::with_capacity(5)
+                    .add("channel", channel.to_string())
+                    .add("source", source.to_string()),
+            },
+            LogType::ChannelTemplateApplied { channel, template } => LogTemplate {
+                key: GearBotString::ChannelTemplateAppliedLog,
+                footer: GearBotString::ChannelLogFooter,
+                args: FluArgs::with_capacity(5)
+                    .add("channel", channel.to_string())
+                    .add("template", template.clone()),
+            },
         }
     }
 
@@ -34,36 +623,42 @@ impl LogType {
         lang: &LanguageIdentifier,
         user: &Arc<CachedUser>,
         channel: &Option<ChannelId>,
+        timestamp: &DateTime<Utc>,
+        appearance: Option<&LogAppearance>,
     ) -> Result<Embed, MessageError> {
-        Ok(match self {
-            LogType::CommandUsed { command } => {
-                let mut command = command.to_string();
-                command.truncate(1800);
-                EmbedBuilder::new()
-                    .description(
-                        ctx.translate_with_args(
-                            lang,
-                            GearBotString::CommandUsedEmbed,
-                            &FluArgs::with_capacity(2)
-                                .add("channel_id", channel.unwrap().to_string())
-                                .add("command", command)
-                                .generate(),
-                        ),
-                    )?
-                    .author(
-                        EmbedAuthorBuilder::new()
-                            .name(user.full_name_with_id())?
-                            .url(user.profile_link()),
-                    )
-                    .thumbnail(ImageSource::url(user.avatar_url())?)
-                    .footer(
-                        EmbedFooterBuilder::new(ctx.translate(lang, GearBotString::CommandUsedFooter))?
-                            .icon_url(ImageSource::url(self.emoji().url())?),
-                    )
-            }
+        let template = self.template(ctx, lang, channel);
+        let args = add_user_args(template.args, user).add("style", "embed");
+        let shows = |field| appearance.map_or(true, |appearance| appearance.shows(field));
+
+        let mut builder =
+            EmbedBuilder::new().description(ctx.translate_with_args(lang, template.key, &args.generate()))?;
+        if shows(LogField::Author) {
+            builder = builder.author(
+                EmbedAuthorBuilder::new()
+                    .name(user.full_name_with_id())?
+                    .url(user.profile_link()),
+            );
         }
-        .timestamp(chrono::Utc::now().format("%+").to_string())
-        .build()?)
+        if shows(LogField::Thumbnail) {
+            builder = builder.thumbnail(ImageSource::url(user.avatar_url())?);
+        }
+        if shows(LogField::Footer) {
+            let icon = appearance
+                .and_then(LogAppearance::icon_url)
+                .unwrap_or_else(|| self.emoji().url());
+            builder = builder.footer(
+                EmbedFooterBuilder::new(ctx.translate(lang, template.footer))?.icon_url(ImageSource::url(icon)?),
+            );
+        }
+        if shows(LogField::Timestamp) {
+            // Discord shows this in the timezone and locale of whoever is reading it.
+            builder = builder.timestamp(timestamp.format("%+").to_string());
+        }
+        if let Some(color) = appearance.and_then(|appearance| appearance.color) {
+            builder = builder.color(color)?;
+        }
+
+        Ok(builder.build()?)
     }
 
     pub fn to_text(
@@ -72,42 +667,150 @@ impl LogType {
         lang: &LanguageIdentifier,
         user: &Arc<CachedUser>,
         channel: &Option<ChannelId>,
+        timestamp: Option<&DateTime<Utc>>,
+        appearance: Option<&LogAppearance>,
     ) -> String {
-        match self {
-            LogType::CommandUsed { command } => {
-                let mut command = command.clone();
-                command.truncate(1800);
-                let args = add_user_args(FluArgs::with_capacity(4), user)
-                    .add("command", command.replace("`", "ˋ"))
-                    .add("channel_id", channel.unwrap().to_string()); // we always have a channel for command executions
+        let template = self.template(ctx, lang, channel);
+        let args = add_user_args(template.args, user).add("style", "text");
+        let text = ctx.translate_with_args(lang, template.key, &args.generate());
+        let emoji = appearance
+            .and_then(|appearance| appearance.emoji.as_deref())
+            .unwrap_or_else(|| self.emoji().for_chat());
 
-                ctx.translate_with_args(lang, GearBotString::CommandUsedText, &args.generate())
-            }
+        match timestamp {
+            // Timestamp markdown gets rendered in the timezone and locale of whoever is reading it.
+            Some(timestamp) => format!(
+                "{} {} {}",
+                ctx.translate_with_args(
+                    lang,
+                    GearBotString::LogTimestamp,
+                    &FluArgs::with_capacity(1)
+                        .add("timestamp", timestamp.timestamp().to_string())
+                        .generate()
+                ),
+                emoji,
+                text
+            ),
+            None => format!("{} {}", emoji, text),
         }
     }
 
     pub fn emoji(&self) -> Emoji {
         match self {
             LogType::CommandUsed { .. } => Emoji::Online,
+            LogType::ModCommandUsed { outcome, .. } => match outcome {
+                CommandOutcome::Succeeded => Emoji::Yes,
+                _ => Emoji::No,
+            },
+            LogType::MessageDeleted { .. } | LogType::MessagesBulkDeleted { .. } => Emoji::Trash,
+            LogType::AutomodTriggered { .. } | LogType::HeatEscalated { .. } => Emoji::Bad,
+            LogType::JoinGateFlagged { .. } | LogType::RaidModeChanged { .. } => Emoji::Warn,
+            LogType::ScreeningCompleted { succeeded, .. } => {
+                if *succeeded {
+                    Emoji::Yes
+                } else {
+                    Emoji::Warn
+                }
+            }
+            LogType::ChallengeIssued { succeeded, .. } => {
+                if *succeeded {
+                    Emoji::Info
+                } else {
+                    Emoji::Warn
+                }
+            }
+            LogType::ChallengePassed { .. } => Emoji::Yes,
+            LogType::WatchedMessage { .. }
+            | LogType::WatchedJoin { .. }
+            | LogType::WatchedVoice { .. }
+            | LogType::WatchedNameChange { .. } => Emoji::Info,
+            LogType::RolePermissionsGranted { .. } | LogType::MemberPermissionsGranted { .. } => Emoji::Warn,
+            LogType::NukeDetected { succeeded, .. } => {
+                if *succeeded {
+                    Emoji::Warn
+                } else {
+                    Emoji::Bad
+                }
+            }
+            LogType::EmojiChanged { change } => match change {
+                EmojiChange::Deleted { .. } => Emoji::Trash,
+                _ => Emoji::Info,
+            },
+            LogType::ChannelCloned { .. } | LogType::ChannelTemplateApplied { .. } => Emoji::Info,
         }
     }
 
     pub fn dataless(&self) -> DataLessLogType {
         match self {
             Self::CommandUsed { .. } => DataLessLogType::CommandUsed,
+            Self::ModCommandUsed { .. } => DataLessLogType::ModCommandUsed,
+            Self::MessageDeleted { .. } => DataLessLogType::MessageDeleted,
+            Self::MessagesBulkDeleted { .. } => DataLessLogType::MessagesBulkDeleted,
+            Self::AutomodTriggered { .. } => DataLessLogType::AutomodTriggered,
+            Self::HeatEscalated { .. } => DataLessLogType::HeatEscalated,
+            Self::JoinGateFlagged { .. } => DataLessLogType::JoinGateFlagged,
+            Self::RaidModeChanged { .. } => DataLessLogType::RaidModeChanged,
+            Self::ScreeningCompleted { .. } => DataLessLogType::ScreeningCompleted,
+            Self::ChallengeIssued { .. } => DataLessLogType::ChallengeIssued,
+            Self::ChallengePassed { .. } => DataLessLogType::ChallengePassed,
+            Self::WatchedMessage { .. } => DataLessLogType::WatchedMessage,
+            Self::WatchedJoin { .. } => DataLessLogType::WatchedJoin,
+            Self::WatchedVoice { .. } => DataLessLogType::WatchedVoice,
+            Self::WatchedNameChange { .. } => DataLessLogType::WatchedNameChange,
+            Self::RolePermissionsGranted { .. } => DataLessLogType::RolePermissionsGranted,
+            Self::MemberPermissionsGranted { .. } => DataLessLogType::MemberPermissionsGranted,
+            Self::NukeDetected { .. } => DataLessLogType::NukeDetected,
+            Self::EmojiChanged { .. } => DataLessLogType::EmojiChanged,
+            Self::ChannelCloned { .. } => DataLessLogType::ChannelCloned,
+            Self::ChannelTemplateApplied { .. } => DataLessLogType::ChannelTemplateApplied,
         }
     }
 }
 
+fn target_mention(ctx: &Arc<BotContext>, lang: &LanguageIdentifier, target: &Option<UserId>) -> String {
+    match target {
+        Some(target) => format!("<@{}> (``{}``)", target, target),
+        None => ctx.translate(lang, GearBotString::ModCommandNoTarget),
+    }
+}
+
+fn grant_executor(ctx: &BotContext, lang: &LanguageIdentifier, executor: &Option<UserId>) -> String {
+    match executor {
+        Some(executor) => format!("<@{}>", executor),
+        None => ctx.translate(lang, GearBotString::PermissionsGrantedUnknown),
+    }
+}
+
+fn grant_outcome(reverted: &Option<bool>) -> &'static str {
+    match reverted {
+        Some(true) => "reverted",
+        Some(false) => "failed",
+        None => "kept",
+    }
+}
+
+fn challenge_name(ctx: &BotContext, lang: &LanguageIdentifier, level: ChallengeLevel) -> String {
+    let key = match level {
+        ChallengeLevel::Reaction => GearBotString::ChallengeReaction,
+        ChallengeLevel::Captcha => GearBotString::ChallengeCaptcha,
+        // Nothing gets issued or passed without a challenge
+        ChallengeLevel::None | ChallengeLevel::Manual => GearBotString::ChallengeManual,
+    };
+    ctx.translate(lang, key)
+}
+
 fn add_user_args<'a>(args: FluArgs<'a>, user: &Arc<CachedUser>) -> FluArgs<'a> {
     args.add("name", user.full_name()).add("user_id", user.id.to_string())
 }
 
-impl LogStyle {
-    pub fn get_fallback(&self) -> Option<Self> {
-        match self {
-            LogStyle::Text => None,
-            LogStyle::Embed => Some(LogStyle::Text),
-        }
-    }
+/// The style independent parts of a log.
+///
+/// Both styles are rendered from the same translation string, which gets a `style` argument of either
+/// `text` or `embed` to select the right variant. Numeric arguments should be passed as numbers rather
+/// than strings so translations can use plural selectors on them.
+struct LogTemplate {
+    key: GearBotString,
+    /// Only used by embeds.
+    footer: GearBotString,
+    args: FluArgs<'static>,
 }
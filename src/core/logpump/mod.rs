@@ -7,14 +7,22 @@ const GEARBOT_EMBED_SENDER: &str = "GearBot moderation logs";
 const DISCORD_SIZE_LIMIT: usize = 2000;
 const BATCH_SIZE: usize = 20;
 const RECV_TIMEOUT: Duration = Duration::from_secs(4);
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+const MAX_SEND_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 pub use log_data::LogData;
 pub use log_filter::LogFilter;
+pub use log_type::CommandOutcome;
 pub use log_type::DataLessLogType;
+pub use log_type::LogFile;
 pub use log_type::LogType;
 
-use crate::core::bot_context::BotContext;
-use crate::core::guild_config::LogStyle;
+use crate::core::bot_context::{BotContext, TaskGuard};
+use crate::core::guild_config::{
+    GuildFeatures, LogAppearance, LogCategory, LogChannelConfig, LogStyle, WebhookIdentity,
+};
 use crate::error::OtherFailure;
 use crate::gearbot_error;
 
@@ -24,7 +32,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc::UnboundedReceiver, Mutex};
 use tokio::time::timeout;
+use twilight_http::request::channel::webhook::ExecuteWebhook;
 use twilight_http::Error;
+use twilight_model::channel::embed::Embed;
 use twilight_model::guild::Permissions;
 use twilight_model::id::{ChannelId, GuildId, WebhookId};
 use unic_langid::LanguageIdentifier;
@@ -43,67 +53,91 @@ pub async fn run(ctx: Arc<BotContext>, mut top_receiver: UnboundedReceiver<LogDa
     log::info!("Logpump started!");
     let mut channel_sync_locks: HashMap<ChannelId, ChannelLock> = HashMap::new();
     loop {
-        let mut to_send: Vec<Arc<LogData>> = Vec::with_capacity(BATCH_SIZE);
+        let mut received: Vec<Arc<LogData>> = Vec::with_capacity(BATCH_SIZE);
 
         // Sit and wait until we have something to do.
         let first_log = top_receiver.recv().await.unwrap();
 
-        to_send.push(Arc::new(first_log));
+        received.push(Arc::new(first_log));
 
         // If its a slow period, this will return early and give us whats around.
-        receive_up_to(BATCH_SIZE, &mut top_receiver, &mut to_send).await;
+        receive_up_to(BATCH_SIZE, &mut top_receiver, &mut received).await;
 
-        let log = to_send
-            .first()
-            .expect("bug: original log that started pup wasn't added to send list");
+        log::debug!("{} logs received", received.len());
+        ctx.stats.logpump_stats.pending_logs.sub(received.len() as i64);
 
-        log::debug!("log data received: {:?}", log);
-        let guild_id = log.guild;
-        match ctx.get_config(guild_id).await {
-            Ok(config) => {
-                for (channel_id, log_config) in &config.log_channels {
-                    // Cheap clone since its just a bunch of `Arc`s inside.
-                    let to_send = to_send.clone();
-                    //check if it could go to this channel
-                    if log_config.categories.contains(&log.log_type.get_category())
-                        && !log_config.disabled_keys.contains(&log.log_type.dataless())
-                    {
-                        let mut matches = false;
-                        for filter in &log_config.filters {
-                            if filter.matches(&log.log_type.dataless(), &log.source_channel, &log.source_user) {
-                                matches = true;
-                                break;
-                            }
-                        }
-                        if matches {
-                            continue;
-                        }
+        // A batch can contain logs for many guilds, buffer them per guild while keeping them in order.
+        let mut per_guild: HashMap<GuildId, Vec<Arc<LogData>>> = HashMap::new();
+        for log in received {
+            per_guild.entry(log.guild).or_default().push(log);
+        }
 
-                        let channel_lock = Arc::clone(
-                            &channel_sync_locks
-                                .entry(*channel_id)
-                                .or_insert(Arc::new(Mutex::new(true))),
-                        );
+        for (guild_id, logs) in per_guild {
+            route(&ctx, &mut channel_sync_locks, guild_id, logs).await;
+        }
+    }
+}
 
-                        if let Ok(lock) = channel_lock.try_lock() {
-                            if *lock == false {
-                                // A pump marked this channel as useless, so deallocate the lock since
-                                // theres a chance we will never use it again.
-                                channel_sync_locks.remove(channel_id);
-                                continue;
-                            }
-                        }
+/// Hands the logs of a guild out to pumps for every log channel that wants them.
+async fn route(
+    ctx: &Arc<BotContext>,
+    channel_sync_locks: &mut HashMap<ChannelId, ChannelLock>,
+    guild_id: GuildId,
+    logs: Vec<Arc<LogData>>,
+) {
+    // Most of what we'd log about is missing from the cache until the outage is over
+    if ctx.cache.in_outage(guild_id).await {
+        return;
+    }
 
-                        tokio::spawn(pump(ctx.clone(), to_send, guild_id, *channel_id, channel_lock));
-                    }
-                }
+    let config = match ctx.get_config(guild_id).await {
+        Ok(config) => config,
+        Err(e) => {
+            gearbot_error!("Logpump error: failed to retrieve config for guild {}: {}", guild_id, e);
+            return;
+        }
+    };
+
+    if !config.features.contains(GuildFeatures::LOGGING) {
+        return;
+    }
+
+    for (channel_id, log_config) in &config.log_channels {
+        // Cheap clones since its just a bunch of `Arc`s.
+        let to_send =
+            logs.iter()
+                .filter(|log| {
+                    //check if it could go to this channel
+                    log_config.categories.contains(&log.log_type.get_category())
+                        && !log_config.disabled_keys.contains(&log.log_type.dataless())
+                        && !log_config.filters.iter().any(|filter| {
+                            filter.matches(&log.log_type.dataless(), &log.source_channel, &log.source_user)
+                        })
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+        if to_send.is_empty() {
+            continue;
+        }
+
+        let channel_lock = Arc::clone(
+            &channel_sync_locks
+                .entry(*channel_id)
+                .or_insert(Arc::new(Mutex::new(true))),
+        );
+
+        if let Ok(lock) = channel_lock.try_lock() {
+            if *lock == false {
+                // A pump marked this channel as useless, so deallocate the lock since
+                // theres a chance we will never use it again.
+                channel_sync_locks.remove(channel_id);
+                continue;
             }
-            Err(e) => gearbot_error!(
-                "Logpump error: failed to retrieve config for guild {}: {}",
-                log.guild,
-                e
-            ),
         }
+
+        ctx.stats.logs_queued(guild_id, to_send.len());
+        tokio::spawn(pump(ctx.clone(), to_send, guild_id, *channel_id, channel_lock));
     }
 }
 
@@ -114,6 +148,9 @@ async fn pump(
     channel_id: ChannelId,
     channel_lock: ChannelLock,
 ) {
+    let _task = TaskGuard::new(&ctx.stats.runtime_stats.pump_tasks);
+    let queued = to_send.len();
+
     // Ensure that only one task at a time can send logs for a channel.
     //
     // Humans are bad at piecing together out of order events, so this ensures that a specific channel gets all of its messages
@@ -121,7 +158,6 @@ async fn pump(
     // a large number of incoming logs.
     let mut time_sync_barrier = channel_lock.lock().await;
 
-    let log_count = to_send.len();
     ctx.stats.logpump_stats.active_pumps.inc();
     let mut webhook_info = None;
     'outer: loop {
@@ -146,42 +182,36 @@ async fn pump(
                 // config found, validate we have the correct permissions
                 // we are not checking for embed permissions since this will be handled by a webhook later
 
-                let mut style = Some(channel_config.style);
+                let mut style = channel_config.style;
+                let use_webhook = channel_config.uses_webhook();
 
                 // Worst case, if someones permissions are really messed up, they lose this log batch.
-                if let Some(s) = &style {
-                    match try_configure_to_send(&ctx, &channel_id, s, &mut webhook_info).await {
-                        Ok(CanSend::MissingWebHook) => style = s.get_fallback(),
-                        Ok(CanSend::MissingPermissions) => {
-                            log::warn!("Missing permissions to log in channel {}, quitting pump", channel_id);
-                            // If we can't log here, every attempt will fail anyway.
-                            break;
-                        }
-                        Ok(CanSend::Yes) => {}
-                        Err(e) => {
-                            gearbot_error!("Failed to determine if we can log or not, did the database die? Falling back just in case. {}", e);
-                            style = s.get_fallback();
-                        }
+                match try_configure_to_send(&ctx, &channel_id, use_webhook, &mut webhook_info).await {
+                    // Without a webhook everything goes out as plain bot messages
+                    Ok(CanSend::MissingWebHook) => style = LogStyle::Text,
+                    Ok(CanSend::MissingPermissions) => {
+                        log::warn!("Missing permissions to log in channel {}, quitting pump", channel_id);
+                        // If we can't log here, every attempt will fail anyway.
+                        break;
+                    }
+                    Ok(CanSend::Yes) => {}
+                    Err(e) => {
+                        gearbot_error!("Failed to determine if we can log or not, did the database die? Falling back just in case. {}", e);
+                        style = LogStyle::Text;
                     }
                 }
 
-                let style = match &style {
-                    Some(s) => s,
-                    // we can't log anything, hit the self-destruct
-                    None => break,
-                };
-
                 let send_style = match &webhook_info {
-                    Some(info) => SendStyle::Webhook(info),
+                    Some(info) if use_webhook => SendStyle::Webhook(info),
                     // We can't log anything here either.
                     //
                     // If the webhook gets trashed in the send loop and `try_configure_to_send` fails to get a new one,
                     // then we really cant do anything.
-                    None if *style == LogStyle::Embed => {
+                    _ if style == LogStyle::Embed => {
                         gearbot_error!("Webhook information wasn't present with embed log styling");
                         break;
                     }
-                    None => SendStyle::Channel, // We aren't using a webhook
+                    _ => SendStyle::Channel, // We aren't using a webhook
                 };
 
                 while !to_send.is_empty() {
@@ -189,9 +219,11 @@ async fn pump(
                         &ctx,
                         &mut to_send,
                         send_style,
+                        style,
+                        channel_config,
+                        &config.log_appearance,
                         &config.language,
                         channel_id,
-                        channel_config.timestamps,
                     )
                     .await
                     {
@@ -209,7 +241,7 @@ async fn pump(
                             // Break from the sending loop so we can try and get a new, valid, webhook.
                             continue 'outer;
                         }
-                        Err(e) => gearbot_error!("Logpump failure: {}", e),
+                        Err(e) => gearbot_error!("Logpump failure, dropped a batch of logs: {}", e),
                     }
                 }
 
@@ -220,8 +252,8 @@ async fn pump(
         }
     }
 
-    ctx.stats.logpump_stats.pending_logs.sub(log_count as i64);
     ctx.stats.logpump_stats.active_pumps.dec();
+    ctx.stats.logs_unqueued(guild_id, queued);
 }
 
 enum CanSend {
@@ -233,33 +265,28 @@ enum CanSend {
 async fn try_configure_to_send(
     ctx: &Arc<BotContext>,
     channel_id: &ChannelId,
-    style: &LogStyle,
+    use_webhook: bool,
     webhook_info: &mut Option<(WebhookId, String)>,
 ) -> Result<CanSend, OtherFailure> {
-    match style {
-        LogStyle::Text => {
-            if ctx
-                .get_channel_permissions_for(ctx.bot_user.id, *channel_id)
-                .await
-                .contains(Permissions::SEND_MESSAGES)
-            {
-                Ok(CanSend::Yes)
-            } else {
-                Ok(CanSend::MissingPermissions)
-            }
+    if use_webhook {
+        if webhook_info.is_none() {
+            // Is there a webhook stored in the database?
+            *webhook_info = get_webhook(ctx, channel_id).await?;
         }
-        LogStyle::Embed => {
-            if webhook_info.is_none() {
-                // Is there a webhook stored in the database?
-                *webhook_info = get_webhook(ctx, channel_id).await?;
-            }
 
-            if webhook_info.is_some() {
-                Ok(CanSend::Yes)
-            } else {
-                Ok(CanSend::MissingWebHook)
-            }
+        if webhook_info.is_some() {
+            Ok(CanSend::Yes)
+        } else {
+            Ok(CanSend::MissingWebHook)
         }
+    } else if ctx
+        .get_channel_permissions_for(ctx.bot_user.id, *channel_id)
+        .await
+        .contains(Permissions::SEND_MESSAGES)
+    {
+        Ok(CanSend::Yes)
+    } else {
+        Ok(CanSend::MissingPermissions)
     }
 }
 
@@ -308,61 +335,226 @@ enum WebhookValidity {
     Unusable,
 }
 
+enum Batch {
+    Text(String, Option<LogFile>),
+    Embeds(Vec<Embed>, Option<LogFile>),
+}
+
+/// Sends as much of the todo list as fits in a single request, backing off when we get ratelimited.
+///
+/// Logs are only removed from the todo list once they are delivered or given up on.
 async fn send(
     ctx: &Arc<BotContext>,
     todo: &mut Vec<Arc<LogData>>,
-    style: SendStyle<'_>,
+    destination: SendStyle<'_>,
+    style: LogStyle,
+    channel_config: &LogChannelConfig,
+    appearance: &HashMap<LogCategory, LogAppearance>,
     language: &LanguageIdentifier,
     channel_id: ChannelId,
-    timestamp: bool,
 ) -> Result<Option<WebhookValidity>, twilight_http::Error> {
+    // Webhooks can only have one identity per message, so logs are batched per identity
+    let identities = match destination {
+        SendStyle::Webhook(_) => Some(&channel_config.webhook_identities),
+        SendStyle::Channel => None,
+    };
+    let identity = identities.and_then(|identities| identities.get(&todo.first()?.log_type.get_category()));
+
+    let (batch, consumed) = build_batch(
+        ctx,
+        todo,
+        style,
+        appearance,
+        language,
+        channel_config.timestamps,
+        identities,
+    )
+    .await;
+
+    let batch = match batch {
+        Some(batch) => batch,
+        None => {
+            // Nothing in there could be rendered, those are already reported.
+            todo.drain(..consumed);
+            return Ok(None);
+        }
+    };
+
+    let mut backoff = BASE_BACKOFF;
+    let mut attempt = 1;
+    let result = loop {
+        let result = match (&batch, destination) {
+            (Batch::Text(output, file), SendStyle::Channel) => {
+                let mut request = ctx.http.create_message(channel_id).content(output.clone()).unwrap();
+                if let Some(file) = file {
+                    request = request.attachment(file.name.clone(), file.data.clone());
+                }
+                request.await.map(|_| None)
+            }
+            (Batch::Text(output, file), SendStyle::Webhook((webhook_id, token))) => {
+                let mut request = ctx.http.execute_webhook(*webhook_id, token).content(output.clone());
+                if let Some(file) = file {
+                    request = request.file(file.name.clone(), file.data.clone());
+                }
+                with_identity(request, identity)
+                    .await
+                    .map(|_| Some(WebhookValidity::Valid))
+            }
+            (Batch::Embeds(embeds, file), SendStyle::Webhook((webhook_id, token))) => {
+                let mut request = ctx.http.execute_webhook(*webhook_id, token).embeds(embeds.clone());
+                if let Some(file) = file {
+                    request = request.file(file.name.clone(), file.data.clone());
+                }
+                with_identity(request, identity)
+                    .await
+                    .map(|_| Some(WebhookValidity::Valid))
+            }
+            (Batch::Embeds(..), SendStyle::Channel) => unreachable!("embed batches are only build for webhooks"),
+        };
+
+        if let Err(e) = &result {
+            ctx.track_http_error(e).await;
+        }
+
+        match result {
+            Err(Error::Response { status, .. })
+                if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_SEND_ATTEMPTS =>
+            {
+                log::warn!(
+                    "Got ratelimited logging to channel {}, backing off for {}ms",
+                    channel_id,
+                    backoff.as_millis()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+
+    match result {
+        Ok(validity) => {
+            for log in todo.drain(..consumed) {
+                ctx.stats.logpump_logged(&style, &log.log_type.get_category()).await;
+            }
+            Ok(validity)
+        }
+        // The webhook was deleted or its token reset, keep the logs around so they can be send again once
+        // there is a new webhook.
+        Err(Error::Response { status, .. })
+            if (status == StatusCode::NOT_FOUND || status == StatusCode::UNAUTHORIZED)
+                && matches!(destination, SendStyle::Webhook(_)) =>
+        {
+            Ok(Some(WebhookValidity::Unusable))
+        }
+        Err(e) => {
+            todo.drain(..consumed);
+            ctx.stats.logpump_stats.dropped_logs.inc_by(consumed as u64);
+            Err(e)
+        }
+    }
+}
+
+fn with_identity(request: ExecuteWebhook<'_>, identity: Option<&WebhookIdentity>) -> ExecuteWebhook<'_> {
+    let identity = match identity {
+        Some(identity) => identity,
+        None => return request,
+    };
+
+    let mut request = request;
+    if let Some(username) = &identity.username {
+        request = request.username(username.clone());
+    }
+    if let Some(avatar_url) = &identity.avatar_url {
+        request = request.avatar_url(avatar_url.clone());
+    }
+    request
+}
+
+/// Assembles the next request out of the front of the todo list.
+///
+/// Returns the batch (if anything could be rendered) and how many logs from the todo list it covers.
+/// A log that comes with a file ends the batch it is in, so every request carries at most one file. With
+/// identities a log that would be sent under another identity than the first one ends the batch as well.
+async fn build_batch(
+    ctx: &Arc<BotContext>,
+    todo: &[Arc<LogData>],
+    style: LogStyle,
+    appearance: &HashMap<LogCategory, LogAppearance>,
+    language: &LanguageIdentifier,
+    timestamp: bool,
+    identities: Option<&HashMap<LogCategory, WebhookIdentity>>,
+) -> (Option<Batch>, usize) {
+    let mut consumed = 0;
+    let mut file = None;
+
+    let identity_of =
+        |log: &Arc<LogData>| identities.and_then(|identities| identities.get(&log.log_type.get_category()));
+    let batch_identity = todo.first().and_then(identity_of);
+
     match style {
-        SendStyle::Channel => {
+        LogStyle::Text => {
             let mut output = String::new();
 
-            while let Some(item) = todo.first() {
+            for item in todo {
+                if identity_of(item) != batch_identity {
+                    break;
+                }
+
                 // Get the user responsible for causing the log event.
                 let user = match ctx.get_user(item.source_user).await {
                     Ok(user) => user,
                     Err(e) => {
                         gearbot_error!("Failure retrieving user info for logging: {}", e);
                         log::error!("Log data: {:?}", item);
+                        consumed += 1;
                         continue;
                     }
                 };
 
-                let timestamp = if timestamp {
-                    format!("`[{}]`", chrono::Utc::now().format("%T").to_string())
-                } else {
-                    String::new()
-                };
-
-                let mut extra = format!(
-                    "{} {} {}",
+                let timestamp = if timestamp { Some(&item.timestamp) } else { None };
+                let mut extra = item.log_type.to_text(
+                    &ctx,
+                    language,
+                    &user,
+                    &item.source_channel,
                     timestamp,
-                    item.log_type.emoji().for_chat(),
-                    item.log_type.to_text(&ctx, language, &user, &item.source_channel)
+                    appearance.get(&item.log_type.get_category()),
                 );
-                extra.truncate(DISCORD_SIZE_LIMIT);
+                truncate(&mut extra, DISCORD_SIZE_LIMIT - 1);
 
-                // Only add to the output and remove from todo if it actually fits
+                // Only add to the output if it actually fits
                 if output.len() + extra.len() < DISCORD_SIZE_LIMIT {
                     output += &extra;
                     output += "\n";
-                    todo.remove(0);
+                    consumed += 1;
+
+                    file = item.log_type.file();
+                    if file.is_some() {
+                        break;
+                    }
                 } else {
                     // The message can't grow any longer without violating the size limit, time to send it.
                     break;
                 }
             }
 
-            // Assembly done, pack it into the future
-            ctx.http.create_message(channel_id).content(output).unwrap().await?;
-            Ok(None)
+            let batch = if output.is_empty() {
+                None
+            } else {
+                Some(Batch::Text(output, file))
+            };
+            (batch, consumed)
         }
-        SendStyle::Webhook(webhook) => {
+        LogStyle::Embed => {
             let mut out = vec![];
-            for data in todo.drain(..) {
+            for data in todo {
+                if out.len() == MAX_EMBEDS_PER_MESSAGE || identity_of(data) != batch_identity {
+                    break;
+                }
+                consumed += 1;
+
                 let user = match ctx.get_user(data.source_user).await {
                     Ok(user) => user,
                     Err(e) => {
@@ -372,28 +564,45 @@ async fn send(
                     }
                 };
 
-                match data.log_type.to_embed(&ctx, language, &user, &data.source_channel) {
-                    Ok(embed) => {
-                        out.push(embed);
-                        if out.len() == 10 {
-                            break;
-                        }
-                    }
+                match data.log_type.to_embed(
+                    &ctx,
+                    language,
+                    &user,
+                    &data.source_channel,
+                    &data.timestamp,
+                    appearance.get(&data.log_type.get_category()),
+                ) {
+                    Ok(embed) => out.push(embed),
                     Err(e) => {
                         gearbot_error!("Failed to create logging embed: {} (data: {:?})", e, data);
+                        continue;
                     }
                 }
-            }
 
-            let (webhook_id, token) = webhook;
-            match ctx.http.execute_webhook(*webhook_id, token).embeds(out).await {
-                Err(Error::Response { status, .. }) if status == StatusCode::NOT_FOUND => {
-                    Ok(Some(WebhookValidity::Unusable))
+                file = data.log_type.file();
+                if file.is_some() {
+                    break;
                 }
-                Err(e) => Err(e),
-                Ok(_) => Ok(Some(WebhookValidity::Valid)),
             }
+
+            let batch = if out.is_empty() {
+                None
+            } else {
+                Some(Batch::Embeds(out, file))
+            };
+            (batch, consumed)
+        }
+    }
+}
+
+/// Shortens a string to at most `max` bytes without cutting a character in half.
+fn truncate(s: &mut String, max: usize) {
+    if s.len() > max {
+        let mut index = max;
+        while !s.is_char_boundary(index) {
+            index -= 1;
         }
+        s.truncate(index);
     }
 }
 
@@ -1,20 +1,61 @@
-pub use bot_config::BotConfig;
+pub use bot_config::{Backups, BotConfig, Metrics, WebhookServer};
 pub use cold_resume_data::ColdRebootData;
-pub use guild_config::GuildConfig;
+pub use guild_config::{CommandCorrection, ConfigProblem, GuildConfig, GuildFeatures, LogCategory, FEATURE_NAMES};
 pub use reactors::Reactor;
 
+pub mod anti_nuke;
+pub mod auto_publish;
+pub mod automod;
+pub mod autoresponder;
+pub mod birthdays;
+pub mod bulk_roles;
+pub mod channel_templates;
+pub mod content_policy;
+pub mod error_tracking;
+pub mod escalation;
+pub mod events;
+pub mod feeds;
+pub mod github;
+
 mod bot_config;
 mod cold_resume_data;
 
 mod bot_context;
-pub use bot_context::{status, BotContext, BotStats, ShardState};
+pub use bot_context::{
+    status, AuditedEntry, AuditedWebhook, BotContext, BotStats, HealthReport, HealthState, ModAction, RoleMutation,
+    ShardState, SnipedMessage, TaskGuard, PRUNE_PREVIEW_TTL, UNDO_WINDOW,
+};
 
 mod command_context;
-pub use command_context::{CommandContext, CommandMessage};
+pub use command_context::{CommandContext, CommandMessage, FetchedMessage};
 
 mod guild_config;
+pub mod guild_emoji;
+pub mod guild_stats;
+
+pub mod heat;
+pub mod image_moderation;
+pub mod join_gate;
 
+pub mod levels;
 pub mod logging;
 pub mod logpump;
+pub mod pin_archive;
+pub mod profiles;
+pub mod prune;
+pub mod public_fetch;
+pub mod raid_mode;
 
 pub mod reactors;
+pub mod role_audit;
+pub mod suggestions;
+pub mod telemetry;
+pub mod temp_voice;
+pub mod text_folding;
+pub mod tickets;
+pub mod toxicity;
+pub mod webhook_server;
+pub mod webhooks;
+
+pub mod tiers;
+pub use tiers::Tier;
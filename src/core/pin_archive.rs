@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use twilight_embed_builder::{EmbedAuthorBuilder, EmbedBuilder, EmbedFooterBuilder, ImageSource};
+use twilight_model::channel::embed::Embed;
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, GuildId};
+
+use crate::cache::CachedUser;
+use crate::core::image_moderation;
+use crate::error::MessageError;
+use crate::utils::embeds::{self, DESCRIPTION_LIMIT};
+
+/// Discord doesn't allow more pins than this in a single channel.
+pub const MAX_PINS: usize = 50;
+/// Pin updates with an older last pin than this are about something getting unpinned.
+const NEW_PIN_WINDOW_SECONDS: i64 = 60;
+
+/// Where the pins of a channel get mirrored to.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PinArchiveChannel {
+    pub archive: ChannelId,
+    /// Unpin the oldest pin once the channel is full, so there is always room for the next one.
+    #[serde(default)]
+    pub unpin_oldest: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PinArchiveConfig {
+    /// For channels without settings of their own, without this only those channels are archived.
+    pub default: Option<PinArchiveChannel>,
+    pub channels: HashMap<ChannelId, PinArchiveChannel>,
+    /// Pins in these channels are never archived.
+    pub ignored_channels: Vec<ChannelId>,
+}
+
+impl PinArchiveConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || !self.channels.is_empty()
+    }
+
+    /// The archive settings for a channel, archives themselves are never archived again.
+    pub fn settings_for(&self, channel: ChannelId) -> Option<&PinArchiveChannel> {
+        if self.ignored_channels.contains(&channel) {
+            return None;
+        }
+        self.channels
+            .get(&channel)
+            .or_else(|| self.default.as_ref())
+            .filter(|settings| settings.archive != channel)
+    }
+
+    /// All channels the archive settings point to.
+    pub fn referenced_channels(&self) -> impl Iterator<Item = ChannelId> + '_ {
+        self.default
+            .iter()
+            .chain(self.channels.values())
+            .map(|settings| settings.archive)
+            .chain(self.channels.keys().copied())
+            .chain(self.ignored_channels.iter().copied())
+    }
+}
+
+/// If the last pin of a pin update happened just now, rather than it being an older pin that is left after
+/// something got unpinned.
+pub fn is_new_pin(last_pin_timestamp: Option<&str>, now: DateTime<Utc>) -> bool {
+    last_pin_timestamp
+        .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+        .map_or(false, |pinned| {
+            now - pinned.with_timezone(&Utc) < Duration::seconds(NEW_PIN_WINDOW_SECONDS)
+        })
+}
+
+/// The copy of a pinned message that goes in the archive.
+pub fn archive_embed(
+    guild_id: GuildId,
+    message: &Message,
+    jump_text: &str,
+    footer: String,
+) -> Result<Embed, MessageError> {
+    let author = CachedUser::from_user(&message.author);
+    let link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        guild_id, message.channel_id, message.id
+    );
+    let jump = format!("\n\n[{}]({})", jump_text, link);
+
+    let mut description = message.content.clone();
+    let mut image = None;
+    for attachment in &message.attachments {
+        if image.is_none() && image_moderation::is_image(&attachment.filename) {
+            image = Some(attachment.url.clone());
+        } else {
+            description += &format!("\n{}", attachment.url);
+        }
+    }
+    let description = embeds::truncate(description.trim(), DESCRIPTION_LIMIT - jump.chars().count()) + &jump;
+
+    let mut builder = EmbedBuilder::new()
+        .description(description)?
+        .author(
+            EmbedAuthorBuilder::new()
+                .name(author.full_name())?
+                .icon_url(ImageSource::url(author.avatar_url())?),
+        )
+        .footer(EmbedFooterBuilder::new(footer)?)
+        .timestamp(message.timestamp.clone());
+    if let Some(image) = image {
+        builder = builder.image(ImageSource::url(image)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_settings_of_a_channel() {
+        let config: PinArchiveConfig = serde_json::from_str(
+            r#"{"default": {"archive": 1}, "channels": {"2": {"archive": 3, "unpin_oldest": true}}, "ignored_channels": [4]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.settings_for(ChannelId(2)).unwrap().archive, ChannelId(3));
+        assert!(config.settings_for(ChannelId(2)).unwrap().unpin_oldest);
+        assert_eq!(config.settings_for(ChannelId(5)).unwrap().archive, ChannelId(1));
+        assert!(config.settings_for(ChannelId(4)).is_none());
+        assert!(config.settings_for(ChannelId(1)).is_none());
+        assert!(!PinArchiveConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn only_recent_pins_are_new() {
+        let now = DateTime::parse_from_rfc3339("2021-06-01T12:00:30+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(is_new_pin(Some("2021-06-01T12:00:00+00:00"), now));
+        assert!(!is_new_pin(Some("2021-06-01T11:50:00+00:00"), now));
+        assert!(!is_new_pin(None, now));
+        assert!(!is_new_pin(Some("yesterday"), now));
+    }
+}
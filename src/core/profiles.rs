@@ -0,0 +1,112 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::GuildConfig;
+
+/// Sections that can't be part of a profile, applying one should never swap out the profiles themselves.
+const EXCLUDED_SECTIONS: &[&str] = &["profiles"];
+
+/// A named snapshot of some sections of a guild config, like "event" or "lockdown".
+///
+/// Sections are the top level keys of the config, and are stored as they were when the profile was saved so
+/// applying it puts them back exactly like that.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ConfigProfile {
+    pub sections: Map<String, Value>,
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    UnknownSection(String),
+    /// The stored sections don't fit the config anymore.
+    Invalid(serde_json::Error),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::UnknownSection(name) => write!(f, "there is no config section called ``{}``", name),
+            ProfileError::Invalid(e) => write!(f, "the profile no longer fits the config: {}", e),
+        }
+    }
+}
+
+impl ConfigProfile {
+    /// Snapshots the given sections of a config.
+    pub fn capture<S: AsRef<str>>(config: &GuildConfig, sections: &[S]) -> Result<Self, ProfileError> {
+        let mut current = config_sections(config)?;
+        let mut captured = Map::new();
+        for section in sections {
+            let section = section.as_ref();
+            let value = current
+                .remove(section)
+                .filter(|_| !EXCLUDED_SECTIONS.contains(&section))
+                .ok_or_else(|| ProfileError::UnknownSection(section.to_string()))?;
+            captured.insert(section.to_string(), value);
+        }
+
+        Ok(ConfigProfile { sections: captured })
+    }
+
+    /// The config with the sections of this profile swapped in.
+    pub fn apply_to(&self, config: &GuildConfig) -> Result<GuildConfig, ProfileError> {
+        let mut current = config_sections(config)?;
+        for (section, value) in &self.sections {
+            current.insert(section.clone(), value.clone());
+        }
+
+        serde_json::from_value(Value::Object(current)).map_err(ProfileError::Invalid)
+    }
+
+    /// Adds the sections of the other profile this one doesn't have yet.
+    pub fn fill_from(&mut self, other: ConfigProfile) {
+        for (section, value) in other.sections {
+            self.sections.entry(section).or_insert(value);
+        }
+    }
+}
+
+fn config_sections(config: &GuildConfig) -> Result<Map<String, Value>, ProfileError> {
+    match serde_json::to_value(config).map_err(ProfileError::Invalid)? {
+        Value::Object(sections) => Ok(sections),
+        // Structs always serialize to objects
+        _ => Ok(Map::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_a_profile_restores_its_sections() {
+        let mut config = GuildConfig::default();
+        config.prefix = String::from("?");
+        config.levels.enabled = true;
+        let profile = ConfigProfile::capture(&config, &["prefix"]).unwrap();
+
+        let mut changed = config.clone();
+        changed.prefix = String::from("!");
+        changed.levels.enabled = false;
+        let restored = profile.apply_to(&changed).unwrap();
+
+        assert_eq!(restored.prefix, "?");
+        assert!(!restored.levels.enabled);
+    }
+
+    #[test]
+    fn rejects_unknown_and_excluded_sections() {
+        let config = GuildConfig::default();
+
+        assert!(matches!(
+            ConfigProfile::capture(&config, &["nope"]),
+            Err(ProfileError::UnknownSection(_))
+        ));
+        assert!(matches!(
+            ConfigProfile::capture(&config, &["profiles"]),
+            Err(ProfileError::UnknownSection(_))
+        ));
+    }
+}
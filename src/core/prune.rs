@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{RoleId, UserId};
+
+use crate::cache::CachedMember;
+
+/// How far back prune looks for activity, and who it leaves alone.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PruneConfig {
+    /// Members without messages or voice activity for this many days are inactive, unless another window is given.
+    pub window_days: u32,
+    /// Members with any of these roles are never pruned.
+    pub exempt_roles: Vec<RoleId>,
+    /// The role inactive members get when they are pruned with a role rather than kicked.
+    pub role: Option<RoleId>,
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        PruneConfig {
+            window_days: 30,
+            exempt_roles: vec![],
+            role: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PruneAction {
+    Kick,
+    /// Hands out the role instead, for guilds that rather move inactive members out of the way.
+    Role(RoleId),
+}
+
+/// The inactive members a moderator was shown, executing a prune only ever acts on these.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PrunePreview {
+    pub moderator: UserId,
+    pub window_days: u32,
+    pub members: Vec<UserId>,
+}
+
+/// The members without any activity in the window.
+///
+/// Members that joined within the window didn't get the chance to be active yet, so they are left out along with
+/// the exempt ones.
+pub fn inactive_members<'a>(
+    members: impl IntoIterator<Item = &'a CachedMember>,
+    active: &HashSet<UserId>,
+    config: &PruneConfig,
+    window_days: u32,
+    now: DateTime<Utc>,
+) -> Vec<UserId> {
+    let window_start = now - Duration::days(window_days as i64);
+    let mut inactive: Vec<UserId> = members
+        .into_iter()
+        .filter(|member| !active.contains(&member.user_id))
+        .filter(|member| !member.roles.iter().any(|role| config.exempt_roles.contains(role)))
+        .filter(|member| {
+            member
+                .joined_at
+                .as_ref()
+                .and_then(|joined| DateTime::parse_from_str(joined, "%FT%T%.f%z").ok())
+                .map_or(true, |joined| joined.with_timezone(&Utc) < window_start)
+        })
+        .map(|member| member.user_id)
+        .collect();
+
+    inactive.sort_unstable();
+    inactive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures::cached_member;
+
+    #[test]
+    fn only_inactive_members_that_had_the_time_to_be_active_are_pruned() {
+        let now = DateTime::parse_from_rfc3339("2021-03-01T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let members = vec![
+            cached_member(1, "2020-01-01T00:00:00.000000+00:00", &[]),
+            cached_member(2, "2020-01-01T00:00:00.000000+00:00", &[]),
+            cached_member(3, "2021-02-20T00:00:00.000000+00:00", &[]),
+            cached_member(4, "2020-01-01T00:00:00.000000+00:00", &[10]),
+        ];
+        let active = vec![UserId(2)].into_iter().collect();
+        let config = PruneConfig {
+            exempt_roles: vec![RoleId(10)],
+            ..PruneConfig::default()
+        };
+
+        assert_eq!(inactive_members(&members, &active, &config, 30, now), vec![UserId(1)]);
+        assert_eq!(
+            inactive_members(&members, &active, &config, 7, now),
+            vec![UserId(1), UserId(3)]
+        );
+    }
+}
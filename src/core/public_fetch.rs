@@ -0,0 +1,145 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::{ClientBuilder, Response};
+use url::{Host, Url};
+
+const MAX_REDIRECTS: usize = 5;
+
+/// A client for fetching urls users gave us, like feeds or emoji images.
+///
+/// Only http(s) urls on the public internet can be fetched, the bot has no business fetching anything on the network
+/// it runs in. This holds for redirects and for domains that resolve to internal addresses as well.
+pub fn client_builder(timeout: Duration) -> ClientBuilder {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS || !is_allowed_url(attempt.url()) {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        }))
+}
+
+/// Checks what can be seen from the url itself, where domains point to is checked when connecting.
+pub fn is_allowed_url(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            !(domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain.ends_with(".local")
+                || domain.ends_with(".internal"))
+        }
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        None => false,
+    }
+}
+
+/// Reads the body a chunk at a time, giving up as soon as it's over the limit. Returns `None` if it was.
+pub async fn read_limited(mut response: Response, limit: usize) -> Result<Option<Vec<u8>>, reqwest::Error> {
+    if response.content_length().unwrap_or(0) as usize > limit {
+        return Ok(None);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() > limit {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(body))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // This network (0.0.0.0/8) and carrier grade NAT (100.64.0.0/10)
+                || first == 0
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            // Unique local (fc00::/7) and link local (fe80::/10) addresses
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+                && ip.to_ipv4().map_or(true, |ip| is_public_ip(IpAddr::V4(ip)))
+        }
+    }
+}
+
+/// Resolves like the system does, but leaves out every address that isn't public. Checking the addresses we
+/// actually connect to is what keeps domains pointing (or rebinding) to internal addresses out.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+            if addrs.is_empty() {
+                return Err(format!("{} doesn't resolve to any public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_allows_public_urls() {
+        let allowed = |url: &str| is_allowed_url(&Url::parse(url).unwrap());
+
+        assert!(allowed("https://example.com/feed.xml"));
+        assert!(allowed("http://93.184.216.34/rss"));
+        assert!(!allowed("ftp://example.com/feed.xml"));
+        assert!(!allowed("http://localhost:8080/feed"));
+        assert!(!allowed("http://127.0.0.1/feed"));
+        assert!(!allowed("http://10.0.0.5/feed"));
+        assert!(!allowed("http://169.254.169.254/latest/meta-data"));
+        assert!(!allowed("http://100.64.0.1/feed"));
+        assert!(!allowed("http://[::1]/feed"));
+        assert!(!allowed("http://[::ffff:192.168.1.1]/feed"));
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        let public = |ip: &str| is_public_ip(ip.parse().unwrap());
+
+        assert!(public("1.1.1.1"));
+        assert!(public("100.128.0.1"));
+        assert!(public("2606:4700::1111"));
+        assert!(!public("100.127.255.254"));
+        assert!(!public("0.1.2.3"));
+        assert!(!public("224.0.0.1"));
+        assert!(!public("fd00::1"));
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::{Permissions, VerificationLevel};
+use twilight_model::id::ChannelId;
+
+use crate::core::join_gate::JoinGateAction;
+
+/// The lockdown that gets applied when raid mode goes on.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RaidModeConfig {
+    /// Lets the raid detector turn raid mode on by itself.
+    pub auto: bool,
+    /// How many joins within the window count as a raid.
+    pub join_threshold: u32,
+    /// In seconds.
+    pub join_window: u32,
+    /// Takes away the permission to create invites from everyone, existing invites keep working.
+    pub pause_invites: bool,
+    /// Raised to this level if it's lower, `None` to leave it alone.
+    pub verification_level: Option<VerificationLevel>,
+    /// Replaces the configured join gate action, the gate is active during raid mode even if it's not enabled.
+    pub join_gate_action: JoinGateAction,
+    /// Channels where everyone loses the permission to send messages.
+    pub restricted_channels: Vec<ChannelId>,
+}
+
+impl Default for RaidModeConfig {
+    fn default() -> Self {
+        RaidModeConfig {
+            auto: false,
+            join_threshold: 10,
+            join_window: 10,
+            pause_invites: true,
+            verification_level: Some(VerificationLevel::High),
+            join_gate_action: JoinGateAction::RaidKick,
+            restricted_channels: vec![],
+        }
+    }
+}
+
+/// Raid mode while it is on, with everything it changed so it can be put back the way it was.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RaidModeState {
+    /// Turned on by the raid detector rather than a moderator.
+    pub automatic: bool,
+    /// Unix timestamp.
+    pub since: i64,
+    pub previous_verification_level: Option<VerificationLevel>,
+    /// The permissions of the everyone role, if the invites were paused.
+    pub previous_everyone_permissions: Option<Permissions>,
+    /// The overwrites for the everyone role of the channels that were restricted, as (allow, deny).
+    pub restricted_channels: Vec<(ChannelId, Option<(Permissions, Permissions)>)>,
+}
+
+/// Raises the verification level to the target, `None` if it is already at least that high.
+pub fn raised_verification_level(current: VerificationLevel, target: VerificationLevel) -> Option<VerificationLevel> {
+    if verification_rank(current) < verification_rank(target) {
+        Some(target)
+    } else {
+        None
+    }
+}
+
+fn verification_rank(level: VerificationLevel) -> u8 {
+    match level {
+        VerificationLevel::None => 0,
+        VerificationLevel::Low => 1,
+        VerificationLevel::Medium => 2,
+        VerificationLevel::High => 3,
+        VerificationLevel::VeryHigh => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_raises_the_verification_level() {
+        assert_eq!(
+            raised_verification_level(VerificationLevel::Low, VerificationLevel::High),
+            Some(VerificationLevel::High)
+        );
+        assert_eq!(
+            raised_verification_level(VerificationLevel::VeryHigh, VerificationLevel::High),
+            None
+        );
+        assert_eq!(
+            raised_verification_level(VerificationLevel::High, VerificationLevel::High),
+            None
+        );
+    }
+}
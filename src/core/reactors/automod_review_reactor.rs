@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::Reaction;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+use unic_langid::LanguageIdentifier;
+
+use crate::cache::CachedMember;
+use crate::commands::meta::nodes::GearBotPermissions;
+use crate::core::automod::AutomodAction;
+use crate::core::bot_context::BotContext;
+use crate::core::reactors::get_emoji;
+use crate::error::ReactorError;
+use crate::translation::{FluArgs, GearBotString};
+use crate::utils::{embeds, Emoji};
+
+/// How much of the offending message is shown to the reviewers.
+const CONTENT_LIMIT: usize = 1000;
+
+/// Marks that an action against a user is waiting for review, so repeat offences don't flood the review channel.
+pub fn review_pending_key(guild_id: GuildId, user_id: UserId, action: AutomodAction) -> String {
+    format!("automod_review_pending:{}:{}:{}", guild_id, user_id, action)
+}
+
+/// An automod action that was held back until a moderator approves or rejects it.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AutomodReviewReactor {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub action: AutomodAction,
+    /// The rule that wanted the action, or "heat" for escalations.
+    pub rule: String,
+    pub channel_id: ChannelId,
+    pub content: String,
+    /// Unix timestamp after which the action is dropped.
+    pub expires_at: i64,
+}
+
+impl AutomodReviewReactor {
+    pub fn new(
+        guild_id: GuildId,
+        user_id: UserId,
+        action: AutomodAction,
+        rule: &str,
+        channel_id: ChannelId,
+        content: &str,
+        expires_in: u32,
+    ) -> Self {
+        AutomodReviewReactor {
+            guild_id,
+            user_id,
+            action,
+            rule: rule.to_string(),
+            channel_id,
+            content: embeds::truncate(&content.replace("`", "ˋ"), CONTENT_LIMIT),
+            expires_at: Utc::now().timestamp() + expires_in as i64,
+        }
+    }
+
+    pub fn processes(&self, reaction: &Reaction) -> Option<Emoji> {
+        get_emoji(vec![Emoji::Yes, Emoji::No], reaction)
+    }
+
+    /// Seconds left before the action is dropped.
+    pub fn remaining(&self) -> u32 {
+        (self.expires_at - Utc::now().timestamp()).max(1) as u32
+    }
+
+    /// What the review is about, without the instructions so it can be kept after the decision.
+    pub fn review_text(&self, ctx: &BotContext, lang: &LanguageIdentifier) -> String {
+        let args = FluArgs::with_capacity(6)
+            .add("gearwarn", Emoji::Warn.for_chat())
+            .add("user_id", self.user_id.to_string())
+            .add("rule", self.rule.clone())
+            .add("channel_id", self.channel_id.to_string())
+            .add("action", self.action.to_string())
+            .add("content", self.content.clone())
+            .generate();
+        ctx.translate_with_args(lang, GearBotString::AutomodReviewQueued, &args)
+    }
+
+    pub fn prompt_text(&self, ctx: &BotContext, lang: &LanguageIdentifier) -> String {
+        let args = FluArgs::with_capacity(3)
+            .add("gearyes", Emoji::Yes.for_chat())
+            .add("gearno", Emoji::No.for_chat())
+            .add("expires", self.expires_at.to_string())
+            .generate();
+        format!(
+            "{}\n{}",
+            self.review_text(ctx, lang),
+            ctx.translate_with_args(lang, GearBotString::AutomodReviewInstructions, &args)
+        )
+    }
+
+    /// Carries out the decision if it comes from someone that's allowed to make it, returning if it did.
+    pub async fn do_the_thing(
+        &self,
+        emoji: &Emoji,
+        ctx: &Arc<BotContext>,
+        member: Option<Arc<CachedMember>>,
+        reaction: &Reaction,
+    ) -> Result<bool, ReactorError> {
+        let (member, guild) = match (member, ctx.cache.get_guild(&self.guild_id).await) {
+            (Some(member), Some(guild)) => (member, guild),
+            _ => return Ok(false),
+        };
+        let config = ctx.get_config(self.guild_id).await?;
+        let permissions = ctx.get_permissions_for(&guild, &member, &config).await;
+        if !permissions.contains(GearBotPermissions::AUTOMOD_COMMAND) {
+            return Ok(false);
+        }
+
+        let moderator = reaction.user_id;
+        let key = if matches!(emoji, Emoji::Yes) {
+            match ctx
+                .punish_for_automod(self.action, self.guild_id, self.user_id, moderator)
+                .await
+            {
+                Ok(()) => GearBotString::AutomodReviewApproved,
+                Err(e) => {
+                    log::warn!(
+                        "Failed to {} {} in {} after it was approved: {}",
+                        self.action,
+                        self.user_id,
+                        self.guild_id,
+                        e
+                    );
+                    GearBotString::AutomodReviewFailed
+                }
+            }
+        } else {
+            GearBotString::AutomodReviewRejected
+        };
+
+        let args = FluArgs::with_capacity(4)
+            .add("gearyes", Emoji::Yes.for_chat())
+            .add("gearno", Emoji::No.for_chat())
+            .add("moderator", moderator.to_string())
+            .add("action", self.action.to_string())
+            .generate();
+        let decision = ctx.translate_with_args(&config.language, key, &args);
+        ctx.http
+            .update_message(reaction.channel_id, reaction.message_id)
+            .content(format!("{}\n{}", self.review_text(ctx, &config.language), decision))?
+            .await?;
+        ctx.datastore
+            .cache_pool
+            .delete(&review_pending_key(self.guild_id, self.user_id, self.action))
+            .await?;
+
+        Ok(true)
+    }
+}
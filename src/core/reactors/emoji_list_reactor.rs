@@ -34,7 +34,7 @@ impl EmojiListReactor {
         if member.is_some() {
             // If we have a cached member, we have a guild id
             if let Some(guild) = ctx.cache.get_guild(&reaction.guild_id.unwrap()).await {
-                let pages = guild.emoji.len() as u8 + 1;
+                let pages = guild.emoji.read().await.len() as u8 + 1;
                 self.page = scroll_page(pages, self.page, &emoji);
                 let embed = gen_emoji_page(self.page, pages, &guild, &ctx.get_config(guild.id).await?, ctx).await?;
                 ctx.http
@@ -88,7 +88,7 @@ pub async fn gen_emoji_page(
         author_builder =
             author_builder.name(ctx.translate_with_args(lang, GearBotString::EmojiPageHeader, &header_args))?;
 
-        let emoji = guild.emoji.get(page as usize - 1).unwrap();
+        let emoji = guild.emoji.read().await.get(page as usize - 1).cloned().unwrap();
 
         let role_info = if emoji.roles.is_empty() {
             gear_no.to_string()
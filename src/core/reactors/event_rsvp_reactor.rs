@@ -0,0 +1,56 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::Reaction;
+use twilight_model::id::GuildId;
+
+use crate::core::bot_context::BotContext;
+use crate::core::events::RsvpStatus;
+use crate::core::reactors::get_emoji;
+use crate::error::ReactorError;
+use crate::utils::Emoji;
+
+/// The announcement of an event, members RSVP by reacting to it until the event starts.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct EventRsvpReactor {
+    pub guild_id: GuildId,
+    pub event_id: i32,
+    /// Unix timestamp.
+    pub starts_at: i64,
+}
+
+impl EventRsvpReactor {
+    pub fn processes(&self, reaction: &Reaction) -> Option<Emoji> {
+        get_emoji(RsvpStatus::ALL.iter().map(|status| status.emoji()).collect(), reaction)
+    }
+
+    /// Seconds left before the event starts.
+    pub fn remaining(&self) -> u32 {
+        (self.starts_at - Utc::now().timestamp()).max(1) as u32
+    }
+
+    /// Records the RSVP and shows it on the announcement.
+    pub async fn do_the_thing(&self, emoji: &Emoji, ctx: &BotContext, reaction: &Reaction) -> Result<(), ReactorError> {
+        let status = match RsvpStatus::from_emoji(emoji) {
+            Some(status) => status,
+            None => return Ok(()),
+        };
+        ctx.datastore
+            .toggle_rsvp(self.guild_id, self.event_id, reaction.user_id, status.as_str())
+            .await?;
+
+        // Cancelled in the meantime
+        let event = match ctx.datastore.get_event(self.guild_id, self.event_id).await? {
+            Some(event) => event,
+            None => return Ok(()),
+        };
+        let config = ctx.get_config(self.guild_id).await?;
+        let rsvps = ctx.datastore.get_rsvps(self.guild_id, self.event_id).await?;
+        let embed = ctx.event_embed(&config, &event, &rsvps)?;
+        ctx.http
+            .update_message(reaction.channel_id, reaction.message_id)
+            .embed(embed)?
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::Reaction;
+use twilight_model::id::{GuildId, UserId};
+
+use crate::core::bot_context::BotContext;
+use crate::core::join_gate::ChallengeLevel;
+use crate::core::reactors::get_emoji;
+use crate::error::ReactorError;
+use crate::utils::Emoji;
+
+/// The DM a new member has to react to before they get into the guild.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JoinChallengeReactor {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+}
+
+impl JoinChallengeReactor {
+    pub fn processes(&self, reaction: &Reaction) -> Option<Emoji> {
+        get_emoji(vec![Emoji::Yes], reaction)
+    }
+
+    /// Lets the member in if it's them reacting, returning if it was.
+    pub async fn do_the_thing(&self, ctx: &BotContext, reaction: &Reaction) -> Result<bool, ReactorError> {
+        if reaction.user_id != self.user_id {
+            return Ok(false);
+        }
+
+        ctx.pass_challenge(self.guild_id, self.user_id, ChallengeLevel::Reaction)
+            .await?;
+        Ok(true)
+    }
+}
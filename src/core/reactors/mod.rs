@@ -2,23 +2,32 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use twilight_model::channel::Reaction;
-use twilight_model::id::MessageId;
+use twilight_model::id::{GuildId, MessageId, UserId};
 
 use crate::core::bot_context::BotContext;
 use crate::core::reactors::emoji_list_reactor::EmojiListReactor;
+use crate::core::reactors::event_rsvp_reactor::EventRsvpReactor;
+use crate::core::reactors::join_challenge_reactor::JoinChallengeReactor;
 use crate::error::{DatabaseError, ReactorError};
 use crate::utils::Emoji;
 
+mod automod_review_reactor;
 mod emoji_list_reactor;
+mod event_rsvp_reactor;
 mod help_reactor;
+mod join_challenge_reactor;
 pub mod reactor_controller;
 
+pub use automod_review_reactor::{review_pending_key, AutomodReviewReactor};
 pub use emoji_list_reactor::gen_emoji_page;
 
 #[derive(Deserialize, Serialize, Debug)]
 pub enum Reactor {
     Help,
     EmojiList(EmojiListReactor),
+    AutomodReview(AutomodReviewReactor),
+    JoinChallenge(JoinChallengeReactor),
+    EventRsvp(EventRsvpReactor),
 }
 
 impl Reactor {
@@ -28,10 +37,25 @@ impl Reactor {
         }
     }
 
+    pub fn new_join_challenge(guild_id: GuildId, user_id: UserId) -> Self {
+        Reactor::JoinChallenge(JoinChallengeReactor { guild_id, user_id })
+    }
+
+    pub fn new_event_rsvp(guild_id: GuildId, event_id: i32, starts_at: i64) -> Self {
+        Reactor::EventRsvp(EventRsvpReactor {
+            guild_id,
+            event_id,
+            starts_at,
+        })
+    }
+
     pub fn processes(&self, reaction: &Reaction) -> Option<Emoji> {
         match self {
             Reactor::Help => None,
             Reactor::EmojiList(inner) => inner.processes(reaction),
+            Reactor::AutomodReview(inner) => inner.processes(reaction),
+            Reactor::JoinChallenge(inner) => inner.processes(reaction),
+            Reactor::EventRsvp(inner) => inner.processes(reaction),
         }
     }
 
@@ -51,6 +75,31 @@ impl Reactor {
                 inner.do_the_thing(emoji, ctx, member, reaction).await?;
                 Reactor::EmojiList { 0: inner }
             }
+            Reactor::AutomodReview(inner) => {
+                // Once decided there is nothing left to react to, the reactions stay to show who decided
+                if inner.do_the_thing(emoji, ctx, member, reaction).await? {
+                    ctx.datastore
+                        .cache_pool
+                        .delete(&format!("reactor:{}", reaction.message_id))
+                        .await?;
+                    return Ok(Reactor::AutomodReview(inner));
+                }
+                Reactor::AutomodReview(inner)
+            }
+            Reactor::JoinChallenge(inner) => {
+                // This lives in a DM, where we can't take the reaction away again
+                if inner.do_the_thing(ctx, reaction).await? {
+                    ctx.datastore
+                        .cache_pool
+                        .delete(&format!("reactor:{}", reaction.message_id))
+                        .await?;
+                }
+                return Ok(Reactor::JoinChallenge(inner));
+            }
+            Reactor::EventRsvp(inner) => {
+                inner.do_the_thing(emoji, ctx, reaction).await?;
+                Reactor::EventRsvp(inner)
+            }
         };
 
         new.save(ctx, reaction.message_id).await?;
@@ -65,7 +114,7 @@ impl Reactor {
         Ok(new)
     }
 
-    pub async fn save(&self, ctx: &Arc<BotContext>, message_id: MessageId) -> Result<(), DatabaseError> {
+    pub async fn save(&self, ctx: &BotContext, message_id: MessageId) -> Result<(), DatabaseError> {
         ctx.datastore
             .cache_pool
             .set(&format!("reactor:{}", message_id), self, Some(self.get_expiry()))
@@ -73,7 +122,11 @@ impl Reactor {
     }
 
     fn get_expiry(&self) -> u32 {
-        60 * 60 * 24
+        match self {
+            Reactor::AutomodReview(inner) => inner.remaining(),
+            Reactor::EventRsvp(inner) => inner.remaining(),
+            _ => 60 * 60 * 24,
+        }
     }
 }
 
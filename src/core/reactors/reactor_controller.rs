@@ -8,6 +8,10 @@ use crate::error::ReactorError;
 
 pub async fn process_reaction(bot_context: &Arc<BotContext>, reaction: &Reaction) -> Result<(), ReactorError> {
     if reaction.user_id != bot_context.bot_user.id {
+        if bot_context.answer_confirmation(reaction).await {
+            return Ok(());
+        }
+
         if let Some(reactor) = bot_context
             .datastore
             .cache_pool
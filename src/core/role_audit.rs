@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use twilight_model::guild::Permissions;
+use twilight_model::id::{GuildId, RoleId};
+
+use crate::cache::{CachedMember, CachedRole};
+
+/// Roles worth a second look, every list is in the order the roles are shown in the server settings.
+#[derive(Debug, Default, PartialEq)]
+pub struct RoleAudit {
+    /// Roles nobody has, integrations take care of their own so those are left out.
+    pub empty: Vec<RoleId>,
+    /// Groups of roles that grant exactly the same permissions, roles without any are left out.
+    pub duplicate_permissions: Vec<Vec<RoleId>>,
+    /// Hoisted roles that aren't from an integration, every one of them splits up the member list.
+    pub hoisted: Vec<RoleId>,
+    /// Roles above the highest role of the bot that members have, with how many. Members with these can't be
+    /// moderated by the bot.
+    pub above_bot: Vec<(RoleId, usize)>,
+}
+
+impl RoleAudit {
+    pub fn is_clean(&self) -> bool {
+        self.empty.is_empty()
+            && self.duplicate_permissions.is_empty()
+            && self.hoisted.is_empty()
+            && self.above_bot.is_empty()
+    }
+}
+
+/// Goes over the roles of a guild, `top_position` being the position of the highest role of the bot.
+pub fn audit_roles<'a>(
+    guild_id: GuildId,
+    roles: impl IntoIterator<Item = &'a CachedRole>,
+    members: impl IntoIterator<Item = &'a CachedMember>,
+    top_position: i64,
+) -> RoleAudit {
+    // The everyone role shares its id with the guild, there's nothing to do about that one
+    let mut roles = roles
+        .into_iter()
+        .filter(|role| role.id.0 != guild_id.0)
+        .collect::<Vec<_>>();
+    roles.sort_by(|a, b| b.position.cmp(&a.position).then(a.id.cmp(&b.id)));
+
+    let mut holders = HashMap::<RoleId, usize>::new();
+    for member in members {
+        for role in &member.roles {
+            *holders.entry(*role).or_default() += 1;
+        }
+    }
+    let holders_of = |role: &CachedRole| holders.get(&role.id).copied().unwrap_or(0);
+
+    let mut by_permissions = Vec::<(Permissions, Vec<RoleId>)>::new();
+    for role in roles
+        .iter()
+        .filter(|role| !role.managed && !role.permissions.is_empty())
+    {
+        match by_permissions
+            .iter_mut()
+            .find(|(permissions, _)| *permissions == role.permissions)
+        {
+            Some((_, group)) => group.push(role.id),
+            None => by_permissions.push((role.permissions, vec![role.id])),
+        }
+    }
+
+    RoleAudit {
+        empty: roles
+            .iter()
+            .filter(|role| !role.managed && holders_of(role) == 0)
+            .map(|role| role.id)
+            .collect(),
+        duplicate_permissions: by_permissions
+            .into_iter()
+            .map(|(_, group)| group)
+            .filter(|group| group.len() > 1)
+            .collect(),
+        hoisted: roles
+            .iter()
+            .filter(|role| role.hoisted && !role.managed)
+            .map(|role| role.id)
+            .collect(),
+        above_bot: roles
+            .iter()
+            .filter(|role| role.position > top_position)
+            .map(|role| (role.id, holders_of(role)))
+            .filter(|(_, holders)| *holders > 0)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures::{cached_member, cached_role};
+
+    fn role(id: u64, position: i64, permissions: Permissions, hoisted: bool, managed: bool) -> CachedRole {
+        CachedRole {
+            hoisted,
+            managed,
+            ..cached_role(id, position, permissions)
+        }
+    }
+
+    #[test]
+    fn finds_roles_to_clean_up() {
+        let moderation = Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS;
+        let roles = vec![
+            role(1, 0, Permissions::SEND_MESSAGES, false, false),
+            role(10, 5, Permissions::ADMINISTRATOR, true, false),
+            role(11, 4, Permissions::empty(), false, true),
+            role(12, 3, moderation, true, false),
+            role(13, 2, moderation, false, false),
+            role(14, 1, Permissions::empty(), false, false),
+        ];
+        let joined = "2020-01-01T00:00:00.000000+00:00";
+        let members = vec![
+            cached_member(100, joined, &[10, 12]),
+            cached_member(101, joined, &[11, 12, 13]),
+        ];
+
+        let audit = audit_roles(GuildId(1), &roles, &members, 4);
+        assert_eq!(audit.empty, vec![RoleId(14)]);
+        assert_eq!(audit.duplicate_permissions, vec![vec![RoleId(12), RoleId(13)]]);
+        assert_eq!(audit.hoisted, vec![RoleId(10), RoleId(12)]);
+        assert_eq!(audit.above_bot, vec![(RoleId(10), 1)]);
+        assert!(!audit.is_clean());
+
+        let clean = audit_roles(GuildId(1), &roles[..1], &members, 4);
+        assert!(clean.is_clean());
+    }
+}
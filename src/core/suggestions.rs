@@ -0,0 +1,122 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use twilight_http::request::channel::reaction::RequestReactionType;
+use twilight_model::id::ChannelId;
+
+/// A box members can drop suggestions in with ``suggest``, staff marks them once they've looked at them.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SuggestionConfig {
+    pub enabled: bool,
+    /// Where suggestions get posted, nothing can be suggested without one.
+    pub channel: Option<ChannelId>,
+    /// Reactions added to every suggestion to vote with, only unicode emoji.
+    pub upvote: String,
+    pub downvote: String,
+    /// DM the member who made the suggestion when staff changes its status.
+    pub notify_author: bool,
+}
+
+impl Default for SuggestionConfig {
+    fn default() -> Self {
+        SuggestionConfig {
+            enabled: false,
+            channel: None,
+            upvote: String::from("👍"),
+            downvote: String::from("👎"),
+            notify_author: true,
+        }
+    }
+}
+
+impl SuggestionConfig {
+    /// The channel suggestions go to, if the box is open.
+    pub fn target(&self) -> Option<ChannelId> {
+        self.channel.filter(|_| self.enabled)
+    }
+
+    pub fn vote_reactions(&self) -> Vec<RequestReactionType> {
+        [&self.upvote, &self.downvote]
+            .iter()
+            .filter(|emoji| !emoji.is_empty())
+            .map(|emoji| RequestReactionType::Unicode {
+                name: emoji.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuggestionStatus {
+    Open,
+    Approved,
+    Denied,
+    Considered,
+}
+
+impl SuggestionStatus {
+    /// How the status is stored.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionStatus::Open => "open",
+            SuggestionStatus::Approved => "approved",
+            SuggestionStatus::Denied => "denied",
+            SuggestionStatus::Considered => "considered",
+        }
+    }
+
+    pub fn from_str(status: &str) -> Option<Self> {
+        match status {
+            "open" => Some(SuggestionStatus::Open),
+            "approved" => Some(SuggestionStatus::Approved),
+            "denied" => Some(SuggestionStatus::Denied),
+            "considered" => Some(SuggestionStatus::Considered),
+            _ => None,
+        }
+    }
+
+    /// The color of the embed of suggestions with this status.
+    pub fn color(&self) -> u32 {
+        match self {
+            SuggestionStatus::Open => 0x72_89da,
+            SuggestionStatus::Approved => 0x43_b581,
+            SuggestionStatus::Denied => 0xf0_4747,
+            SuggestionStatus::Considered => 0xfa_a61a,
+        }
+    }
+}
+
+impl fmt::Display for SuggestionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_open_boxes_have_a_target() {
+        let mut config: SuggestionConfig = serde_json::from_str(r#"{"channel": 5, "downvote": ""}"#).unwrap();
+        assert!(config.target().is_none());
+
+        config.enabled = true;
+        assert_eq!(config.target(), Some(ChannelId(5)));
+        assert_eq!(config.vote_reactions().len(), 1);
+    }
+
+    #[test]
+    fn statuses_survive_storage() {
+        for status in &[
+            SuggestionStatus::Open,
+            SuggestionStatus::Approved,
+            SuggestionStatus::Denied,
+            SuggestionStatus::Considered,
+        ] {
+            assert_eq!(SuggestionStatus::from_str(status.as_str()), Some(*status));
+        }
+        assert!(SuggestionStatus::from_str("maybe").is_none());
+    }
+}
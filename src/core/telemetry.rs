@@ -0,0 +1,54 @@
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::bot_config::BotConfig;
+use crate::database::QueryMetrics;
+use crate::error::StartupError;
+use crate::gearbot_error;
+
+/// Installs the subscriber that times the database queries for the metrics, and exports the tracing spans of event
+/// handling, commands and database queries over OTLP when configured.
+///
+/// Without an exporter the other spans are still created but go nowhere, so they cost next to nothing.
+pub fn initialize(config: &BotConfig, cluster_id: u64, query_metrics: QueryMetrics) -> Result<(), StartupError> {
+    let subscriber = tracing_subscriber::registry().with(query_metrics);
+    let config = match &config.tracing {
+        Some(config) => config,
+        None => {
+            return tracing::subscriber::set_global_default(subscriber).map_err(|e| {
+                gearbot_error!("Failed to install the query metrics: {}", e);
+                StartupError::InvalidConfig
+            })
+        }
+    };
+
+    let resource = Resource::new(vec![
+        KeyValue::new("service.name", config.service_name.clone()),
+        KeyValue::new("service.instance.id", format!("cluster-{}", cluster_id)),
+    ]);
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.endpoint),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(trace::Sampler::TraceIdRatioBased(config.sample_ratio))
+                .with_resource(resource),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|e| {
+            gearbot_error!("Failed to set up the tracing exporter: {}", e);
+            StartupError::InvalidConfig
+        })?;
+
+    let subscriber = subscriber.with(tracing_opentelemetry::layer().with_tracer(tracer));
+    tracing::subscriber::set_global_default(subscriber).map_err(|e| {
+        gearbot_error!("Failed to install the tracing exporter: {}", e);
+        StartupError::InvalidConfig
+    })
+}
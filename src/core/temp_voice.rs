@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::guild::Permissions;
+use twilight_model::id::ChannelId;
+
+/// Discord doesn't allow longer channel names.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// "Join to create" voice channels: joining a hub gets you a room of your own, that goes away once it's empty.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TempVoiceConfig {
+    /// Voice channels that create a room when joined, rooms end up in the same category as their hub.
+    pub hubs: Vec<ChannelId>,
+    /// ``{user}`` is replaced with the name of the member the room is for.
+    pub name: String,
+    /// 0 is unlimited.
+    pub user_limit: u64,
+    /// What the member the room is for is allowed to do in it, on top of what they can do anyway.
+    pub owner_permissions: Permissions,
+}
+
+impl Default for TempVoiceConfig {
+    fn default() -> Self {
+        TempVoiceConfig {
+            hubs: vec![],
+            name: String::from("{user}'s room"),
+            user_limit: 0,
+            owner_permissions: Permissions::MANAGE_CHANNELS | Permissions::MOVE_MEMBERS | Permissions::MUTE_MEMBERS,
+        }
+    }
+}
+
+impl TempVoiceConfig {
+    pub fn room_name(&self, user: &str) -> String {
+        let name = self.name.replace("{user}", user);
+        match name.char_indices().nth(MAX_NAME_LENGTH) {
+            Some((end, _)) => name[..end].to_string(),
+            None => name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn room_names_fit_discord_limits() {
+        let config = TempVoiceConfig::default();
+
+        assert_eq!(config.room_name("Someone"), "Someone's room");
+        assert_eq!(config.room_name(&"é".repeat(120)).chars().count(), MAX_NAME_LENGTH);
+    }
+}
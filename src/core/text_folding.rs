@@ -0,0 +1,96 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Folds text into the plain letters it looks like, so automod patterns can't be dodged with lookalikes.
+///
+/// Compatibility forms like fullwidth or mathematical letters are decomposed (NFKD), the accents and other combining
+/// marks that come off are dropped, invisible characters are stripped and letters from other scripts that look like
+/// latin ones are replaced by them. Case is kept, patterns decide for themselves if that matters.
+pub fn fold(content: &str) -> String {
+    content
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c) && !is_invisible(*c))
+        .map(fold_confusable)
+        .collect()
+}
+
+/// Characters that don't show up at all, but do break up words.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{061C}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{3164}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+    )
+}
+
+/// Cyrillic and Greek letters that look the same as a latin one, decomposing takes care of the rest.
+fn fold_confusable(c: char) -> char {
+    match c {
+        'а' | 'α' | 'ɑ' => 'a',
+        'ԁ' => 'd',
+        'е' | 'ε' => 'e',
+        'ɡ' => 'g',
+        'һ' => 'h',
+        'і' | 'ι' | 'ı' => 'i',
+        'ј' => 'j',
+        'κ' => 'k',
+        'ӏ' => 'l',
+        'ո' => 'n',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ԛ' => 'q',
+        'с' => 'c',
+        'ѕ' => 's',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ԝ' => 'w',
+        'х' | 'χ' => 'x',
+        'у' => 'y',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'С' => 'C',
+        'Е' | 'Ε' => 'E',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' | 'Ӏ' => 'I',
+        'Ј' => 'J',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'Х' | 'Χ' => 'X',
+        'У' | 'Υ' | 'Ү' => 'Y',
+        'Ζ' => 'Z',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_lookalikes() {
+        assert_eq!(fold("plain text"), "plain text");
+        // Fullwidth and mathematical bold
+        assert_eq!(fold("ｂａｄ 𝐛𝐚𝐝"), "bad bad");
+        // Cyrillic а and о, Greek Ο
+        assert_eq!(fold("bаd wоrd ΟK"), "bad word OK");
+        assert_eq!(fold("b\u{200B}a\u{200D}d\u{FEFF}"), "bad");
+        assert_eq!(fold("b̷a̷d̷ café"), "bad cafe");
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType};
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+
+/// What everyone that belongs in a ticket can do there.
+const TICKET_PERMISSIONS: Permissions = Permissions::from_bits_truncate(
+    Permissions::VIEW_CHANNEL.bits()
+        | Permissions::SEND_MESSAGES.bits()
+        | Permissions::READ_MESSAGE_HISTORY.bits()
+        | Permissions::ATTACH_FILES.bits()
+        | Permissions::EMBED_LINKS.bits(),
+);
+
+/// Private channels members open to talk to staff.
+///
+/// Opening one from a button needs message components, which twilight 0.4 doesn't have (see the handlers), so the
+/// panel works with a reaction until then.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TicketConfig {
+    pub enabled: bool,
+    /// Ticket channels are created in this category, at the top of the channel list without one.
+    pub category: Option<ChannelId>,
+    /// Can see, claim and close every ticket.
+    pub staff_roles: Vec<RoleId>,
+    /// How many tickets a member can have open at the same time, 0 for no limit.
+    pub max_open: u32,
+    /// Posted in every new ticket, ``{user}`` is replaced with a mention of who opened it.
+    pub welcome: String,
+    /// The message members can react to to open a ticket, posted with ``ticket panel``.
+    pub panel: Option<MessageId>,
+    /// Store a transcript in the archive when a ticket gets closed.
+    pub transcripts: bool,
+}
+
+impl Default for TicketConfig {
+    fn default() -> Self {
+        TicketConfig {
+            enabled: false,
+            category: None,
+            staff_roles: vec![],
+            max_open: 1,
+            welcome: String::from("{user} thanks for reaching out, staff will be with you shortly."),
+            panel: None,
+            transcripts: true,
+        }
+    }
+}
+
+impl TicketConfig {
+    pub fn welcome_message(&self, user_id: UserId) -> String {
+        self.welcome.replace("{user}", &format!("<@{}>", user_id))
+    }
+
+    pub fn is_staff(&self, roles: &[RoleId]) -> bool {
+        roles.iter().any(|role| self.staff_roles.contains(role))
+    }
+}
+
+/// What came of trying to open a ticket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TicketOutcome {
+    Opened(ChannelId),
+    /// The member already has as many open as they can, this is one of them.
+    AtLimit(ChannelId),
+    Disabled,
+    /// The channel couldn't be created.
+    Failed,
+}
+
+pub fn channel_name(id: i32) -> String {
+    format!("ticket-{:04}", id)
+}
+
+/// Hides the ticket from everyone but the member who opened it, the staff and ourselves.
+pub fn overwrites(guild_id: GuildId, owner: UserId, bot: UserId, staff_roles: &[RoleId]) -> Vec<PermissionOverwrite> {
+    let mut overwrites = vec![PermissionOverwrite {
+        allow: Permissions::empty(),
+        deny: Permissions::VIEW_CHANNEL,
+        // The everyone role has the same id as the guild
+        kind: PermissionOverwriteType::Role(RoleId(guild_id.0)),
+    }];
+    for user in &[owner, bot] {
+        overwrites.push(PermissionOverwrite {
+            allow: TICKET_PERMISSIONS,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(*user),
+        });
+    }
+    for role in staff_roles {
+        overwrites.push(PermissionOverwrite {
+            allow: TICKET_PERMISSIONS,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Role(*role),
+        });
+    }
+    overwrites
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_members_of_the_ticket_can_see_it() {
+        let overwrites = overwrites(GuildId(1), UserId(2), UserId(3), &[RoleId(4)]);
+
+        assert_eq!(overwrites.len(), 4);
+        assert_eq!(overwrites[0].kind, PermissionOverwriteType::Role(RoleId(1)));
+        assert!(overwrites[0].deny.contains(Permissions::VIEW_CHANNEL));
+        assert!(overwrites[1..].iter().all(|overwrite| overwrite
+            .allow
+            .contains(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES)));
+    }
+
+    #[test]
+    fn names_and_welcomes_tickets() {
+        let config = TicketConfig {
+            staff_roles: vec![RoleId(5)],
+            ..TicketConfig::default()
+        };
+
+        assert_eq!(channel_name(7), "ticket-0007");
+        assert!(config.welcome_message(UserId(2)).starts_with("<@2> "));
+        assert!(config.is_staff(&[RoleId(6), RoleId(5)]));
+        assert!(!config.is_staff(&[RoleId(6)]));
+    }
+}
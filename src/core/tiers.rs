@@ -0,0 +1,85 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// What a guild is entitled to, higher tiers raise the limits of some features.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Free,
+    Premium,
+}
+
+/// The limits that come with a tier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TierLimits {
+    pub custom_commands: usize,
+    /// How long transcripts of bulk deletes are kept.
+    pub archive_days: u32,
+    pub custom_log_formats: bool,
+}
+
+impl Tier {
+    pub const ALL: [Tier; 2] = [Tier::Free, Tier::Premium];
+
+    pub fn limits(self) -> TierLimits {
+        match self {
+            Tier::Free => TierLimits {
+                custom_commands: 25,
+                archive_days: 30,
+                custom_log_formats: false,
+            },
+            Tier::Premium => TierLimits {
+                custom_commands: 250,
+                archive_days: 365,
+                custom_log_formats: true,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::Free => "free",
+            Tier::Premium => "premium",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Tier::ALL
+            .iter()
+            .copied()
+            .find(|tier| tier.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Tiers are stored by their number, unknown ones (like one that got removed) fall back to free.
+    pub fn from_id(id: i16) -> Self {
+        match id {
+            1 => Tier::Premium,
+            _ => Tier::Free,
+        }
+    }
+
+    pub fn id(self) -> i16 {
+        self as i16
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiers_roundtrip_and_order() {
+        for tier in Tier::ALL.iter().copied() {
+            assert_eq!(Tier::from_id(tier.id()), tier);
+            assert_eq!(Tier::from_name(tier.name()), Some(tier));
+        }
+        assert!(Tier::Premium > Tier::Free);
+        assert_eq!(Tier::from_id(42), Tier::Free);
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use twilight_model::id::RoleId;
+
+use super::bot_config::ToxicityApi;
+use super::text_folding;
+use crate::error::ClassifierError;
+
+pub type ScoreResult<'a> = Pin<Box<dyn Future<Output = Result<Vec<AttributeScore>, ClassifierError>> + Send + 'a>>;
+
+/// Something that can tell how toxic a message is.
+pub trait MessageClassifier: Send + Sync {
+    /// Scores the content for every attribute the classifier knows about, like ``toxicity`` or ``insult``.
+    fn score<'a>(&'a self, content: &'a str) -> ScoreResult<'a>;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AttributeScore {
+    /// Lowercase, like ``toxicity`` or ``threat``.
+    pub attribute: String,
+    /// How likely the content is to be seen as this, from 0 to 1.
+    pub score: f32,
+}
+
+/// Talks to a Perspective style API over HTTP, see [`ToxicityApi`] for what it expects.
+pub struct HttpMessageClassifier {
+    client: reqwest::Client,
+    endpoint: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ScoreResponse {
+    scores: BTreeMap<String, f32>,
+}
+
+impl HttpMessageClassifier {
+    pub fn new(config: &ToxicityApi) -> Result<Self, ClassifierError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+
+        Ok(HttpMessageClassifier {
+            client,
+            endpoint: config.endpoint.clone(),
+            token: config.token.clone(),
+        })
+    }
+}
+
+impl MessageClassifier for HttpMessageClassifier {
+    fn score<'a>(&'a self, content: &'a str) -> ScoreResult<'a> {
+        Box::pin(async move {
+            let body = serde_json::json!({ "text": content }).to_string();
+            let mut request = self
+                .client
+                .post(&self.endpoint)
+                .header("Content-Type", "application/json")
+                .body(body);
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await?.error_for_status()?.bytes().await?;
+            let response: ScoreResponse =
+                serde_json::from_slice(&response).map_err(ClassifierError::InvalidResponse)?;
+
+            Ok(response
+                .scores
+                .into_iter()
+                .map(|(attribute, score)| AttributeScore {
+                    attribute: attribute.to_lowercase(),
+                    score,
+                })
+                .collect())
+        })
+    }
+}
+
+/// Scores messages locally from lists of words, for when there is no API or it can't be reached.
+///
+/// An attribute scores 1 if any of its words is in the message and 0 otherwise, there is no in between.
+pub struct KeywordClassifier {
+    keywords: Vec<(String, Vec<String>)>,
+}
+
+impl KeywordClassifier {
+    pub fn new(keywords: &BTreeMap<String, Vec<String>>) -> Self {
+        let keywords = keywords
+            .iter()
+            .map(|(attribute, words)| {
+                let words = words
+                    .iter()
+                    .map(|word| text_folding::fold(word).to_lowercase())
+                    .filter(|word| !word.trim().is_empty())
+                    .collect();
+                (attribute.to_lowercase(), words)
+            })
+            .collect();
+
+        KeywordClassifier { keywords }
+    }
+
+    fn score_now(&self, content: &str) -> Vec<AttributeScore> {
+        let content = text_folding::fold(content).to_lowercase();
+        let words = content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+
+        self.keywords
+            .iter()
+            .filter(|(_, keywords)| !keywords.is_empty())
+            .map(|(attribute, keywords)| {
+                let found = keywords.iter().any(|keyword| contains_phrase(&words, keyword));
+                AttributeScore {
+                    attribute: attribute.clone(),
+                    score: if found { 1.0 } else { 0.0 },
+                }
+            })
+            .collect()
+    }
+}
+
+impl MessageClassifier for KeywordClassifier {
+    fn score<'a>(&'a self, content: &'a str) -> ScoreResult<'a> {
+        let scores = self.score_now(content);
+        Box::pin(async move { Ok(scores) })
+    }
+}
+
+/// If the words of the phrase show up one after the other, so keywords don't match in the middle of other words.
+fn contains_phrase(words: &[&str], phrase: &str) -> bool {
+    let phrase = phrase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    !phrase.is_empty() && words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+}
+
+/// How much heat toxic messages add, per attribute. Needs the automod feature and heat to be enabled.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ToxicityConfig {
+    /// Heat for a message with a score of 1 for the attribute, lower scores add less.
+    pub weights: BTreeMap<String, f32>,
+    /// Scores below this don't add any heat.
+    pub min_score: f32,
+    /// Members with any of these roles are never scored.
+    pub exempt_roles: Vec<RoleId>,
+    /// Words per attribute, used instead of the API when the bot doesn't have one configured.
+    pub keywords: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for ToxicityConfig {
+    fn default() -> Self {
+        ToxicityConfig {
+            weights: BTreeMap::new(),
+            min_score: 0.7,
+            exempt_roles: vec![],
+            keywords: BTreeMap::new(),
+        }
+    }
+}
+
+impl ToxicityConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.weights.values().any(|weight| *weight > 0.0)
+    }
+
+    /// The heat the scores add up to, with the scores that counted towards it.
+    pub fn heat_for<'a>(&self, scores: &'a [AttributeScore]) -> (u32, Vec<&'a AttributeScore>) {
+        let mut heat = 0.0;
+        let mut counted = vec![];
+        for score in scores.iter().filter(|score| score.score >= self.min_score) {
+            let weight = self
+                .weights
+                .iter()
+                .find(|(attribute, _)| attribute.eq_ignore_ascii_case(&score.attribute))
+                .map_or(0.0, |(_, weight)| weight.max(0.0));
+            if weight > 0.0 {
+                heat += weight * score.score.min(1.0);
+                counted.push(score);
+            }
+        }
+
+        (heat.round() as u32, counted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(attribute: &str, score: f32) -> AttributeScore {
+        AttributeScore {
+            attribute: String::from(attribute),
+            score,
+        }
+    }
+
+    #[test]
+    fn weighs_scores_into_heat() {
+        let config: ToxicityConfig =
+            serde_json::from_str(r#"{"weights": {"toxicity": 10, "threat": 20, "profanity": 0}}"#).unwrap();
+        assert!(config.is_enabled());
+
+        let scores = vec![
+            score("toxicity", 0.9),
+            score("THREAT", 0.5),
+            score("profanity", 1.0),
+            score("insult", 1.0),
+        ];
+        let (heat, counted) = config.heat_for(&scores);
+        assert_eq!(heat, 9);
+        assert_eq!(counted, vec![&scores[0]]);
+
+        assert_eq!(config.heat_for(&[score("toxicity", 1.0), score("threat", 0.75)]).0, 25);
+        assert_eq!(config.heat_for(&[]).0, 0);
+        assert!(!ToxicityConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn matches_whole_keywords() {
+        let mut keywords = BTreeMap::new();
+        keywords.insert(
+            String::from("Insult"),
+            vec![String::from("idiot"), String::from("go away")],
+        );
+        keywords.insert(String::from("threat"), vec![String::from("  ")]);
+        let classifier = KeywordClassifier::new(&keywords);
+
+        assert_eq!(classifier.score_now("what an IDIOT!"), vec![score("insult", 1.0)]);
+        assert_eq!(classifier.score_now("what an іdіot"), vec![score("insult", 1.0)]);
+        assert_eq!(classifier.score_now("please go, away"), vec![score("insult", 1.0)]);
+        assert_eq!(classifier.score_now("idiotic"), vec![score("insult", 0.0)]);
+        assert_eq!(classifier.score_now("go somewhere away"), vec![score("insult", 0.0)]);
+    }
+}
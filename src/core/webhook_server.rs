@@ -0,0 +1,167 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+
+use crate::core::webhooks::MAX_PAYLOAD_SIZE;
+use crate::core::{BotContext, WebhookServer};
+use crate::error::DatabaseError;
+use crate::{gearbot_error, gearbot_info};
+
+/// GitHub deliveries bigger than this are refused before reading them, GitHub caps its payloads at 25MB but those
+/// are pushes with thousands of commits that wouldn't fit in a message anyways.
+const MAX_BODY_SIZE: u64 = 5 * 1024 * 1024;
+
+const GITHUB_PATH: &str = "/webhooks/github";
+/// Followed by the token of the endpoint.
+pub const CUSTOM_PATH: &str = "/webhooks/custom/";
+
+/// What became of a delivery, decides the status code the sender gets back.
+#[derive(Debug, PartialEq)]
+pub enum Delivery {
+    /// Passed on to at least one channel.
+    Relayed,
+    /// Valid, but nobody wants to see it.
+    Ignored,
+    /// Not signed (or authenticated) for any of the guilds it would go to.
+    Unauthorized,
+    /// Not something we can read.
+    Malformed,
+    /// The endpoint got too many deliveries recently.
+    RateLimited,
+}
+
+impl Delivery {
+    fn status(&self) -> StatusCode {
+        match self {
+            Delivery::Relayed => StatusCode::OK,
+            Delivery::Ignored => StatusCode::ACCEPTED,
+            Delivery::Unauthorized => StatusCode::UNAUTHORIZED,
+            Delivery::Malformed => StatusCode::BAD_REQUEST,
+            Delivery::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+/// Serves the endpoints other services send their webhooks to, until it fails.
+pub async fn run(config: WebhookServer, context: Arc<BotContext>) {
+    let addr = config.bind;
+    let webhook_service = make_service_fn(move |_| {
+        let context = context.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let context = context.clone();
+                async move { Ok::<_, Infallible>(handle_request(req, context).await) }
+            }))
+        }
+    });
+
+    let server = match hyper::server::Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(webhook_service),
+        Err(e) => {
+            gearbot_error!("Failed to bind the webhook server to {}: {}", addr, e);
+            return;
+        }
+    };
+    gearbot_info!("Receiving webhooks on {}", addr);
+    if let Err(e) = server.await {
+        gearbot_error!("The webhook server failed: {}", e)
+    }
+}
+
+async fn handle_request(req: Request<Body>, context: Arc<BotContext>) -> Response<Body> {
+    let path = req.uri().path().to_string();
+    let token = path.strip_prefix(CUSTOM_PATH).map(String::from);
+    if path != GITHUB_PATH && token.is_none() {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+    if req.method() != Method::POST {
+        return status_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    match token {
+        Some(token) => receive_custom(req, token, context).await,
+        None => receive_github(req, context).await,
+    }
+}
+
+async fn receive_github(req: Request<Body>, context: Arc<BotContext>) -> Response<Body> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let event = header("X-GitHub-Event");
+    let signature = header("X-Hub-Signature-256");
+    let (event, signature) = match (event, signature) {
+        (Some(event), Some(signature)) => (event, signature),
+        (None, _) => return status_response(StatusCode::BAD_REQUEST),
+        (_, None) => return status_response(StatusCode::UNAUTHORIZED),
+    };
+
+    let body = match read_body(req, MAX_BODY_SIZE).await {
+        Ok(body) => body,
+        Err(status) => return status_response(status),
+    };
+    handle_delivery("GitHub", async move {
+        context.receive_github_webhook(&event, &signature, &body).await
+    })
+    .await
+}
+
+async fn receive_custom(req: Request<Body>, token: String, context: Arc<BotContext>) -> Response<Body> {
+    let body = match read_body(req, MAX_PAYLOAD_SIZE).await {
+        Ok(body) => body,
+        Err(status) => return status_response(status),
+    };
+    handle_delivery("custom webhook", async move {
+        context.receive_custom_webhook(&token, &body).await
+    })
+    .await
+}
+
+async fn handle_delivery(
+    kind: &str,
+    handler: impl Future<Output = Result<Delivery, DatabaseError>> + Send + 'static,
+) -> Response<Body> {
+    // Spawned so the relaying finishes even if the sender stops waiting for the answer
+    match tokio::spawn(handler).await {
+        Ok(Ok(delivery)) => status_response(delivery.status()),
+        Ok(Err(e)) => {
+            gearbot_error!("Failed to handle a {} delivery: {}", kind, e);
+            status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => {
+            gearbot_error!("Handling a {} delivery panicked: {}", kind, e);
+            status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Reads the whole body, as long as it said how big it is upfront and that's within the limit.
+async fn read_body(req: Request<Body>, limit: u64) -> Result<Vec<u8>, StatusCode> {
+    let length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or(StatusCode::LENGTH_REQUIRED)?;
+    if length > limit {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    // Hyper refuses to read past the content length, so this can't grow beyond what was checked above
+    hyper::body::to_bytes(req.into_body())
+        .await
+        .map(|body| body.to_vec())
+        .map_err(|_| StatusCode::BAD_REQUEST)
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,88 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::utils::embeds;
+
+/// How many endpoints a single guild can have.
+pub const MAX_ENDPOINTS: usize = 10;
+pub const TEMPLATE_LIMIT: usize = 1000;
+/// Posts the `content` of the payload, so anything that can talk to a discord webhook works out of the box.
+pub const DEFAULT_TEMPLATE: &str = "{content}";
+/// Payloads bigger than this are refused, templates only ever show a small part of them.
+pub const MAX_PAYLOAD_SIZE: u64 = 64 * 1024;
+/// Deliveries an endpoint takes per window, the rest is refused until the window is over.
+pub const RATE_LIMIT: isize = 30;
+pub const RATE_LIMIT_WINDOW_SECONDS: u32 = 60;
+/// Messages can't be longer than this.
+const MESSAGE_LIMIT: usize = 2000;
+
+lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{([A-Za-z0-9_.-]+)\}").unwrap();
+}
+
+/// Tokens are only stored hashed, the hash is what deliveries are looked up by.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Fills in the `{placeholders}` of the template with the values at those paths in the payload, like
+/// `{build.status}` or `{commits.0.message}`. Paths that aren't in the payload are left empty, and so is the result
+/// when nothing is left.
+pub fn render(template: &str, payload: &Value) -> String {
+    // Replaced in one go, so anything in the payload that looks like a placeholder stays as it is
+    let rendered = PLACEHOLDER.replace_all(template, |captures: &Captures| {
+        let path = &captures[1];
+        match lookup(payload, path) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        }
+    });
+
+    match rendered.trim() {
+        "" => String::new(),
+        rendered => embeds::truncate(rendered, MESSAGE_LIMIT),
+    }
+}
+
+fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(payload, |value, part| match value {
+        Value::Object(map) => map.get(part),
+        Value::Array(values) => part.parse::<usize>().ok().and_then(|index| values.get(index)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_paths_in_the_payload() {
+        let payload = serde_json::json!({
+            "content": "Deployed",
+            "build": {"number": 42, "passed": true, "tags": ["stable", "{content}"]},
+            "nothing": null
+        });
+
+        assert_eq!(render(DEFAULT_TEMPLATE, &payload), "Deployed");
+        assert_eq!(
+            render("Build #{build.number} passed: {build.passed}", &payload),
+            "Build #42 passed: true"
+        );
+        assert_eq!(render("{build.tags.0} {build.tags.1}", &payload), "stable {content}");
+        assert_eq!(render("[{nothing}{missing}{build.tags.5}]", &payload), "[]");
+        assert_eq!(render(" {missing} ", &payload), "");
+        assert_eq!(render("{build.tags}", &payload), r#"["stable","{content}"]"#);
+    }
+
+    #[test]
+    fn hashes_tokens() {
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
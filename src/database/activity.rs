@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+use crate::utils;
+
+impl DataStorage {
+    /// Marks a user as seen in a voice channel of the guild just now.
+    #[instrument(skip_all, err)]
+    pub async fn record_voice_activity(&self, guild_id: GuildId, user_id: UserId) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO voiceactivity (guild_id, user_id, last_seen) VALUES ($1, $2, now())
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET last_seen = excluded.last_seen",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Everyone that sent a logged message or was in voice in the guild over the last `days` days.
+    #[instrument(skip_all, err)]
+    pub async fn get_active_users(&self, guild_id: GuildId, days: u32) -> Result<HashSet<UserId>, DatabaseError> {
+        // Messages don't have a timestamp of their own, but their id does
+        let first_message = utils::snowflake_at(Utc::now() - Duration::days(days as i64));
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            "SELECT DISTINCT author_id FROM message WHERE guild_id = $1 AND id >= $2
+            UNION
+            SELECT user_id FROM voiceactivity WHERE guild_id = $1 AND last_seen >= now() - make_interval(days => $3)",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(first_message as i64)
+        .bind(days as i32)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| UserId(row.0 as u64)).collect())
+    }
+}
@@ -0,0 +1,218 @@
+use std::io::{Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{crypto, DataStorage};
+use crate::core::GuildConfig;
+use crate::error::{BackupError, DatabaseError};
+
+/// Everything that goes into a backup file.
+///
+/// Configs are kept as raw json so a backup made by an older version can still be restored after the
+/// config got new fields.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupSnapshot {
+    /// Unix timestamp of when the snapshot was taken.
+    pub created_at: i64,
+    pub configs: Vec<BackedUpConfig>,
+    /// Only present when infractions were included.
+    pub infractions: Option<Vec<BackedUpInfraction>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BackedUpConfig {
+    pub id: i64,
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BackedUpInfraction {
+    pub id: i32,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub mod_id: i64,
+    pub kind: String,
+    /// Unix timestamps.
+    pub start: i64,
+    pub end: Option<i64>,
+}
+
+impl DataStorage {
+    /// Collects all guild configs, and optionally all infractions, into a snapshot.
+    #[instrument(skip_all, err)]
+    pub async fn create_backup_snapshot(&self, include_infractions: bool) -> Result<BackupSnapshot, DatabaseError> {
+        let configs = sqlx::query_as("SELECT id, config FROM guildconfig ORDER BY id")
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        let infractions = if include_infractions {
+            let infractions = sqlx::query_as(
+                "SELECT id, guild_id, user_id, mod_id, type::text AS kind,
+                extract(epoch FROM start)::bigint AS start, extract(epoch FROM \"end\")::bigint AS \"end\"
+                FROM history ORDER BY id",
+            )
+            .fetch_all(&self.persistent_pool)
+            .await?;
+            Some(infractions)
+        } else {
+            None
+        };
+
+        let (created_at,): (i64,) = sqlx::query_as("SELECT extract(epoch FROM now())::bigint")
+            .fetch_one(&self.persistent_pool)
+            .await?;
+
+        Ok(BackupSnapshot {
+            created_at,
+            configs,
+            infractions,
+        })
+    }
+
+    /// Restores the configs of a snapshot, and its infractions if asked to, returning how many of each were restored.
+    ///
+    /// Configs overwrite the current ones, guilds that don't have a config anymore get a new encryption key.
+    /// Infractions that still exist are left alone. Every config is checked before anything gets written.
+    #[instrument(skip_all, err)]
+    pub async fn restore_backup_snapshot(
+        &self,
+        snapshot: &BackupSnapshot,
+        include_infractions: bool,
+    ) -> Result<(usize, usize), DatabaseError> {
+        for backed_up in &snapshot.configs {
+            serde_json::from_value::<GuildConfig>(backed_up.config.clone()).map_err(DatabaseError::Deserializing)?;
+        }
+
+        let mut transaction = self.persistent_pool.begin().await?;
+
+        for backed_up in &snapshot.configs {
+            let guild_encryption_key =
+                crypto::generate_guild_encryption_key(&self.primary_encryption_key, backed_up.id as u64);
+            sqlx::query(
+                "INSERT INTO guildconfig (id, config, encryption_key) VALUES ($1, $2, $3)
+                ON CONFLICT (id) DO UPDATE SET config = excluded.config",
+            )
+            .bind(backed_up.id)
+            .bind(&backed_up.config)
+            .bind(guild_encryption_key)
+            .execute(&mut transaction)
+            .await?;
+        }
+
+        let mut restored_infractions = 0;
+        if let (true, Some(infractions)) = (include_infractions, &snapshot.infractions) {
+            for infraction in infractions {
+                let result = sqlx::query(
+                    "INSERT INTO history (id, guild_id, user_id, mod_id, type, start, \"end\")
+                    VALUES ($1, $2, $3, $4, $5::historyType, to_timestamp($6), to_timestamp($7))
+                    ON CONFLICT (id) DO NOTHING",
+                )
+                .bind(infraction.id)
+                .bind(infraction.guild_id)
+                .bind(infraction.user_id)
+                .bind(infraction.mod_id)
+                .bind(&infraction.kind)
+                .bind(infraction.start as f64)
+                .bind(infraction.end.map(|end| end as f64))
+                .execute(&mut transaction)
+                .await?;
+                restored_infractions += result.rows_affected() as usize;
+            }
+
+            // Make sure new infractions don't collide with the restored ids
+            sqlx::query("SELECT setval(pg_get_serial_sequence('history', 'id'), coalesce(max(id), 1)) FROM history")
+                .execute(&mut transaction)
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok((snapshot.configs.len(), restored_infractions))
+    }
+
+    /// Turns a snapshot into the compressed and encrypted contents of a backup file.
+    pub fn encode_backup(&self, snapshot: &BackupSnapshot) -> Result<Vec<u8>, BackupError> {
+        encode_snapshot(snapshot, &self.primary_encryption_key)
+    }
+
+    /// Reads a snapshot back out of the contents of a backup file.
+    pub fn decode_backup(&self, bytes: &[u8]) -> Result<BackupSnapshot, BackupError> {
+        decode_snapshot(bytes, &self.primary_encryption_key)
+    }
+}
+
+fn encode_snapshot(snapshot: &BackupSnapshot, key: &crypto::EncryptionKey) -> Result<Vec<u8>, BackupError> {
+    let json = serde_json::to_vec(snapshot).map_err(BackupError::Serializing)?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json)?;
+    let compressed = encoder.finish()?;
+
+    Ok(crypto::encrypt_backup_bytes(&compressed, key))
+}
+
+fn decode_snapshot(bytes: &[u8], key: &crypto::EncryptionKey) -> Result<BackupSnapshot, BackupError> {
+    let compressed = crypto::decrypt_backup_bytes(bytes, key).ok_or(BackupError::Corrupted)?;
+
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|_| BackupError::Corrupted)?;
+
+    serde_json::from_slice(&json).map_err(BackupError::Serializing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> BackupSnapshot {
+        BackupSnapshot {
+            created_at: 1_600_000_000,
+            configs: vec![BackedUpConfig {
+                id: 365498559174410241,
+                config: serde_json::json!({"prefix": "!"}),
+            }],
+            infractions: Some(vec![BackedUpInfraction {
+                id: 1,
+                guild_id: 365498559174410241,
+                user_id: 106354106196570112,
+                mod_id: 106354106196570112,
+                kind: "warning".to_string(),
+                start: 1_600_000_000,
+                end: None,
+            }]),
+        }
+    }
+
+    #[test]
+    fn backups_round_trip() {
+        let key = crypto::EncryptionKey::construct_owned(&[7; 32]);
+        let encoded = encode_snapshot(&snapshot(), &key).unwrap();
+        let decoded = decode_snapshot(&encoded, &key).unwrap();
+
+        assert_eq!(decoded.created_at, 1_600_000_000);
+        assert_eq!(decoded.configs[0].config, serde_json::json!({"prefix": "!"}));
+        assert_eq!(decoded.infractions.unwrap()[0].kind, "warning");
+    }
+
+    #[test]
+    fn backups_need_the_same_key() {
+        let key = crypto::EncryptionKey::construct_owned(&[7; 32]);
+        let other_key = crypto::EncryptionKey::construct_owned(&[8; 32]);
+        let encoded = encode_snapshot(&snapshot(), &key).unwrap();
+
+        assert!(matches!(
+            decode_snapshot(&encoded, &other_key),
+            Err(BackupError::Corrupted)
+        ));
+        assert!(matches!(
+            decode_snapshot(&encoded[..5], &key),
+            Err(BackupError::Corrupted)
+        ));
+    }
+}
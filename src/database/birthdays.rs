@@ -0,0 +1,109 @@
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::structures::StoredBirthday;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Registers the birthday of a user in a guild, replacing the one they had.
+    ///
+    /// When it was last announced is kept, so moving it around doesn't get it announced multiple times a year.
+    #[instrument(skip_all, err)]
+    pub async fn set_birthday(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        month: u32,
+        day: u32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO birthday (guild_id, user_id, month, day) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET month = excluded.month, day = excluded.day",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .bind(month as i16)
+        .bind(day as i16)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the month and day of the birthday of the user, if they registered one.
+    #[instrument(skip_all, err)]
+    pub async fn get_birthday(&self, guild_id: GuildId, user_id: UserId) -> Result<Option<(u32, u32)>, DatabaseError> {
+        let row: Option<(i16, i16)> =
+            sqlx::query_as("SELECT month, day FROM birthday WHERE guild_id = $1 AND user_id = $2")
+                .bind(guild_id.0 as i64)
+                .bind(user_id.0 as i64)
+                .fetch_optional(&self.persistent_pool)
+                .await?;
+
+        Ok(row.map(|(month, day)| (month as u32, day as u32)))
+    }
+
+    /// Returns if there was a birthday to remove.
+    #[instrument(skip_all, err)]
+    pub async fn remove_birthday(&self, guild_id: GuildId, user_id: UserId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM birthday WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fetches all guilds where anyone registered a birthday.
+    #[instrument(skip_all, err)]
+    pub async fn get_birthday_guilds(&self) -> Result<Vec<GuildId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT DISTINCT guild_id FROM birthday")
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| GuildId(row.0 as u64)).collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_birthdays(&self, guild_id: GuildId) -> Result<Vec<StoredBirthday>, DatabaseError> {
+        let birthdays = sqlx::query_as("SELECT * FROM birthday WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(birthdays)
+    }
+
+    /// Remembers the birthday was announced this year, and if the role was handed out for it.
+    #[instrument(skip_all, err)]
+    pub async fn mark_birthday_announced(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        year: i32,
+        role_given: bool,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE birthday SET announced_year = $3, role_given = $4 WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .bind(year)
+            .bind(role_given)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn mark_birthday_role_removed(&self, guild_id: GuildId, user_id: UserId) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE birthday SET role_given = false WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use tracing::instrument;
+use twilight_model::id::UserId;
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Lists everyone who isn't allowed to use the bot at all.
+    #[instrument(skip_all, err)]
+    pub async fn get_blocked_users(&self) -> Result<Vec<UserId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT user_id FROM blockeduser")
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| UserId(row.0 as u64)).collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn block_user(
+        &self,
+        user_id: UserId,
+        reason: Option<&str>,
+        blocked_by: UserId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO blockeduser (user_id, reason, blocked_by) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE SET reason = excluded.reason, blocked_by = excluded.blocked_by, blocked_at = now()",
+        )
+        .bind(user_id.0 as i64)
+        .bind(reason)
+        .bind(blocked_by.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lifts the block of a user, returning if they were blocked to begin with.
+    #[instrument(skip_all, err)]
+    pub async fn unblock_user(&self, user_id: UserId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM blockeduser WHERE user_id = $1")
+            .bind(user_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
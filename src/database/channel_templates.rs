@@ -0,0 +1,73 @@
+use tracing::instrument;
+use twilight_model::id::GuildId;
+
+use super::DataStorage;
+use crate::core::channel_templates::ChannelTemplate;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Stores a template under its name, replacing any template that already had it.
+    #[instrument(skip_all, err)]
+    pub async fn save_channel_template(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        template: &ChannelTemplate,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO channeltemplate (guild_id, name, template) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, name) DO UPDATE SET template = excluded.template",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(serde_json::to_value(template).map_err(DatabaseError::Serializing)?)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_channel_template(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Result<Option<ChannelTemplate>, DatabaseError> {
+        let row: Option<(serde_json::Value,)> =
+            sqlx::query_as("SELECT template FROM channeltemplate WHERE guild_id = $1 AND name = $2")
+                .bind(guild_id.0 as i64)
+                .bind(name)
+                .fetch_optional(&self.persistent_pool)
+                .await?;
+
+        match row {
+            Some((template,)) => Ok(Some(
+                serde_json::from_value(template).map_err(DatabaseError::Deserializing)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// The names of all templates of a guild, alphabetically.
+    #[instrument(skip_all, err)]
+    pub async fn get_channel_template_names(&self, guild_id: GuildId) -> Result<Vec<String>, DatabaseError> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT name FROM channeltemplate WHERE guild_id = $1 ORDER BY name")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.0).collect())
+    }
+
+    /// Removes a template, returning if there was one.
+    #[instrument(skip_all, err)]
+    pub async fn remove_channel_template(&self, guild_id: GuildId, name: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM channeltemplate WHERE guild_id = $1 AND name = $2")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
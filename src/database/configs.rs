@@ -1,4 +1,5 @@
 use log::info;
+use tracing::instrument;
 
 use super::{crypto, DataStorage};
 use crate::core::GuildConfig;
@@ -8,6 +9,7 @@ impl DataStorage {
     /// Fetches a guild configuration from the database, returning it if it existed.
     ///
     /// The permissions inside the config are guaranteed to be in the correct order.
+    #[instrument(skip_all, err)]
     pub async fn get_guild_config(&self, guild_id: u64) -> Result<Option<GuildConfig>, DatabaseError> {
         let row: Option<(serde_json::Value,)> = sqlx::query_as("SELECT config from guildconfig where id=$1")
             .bind(guild_id as i64)
@@ -27,6 +29,7 @@ impl DataStorage {
     }
 
     /// Creates a new guild configuration for the specified guild and inserts it into the database.
+    #[instrument(skip_all, err)]
     pub async fn create_new_guild_config(&self, guild_id: u64) -> Result<GuildConfig, DatabaseError> {
         info!("No config found for {}, inserting blank one", guild_id);
         let new_config = GuildConfig::default();
@@ -47,6 +50,7 @@ impl DataStorage {
     /// Updates a guild config for the specified guild with the provided new value.
     ///
     /// Errors if the guild doesn't exist already.
+    #[instrument(skip_all, err)]
     pub async fn set_guild_config(&self, guild_id: u64, config: &GuildConfig) -> Result<(), DatabaseError> {
         sqlx::query("UPDATE guildconfig set config=$1 WHERE id=$2")
             .bind(serde_json::to_value(config).map_err(DatabaseError::Serializing)?)
@@ -45,11 +45,7 @@ pub fn encrypt_bytes(plaintext: &[u8], key: &EncryptionKey, msg_id: u64) -> Vec<
     // Since nonce's only never need to be reused, and Discor's snowflakes for messages
     // are unique, we can use the messasge id to construct the nonce with its 64 bits, and then
     // pad the rest with zeros.
-    let mut nonce_bytes = [0u8; 12];
-    let msg_id_bytes = msg_id.to_le_bytes();
-    nonce_bytes[..8].copy_from_slice(&msg_id_bytes);
-    nonce_bytes[8..].copy_from_slice(&[0u8; 4]);
-
+    let nonce_bytes = make_nonce(msg_id, NonceDomain::Message);
     let nonce = GenericArray::from_slice(&nonce_bytes);
 
     aead.encrypt(&nonce, plaintext).expect("Failed to encrypt an object!")
@@ -58,12 +54,72 @@ pub fn encrypt_bytes(plaintext: &[u8], key: &EncryptionKey, msg_id: u64) -> Vec<
 pub fn decrypt_bytes(ciphertext: &[u8], key: &EncryptionKey, msg_id: u64) -> Vec<u8> {
     let aead = Aes256Gcm::new(&key.0);
 
-    let mut nonce_bytes = [0u8; 12];
-    let msg_id_bytes = msg_id.to_le_bytes();
-    nonce_bytes[..8].copy_from_slice(&msg_id_bytes);
-    nonce_bytes[8..].copy_from_slice(&[0u8; 4]);
+    let nonce_bytes = make_nonce(msg_id, NonceDomain::Message);
+    let nonce = GenericArray::from_slice(&nonce_bytes);
+
+    aead.decrypt(&nonce, ciphertext).expect("Failed to decrypt an object!")
+}
+
+/// Encrypts an archived object, such as a bulk delete transcript.
+///
+/// Archives are keyed by the id of a message they contain, so they use their own nonce domain to never
+/// share a nonce with that message under the same guild key.
+pub fn encrypt_archive_bytes(plaintext: &[u8], key: &EncryptionKey, archive_id: u64) -> Vec<u8> {
+    let aead = Aes256Gcm::new(&key.0);
 
+    let nonce_bytes = make_nonce(archive_id, NonceDomain::Archive);
+    let nonce = GenericArray::from_slice(&nonce_bytes);
+
+    aead.encrypt(&nonce, plaintext).expect("Failed to encrypt an object!")
+}
+
+pub fn decrypt_archive_bytes(ciphertext: &[u8], key: &EncryptionKey, archive_id: u64) -> Vec<u8> {
+    let aead = Aes256Gcm::new(&key.0);
+
+    let nonce_bytes = make_nonce(archive_id, NonceDomain::Archive);
     let nonce = GenericArray::from_slice(&nonce_bytes);
 
     aead.decrypt(&nonce, ciphertext).expect("Failed to decrypt an object!")
 }
+
+/// Encrypts a backup snapshot with the main key.
+///
+/// Backups have no id to derive a nonce from, so a random one is generated and stored in front of the ciphertext.
+pub fn encrypt_backup_bytes(plaintext: &[u8], key: &EncryptionKey) -> Vec<u8> {
+    let aead = Aes256Gcm::new(&key.0);
+
+    let mut nonce_bytes = [0u8; 12];
+    thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = GenericArray::from_slice(&nonce_bytes);
+
+    let mut output = nonce_bytes.to_vec();
+    output.extend(aead.encrypt(&nonce, plaintext).expect("Failed to encrypt an object!"));
+    output
+}
+
+/// Decrypts a backup snapshot, returning `None` if it's damaged or was made with a different key.
+pub fn decrypt_backup_bytes(ciphertext: &[u8], key: &EncryptionKey) -> Option<Vec<u8>> {
+    if ciphertext.len() < 12 {
+        return None;
+    }
+
+    let aead = Aes256Gcm::new(&key.0);
+    let (nonce_bytes, ciphertext) = ciphertext.split_at(12);
+    let nonce = GenericArray::from_slice(nonce_bytes);
+
+    aead.decrypt(&nonce, ciphertext).ok()
+}
+
+#[derive(Clone, Copy)]
+enum NonceDomain {
+    Message = 0,
+    Archive = 1,
+}
+
+/// Builds a nonce out of the 64 bits of the id, with the domain in the remaining 32 bits.
+fn make_nonce(id: u64, domain: NonceDomain) -> [u8; 12] {
+    let mut nonce_bytes = [0u8; 12];
+    nonce_bytes[..8].copy_from_slice(&id.to_le_bytes());
+    nonce_bytes[8..].copy_from_slice(&(domain as u32).to_le_bytes());
+    nonce_bytes
+}
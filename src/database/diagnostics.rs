@@ -0,0 +1,64 @@
+use tracing::instrument;
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+/// A read-only query for looking into the state of the database.
+///
+/// Every query returns rows of a label and a number, so they can all be displayed the same way.
+pub struct Diagnostic {
+    pub name: &'static str,
+    pub description: &'static str,
+    query: &'static str,
+}
+
+pub const DIAGNOSTICS: [Diagnostic; 6] = [
+    Diagnostic {
+        name: "table_rows",
+        description: "Estimated amount of rows per table",
+        query: "SELECT relname::text, n_live_tup::bigint FROM pg_stat_user_tables ORDER BY n_live_tup DESC",
+    },
+    Diagnostic {
+        name: "table_sizes",
+        description: "Size on disk per table in bytes, including indexes",
+        query: "SELECT relname::text, pg_total_relation_size(relid)::bigint FROM pg_statio_user_tables ORDER BY 2 DESC",
+    },
+    Diagnostic {
+        name: "message_guilds",
+        description: "Guilds with the most stored messages",
+        query: "SELECT guild_id::text, count(*) FROM message GROUP BY guild_id ORDER BY 2 DESC LIMIT 25",
+    },
+    Diagnostic {
+        name: "pending_purges",
+        description: "Guilds waiting for their data to be removed, with the unix timestamp it happens at",
+        query: "SELECT guild_id::text, extract(epoch from purge_at)::bigint FROM guildpurge ORDER BY purge_at",
+    },
+    Diagnostic {
+        name: "tiers",
+        description: "Guilds on a tier other than free",
+        query: "SELECT guild_id::text, tier::bigint FROM guildtier ORDER BY tier DESC, guild_id",
+    },
+    Diagnostic {
+        name: "connections",
+        description: "Connections to the database by state",
+        query: "SELECT coalesce(state, 'unknown'), count(*) FROM pg_stat_activity
+            WHERE datname = current_database() GROUP BY 1 ORDER BY 2 DESC",
+    },
+];
+
+impl Diagnostic {
+    pub fn find(name: &str) -> Option<&'static Diagnostic> {
+        DIAGNOSTICS.iter().find(|diagnostic| diagnostic.name == name)
+    }
+}
+
+impl DataStorage {
+    #[instrument(skip_all, err)]
+    pub async fn run_diagnostic(&self, diagnostic: &Diagnostic) -> Result<Vec<(String, i64)>, DatabaseError> {
+        let rows = sqlx::query_as(diagnostic.query)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,167 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId, MessageId, UserId};
+
+use super::structures::{StoredEvent, StoredRsvp};
+use super::DataStorage;
+use crate::core::events::EventDetails;
+use crate::error::DatabaseError;
+
+const EVENT_COLUMNS: &str = "guild_id, id, channel_id, message_id, creator_id, title, description,
+    extract(epoch from starts_at)::bigint AS starts_at";
+
+impl DataStorage {
+    /// Claims the next event number of the guild, the message is added once it's posted.
+    ///
+    /// `remind_at` is a unix timestamp, without one nobody gets reminded.
+    #[instrument(skip_all, err)]
+    pub async fn create_event(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        creator: UserId,
+        details: &EventDetails,
+        remind_at: Option<i64>,
+    ) -> Result<StoredEvent, DatabaseError> {
+        let event = sqlx::query_as(&format!(
+            "INSERT INTO event (guild_id, id, channel_id, creator_id, title, description, starts_at, remind_at)
+            SELECT $1, coalesce(max(id), 0) + 1, $2, $3, $4, $5, to_timestamp($6), to_timestamp($7)
+            FROM event WHERE guild_id = $1
+            RETURNING {}",
+            EVENT_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(creator.0 as i64)
+        .bind(&details.title)
+        .bind(&details.description)
+        .bind(details.starts_at.timestamp() as f64)
+        .bind(remind_at.map(|remind_at| remind_at as f64))
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_event_message(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        message_id: MessageId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE event SET message_id = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(message_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the event along with everyone's RSVP to it.
+    #[instrument(skip_all, err)]
+    pub async fn remove_event(&self, guild_id: GuildId, id: i32) -> Result<(), DatabaseError> {
+        let mut transaction = self.persistent_pool.begin().await?;
+        sqlx::query("DELETE FROM eventrsvp WHERE guild_id = $1 AND event_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM event WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_event(&self, guild_id: GuildId, id: i32) -> Result<Option<StoredEvent>, DatabaseError> {
+        let event = sqlx::query_as(&format!(
+            "SELECT {} FROM event WHERE guild_id = $1 AND id = $2",
+            EVENT_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(id)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Sets the RSVP of a member, picking the one they already had takes it back instead.
+    #[instrument(skip_all, err)]
+    pub async fn toggle_rsvp(
+        &self,
+        guild_id: GuildId,
+        event_id: i32,
+        user_id: UserId,
+        status: &str,
+    ) -> Result<(), DatabaseError> {
+        let removed =
+            sqlx::query("DELETE FROM eventrsvp WHERE guild_id = $1 AND event_id = $2 AND user_id = $3 AND status = $4")
+                .bind(guild_id.0 as i64)
+                .bind(event_id)
+                .bind(user_id.0 as i64)
+                .bind(status)
+                .execute(&self.persistent_pool)
+                .await?;
+        if removed.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO eventrsvp (guild_id, event_id, user_id, status) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, event_id, user_id) DO UPDATE SET status = excluded.status, responded_at = now()",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(event_id)
+        .bind(user_id.0 as i64)
+        .bind(status)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Everyone's RSVP to the event, in the order they responded.
+    #[instrument(skip_all, err)]
+    pub async fn get_rsvps(&self, guild_id: GuildId, event_id: i32) -> Result<Vec<StoredRsvp>, DatabaseError> {
+        let rsvps = sqlx::query_as(
+            "SELECT user_id, status FROM eventrsvp WHERE guild_id = $1 AND event_id = $2 ORDER BY responded_at",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(event_id)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(rsvps)
+    }
+
+    /// Events of all guilds whose reminder should have gone out by now.
+    #[instrument(skip_all, err)]
+    pub async fn get_due_event_reminders(&self) -> Result<Vec<StoredEvent>, DatabaseError> {
+        let events = sqlx::query_as(&format!(
+            "SELECT {} FROM event WHERE remind_at <= now() ORDER BY remind_at",
+            EVENT_COLUMNS
+        ))
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn clear_event_reminder(&self, guild_id: GuildId, id: i32) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE event SET remind_at = NULL WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+}
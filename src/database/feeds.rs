@@ -0,0 +1,212 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::structures::StoredFeed;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+const FEED_COLUMNS: &str = "guild_id, id, channel_id, url, title, template, interval_minutes";
+
+/// How long entries that dropped out of the feed are remembered, in case they show up again.
+const SEEN_RETENTION_DAYS: i32 = 30;
+
+impl DataStorage {
+    /// Claims the next feed number of the guild, the first poll happens once the interval passed.
+    #[instrument(skip_all, err)]
+    pub async fn create_feed(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        url: &str,
+        title: Option<&str>,
+        template: &str,
+        interval_minutes: i32,
+    ) -> Result<StoredFeed, DatabaseError> {
+        let feed = sqlx::query_as(&format!(
+            "INSERT INTO feed (guild_id, id, channel_id, url, title, template, interval_minutes, next_poll_at)
+            SELECT $1, coalesce(max(id), 0) + 1, $2, $3, $4, $5, $6, now() + make_interval(mins => $6)
+            FROM feed WHERE guild_id = $1
+            RETURNING {}",
+            FEED_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(url)
+        .bind(title)
+        .bind(template)
+        .bind(interval_minutes)
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(feed)
+    }
+
+    /// Removes the feed along with the entries it remembers.
+    #[instrument(skip_all, err)]
+    pub async fn remove_feed(&self, guild_id: GuildId, id: i32) -> Result<bool, DatabaseError> {
+        let mut transaction = self.persistent_pool.begin().await?;
+        sqlx::query("DELETE FROM feedentry WHERE guild_id = $1 AND feed_id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        let removed = sqlx::query("DELETE FROM feed WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_feeds(&self, guild_id: GuildId) -> Result<Vec<StoredFeed>, DatabaseError> {
+        let feeds = sqlx::query_as(&format!(
+            "SELECT {} FROM feed WHERE guild_id = $1 ORDER BY id",
+            FEED_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(feeds)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_feed(&self, guild_id: GuildId, id: i32) -> Result<Option<StoredFeed>, DatabaseError> {
+        let feed = sqlx::query_as(&format!(
+            "SELECT {} FROM feed WHERE guild_id = $1 AND id = $2",
+            FEED_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(id)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(feed)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_feed_template(&self, guild_id: GuildId, id: i32, template: &str) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query("UPDATE feed SET template = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(template)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
+    /// Changes how often the feed is polled, starting from now.
+    #[instrument(skip_all, err)]
+    pub async fn set_feed_interval(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        interval_minutes: i32,
+    ) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query(
+            "UPDATE feed SET interval_minutes = $3, next_poll_at = now() + make_interval(mins => $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(id)
+        .bind(interval_minutes)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
+    /// Feeds of all guilds that should be polled by now.
+    #[instrument(skip_all, err)]
+    pub async fn get_due_feeds(&self) -> Result<Vec<StoredFeed>, DatabaseError> {
+        let feeds = sqlx::query_as(&format!(
+            "SELECT {} FROM feed WHERE next_poll_at <= now() ORDER BY next_poll_at",
+            FEED_COLUMNS
+        ))
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(feeds)
+    }
+
+    /// Pushes the next poll of the feed one interval into the future.
+    #[instrument(skip_all, err)]
+    pub async fn schedule_feed_poll(&self, guild_id: GuildId, id: i32) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE feed SET next_poll_at = now() + make_interval(mins => interval_minutes)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(id)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Which of these entries of the feed were seen before.
+    #[instrument(skip_all, err)]
+    pub async fn get_seen_feed_entries(
+        &self,
+        guild_id: GuildId,
+        feed_id: i32,
+        entries: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        let seen: Vec<(String,)> = sqlx::query_as(
+            "SELECT entry_id FROM feedentry WHERE guild_id = $1 AND feed_id = $2 AND entry_id = ANY($3)",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(feed_id)
+        .bind(entries)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(seen.into_iter().map(|(entry,)| entry).collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn mark_feed_entries_seen(
+        &self,
+        guild_id: GuildId,
+        feed_id: i32,
+        entries: &[String],
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO feedentry (guild_id, feed_id, entry_id) SELECT $1, $2, unnest($3::text[])
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(feed_id)
+        .bind(entries)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forgets entries that have been out of the feed for a while, the ones still in it are always kept.
+    #[instrument(skip_all, err)]
+    pub async fn prune_feed_entries(
+        &self,
+        guild_id: GuildId,
+        feed_id: i32,
+        current: &[String],
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "DELETE FROM feedentry WHERE guild_id = $1 AND feed_id = $2 AND entry_id <> ALL($3)
+            AND seen_at < now() - make_interval(days => $4)",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(feed_id)
+        .bind(current)
+        .bind(SEEN_RETENTION_DAYS)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+}
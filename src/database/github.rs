@@ -0,0 +1,99 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::structures::StoredGithubRepo;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Starts relaying a repository, `false` if the guild already does.
+    #[instrument(skip_all, err)]
+    pub async fn add_github_repo(
+        &self,
+        guild_id: GuildId,
+        repository: &str,
+        channel_id: ChannelId,
+        events: &[String],
+        secret: &str,
+    ) -> Result<bool, DatabaseError> {
+        let added = sqlx::query(
+            "INSERT INTO githubrepo (guild_id, repository, channel_id, events, secret) VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(repository)
+        .bind(channel_id.0 as i64)
+        .bind(events)
+        .bind(secret)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(added.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn remove_github_repo(&self, guild_id: GuildId, repository: &str) -> Result<bool, DatabaseError> {
+        let removed = sqlx::query("DELETE FROM githubrepo WHERE guild_id = $1 AND repository = $2")
+            .bind(guild_id.0 as i64)
+            .bind(repository)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_github_repos(&self, guild_id: GuildId) -> Result<Vec<StoredGithubRepo>, DatabaseError> {
+        let repos = sqlx::query_as("SELECT * FROM githubrepo WHERE guild_id = $1 ORDER BY repository")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(repos)
+    }
+
+    /// Every guild that relays the repository, deliveries are checked against each of their secrets.
+    #[instrument(skip_all, err)]
+    pub async fn get_github_subscriptions(&self, repository: &str) -> Result<Vec<StoredGithubRepo>, DatabaseError> {
+        let repos = sqlx::query_as("SELECT * FROM githubrepo WHERE repository = $1")
+            .bind(repository)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(repos)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_github_repo_events(
+        &self,
+        guild_id: GuildId,
+        repository: &str,
+        events: &[String],
+    ) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query("UPDATE githubrepo SET events = $3 WHERE guild_id = $1 AND repository = $2")
+            .bind(guild_id.0 as i64)
+            .bind(repository)
+            .bind(events)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_github_repo_secret(
+        &self,
+        guild_id: GuildId,
+        repository: &str,
+        secret: &str,
+    ) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query("UPDATE githubrepo SET secret = $3 WHERE guild_id = $1 AND repository = $2")
+            .bind(guild_id.0 as i64)
+            .bind(repository)
+            .bind(secret)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,178 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::structures::{ChannelActivityHour, CommandUsage, GuildStatsDay};
+use super::DataStorage;
+use crate::core::guild_stats::{CommandUseCounts, GuildStatCounts};
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Adds counts to the bucket of the current day (in UTC) of the guild.
+    #[instrument(skip_all, err)]
+    pub async fn add_guild_stats(&self, guild_id: GuildId, counts: &GuildStatCounts) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO guildstats (guild_id, day, messages, joins, leaves, commands, automod_triggers)
+            VALUES ($1, (now() AT TIME ZONE 'UTC')::date, $2, $3, $4, $5, $6)
+            ON CONFLICT (guild_id, day) DO UPDATE SET
+                messages = guildstats.messages + excluded.messages,
+                joins = guildstats.joins + excluded.joins,
+                leaves = guildstats.leaves + excluded.leaves,
+                commands = guildstats.commands + excluded.commands,
+                automod_triggers = guildstats.automod_triggers + excluded.automod_triggers",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(counts.messages as i32)
+        .bind(counts.joins as i32)
+        .bind(counts.leaves as i32)
+        .bind(counts.commands as i32)
+        .bind(counts.automod_triggers as i32)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The statistics of the last days of a guild, oldest first. Days without anything to count are left out.
+    #[instrument(skip_all, err)]
+    pub async fn get_guild_stats(&self, guild_id: GuildId, days: u32) -> Result<Vec<GuildStatsDay>, DatabaseError> {
+        let stats = sqlx::query_as(
+            "SELECT extract(epoch FROM day)::bigint AS day, messages, joins, leaves, commands, automod_triggers
+            FROM guildstats
+            WHERE guild_id = $1 AND day > (now() AT TIME ZONE 'UTC')::date - $2::int
+            ORDER BY day",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(days as i32)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Removes the statistics older than this many days of all guilds, returning how many days were removed.
+    #[instrument(skip_all, err)]
+    pub async fn prune_guild_stats(&self, days: u32) -> Result<u64, DatabaseError> {
+        let result = sqlx::query("DELETE FROM guildstats WHERE day <= (now() AT TIME ZONE 'UTC')::date - $1::int")
+            .bind(days as i32)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Adds messages to the bucket of a channel for the hour starting at `hour`.
+    #[instrument(skip_all, err)]
+    pub async fn add_channel_activity(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        hour: i64,
+        messages: u32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO channelactivity (guild_id, channel_id, hour, messages)
+            VALUES ($1, $2, to_timestamp($3) AT TIME ZONE 'UTC', $4)
+            ON CONFLICT (guild_id, channel_id, hour) DO UPDATE SET
+                messages = channelactivity.messages + excluded.messages",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(hour)
+        .bind(messages as i32)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The hourly message counts of every channel of a guild over the last days, oldest first.
+    #[instrument(skip_all, err)]
+    pub async fn get_channel_activity(
+        &self,
+        guild_id: GuildId,
+        days: u32,
+    ) -> Result<Vec<ChannelActivityHour>, DatabaseError> {
+        let activity = sqlx::query_as(
+            "SELECT channel_id, extract(epoch FROM hour)::bigint AS hour, messages
+            FROM channelactivity
+            WHERE guild_id = $1 AND hour >= (now() AT TIME ZONE 'UTC') - make_interval(days => $2)
+            ORDER BY hour, channel_id",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(days as i32)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(activity)
+    }
+
+    /// Removes the channel activity older than this many days of all guilds, returning how many hours were removed.
+    #[instrument(skip_all, err)]
+    pub async fn prune_channel_activity(&self, days: u32) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            "DELETE FROM channelactivity WHERE hour < (now() AT TIME ZONE 'UTC') - make_interval(days => $1)",
+        )
+        .bind(days as i32)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Adds uses of a command in a channel to the bucket of the current day (in UTC).
+    #[instrument(skip_all, err)]
+    pub async fn add_command_usage(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        command: &str,
+        counts: CommandUseCounts,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO commandusage (guild_id, channel_id, day, command, uses, failures)
+            VALUES ($1, $2, (now() AT TIME ZONE 'UTC')::date, $3, $4, $5)
+            ON CONFLICT (guild_id, day, command, channel_id) DO UPDATE SET
+                uses = commandusage.uses + excluded.uses,
+                failures = commandusage.failures + excluded.failures",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(command)
+        .bind(counts.uses as i32)
+        .bind(counts.failures as i32)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// How much every command was used in a guild over the last days, most used first.
+    #[instrument(skip_all, err)]
+    pub async fn get_command_usage(&self, guild_id: GuildId, days: u32) -> Result<Vec<CommandUsage>, DatabaseError> {
+        let usage = sqlx::query_as(
+            "SELECT command, sum(uses)::bigint AS uses, sum(failures)::bigint AS failures,
+                count(DISTINCT channel_id) AS channels, extract(epoch FROM max(day))::bigint AS last_used
+            FROM commandusage
+            WHERE guild_id = $1 AND day > (now() AT TIME ZONE 'UTC')::date - $2::int
+            GROUP BY command
+            ORDER BY uses DESC, command",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(days as i32)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// Removes the command usage older than this many days of all guilds, returning how many rows were removed.
+    #[instrument(skip_all, err)]
+    pub async fn prune_command_usage(&self, days: u32) -> Result<u64, DatabaseError> {
+        let result = sqlx::query("DELETE FROM commandusage WHERE day <= (now() AT TIME ZONE 'UTC')::date - $1::int")
+            .bind(days as i32)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
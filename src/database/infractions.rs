@@ -0,0 +1,168 @@
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+/// How many other guilds of the reputation network have something on record for a user, never what it is.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NetworkReputation {
+    /// Guilds they are banned from right now.
+    pub bans: u64,
+    /// Guilds with warnings, censors, mutes or kicks on record for them.
+    pub infractions: u64,
+}
+
+/// How many infractions a moderator gave out over a period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeratorWorkload {
+    pub mod_id: UserId,
+    pub total: u64,
+    /// Infractions per history type, the most given out first.
+    pub kinds: Vec<(String, u64)>,
+}
+
+/// Counts the guilds that opted into the reputation network with infractions for a user, leaving out the guild
+/// that asks. A ban stops counting once it ran out or the user got unbanned after it.
+const NETWORK_REPUTATION: &str = "SELECT
+    count(DISTINCT h.guild_id) FILTER (
+        WHERE h.type IN ('ban', 'forceban', 'tempban')
+            AND (h.\"end\" IS NULL OR h.\"end\" > now())
+            AND NOT EXISTS (
+                SELECT 1 FROM history u
+                WHERE u.guild_id = h.guild_id AND u.user_id = h.user_id AND u.type = 'unban' AND u.start > h.start
+            )
+    ),
+    count(DISTINCT h.guild_id) FILTER (WHERE h.type IN ('warning', 'censor', 'mute', 'kick', 'cleankick'))
+FROM history h
+JOIN guildconfig ON h.guild_id = guildconfig.id
+WHERE h.user_id = $1
+    AND h.guild_id != $2
+    AND guildconfig.config->>'reputation_network' = 'true'";
+
+impl DataStorage {
+    /// Puts an infraction on record, `kind` has to be one of the history types like `warning` or `ban`.
+    #[instrument(skip_all, err)]
+    pub async fn insert_infraction(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        mod_id: UserId,
+        kind: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("INSERT INTO history (guild_id, user_id, mod_id, type) VALUES ($1, $2, $3, $4::historyType)")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .bind(mod_id.0 as i64)
+            .bind(kind)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Starts watching the user, returns `false` if they were already being watched.
+    #[instrument(skip_all, err)]
+    pub async fn start_watch(&self, guild_id: GuildId, user_id: UserId, mod_id: UserId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "INSERT INTO history (guild_id, user_id, mod_id, type)
+            SELECT $1, $2, $3, 'watch'
+            WHERE NOT EXISTS (
+                SELECT 1 FROM history WHERE guild_id = $1 AND user_id = $2 AND type = 'watch' AND \"end\" IS NULL
+            )",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .bind(mod_id.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stops watching the user, returns `false` if they weren't being watched.
+    #[instrument(skip_all, err)]
+    pub async fn end_watch(&self, guild_id: GuildId, user_id: UserId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE history SET \"end\" = now()
+            WHERE guild_id = $1 AND user_id = $2 AND type = 'watch' AND \"end\" IS NULL",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_watched_users(&self, guild_id: GuildId) -> Result<Vec<UserId>, DatabaseError> {
+        let rows: Vec<(i64,)> =
+            sqlx::query_as("SELECT user_id FROM history WHERE guild_id = $1 AND type = 'watch' AND \"end\" IS NULL")
+                .bind(guild_id.0 as i64)
+                .fetch_all(&self.persistent_pool)
+                .await?;
+
+        Ok(rows.into_iter().map(|row| UserId(row.0 as u64)).collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_network_reputation(
+        &self,
+        user_id: UserId,
+        asking_guild: GuildId,
+    ) -> Result<NetworkReputation, DatabaseError> {
+        let (bans, infractions): (i64, i64) = sqlx::query_as(NETWORK_REPUTATION)
+            .bind(user_id.0 as i64)
+            .bind(asking_guild.0 as i64)
+            .fetch_one(&self.persistent_pool)
+            .await?;
+
+        Ok(NetworkReputation {
+            bans: bans as u64,
+            infractions: infractions as u64,
+        })
+    }
+
+    /// The infractions every moderator of a guild gave out over the last days, busiest moderator first. Notes and
+    /// watches aren't counted, those aren't actions against anyone.
+    #[instrument(skip_all, err)]
+    pub async fn get_moderator_workload(
+        &self,
+        guild_id: GuildId,
+        days: u32,
+    ) -> Result<Vec<ModeratorWorkload>, DatabaseError> {
+        let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+            "SELECT mod_id, type::text, count(*)
+            FROM history
+            WHERE guild_id = $1 AND type NOT IN ('note', 'watch') AND start > now() - make_interval(days => $2)
+            GROUP BY mod_id, type
+            ORDER BY count(*) DESC, type",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(days as i32)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        let mut workloads: Vec<ModeratorWorkload> = vec![];
+        for (mod_id, kind, count) in rows {
+            let mod_id = UserId(mod_id as u64);
+            let index = match workloads.iter().position(|workload| workload.mod_id == mod_id) {
+                Some(index) => index,
+                None => {
+                    workloads.push(ModeratorWorkload {
+                        mod_id,
+                        total: 0,
+                        kinds: vec![],
+                    });
+                    workloads.len() - 1
+                }
+            };
+            workloads[index].total += count as u64;
+            workloads[index].kinds.push((kind, count as u64));
+        }
+        workloads.sort_by(|a, b| b.total.cmp(&a.total).then(a.mod_id.cmp(&b.mod_id)));
+
+        Ok(workloads)
+    }
+}
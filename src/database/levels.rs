@@ -0,0 +1,70 @@
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::structures::StoredUserLevel;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Adds xp to a user in a guild, creating their entry if they didn't have one yet.
+    ///
+    /// Returns the new total amount of xp the user has.
+    #[instrument(skip_all, err)]
+    pub async fn add_xp(&self, guild_id: GuildId, user_id: UserId, amount: u32) -> Result<u64, DatabaseError> {
+        let total: (i64,) = sqlx::query_as(
+            "INSERT INTO userlevel (guild_id, user_id, xp) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET xp = userlevel.xp + $3
+            RETURNING xp",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .bind(amount as i64)
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(total.0 as u64)
+    }
+
+    /// Fetches the total xp and leaderboard position of a user, if they have earned any xp in the guild.
+    #[instrument(skip_all, err)]
+    pub async fn get_rank(&self, guild_id: GuildId, user_id: UserId) -> Result<Option<(u64, u64)>, DatabaseError> {
+        let row: Option<(i64, i64)> = sqlx::query_as(
+            "SELECT ul.xp, (SELECT count(*) FROM userlevel WHERE guild_id = $1 AND xp > ul.xp) + 1
+            FROM userlevel ul WHERE ul.guild_id = $1 AND ul.user_id = $2",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(row.map(|(xp, rank)| (xp as u64, rank as u64)))
+    }
+
+    /// Fetches a page of the guild leaderboard, sorted by xp from high to low.
+    #[instrument(skip_all, err)]
+    pub async fn get_leaderboard(
+        &self,
+        guild_id: GuildId,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<StoredUserLevel>, DatabaseError> {
+        let entries = sqlx::query_as("SELECT * FROM userlevel WHERE guild_id = $1 ORDER BY xp DESC LIMIT $2 OFFSET $3")
+            .bind(guild_id.0 as i64)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(entries)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_leaderboard_size(&self, guild_id: GuildId) -> Result<u64, DatabaseError> {
+        let count: (i64,) = sqlx::query_as("SELECT count(*) FROM userlevel WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .fetch_one(&self.persistent_pool)
+            .await?;
+
+        Ok(count.0 as u64)
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::Instant;
+
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// How long queries took and how many failed, per family of queries.
+///
+/// Timed from the spans the database functions are instrumented with, so a function that runs several queries
+/// counts as one.
+#[derive(Clone)]
+pub struct QueryMetrics {
+    latency: HistogramVec,
+    errors: IntCounterVec,
+}
+
+/// Reads the connections of the pool when the metrics are gathered, it doesn't tell when they change.
+struct PoolCollector {
+    pool: sqlx::PgPool,
+    connections: IntGaugeVec,
+}
+
+/// When the span of a database function was created.
+struct QueryStart(Instant);
+
+impl Default for QueryMetrics {
+    fn default() -> Self {
+        let latency = HistogramVec::new(
+            HistogramOpts::new("query_duration_seconds", "How long database queries took").buckets(vec![
+                0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+            ]),
+            &["family"],
+        )
+        .unwrap();
+        let errors =
+            IntCounterVec::new(Opts::new("query_errors", "Database queries that failed"), &["family"]).unwrap();
+
+        QueryMetrics { latency, errors }
+    }
+}
+
+impl QueryMetrics {
+    /// Adds the query metrics and the connections of the pool to the registry.
+    pub fn register(&self, registry: &Registry, pool: sqlx::PgPool) {
+        let connections = IntGaugeVec::new(
+            Opts::new("database_connections", "Connections of the Postgres pool"),
+            &["state"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(self.latency.clone())).unwrap();
+        registry.register(Box::new(self.errors.clone())).unwrap();
+        registry
+            .register(Box::new(PoolCollector { pool, connections }))
+            .unwrap();
+    }
+}
+
+impl<S> Layer<S> for QueryMetrics
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if query_family(span.metadata()).is_some() {
+                span.extensions_mut().insert(QueryStart(Instant::now()));
+            }
+        }
+    }
+
+    /// Failing functions log their error inside their span.
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        if let Some(family) = ctx.event_span(event).and_then(|span| query_family(span.metadata())) {
+            self.errors.with_label_values(&[family]).inc();
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let (Some(family), Some(start)) = (query_family(span.metadata()), span.extensions().get::<QueryStart>())
+            {
+                self.latency
+                    .with_label_values(&[family])
+                    .observe(start.0.elapsed().as_secs_f64());
+            }
+        }
+    }
+}
+
+impl Collector for PoolCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.connections.desc()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let open = self.pool.size() as i64;
+        let idle = self.pool.num_idle() as i64;
+        self.connections.with_label_values(&["open"]).set(open);
+        self.connections.with_label_values(&["idle"]).set(idle);
+        self.connections.with_label_values(&["in_use"]).set(open - idle);
+        self.connections.collect()
+    }
+}
+
+/// The family of the database function a span belongs to, named after the module it's in.
+fn query_family(metadata: &'static Metadata<'static>) -> Option<&'static str> {
+    family_of_target(metadata.target())
+}
+
+fn family_of_target(target: &'static str) -> Option<&'static str> {
+    let module = target.strip_prefix("gearbot::database")?;
+    if module.is_empty() {
+        // The messages, archives and webhooks in the root of the database module
+        return Some("storage");
+    }
+    module.strip_prefix("::")?.split("::").next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_families_after_modules() {
+        assert_eq!(family_of_target("gearbot::database"), Some("storage"));
+        assert_eq!(family_of_target("gearbot::database::configs"), Some("configs"));
+        assert_eq!(
+            family_of_target("gearbot::database::redis::cluster_link"),
+            Some("redis")
+        );
+        assert_eq!(family_of_target("gearbot::databases"), None);
+        assert_eq!(family_of_target("gearbot::core::logpump"), None);
+    }
+}
@@ -1,13 +1,63 @@
+pub mod activity;
+
+pub mod backups;
+
+pub mod birthdays;
+
+pub mod blocklist;
+
+pub mod channel_templates;
+
 pub mod configs;
 
 mod crypto;
 use crypto::EncryptionKey;
 
+pub mod diagnostics;
+
+pub mod events;
+
+pub mod feeds;
+
+pub mod github;
+
+pub mod guild_stats;
+
+pub mod infractions;
+
+pub mod levels;
+
+mod metrics;
+pub use metrics::QueryMetrics;
+
+pub mod pin_archive;
+
+pub mod profiles;
+
+pub mod purges;
+
 pub mod redis;
 
+pub mod retention;
+
+pub mod suggestions;
+
+pub mod temp_voice;
+
+pub mod tickets;
+
+pub mod tiers;
+
+pub mod user_data;
+
+pub mod webhooks;
+
 pub mod structures;
-use structures::{StoredUserMessage, UserMessage};
+use structures::{MessageSearch, StoredAttachment, StoredUserMessage, UserMessage};
 
+use std::time::{Duration, Instant};
+
+use tracing::instrument;
 use twilight_model::channel::{Attachment, Message};
 use twilight_model::id::{ChannelId, GuildId, MessageId, UserId, WebhookId};
 
@@ -24,6 +74,7 @@ pub struct DataStorage {
     persistent_pool: sqlx::PgPool,
     pub cache_pool: Redis,
     primary_encryption_key: EncryptionKey<'static>,
+    query_metrics: QueryMetrics,
 }
 
 impl DataStorage {
@@ -69,15 +120,34 @@ impl DataStorage {
             persistent_pool: postgres_pool,
             cache_pool: redis_pool,
             primary_encryption_key: EncryptionKey::construct_owned(&config.main_encryption_key),
+            query_metrics: QueryMetrics::default(),
         })
     }
 
+    /// The tracing layer that times the queries, has to be part of the global subscriber to see them.
+    pub fn query_metrics(&self) -> QueryMetrics {
+        self.query_metrics.clone()
+    }
+
+    /// Exposes the query latencies, errors and the connections of the pool on the metrics endpoint.
+    pub fn register_metrics(&self, registry: &prometheus::Registry) {
+        self.query_metrics.register(registry, self.persistent_pool.clone());
+    }
+
+    /// Checks if Postgres is still reachable.
+    #[instrument(skip_all, err)]
+    pub async fn ping(&self) -> Result<(), DatabaseError> {
+        sqlx::query("SELECT 1").execute(&self.persistent_pool).await?;
+        Ok(())
+    }
+
     /// Inserts a message into the database.
     ///
     /// The guild ID provided *must* be the same guild that the message was recieved in, otherwise
     /// it will fail to decrypt upon retrieval.
+    #[instrument(skip_all, err)]
     pub async fn insert_message(&self, message: &Message, guild_id: GuildId) -> Result<(), DatabaseError> {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
 
         let ciphertext = {
             let plaintext = message.content.as_bytes();
@@ -106,6 +176,7 @@ impl DataStorage {
     }
 
     /// Inserts a message attachment into the database.
+    #[instrument(skip_all, err)]
     pub async fn insert_attachment(&self, message_id: MessageId, attachment: &Attachment) -> Result<(), DatabaseError> {
         sqlx::query(
             "INSERT INTO attachment (id, name, image, message_id)
@@ -121,37 +192,45 @@ impl DataStorage {
         Ok(())
     }
 
+    /// Retrieves the attachments that were stored for a message.
+    #[instrument(skip_all, err)]
+    pub async fn get_attachments(&self, message_id: MessageId) -> Result<Vec<StoredAttachment>, DatabaseError> {
+        let attachments = sqlx::query_as("SELECT * from attachment where message_id=$1 order by id")
+            .bind(message_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(attachments)
+    }
+
     /// Retrieves a user's message from the database, if it existed.
     ///
     /// The guild ID provided *must* be the same guild that the message was recieved in, otherwise
     /// it will fail to decrypt upon retrieval.
+    #[instrument(skip_all, err)]
     pub async fn get_full_message(
         &self,
         message_id: MessageId,
         guild_id: GuildId,
     ) -> Result<Option<UserMessage>, DatabaseError> {
-        let stored_message: Option<StoredUserMessage> = sqlx::query_as("SELECT * from message where id=$1")
-            .bind(message_id.0 as i64)
-            .fetch_optional(&self.persistent_pool)
-            .await?;
+        // Only look in the guild we were given, messages from other guilds would fail to decrypt
+        let stored_message: Option<StoredUserMessage> =
+            sqlx::query_as("SELECT * from message where id=$1 and guild_id=$2")
+                .bind(message_id.0 as i64)
+                .bind(guild_id.0 as i64)
+                .fetch_optional(&self.persistent_pool)
+                .await?;
 
         let user_msg = match stored_message {
             Some(sm) => {
-                let start = std::time::Instant::now();
+                let start = Instant::now();
 
                 let guild_key = self.get_guild_encryption_key(guild_id).await?;
-                let decrypted_content = crypto::decrypt_bytes(&sm.encrypted_content, &guild_key, message_id.0);
+                let message = decrypt_message(sm, &guild_key);
 
                 log::debug!("It took {}us to decrypt a user message!", start.elapsed().as_micros());
 
-                Some(UserMessage {
-                    content: String::from_utf8(decrypted_content).unwrap(),
-                    author: UserId(sm.author_id as u64),
-                    channel: ChannelId(sm.channel_id as u64),
-                    guild: GuildId(sm.guild_id as u64),
-                    kind: sm.kind(),
-                    pinned: sm.pinned,
-                })
+                Some(message)
             }
             None => None,
         };
@@ -159,6 +238,135 @@ impl DataStorage {
         Ok(user_msg)
     }
 
+    /// Retrieves all of the given messages that exist in the database, ordered from oldest to newest.
+    ///
+    /// The same guild restrictions as [`get_full_message`] apply.
+    ///
+    /// [`get_full_message`]: Self::get_full_message
+    #[instrument(skip_all, err)]
+    pub async fn get_full_messages(
+        &self,
+        message_ids: &[MessageId],
+        guild_id: GuildId,
+    ) -> Result<Vec<(MessageId, UserMessage)>, DatabaseError> {
+        let ids: Vec<i64> = message_ids.iter().map(|id| id.0 as i64).collect();
+        let stored_messages: Vec<StoredUserMessage> =
+            sqlx::query_as("SELECT * from message where id = any($1) and guild_id=$2 order by id")
+                .bind(ids)
+                .bind(guild_id.0 as i64)
+                .fetch_all(&self.persistent_pool)
+                .await?;
+
+        if stored_messages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let guild_key = self.get_guild_encryption_key(guild_id).await?;
+        let messages = stored_messages
+            .into_iter()
+            .map(|sm| (MessageId(sm.id as u64), decrypt_message(sm, &guild_key)))
+            .collect();
+
+        Ok(messages)
+    }
+
+    /// Retrieves the most recent messages of a guild matching the search, newest first.
+    ///
+    /// Everything gets decrypted, so keep the limit reasonable.
+    #[instrument(skip_all, err)]
+    pub async fn search_messages(
+        &self,
+        guild_id: GuildId,
+        search: &MessageSearch,
+        limit: u32,
+    ) -> Result<Vec<(MessageId, UserMessage)>, DatabaseError> {
+        let stored_messages: Vec<StoredUserMessage> = sqlx::query_as(
+            "SELECT * from message where guild_id=$1
+            and ($2::bigint is null or author_id=$2)
+            and ($3::bigint is null or channel_id=$3)
+            and ($4::bigint is null or id > $4)
+            and ($5::bigint is null or id < $5)
+            order by id desc limit $6",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(search.author.map(|id| id.0 as i64))
+        .bind(search.channel.map(|id| id.0 as i64))
+        .bind(search.after.map(|id| id.0 as i64))
+        .bind(search.before.map(|id| id.0 as i64))
+        .bind(limit as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        if stored_messages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let start = Instant::now();
+        let guild_key = self.get_guild_encryption_key(guild_id).await?;
+        let messages: Vec<(MessageId, UserMessage)> = stored_messages
+            .into_iter()
+            .map(|sm| (MessageId(sm.id as u64), decrypt_message(sm, &guild_key)))
+            .collect();
+        log::debug!(
+            "It took {}us to decrypt {} messages for a search!",
+            start.elapsed().as_micros(),
+            messages.len()
+        );
+
+        Ok(messages)
+    }
+
+    /// Stores an encrypted copy of a transcript in the archive.
+    ///
+    /// The id has to be unique across all archives, the id of a message the transcript contains works for this.
+    #[instrument(skip_all, err)]
+    pub async fn insert_archive(
+        &self,
+        archive_id: u64,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        content: &str,
+    ) -> Result<(), DatabaseError> {
+        let ciphertext = {
+            let guild_key = self.get_guild_encryption_key(guild_id).await?;
+            crypto::encrypt_archive_bytes(content.as_bytes(), &guild_key, archive_id)
+        };
+
+        sqlx::query(
+            "INSERT INTO archive (id, guild_id, channel_id, encrypted_content)
+            VALUES ($1, $2, $3, $4)",
+        )
+        .bind(archive_id as i64)
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(ciphertext)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves and decrypts an archived transcript, if it exists in this guild.
+    #[instrument(skip_all, err)]
+    pub async fn get_archive(&self, archive_id: u64, guild_id: GuildId) -> Result<Option<String>, DatabaseError> {
+        let stored: Option<(Vec<u8>,)> =
+            sqlx::query_as("SELECT encrypted_content from archive where id=$1 and guild_id=$2")
+                .bind(archive_id as i64)
+                .bind(guild_id.0 as i64)
+                .fetch_optional(&self.persistent_pool)
+                .await?;
+
+        match stored {
+            Some((ciphertext,)) => {
+                let guild_key = self.get_guild_encryption_key(guild_id).await?;
+                let plaintext = crypto::decrypt_archive_bytes(&ciphertext, &guild_key, archive_id);
+                Ok(Some(String::from_utf8(plaintext).unwrap()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip_all, err)]
     pub async fn get_webhook_parts(&self, channel_id: ChannelId) -> Result<Option<(WebhookId, String)>, DatabaseError> {
         let data: Option<WebhookInfo> = sqlx::query_as("SELECT * from webhook where channel_id=$1")
             .bind(channel_id.0 as i64)
@@ -170,6 +378,7 @@ impl DataStorage {
         }
     }
 
+    #[instrument(skip_all, err)]
     pub async fn insert_webhook(
         &self,
         channel_id: ChannelId,
@@ -186,6 +395,7 @@ impl DataStorage {
         Ok(())
     }
 
+    #[instrument(skip_all, err)]
     pub async fn remove_webhook(&self, channel_id: ChannelId) -> Result<(), DatabaseError> {
         sqlx::query("DELETE FROM webhook where channel_id = $1")
             .bind(channel_id.0 as i64)
@@ -195,6 +405,15 @@ impl DataStorage {
         Ok(())
     }
 
+    /// Measures the round trip time of a trivial query against the SQL server.
+    #[instrument(skip_all, err)]
+    pub async fn ping(&self) -> Result<Duration, DatabaseError> {
+        let start = Instant::now();
+        sqlx::query("SELECT 1").execute(&self.persistent_pool).await?;
+
+        Ok(start.elapsed())
+    }
+
     /// Fetches the encryption key for a guild out of its config.
     async fn get_guild_encryption_key(&self, guild_id: GuildId) -> Result<EncryptionKey<'_>, DatabaseError> {
         let ek_bytes: (Vec<u8>,) = sqlx::query_as("SELECT encryption_key from guildconfig where id=$1")
@@ -211,3 +430,16 @@ impl DataStorage {
         Ok(guild_key)
     }
 }
+
+fn decrypt_message(stored: StoredUserMessage, guild_key: &EncryptionKey) -> UserMessage {
+    let decrypted_content = crypto::decrypt_bytes(&stored.encrypted_content, guild_key, stored.id as u64);
+
+    UserMessage {
+        content: String::from_utf8(decrypted_content).unwrap(),
+        author: UserId(stored.author_id as u64),
+        channel: ChannelId(stored.channel_id as u64),
+        guild: GuildId(stored.guild_id as u64),
+        kind: stored.kind(),
+        pinned: stored.pinned,
+    }
+}
@@ -0,0 +1,37 @@
+use tracing::instrument;
+use twilight_model::id::{GuildId, MessageId};
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    #[instrument(skip_all, err)]
+    pub async fn is_pin_archived(&self, message_id: MessageId) -> Result<bool, DatabaseError> {
+        let row: Option<(i64,)> = sqlx::query_as("SELECT archive_message_id FROM pinarchive WHERE message_id = $1")
+            .bind(message_id.0 as i64)
+            .fetch_optional(&self.persistent_pool)
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn mark_pin_archived(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+        archive_message_id: MessageId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO pinarchive (message_id, guild_id, archive_message_id) VALUES ($1, $2, $3)
+            ON CONFLICT (message_id) DO NOTHING",
+        )
+        .bind(message_id.0 as i64)
+        .bind(guild_id.0 as i64)
+        .bind(archive_message_id.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use tracing::instrument;
+use twilight_model::id::GuildId;
+
+use super::DataStorage;
+use crate::core::profiles::ConfigProfile;
+use crate::error::DatabaseError;
+
+/// A profile that is applied for a limited time, with what to put back once the time is up.
+pub struct PendingRevert {
+    pub profile: String,
+    /// Unix timestamp.
+    pub revert_at: i64,
+    pub snapshot: ConfigProfile,
+}
+
+impl DataStorage {
+    /// Schedules the snapshot to be put back, replacing any revert that was pending for the guild.
+    #[instrument(skip_all, err)]
+    pub async fn schedule_profile_revert(
+        &self,
+        guild_id: GuildId,
+        profile: &str,
+        after: Duration,
+        snapshot: &ConfigProfile,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO profilerevert (guild_id, profile, revert_at, snapshot) VALUES ($1, $2, now() + make_interval(secs => $3), $4)
+            ON CONFLICT (guild_id) DO UPDATE SET profile = excluded.profile, revert_at = excluded.revert_at, snapshot = excluded.snapshot",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(profile)
+        .bind(after.as_secs() as f64)
+        .bind(serde_json::to_value(snapshot).map_err(DatabaseError::Serializing)?)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_profile_revert(&self, guild_id: GuildId) -> Result<Option<PendingRevert>, DatabaseError> {
+        let row: Option<(String, i64, serde_json::Value)> = sqlx::query_as(
+            "SELECT profile, extract(epoch from revert_at)::bigint, snapshot FROM profilerevert WHERE guild_id = $1",
+        )
+        .bind(guild_id.0 as i64)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        match row {
+            Some((profile, revert_at, snapshot)) => Ok(Some(PendingRevert {
+                profile,
+                revert_at,
+                snapshot: serde_json::from_value(snapshot).map_err(DatabaseError::Deserializing)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn cancel_profile_revert(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM profilerevert WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetches all guilds that have a profile to revert by now.
+    #[instrument(skip_all, err)]
+    pub async fn get_due_profile_reverts(&self) -> Result<Vec<GuildId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT guild_id FROM profilerevert WHERE revert_at <= now()")
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| GuildId(row.0 as u64)).collect())
+    }
+}
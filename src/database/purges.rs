@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use tracing::instrument;
+use twilight_model::id::GuildId;
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+/// Every statement needed to remove all data belonging to a guild, in an order that respects references.
+const PURGE_STATEMENTS: &[&str] = &[
+    "DELETE FROM attachment WHERE message_id IN (SELECT id FROM message WHERE guild_id = $1)",
+    "DELETE FROM message WHERE guild_id = $1",
+    "DELETE FROM archive WHERE guild_id = $1",
+    "DELETE FROM timedaction WHERE history_id IN (SELECT id FROM history WHERE guild_id = $1)",
+    "DELETE FROM history WHERE guild_id = $1",
+    "DELETE FROM customcommand WHERE guild_id = $1",
+    "DELETE FROM userlevel WHERE guild_id = $1",
+    "DELETE FROM profilerevert WHERE guild_id = $1",
+    "DELETE FROM voiceactivity WHERE guild_id = $1",
+    "DELETE FROM birthday WHERE guild_id = $1",
+    "DELETE FROM tempvoiceroom WHERE guild_id = $1",
+    "DELETE FROM guildstats WHERE guild_id = $1",
+    "DELETE FROM channelactivity WHERE guild_id = $1",
+    "DELETE FROM commandusage WHERE guild_id = $1",
+    "DELETE FROM channeltemplate WHERE guild_id = $1",
+    "DELETE FROM pinarchive WHERE guild_id = $1",
+    "DELETE FROM suggestion WHERE guild_id = $1",
+    "DELETE FROM ticket WHERE guild_id = $1",
+    "DELETE FROM eventrsvp WHERE guild_id = $1",
+    "DELETE FROM event WHERE guild_id = $1",
+    "DELETE FROM feedentry WHERE guild_id = $1",
+    "DELETE FROM feed WHERE guild_id = $1",
+    "DELETE FROM githubrepo WHERE guild_id = $1",
+    "DELETE FROM webhookendpoint WHERE guild_id = $1",
+    "DELETE FROM guildconfig WHERE id = $1",
+    "DELETE FROM guildpurge WHERE guild_id = $1",
+];
+
+impl DataStorage {
+    /// Schedules all data of a guild to be removed once the retention period has passed.
+    ///
+    /// Scheduling a guild that already has a pending purge restarts its retention period.
+    #[instrument(skip_all, err)]
+    pub async fn schedule_guild_purge(&self, guild_id: GuildId, retention: Duration) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO guildpurge (guild_id, purge_at) VALUES ($1, now() + make_interval(secs => $2))
+            ON CONFLICT (guild_id) DO UPDATE SET purge_at = excluded.purge_at",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(retention.as_secs() as f64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cancels a pending purge, returning if there was one.
+    #[instrument(skip_all, err)]
+    pub async fn cancel_guild_purge(&self, guild_id: GuildId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM guildpurge WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fetches all guilds whose retention period has run out.
+    #[instrument(skip_all, err)]
+    pub async fn get_due_guild_purges(&self) -> Result<Vec<GuildId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT guild_id FROM guildpurge WHERE purge_at <= now()")
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| GuildId(row.0 as u64)).collect())
+    }
+
+    /// Removes everything stored for a guild: its config, messages, archives, infractions, custom commands and levels.
+    ///
+    /// This happens in a single transaction so a failure halfway doesn't leave a guild without its encryption key.
+    #[instrument(skip_all, err)]
+    pub async fn purge_guild_data(&self, guild_id: GuildId) -> Result<(), DatabaseError> {
+        let mut transaction = self.persistent_pool.begin().await?;
+
+        for statement in PURGE_STATEMENTS {
+            sqlx::query(statement)
+                .bind(guild_id.0 as i64)
+                .execute(&mut transaction)
+                .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::commands::meta::nodes::GearBotPermissions;
+use crate::database::structures::{CommandUsage, GuildStatsDay};
 use serde::{Deserialize, Serialize};
 use twilight_model::id::{GuildId, UserId};
 use twilight_model::user::UserFlags;
@@ -10,11 +11,17 @@ pub struct ApiRequest {
     pub request: Request,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Request {
     TeamInfo,
     UserInfo(UserId),
     MutualGuilds(UserId),
+    ReloadBlocklist,
+    ClearConfigCache,
+    /// The statistics of the last this many days of a guild.
+    GuildStats(GuildId, u32),
+    /// How much every command was used in a guild over the last this many days.
+    CommandUsage(GuildId, u32),
 }
 
 impl Request {
@@ -23,6 +30,10 @@ impl Request {
             Request::TeamInfo => "Team info",
             Request::UserInfo { .. } => "User info",
             Request::MutualGuilds(_) => "User mutual guilds",
+            Request::ReloadBlocklist => "Blocklist reload",
+            Request::ClearConfigCache => "Config cache clear",
+            Request::GuildStats(..) => "Guild statistics",
+            Request::CommandUsage(..) => "Command usage",
         }
     }
 }
@@ -33,14 +44,19 @@ pub struct Reply {
     pub data: ReplyData,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum ReplyData {
     TeamInfo(TeamInfo),
     UserInfo(Option<UserInfo>),
     MutualGuildList(Vec<MinimalGuildInfo>),
+    /// How many users are on the blocklist after reloading it.
+    BlocklistReloaded(usize),
+    ConfigCacheCleared,
+    GuildStats(Vec<GuildStatsDay>),
+    CommandUsage(Vec<CommandUsage>),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TeamInfo {
     pub members: Vec<TeamMember>,
 }
@@ -57,7 +73,7 @@ pub struct RawTeamMember {
     pub socials: TeamSocials,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TeamMember {
     pub username: String,
     pub discriminator: String,
@@ -74,28 +90,28 @@ pub struct TeamSocials {
     pub website: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UserInfo {
     pub id: String,
     pub name: String,
     pub discriminator: String,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub avatar: Option<String>,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub bot_user: bool,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub system_user: bool,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub public_flags: Option<UserFlags>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MinimalGuildInfo {
     pub id: u64,
     pub name: String,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub icon: Option<String>,
-    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub owned: bool,
     pub permissions: GearBotPermissions,
 }
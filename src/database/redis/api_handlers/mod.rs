@@ -1,4 +1,35 @@
+use std::sync::Arc;
+
+use crate::core::guild_stats::STATS_RETENTION_DAYS;
+use crate::core::BotContext;
+use crate::error::ApiMessageError;
+use api_structs::{ReplyData, Request};
+
 pub mod api_structs;
 pub mod mutual_guilds;
 pub mod team_info;
 pub mod user_info;
+
+/// Answers a request, regardless of it coming from the api or another cluster.
+pub async fn handle_request(ctx: &Arc<BotContext>, request: Request) -> Result<ReplyData, ApiMessageError> {
+    match request {
+        Request::TeamInfo => team_info::get_team_info(ctx.clone()).await,
+        Request::UserInfo(user_id) => user_info::get_user_info(ctx, user_id).await,
+        Request::MutualGuilds(user_id) => mutual_guilds::get_mutual_guilds(ctx, &user_id).await,
+        Request::ReloadBlocklist => Ok(ReplyData::BlocklistReloaded(ctx.reload_blocklist().await?)),
+        Request::ClearConfigCache => {
+            ctx.clear_cached_configs().await;
+            Ok(ReplyData::ConfigCacheCleared)
+        }
+        Request::GuildStats(guild_id, days) => Ok(ReplyData::GuildStats(
+            ctx.datastore
+                .get_guild_stats(guild_id, days.min(STATS_RETENTION_DAYS))
+                .await?,
+        )),
+        Request::CommandUsage(guild_id, days) => Ok(ReplyData::CommandUsage(
+            ctx.datastore
+                .get_command_usage(guild_id, days.min(STATS_RETENTION_DAYS))
+                .await?,
+        )),
+    }
+}
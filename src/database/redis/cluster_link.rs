@@ -0,0 +1,206 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use twilight_model::id::GuildId;
+use uuid::Uuid;
+
+use super::api_handlers::api_structs::{ReplyData, Request};
+use super::api_handlers::handle_request;
+use super::Redis;
+use crate::cache::CachedGuild;
+use crate::core::BotContext;
+use crate::error::ApiCommunicaionError;
+use crate::gearbot_error;
+
+/// All clusters listen on this channel for requests from their siblings.
+const CLUSTER_REQUEST_CHANNEL: &str = "cluster-requests";
+/// Announcements of guilds that added the bot for the first time.
+const NEW_GUILD_CHANNEL: &str = "new-guilds";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClusterRequest {
+    pub uuid: Uuid,
+    pub origin: u64,
+    pub request: Request,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClusterReply {
+    pub uuid: Uuid,
+    pub cluster: u64,
+    pub data: ReplyData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewGuildNotification {
+    pub cluster: u64,
+    pub guild_id: GuildId,
+    pub name: String,
+    pub member_count: u64,
+}
+
+/// The answer of a single cluster, along with how long it took to arrive.
+pub struct ClusterResponse {
+    pub cluster: u64,
+    pub latency: Duration,
+    pub data: ReplyData,
+}
+
+fn reply_channel(cluster_id: u64) -> String {
+    format!("cluster-replies-{}", cluster_id)
+}
+
+impl Redis {
+    /// Listens for requests from other clusters and answers them.
+    pub async fn establish_cluster_link(&self, ctx: Arc<BotContext>) {
+        let con = match self.pool.spawn("cluster_requests").await {
+            Ok(con) => con,
+            Err(e) => {
+                gearbot_error!("Failed to establish the cluster request link: {}", e);
+                return;
+            }
+        };
+
+        let cluster_id = ctx.scheme_info.cluster_id;
+        log::debug!("establishing cluster request link");
+
+        con.subscribe(&[CLUSTER_REQUEST_CHANNEL])
+            .await
+            .unwrap()
+            .for_each(|message| async {
+                let content = message.message;
+                let message: ClusterRequest = match serde_json::from_slice(&content) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        gearbot_error!("Received an invalid request from another cluster: {}", e);
+                        return;
+                    }
+                };
+                log::debug!(
+                    "Received {} request from cluster {}",
+                    message.request.get_type(),
+                    message.origin
+                );
+
+                let request_type = message.request.get_type().to_string();
+                let data = match handle_request(&ctx, message.request).await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        gearbot_error!("Failed to handle a cluster request ({}): {}", request_type, e);
+                        return;
+                    }
+                };
+
+                let reply = ClusterReply {
+                    uuid: message.uuid,
+                    cluster: cluster_id,
+                    data,
+                };
+                if let Err(e) = self.publish(&reply_channel(message.origin), &reply).await {
+                    gearbot_error!("Failed to reply to cluster {}: {}", message.origin, e);
+                }
+            })
+            .await;
+    }
+
+    /// Listens for replies to requests this cluster sent out and routes them to whoever is waiting.
+    pub async fn establish_cluster_reply_link(&self, cluster_id: u64) {
+        let con = match self.pool.spawn("cluster_replies").await {
+            Ok(con) => con,
+            Err(e) => {
+                gearbot_error!("Failed to establish the cluster reply link: {}", e);
+                return;
+            }
+        };
+
+        log::debug!("establishing cluster reply link");
+
+        con.subscribe(&[reply_channel(cluster_id)])
+            .await
+            .unwrap()
+            .for_each(|message| async {
+                let content = message.message;
+                let reply: ClusterReply = match serde_json::from_slice(&content) {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        gearbot_error!("Received an invalid reply from another cluster: {}", e);
+                        return;
+                    }
+                };
+
+                // The request might already have timed out, in which case nobody cares anymore
+                if let Some(sender) = self.pending_cluster_requests.lock().await.get(&reply.uuid) {
+                    let _ = sender.send(reply);
+                }
+            })
+            .await;
+    }
+
+    /// Sends a request to every cluster (including this one) and collects the replies that arrive in time.
+    pub async fn query_clusters(
+        &self,
+        origin: u64,
+        request: Request,
+        cluster_count: u64,
+        max_wait: Duration,
+    ) -> Result<Vec<ClusterResponse>, ApiCommunicaionError> {
+        let uuid = Uuid::new_v4();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        self.pending_cluster_requests.lock().await.insert(uuid, sender);
+
+        let start = Instant::now();
+        let published = self
+            .publish(CLUSTER_REQUEST_CHANNEL, &ClusterRequest { uuid, origin, request })
+            .await;
+
+        let mut responses = vec![];
+        if published.is_ok() {
+            while (responses.len() as u64) < cluster_count {
+                let remaining = max_wait.checked_sub(start.elapsed()).unwrap_or_default();
+                match timeout(remaining, receiver.recv()).await {
+                    Ok(Some(reply)) => responses.push(ClusterResponse {
+                        cluster: reply.cluster,
+                        latency: start.elapsed(),
+                        data: reply.data,
+                    }),
+                    // Either timed out or the sender is gone, both mean no more replies are coming
+                    _ => break,
+                }
+            }
+        }
+
+        self.pending_cluster_requests.lock().await.remove(&uuid);
+        published?;
+
+        responses.sort_by_key(|response| response.cluster);
+        Ok(responses)
+    }
+
+    /// Lets the other clusters know a guild added the bot for the first time.
+    pub async fn announce_new_guild(&self, cluster: u64, guild: &CachedGuild) -> Result<(), ApiCommunicaionError> {
+        let notification = NewGuildNotification {
+            cluster,
+            guild_id: guild.id,
+            name: guild.name.clone(),
+            member_count: guild.member_count.load(Ordering::Relaxed),
+        };
+        self.publish(NEW_GUILD_CHANNEL, &notification).await
+    }
+
+    async fn publish<T: Serialize>(&self, channel: &str, message: &T) -> Result<(), ApiCommunicaionError> {
+        self.pool
+            .get()
+            .await
+            .publish(
+                channel,
+                serde_json::to_string(message).map_err(ApiCommunicaionError::Serializing)?,
+            )
+            .await?;
+        Ok(())
+    }
+}
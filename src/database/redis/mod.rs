@@ -1,23 +1,28 @@
-use darkredis::ConnectionPool;
+use darkredis::{Command, ConnectionPool};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::core::BotContext;
-use crate::database::redis::api_handlers::api_structs::{ApiRequest, Reply, Request};
-use crate::database::redis::api_handlers::mutual_guilds::get_mutual_guilds;
-use crate::database::redis::api_handlers::team_info::get_team_info;
-use crate::database::redis::api_handlers::user_info::get_user_info;
+use crate::database::redis::api_handlers::api_structs::{ApiRequest, Reply};
+use crate::database::redis::api_handlers::handle_request;
+use crate::database::redis::cluster_link::ClusterReply;
 use crate::error::{ApiCommunicaionError, DatabaseError};
 use crate::gearbot_error;
 use futures_util::StreamExt;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::{mpsc::UnboundedSender, Mutex};
+use tracing::instrument;
+use uuid::Uuid;
 
 pub mod api_handlers;
+pub mod cluster_link;
 
 /// An abstraction layer around a connection to Redis.
 ///
 /// All interactions with Redis should go through this.
 pub struct Redis {
     pool: ConnectionPool,
+    pending_cluster_requests: Mutex<HashMap<Uuid, UnboundedSender<ClusterReply>>>,
 }
 
 impl Redis {
@@ -26,12 +31,16 @@ impl Redis {
     /// 5 connections are opened by default.
     pub async fn new(conn_addr: &str) -> Result<Self, darkredis::Error> {
         let pool = ConnectionPool::create(conn_addr.to_owned(), None, 5).await?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            pending_cluster_requests: Mutex::new(HashMap::new()),
+        })
     }
 
     /// Retrieves a value from Redis.
     ///
     /// Returns `None` if the key didn't exist.
+    #[instrument(skip(self), err)]
     pub async fn get<D: DeserializeOwned>(&self, key: &str) -> Result<Option<D>, DatabaseError> {
         let mut conn = self.pool.get().await;
 
@@ -46,6 +55,7 @@ impl Redis {
     /// Inserts a value into Redis.
     ///
     /// The value will automatically expire at the optionally provided time.
+    #[instrument(skip(self, value), err)]
     pub async fn set<T: Serialize>(&self, key: &str, value: &T, expiry: Option<u32>) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await;
 
@@ -59,7 +69,71 @@ impl Redis {
         Ok(())
     }
 
+    /// Counts a hit on the key and returns the hits so far, the count starts over once the window of the first hit
+    /// is over.
+    #[instrument(skip(self), err)]
+    pub async fn count_hit(&self, key: &str, window: u32) -> Result<isize, DatabaseError> {
+        let mut conn = self.pool.get().await;
+
+        let hits = conn.incr(key).await?;
+        if hits == 1 {
+            conn.expire_seconds(key, window).await?;
+        }
+
+        Ok(hits)
+    }
+
+    /// Pushes a value to the front of a list, only the newest `max_len` values are kept.
+    ///
+    /// The whole list expires at the provided time after the last push.
+    #[instrument(skip(self, value), err)]
+    pub async fn push_capped<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        max_len: usize,
+        expiry: u32,
+    ) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await;
+
+        let data = serde_json::to_string(value).map_err(DatabaseError::Serializing)?;
+        conn.lpush(key, data).await?;
+        conn.ltrim(key, 0, max_len - 1).await?;
+        conn.expire_seconds(key, expiry).await?;
+
+        Ok(())
+    }
+
+    /// Retrieves the value at the front of a list without taking it out.
+    ///
+    /// Returns `None` if the list is empty or didn't exist.
+    #[instrument(skip(self), err)]
+    pub async fn list_front<D: DeserializeOwned>(&self, key: &str) -> Result<Option<D>, DatabaseError> {
+        let mut conn = self.pool.get().await;
+
+        match conn.lrange(key, 0, 0).await?.first() {
+            Some(value) => {
+                let value = serde_json::from_slice(value).map_err(DatabaseError::Deserializing)?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the first occurrence of a value from a list, wherever it ended up.
+    #[instrument(skip(self, value), err)]
+    pub async fn list_remove<T: Serialize>(&self, key: &str, value: &T) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await;
+
+        let data = serde_json::to_string(value).map_err(DatabaseError::Serializing)?;
+        conn.run_command(Command::new("LREM").arg(&key).arg(&"1").arg(&data))
+            .await?;
+
+        Ok(())
+    }
+
     /// Deletes a value from Redis.
+    #[instrument(skip(self), err)]
     pub async fn delete(&self, key: &str) -> Result<(), darkredis::Error> {
         let mut conn = self.pool.get().await;
 
@@ -88,11 +162,8 @@ impl Redis {
                 let message: ApiRequest = serde_json::from_slice(&content).unwrap();
                 log::debug!("Received {} request from the api", message.request.get_type());
 
-                let result = match message.request {
-                    Request::TeamInfo => get_team_info(ctx.clone()).await,
-                    Request::UserInfo(user_id) => get_user_info(&ctx, user_id).await,
-                    Request::MutualGuilds(user_id) => get_mutual_guilds(&ctx, &user_id).await,
-                };
+                let request_type = message.request.get_type().to_string();
+                let result = handle_request(&ctx, message.request).await;
 
                 match result {
                     Ok(data) => {
@@ -107,11 +178,7 @@ impl Redis {
                             Err(e) => gearbot_error!("Failed to send message to the api: {}", e),
                         }
                     }
-                    Err(e) => gearbot_error!(
-                        "Failed to handle a message from the api ({}): {}",
-                        message.request.get_type(),
-                        e
-                    ),
+                    Err(e) => gearbot_error!("Failed to handle a message from the api ({}): {}", request_type, e),
                 }
             })
             .await;
@@ -0,0 +1,56 @@
+use tracing::instrument;
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+/// What a pruning pass removed.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Pruned {
+    pub rows: u64,
+    /// The space the removed rows took up, as reported by the database.
+    pub bytes: u64,
+}
+
+/// Removes archives older than the retention period of their guild.
+const PRUNE_ARCHIVES: &str = "WITH pruned AS (
+    DELETE FROM archive USING guildconfig
+    WHERE archive.guild_id = guildconfig.id
+        AND guildconfig.config->>'archive_retention_days' IS NOT NULL
+        AND archive.created < now() - make_interval(days => (guildconfig.config->>'archive_retention_days')::int)
+    RETURNING octet_length(archive.encrypted_content) AS size
+)
+SELECT count(*), coalesce(sum(size), 0)::bigint FROM pruned";
+
+/// Removes attachments older than the retention period of their guild, attachments don't have a timestamp of
+/// their own so the one in the snowflake of their message is used.
+const PRUNE_ATTACHMENTS: &str = "WITH pruned AS (
+    DELETE FROM attachment USING message, guildconfig
+    WHERE attachment.message_id = message.id
+        AND message.guild_id = guildconfig.id
+        AND guildconfig.config->>'attachment_retention_days' IS NOT NULL
+        AND to_timestamp(((attachment.message_id >> 22) + 1420070400000) / 1000.0)
+            < now() - make_interval(days => (guildconfig.config->>'attachment_retention_days')::int)
+    RETURNING pg_column_size(attachment.*) AS size
+)
+SELECT count(*), coalesce(sum(size), 0)::bigint FROM pruned";
+
+impl DataStorage {
+    #[instrument(skip_all, err)]
+    pub async fn prune_expired_archives(&self) -> Result<Pruned, DatabaseError> {
+        self.prune(PRUNE_ARCHIVES).await
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn prune_expired_attachments(&self) -> Result<Pruned, DatabaseError> {
+        self.prune(PRUNE_ATTACHMENTS).await
+    }
+
+    async fn prune(&self, statement: &str) -> Result<Pruned, DatabaseError> {
+        let (rows, bytes): (i64, i64) = sqlx::query_as(statement).fetch_one(&self.persistent_pool).await?;
+
+        Ok(Pruned {
+            rows: rows as u64,
+            bytes: bytes as u64,
+        })
+    }
+}
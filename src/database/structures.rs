@@ -1,5 +1,6 @@
+use serde::{Deserialize, Serialize};
 use twilight_model::channel::message::MessageType;
-use twilight_model::id::{ChannelId, GuildId, UserId};
+use twilight_model::id::{ChannelId, GuildId, MessageId, UserId};
 
 #[derive(Debug)]
 pub struct UserMessage {
@@ -11,6 +12,17 @@ pub struct UserMessage {
     pub pinned: bool,
 }
 
+/// Narrows down which archived messages get looked at, the content itself can only be checked after decrypting.
+#[derive(Debug, Default)]
+pub struct MessageSearch {
+    pub author: Option<UserId>,
+    pub channel: Option<ChannelId>,
+    /// Only messages newer than this one.
+    pub after: Option<MessageId>,
+    /// Only messages older than this one.
+    pub before: Option<MessageId>,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub(super) struct StoredUserMessage {
     pub id: i64,
@@ -24,10 +36,136 @@ pub(super) struct StoredUserMessage {
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct StoredAttachment {
-    id: i64,
-    filename: String,
-    is_image: bool,
-    message_id: i64,
+    pub id: i64,
+    #[sqlx(rename = "name")]
+    pub filename: String,
+    #[sqlx(rename = "image")]
+    pub is_image: bool,
+    pub message_id: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredUserLevel {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub xp: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredBirthday {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub month: i16,
+    pub day: i16,
+    pub announced_year: Option<i32>,
+    pub role_given: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredSuggestion {
+    pub guild_id: i64,
+    pub id: i32,
+    pub author_id: i64,
+    pub channel_id: i64,
+    /// Only missing while it's being posted.
+    pub message_id: Option<i64>,
+    pub status: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredTicket {
+    pub guild_id: i64,
+    pub id: i32,
+    pub owner_id: i64,
+    /// Only missing while the channel is being created.
+    pub channel_id: Option<i64>,
+    pub claimed_by: Option<i64>,
+    pub closed: bool,
+    pub transcript_id: Option<i64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredEvent {
+    pub guild_id: i64,
+    pub id: i32,
+    pub channel_id: i64,
+    /// Only missing while it's being posted.
+    pub message_id: Option<i64>,
+    pub creator_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    /// Unix timestamp.
+    pub starts_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredRsvp {
+    pub user_id: i64,
+    pub status: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredFeed {
+    pub guild_id: i64,
+    pub id: i32,
+    pub channel_id: i64,
+    pub url: String,
+    /// What the feed calls itself, if it does.
+    pub title: Option<String>,
+    pub template: String,
+    pub interval_minutes: i32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredGithubRepo {
+    pub guild_id: i64,
+    pub repository: String,
+    pub channel_id: i64,
+    pub events: Vec<String>,
+    pub secret: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredWebhookEndpoint {
+    pub guild_id: i64,
+    pub id: i32,
+    pub name: String,
+    pub channel_id: i64,
+    pub template: String,
+}
+
+/// The statistics of a guild for a single day.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct GuildStatsDay {
+    /// The start of the day in UTC, as a unix timestamp.
+    pub day: i64,
+    pub messages: i32,
+    pub joins: i32,
+    pub leaves: i32,
+    pub commands: i32,
+    pub automod_triggers: i32,
+}
+
+/// How much a command was used in a guild over a period.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct CommandUsage {
+    /// The full command, like ``config validate``.
+    pub command: String,
+    pub uses: i64,
+    pub failures: i64,
+    /// In how many different channels it was used.
+    pub channels: i64,
+    /// The day it was last used, as a unix timestamp.
+    pub last_used: i64,
+}
+
+/// Messages sent in a channel during an hour.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ChannelActivityHour {
+    pub channel_id: i64,
+    /// The start of the hour in UTC, as a unix timestamp.
+    pub hour: i64,
+    pub messages: i32,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -0,0 +1,82 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId, MessageId, UserId};
+
+use super::structures::StoredSuggestion;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Claims the next suggestion number of the guild, the message is added once it's posted.
+    #[instrument(skip_all, err)]
+    pub async fn create_suggestion(
+        &self,
+        guild_id: GuildId,
+        author_id: UserId,
+        channel_id: ChannelId,
+    ) -> Result<i32, DatabaseError> {
+        let (id,): (i32,) = sqlx::query_as(
+            "INSERT INTO suggestion (guild_id, id, author_id, channel_id)
+            SELECT $1, coalesce(max(id), 0) + 1, $2, $3 FROM suggestion WHERE guild_id = $1
+            RETURNING id",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(author_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_suggestion_message(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        message_id: MessageId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE suggestion SET message_id = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(message_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gives the number back when the suggestion couldn't be posted.
+    #[instrument(skip_all, err)]
+    pub async fn remove_suggestion(&self, guild_id: GuildId, id: i32) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM suggestion WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_suggestion(&self, guild_id: GuildId, id: i32) -> Result<Option<StoredSuggestion>, DatabaseError> {
+        let suggestion = sqlx::query_as("SELECT * FROM suggestion WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .fetch_optional(&self.persistent_pool)
+            .await?;
+
+        Ok(suggestion)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_suggestion_status(&self, guild_id: GuildId, id: i32, status: &str) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE suggestion SET status = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(status)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    #[instrument(skip_all, err)]
+    pub async fn insert_temp_room(
+        &self,
+        channel_id: ChannelId,
+        guild_id: GuildId,
+        owner: UserId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("INSERT INTO tempvoiceroom (channel_id, guild_id, owner_id) VALUES ($1, $2, $3)")
+            .bind(channel_id.0 as i64)
+            .bind(guild_id.0 as i64)
+            .bind(owner.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetches the temporary voice channels of a guild.
+    #[instrument(skip_all, err)]
+    pub async fn get_temp_rooms(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT channel_id FROM tempvoiceroom WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| ChannelId(row.0 as u64)).collect())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn remove_temp_room(&self, channel_id: ChannelId) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM tempvoiceroom WHERE channel_id = $1")
+            .bind(channel_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+use super::structures::StoredTicket;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+const TICKET_COLUMNS: &str =
+    "guild_id, id, owner_id, channel_id, claimed_by, closed_at IS NOT NULL AS closed, transcript_id";
+
+impl DataStorage {
+    /// Claims the next ticket number of the guild, the channel is added once it's created.
+    #[instrument(skip_all, err)]
+    pub async fn create_ticket(&self, guild_id: GuildId, owner: UserId) -> Result<i32, DatabaseError> {
+        let (id,): (i32,) = sqlx::query_as(
+            "INSERT INTO ticket (guild_id, id, owner_id)
+            SELECT $1, coalesce(max(id), 0) + 1, $2 FROM ticket WHERE guild_id = $1
+            RETURNING id",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(owner.0 as i64)
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_ticket_channel(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        channel_id: ChannelId,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE ticket SET channel_id = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(channel_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gives the number back when the channel couldn't be created.
+    #[instrument(skip_all, err)]
+    pub async fn remove_ticket(&self, guild_id: GuildId, id: i32) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM ticket WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_ticket(&self, guild_id: GuildId, id: i32) -> Result<Option<StoredTicket>, DatabaseError> {
+        let ticket = sqlx::query_as(&format!(
+            "SELECT {} FROM ticket WHERE guild_id = $1 AND id = $2",
+            TICKET_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(id)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(ticket)
+    }
+
+    /// The open ticket that lives in this channel, if it is one.
+    #[instrument(skip_all, err)]
+    pub async fn get_channel_ticket(&self, channel_id: ChannelId) -> Result<Option<StoredTicket>, DatabaseError> {
+        let ticket = sqlx::query_as(&format!(
+            "SELECT {} FROM ticket WHERE channel_id = $1 AND closed_at IS NULL",
+            TICKET_COLUMNS
+        ))
+        .bind(channel_id.0 as i64)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(ticket)
+    }
+
+    /// The tickets a member has open, including any that are still getting their channel.
+    #[instrument(skip_all, err)]
+    pub async fn get_open_tickets(&self, guild_id: GuildId, owner: UserId) -> Result<Vec<StoredTicket>, DatabaseError> {
+        let tickets = sqlx::query_as(&format!(
+            "SELECT {} FROM ticket WHERE guild_id = $1 AND owner_id = $2 AND closed_at IS NULL ORDER BY id",
+            TICKET_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(owner.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(tickets)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn claim_ticket(&self, guild_id: GuildId, id: i32, staff: UserId) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE ticket SET claimed_by = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(staff.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tickets whose channel got deleted by hand count as closed, there is nothing left to make a transcript of.
+    #[instrument(skip_all, err)]
+    pub async fn close_channel_ticket(&self, channel_id: ChannelId) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE ticket SET closed_at = now() WHERE channel_id = $1 AND closed_at IS NULL")
+            .bind(channel_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// `transcript_id` is the archive the transcript was stored as, if one was made.
+    #[instrument(skip_all, err)]
+    pub async fn close_ticket(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        transcript_id: Option<u64>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE ticket SET closed_at = now(), transcript_id = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(transcript_id.map(|id| id as i64))
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::DataStorage;
+use crate::core::Tier;
+use crate::error::DatabaseError;
+
+impl DataStorage {
+    /// Fetches the tier of a guild, guilds that were never granted one are on the free tier.
+    #[instrument(skip_all, err)]
+    pub async fn get_guild_tier(&self, guild_id: GuildId) -> Result<Tier, DatabaseError> {
+        let row: Option<(i16,)> = sqlx::query_as("SELECT tier FROM guildtier WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .fetch_optional(&self.persistent_pool)
+            .await?;
+
+        Ok(row.map(|(tier,)| Tier::from_id(tier)).unwrap_or(Tier::Free))
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_guild_tier(&self, guild_id: GuildId, tier: Tier, granted_by: UserId) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO guildtier (guild_id, tier, granted_by) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id) DO UPDATE SET tier = excluded.tier, granted_by = excluded.granted_by, granted_at = now()",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(tier.id())
+        .bind(granted_by.0 as i64)
+        .execute(&self.persistent_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Puts a guild back on the free tier, returning if it had another one.
+    #[instrument(skip_all, err)]
+    pub async fn remove_guild_tier(&self, guild_id: GuildId) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM guildtier WHERE guild_id = $1")
+            .bind(guild_id.0 as i64)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tracing::instrument;
+use twilight_model::id::{GuildId, UserId};
+
+use super::structures::StoredUserMessage;
+use super::{decrypt_message, DataStorage};
+use crate::error::DatabaseError;
+
+/// Everything stored about a single user, in the shape it gets handed to them.
+#[derive(Debug, Serialize)]
+pub struct UserDataExport {
+    pub user_id: UserId,
+    pub infractions: Vec<ExportedInfraction>,
+    pub levels: Vec<ExportedLevel>,
+    pub birthdays: Vec<ExportedBirthday>,
+    pub suggestions: Vec<ExportedSuggestion>,
+    pub rsvps: Vec<ExportedRsvp>,
+    pub tickets: Vec<ExportedTicket>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+/// An infraction or note about the user.
+///
+/// The moderator who handed it out is left out, that's their personal data and not the target's.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportedInfraction {
+    pub id: i32,
+    pub guild_id: i64,
+    pub kind: String,
+    pub start: i64,
+    pub end: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportedLevel {
+    pub guild_id: i64,
+    pub xp: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportedBirthday {
+    pub guild_id: i64,
+    pub month: i16,
+    pub day: i16,
+}
+
+/// The suggestion itself only lives in the message it was posted as.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportedSuggestion {
+    pub guild_id: i64,
+    pub id: i32,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportedRsvp {
+    pub guild_id: i64,
+    pub event_id: i32,
+    pub status: String,
+}
+
+/// A ticket the user opened, with the transcript of it if one was stored.
+///
+/// Like with infractions, the staff member who claimed it is left out.
+#[derive(Debug, Serialize)]
+pub struct ExportedTicket {
+    pub guild_id: i64,
+    pub id: i32,
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+    pub transcript: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedMessage {
+    pub id: u64,
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub content: String,
+    pub attachments: Vec<String>,
+}
+
+/// What was removed when a user asked for their data to be deleted.
+#[derive(Debug, Default)]
+pub struct UserDataDeletion {
+    pub messages: u64,
+    pub levels: u64,
+    pub infractions: u64,
+    /// Infractions that are still within the retention period of their guild.
+    pub kept_infractions: u64,
+}
+
+impl DataStorage {
+    /// Collects the infractions, levels, birthdays, suggestions, event RSVPs, tickets and logged messages of a user
+    /// across all guilds.
+    #[instrument(skip_all, err)]
+    pub async fn get_user_data(&self, user_id: UserId) -> Result<UserDataExport, DatabaseError> {
+        let infractions = sqlx::query_as(
+            "SELECT id, guild_id, type::text AS kind, extract(epoch from start)::bigint AS start,
+            extract(epoch from \"end\")::bigint AS \"end\"
+            FROM history WHERE user_id = $1 ORDER BY start",
+        )
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        let levels = sqlx::query_as("SELECT guild_id, xp FROM userlevel WHERE user_id = $1 ORDER BY guild_id")
+            .bind(user_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        let birthdays =
+            sqlx::query_as("SELECT guild_id, month, day FROM birthday WHERE user_id = $1 ORDER BY guild_id")
+                .bind(user_id.0 as i64)
+                .fetch_all(&self.persistent_pool)
+                .await?;
+
+        let suggestions =
+            sqlx::query_as("SELECT guild_id, id, status FROM suggestion WHERE author_id = $1 ORDER BY guild_id, id")
+                .bind(user_id.0 as i64)
+                .fetch_all(&self.persistent_pool)
+                .await?;
+
+        let rsvps = sqlx::query_as(
+            "SELECT guild_id, event_id, status FROM eventrsvp WHERE user_id = $1 ORDER BY guild_id, event_id",
+        )
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        let ticket_rows: Vec<(i64, i32, i64, Option<i64>, Option<i64>)> = sqlx::query_as(
+            "SELECT guild_id, id, extract(epoch from opened_at)::bigint, extract(epoch from closed_at)::bigint,
+            transcript_id FROM ticket WHERE owner_id = $1 ORDER BY guild_id, id",
+        )
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        let mut tickets = Vec::with_capacity(ticket_rows.len());
+        for (guild_id, id, opened_at, closed_at, transcript_id) in ticket_rows {
+            let transcript = match transcript_id {
+                Some(transcript_id) => self.get_archive(transcript_id as u64, GuildId(guild_id as u64)).await?,
+                None => None,
+            };
+            tickets.push(ExportedTicket {
+                guild_id,
+                id,
+                opened_at,
+                closed_at,
+                transcript,
+            });
+        }
+
+        let stored_messages: Vec<StoredUserMessage> =
+            sqlx::query_as("SELECT * FROM message WHERE author_id = $1 ORDER BY id")
+                .bind(user_id.0 as i64)
+                .fetch_all(&self.persistent_pool)
+                .await?;
+
+        let attachment_rows: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT message_id, name FROM attachment
+            WHERE message_id IN (SELECT id FROM message WHERE author_id = $1)",
+        )
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        let mut attachments: HashMap<i64, Vec<String>> = HashMap::new();
+        for (message_id, name) in attachment_rows {
+            attachments.entry(message_id).or_default().push(name);
+        }
+
+        // Every guild has its own key, fetch each one only once
+        let mut keys = HashMap::new();
+        let mut messages = Vec::with_capacity(stored_messages.len());
+        for stored in stored_messages {
+            let guild_id = GuildId(stored.guild_id as u64);
+            if !keys.contains_key(&guild_id) {
+                keys.insert(guild_id, self.get_guild_encryption_key(guild_id).await?);
+            }
+
+            let id = stored.id;
+            let message = decrypt_message(stored, &keys[&guild_id]);
+            messages.push(ExportedMessage {
+                id: id as u64,
+                guild_id: message.guild.0,
+                channel_id: message.channel.0,
+                content: message.content,
+                attachments: attachments.remove(&id).unwrap_or_default(),
+            });
+        }
+
+        Ok(UserDataExport {
+            user_id,
+            infractions,
+            levels,
+            birthdays,
+            suggestions,
+            rsvps,
+            tickets,
+            messages,
+        })
+    }
+
+    /// Lists the guilds that have infractions on record for this user.
+    #[instrument(skip_all, err)]
+    pub async fn get_infraction_guilds(&self, user_id: UserId) -> Result<Vec<GuildId>, DatabaseError> {
+        let rows: Vec<(i64,)> = sqlx::query_as("SELECT DISTINCT guild_id FROM history WHERE user_id = $1")
+            .bind(user_id.0 as i64)
+            .fetch_all(&self.persistent_pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| GuildId(row.0 as u64)).collect())
+    }
+
+    /// Removes the levels, birthdays, suggestions, event RSVPs, voice activity, closed tickets (transcripts included)
+    /// and logged messages of a user, along with the infractions that are past their retention.
+    ///
+    /// `retention` holds the retention period in days for every guild with infractions on record, guilds without a
+    /// period keep them indefinitely. Infractions that are still running (like a temporary ban) are always kept, as
+    /// are tickets that are still open since their channel is still in use.
+    #[instrument(skip_all, err)]
+    pub async fn delete_user_data(
+        &self,
+        user_id: UserId,
+        retention: &[(GuildId, Option<u32>)],
+    ) -> Result<UserDataDeletion, DatabaseError> {
+        let user = user_id.0 as i64;
+        let mut deletion = UserDataDeletion::default();
+        let mut transaction = self.persistent_pool.begin().await?;
+
+        sqlx::query("DELETE FROM attachment WHERE message_id IN (SELECT id FROM message WHERE author_id = $1)")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+        deletion.messages = sqlx::query("DELETE FROM message WHERE author_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?
+            .rows_affected();
+        deletion.levels = sqlx::query("DELETE FROM userlevel WHERE user_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?
+            .rows_affected();
+        sqlx::query("DELETE FROM voiceactivity WHERE user_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM birthday WHERE user_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM suggestion WHERE author_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM eventrsvp WHERE user_id = $1")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query(
+            "DELETE FROM archive USING ticket
+            WHERE archive.id = ticket.transcript_id AND archive.guild_id = ticket.guild_id
+                AND ticket.owner_id = $1 AND ticket.closed_at IS NOT NULL",
+        )
+        .bind(user)
+        .execute(&mut transaction)
+        .await?;
+        sqlx::query("DELETE FROM ticket WHERE owner_id = $1 AND closed_at IS NOT NULL")
+            .bind(user)
+            .execute(&mut transaction)
+            .await?;
+
+        for (guild_id, days) in retention {
+            if let Some(days) = days {
+                deletion.infractions += sqlx::query(
+                    "DELETE FROM history WHERE user_id = $1 AND guild_id = $2
+                    AND start < now() - make_interval(days => $3)
+                    AND id NOT IN (SELECT history_id FROM timedaction)",
+                )
+                .bind(user)
+                .bind(guild_id.0 as i64)
+                .bind(*days as i32)
+                .execute(&mut transaction)
+                .await?
+                .rows_affected();
+            }
+        }
+
+        let kept: (i64,) = sqlx::query_as("SELECT count(*) FROM history WHERE user_id = $1")
+            .bind(user)
+            .fetch_one(&mut transaction)
+            .await?;
+        deletion.kept_infractions = kept.0 as u64;
+
+        transaction.commit().await?;
+        Ok(deletion)
+    }
+}
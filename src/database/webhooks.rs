@@ -0,0 +1,113 @@
+use tracing::instrument;
+use twilight_model::id::{ChannelId, GuildId};
+
+use super::structures::StoredWebhookEndpoint;
+use super::DataStorage;
+use crate::error::DatabaseError;
+
+const ENDPOINT_COLUMNS: &str = "guild_id, id, name, channel_id, template";
+
+impl DataStorage {
+    /// Claims the next endpoint number of the guild.
+    #[instrument(skip_all, err)]
+    pub async fn create_webhook_endpoint(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        channel_id: ChannelId,
+        template: &str,
+        token_hash: &str,
+    ) -> Result<StoredWebhookEndpoint, DatabaseError> {
+        let endpoint = sqlx::query_as(&format!(
+            "INSERT INTO webhookendpoint (guild_id, id, name, channel_id, template, token_hash)
+            SELECT $1, coalesce(max(id), 0) + 1, $2, $3, $4, $5
+            FROM webhookendpoint WHERE guild_id = $1
+            RETURNING {}",
+            ENDPOINT_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(channel_id.0 as i64)
+        .bind(template)
+        .bind(token_hash)
+        .fetch_one(&self.persistent_pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn remove_webhook_endpoint(&self, guild_id: GuildId, id: i32) -> Result<bool, DatabaseError> {
+        let removed = sqlx::query("DELETE FROM webhookendpoint WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn get_webhook_endpoints(&self, guild_id: GuildId) -> Result<Vec<StoredWebhookEndpoint>, DatabaseError> {
+        let endpoints = sqlx::query_as(&format!(
+            "SELECT {} FROM webhookendpoint WHERE guild_id = $1 ORDER BY id",
+            ENDPOINT_COLUMNS
+        ))
+        .bind(guild_id.0 as i64)
+        .fetch_all(&self.persistent_pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// The endpoint a delivery is for, tokens are unique across all guilds.
+    #[instrument(skip_all, err)]
+    pub async fn get_webhook_endpoint_by_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<StoredWebhookEndpoint>, DatabaseError> {
+        let endpoint = sqlx::query_as(&format!(
+            "SELECT {} FROM webhookendpoint WHERE token_hash = $1",
+            ENDPOINT_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.persistent_pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_webhook_endpoint_template(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        template: &str,
+    ) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query("UPDATE webhookendpoint SET template = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(template)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
+    #[instrument(skip_all, err)]
+    pub async fn set_webhook_endpoint_token(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        token_hash: &str,
+    ) -> Result<bool, DatabaseError> {
+        let updated = sqlx::query("UPDATE webhookendpoint SET token_hash = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .bind(token_hash)
+            .execute(&self.persistent_pool)
+            .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+}
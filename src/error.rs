@@ -3,13 +3,16 @@ use std::{error, fmt, io};
 use std::fmt::Formatter;
 use twilight_embed_builder::{
     EmbedAuthorNameError, EmbedBuildError, EmbedColorError, EmbedDescriptionError, EmbedFieldError,
-    EmbedFooterTextError, ImageSourceUrlError,
+    EmbedFooterTextError, EmbedTitleError, ImageSourceUrlError,
 };
 use twilight_gateway::cluster::{ClusterCommandError, ClusterStartError};
 use twilight_gateway::{cluster, shard};
 use twilight_http::request::channel::message::create_message::CreateMessageError;
 use twilight_http::request::channel::message::update_message::UpdateMessageError;
-use twilight_model::id::{ChannelId, GuildId, UserId};
+use twilight_http::request::AuditLogReasonError;
+use twilight_model::id::{ChannelId, GuildId, RoleId, UserId};
+
+use crate::core::Tier;
 
 pub type CommandResult = Result<(), CommandError>;
 
@@ -121,6 +124,7 @@ pub enum MessageError {
     EmbedAuthorName(EmbedAuthorNameError),
     ImageSourceUrl(ImageSourceUrlError),
     EmbedFooter(EmbedFooterTextError),
+    EmbedTitle(EmbedTitleError),
 }
 
 impl error::Error for MessageError {}
@@ -137,6 +141,7 @@ impl fmt::Display for MessageError {
             MessageError::EmbedAuthorName(e) => write!(f, "Failed to set embed author name: {}", e),
             MessageError::ImageSourceUrl(e) => write!(f, "Failed to set embed image url: {}", e),
             MessageError::EmbedFooter(e) => write!(f, "Failed to set embed footer: {}", e),
+            MessageError::EmbedTitle(e) => write!(f, "Failed to set embed title: {}", e),
         }
     }
 }
@@ -162,6 +167,73 @@ impl fmt::Display for DatabaseError {
     }
 }
 
+#[derive(Debug)]
+pub enum BackupError {
+    NotConfigured,
+    Io(io::Error),
+    Database(DatabaseError),
+    Serializing(serde_json::Error),
+    /// The file is damaged or was encrypted with a different main key.
+    Corrupted,
+}
+
+impl error::Error for BackupError {}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::NotConfigured => write!(f, "Backups are not configured"),
+            BackupError::Io(e) => write!(f, "Failed to access the backup file: {}", e),
+            BackupError::Database(e) => write!(f, "Database failure during backup: {}", e),
+            BackupError::Serializing(e) => write!(f, "Failed to (de)serialize the backup: {}", e),
+            BackupError::Corrupted => write!(f, "The backup is corrupted or was made with a different key"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClassifierError {
+    Request(reqwest::Error),
+    /// The service answered with something else than what it was asked for.
+    InvalidResponse(serde_json::Error),
+}
+
+impl error::Error for ClassifierError {}
+
+impl fmt::Display for ClassifierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassifierError::Request(e) => write!(f, "Failed to reach the classification service: {}", e),
+            ClassifierError::InvalidResponse(e) => {
+                write!(f, "The classification service sent an invalid response: {}", e)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FeedError {
+    Request(reqwest::Error),
+    /// Not an http(s) url, or one pointing at something that isn't on the public internet.
+    InvalidUrl,
+    TooLarge,
+    /// The url works, but what is behind it isn't an RSS or Atom feed.
+    NotAFeed,
+}
+
+impl error::Error for FeedError {}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedError::Request(e) => write!(f, "Failed to fetch the feed: {}", e),
+            FeedError::InvalidUrl => write!(f, "The feed url is not allowed"),
+            FeedError::TooLarge => write!(f, "The feed is too large"),
+            FeedError::NotAFeed => write!(f, "The url doesn't point to an RSS or Atom feed"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum EmojiError {
     UnknownEmoji(String),
@@ -198,6 +270,10 @@ impl fmt::Display for ApiMessageError {
 pub enum CommandError {
     NoDM,
     InvalidPermissions,
+    /// The guild needs at least this tier for what was asked.
+    MissingTier(Tier),
+    /// A role change that isn't safe for us to make.
+    RoleGuard(RoleGuardError),
     ParseError(ParseError),
     OtherFailure(OtherFailure),
 }
@@ -209,12 +285,48 @@ impl fmt::Display for CommandError {
         match self {
             CommandError::NoDM => write!(f, "You can not use this command in DMs"),
             CommandError::InvalidPermissions => write!(f, "You don't have the permissions to run this command!"),
+            CommandError::MissingTier(tier) => write!(f, "This server needs the {} tier for that", tier),
+            CommandError::RoleGuard(e) => write!(f, "{}", e),
             CommandError::ParseError(e) => write!(f, "Failed to parse the command arguments!\n``{}``", e),
             CommandError::OtherFailure(_) => write!(f, "Unexpected error while executing the command, please report this on the support server if it keeps happening"),
         }
     }
 }
 
+#[derive(Debug)]
+pub enum RoleGuardError {
+    MissingManageRoles,
+    UnknownRole(RoleId),
+    Everyone,
+    Managed(RoleId),
+    AboveBot(RoleId),
+    /// Not below the highest role of the moderator that asked for it.
+    AboveActor(RoleId),
+    Elevated(RoleId),
+    Twilight(twilight_http::Error),
+}
+
+impl error::Error for RoleGuardError {}
+
+impl fmt::Display for RoleGuardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RoleGuardError::MissingManageRoles => write!(f, "I need the manage roles permission for that"),
+            RoleGuardError::UnknownRole(id) => write!(f, "The role ``{}`` doesn't exist (anymore)", id),
+            RoleGuardError::Everyone => write!(f, "The everyone role can't be added or removed"),
+            RoleGuardError::Managed(id) => write!(f, "The role ``{}`` is managed by discord or an integration", id),
+            RoleGuardError::AboveBot(id) => write!(f, "The role ``{}`` is not below my highest role", id),
+            RoleGuardError::AboveActor(id) => write!(f, "The role ``{}`` is not below your highest role", id),
+            RoleGuardError::Elevated(id) => write!(
+                f,
+                "The role ``{}`` has moderator or admin permissions, I won't hand it out",
+                id
+            ),
+            RoleGuardError::Twilight(e) => write!(f, "Failed to interact with the discord api: {}", e),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum OtherFailure {
     ShardOrCluster(String),
@@ -222,6 +334,7 @@ pub enum OtherFailure {
     DatabaseError(DatabaseError),
     CorruptCache,
     Message(MessageError),
+    AuditLogReason(AuditLogReasonError),
 }
 
 impl error::Error for OtherFailure {}
@@ -234,6 +347,7 @@ impl fmt::Display for OtherFailure {
             OtherFailure::ShardOrCluster(e) => write!(f, "Shard command failed: {}", e),
             OtherFailure::TwilightHttp(e) => write!(f, "Something when wrong interacting with the discord api: {}", e),
             OtherFailure::Message(e) => write!(f, "Failed to construct a message: {}", e),
+            OtherFailure::AuditLogReason(e) => write!(f, "Invalid audit log reason: {}", e),
         }
     }
 }
@@ -245,6 +359,7 @@ pub enum ParseError {
     MemberNotFoundByName(String),
     MultipleMembersByName(String),
     WrongArgumentType(String),
+    InvalidDice(String),
     InvalidUserID(u64),
     UnknownChannel(u64),
     NoChannelAccessBot(String),
@@ -274,6 +389,11 @@ impl fmt::Display for ParseError {
                 "The wrong type was provided! Expected a {}, but got something else!",
                 expected
             ),
+            ParseError::InvalidDice(input) => write!(
+                f,
+                "``{}`` is not a valid dice expression, try something like ``2d20+3``",
+                input
+            ),
             ParseError::InvalidUserID(id) => write!(f, "``{}`` is not a valid discord userid", id),
             ParseError::UnknownChannel(id) => write!(f, "Unable to find any channel with id ``{}``", id),
             ParseError::NoChannelAccessBot(_) => write!(f, "I do not have access to that channel!"),
@@ -345,6 +465,12 @@ impl From<EmbedDescriptionError> for CommandError {
     }
 }
 
+impl From<EmbedTitleError> for CommandError {
+    fn from(e: EmbedTitleError) -> Self {
+        CommandError::OtherFailure(OtherFailure::Message(MessageError::EmbedTitle(e)))
+    }
+}
+
 impl From<EmbedColorError> for CommandError {
     fn from(e: EmbedColorError) -> Self {
         CommandError::OtherFailure(OtherFailure::Message(MessageError::EmbedColor(e)))
@@ -356,6 +482,12 @@ impl From<EmbedAuthorNameError> for CommandError {
         CommandError::OtherFailure(OtherFailure::Message(MessageError::EmbedAuthorName(e)))
     }
 }
+impl From<EmbedFooterTextError> for CommandError {
+    fn from(e: EmbedFooterTextError) -> Self {
+        CommandError::OtherFailure(OtherFailure::Message(MessageError::EmbedFooter(e)))
+    }
+}
+
 impl From<ImageSourceUrlError> for CommandError {
     fn from(e: ImageSourceUrlError) -> Self {
         CommandError::OtherFailure(OtherFailure::Message(MessageError::ImageSourceUrl(e)))
@@ -380,6 +512,27 @@ impl From<twilight_http::Error> for CommandError {
     }
 }
 
+impl From<twilight_http::Error> for RoleGuardError {
+    fn from(e: twilight_http::Error) -> Self {
+        RoleGuardError::Twilight(e)
+    }
+}
+
+impl From<RoleGuardError> for CommandError {
+    fn from(e: RoleGuardError) -> Self {
+        match e {
+            RoleGuardError::Twilight(e) => CommandError::OtherFailure(OtherFailure::TwilightHttp(e)),
+            e => CommandError::RoleGuard(e),
+        }
+    }
+}
+
+impl From<AuditLogReasonError> for CommandError {
+    fn from(e: AuditLogReasonError) -> Self {
+        CommandError::OtherFailure(OtherFailure::AuditLogReason(e))
+    }
+}
+
 impl From<OtherFailure> for CommandError {
     fn from(e: OtherFailure) -> Self {
         CommandError::OtherFailure(e)
@@ -403,6 +556,12 @@ impl From<darkredis::Error> for ApiCommunicaionError {
     }
 }
 
+impl From<ApiCommunicaionError> for CommandError {
+    fn from(e: ApiCommunicaionError) -> Self {
+        CommandError::OtherFailure(OtherFailure::ShardOrCluster(e.to_string()))
+    }
+}
+
 impl From<DatabaseError> for ParseError {
     fn from(e: DatabaseError) -> Self {
         ParseError::Other(OtherFailure::DatabaseError(e))
@@ -570,6 +729,24 @@ impl From<EmbedBuildError> for MessageError {
     }
 }
 
+impl From<EmbedTitleError> for MessageError {
+    fn from(e: EmbedTitleError) -> Self {
+        MessageError::EmbedTitle(e)
+    }
+}
+
+impl From<EmbedColorError> for MessageError {
+    fn from(e: EmbedColorError) -> Self {
+        MessageError::EmbedColor(e)
+    }
+}
+
+impl From<EmbedFieldError> for MessageError {
+    fn from(e: EmbedFieldError) -> Self {
+        MessageError::EmbedField(e)
+    }
+}
+
 impl From<EmbedFooterTextError> for MessageError {
     fn from(e: EmbedFooterTextError) -> Self {
         MessageError::EmbedFooter(e)
@@ -593,3 +770,27 @@ impl From<DatabaseError> for ApiMessageError {
         ApiMessageError::Database(e)
     }
 }
+
+impl From<reqwest::Error> for ClassifierError {
+    fn from(e: reqwest::Error) -> Self {
+        ClassifierError::Request(e)
+    }
+}
+
+impl From<reqwest::Error> for FeedError {
+    fn from(e: reqwest::Error) -> Self {
+        FeedError::Request(e)
+    }
+}
+
+impl From<io::Error> for BackupError {
+    fn from(e: io::Error) -> Self {
+        BackupError::Io(e)
+    }
+}
+
+impl From<DatabaseError> for BackupError {
+    fn from(e: DatabaseError) -> Self {
+        BackupError::Database(e)
+    }
+}
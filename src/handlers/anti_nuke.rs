@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+use twilight_model::channel::{Channel, GuildChannel};
+
+use crate::core::anti_nuke::NukeAction;
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    match event {
+        Event::ChannelDelete(channel) => {
+            if let Channel::Guild(channel) = &channel.0 {
+                let (guild_id, channel_id) = match channel {
+                    GuildChannel::Text(text) => (text.guild_id, text.id),
+                    GuildChannel::Voice(voice) => (voice.guild_id, voice.id),
+                    GuildChannel::Category(category) => (category.guild_id, category.id),
+                };
+                if let Some(guild_id) = guild_id {
+                    ctx.track_nuke_action(guild_id, NukeAction::ChannelDelete, Some(channel_id.0))
+                        .await?;
+                }
+            }
+        }
+        Event::RoleDelete(delete) => {
+            ctx.track_nuke_action(delete.guild_id, NukeAction::RoleDelete, Some(delete.role_id.0))
+                .await?;
+        }
+        Event::BanAdd(ban) => {
+            ctx.track_nuke_action(ban.guild_id, NukeAction::Ban, Some(ban.user.id.0))
+                .await?;
+        }
+        // Also sent for updates and deletions, only new audit log entries get counted
+        Event::WebhooksUpdate(update) => {
+            ctx.track_nuke_action(update.guild_id, NukeAction::WebhookCreate, None)
+                .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
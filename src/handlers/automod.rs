@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    match event {
+        Event::MessageCreate(msg) => {
+            if msg.author.bot || msg.webhook_id.is_some() {
+                return Ok(());
+            }
+
+            if let Some(guild_id) = msg.guild_id {
+                ctx.run_automod(&msg.0, guild_id).await?;
+                ctx.run_content_policy(&msg.0, guild_id).await?;
+                ctx.run_toxicity_check(&msg.0, guild_id).await?;
+                ctx.run_image_moderation(&msg.0, guild_id).await?;
+                ctx.run_image_text_automod(&msg.0, guild_id).await?;
+                ctx.run_autoresponder(&msg.0, guild_id).await?;
+            }
+        }
+        // Embeds of links get added to the message after it was sent
+        Event::MessageUpdate(update) => {
+            if update.author.as_ref().map_or(false, |author| author.bot) {
+                return Ok(());
+            }
+
+            let has_embeds = update.embeds.as_ref().map_or(false, |embeds| !embeds.is_empty());
+            if let (Some(guild_id), true) = (update.guild_id, has_embeds) {
+                ctx.run_embed_content_policy(update.channel_id, update.id, guild_id)
+                    .await?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
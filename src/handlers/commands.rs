@@ -13,36 +13,38 @@ pub async fn handle_event<'a>(shard_id: u64, event: Event, ctx: Arc<BotContext>)
         Event::MessageCreate(msg) if !msg.author.bot => {
             trace!("Received a message from {}, saying {}", msg.author.name, msg.content);
 
-            let p = match msg.guild_id {
-                Some(guild_id) => {
-                    let guild = ctx.cache.get_guild(&guild_id).await;
-                    match guild {
-                        Some(g) => {
-                            if !g.complete.load(Ordering::SeqCst) {
-                                debug!("Message received in {} but the guild isn't fully cached yet!", g.id);
-                                return Ok(()); //not cached yet, just ignore for now
-                            }
+            if !ctx.health.state().await.accepts_commands() {
+                debug!("Message received before the cluster is ready, ignoring it");
+                return Ok(());
+            }
+
+            if let Some(guild_id) = msg.guild_id {
+                match ctx.cache.get_guild(&guild_id).await {
+                    Some(g) => {
+                        if !g.complete.load(Ordering::SeqCst) {
+                            debug!("Message received in {} but the guild isn't fully cached yet!", g.id);
+                            return Ok(()); //not cached yet, just ignore for now
                         }
-                        None => return Ok(()), // we didn't even get a guild create yet
                     }
-
-                    let config = ctx.get_config(guild_id).await?;
-                    config.prefix.clone()
+                    None => return Ok(()), // we didn't even get a guild create yet
                 }
-                None => String::from("!"),
-            };
+            }
 
-            let prefix = if msg.content.starts_with(&p) {
-                Some(p)
+            // Mentioning the bot always works, so people can't lock themselves out with a bad prefix
+            let mention_1 = format!("<@{}>", ctx.bot_user.id);
+            let mention_2 = format!("<@!{}>", ctx.bot_user.id);
+            let prefix = if msg.content.starts_with(&mention_1) {
+                Some(mention_1)
+            } else if msg.content.starts_with(&mention_2) {
+                Some(mention_2)
             } else {
-                let mention_1 = format!("<@{}>", ctx.bot_user.id);
-                let mention_2 = format!("<@!{}>", ctx.bot_user.id);
-                if msg.content.starts_with(&mention_1) {
-                    Some(mention_1)
-                } else if msg.content.starts_with(&mention_2) {
-                    Some(mention_2)
-                } else {
-                    None
+                match msg.guild_id {
+                    Some(guild_id) => {
+                        let config = ctx.get_config(guild_id).await?;
+                        config.matching_prefix(&msg.content).map(String::from)
+                    }
+                    None if msg.content.starts_with('!') => Some(String::from("!")),
+                    None => None,
                 }
             };
 
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use log::debug;
 use twilight_gateway::Event;
+use twilight_model::channel::{Channel, GuildChannel};
 
 use crate::core::reactors::reactor_controller;
 use crate::core::BotContext;
@@ -32,8 +33,41 @@ pub async fn handle_event(shard_id: u64, event: &Event, ctx: Arc<BotContext>) ->
         Event::Resumed => {
             gearbot_info!("Shard {} successfully resumed", shard_id);
         }
+        Event::GuildCreate(guild) => {
+            ctx.cancel_guild_purge(guild.id).await?;
+            ctx.onboard_guild(guild.id).await?;
+        }
+        // Unavailable guilds are outages, we didn't actually leave those.
+        Event::GuildDelete(guild) if !guild.unavailable => {
+            ctx.schedule_guild_purge(guild.id).await?;
+        }
+        // Posts in announcement channels only reach the channels following them once they're published
+        Event::MessageCreate(message) => ctx.auto_publish(&message.0).await?,
         Event::ReactionAdd(reaction) => {
             reactor_controller::process_reaction(&ctx, reaction).await?;
+            ctx.open_ticket_from_panel(reaction).await?;
+        }
+        // Leaving voice doesn't have a channel, being in one is what counts as activity for prune
+        Event::VoiceStateUpdate(update) => {
+            if let Some(guild_id) = update.0.guild_id {
+                if update.0.channel_id.is_some() {
+                    ctx.datastore.record_voice_activity(guild_id, update.0.user_id).await?;
+                }
+                ctx.update_temp_voice(guild_id, update.0.user_id, update.0.channel_id)
+                    .await?;
+            }
+        }
+        // Rooms that get deleted by hand don't need cleaning up anymore, and tickets are as good as closed
+        Event::ChannelDelete(channel) => match &channel.0 {
+            Channel::Guild(GuildChannel::Voice(voice)) => ctx.datastore.remove_temp_room(voice.id).await?,
+            Channel::Guild(GuildChannel::Text(text)) => ctx.datastore.close_channel_ticket(text.id).await?,
+            _ => (),
+        },
+        Event::ChannelPinsUpdate(update) => {
+            if let Some(guild_id) = update.guild_id {
+                ctx.archive_pins(guild_id, update.channel_id, update.last_pin_timestamp.as_deref())
+                    .await?;
+            }
         }
 
         _ => (),
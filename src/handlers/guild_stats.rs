@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+
+use crate::core::guild_stats::GuildStat;
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    match event {
+        Event::MessageCreate(msg) if !msg.author.bot && msg.webhook_id.is_none() => {
+            if let Some(guild_id) = msg.guild_id {
+                ctx.count_guild_stat(guild_id, GuildStat::Message).await;
+                ctx.count_channel_message(guild_id, msg.channel_id).await;
+            }
+        }
+        Event::MemberAdd(member) => ctx.count_guild_stat(member.guild_id, GuildStat::Join).await,
+        Event::MemberRemove(member) => ctx.count_guild_stat(member.guild_id, GuildStat::Leave).await,
+        _ => {}
+    }
+
+    Ok(())
+}
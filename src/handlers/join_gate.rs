@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+
+use crate::core::join_gate::ChallengeLevel;
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    match event {
+        Event::MemberAdd(member) if !member.user.bot => {
+            ctx.track_join_for_raids(member.guild_id).await?;
+            if member.pending {
+                ctx.track_screening(member.guild_id, member.user.id, None, ChallengeLevel::None)
+                    .await?;
+            }
+            ctx.run_join_gate(&member.0).await?;
+        }
+        Event::MemberUpdate(update) if !update.pending && !update.user.bot => {
+            ctx.complete_screening(update.guild_id, update.user.id).await?;
+        }
+        // Captchas are answered in DMs
+        Event::MessageCreate(message) if message.guild_id.is_none() && !message.author.bot => {
+            ctx.answer_captcha(message.author.id, &message.content).await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    if let Event::MessageCreate(msg) = event {
+        if msg.author.bot || msg.webhook_id.is_some() {
+            return Ok(());
+        }
+
+        if let Some(guild_id) = msg.guild_id {
+            ctx.award_message_xp(&msg.0, guild_id).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,9 @@
+pub mod anti_nuke;
+pub mod automod;
 pub mod commands;
 pub mod general;
+pub mod guild_stats;
+pub mod join_gate;
+pub mod levels;
 pub mod modlog;
+pub mod watchlist;
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use log::debug;
 use twilight_gateway::Event;
 use twilight_model::gateway::payload::RequestGuildMembers;
 
-use crate::core::BotContext;
+use crate::core::logpump::{LogData, LogType};
+use crate::core::{BotContext, GuildFeatures, SnipedMessage};
 use crate::error::EventHandlerError;
+use crate::utils::transcript::{render_transcript, TranscriptEntry};
 
 pub async fn handle_event(shard_id: u64, event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
     match &event {
@@ -90,17 +93,220 @@ pub async fn handle_event(shard_id: u64, event: &Event, ctx: Arc<BotContext>) ->
 
         Event::MessageCreate(msg) => {
             if let Some(guild_id) = msg.guild_id {
-                let config = &ctx.get_config(guild_id).await?.message_logs;
+                let config = ctx.get_config(guild_id).await?;
+                if !config.features.contains(GuildFeatures::MESSAGE_LOGS) {
+                    return Ok(());
+                }
+                let config = &config.message_logs;
 
-                let should_log = config.enabled
-                    && !config.ignored_users.contains(&msg.author.id.0)
-                    && !(config.ignore_bots && msg.author.bot);
+                let roles = msg.member.as_ref().map(|member| member.roles.as_slice()).unwrap_or(&[]);
+                let should_log = !config.ignores(msg.author.id, msg.author.bot, msg.channel_id, roles, &msg.content);
 
                 if should_log {
                     ctx.insert_message(&msg.0, guild_id).await?;
                 }
             }
         }
+        Event::MessageDelete(delete) => {
+            if let Some(guild_id) = delete.guild_id {
+                // Without message logs nothing got stored to begin with
+                let config = ctx.get_config(guild_id).await?;
+                if !config.features.contains(GuildFeatures::MESSAGE_LOGS)
+                    || ctx.is_excluded_from_snipe(delete.id).await?
+                {
+                    return Ok(());
+                }
+
+                if let Some(message) = ctx.fetch_user_message(delete.id, guild_id).await? {
+                    let content = match ctx.get_edited_content(delete.id).await? {
+                        Some(content) => content,
+                        None => message.content,
+                    };
+
+                    let snipe = SnipedMessage {
+                        id: delete.id,
+                        author: message.author,
+                        content: content.clone(),
+                        previous_content: None,
+                        timestamp: chrono::Utc::now().timestamp(),
+                    };
+                    if config.features.contains(GuildFeatures::SNIPES) {
+                        ctx.store_snipe(delete.channel_id, &snipe).await?;
+                    }
+
+                    // Roles or patterns might have changed since the message got stored.
+                    let roles = match ctx.cache.get_member(&guild_id, &message.author).await {
+                        Some(member) => member.roles.clone(),
+                        None => vec![],
+                    };
+                    if config
+                        .message_logs
+                        .ignores(message.author, false, delete.channel_id, &roles, &snipe.content)
+                    {
+                        return Ok(());
+                    }
+
+                    let attachments = ctx.datastore.get_attachments(delete.id).await?;
+                    let attachments = ctx
+                        .describe_deleted_attachments(
+                            guild_id,
+                            delete.channel_id,
+                            delete.id,
+                            &attachments,
+                            config.message_logs.mirror_attachments,
+                        )
+                        .await;
+
+                    ctx.log(LogData {
+                        log_type: LogType::MessageDeleted {
+                            channel: delete.channel_id,
+                            content,
+                            attachments,
+                        },
+                        guild: guild_id,
+                        source_channel: Some(delete.channel_id),
+                        source_user: message.author,
+                        timestamp: chrono::Utc::now(),
+                    });
+                }
+            }
+        }
+        Event::MessageDeleteBulk(delete) => {
+            if let Some(guild_id) = delete.guild_id {
+                let config = ctx.get_config(guild_id).await?;
+                let message_logs = &config.message_logs;
+                if !config.features.contains(GuildFeatures::MESSAGE_LOGS)
+                    || !message_logs.enabled
+                    || message_logs.ignored_channels.contains(&delete.channel_id.0)
+                {
+                    return Ok(());
+                }
+
+                let mut recovered = ctx.fetch_user_messages(&delete.ids, guild_id).await?;
+
+                // Roles or patterns might have changed since the messages got stored.
+                let mut author_roles = HashMap::new();
+                for (_, message) in &recovered {
+                    if !author_roles.contains_key(&message.author) {
+                        let roles = match ctx.cache.get_member(&guild_id, &message.author).await {
+                            Some(member) => member.roles.clone(),
+                            None => vec![],
+                        };
+                        author_roles.insert(message.author, roles);
+                    }
+                }
+                recovered.retain(|(_, message)| {
+                    !message_logs.ignores(
+                        message.author,
+                        false,
+                        delete.channel_id,
+                        &author_roles[&message.author],
+                        &message.content,
+                    )
+                });
+
+                let transcript = if recovered.is_empty() {
+                    None
+                } else {
+                    let mut authors = HashMap::new();
+                    for (_, message) in &recovered {
+                        if !authors.contains_key(&message.author) {
+                            let name = match ctx.get_user(message.author).await {
+                                Ok(user) => user.full_name_with_id(),
+                                Err(_) => format!("Unknown user ({})", message.author),
+                            };
+                            authors.insert(message.author, name);
+                        }
+                    }
+
+                    let entries: Vec<TranscriptEntry> = recovered
+                        .iter()
+                        .map(|(id, message)| TranscriptEntry {
+                            id: *id,
+                            author: &authors[&message.author],
+                            content: &message.content,
+                        })
+                        .collect();
+
+                    let channel_name = match ctx.cache.get_channel(delete.channel_id).await {
+                        Some(channel) => channel.get_name().to_string(),
+                        None => delete.channel_id.to_string(),
+                    };
+                    let transcript = render_transcript(
+                        &channel_name,
+                        delete.channel_id,
+                        delete.ids.len(),
+                        &entries,
+                        config.tz(),
+                    );
+
+                    // Messages can only be deleted once, so the oldest one makes for a unique archive id.
+                    let archive_id = delete.ids.iter().map(|id| id.0).min().unwrap();
+                    ctx.datastore
+                        .insert_archive(archive_id, guild_id, delete.channel_id, &transcript)
+                        .await?;
+
+                    Some(transcript)
+                };
+
+                // There is no way to tell who deleted them from the event itself.
+                ctx.log(LogData {
+                    log_type: LogType::MessagesBulkDeleted {
+                        channel: delete.channel_id,
+                        count: delete.ids.len() as u64,
+                        recovered: recovered.len() as u64,
+                        transcript,
+                    },
+                    guild: guild_id,
+                    source_channel: Some(delete.channel_id),
+                    source_user: ctx.bot_user.id,
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        }
+        Event::MessageUpdate(update) => {
+            if let (Some(guild_id), Some(new_content)) = (update.guild_id, &update.content) {
+                let config = ctx.get_config(guild_id).await?;
+                if !config.features.contains(GuildFeatures::MESSAGE_LOGS) {
+                    return Ok(());
+                }
+
+                let previous = match ctx.get_edited_content(update.id).await? {
+                    Some(content) => Some((content, None)),
+                    None => ctx
+                        .fetch_user_message(update.id, guild_id)
+                        .await?
+                        .map(|message| (message.content, Some(message.author))),
+                };
+
+                if let Some((previous_content, author)) = previous {
+                    // embed unfurling also triggers an update, but without the content actually changing
+                    if previous_content == *new_content {
+                        return Ok(());
+                    }
+
+                    ctx.store_edited_content(update.id, new_content).await?;
+                    if !config.features.contains(GuildFeatures::SNIPES) {
+                        return Ok(());
+                    }
+
+                    let author = match (author, &update.author) {
+                        (Some(author), _) => author,
+                        (None, Some(user)) => user.id,
+                        (None, None) => return Ok(()),
+                    };
+
+                    let snipe = SnipedMessage {
+                        id: update.id,
+                        author,
+                        content: new_content.clone(),
+                        previous_content: Some(previous_content),
+                        timestamp: chrono::Utc::now().timestamp(),
+                    };
+                    ctx.store_snipe(update.channel_id, &snipe).await?;
+                }
+            }
+        }
         Event::GuildCreate(guild) => {
             let c = ctx.cluster.clone();
             let data = RequestGuildMembers::builder(guild.id).query("", None);
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use twilight_gateway::Event;
+
+use crate::core::BotContext;
+use crate::error::EventHandlerError;
+
+pub async fn handle_event(event: &Event, ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
+    match event {
+        Event::MessageCreate(msg) if msg.webhook_id.is_none() => {
+            if let Some(guild_id) = msg.guild_id {
+                ctx.watch_message(&msg.0, guild_id).await?;
+            }
+        }
+        Event::MemberAdd(member) => {
+            ctx.watch_join(member.guild_id, member.user.id).await?;
+        }
+        Event::MemberUpdate(update) => {
+            ctx.watch_name(update.guild_id, &update.user, update.nick.as_deref())
+                .await?;
+        }
+        Event::VoiceStateUpdate(update) => {
+            if let Some(guild_id) = update.0.guild_id {
+                ctx.watch_voice(guild_id, update.0.user_id, update.0.channel_id).await?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -3,6 +3,8 @@
 
 use std::convert::{Infallible, TryFrom};
 use std::env;
+use std::net::IpAddr;
+use std::ops::Range;
 use std::process;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -10,6 +12,7 @@ use std::time::{Duration, Instant};
 use futures_util::stream::StreamExt;
 use git_version::git_version;
 use log::{debug, info};
+use once_cell::sync::OnceCell;
 use tokio::{self, runtime::Runtime, sync::mpsc};
 use twilight_gateway::{cluster::ShardScheme, shard::ResumeSession, Cluster, Event};
 use twilight_http::{request::channel::allowed_mentions::AllowedMentionsBuilder, Client as HttpClient};
@@ -17,14 +20,22 @@ use twilight_model::{
     gateway::{
         payload::update_status::UpdateStatusInfo,
         presence::{ActivityType, Status},
-        Intents,
     },
     user::CurrentUser,
 };
 
 use prometheus::{Encoder, TextEncoder};
-
-use crate::core::{logging, logpump, status as bot_status, BotConfig, BotContext, BotStats, ColdRebootData};
+use subtle::ConstantTimeEq;
+use tracing::{info_span, Instrument};
+
+use crate::core::error_tracking::{self, ErrorLevel, ErrorTags};
+use crate::core::image_moderation::{HttpClassifier, HttpTextExtractor, ImageClassifier, TextExtractor};
+use crate::core::logging::{self, LogContext};
+use crate::core::toxicity::{HttpMessageClassifier, MessageClassifier};
+use crate::core::webhook_server;
+use crate::core::{
+    logpump, status as bot_status, telemetry, BotConfig, BotContext, BotStats, ColdRebootData, Metrics, TaskGuard,
+};
 use crate::error::{EventHandlerError, StartupError};
 use commands::ROOT_NODE;
 use translation::Translations;
@@ -50,16 +61,45 @@ mod handlers;
 mod translation;
 mod utils;
 
+#[cfg(test)]
+mod test_utils;
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const GIT_VERSION: &str = git_version!();
 
 #[derive(Debug, Copy, Clone)]
 pub struct SchemeInfo {
     pub cluster_id: u64,
+    pub cluster_count: u64,
     pub shards_per_cluster: u64,
     pub total_shards: u64,
 }
 
+impl SchemeInfo {
+    /// Spreads the shards over the clusters, when they don't divide evenly the last cluster gets less of them.
+    pub fn new(cluster_id: u64, cluster_count: u64, total_shards: u64) -> Self {
+        let cluster_count = cluster_count.max(1);
+        SchemeInfo {
+            cluster_id,
+            cluster_count,
+            shards_per_cluster: (total_shards + cluster_count - 1) / cluster_count,
+            total_shards,
+        }
+    }
+
+    /// The shards this cluster is responsible for.
+    pub fn shard_range(&self) -> Range<u64> {
+        let start = (self.cluster_id * self.shards_per_cluster).min(self.total_shards);
+        let end = (start + self.shards_per_cluster).min(self.total_shards);
+        start..end
+    }
+
+    pub fn shard_count(&self) -> u64 {
+        let range = self.shard_range();
+        range.end - range.start
+    }
+}
+
 fn main() -> Result<(), StartupError> {
     let runtime = Runtime::new()?;
 
@@ -75,16 +115,8 @@ async fn real_main() -> Result<(), StartupError> {
     let config = BotConfig::new(&env::var("CONFIG_FILE").unwrap_or_else(|_| String::from("config.toml")))?;
     println!("Loaded config file");
 
-    let mut builder = HttpClient::builder()
-        .token(&config.tokens.discord)
-        .default_allowed_mentions(AllowedMentionsBuilder::new().build_solo());
-    if let Some(proxy_url) = &config.proxy_url {
-        builder = builder.proxy(proxy_url, true).ratelimiter(None);
-    }
-
-    let http = builder.build();
     // Validate token and figure out who we are
-    let bot_user = http.current_user().await?;
+    let (http, bot_user, rest_route) = connect_http(&config).await?;
     println!(
         "Token validated, connecting to discord as {}#{}",
         bot_user.name, bot_user.discriminator
@@ -96,6 +128,15 @@ async fn real_main() -> Result<(), StartupError> {
     }
 
     gearbot_important!("Starting Gearbot v{}. Hello there, Ferris!", VERSION);
+    match rest_route {
+        RestRoute::Direct => {}
+        RestRoute::Proxy(url) => gearbot_info!("Sending all api requests through the rest proxy at {}", url),
+        RestRoute::ProxyFallback(url, e) => gearbot_info!(
+            "Failed to reach the rest proxy at {} ({}), falling back to talking to discord directly",
+            url,
+            e
+        ),
+    }
 
     let translations = translation::load_translations();
     gearbot_info!("Loaded translations!");
@@ -111,21 +152,38 @@ async fn real_main() -> Result<(), StartupError> {
     // end of the critical failure zone, everything from here on out should be properly wrapped
     // and handled
 
-    // Parse CLI arguments for sharding and cluster info
-    let args = std::env::args().skip(1).collect::<Vec<String>>();
-    let cluster_id = args
-        .get(0)
-        .map(|cs| cs.parse::<u64>().unwrap_or_default())
-        .unwrap_or_default();
-    let shards_per_cluster = args.get(1).map(|spc| spc.parse::<u64>().unwrap_or(1)).unwrap_or(1);
-    let total_shards = args.get(2).map(|ts| ts.parse::<u64>().unwrap_or(1)).unwrap_or(1);
-
-    let scheme_info = SchemeInfo {
-        cluster_id,
-        shards_per_cluster,
-        total_shards,
+    // Multi-process deployments can share a config file and pass the cluster on the command line instead
+    let cluster_id = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse::<u64>().ok())
+        .unwrap_or(config.sharding.cluster_id);
+
+    if let Err(e) = error_tracking::initialize(&config, cluster_id) {
+        gearbot_error!("The error tracking DSN is invalid");
+        return Err(e);
+    }
+    telemetry::initialize(&config, cluster_id, datastore.query_metrics())?;
+
+    let total_shards = match config.sharding.total_shards {
+        Some(total_shards) => total_shards,
+        None => {
+            let recommended = http.gateway().authed().await?.shards;
+            gearbot_info!("Using the recommended amount of {} shards", recommended);
+            recommended
+        }
     };
 
+    let scheme_info = SchemeInfo::new(cluster_id, config.sharding.cluster_count, total_shards);
+    if scheme_info.shard_count() == 0 {
+        gearbot_error!(
+            "Cluster {} has no shards to run, {} shards are not enough for {} clusters",
+            cluster_id,
+            total_shards,
+            scheme_info.cluster_count
+        );
+        return Err(StartupError::InvalidConfig);
+    }
+
     if let Err(e) = run(scheme_info, config, http, bot_user, datastore, translations).await {
         gearbot_error!("Failed to start the bot: {}", e)
     }
@@ -141,30 +199,25 @@ async fn run(
     datastore: DataStorage,
     translations: Translations,
 ) -> Result<(), StartupError> {
-    let sharding_scheme = ShardScheme::try_from((
-        scheme_info.cluster_id * scheme_info.shards_per_cluster
-            ..scheme_info.cluster_id * scheme_info.shards_per_cluster + scheme_info.shards_per_cluster,
-        scheme_info.total_shards,
-    ))
-    .unwrap();
-
-    let intents = Intents::GUILDS
-        | Intents::GUILD_MEMBERS
-        | Intents::GUILD_BANS
-        | Intents::GUILD_EMOJIS
-        | Intents::GUILD_INVITES
-        | Intents::GUILD_VOICE_STATES
-        | Intents::GUILD_MESSAGES
-        | Intents::GUILD_MESSAGE_REACTIONS
-        | Intents::DIRECT_MESSAGES
-        | Intents::DIRECT_MESSAGE_REACTIONS;
+    let sharding_scheme = ShardScheme::try_from((scheme_info.shard_range(), scheme_info.total_shards)).unwrap();
+
+    let intents = config.intents.intents();
 
     let stats = Arc::new(BotStats::new(scheme_info.cluster_id));
-    tokio::spawn(run_metrics_server(Arc::clone(&stats)));
+    datastore.register_metrics(&stats.registry);
+    // The server is up before the context exists so metrics are available during startup, health checks will report
+    // the bot as starting until it's filled in
+    let health_context = Arc::new(OnceCell::new());
+    tokio::spawn(run_metrics_server(
+        config.metrics,
+        Arc::clone(&stats),
+        Arc::clone(&health_context),
+    ));
 
     let cache = Cache::new(scheme_info.cluster_id, Arc::clone(&stats));
 
     let mut cb = Cluster::builder(&config.tokens.discord, intents)
+        .http_client(http.clone())
         .shard_scheme(sharding_scheme)
         .presence(UpdateStatusInfo::new(
             vec![bot_status::generate_activity(
@@ -187,7 +240,7 @@ async fn run(
                 cache_pool.delete(&key).await?;
 
                 if (cold_cache.total_shards == scheme_info.total_shards)
-                    && (cold_cache.shard_count == scheme_info.shards_per_cluster)
+                    && (cold_cache.shard_count == scheme_info.shard_count())
                 {
                     let map = cold_cache
                         .resume_data
@@ -225,6 +278,37 @@ async fn run(
 
     let cluster = cb.build().await?;
 
+    let image_classifier = match &config.moderation_api {
+        Some(api) => match HttpClassifier::new(api) {
+            Ok(classifier) => Some(Box::new(classifier) as Box<dyn ImageClassifier>),
+            Err(e) => {
+                gearbot_error!("Failed to set up the moderation API client: {}", e);
+                return Err(StartupError::InvalidConfig);
+            }
+        },
+        None => None,
+    };
+    let text_extractor = match &config.ocr_api {
+        Some(api) => match HttpTextExtractor::new(api) {
+            Ok(extractor) => Some(Box::new(extractor) as Box<dyn TextExtractor>),
+            Err(e) => {
+                gearbot_error!("Failed to set up the OCR API client: {}", e);
+                return Err(StartupError::InvalidConfig);
+            }
+        },
+        None => None,
+    };
+    let message_classifier = match &config.toxicity_api {
+        Some(api) => match HttpMessageClassifier::new(api) {
+            Ok(classifier) => Some(Box::new(classifier) as Box<dyn MessageClassifier>),
+            Err(e) => {
+                gearbot_error!("Failed to set up the toxicity API client: {}", e);
+                return Err(StartupError::InvalidConfig);
+            }
+        },
+        None => None,
+    };
+
     let (sender, receiver) = mpsc::unbounded_channel();
 
     let context = Arc::new(
@@ -234,11 +318,23 @@ async fn run(
             datastore,
             translations,
             config.global_admins,
+            config.attachment_mirror.map(|mirror| mirror.channel),
+            image_classifier,
+            text_extractor,
+            message_classifier,
+            config.backups,
+            config.data_retention_days,
+            config.webhooks.as_ref().map(|webhooks| webhooks.public_url.clone()),
             stats,
             sender,
         )
         .await,
     );
+    match context.reload_blocklist().await {
+        Ok(count) => log::info!("Loaded {} blocked users", count),
+        Err(e) => gearbot_error!("Failed to load the blocklist: {}", e),
+    }
+    let _ = health_context.set(context.clone());
     let ctx = context.clone();
     let mut _logpump_task = tokio::spawn(logpump::run(ctx, receiver));
 
@@ -249,6 +345,74 @@ async fn run(
         c.datastore.cache_pool.establish_api_link(c.clone()).await;
     });
 
+    //establish links with the other clusters
+    let c = context.clone();
+    log::debug!("spawning cluster links");
+    tokio::spawn(async move {
+        c.datastore.cache_pool.establish_cluster_link(c.clone()).await;
+    });
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.datastore
+            .cache_pool
+            .establish_cluster_reply_link(c.scheme_info.cluster_id)
+            .await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_guild_purges().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_health_checks().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_backups().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_profile_reverts().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_retention_janitor().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_birthday_announcer().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_event_reminders().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_feed_poller().await;
+    });
+
+    if let Some(webhooks) = config.webhooks {
+        tokio::spawn(webhook_server::run(webhooks, context.clone()));
+    }
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.run_stats_collector().await;
+    });
+
+    let c = context.clone();
+    tokio::spawn(async move {
+        c.stats.run_runtime_probe().await;
+    });
+
     let shutdown_ctx = context.clone();
     ctrlc::set_handler(move || {
         // We need a seperate runtime, because at this point in the program,
@@ -269,13 +433,40 @@ async fn run(
     let mut bot_events = context.cluster.events();
     while let Some(event) = bot_events.next().await {
         let c = context.clone();
-        context.update_stats(event.0, &event.1).await; //this is fine to await, only async for updating shard states, gona be extremely rare something else also has a lock on that
-        context.cache.update(event.0, &event.1, context.clone()).await; //we are awaiting this because cache needs ot be updated before it's safe to spawn off the handling, to avoid working with stale data
-        tokio::spawn(async {
-            if let Err(e) = handle_event(event, c).await {
-                gearbot_error!("{}", e);
+        // Everything done for the event, from here to the last rest call and query of the handlers, ends up in here
+        let span = info_span!("gateway_event", shard = event.0, kind = ?event.1.kind());
+        async {
+            context.update_stats(event.0, &event.1).await; //this is fine to await, only async for updating shard states, gona be extremely rare something else also has a lock on that
+            context.cache.update(event.0, &event.1, context.clone()).await; //we are awaiting this because cache needs ot be updated before it's safe to spawn off the handling, to avoid working with stale data
+        }
+        .instrument(info_span!(parent: &span, "cache_update"))
+        .await;
+        let shard = event.0;
+        let task = TaskGuard::new(&context.stats.runtime_stats.event_tasks);
+        let log_context = LogContext {
+            shard: Some(shard),
+            ..LogContext::default()
+        };
+        tokio::spawn(logging::with_context(
+            log_context,
+            async move {
+                let kind = event.1.kind();
+                if let Err(e) = handle_event(event, c.clone()).await {
+                    if let EventHandlerError::Twilight(http_error) = &e {
+                        c.track_http_error(http_error).await;
+                    }
+                    gearbot_error!("{}", e);
+                    let tags = ErrorTags {
+                        shard: Some(shard),
+                        event: Some(format!("{:?}", kind)),
+                        ..ErrorTags::default()
+                    };
+                    error_tracking::capture(ErrorLevel::Error, e.to_string(), tags);
+                }
+                drop(task);
             }
-        });
+            .instrument(span),
+        ));
     }
     context.cluster.down();
 
@@ -285,41 +476,267 @@ async fn run(
     Ok(())
 }
 
+/// How api requests reach discord, this is only reported once logging is set up.
+enum RestRoute {
+    Direct,
+    Proxy(String),
+    /// The proxy couldn't be reached, with why.
+    ProxyFallback(String, twilight_http::Error),
+}
+
+/// Sets up the http client, going through the rest proxy if there is one.
+///
+/// When the proxy doesn't work and falling back is allowed, this cluster talks to discord directly instead.
+async fn connect_http(config: &BotConfig) -> Result<(HttpClient, CurrentUser, RestRoute), StartupError> {
+    let builder = || {
+        HttpClient::builder()
+            .token(&config.tokens.discord)
+            .default_allowed_mentions(AllowedMentionsBuilder::new().build_solo())
+    };
+
+    if let Some(proxy) = &config.rest_proxy {
+        let http = builder()
+            .proxy(proxy.url.clone(), proxy.use_http)
+            .ratelimiter(None)
+            .build();
+        match http.current_user().await {
+            Ok(bot_user) => return Ok((http, bot_user, RestRoute::Proxy(proxy.url.clone()))),
+            Err(e) if proxy.fallback_to_direct => {
+                let http = builder().build();
+                let bot_user = http.current_user().await?;
+                return Ok((http, bot_user, RestRoute::ProxyFallback(proxy.url.clone(), e)));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let http = builder().build();
+    let bot_user = http.current_user().await?;
+    Ok((http, bot_user, RestRoute::Direct))
+}
+
 async fn handle_event(event: (u64, Event), ctx: Arc<BotContext>) -> Result<(), EventHandlerError> {
-    handlers::modlog::handle_event(event.0, &event.1, ctx.clone()).await?;
-    handlers::general::handle_event(event.0, &event.1, ctx.clone()).await?;
+    handlers::modlog::handle_event(event.0, &event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "modlog"))
+        .await?;
+    handlers::general::handle_event(event.0, &event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "general"))
+        .await?;
+    handlers::automod::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "automod"))
+        .await?;
+    handlers::join_gate::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "join_gate"))
+        .await?;
+    handlers::anti_nuke::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "anti_nuke"))
+        .await?;
+    handlers::levels::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "levels"))
+        .await?;
+    handlers::watchlist::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "watchlist"))
+        .await?;
+    handlers::guild_stats::handle_event(&event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "guild_stats"))
+        .await?;
 
     // Bot stat handling "hooks". This can be converted into a match if we have more stats to register here.
     if let Event::MessageCreate(msg) = &event.1 {
         ctx.stats.new_message(&ctx, msg).await;
     }
 
-    handlers::commands::handle_event(event.0, event.1, ctx.clone()).await?;
+    handlers::commands::handle_event(event.0, event.1, ctx.clone())
+        .instrument(info_span!("handler", name = "commands"))
+        .await?;
 
     Ok(())
 }
 
-async fn run_metrics_server(stats: Arc<BotStats>) {
+async fn run_metrics_server(config: Metrics, stats: Arc<BotStats>, context: Arc<OnceCell<Arc<BotContext>>>) {
+    use hyper::server::conn::AddrStream;
     use hyper::service::{make_service_fn, service_fn};
-    use hyper::{Body, Response};
 
-    let metric_service = make_service_fn(move |_| {
+    let addr = config.bind;
+    let config = Arc::new(config);
+    let metric_service = make_service_fn(move |connection: &AddrStream| {
+        let remote = connection.remote_addr().ip();
+        let config = config.clone();
         let stats = stats.clone();
+        let context = context.clone();
         async move {
-            Ok::<_, Infallible>(service_fn(move |_req| {
-                let mut buffer = vec![];
-                let encoder = TextEncoder::new();
-                let metric_families = stats.registry.gather();
-                encoder.encode(&metric_families, &mut buffer).unwrap();
-
-                async move { Ok::<_, Infallible>(Response::new(Body::from(buffer))) }
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let config = config.clone();
+                let stats = stats.clone();
+                let context = context.clone();
+                async move { Ok::<_, Infallible>(handle_metrics_request(req, remote, &config, &stats, &context).await) }
             }))
         }
     });
 
-    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 9091));
-    let server = hyper::server::Server::bind(&addr).serve(metric_service);
+    let server = match hyper::server::Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(metric_service),
+        Err(e) => {
+            gearbot_error!("Failed to bind the metrics server to {}: {}", addr, e);
+            return;
+        }
+    };
+    gearbot_info!("Serving metrics on {}", addr);
     if let Err(e) = server.await {
         gearbot_error!("The metrics server failed: {}", e)
     }
 }
+
+async fn handle_metrics_request(
+    req: hyper::Request<hyper::Body>,
+    remote: IpAddr,
+    config: &Metrics,
+    stats: &BotStats,
+    context: &OnceCell<Arc<BotContext>>,
+) -> hyper::Response<hyper::Body> {
+    use hyper::{header, Body, Response, StatusCode};
+
+    let authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if let Err(status) = check_metrics_access(config, req.uri().path(), remote, authorization) {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = status;
+        return response;
+    }
+
+    let path = req.uri().path();
+    if path == "/healthz" || path == "/readyz" {
+        let (status, body) = match context.get() {
+            Some(ctx) => {
+                let report = ctx.update_health().await;
+                let ok = if path == "/readyz" {
+                    report.is_ready()
+                } else {
+                    report.is_healthy()
+                };
+                let status = if ok {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                };
+                (status, serde_json::to_string(&report).unwrap_or_default())
+            }
+            None => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                String::from("{\"state\":\"starting\"}"),
+            ),
+        };
+
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = status;
+        return response;
+    }
+
+    let mut buffer = vec![];
+    let encoder = TextEncoder::new();
+    stats.collect_sys_info();
+    let metric_families = stats.registry.gather();
+    encoder.encode(&metric_families, &mut buffer).unwrap();
+
+    Response::new(Body::from(buffer))
+}
+
+/// Checks the source address against the allowlist, and the authorization header against the token if there is one.
+///
+/// The liveness check on `/healthz` only goes through the allowlist, so orchestrators can probe it without the token.
+fn check_metrics_access(
+    config: &Metrics,
+    path: &str,
+    remote: IpAddr,
+    authorization: Option<&str>,
+) -> Result<(), hyper::StatusCode> {
+    if !config.allowed_ips.is_empty() && !config.allowed_ips.contains(&remote) {
+        return Err(hyper::StatusCode::FORBIDDEN);
+    }
+
+    if let Some(token) = config.token.as_ref().filter(|_| path != "/healthz") {
+        // Compared in constant time, so the token can't be guessed byte by byte from response times
+        let authorized = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map_or(false, |provided| {
+                bool::from(provided.as_bytes().ct_eq(token.as_bytes()))
+            });
+        if !authorized {
+            return Err(hyper::StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_shards_over_clusters() {
+        let ranges: Vec<Range<u64>> = (0..3).map(|id| SchemeInfo::new(id, 3, 10).shard_range()).collect();
+        assert_eq!(ranges, vec![0..4, 4..8, 8..10]);
+    }
+
+    #[test]
+    fn clusters_without_shards_are_empty() {
+        let scheme = SchemeInfo::new(2, 3, 2);
+        assert_eq!(scheme.shard_count(), 0);
+    }
+
+    #[test]
+    fn metrics_access_checks_ip_and_token() {
+        let config = Metrics {
+            token: Some(String::from("secret")),
+            allowed_ips: vec![IpAddr::from([10, 0, 0, 1])],
+            ..Default::default()
+        };
+        let allowed = IpAddr::from([10, 0, 0, 1]);
+
+        assert_eq!(
+            check_metrics_access(&config, "/metrics", allowed, Some("Bearer secret")),
+            Ok(())
+        );
+        assert_eq!(
+            check_metrics_access(&config, "/metrics", allowed, Some("Bearer wrong")),
+            Err(hyper::StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            check_metrics_access(&config, "/metrics", allowed, Some("Bearer secretsecret")),
+            Err(hyper::StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            check_metrics_access(&config, "/readyz", allowed, None),
+            Err(hyper::StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            check_metrics_access(&config, "/metrics", IpAddr::from([10, 0, 0, 2]), Some("Bearer secret")),
+            Err(hyper::StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            check_metrics_access(&Metrics::default(), "/metrics", allowed, None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn liveness_checks_skip_the_token() {
+        let config = Metrics {
+            token: Some(String::from("secret")),
+            allowed_ips: vec![IpAddr::from([10, 0, 0, 1])],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_metrics_access(&config, "/healthz", IpAddr::from([10, 0, 0, 1]), None),
+            Ok(())
+        );
+        assert_eq!(
+            check_metrics_access(&config, "/healthz", IpAddr::from([10, 0, 0, 2]), None),
+            Err(hyper::StatusCode::FORBIDDEN)
+        );
+    }
+}
@@ -1,31 +1,79 @@
 use std::cmp;
+use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use chrono::Utc;
 use lazy_static::lazy_static;
 use log::{debug, info, trace};
+use tracing::{info_span, Instrument};
+use twilight_model::channel::Message;
 use twilight_model::gateway::payload::MessageCreate;
 use twilight_model::guild::Permissions;
-use twilight_model::id::{GuildId, UserId};
+use twilight_model::id::{ChannelId, GuildId, UserId};
 
-use crate::cache::{CachedGuild, CachedMember, CachedUser};
+use crate::cache::{CachedChannel, CachedGuild, CachedMember, CachedUser};
 use crate::commands::{
-    meta::nodes::{CommandNode, GearBotPermissions},
+    meta::nodes::{CommandGroup, CommandNode},
     ROOT_NODE,
 };
-use crate::core::{BotContext, CommandContext, CommandMessage, GuildConfig};
-use crate::error::{CommandError, EventHandlerError, ParseError};
+use crate::core::error_tracking::{self, ErrorLevel, ErrorTags};
+use crate::core::guild_stats::GuildStat;
+use crate::core::logging::{self, LogContext};
+use crate::core::logpump::{CommandOutcome, LogData, LogType};
+use crate::core::{BotContext, CommandContext, CommandCorrection, CommandMessage, GuildConfig};
+use crate::error::{CommandError, EventHandlerError, OtherFailure, ParseError};
 use crate::gearbot_error;
 use crate::translation::{FluArgs, GearBotString};
-use crate::utils::{matchers, Emoji};
+use crate::utils::dice::DiceRoll;
+use crate::utils::{self, fuzzy, matchers, Emoji};
 
 lazy_static! {
     static ref BLANK_CONFIG: Arc<GuildConfig> = Arc::new(GuildConfig::default());
 }
 
+const DRY_RUN_FLAGS: [&str; 2] = ["--dry-run", "-n"];
+
+/// Splits a message up in arguments, words between double quotes stay together.
+fn split_arguments(content: &str) -> Vec<String> {
+    let temp = content.split_whitespace().collect::<Vec<&str>>();
+    let mut parts = vec![];
+
+    let mut index = 0;
+    while index < temp.len() {
+        let mut part = temp[index].to_string();
+        index += 1;
+        if part.starts_with('"') && !part.ends_with('"') {
+            let mut new_part = part.clone();
+            let mut new_index = index;
+            while new_index < temp.len() {
+                new_part += " ";
+                new_part += &*temp[new_index];
+                new_index += 1;
+                if new_part.ends_with('"') {
+                    index = new_index;
+                    part = new_part.clone();
+                    break;
+                }
+            }
+        }
+
+        if let Some(new_part) = part.strip_prefix('"') {
+            if let Some(new_part) = new_part.strip_suffix('"') {
+                part = new_part.to_string()
+            }
+        }
+
+        parts.push(part);
+    }
+
+    parts
+}
+
 pub struct Parser {
     pub parts: Vec<String>,
     index: usize,
+    pub dry_run: bool,
     ctx: Arc<BotContext>,
     shard_id: u64,
     guild_id: Option<GuildId>,
@@ -33,46 +81,30 @@ pub struct Parser {
 
 impl Parser {
     fn new(content: &str, ctx: Arc<BotContext>, shard_id: u64, guild_id: Option<GuildId>) -> Self {
-        let temp = content.split_whitespace().collect::<Vec<&str>>();
-        let mut parts = vec![];
-
-        let mut index = 0;
-        while index < temp.len() {
-            let mut part = temp[index].to_string();
-            index += 1;
-            if part.starts_with('"') && !part.ends_with('"') {
-                let mut new_part = part.clone();
-                let mut new_index = index;
-                while new_index < temp.len() {
-                    new_part += " ";
-                    new_part += &*temp[new_index];
-                    new_index += 1;
-                    if new_part.ends_with('"') {
-                        index = new_index;
-                        part = new_part.clone();
-                        break;
-                    }
-                }
-            }
-
-            if let Some(new_part) = part.strip_prefix('"') {
-                if let Some(new_part) = new_part.strip_suffix('"') {
-                    part = new_part.to_string()
-                }
-            }
-
-            parts.push(part);
-        }
+        let parts = split_arguments(content);
 
         Parser {
             parts,
             index: 0,
+            dry_run: false,
             ctx,
             shard_id,
             guild_id,
         }
     }
 
+    /// Swaps out one of the guild's own aliases for what it stands for, so `p 50 bots` can become `purge 50 bots`.
+    fn expand_alias(&mut self, aliases: &HashMap<String, String>) {
+        let expansion = match self.parts.first().and_then(|first| aliases.get(&first.to_lowercase())) {
+            Some(expansion) => expansion,
+            None => return,
+        };
+
+        let mut parts = split_arguments(expansion);
+        parts.extend(self.parts.drain(1..));
+        self.parts = parts;
+    }
+
     pub fn get_command(&mut self) -> Vec<Arc<CommandNode>> {
         let mut nodes = vec![];
         let mut to_search = &ROOT_NODE.all_commands;
@@ -92,6 +124,73 @@ impl Parser {
         nodes
     }
 
+    /// Handles a command that doesn't exist, by suggesting the closest ones or by running one if the guild allows it.
+    ///
+    /// Returns the nodes of the command to run instead, if any.
+    async fn correct_command(&mut self, message: &Message) -> Result<Option<Vec<Arc<CommandNode>>>, EventHandlerError> {
+        let guild_id = match message.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(None),
+        };
+        let typed = match self.parts.first() {
+            Some(typed) => typed.clone(),
+            None => return Ok(None),
+        };
+
+        let config = self.ctx.get_config(guild_id).await?;
+        if config.command_correction == CommandCorrection::Off {
+            return Ok(None);
+        }
+
+        // Bot admin commands are left out, no need to advertise those to everyone
+        let candidates = ROOT_NODE
+            .all_commands
+            .iter()
+            .filter(|(_, node)| node.group != CommandGroup::BotAdmin)
+            .map(|(name, _)| name.as_str());
+        let matches = fuzzy::closest_matches(&typed, candidates, 3);
+        let (best, distance) = match matches.first() {
+            Some(best) => *best,
+            None => return Ok(None),
+        };
+
+        let unambiguous = matches.get(1).map_or(true, |second| second.1 > distance);
+        let node = &ROOT_NODE.all_commands[best];
+        if config.command_correction == CommandCorrection::AutoCorrect
+            && distance == 1
+            && unambiguous
+            && !node.supports_dry_run
+        {
+            debug!("Correcting unknown command {} to {}", typed, best);
+            self.parts[0] = best.to_string();
+            return Ok(Some(self.get_command()));
+        }
+
+        // Only a nice to have, not worth adding to the pressure
+        if self.ctx.rate_limits.is_slowed_down() {
+            return Ok(None);
+        }
+
+        let suggestions = matches
+            .iter()
+            .map(|(name, _)| format!("``{}{}``", config.prefix, name))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let args = FluArgs::with_capacity(2)
+            .add("gearno", Emoji::No.for_chat())
+            .add("suggestions", suggestions)
+            .generate();
+        let reply = self
+            .ctx
+            .translate_with_args(&config.language, GearBotString::UnknownCommand, &args);
+        // Not being able to talk in this channel is no reason to complain, it wasn't a command to begin with
+        if let Ok(request) = self.ctx.http.create_message(message.channel_id).content(reply) {
+            let _ = request.await;
+        }
+
+        Ok(None)
+    }
+
     pub async fn figure_it_out(
         prefix: &str,
         message: Box<MessageCreate>,
@@ -104,8 +203,28 @@ impl Parser {
         let mut parser = Parser::new(&message.content[prefix.len()..], ctx, shard_id, message.guild_id);
         trace!("Parser processing message: {:?}", message.content);
 
+        // Blocked users are ignored entirely, telling them would only invite them to keep trying
+        if parser.ctx.is_blocked(message.author.id).await {
+            return Ok(());
+        }
+
+        if let Some(guild_id) = message.guild_id {
+            let config = parser.ctx.get_config(guild_id).await?;
+            if config.bot_banned_users.contains(&message.author.id) {
+                return Ok(());
+            }
+            parser.expand_alias(&config.command_aliases);
+        }
+
         // Parse the message to get the nodes
-        let command_nodes = parser.get_command();
+        let mut command_nodes = parser.get_command();
+
+        if command_nodes.is_empty() {
+            match parser.correct_command(&message).await? {
+                Some(corrected) => command_nodes = corrected,
+                None => return Ok(()),
+            }
+        }
 
         // Is there a valid node to execute?
         let node = match command_nodes.last() {
@@ -113,6 +232,10 @@ impl Parser {
             None => return Ok(()),
         };
 
+        if node.supports_dry_run {
+            parser.take_dry_run_flag();
+        }
+
         // Assemble the command's name
         let mut name = String::new();
         for node in command_nodes.iter().skip(1) {
@@ -122,9 +245,29 @@ impl Parser {
 
         let ctx = Arc::clone(&parser.ctx);
 
+        // Moderation commands get an audit trail, grab what they are being used with before the parser is handed off
+        let audit = if node.group == CommandGroup::Moderation {
+            let command = command_nodes
+                .iter()
+                .map(|node| node.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            // Only actual user ids, so ``purge 50`` isn't logged against <@50>
+            let target = parser
+                .peek()
+                .and_then(|arg| matchers::get_user_snowflake(arg))
+                .map(UserId);
+            let arguments = matchers::redact_sensitive(&parser.get_remaining());
+            Some((command, target, arguments))
+        } else {
+            None
+        };
+
         let channel_id = message.channel_id;
         let channel = match ctx.cache.get_channel(channel_id).await {
             Some(channel) => channel,
+            // We don't get told about DM channels being opened, so this might be the first we hear of it
+            None if message.guild_id.is_none() => ctx.get_dm_for_user(message.author.id).await?,
             None => return Err(EventHandlerError::UnknownChannel(channel_id)),
         };
 
@@ -151,17 +294,15 @@ impl Parser {
 
             (Some(guild), Some(member), config, permissions)
         } else {
-            let mut perms = GearBotPermissions::empty() | BLANK_CONFIG.permission_groups[0].granted_perms;
-            ctx.apply_admin_perms(&message.author.id, &mut perms);
+            let perms = ctx.get_dm_permissions(&message.author.id, &BLANK_CONFIG);
             (None, None, Arc::clone(&BLANK_CONFIG), perms)
         };
 
-        // Silently ignore any DMs
+        // Silently ignore any DMs, unless it's one of the few commands that only work there
         // TODO: Maybe return an error?
-        let guild = match guild {
-            Some(g) => g,
-            None => return Ok(()),
-        };
+        if guild.is_none() && !node.dm_only {
+            return Ok(());
+        }
 
         let cmdm = CommandMessage {
             id: message.id,
@@ -177,11 +318,56 @@ impl Parser {
             tts: message.tts,
         };
 
-        let context = CommandContext::new(Arc::clone(&ctx), config, cmdm, guild, shard_id, parser, permissions);
+        let guild_id = guild.as_ref().map(|guild| guild.id);
+        let author_id = message.author.id;
+        let log_command = |outcome: CommandOutcome| {
+            if let (Some((command, target, arguments)), Some(guild_id)) = (audit, guild_id) {
+                ctx.log(LogData {
+                    log_type: LogType::ModCommandUsed {
+                        command,
+                        arguments,
+                        target,
+                        outcome,
+                    },
+                    guild: guild_id,
+                    source_channel: Some(channel_id),
+                    source_user: author_id,
+                    timestamp: Utc::now(),
+                });
+            }
+        };
+
+        let in_guild = guild.is_some();
+        // Top level commands are what gets disabled, that takes care of their subcommands as well
+        let top_level = &command_nodes[0].name;
+        let disabled_here = in_guild && config.command_disabled_in(channel_id, top_level);
+        let output_channel = config
+            .command_output_channels
+            .get(top_level)
+            .copied()
+            .filter(|output_channel| in_guild && *output_channel != channel_id);
+        let mut context = CommandContext::new(Arc::clone(&ctx), config, cmdm, guild, shard_id, parser, permissions);
+
+        // Commands dealing with personal data shouldn't be spilling it into a guild channel
+        if node.dm_only && in_guild {
+            let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
+            let _ = context.reply(GearBotString::DmOnlyCommand, args).await;
+            return Ok(());
+        }
+
+        if disabled_here {
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("command", top_level.as_str())
+                .generate();
+            let _ = context.reply(GearBotString::CommandDisabledInChannel, args).await;
+            return Ok(());
+        }
 
         if !permissions.contains(node.command_permission) {
             let args = FluArgs::with_capacity(1).add("gearno", Emoji::No.for_chat()).generate();
             let _ = context.reply(GearBotString::MissingPermissions, args).await; //ignore result as there is nothing we can do if this fails
+            log_command(CommandOutcome::MissingPermissions);
             return Ok(());
         }
 
@@ -225,9 +411,80 @@ impl Parser {
             return Ok(());
         }
 
+        // Replies only move if they can actually be sent there, otherwise they stay here like nothing was configured
+        if let Some(output_channel) = output_channel {
+            let needed = Permissions::SEND_MESSAGES | node.bot_permissions;
+            if context.bot_has_permissions_in_channel(output_channel, needed).await {
+                let args = FluArgs::with_capacity(2)
+                    .add("gearinfo", Emoji::Info.for_chat())
+                    .add("channel", output_channel.to_string())
+                    .generate();
+                let _ = context.reply(GearBotString::CommandOutputRedirected, args).await;
+                context.redirect_output(output_channel);
+            }
+        }
+
+        // Better to say what's missing up front than to fail halfway through the command
+        let missing = node.bot_permissions - context.get_bot_channel_permissions().await;
+        if !missing.is_empty() {
+            let permissions = utils::permission_names(missing)
+                .iter()
+                .map(|name| format!("``{}``", name))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let args = FluArgs::with_capacity(2)
+                .add("gearno", Emoji::No.for_chat())
+                .add("permissions", permissions)
+                .generate();
+            let _ = context.reply(GearBotString::BotMissingPermissions, args).await;
+            return Ok(());
+        }
+
+        // Dry runs don't change anything, no need to ask about those
+        if node.requires_confirmation && !context.is_dry_run() {
+            let command = command_nodes
+                .iter()
+                .map(|node| node.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let args = FluArgs::with_capacity(2)
+                .add("gearwarn", Emoji::Warn.for_chat())
+                .add("command", command)
+                .generate();
+            // Failing to ask counts as a no as well
+            if !context
+                .confirm(GearBotString::ConfirmCommand, args)
+                .await
+                .unwrap_or(false)
+            {
+                return Ok(());
+            }
+        }
+
         match &node.handler {
             Some(handler) => {
-                if let Err(e) = handler(context).await {
+                if let Some(guild_id) = guild_id {
+                    ctx.count_guild_stat(guild_id, GuildStat::Command).await;
+                }
+                let command = command_nodes
+                    .iter()
+                    .map(|node| node.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                let log_context = LogContext {
+                    shard: Some(shard_id),
+                    guild: guild_id,
+                    command: Some(command.clone()),
+                };
+                let span = info_span!("command", command = %command, guild = ?guild_id);
+                let result = logging::with_context(log_context, handler(context).instrument(span)).await;
+                if let Some(guild_id) = guild_id {
+                    ctx.count_command_use(guild_id, channel_id, &command, result.is_ok())
+                        .await;
+                }
+                if let Err(e) = result {
+                    log_command(CommandOutcome::Failed(e.to_string()));
+
                     match e {
                         CommandError::ParseError(e) => {
                             ctx.http
@@ -240,7 +497,10 @@ impl Parser {
                                 .unwrap()
                                 .await?;
                         }
-                        CommandError::NoDM | CommandError::InvalidPermissions => {
+                        CommandError::NoDM
+                        | CommandError::InvalidPermissions
+                        | CommandError::MissingTier(_)
+                        | CommandError::RoleGuard(_) => {
                             ctx.http
                                 .create_message(channel_id)
                                 .content(format!("{} {}", Emoji::No.for_chat(), e))
@@ -248,15 +508,27 @@ impl Parser {
                                 .await?;
                         }
                         CommandError::OtherFailure(e) => {
+                            if let OtherFailure::TwilightHttp(http_error) = &e {
+                                ctx.track_http_error(http_error).await;
+                            }
                             ctx.http.create_message(channel_id)
                                 .content(format!("{} Something went very wrong trying to execute that command, please try again later or report this on the support server {}", Emoji::Bug.for_chat(), Emoji::Bug.for_chat())).unwrap()
                                 .await?;
 
                             //TODO: better logging
                             gearbot_error!("Command error: {}", e);
+                            let tags = ErrorTags {
+                                shard: Some(shard_id),
+                                guild: guild_id,
+                                command: Some(command),
+                                ..ErrorTags::default()
+                            };
+                            error_tracking::capture(ErrorLevel::Error, format!("Command error: {}", e), tags);
                             return Ok(());
                         }
                     }
+                } else {
+                    log_command(CommandOutcome::Succeeded);
                 }
 
                 ctx.stats.total_command_counts.fetch_add(1, Ordering::Relaxed);
@@ -271,6 +543,18 @@ impl Parser {
         }
     }
 
+    /// Removes any dry run flags from the remaining arguments, marking the parser as in dry run mode if there were any.
+    fn take_dry_run_flag(&mut self) {
+        let before = self.parts.len();
+        let index = self.index;
+        let mut position = 0;
+        self.parts.retain(|part| {
+            position += 1;
+            position <= index || !DRY_RUN_FLAGS.contains(&part.as_str())
+        });
+        self.dry_run = self.parts.len() != before;
+    }
+
     pub fn get_next(&mut self) -> Result<&str, ParseError> {
         if self.index == self.parts.len() {
             Err(ParseError::MissingArgument)
@@ -377,7 +661,7 @@ impl Parser {
     }
 
     /// Parses what comes next as discord user
-    async fn get_user(&mut self) -> Result<Arc<CachedUser>, ParseError> {
+    pub async fn get_user(&mut self) -> Result<Arc<CachedUser>, ParseError> {
         match self.get_affected_user()? {
             Some(id) => Ok(self.ctx.get_user(UserId(id)).await?),
             None => {
@@ -404,6 +688,33 @@ impl Parser {
         }
     }
 
+    /// Parses what comes next as a channel of this guild, either a mention or an id
+    pub async fn get_channel(&mut self) -> Result<Arc<CachedChannel>, ParseError> {
+        let guild_id = self.get_guild_id()?;
+        let input = self.get_next()?;
+        let id =
+            matchers::get_snowflake(input).ok_or_else(|| ParseError::WrongArgumentType(String::from("channel")))?;
+
+        match self.ctx.cache.get_channel(ChannelId(id)).await {
+            Some(channel) if channel.get_guild_id() == Some(guild_id) => Ok(channel),
+            _ => Err(ParseError::UnknownChannel(id)),
+        }
+    }
+
+    /// Parses what comes next as a dice expression, like `2d20+3`
+    pub fn get_dice(&mut self) -> Result<DiceRoll, ParseError> {
+        let input = self.get_next()?;
+        DiceRoll::parse(input).ok_or_else(|| ParseError::InvalidDice(input.to_string()))
+    }
+
+    pub fn get_dice_or(&mut self, alternative: DiceRoll) -> Result<DiceRoll, ParseError> {
+        if self.has_next() {
+            self.get_dice()
+        } else {
+            Ok(alternative)
+        }
+    }
+
     pub fn peek(&self) -> Option<&String> {
         self.parts.get(self.index)
     }
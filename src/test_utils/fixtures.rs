@@ -0,0 +1,118 @@
+use serde_json::json;
+use twilight_model::guild::{Guild, Member, Permissions, Role};
+use twilight_model::user::User;
+
+use crate::cache::{CachedGuild, CachedMember, CachedRole};
+
+// Everything is built from json shaped like the api payloads rather than struct literals, so fixtures don't need
+// touching every time twilight adds a field.
+
+pub fn user(id: u64, name: &str) -> User {
+    serde_json::from_value(json!({
+        "id": id.to_string(),
+        "username": name,
+        "discriminator": "0001",
+        "avatar": null,
+        "bot": false,
+    }))
+    .expect("invalid user fixture")
+}
+
+pub fn role(id: u64, name: &str, position: i64, permissions: Permissions) -> Role {
+    serde_json::from_value(json!({
+        "id": id.to_string(),
+        "name": name,
+        "color": 0,
+        "hoist": false,
+        "position": position,
+        "permissions": permissions.bits().to_string(),
+        "managed": false,
+        "mentionable": false,
+    }))
+    .expect("invalid role fixture")
+}
+
+pub fn member(guild_id: u64, user: &User, roles: &[u64]) -> Member {
+    serde_json::from_value(json!({
+        "guild_id": guild_id.to_string(),
+        "user": user,
+        "nick": null,
+        "roles": roles.iter().map(|role| role.to_string()).collect::<Vec<String>>(),
+        "joined_at": "2020-01-01T00:00:00.000000+00:00",
+        "premium_since": null,
+        "deaf": false,
+        "mute": false,
+        "pending": false,
+    }))
+    .expect("invalid member fixture")
+}
+
+/// A guild as it shows up in a guild create, the everyone role (with the same id as the guild) is always included.
+pub fn guild(id: u64, owner_id: u64, roles: Vec<Role>, members: Vec<Member>) -> Guild {
+    let mut roles = roles;
+    if !roles.iter().any(|role| role.id.0 == id) {
+        roles.push(role(
+            id,
+            "@everyone",
+            0,
+            Permissions::SEND_MESSAGES | Permissions::VIEW_CHANNEL,
+        ));
+    }
+
+    serde_json::from_value(json!({
+        "id": id.to_string(),
+        "name": format!("Test guild {}", id),
+        "icon": null,
+        "splash": null,
+        "discovery_splash": null,
+        "owner_id": owner_id.to_string(),
+        "region": "europe",
+        "afk_channel_id": null,
+        "afk_timeout": 300,
+        "verification_level": 0,
+        "default_message_notifications": 0,
+        "explicit_content_filter": 0,
+        "roles": roles,
+        "emojis": [],
+        "features": [],
+        "mfa_level": 0,
+        "application_id": null,
+        "system_channel_id": null,
+        "system_channel_flags": 0,
+        "rules_channel_id": null,
+        "joined_at": "2020-01-01T00:00:00.000000+00:00",
+        "large": false,
+        "unavailable": false,
+        "member_count": members.len(),
+        "members": members,
+        "channels": [],
+        "presences": [],
+        "voice_states": [],
+        "max_presences": null,
+        "max_members": 250000,
+        "vanity_url_code": null,
+        "description": null,
+        "banner": null,
+        "premium_tier": 0,
+        "premium_subscription_count": 0,
+        "preferred_locale": "en-US",
+        "public_updates_channel_id": null,
+        "nsfw": false,
+    }))
+    .expect("invalid guild fixture")
+}
+
+pub async fn cached_guild(guild: Guild) -> CachedGuild {
+    CachedGuild::from_guild(guild).await
+}
+
+pub fn cached_role(id: u64, position: i64, permissions: Permissions) -> CachedRole {
+    CachedRole::from_role(&role(id, "test", position, permissions))
+}
+
+/// A member as the cache has it, `joined_at` is in the same format the api uses.
+pub fn cached_member(id: u64, joined_at: &str, roles: &[u64]) -> CachedMember {
+    let mut member = member(1, &user(id, "member"), roles);
+    member.joined_at = Some(joined_at.to_string());
+    CachedMember::from_member(&member)
+}
@@ -0,0 +1,27 @@
+use twilight_gateway::Event;
+use twilight_model::gateway::payload::{GuildCreate, GuildDelete, MemberAdd, MemberRemove};
+use twilight_model::guild::{Guild, Member};
+use twilight_model::id::GuildId;
+use twilight_model::user::User;
+
+pub fn guild_create(guild: Guild) -> Event {
+    Event::GuildCreate(Box::new(GuildCreate(guild)))
+}
+
+pub fn guild_delete(guild_id: u64, unavailable: bool) -> Event {
+    Event::GuildDelete(Box::new(GuildDelete {
+        id: GuildId(guild_id),
+        unavailable,
+    }))
+}
+
+pub fn member_add(member: Member) -> Event {
+    Event::MemberAdd(Box::new(MemberAdd(member)))
+}
+
+pub fn member_remove(guild_id: u64, user: User) -> Event {
+    Event::MemberRemove(MemberRemove {
+        guild_id: GuildId(guild_id),
+        user,
+    })
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use twilight_http::Client as HttpClient;
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// The route without the api version prefix, like `/channels/123/messages`.
+    pub path: String,
+    pub body: Value,
+}
+
+type Responses = HashMap<(Method, String), (StatusCode, Value)>;
+
+/// A stand-in for the discord api that records every request it gets.
+///
+/// Routes without a configured response get a 404, same as discord does for unknown objects.
+pub struct FakeDiscord {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    responses: Arc<Mutex<Responses>>,
+}
+
+impl FakeDiscord {
+    pub async fn start() -> Self {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let responses = Arc::new(Mutex::new(HashMap::new()));
+
+        let service_requests = requests.clone();
+        let service_responses = responses.clone();
+        let service = make_service_fn(move |_| {
+            let requests = service_requests.clone();
+            let responses = service_responses.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let requests = requests.clone();
+                    let responses = responses.clone();
+                    async move { Ok::<_, Infallible>(handle(req, &requests, &responses).await) }
+                }))
+            }
+        });
+
+        let server = hyper::server::Server::bind(&([127, 0, 0, 1], 0).into()).serve(service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        FakeDiscord {
+            addr,
+            requests,
+            responses,
+        }
+    }
+
+    /// A client that sends everything to this fake instead of discord.
+    pub fn client(&self) -> HttpClient {
+        HttpClient::builder()
+            .token("fake-token")
+            .proxy(self.addr.to_string(), true)
+            .ratelimiter(None)
+            .build()
+    }
+
+    pub async fn respond(&self, method: Method, path: &str, status: StatusCode, body: Value) {
+        self.responses
+            .lock()
+            .await
+            .insert((method, path.to_string()), (status, body));
+    }
+
+    pub async fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().await.clone()
+    }
+
+    pub async fn requests_to(&self, method: Method, path: &str) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .await
+            .iter()
+            .filter(|request| request.method == method && request.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+async fn handle(
+    req: Request<Body>,
+    requests: &Mutex<Vec<RecordedRequest>>,
+    responses: &Mutex<Responses>,
+) -> Response<Body> {
+    let method = req.method().clone();
+    let path = strip_version(req.uri().path());
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    requests.lock().await.push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body,
+    });
+
+    let (status, body) = responses
+        .lock()
+        .await
+        .get(&(method, path))
+        .cloned()
+        .unwrap_or_else(|| (StatusCode::NOT_FOUND, json!({"message": "Unknown", "code": 0})));
+
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn strip_version(path: &str) -> String {
+    match path.strip_prefix("/api/") {
+        Some(rest) => match rest.find('/') {
+            Some(index) => rest[index..].to_string(),
+            None => String::from("/"),
+        },
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_api_version() {
+        assert_eq!(strip_version("/api/v8/channels/1/messages"), "/channels/1/messages");
+        assert_eq!(strip_version("/channels/1"), "/channels/1");
+    }
+}
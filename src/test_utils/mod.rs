@@ -0,0 +1,45 @@
+//! Helpers for testing code that talks to discord without actually connecting to it.
+//!
+//! - [`fixtures`] builds guilds, roles, members and users the same way they arrive from the api, or as cached
+//! - [`gateway`] wraps those into gateway events, ready to be fed into whatever handles them
+//! - [`http`] runs a fake discord api that records every request and replies with canned responses
+
+pub mod fixtures;
+pub mod gateway;
+pub mod http;
+
+#[cfg(test)]
+mod tests {
+    use hyper::Method;
+    use twilight_model::guild::Permissions;
+    use twilight_model::id::{ChannelId, RoleId, UserId};
+
+    use super::{fixtures, http::FakeDiscord};
+
+    #[tokio::test]
+    async fn fixtures_make_it_into_the_cache() {
+        let owner = fixtures::user(2, "owner");
+        let moderator = fixtures::role(3, "moderator", 1, Permissions::KICK_MEMBERS);
+        let guild = fixtures::guild(1, 2, vec![moderator], vec![fixtures::member(1, &owner, &[3])]);
+
+        let cached = fixtures::cached_guild(guild).await;
+        assert_eq!(cached.owner_id, UserId(2));
+        let roles = cached.roles.read().await;
+        assert_eq!(roles.len(), 2);
+        assert_eq!(roles[&RoleId(3)].permissions, Permissions::KICK_MEMBERS);
+    }
+
+    #[tokio::test]
+    async fn fake_discord_records_requests() {
+        let discord = FakeDiscord::start().await;
+        let http = discord.client();
+
+        // Nothing is configured for this route so it fails, but it still gets recorded
+        let result = http.create_message(ChannelId(5)).content("hello").unwrap().await;
+        assert!(result.is_err());
+
+        let requests = discord.requests_to(Method::POST, "/channels/5/messages").await;
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body["content"], "hello");
+    }
+}
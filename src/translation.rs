@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::sync::Arc;
 
@@ -11,6 +11,10 @@ type FluentBundle = RawBundle<FluentResource, Memoizer>;
 
 use crate::gearbot_warn;
 
+mod formatting;
+
+pub use formatting::{format_date, format_datetime, format_number, format_zoned_datetime};
+
 const TRANSLATION_DIR: &str = "./lang";
 const FAILED_TRANSLATE_FALLBACK_MSG: &str =
     "A translation error occured and no fallback could be found! Something may be wrong with the guild configuration!";
@@ -20,7 +24,30 @@ const FAILED_TRANSLATE_FALLBACK_MSG: &str =
 pub const DEFAULT_LANG: LanguageIdentifier = langid!("en_US");
 
 /// The transations for all languages that the bot can handle.
-pub struct Translations(HashMap<LanguageIdentifier, Arc<FluentBundle>>);
+pub struct Translations {
+    bundles: HashMap<LanguageIdentifier, Arc<FluentBundle>>,
+    /// The keys each language has a translation for, to report how complete it is.
+    keys: HashMap<LanguageIdentifier, HashSet<String>>,
+}
+
+/// How much of the default language a language has translated.
+pub struct LanguageCoverage {
+    pub language: LanguageIdentifier,
+    pub translated: usize,
+    pub total: usize,
+    /// Keys of the default language this language doesn't have yet, sorted.
+    pub missing: Vec<String>,
+}
+
+impl LanguageCoverage {
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.translated as f64 / self.total as f64 * 100.0
+        }
+    }
+}
 
 pub struct FluArgs<'a>(FluentArgs<'a>);
 
@@ -46,39 +73,7 @@ impl Translations {
     /// Retreives a string key to use when sending a message to chat that *does not* require arguments and can be sent as fetched with no
     /// further modifications.
     pub fn get_text_plain(&self, lang_key: &LanguageIdentifier, string_key: GearBotString) -> Cow<str> {
-        // TODO: See how well this will work out in practice with unwrapping
-        let lang_bundle = self.0.get(lang_key).unwrap();
-
-        if let Some(expected_msg) = lang_bundle.get_message(string_key.as_str()) {
-            let mut errors = Vec::new();
-
-            let pattern = expected_msg.value().unwrap();
-
-            let value = lang_bundle.format_pattern(pattern, None, &mut errors);
-
-            handle_translation_error(&errors, string_key, false);
-
-            value
-        } else {
-            // If we can't find the key in the expected language, fallback to English
-            let fallback_bundle = self.0.get(&DEFAULT_LANG).unwrap();
-
-            if let Some(fallback_msg) = fallback_bundle.get_message(string_key.as_str()) {
-                let mut errors = Vec::new();
-
-                let pattern = fallback_msg.value().unwrap();
-
-                let value = lang_bundle.format_pattern(pattern, None, &mut errors);
-
-                handle_translation_error(&errors, string_key, true);
-
-                value
-            } else {
-                // Something really went wrong, error in chat and the logs
-                gearbot_warn!("{}", FAILED_TRANSLATE_FALLBACK_MSG);
-                Cow::Borrowed(FAILED_TRANSLATE_FALLBACK_MSG)
-            }
-        }
+        self.format(lang_key, string_key, None)
     }
 
     /// Retreives a string key to use when sending a message to chat that *does* require arguments and must have some fields
@@ -91,42 +86,88 @@ impl Translations {
         string_key: GearBotString,
         args: &'a FluentArgs<'a>,
     ) -> Cow<'a, str> {
-        let lang_bundle = self.0.get(lang_key).unwrap();
-
-        if let Some(expected_msg) = lang_bundle.get_message(string_key.as_str()) {
-            let mut errors = Vec::new();
-
-            let pattern = expected_msg.value().unwrap();
-
-            let value = lang_bundle.format_pattern(pattern, Some(args), &mut errors);
-
-            handle_translation_error(&errors, string_key, false);
-
-            value
-        } else {
-            // If we can't find the key in the expected language, fallback to English
-            let fallback_bundle = self.0.get(&DEFAULT_LANG).unwrap();
+        self.format(lang_key, string_key, Some(args))
+    }
 
-            if let Some(fallback_msg) = fallback_bundle.get_message(string_key.as_str()) {
-                let mut errors = Vec::new();
+    /// Formats the string in the first language of the fallback chain that has it, so languages that are only
+    /// partially translated still show something readable.
+    fn format<'a>(
+        &'a self,
+        lang_key: &LanguageIdentifier,
+        string_key: GearBotString,
+        args: Option<&'a FluentArgs<'a>>,
+    ) -> Cow<'a, str> {
+        for (i, bundle) in self.fallback_chain(lang_key).into_iter().enumerate() {
+            let pattern = match bundle.get_message(string_key.as_str()).and_then(|msg| msg.value()) {
+                Some(pattern) => pattern,
+                None => continue,
+            };
 
-                let pattern = fallback_msg.value().unwrap();
+            let mut errors = Vec::new();
+            let value = bundle.format_pattern(pattern, args, &mut errors);
+            handle_translation_error(&errors, string_key, i > 0);
 
-                let value = lang_bundle.format_pattern(pattern, Some(args), &mut errors);
+            return value;
+        }
 
-                handle_translation_error(&errors, string_key, true);
+        // Something really went wrong, error in chat and the logs
+        gearbot_warn!("{}", FAILED_TRANSLATE_FALLBACK_MSG);
+        Cow::Borrowed(FAILED_TRANSLATE_FALLBACK_MSG)
+    }
 
-                value
-            } else {
-                // Something really went wrong, error in chat and the logs
-                gearbot_warn!("{}", FAILED_TRANSLATE_FALLBACK_MSG);
-                Cow::Borrowed(FAILED_TRANSLATE_FALLBACK_MSG)
+    /// The bundles to look for a string in: the language itself, the language without its region (``de`` for
+    /// ``de_AT``) and finally the default language. Languages that aren't loaded are skipped.
+    fn fallback_chain(&self, lang_key: &LanguageIdentifier) -> Vec<&Arc<FluentBundle>> {
+        let base = LanguageIdentifier::from_parts(lang_key.language, None, None, &[]);
+
+        let mut chain: Vec<&Arc<FluentBundle>> = Vec::with_capacity(3);
+        for lang in [lang_key, &base, &DEFAULT_LANG].iter() {
+            if let Some(bundle) = self.bundles.get(lang) {
+                if !chain.iter().any(|known| Arc::ptr_eq(known, bundle)) {
+                    chain.push(bundle);
+                }
             }
         }
+        chain
+    }
+
+    /// The coverage of every loaded language, compared to the default language.
+    pub fn coverage(&self) -> Vec<LanguageCoverage> {
+        let empty = HashSet::new();
+        let expected = self.keys.get(&DEFAULT_LANG).unwrap_or(&empty);
+
+        let mut coverage: Vec<LanguageCoverage> = self
+            .keys
+            .iter()
+            .map(|(language, keys)| {
+                let mut missing: Vec<String> = expected.difference(keys).cloned().collect();
+                missing.sort();
+                LanguageCoverage {
+                    language: language.clone(),
+                    translated: expected.len() - missing.len(),
+                    total: expected.len(),
+                    missing,
+                }
+            })
+            .collect();
+        coverage.sort_by(|a, b| a.language.to_string().cmp(&b.language.to_string()));
+        coverage
+    }
+
+    /// Writes out a duration in seconds in the language, with at most `max_parts` units.
+    pub fn format_duration(&self, lang_key: &LanguageIdentifier, seconds: i64, max_parts: usize) -> String {
+        formatting::duration_parts(seconds, max_parts)
+            .into_iter()
+            .map(|(unit, amount)| {
+                let args = FluArgs::with_capacity(1).add("count", amount).generate();
+                self.get_text_with_args(lang_key, unit, &args).into_owned()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 
     pub fn get_translator(&self, lang: &LanguageIdentifier) -> Arc<FluentBundle> {
-        Arc::clone(self.0.get(lang).unwrap())
+        Arc::clone(self.fallback_chain(lang)[0])
     }
 }
 
@@ -152,6 +193,7 @@ fn handle_translation_error(errors: &[FluentError], key: GearBotString, is_fallb
 // This allows us to take full advantage of the type system to make sure that a key always exists in an
 // ergonomic way instead of checking a bunch of options.
 /// This is where *all* of the different things Gearbot can say should go.
+#[derive(Clone, Copy)]
 pub enum GearBotString {
     // Basic commands
     PingPong,
@@ -160,22 +202,590 @@ pub enum GearBotString {
     CoinflipNo,
     UserinfoHeader,
     UserinfoNoRoles,
+    UserinfoNetworkReputation,
     AboutDescription,
     QuoteNotFound,
+    QuoteJumpLink,
+    QuoteFooter,
+    UidDescription,
+    UidMatchesHeader,
+    UidNoMatches,
+    UidMatchUser,
+    UidMatchChannel,
+    UidMatchRole,
+    UidMatchGuild,
+    PingGateway,
+    PingRest,
+    PingDatabase,
+    PingShardLatency,
+    PingCurrentShardLatency,
+    PingClusterAverage,
+    PingUnavailable,
 
     EmojiPageHeader,
     EmojiOverviewHeader,
     EmojiInfo,
 
-    //General logs (Text)
-    CommandUsedText,
-
-    //General logs (embed)
-    CommandUsedEmbed,
+    // Levels
+    LevelsDisabled,
+    LevelsNoXp,
+    LevelsRank,
+    LevelsLeaderboardHeader,
+    LevelsLeaderboardEntry,
+    LevelsLeaderboardEmpty,
+
+    // Moderation
+    SnipeNothing,
+    EditSnipeNothing,
+    SnipeBefore,
+    SnipeAfter,
+    UndoNothing,
+    UndoRoleRemoved,
+    UndoRolesAdded,
+    UndoRolesRemoved,
+    UndoChannelsLocked,
+
+    // Fun commands
+    FunRollResult,
+    FunChooseNotEnough,
+    FunChooseResult,
+    FunEightBallNoQuestion,
+    FunEightBallCertain,
+    FunEightBallNoDoubt,
+    FunEightBallDefinitely,
+    FunEightBallOutlookGood,
+    FunEightBallHazy,
+    FunEightBallAskLater,
+    FunEightBallCannotPredict,
+    FunEightBallNo,
+    FunEightBallSourcesNo,
+    FunEightBallDoubtful,
+
+    // Command logs
+    ModCommandUsed,
+    ModCommandUsedFooter,
+    ModCommandNoTarget,
+    ModCommandNoArguments,
+    ModCommandSucceeded,
+    ModCommandFailed,
+    ModCommandDenied,
+
+    // Dry runs
+    DryRunNotice,
+
+    // Confirmations
+    ConfirmCommand,
+    ConfirmationInstructions,
+    ConfirmationCancelled,
+
+    // Output redirection
+    CommandOutputRedirected,
+
+    // Message logs
+    MessagesBulkDeleted,
+    MessagesBulkDeletedFooter,
+    MessageDeleted,
+    MessageDeletedAttachments,
+    MessageDeletedFooter,
+
+    // Onboarding
+    OnboardingWelcome,
+    OnboardingNoLogChannel,
+
+    // My data
+    MyDataExport,
+    MyDataDeleteConfirm,
+    MyDataDeleted,
+
+    // Birthdays
+    BirthdaysDisabled,
+    BirthdayInvalidDate,
+    BirthdayShow,
+    BirthdayNone,
+    BirthdaySet,
+    BirthdayRemoved,
+
+    // Features
+    FeatureDisabled,
+    FeatureUnknown,
+    FeatureListHeader,
+    FeatureListEntry,
+    FeatureEnabledNow,
+    FeatureDisabledNow,
+
+    // Aliases
+    AliasListHeader,
+    AliasListEntry,
+    AliasListEmpty,
+    AliasAdded,
+    AliasRemoved,
+    AliasUnknown,
+    AliasTaken,
+    AliasUnknownCommand,
+    AliasLimit,
+
+    // Embeds
+    EmbedOverflow,
+
+    // Channel commands
+    ChannelCommandsHeader,
+    ChannelCommandsEntry,
+    ChannelCommandsOverviewHeader,
+    ChannelCommandsOverviewEntry,
+    ChannelCommandsOverviewEmpty,
+    ChannelCommandDisabled,
+    ChannelCommandEnabled,
+    ChannelCommandNotDisabled,
+    ChannelCommandUnknown,
+    ChannelCommandLocked,
+    ChannelCommandOutputSet,
+    ChannelCommandOutputCleared,
+
+    // Bot bans
+    BotBanAdded,
+    BotBanRemoved,
+    BotBanAlreadyBanned,
+    BotBanNotBanned,
+    BotBanSelf,
+    BotBanOwner,
+
+    // Search
+    SearchHeader,
+    SearchEntry,
+    SearchNothing,
+    SearchScanLimit,
+    SearchInvalidFilter,
+
+    // Archive
+    ArchiveCreated,
+    ArchiveStored,
+    ArchiveNothing,
+    ArchiveRetrieved,
+    ArchiveUnknown,
+
+    // Config transfer
+    ConfigExported,
+    ConfigImportNoFile,
+    ConfigImportTooBig,
+    ConfigImportDownloadFailed,
+    ConfigImportInvalid,
+    ConfigImported,
+    ConfigValidateInvalid,
+    ConfigValidateValid,
+    ConfigValidateProblems,
+    ConfigValidateMore,
+
+    // Automod logs
+    AutomodTriggered,
+    AutomodTriggeredFooter,
+    AutomodNoActions,
+    HeatEscalated,
+    HeatEscalatedFooter,
+    LogActionFailed,
+
+    // Automod
+    AutomodListHeader,
+    AutomodListEntry,
+    AutomodListEmpty,
+    AutomodRuleAdded,
+    AutomodRuleUpdated,
+    AutomodRuleRemoved,
+    AutomodRuleInvalid,
+    AutomodRuleUnknown,
+    AutomodPatternTooLong,
+    AutomodPatternTooDeep,
+    AutomodPatternTooBig,
+
+    // Autoresponses
+    AutoResponseListHeader,
+    AutoResponseListEntry,
+    AutoResponseListEmpty,
+    AutoResponseAdded,
+    AutoResponseUpdated,
+    AutoResponseRemoved,
+    AutoResponseInvalid,
+    AutoResponseUnknown,
+
+    // Content policies
+    ContentPolicySpoilerDm,
+    ContentPolicyNsfwDm,
+
+    // Suggestions
+    SuggestionsDisabled,
+    SuggestionSent,
+    SuggestionFailed,
+    SuggestionTitle,
+    SuggestionStatusField,
+    SuggestionReasonField,
+    SuggestionOpen,
+    SuggestionApproved,
+    SuggestionDenied,
+    SuggestionConsidered,
+    SuggestionUpdated,
+    SuggestionUnknown,
+    SuggestionStatusDm,
+
+    // Tickets
+    TicketsDisabled,
+    TicketOpened,
+    TicketAtLimit,
+    TicketFailed,
+    TicketNotATicket,
+    TicketNotYours,
+    TicketClaimed,
+    TicketAlreadyClaimed,
+    TicketUnknown,
+    TicketNoTranscript,
+    TicketTranscript,
+    TicketPanel,
+
+    // Events
+    EventInvalidTime,
+    EventInPast,
+    EventStartsField,
+    EventGoingField,
+    EventMaybeField,
+    EventDeclinedField,
+    EventNobody,
+    EventFooter,
+    EventReminder,
+    EventUnknown,
+    EventNotYours,
+    EventCancelled,
+
+    // Auto publish
+    AutoPublishListEmpty,
+    AutoPublishListHeader,
+    AutoPublishListEntry,
+    AutoPublishListEntryBots,
+    AutoPublishEveryone,
+    AutoPublishNotAnnouncements,
+    AutoPublishMissingPermissions,
+    AutoPublishAdded,
+    AutoPublishRemoved,
+    AutoPublishNotConfigured,
+    AutoPublishFilterUpdated,
+    AutoPublishBotsEnabled,
+    AutoPublishBotsDisabled,
+
+    // Feeds
+    FeedListEmpty,
+    FeedListHeader,
+    FeedListEntry,
+    FeedCantPost,
+    FeedInvalidInterval,
+    FeedLimit,
+    FeedInvalidUrl,
+    FeedUnreadable,
+    FeedAdded,
+    FeedUnknown,
+    FeedRemoved,
+    FeedTemplateTooLong,
+    FeedTemplateUpdated,
+    FeedIntervalUpdated,
+    // GitHub
+    GithubNotEnabled,
+    GithubListEmpty,
+    GithubListHeader,
+    GithubListEntry,
+    GithubInvalidRepository,
+    GithubCantPost,
+    GithubUnknownEvent,
+    GithubLimit,
+    GithubAlreadyFollowed,
+    GithubSetupDm,
+    GithubDmFailed,
+    GithubAdded,
+    GithubUnknownRepository,
+    GithubRemoved,
+    GithubEventsUpdated,
+    GithubSecretReset,
+    GithubPushTitle,
+    GithubPushMore,
+    GithubReleaseTitle,
+    GithubIssueOpened,
+    GithubIssueClosed,
+    GithubIssueReopened,
+    // Webhooks
+    WebhookNotEnabled,
+    WebhookListEmpty,
+    WebhookListHeader,
+    WebhookListEntry,
+    WebhookCantPost,
+    WebhookTemplateTooLong,
+    WebhookLimit,
+    WebhookSetupDm,
+    WebhookDmFailed,
+    WebhookAdded,
+    WebhookUnknown,
+    WebhookRemoved,
+    WebhookTemplateUpdated,
+    WebhookTokenReset,
+
+    // Pin archive
+    PinArchiveJump,
+    PinArchiveFooter,
+
+    // Heat
+    HeatCurrent,
+    HeatDisabled,
+    HeatReset,
+
+    // Join gate logs
+    JoinGateFlagged,
+    JoinGateFlaggedFooter,
+    JoinGateNewAccount,
+    JoinGateNoAvatar,
+    JoinGateNetworkBans,
+    JoinGateActionLog,
+    JoinGateActionProbation,
+    JoinGateActionVerification,
+    JoinGateActionKick,
+    JoinGateAfterScreening,
+
+    // Join gate
+    JoinGateVerified,
+    JoinGateNotGated,
+
+    // Raid mode logs
+    RaidModeEnabledLog,
+    RaidModeDetected,
+    RaidModeDisabledLog,
+    RaidModeFooter,
+
+    // Raid mode
+    RaidModeStatusOn,
+    RaidModeStatusOff,
+    RaidModeOn,
+    RaidModeOff,
+    RaidModeAlreadyOn,
+    RaidModeAlreadyOff,
+    RaidModeNoChanges,
+    RaidModeVerificationRaised,
+    RaidModeInvitesPaused,
+    RaidModeChannelsRestricted,
+    RaidModeAutoOn,
+    RaidModeAutoOff,
+
+    // Profiles
+    ProfileListEmpty,
+    ProfileListHeader,
+    ProfileListEntry,
+    ProfileListPending,
+    ProfileLimitReached,
+    ProfileSaved,
+    ProfileDeleted,
+    ProfileUnknown,
+    ProfileInvalid,
+    ProfileInvalidDuration,
+    ProfileApplied,
+    ProfileAppliedUntil,
+    ProfileReverted,
+    ProfileNothingToRevert,
+
+    // Timezone
+    TimezoneUnknown,
+    TimezoneSet,
+
+    // Prune
+    PruneInvalidWindow,
+    PrunePreview,
+    PruneNothing,
+    PruneNoPreview,
+    PruneNoRole,
+    PruneExecuted,
+    PruneDryRun,
+
+    // Durations
+    DurationYears,
+    DurationMonths,
+    DurationWeeks,
+    DurationDays,
+    DurationHours,
+    DurationMinutes,
+    DurationSeconds,
+
+    // Screening logs
+    ScreeningCompleted,
+    ScreeningCompletedRole,
+    ScreeningFooter,
+
+    // Automod reviews
+    AutomodReviewQueued,
+    AutomodReviewInstructions,
+    AutomodReviewApproved,
+    AutomodReviewFailed,
+    AutomodReviewRejected,
+
+    // Watchlist logs
+    WatchedMessage,
+    WatchedJoin,
+    WatchedVoiceJoined,
+    WatchedVoiceLeft,
+    WatchedNameChange,
+    WatchlistFooter,
+
+    // Watchlist
+    WatchAdded,
+    WatchAlready,
+    WatchRemoved,
+    WatchNotWatched,
+
+    // Integration audit
+    AuditIntegrationsHeader,
+    AuditWebhook,
+    AuditBot,
+    AuditIntegration,
+    AuditUnknown,
+    AuditNothing,
+
+    // Permission escalation logs
+    RolePermissionsGranted,
+    MemberPermissionsGranted,
+    PermissionsGrantedUnknown,
+    PermissionsGrantedFooter,
+
+    // Anti-nuke logs
+    NukeDetected,
+    NukeDetectedFooter,
+    NukeRolesStripped,
+    NukeBanned,
+
+    // Join challenge logs
+    ChallengeIssued,
+    ChallengePassed,
+    ChallengeFooter,
+    ChallengeReaction,
+    ChallengeCaptcha,
+    ChallengeManual,
+
+    // Join challenges
+    ChallengeReactionPrompt,
+    ChallengeCaptchaPrompt,
+    ChallengeCaptchaWrong,
+    ChallengePassedDm,
+
+    // Log style
+    LogStyleUnknownCategory,
+    LogStylePreviewContent,
+
+    // Guild statistics
+    StatsGuildEmpty,
+    StatsGuildHeader,
+    StatsGuildLine,
+    StatsGuildTrend,
+    StatsGuildDaily,
+    StatsGuildMessages,
+    StatsGuildJoins,
+    StatsGuildLeaves,
+    StatsGuildCommands,
+    StatsGuildAutomod,
+
+    // Activity reports
+    ActivityReportInvalidWindow,
+    ActivityReportEmpty,
+    ActivityReportTitle,
+    ActivityReportTotal,
+    ActivityReportChannels,
+    ActivityReportChannel,
+    ActivityReportMoreChannels,
+    ActivityReportHours,
+    ActivityReportExported,
+
+    // Moderator statistics
+    ModStatsInvalidPeriod,
+    ModStatsNothing,
+    ModStatsTitle,
+    ModStatsModerator,
+    ModStatsKind,
+    ModStatsFooter,
+
+    // Command usage
+    UsageInvalidWindow,
+    UsageEmpty,
+    UsageHeader,
+    UsageLine,
+    UsageFailures,
+    UsageMore,
+    UsageUnused,
+
+    // Bulk roles
+    BulkRoleInvalidFilter,
+    BulkRoleNothing,
+    BulkRoleDryRun,
+    BulkRoleAlreadyRunning,
+    BulkRoleProgress,
+    BulkRoleFinished,
+    BulkRoleCancelled,
+    BulkRoleCancelRequested,
+    BulkRoleNotRunning,
+
+    // Role audits
+    RoleAuditClean,
+    RoleAuditHeader,
+    RoleAuditEmpty,
+    RoleAuditDuplicates,
+    RoleAuditHoisted,
+    RoleAuditAboveBot,
+    RoleAuditHolders,
+    RoleAuditFixNothing,
+    RoleAuditFixDryRun,
+    RoleAuditFixed,
+
+    // Emoji logs
+    EmojiCreatedLog,
+    EmojiDeletedLog,
+    EmojiRenamedLog,
+    EmojiLogFooter,
+
+    // Emoji management
+    EmojiAdded,
+    EmojiRemoved,
+    EmojiRenamed,
+    EmojiInvalidName,
+    EmojiNoSlots,
+    EmojiNoImage,
+    EmojiDownloadFailed,
+    EmojiTooBig,
+    EmojiNotFound,
+    EmojiManaged,
+    EmojiNothingToSteal,
+
+    // Channel templates logs
+    ChannelClonedLog,
+    ChannelTemplateAppliedLog,
+    ChannelLogFooter,
+
+    // Channel templates
+    ChannelCloned,
+    ChannelCreateFailed,
+    ChannelTemplateSaved,
+    ChannelTemplateApplied,
+    ChannelTemplateRemoved,
+    ChannelTemplateUnknown,
+    ChannelTemplateInvalidName,
+    ChannelTemplateLimitReached,
+    ChannelTemplateUnsupported,
+    ChannelTemplateListEmpty,
+    ChannelTemplateListHeader,
+
+    // Whereis
+    WhereisTitle,
+    WhereisCluster,
+    WhereisSummary,
+    WhereisMissing,
+
+    //General logs
+    CommandUsed,
     CommandUsedFooter,
+    LogTimestamp,
 
     //Errors
     MissingPermissions,
+    DmOnlyCommand,
+    UnknownCommand,
+    BotMissingPermissions,
+    CommandDisabledInChannel,
 
     //DM error strings
     UnableToReply,
@@ -199,9 +809,459 @@ impl GearBotString {
             GearBotString::EmojiPageHeader => "basic__emoji_page_header",
             GearBotString::EmojiOverviewHeader => "basic__emoji_overview_header",
             GearBotString::EmojiInfo => "basic__emoji_info",
-            GearBotString::CommandUsedText => "command_used_text",
-            GearBotString::CommandUsedEmbed => "command_used_embed",
+            GearBotString::CommandUsed => "command_used",
+            GearBotString::LogTimestamp => "log_timestamp",
             GearBotString::CommandUsedFooter => "command_used_footer",
+            GearBotString::LevelsDisabled => "levels__disabled",
+            GearBotString::LevelsNoXp => "levels__no_xp",
+            GearBotString::LevelsRank => "levels__rank",
+            GearBotString::LevelsLeaderboardHeader => "levels__leaderboard_header",
+            GearBotString::LevelsLeaderboardEntry => "levels__leaderboard_entry",
+            GearBotString::LevelsLeaderboardEmpty => "levels__leaderboard_empty",
+            GearBotString::SnipeNothing => "moderation__snipe_nothing",
+            GearBotString::EditSnipeNothing => "moderation__editsnipe_nothing",
+            GearBotString::SnipeBefore => "moderation__snipe_before",
+            GearBotString::SnipeAfter => "moderation__snipe_after",
+            GearBotString::QuoteJumpLink => "basic__quote_jump_link",
+            GearBotString::QuoteFooter => "basic__quote_footer",
+            GearBotString::FunRollResult => "fun__roll_result",
+            GearBotString::FunChooseNotEnough => "fun__choose_not_enough",
+            GearBotString::FunChooseResult => "fun__choose_result",
+            GearBotString::FunEightBallNoQuestion => "fun__eightball_no_question",
+            GearBotString::FunEightBallCertain => "fun__eightball_certain",
+            GearBotString::FunEightBallNoDoubt => "fun__eightball_no_doubt",
+            GearBotString::FunEightBallDefinitely => "fun__eightball_definitely",
+            GearBotString::FunEightBallOutlookGood => "fun__eightball_outlook_good",
+            GearBotString::FunEightBallHazy => "fun__eightball_hazy",
+            GearBotString::FunEightBallAskLater => "fun__eightball_ask_later",
+            GearBotString::FunEightBallCannotPredict => "fun__eightball_cannot_predict",
+            GearBotString::FunEightBallNo => "fun__eightball_no",
+            GearBotString::FunEightBallSourcesNo => "fun__eightball_sources_no",
+            GearBotString::FunEightBallDoubtful => "fun__eightball_doubtful",
+            GearBotString::UidDescription => "basic__uid_description",
+            GearBotString::UidMatchesHeader => "basic__uid_matches_header",
+            GearBotString::UidNoMatches => "basic__uid_no_matches",
+            GearBotString::UidMatchUser => "basic__uid_match_user",
+            GearBotString::UidMatchChannel => "basic__uid_match_channel",
+            GearBotString::UidMatchRole => "basic__uid_match_role",
+            GearBotString::UidMatchGuild => "basic__uid_match_guild",
+            GearBotString::PingGateway => "basic__ping_gateway",
+            GearBotString::PingRest => "basic__ping_rest",
+            GearBotString::PingDatabase => "basic__ping_database",
+            GearBotString::PingShardLatency => "basic__ping_shard_latency",
+            GearBotString::PingCurrentShardLatency => "basic__ping_current_shard_latency",
+            GearBotString::PingClusterAverage => "basic__ping_cluster_average",
+            GearBotString::PingUnavailable => "basic__ping_unavailable",
+            GearBotString::ModCommandUsed => "mod_command_used",
+            GearBotString::ModCommandUsedFooter => "mod_command_used_footer",
+            GearBotString::ModCommandNoTarget => "mod_command_no_target",
+            GearBotString::ModCommandNoArguments => "mod_command_no_arguments",
+            GearBotString::ModCommandSucceeded => "mod_command_succeeded",
+            GearBotString::ModCommandFailed => "mod_command_failed",
+            GearBotString::ModCommandDenied => "mod_command_denied",
+            GearBotString::DryRunNotice => "dry_run_notice",
+            GearBotString::UndoNothing => "moderation__undo_nothing",
+            GearBotString::UndoRoleRemoved => "moderation__undo_role_removed",
+            GearBotString::UndoRolesAdded => "moderation__undo_roles_added",
+            GearBotString::UndoRolesRemoved => "moderation__undo_roles_removed",
+            GearBotString::UndoChannelsLocked => "moderation__undo_channels_locked",
+            GearBotString::MessagesBulkDeleted => "messages_bulk_deleted",
+            GearBotString::MessagesBulkDeletedFooter => "messages_bulk_deleted_footer",
+            GearBotString::MessageDeleted => "message_deleted",
+            GearBotString::MessageDeletedAttachments => "message_deleted_attachments",
+            GearBotString::MessageDeletedFooter => "message_deleted_footer",
+            GearBotString::OnboardingWelcome => "onboarding_welcome",
+            GearBotString::OnboardingNoLogChannel => "onboarding_no_log_channel",
+            GearBotString::MyDataExport => "basic__mydata_export",
+            GearBotString::MyDataDeleteConfirm => "basic__mydata_delete_confirm",
+            GearBotString::MyDataDeleted => "basic__mydata_deleted",
+            GearBotString::DmOnlyCommand => "errors_dm_only_command",
+            GearBotString::FeatureDisabled => "guild_admin__feature_disabled",
+            GearBotString::FeatureUnknown => "guild_admin__feature_unknown",
+            GearBotString::FeatureListHeader => "guild_admin__feature_list_header",
+            GearBotString::FeatureListEntry => "guild_admin__feature_list_entry",
+            GearBotString::FeatureEnabledNow => "guild_admin__feature_enabled",
+            GearBotString::FeatureDisabledNow => "guild_admin__feature_disabled_now",
+            GearBotString::UnknownCommand => "errors_unknown_command",
+            GearBotString::AliasListHeader => "guild_admin__alias_list_header",
+            GearBotString::AliasListEntry => "guild_admin__alias_list_entry",
+            GearBotString::AliasListEmpty => "guild_admin__alias_list_empty",
+            GearBotString::AliasAdded => "guild_admin__alias_added",
+            GearBotString::AliasRemoved => "guild_admin__alias_removed",
+            GearBotString::AliasUnknown => "guild_admin__alias_unknown",
+            GearBotString::AliasTaken => "guild_admin__alias_taken",
+            GearBotString::AliasUnknownCommand => "guild_admin__alias_unknown_command",
+            GearBotString::AliasLimit => "guild_admin__alias_limit",
+            GearBotString::BotMissingPermissions => "errors_bot_missing_permissions",
+            GearBotString::EmbedOverflow => "embed_overflow",
+            GearBotString::CommandDisabledInChannel => "errors_command_disabled_in_channel",
+            GearBotString::ChannelCommandsHeader => "guild_admin__channel_commands_header",
+            GearBotString::ChannelCommandsEntry => "guild_admin__channel_commands_entry",
+            GearBotString::ChannelCommandsOverviewHeader => "guild_admin__channel_commands_overview_header",
+            GearBotString::ChannelCommandsOverviewEntry => "guild_admin__channel_commands_overview_entry",
+            GearBotString::ChannelCommandsOverviewEmpty => "guild_admin__channel_commands_overview_empty",
+            GearBotString::ChannelCommandDisabled => "guild_admin__channel_command_disabled",
+            GearBotString::ChannelCommandEnabled => "guild_admin__channel_command_enabled",
+            GearBotString::ChannelCommandNotDisabled => "guild_admin__channel_command_not_disabled",
+            GearBotString::ChannelCommandUnknown => "guild_admin__channel_command_unknown",
+            GearBotString::ChannelCommandLocked => "guild_admin__channel_command_locked",
+            GearBotString::BotBanAdded => "guild_admin__botban_added",
+            GearBotString::BotBanRemoved => "guild_admin__botban_removed",
+            GearBotString::BotBanAlreadyBanned => "guild_admin__botban_already_banned",
+            GearBotString::BotBanNotBanned => "guild_admin__botban_not_banned",
+            GearBotString::BotBanSelf => "guild_admin__botban_self",
+            GearBotString::BotBanOwner => "guild_admin__botban_owner",
+            GearBotString::SearchHeader => "moderation__search_header",
+            GearBotString::SearchEntry => "moderation__search_entry",
+            GearBotString::SearchNothing => "moderation__search_nothing",
+            GearBotString::SearchScanLimit => "moderation__search_scan_limit",
+            GearBotString::SearchInvalidFilter => "moderation__search_invalid_filter",
+            GearBotString::ArchiveCreated => "moderation__archive_created",
+            GearBotString::ArchiveStored => "moderation__archive_stored",
+            GearBotString::ArchiveNothing => "moderation__archive_nothing",
+            GearBotString::ArchiveRetrieved => "moderation__archive_retrieved",
+            GearBotString::ArchiveUnknown => "moderation__archive_unknown",
+            GearBotString::ConfigExported => "guild_admin__config_exported",
+            GearBotString::ConfigImportNoFile => "guild_admin__config_import_no_file",
+            GearBotString::ConfigImportTooBig => "guild_admin__config_import_too_big",
+            GearBotString::ConfigImportDownloadFailed => "guild_admin__config_import_download_failed",
+            GearBotString::ConfigImportInvalid => "guild_admin__config_import_invalid",
+            GearBotString::ConfigImported => "guild_admin__config_imported",
+            GearBotString::AutomodTriggered => "automod_triggered",
+            GearBotString::AutomodTriggeredFooter => "automod_triggered_footer",
+            GearBotString::AutomodNoActions => "automod_no_actions",
+            GearBotString::AutomodListHeader => "guild_admin__automod_list_header",
+            GearBotString::AutomodListEntry => "guild_admin__automod_list_entry",
+            GearBotString::AutomodListEmpty => "guild_admin__automod_list_empty",
+            GearBotString::AutomodRuleAdded => "guild_admin__automod_rule_added",
+            GearBotString::AutomodRuleUpdated => "guild_admin__automod_rule_updated",
+            GearBotString::AutomodRuleRemoved => "guild_admin__automod_rule_removed",
+            GearBotString::AutomodRuleInvalid => "guild_admin__automod_rule_invalid",
+            GearBotString::AutomodRuleUnknown => "guild_admin__automod_rule_unknown",
+            GearBotString::HeatEscalated => "heat_escalated",
+            GearBotString::HeatEscalatedFooter => "heat_escalated_footer",
+            GearBotString::LogActionFailed => "log_action_failed",
+            GearBotString::HeatCurrent => "moderation__heat_current",
+            GearBotString::HeatDisabled => "moderation__heat_disabled",
+            GearBotString::HeatReset => "moderation__heat_reset",
+            GearBotString::JoinGateFlagged => "join_gate_flagged",
+            GearBotString::JoinGateFlaggedFooter => "join_gate_flagged_footer",
+            GearBotString::JoinGateNewAccount => "join_gate_new_account",
+            GearBotString::JoinGateNoAvatar => "join_gate_no_avatar",
+            GearBotString::JoinGateActionLog => "join_gate_action_log",
+            GearBotString::JoinGateActionProbation => "join_gate_action_probation",
+            GearBotString::JoinGateActionVerification => "join_gate_action_verification",
+            GearBotString::JoinGateActionKick => "join_gate_action_kick",
+            GearBotString::JoinGateVerified => "moderation__join_gate_verified",
+            GearBotString::JoinGateNotGated => "moderation__join_gate_not_gated",
+            GearBotString::RaidModeEnabledLog => "raid_mode_enabled_log",
+            GearBotString::RaidModeDetected => "raid_mode_detected",
+            GearBotString::RaidModeDisabledLog => "raid_mode_disabled_log",
+            GearBotString::RaidModeFooter => "raid_mode_footer",
+            GearBotString::RaidModeStatusOn => "moderation__raid_mode_status_on",
+            GearBotString::RaidModeStatusOff => "moderation__raid_mode_status_off",
+            GearBotString::RaidModeOn => "moderation__raid_mode_on",
+            GearBotString::RaidModeOff => "moderation__raid_mode_off",
+            GearBotString::RaidModeAlreadyOn => "moderation__raid_mode_already_on",
+            GearBotString::RaidModeAlreadyOff => "moderation__raid_mode_already_off",
+            GearBotString::RaidModeNoChanges => "moderation__raid_mode_no_changes",
+            GearBotString::RaidModeVerificationRaised => "moderation__raid_mode_verification_raised",
+            GearBotString::RaidModeInvitesPaused => "moderation__raid_mode_invites_paused",
+            GearBotString::RaidModeChannelsRestricted => "moderation__raid_mode_channels_restricted",
+            GearBotString::RaidModeAutoOn => "moderation__raid_mode_auto_on",
+            GearBotString::RaidModeAutoOff => "moderation__raid_mode_auto_off",
+            GearBotString::ProfileListEmpty => "guild_admin__profile_list_empty",
+            GearBotString::ProfileListHeader => "guild_admin__profile_list_header",
+            GearBotString::ProfileListEntry => "guild_admin__profile_list_entry",
+            GearBotString::ProfileListPending => "guild_admin__profile_list_pending",
+            GearBotString::ProfileLimitReached => "guild_admin__profile_limit_reached",
+            GearBotString::ProfileSaved => "guild_admin__profile_saved",
+            GearBotString::ProfileDeleted => "guild_admin__profile_deleted",
+            GearBotString::ProfileUnknown => "guild_admin__profile_unknown",
+            GearBotString::ProfileInvalid => "guild_admin__profile_invalid",
+            GearBotString::ProfileInvalidDuration => "guild_admin__profile_invalid_duration",
+            GearBotString::ProfileApplied => "guild_admin__profile_applied",
+            GearBotString::ProfileAppliedUntil => "guild_admin__profile_applied_until",
+            GearBotString::ProfileReverted => "guild_admin__profile_reverted",
+            GearBotString::ProfileNothingToRevert => "guild_admin__profile_nothing_to_revert",
+            GearBotString::DurationYears => "duration_years",
+            GearBotString::DurationMonths => "duration_months",
+            GearBotString::DurationWeeks => "duration_weeks",
+            GearBotString::DurationDays => "duration_days",
+            GearBotString::DurationHours => "duration_hours",
+            GearBotString::DurationMinutes => "duration_minutes",
+            GearBotString::DurationSeconds => "duration_seconds",
+            GearBotString::JoinGateAfterScreening => "join_gate_after_screening",
+            GearBotString::ScreeningCompleted => "screening_completed",
+            GearBotString::ScreeningCompletedRole => "screening_completed_role",
+            GearBotString::ScreeningFooter => "screening_footer",
+            GearBotString::PruneInvalidWindow => "moderation__prune_invalid_window",
+            GearBotString::PrunePreview => "moderation__prune_preview",
+            GearBotString::PruneNothing => "moderation__prune_nothing",
+            GearBotString::PruneNoPreview => "moderation__prune_no_preview",
+            GearBotString::PruneNoRole => "moderation__prune_no_role",
+            GearBotString::PruneExecuted => "moderation__prune_executed",
+            GearBotString::PruneDryRun => "moderation__prune_dry_run",
+            GearBotString::BirthdaysDisabled => "basic__birthdays_disabled",
+            GearBotString::BirthdayInvalidDate => "basic__birthday_invalid_date",
+            GearBotString::BirthdayShow => "basic__birthday_show",
+            GearBotString::BirthdayNone => "basic__birthday_none",
+            GearBotString::BirthdaySet => "basic__birthday_set",
+            GearBotString::BirthdayRemoved => "basic__birthday_removed",
+            GearBotString::TimezoneUnknown => "guild_admin__timezone_unknown",
+            GearBotString::TimezoneSet => "guild_admin__timezone_set",
+            GearBotString::ConfirmCommand => "confirm_command",
+            GearBotString::ConfirmationInstructions => "confirmation_instructions",
+            GearBotString::ConfirmationCancelled => "confirmation_cancelled",
+            GearBotString::AutomodReviewQueued => "automod_review_queued",
+            GearBotString::AutomodReviewInstructions => "automod_review_instructions",
+            GearBotString::AutomodReviewApproved => "automod_review_approved",
+            GearBotString::AutomodReviewFailed => "automod_review_failed",
+            GearBotString::AutomodReviewRejected => "automod_review_rejected",
+            GearBotString::CommandOutputRedirected => "command_output_redirected",
+            GearBotString::ChannelCommandOutputSet => "guild_admin__channel_command_output_set",
+            GearBotString::ChannelCommandOutputCleared => "guild_admin__channel_command_output_cleared",
+            GearBotString::WatchedMessage => "watched_message",
+            GearBotString::WatchedJoin => "watched_join",
+            GearBotString::WatchedVoiceJoined => "watched_voice_joined",
+            GearBotString::WatchedVoiceLeft => "watched_voice_left",
+            GearBotString::WatchedNameChange => "watched_name_change",
+            GearBotString::WatchlistFooter => "watchlist_footer",
+            GearBotString::WatchAdded => "moderation__watch_added",
+            GearBotString::WatchAlready => "moderation__watch_already",
+            GearBotString::WatchRemoved => "moderation__watch_removed",
+            GearBotString::WatchNotWatched => "moderation__watch_not_watched",
+            GearBotString::UserinfoNetworkReputation => "basic__userinfo_network_reputation",
+            GearBotString::JoinGateNetworkBans => "join_gate_network_bans",
+            GearBotString::AuditIntegrationsHeader => "moderation__audit_integrations_header",
+            GearBotString::AuditWebhook => "moderation__audit_webhook",
+            GearBotString::AuditBot => "moderation__audit_bot",
+            GearBotString::AuditIntegration => "moderation__audit_integration",
+            GearBotString::AuditUnknown => "moderation__audit_unknown",
+            GearBotString::AuditNothing => "moderation__audit_nothing",
+            GearBotString::RolePermissionsGranted => "role_permissions_granted",
+            GearBotString::MemberPermissionsGranted => "member_permissions_granted",
+            GearBotString::PermissionsGrantedUnknown => "permissions_granted_unknown",
+            GearBotString::PermissionsGrantedFooter => "permissions_granted_footer",
+            GearBotString::NukeDetected => "nuke_detected",
+            GearBotString::NukeDetectedFooter => "nuke_detected_footer",
+            GearBotString::NukeRolesStripped => "nuke_roles_stripped",
+            GearBotString::NukeBanned => "nuke_banned",
+            GearBotString::ChallengeIssued => "challenge_issued",
+            GearBotString::ChallengePassed => "challenge_passed",
+            GearBotString::ChallengeFooter => "challenge_footer",
+            GearBotString::ChallengeReaction => "challenge_reaction",
+            GearBotString::ChallengeCaptcha => "challenge_captcha",
+            GearBotString::ChallengeManual => "challenge_manual",
+            GearBotString::ChallengeReactionPrompt => "challenge_reaction_prompt",
+            GearBotString::ChallengeCaptchaPrompt => "challenge_captcha_prompt",
+            GearBotString::ChallengeCaptchaWrong => "challenge_captcha_wrong",
+            GearBotString::ChallengePassedDm => "challenge_passed_dm",
+            GearBotString::LogStyleUnknownCategory => "guild_admin__log_style_unknown_category",
+            GearBotString::LogStylePreviewContent => "guild_admin__log_style_preview_content",
+            GearBotString::StatsGuildEmpty => "guild_admin__stats_guild_empty",
+            GearBotString::StatsGuildHeader => "guild_admin__stats_guild_header",
+            GearBotString::StatsGuildLine => "guild_admin__stats_guild_line",
+            GearBotString::StatsGuildTrend => "guild_admin__stats_guild_trend",
+            GearBotString::StatsGuildDaily => "guild_admin__stats_guild_daily",
+            GearBotString::StatsGuildMessages => "guild_admin__stats_guild_messages",
+            GearBotString::StatsGuildJoins => "guild_admin__stats_guild_joins",
+            GearBotString::StatsGuildLeaves => "guild_admin__stats_guild_leaves",
+            GearBotString::StatsGuildCommands => "guild_admin__stats_guild_commands",
+            GearBotString::StatsGuildAutomod => "guild_admin__stats_guild_automod",
+            GearBotString::ActivityReportInvalidWindow => "guild_admin__activity_report_invalid_window",
+            GearBotString::ActivityReportEmpty => "guild_admin__activity_report_empty",
+            GearBotString::ActivityReportTitle => "guild_admin__activity_report_title",
+            GearBotString::ActivityReportTotal => "guild_admin__activity_report_total",
+            GearBotString::ActivityReportChannels => "guild_admin__activity_report_channels",
+            GearBotString::ActivityReportChannel => "guild_admin__activity_report_channel",
+            GearBotString::ActivityReportMoreChannels => "guild_admin__activity_report_more_channels",
+            GearBotString::ActivityReportHours => "guild_admin__activity_report_hours",
+            GearBotString::ActivityReportExported => "guild_admin__activity_report_exported",
+            GearBotString::ModStatsInvalidPeriod => "moderation__mod_stats_invalid_period",
+            GearBotString::ModStatsNothing => "moderation__mod_stats_nothing",
+            GearBotString::ModStatsTitle => "moderation__mod_stats_title",
+            GearBotString::ModStatsModerator => "moderation__mod_stats_moderator",
+            GearBotString::ModStatsKind => "moderation__mod_stats_kind",
+            GearBotString::ModStatsFooter => "moderation__mod_stats_footer",
+            GearBotString::ConfigValidateInvalid => "guild_admin__config_validate_invalid",
+            GearBotString::ConfigValidateValid => "guild_admin__config_validate_valid",
+            GearBotString::ConfigValidateProblems => "guild_admin__config_validate_problems",
+            GearBotString::ConfigValidateMore => "guild_admin__config_validate_more",
+            GearBotString::AutomodPatternTooLong => "guild_admin__automod_pattern_too_long",
+            GearBotString::AutomodPatternTooDeep => "guild_admin__automod_pattern_too_deep",
+            GearBotString::AutomodPatternTooBig => "guild_admin__automod_pattern_too_big",
+            GearBotString::AutoResponseListHeader => "guild_admin__autoresponse_list_header",
+            GearBotString::AutoResponseListEntry => "guild_admin__autoresponse_list_entry",
+            GearBotString::AutoResponseListEmpty => "guild_admin__autoresponse_list_empty",
+            GearBotString::AutoResponseAdded => "guild_admin__autoresponse_added",
+            GearBotString::AutoResponseUpdated => "guild_admin__autoresponse_updated",
+            GearBotString::AutoResponseRemoved => "guild_admin__autoresponse_removed",
+            GearBotString::AutoResponseInvalid => "guild_admin__autoresponse_invalid",
+            GearBotString::AutoResponseUnknown => "guild_admin__autoresponse_unknown",
+            GearBotString::ContentPolicySpoilerDm => "content_policy_spoiler_dm",
+            GearBotString::ContentPolicyNsfwDm => "content_policy_nsfw_dm",
+            GearBotString::SuggestionsDisabled => "basic__suggestions_disabled",
+            GearBotString::SuggestionSent => "basic__suggestion_sent",
+            GearBotString::SuggestionFailed => "basic__suggestion_failed",
+            GearBotString::SuggestionTitle => "basic__suggestion_title",
+            GearBotString::SuggestionStatusField => "basic__suggestion_status",
+            GearBotString::SuggestionReasonField => "basic__suggestion_reason",
+            GearBotString::SuggestionOpen => "basic__suggestion_open",
+            GearBotString::SuggestionApproved => "basic__suggestion_approved",
+            GearBotString::SuggestionDenied => "basic__suggestion_denied",
+            GearBotString::SuggestionConsidered => "basic__suggestion_considered",
+            GearBotString::SuggestionUpdated => "moderation__suggestion_updated",
+            GearBotString::SuggestionUnknown => "moderation__suggestion_unknown",
+            GearBotString::SuggestionStatusDm => "suggestion_status_dm",
+            GearBotString::TicketsDisabled => "moderation__tickets_disabled",
+            GearBotString::TicketOpened => "moderation__ticket_opened",
+            GearBotString::TicketAtLimit => "moderation__ticket_at_limit",
+            GearBotString::TicketFailed => "moderation__ticket_failed",
+            GearBotString::TicketNotATicket => "moderation__ticket_not_a_ticket",
+            GearBotString::TicketNotYours => "moderation__ticket_not_yours",
+            GearBotString::TicketClaimed => "moderation__ticket_claimed",
+            GearBotString::TicketAlreadyClaimed => "moderation__ticket_already_claimed",
+            GearBotString::TicketUnknown => "moderation__ticket_unknown",
+            GearBotString::TicketNoTranscript => "moderation__ticket_no_transcript",
+            GearBotString::TicketTranscript => "moderation__ticket_transcript",
+            GearBotString::TicketPanel => "moderation__ticket_panel",
+            GearBotString::EventInvalidTime => "basic__event_invalid_time",
+            GearBotString::EventInPast => "basic__event_in_past",
+            GearBotString::EventStartsField => "basic__event_starts",
+            GearBotString::EventGoingField => "basic__event_going",
+            GearBotString::EventMaybeField => "basic__event_maybe",
+            GearBotString::EventDeclinedField => "basic__event_declined",
+            GearBotString::EventNobody => "basic__event_nobody",
+            GearBotString::EventFooter => "basic__event_footer",
+            GearBotString::EventReminder => "event_reminder",
+            GearBotString::EventUnknown => "basic__event_unknown",
+            GearBotString::EventNotYours => "basic__event_not_yours",
+            GearBotString::EventCancelled => "basic__event_cancelled",
+            GearBotString::AutoPublishListEmpty => "guild_admin__auto_publish_list_empty",
+            GearBotString::AutoPublishListHeader => "guild_admin__auto_publish_list_header",
+            GearBotString::AutoPublishListEntry => "guild_admin__auto_publish_list_entry",
+            GearBotString::AutoPublishListEntryBots => "guild_admin__auto_publish_list_entry_bots",
+            GearBotString::AutoPublishEveryone => "guild_admin__auto_publish_everyone",
+            GearBotString::AutoPublishNotAnnouncements => "guild_admin__auto_publish_not_announcements",
+            GearBotString::AutoPublishMissingPermissions => "guild_admin__auto_publish_missing_permissions",
+            GearBotString::AutoPublishAdded => "guild_admin__auto_publish_added",
+            GearBotString::AutoPublishRemoved => "guild_admin__auto_publish_removed",
+            GearBotString::AutoPublishNotConfigured => "guild_admin__auto_publish_not_configured",
+            GearBotString::AutoPublishFilterUpdated => "guild_admin__auto_publish_filter_updated",
+            GearBotString::AutoPublishBotsEnabled => "guild_admin__auto_publish_bots_enabled",
+            GearBotString::AutoPublishBotsDisabled => "guild_admin__auto_publish_bots_disabled",
+            GearBotString::FeedListEmpty => "guild_admin__feed_list_empty",
+            GearBotString::FeedListHeader => "guild_admin__feed_list_header",
+            GearBotString::FeedListEntry => "guild_admin__feed_list_entry",
+            GearBotString::FeedCantPost => "guild_admin__feed_cant_post",
+            GearBotString::FeedInvalidInterval => "guild_admin__feed_invalid_interval",
+            GearBotString::FeedLimit => "guild_admin__feed_limit",
+            GearBotString::FeedInvalidUrl => "guild_admin__feed_invalid_url",
+            GearBotString::FeedUnreadable => "guild_admin__feed_unreadable",
+            GearBotString::FeedAdded => "guild_admin__feed_added",
+            GearBotString::FeedUnknown => "guild_admin__feed_unknown",
+            GearBotString::FeedRemoved => "guild_admin__feed_removed",
+            GearBotString::FeedTemplateTooLong => "guild_admin__feed_template_too_long",
+            GearBotString::FeedTemplateUpdated => "guild_admin__feed_template_updated",
+            GearBotString::FeedIntervalUpdated => "guild_admin__feed_interval_updated",
+            GearBotString::GithubNotEnabled => "guild_admin__github_not_enabled",
+            GearBotString::GithubListEmpty => "guild_admin__github_list_empty",
+            GearBotString::GithubListHeader => "guild_admin__github_list_header",
+            GearBotString::GithubListEntry => "guild_admin__github_list_entry",
+            GearBotString::GithubInvalidRepository => "guild_admin__github_invalid_repository",
+            GearBotString::GithubCantPost => "guild_admin__github_cant_post",
+            GearBotString::GithubUnknownEvent => "guild_admin__github_unknown_event",
+            GearBotString::GithubLimit => "guild_admin__github_limit",
+            GearBotString::GithubAlreadyFollowed => "guild_admin__github_already_followed",
+            GearBotString::GithubSetupDm => "guild_admin__github_setup_dm",
+            GearBotString::GithubDmFailed => "guild_admin__github_dm_failed",
+            GearBotString::GithubAdded => "guild_admin__github_added",
+            GearBotString::GithubUnknownRepository => "guild_admin__github_unknown_repository",
+            GearBotString::GithubRemoved => "guild_admin__github_removed",
+            GearBotString::GithubEventsUpdated => "guild_admin__github_events_updated",
+            GearBotString::GithubSecretReset => "guild_admin__github_secret_reset",
+            GearBotString::GithubPushTitle => "guild_admin__github_push_title",
+            GearBotString::GithubPushMore => "guild_admin__github_push_more",
+            GearBotString::GithubReleaseTitle => "guild_admin__github_release_title",
+            GearBotString::GithubIssueOpened => "guild_admin__github_issue_opened",
+            GearBotString::GithubIssueClosed => "guild_admin__github_issue_closed",
+            GearBotString::GithubIssueReopened => "guild_admin__github_issue_reopened",
+            GearBotString::WebhookNotEnabled => "guild_admin__webhook_not_enabled",
+            GearBotString::WebhookListEmpty => "guild_admin__webhook_list_empty",
+            GearBotString::WebhookListHeader => "guild_admin__webhook_list_header",
+            GearBotString::WebhookListEntry => "guild_admin__webhook_list_entry",
+            GearBotString::WebhookCantPost => "guild_admin__webhook_cant_post",
+            GearBotString::WebhookTemplateTooLong => "guild_admin__webhook_template_too_long",
+            GearBotString::WebhookLimit => "guild_admin__webhook_limit",
+            GearBotString::WebhookSetupDm => "guild_admin__webhook_setup_dm",
+            GearBotString::WebhookDmFailed => "guild_admin__webhook_dm_failed",
+            GearBotString::WebhookAdded => "guild_admin__webhook_added",
+            GearBotString::WebhookUnknown => "guild_admin__webhook_unknown",
+            GearBotString::WebhookRemoved => "guild_admin__webhook_removed",
+            GearBotString::WebhookTemplateUpdated => "guild_admin__webhook_template_updated",
+            GearBotString::WebhookTokenReset => "guild_admin__webhook_token_reset",
+            GearBotString::PinArchiveJump => "pin_archive_jump",
+            GearBotString::PinArchiveFooter => "pin_archive_footer",
+            GearBotString::UsageInvalidWindow => "guild_admin__usage_invalid_window",
+            GearBotString::UsageEmpty => "guild_admin__usage_empty",
+            GearBotString::UsageHeader => "guild_admin__usage_header",
+            GearBotString::UsageLine => "guild_admin__usage_line",
+            GearBotString::UsageFailures => "guild_admin__usage_failures",
+            GearBotString::UsageMore => "guild_admin__usage_more",
+            GearBotString::UsageUnused => "guild_admin__usage_unused",
+            GearBotString::BulkRoleInvalidFilter => "moderation__bulk_role_invalid_filter",
+            GearBotString::BulkRoleNothing => "moderation__bulk_role_nothing",
+            GearBotString::BulkRoleDryRun => "moderation__bulk_role_dry_run",
+            GearBotString::BulkRoleAlreadyRunning => "moderation__bulk_role_already_running",
+            GearBotString::BulkRoleProgress => "moderation__bulk_role_progress",
+            GearBotString::BulkRoleFinished => "moderation__bulk_role_finished",
+            GearBotString::BulkRoleCancelled => "moderation__bulk_role_cancelled",
+            GearBotString::BulkRoleCancelRequested => "moderation__bulk_role_cancel_requested",
+            GearBotString::BulkRoleNotRunning => "moderation__bulk_role_not_running",
+            GearBotString::RoleAuditClean => "moderation__role_audit_clean",
+            GearBotString::RoleAuditHeader => "moderation__role_audit_header",
+            GearBotString::RoleAuditEmpty => "moderation__role_audit_empty",
+            GearBotString::RoleAuditDuplicates => "moderation__role_audit_duplicates",
+            GearBotString::RoleAuditHoisted => "moderation__role_audit_hoisted",
+            GearBotString::RoleAuditAboveBot => "moderation__role_audit_above_bot",
+            GearBotString::RoleAuditHolders => "moderation__role_audit_holders",
+            GearBotString::RoleAuditFixNothing => "moderation__role_audit_fix_nothing",
+            GearBotString::RoleAuditFixDryRun => "moderation__role_audit_fix_dry_run",
+            GearBotString::RoleAuditFixed => "moderation__role_audit_fixed",
+            GearBotString::EmojiCreatedLog => "emoji_created",
+            GearBotString::EmojiDeletedLog => "emoji_deleted",
+            GearBotString::EmojiRenamedLog => "emoji_renamed",
+            GearBotString::EmojiLogFooter => "emoji_log_footer",
+            GearBotString::EmojiAdded => "basic__emoji_added",
+            GearBotString::EmojiRemoved => "basic__emoji_removed",
+            GearBotString::EmojiRenamed => "basic__emoji_renamed",
+            GearBotString::EmojiInvalidName => "basic__emoji_invalid_name",
+            GearBotString::EmojiNoSlots => "basic__emoji_no_slots",
+            GearBotString::EmojiNoImage => "basic__emoji_no_image",
+            GearBotString::EmojiDownloadFailed => "basic__emoji_download_failed",
+            GearBotString::EmojiTooBig => "basic__emoji_too_big",
+            GearBotString::EmojiNotFound => "basic__emoji_not_found",
+            GearBotString::EmojiManaged => "basic__emoji_managed",
+            GearBotString::EmojiNothingToSteal => "basic__emoji_nothing_to_steal",
+            GearBotString::ChannelClonedLog => "channel_cloned",
+            GearBotString::ChannelTemplateAppliedLog => "channel_template_applied",
+            GearBotString::ChannelLogFooter => "channel_log_footer",
+            GearBotString::ChannelCloned => "guild_admin__channel_cloned",
+            GearBotString::ChannelCreateFailed => "guild_admin__channel_create_failed",
+            GearBotString::ChannelTemplateSaved => "guild_admin__channel_template_saved",
+            GearBotString::ChannelTemplateApplied => "guild_admin__channel_template_applied",
+            GearBotString::ChannelTemplateRemoved => "guild_admin__channel_template_removed",
+            GearBotString::ChannelTemplateUnknown => "guild_admin__channel_template_unknown",
+            GearBotString::ChannelTemplateInvalidName => "guild_admin__channel_template_invalid_name",
+            GearBotString::ChannelTemplateLimitReached => "guild_admin__channel_template_limit_reached",
+            GearBotString::ChannelTemplateUnsupported => "guild_admin__channel_template_unsupported",
+            GearBotString::ChannelTemplateListEmpty => "guild_admin__channel_template_list_empty",
+            GearBotString::ChannelTemplateListHeader => "guild_admin__channel_template_list_header",
+            GearBotString::WhereisTitle => "admin__whereis_title",
+            GearBotString::WhereisCluster => "admin__whereis_cluster",
+            GearBotString::WhereisSummary => "admin__whereis_summary",
+            GearBotString::WhereisMissing => "admin__whereis_missing",
         }
     }
 
@@ -213,7 +1273,8 @@ impl GearBotString {
 pub fn load_translations() -> Translations {
     let translation_files = fs::read_dir(TRANSLATION_DIR).expect("The translation directory was not found!");
 
-    let mut translations = HashMap::new();
+    let mut bundles = HashMap::new();
+    let mut keys = HashMap::new();
 
     for lang_dir in translation_files {
         let lang_dir = lang_dir.unwrap();
@@ -234,6 +1295,8 @@ pub fn load_translations() -> Translations {
         let locales = vec![langid.clone()];
         let mut bundle = FluentBundle::new_concurrent(locales);
         bundle.set_use_isolating(false);
+        formatting::register_functions(&mut bundle, &langid);
+        let mut lang_keys = HashSet::new();
         for t_file in fs::read_dir(lang_dir.path()).unwrap() {
             let t_file = {
                 let tmp = t_file.unwrap();
@@ -248,24 +1311,27 @@ pub fn load_translations() -> Translations {
                 let res = FluentResource::try_new(tl_string).unwrap();
 
                 bundle.add_resource(res).unwrap();
+                lang_keys.insert(translation_key);
             }
         }
 
-        translations.insert(langid, Arc::new(bundle));
+        keys.insert(langid.clone(), lang_keys);
+        bundles.insert(langid, Arc::new(bundle));
     }
 
-    Translations(translations)
+    Translations { bundles, keys }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{GearBotString, TRANSLATION_DIR};
+    use super::{FluArgs, GearBotString, DEFAULT_LANG, TRANSLATION_DIR};
     use lazy_static::lazy_static;
     use std::collections::HashMap;
     use std::fs;
+    use unic_langid::langid;
 
     lazy_static! {
-        static ref ALL_TRANSLATION_STR_KEYS: [&'static str; 17] = [
+        static ref ALL_TRANSLATION_STR_KEYS: [&'static str; 467] = [
             GearBotString::PingPong.as_str(),
             GearBotString::CoinflipDefault.as_str(),
             GearBotString::CoinflipYes.as_str(),
@@ -280,9 +1346,459 @@ mod tests {
             GearBotString::EmojiPageHeader.as_str(),
             GearBotString::EmojiOverviewHeader.as_str(),
             GearBotString::EmojiInfo.as_str(),
-            GearBotString::CommandUsedText.as_str(),
-            GearBotString::CommandUsedEmbed.as_str(),
+            GearBotString::CommandUsed.as_str(),
+            GearBotString::LogTimestamp.as_str(),
             GearBotString::CommandUsedFooter.as_str(),
+            GearBotString::LevelsDisabled.as_str(),
+            GearBotString::LevelsNoXp.as_str(),
+            GearBotString::LevelsRank.as_str(),
+            GearBotString::LevelsLeaderboardHeader.as_str(),
+            GearBotString::LevelsLeaderboardEntry.as_str(),
+            GearBotString::LevelsLeaderboardEmpty.as_str(),
+            GearBotString::SnipeNothing.as_str(),
+            GearBotString::EditSnipeNothing.as_str(),
+            GearBotString::SnipeBefore.as_str(),
+            GearBotString::SnipeAfter.as_str(),
+            GearBotString::QuoteJumpLink.as_str(),
+            GearBotString::QuoteFooter.as_str(),
+            GearBotString::FunRollResult.as_str(),
+            GearBotString::FunChooseNotEnough.as_str(),
+            GearBotString::FunChooseResult.as_str(),
+            GearBotString::FunEightBallNoQuestion.as_str(),
+            GearBotString::FunEightBallCertain.as_str(),
+            GearBotString::FunEightBallNoDoubt.as_str(),
+            GearBotString::FunEightBallDefinitely.as_str(),
+            GearBotString::FunEightBallOutlookGood.as_str(),
+            GearBotString::FunEightBallHazy.as_str(),
+            GearBotString::FunEightBallAskLater.as_str(),
+            GearBotString::FunEightBallCannotPredict.as_str(),
+            GearBotString::FunEightBallNo.as_str(),
+            GearBotString::FunEightBallSourcesNo.as_str(),
+            GearBotString::FunEightBallDoubtful.as_str(),
+            GearBotString::UidDescription.as_str(),
+            GearBotString::UidMatchesHeader.as_str(),
+            GearBotString::UidNoMatches.as_str(),
+            GearBotString::UidMatchUser.as_str(),
+            GearBotString::UidMatchChannel.as_str(),
+            GearBotString::UidMatchRole.as_str(),
+            GearBotString::UidMatchGuild.as_str(),
+            GearBotString::PingGateway.as_str(),
+            GearBotString::PingRest.as_str(),
+            GearBotString::PingDatabase.as_str(),
+            GearBotString::PingShardLatency.as_str(),
+            GearBotString::PingCurrentShardLatency.as_str(),
+            GearBotString::PingClusterAverage.as_str(),
+            GearBotString::PingUnavailable.as_str(),
+            GearBotString::ModCommandUsed.as_str(),
+            GearBotString::ModCommandUsedFooter.as_str(),
+            GearBotString::ModCommandNoTarget.as_str(),
+            GearBotString::ModCommandNoArguments.as_str(),
+            GearBotString::ModCommandSucceeded.as_str(),
+            GearBotString::ModCommandFailed.as_str(),
+            GearBotString::ModCommandDenied.as_str(),
+            GearBotString::DryRunNotice.as_str(),
+            GearBotString::UndoNothing.as_str(),
+            GearBotString::UndoRoleRemoved.as_str(),
+            GearBotString::UndoRolesAdded.as_str(),
+            GearBotString::UndoRolesRemoved.as_str(),
+            GearBotString::UndoChannelsLocked.as_str(),
+            GearBotString::MessagesBulkDeleted.as_str(),
+            GearBotString::MessagesBulkDeletedFooter.as_str(),
+            GearBotString::MessageDeleted.as_str(),
+            GearBotString::MessageDeletedAttachments.as_str(),
+            GearBotString::MessageDeletedFooter.as_str(),
+            GearBotString::OnboardingWelcome.as_str(),
+            GearBotString::OnboardingNoLogChannel.as_str(),
+            GearBotString::MyDataExport.as_str(),
+            GearBotString::MyDataDeleteConfirm.as_str(),
+            GearBotString::MyDataDeleted.as_str(),
+            GearBotString::DmOnlyCommand.as_str(),
+            GearBotString::FeatureDisabled.as_str(),
+            GearBotString::FeatureUnknown.as_str(),
+            GearBotString::FeatureListHeader.as_str(),
+            GearBotString::FeatureListEntry.as_str(),
+            GearBotString::FeatureEnabledNow.as_str(),
+            GearBotString::FeatureDisabledNow.as_str(),
+            GearBotString::UnknownCommand.as_str(),
+            GearBotString::AliasListHeader.as_str(),
+            GearBotString::AliasListEntry.as_str(),
+            GearBotString::AliasListEmpty.as_str(),
+            GearBotString::AliasAdded.as_str(),
+            GearBotString::AliasRemoved.as_str(),
+            GearBotString::AliasUnknown.as_str(),
+            GearBotString::AliasTaken.as_str(),
+            GearBotString::AliasUnknownCommand.as_str(),
+            GearBotString::AliasLimit.as_str(),
+            GearBotString::BotMissingPermissions.as_str(),
+            GearBotString::EmbedOverflow.as_str(),
+            GearBotString::CommandDisabledInChannel.as_str(),
+            GearBotString::ChannelCommandsHeader.as_str(),
+            GearBotString::ChannelCommandsEntry.as_str(),
+            GearBotString::ChannelCommandsOverviewHeader.as_str(),
+            GearBotString::ChannelCommandsOverviewEntry.as_str(),
+            GearBotString::ChannelCommandsOverviewEmpty.as_str(),
+            GearBotString::ChannelCommandDisabled.as_str(),
+            GearBotString::ChannelCommandEnabled.as_str(),
+            GearBotString::ChannelCommandNotDisabled.as_str(),
+            GearBotString::ChannelCommandUnknown.as_str(),
+            GearBotString::ChannelCommandLocked.as_str(),
+            GearBotString::BotBanAdded.as_str(),
+            GearBotString::BotBanRemoved.as_str(),
+            GearBotString::BotBanAlreadyBanned.as_str(),
+            GearBotString::BotBanNotBanned.as_str(),
+            GearBotString::BotBanSelf.as_str(),
+            GearBotString::BotBanOwner.as_str(),
+            GearBotString::SearchHeader.as_str(),
+            GearBotString::SearchEntry.as_str(),
+            GearBotString::SearchNothing.as_str(),
+            GearBotString::SearchScanLimit.as_str(),
+            GearBotString::SearchInvalidFilter.as_str(),
+            GearBotString::ArchiveCreated.as_str(),
+            GearBotString::ArchiveStored.as_str(),
+            GearBotString::ArchiveNothing.as_str(),
+            GearBotString::ArchiveRetrieved.as_str(),
+            GearBotString::ArchiveUnknown.as_str(),
+            GearBotString::ConfigExported.as_str(),
+            GearBotString::ConfigImportNoFile.as_str(),
+            GearBotString::ConfigImportTooBig.as_str(),
+            GearBotString::ConfigImportDownloadFailed.as_str(),
+            GearBotString::ConfigImportInvalid.as_str(),
+            GearBotString::ConfigImported.as_str(),
+            GearBotString::AutomodTriggered.as_str(),
+            GearBotString::AutomodTriggeredFooter.as_str(),
+            GearBotString::AutomodNoActions.as_str(),
+            GearBotString::AutomodListHeader.as_str(),
+            GearBotString::AutomodListEntry.as_str(),
+            GearBotString::AutomodListEmpty.as_str(),
+            GearBotString::AutomodRuleAdded.as_str(),
+            GearBotString::AutomodRuleUpdated.as_str(),
+            GearBotString::AutomodRuleRemoved.as_str(),
+            GearBotString::AutomodRuleInvalid.as_str(),
+            GearBotString::AutomodRuleUnknown.as_str(),
+            GearBotString::HeatEscalated.as_str(),
+            GearBotString::HeatEscalatedFooter.as_str(),
+            GearBotString::LogActionFailed.as_str(),
+            GearBotString::HeatCurrent.as_str(),
+            GearBotString::HeatDisabled.as_str(),
+            GearBotString::HeatReset.as_str(),
+            GearBotString::JoinGateFlagged.as_str(),
+            GearBotString::JoinGateFlaggedFooter.as_str(),
+            GearBotString::JoinGateNewAccount.as_str(),
+            GearBotString::JoinGateNoAvatar.as_str(),
+            GearBotString::JoinGateActionLog.as_str(),
+            GearBotString::JoinGateActionProbation.as_str(),
+            GearBotString::JoinGateActionVerification.as_str(),
+            GearBotString::JoinGateActionKick.as_str(),
+            GearBotString::JoinGateVerified.as_str(),
+            GearBotString::JoinGateNotGated.as_str(),
+            GearBotString::RaidModeEnabledLog.as_str(),
+            GearBotString::RaidModeDetected.as_str(),
+            GearBotString::RaidModeDisabledLog.as_str(),
+            GearBotString::RaidModeFooter.as_str(),
+            GearBotString::RaidModeStatusOn.as_str(),
+            GearBotString::RaidModeStatusOff.as_str(),
+            GearBotString::RaidModeOn.as_str(),
+            GearBotString::RaidModeOff.as_str(),
+            GearBotString::RaidModeAlreadyOn.as_str(),
+            GearBotString::RaidModeAlreadyOff.as_str(),
+            GearBotString::RaidModeNoChanges.as_str(),
+            GearBotString::RaidModeVerificationRaised.as_str(),
+            GearBotString::RaidModeInvitesPaused.as_str(),
+            GearBotString::RaidModeChannelsRestricted.as_str(),
+            GearBotString::RaidModeAutoOn.as_str(),
+            GearBotString::RaidModeAutoOff.as_str(),
+            GearBotString::ProfileListEmpty.as_str(),
+            GearBotString::ProfileListHeader.as_str(),
+            GearBotString::ProfileListEntry.as_str(),
+            GearBotString::ProfileListPending.as_str(),
+            GearBotString::ProfileLimitReached.as_str(),
+            GearBotString::ProfileSaved.as_str(),
+            GearBotString::ProfileDeleted.as_str(),
+            GearBotString::ProfileUnknown.as_str(),
+            GearBotString::ProfileInvalid.as_str(),
+            GearBotString::ProfileInvalidDuration.as_str(),
+            GearBotString::ProfileApplied.as_str(),
+            GearBotString::ProfileAppliedUntil.as_str(),
+            GearBotString::ProfileReverted.as_str(),
+            GearBotString::ProfileNothingToRevert.as_str(),
+            GearBotString::DurationYears.as_str(),
+            GearBotString::DurationMonths.as_str(),
+            GearBotString::DurationWeeks.as_str(),
+            GearBotString::DurationDays.as_str(),
+            GearBotString::DurationHours.as_str(),
+            GearBotString::DurationMinutes.as_str(),
+            GearBotString::DurationSeconds.as_str(),
+            GearBotString::JoinGateAfterScreening.as_str(),
+            GearBotString::ScreeningCompleted.as_str(),
+            GearBotString::ScreeningCompletedRole.as_str(),
+            GearBotString::ScreeningFooter.as_str(),
+            GearBotString::PruneInvalidWindow.as_str(),
+            GearBotString::PrunePreview.as_str(),
+            GearBotString::PruneNothing.as_str(),
+            GearBotString::PruneNoPreview.as_str(),
+            GearBotString::PruneNoRole.as_str(),
+            GearBotString::PruneExecuted.as_str(),
+            GearBotString::PruneDryRun.as_str(),
+            GearBotString::BirthdaysDisabled.as_str(),
+            GearBotString::BirthdayInvalidDate.as_str(),
+            GearBotString::BirthdayShow.as_str(),
+            GearBotString::BirthdayNone.as_str(),
+            GearBotString::BirthdaySet.as_str(),
+            GearBotString::BirthdayRemoved.as_str(),
+            GearBotString::TimezoneUnknown.as_str(),
+            GearBotString::TimezoneSet.as_str(),
+            GearBotString::ConfirmCommand.as_str(),
+            GearBotString::ConfirmationInstructions.as_str(),
+            GearBotString::ConfirmationCancelled.as_str(),
+            GearBotString::AutomodReviewQueued.as_str(),
+            GearBotString::AutomodReviewInstructions.as_str(),
+            GearBotString::AutomodReviewApproved.as_str(),
+            GearBotString::AutomodReviewFailed.as_str(),
+            GearBotString::AutomodReviewRejected.as_str(),
+            GearBotString::CommandOutputRedirected.as_str(),
+            GearBotString::ChannelCommandOutputSet.as_str(),
+            GearBotString::ChannelCommandOutputCleared.as_str(),
+            GearBotString::WatchedMessage.as_str(),
+            GearBotString::WatchedJoin.as_str(),
+            GearBotString::WatchedVoiceJoined.as_str(),
+            GearBotString::WatchedVoiceLeft.as_str(),
+            GearBotString::WatchedNameChange.as_str(),
+            GearBotString::WatchlistFooter.as_str(),
+            GearBotString::WatchAdded.as_str(),
+            GearBotString::WatchAlready.as_str(),
+            GearBotString::WatchRemoved.as_str(),
+            GearBotString::WatchNotWatched.as_str(),
+            GearBotString::UserinfoNetworkReputation.as_str(),
+            GearBotString::JoinGateNetworkBans.as_str(),
+            GearBotString::AuditIntegrationsHeader.as_str(),
+            GearBotString::AuditWebhook.as_str(),
+            GearBotString::AuditBot.as_str(),
+            GearBotString::AuditIntegration.as_str(),
+            GearBotString::AuditUnknown.as_str(),
+            GearBotString::AuditNothing.as_str(),
+            GearBotString::RolePermissionsGranted.as_str(),
+            GearBotString::MemberPermissionsGranted.as_str(),
+            GearBotString::PermissionsGrantedUnknown.as_str(),
+            GearBotString::PermissionsGrantedFooter.as_str(),
+            GearBotString::NukeDetected.as_str(),
+            GearBotString::NukeDetectedFooter.as_str(),
+            GearBotString::NukeRolesStripped.as_str(),
+            GearBotString::NukeBanned.as_str(),
+            GearBotString::ChallengeIssued.as_str(),
+            GearBotString::ChallengePassed.as_str(),
+            GearBotString::ChallengeFooter.as_str(),
+            GearBotString::ChallengeReaction.as_str(),
+            GearBotString::ChallengeCaptcha.as_str(),
+            GearBotString::ChallengeManual.as_str(),
+            GearBotString::ChallengeReactionPrompt.as_str(),
+            GearBotString::ChallengeCaptchaPrompt.as_str(),
+            GearBotString::ChallengeCaptchaWrong.as_str(),
+            GearBotString::ChallengePassedDm.as_str(),
+            GearBotString::LogStyleUnknownCategory.as_str(),
+            GearBotString::LogStylePreviewContent.as_str(),
+            GearBotString::StatsGuildEmpty.as_str(),
+            GearBotString::StatsGuildHeader.as_str(),
+            GearBotString::StatsGuildLine.as_str(),
+            GearBotString::StatsGuildTrend.as_str(),
+            GearBotString::StatsGuildDaily.as_str(),
+            GearBotString::StatsGuildMessages.as_str(),
+            GearBotString::StatsGuildJoins.as_str(),
+            GearBotString::StatsGuildLeaves.as_str(),
+            GearBotString::StatsGuildCommands.as_str(),
+            GearBotString::StatsGuildAutomod.as_str(),
+            GearBotString::ActivityReportInvalidWindow.as_str(),
+            GearBotString::ActivityReportEmpty.as_str(),
+            GearBotString::ActivityReportTitle.as_str(),
+            GearBotString::ActivityReportTotal.as_str(),
+            GearBotString::ActivityReportChannels.as_str(),
+            GearBotString::ActivityReportChannel.as_str(),
+            GearBotString::ActivityReportMoreChannels.as_str(),
+            GearBotString::ActivityReportHours.as_str(),
+            GearBotString::ActivityReportExported.as_str(),
+            GearBotString::ModStatsInvalidPeriod.as_str(),
+            GearBotString::ModStatsNothing.as_str(),
+            GearBotString::ModStatsTitle.as_str(),
+            GearBotString::ModStatsModerator.as_str(),
+            GearBotString::ModStatsKind.as_str(),
+            GearBotString::ModStatsFooter.as_str(),
+            GearBotString::ConfigValidateInvalid.as_str(),
+            GearBotString::ConfigValidateValid.as_str(),
+            GearBotString::ConfigValidateProblems.as_str(),
+            GearBotString::ConfigValidateMore.as_str(),
+            GearBotString::AutomodPatternTooLong.as_str(),
+            GearBotString::AutomodPatternTooDeep.as_str(),
+            GearBotString::AutomodPatternTooBig.as_str(),
+            GearBotString::AutoResponseListHeader.as_str(),
+            GearBotString::AutoResponseListEntry.as_str(),
+            GearBotString::AutoResponseListEmpty.as_str(),
+            GearBotString::AutoResponseAdded.as_str(),
+            GearBotString::AutoResponseUpdated.as_str(),
+            GearBotString::AutoResponseRemoved.as_str(),
+            GearBotString::AutoResponseInvalid.as_str(),
+            GearBotString::AutoResponseUnknown.as_str(),
+            GearBotString::ContentPolicySpoilerDm.as_str(),
+            GearBotString::ContentPolicyNsfwDm.as_str(),
+            GearBotString::SuggestionsDisabled.as_str(),
+            GearBotString::SuggestionSent.as_str(),
+            GearBotString::SuggestionFailed.as_str(),
+            GearBotString::SuggestionTitle.as_str(),
+            GearBotString::SuggestionStatusField.as_str(),
+            GearBotString::SuggestionReasonField.as_str(),
+            GearBotString::SuggestionOpen.as_str(),
+            GearBotString::SuggestionApproved.as_str(),
+            GearBotString::SuggestionDenied.as_str(),
+            GearBotString::SuggestionConsidered.as_str(),
+            GearBotString::SuggestionUpdated.as_str(),
+            GearBotString::SuggestionUnknown.as_str(),
+            GearBotString::SuggestionStatusDm.as_str(),
+            GearBotString::TicketsDisabled.as_str(),
+            GearBotString::TicketOpened.as_str(),
+            GearBotString::TicketAtLimit.as_str(),
+            GearBotString::TicketFailed.as_str(),
+            GearBotString::TicketNotATicket.as_str(),
+            GearBotString::TicketNotYours.as_str(),
+            GearBotString::TicketClaimed.as_str(),
+            GearBotString::TicketAlreadyClaimed.as_str(),
+            GearBotString::TicketUnknown.as_str(),
+            GearBotString::TicketNoTranscript.as_str(),
+            GearBotString::TicketTranscript.as_str(),
+            GearBotString::TicketPanel.as_str(),
+            GearBotString::EventInvalidTime.as_str(),
+            GearBotString::EventInPast.as_str(),
+            GearBotString::EventStartsField.as_str(),
+            GearBotString::EventGoingField.as_str(),
+            GearBotString::EventMaybeField.as_str(),
+            GearBotString::EventDeclinedField.as_str(),
+            GearBotString::EventNobody.as_str(),
+            GearBotString::EventFooter.as_str(),
+            GearBotString::EventReminder.as_str(),
+            GearBotString::EventUnknown.as_str(),
+            GearBotString::EventNotYours.as_str(),
+            GearBotString::EventCancelled.as_str(),
+            GearBotString::AutoPublishListEmpty.as_str(),
+            GearBotString::AutoPublishListHeader.as_str(),
+            GearBotString::AutoPublishListEntry.as_str(),
+            GearBotString::AutoPublishListEntryBots.as_str(),
+            GearBotString::AutoPublishEveryone.as_str(),
+            GearBotString::AutoPublishNotAnnouncements.as_str(),
+            GearBotString::AutoPublishMissingPermissions.as_str(),
+            GearBotString::AutoPublishAdded.as_str(),
+            GearBotString::AutoPublishRemoved.as_str(),
+            GearBotString::AutoPublishNotConfigured.as_str(),
+            GearBotString::AutoPublishFilterUpdated.as_str(),
+            GearBotString::AutoPublishBotsEnabled.as_str(),
+            GearBotString::AutoPublishBotsDisabled.as_str(),
+            GearBotString::FeedListEmpty.as_str(),
+            GearBotString::FeedListHeader.as_str(),
+            GearBotString::FeedListEntry.as_str(),
+            GearBotString::FeedCantPost.as_str(),
+            GearBotString::FeedInvalidInterval.as_str(),
+            GearBotString::FeedLimit.as_str(),
+            GearBotString::FeedInvalidUrl.as_str(),
+            GearBotString::FeedUnreadable.as_str(),
+            GearBotString::FeedAdded.as_str(),
+            GearBotString::FeedUnknown.as_str(),
+            GearBotString::FeedRemoved.as_str(),
+            GearBotString::FeedTemplateTooLong.as_str(),
+            GearBotString::FeedTemplateUpdated.as_str(),
+            GearBotString::FeedIntervalUpdated.as_str(),
+            GearBotString::GithubNotEnabled.as_str(),
+            GearBotString::GithubListEmpty.as_str(),
+            GearBotString::GithubListHeader.as_str(),
+            GearBotString::GithubListEntry.as_str(),
+            GearBotString::GithubInvalidRepository.as_str(),
+            GearBotString::GithubCantPost.as_str(),
+            GearBotString::GithubUnknownEvent.as_str(),
+            GearBotString::GithubLimit.as_str(),
+            GearBotString::GithubAlreadyFollowed.as_str(),
+            GearBotString::GithubSetupDm.as_str(),
+            GearBotString::GithubDmFailed.as_str(),
+            GearBotString::GithubAdded.as_str(),
+            GearBotString::GithubUnknownRepository.as_str(),
+            GearBotString::GithubRemoved.as_str(),
+            GearBotString::GithubEventsUpdated.as_str(),
+            GearBotString::GithubSecretReset.as_str(),
+            GearBotString::GithubPushTitle.as_str(),
+            GearBotString::GithubPushMore.as_str(),
+            GearBotString::GithubReleaseTitle.as_str(),
+            GearBotString::GithubIssueOpened.as_str(),
+            GearBotString::GithubIssueClosed.as_str(),
+            GearBotString::GithubIssueReopened.as_str(),
+            GearBotString::WebhookNotEnabled.as_str(),
+            GearBotString::WebhookListEmpty.as_str(),
+            GearBotString::WebhookListHeader.as_str(),
+            GearBotString::WebhookListEntry.as_str(),
+            GearBotString::WebhookCantPost.as_str(),
+            GearBotString::WebhookTemplateTooLong.as_str(),
+            GearBotString::WebhookLimit.as_str(),
+            GearBotString::WebhookSetupDm.as_str(),
+            GearBotString::WebhookDmFailed.as_str(),
+            GearBotString::WebhookAdded.as_str(),
+            GearBotString::WebhookUnknown.as_str(),
+            GearBotString::WebhookRemoved.as_str(),
+            GearBotString::WebhookTemplateUpdated.as_str(),
+            GearBotString::WebhookTokenReset.as_str(),
+            GearBotString::PinArchiveJump.as_str(),
+            GearBotString::PinArchiveFooter.as_str(),
+            GearBotString::UsageInvalidWindow.as_str(),
+            GearBotString::UsageEmpty.as_str(),
+            GearBotString::UsageHeader.as_str(),
+            GearBotString::UsageLine.as_str(),
+            GearBotString::UsageFailures.as_str(),
+            GearBotString::UsageMore.as_str(),
+            GearBotString::UsageUnused.as_str(),
+            GearBotString::BulkRoleInvalidFilter.as_str(),
+            GearBotString::BulkRoleNothing.as_str(),
+            GearBotString::BulkRoleDryRun.as_str(),
+            GearBotString::BulkRoleAlreadyRunning.as_str(),
+            GearBotString::BulkRoleProgress.as_str(),
+            GearBotString::BulkRoleFinished.as_str(),
+            GearBotString::BulkRoleCancelled.as_str(),
+            GearBotString::BulkRoleCancelRequested.as_str(),
+            GearBotString::BulkRoleNotRunning.as_str(),
+            GearBotString::RoleAuditClean.as_str(),
+            GearBotString::RoleAuditHeader.as_str(),
+            GearBotString::RoleAuditEmpty.as_str(),
+            GearBotString::RoleAuditDuplicates.as_str(),
+            GearBotString::RoleAuditHoisted.as_str(),
+            GearBotString::RoleAuditAboveBot.as_str(),
+            GearBotString::RoleAuditHolders.as_str(),
+            GearBotString::RoleAuditFixNothing.as_str(),
+            GearBotString::RoleAuditFixDryRun.as_str(),
+            GearBotString::RoleAuditFixed.as_str(),
+            GearBotString::EmojiCreatedLog.as_str(),
+            GearBotString::EmojiDeletedLog.as_str(),
+            GearBotString::EmojiRenamedLog.as_str(),
+            GearBotString::EmojiLogFooter.as_str(),
+            GearBotString::EmojiAdded.as_str(),
+            GearBotString::EmojiRemoved.as_str(),
+            GearBotString::EmojiRenamed.as_str(),
+            GearBotString::EmojiInvalidName.as_str(),
+            GearBotString::EmojiNoSlots.as_str(),
+            GearBotString::EmojiNoImage.as_str(),
+            GearBotString::EmojiDownloadFailed.as_str(),
+            GearBotString::EmojiTooBig.as_str(),
+            GearBotString::EmojiNotFound.as_str(),
+            GearBotString::EmojiManaged.as_str(),
+            GearBotString::EmojiNothingToSteal.as_str(),
+            GearBotString::ChannelClonedLog.as_str(),
+            GearBotString::ChannelTemplateAppliedLog.as_str(),
+            GearBotString::ChannelLogFooter.as_str(),
+            GearBotString::ChannelCloned.as_str(),
+            GearBotString::ChannelCreateFailed.as_str(),
+            GearBotString::ChannelTemplateSaved.as_str(),
+            GearBotString::ChannelTemplateApplied.as_str(),
+            GearBotString::ChannelTemplateRemoved.as_str(),
+            GearBotString::ChannelTemplateUnknown.as_str(),
+            GearBotString::ChannelTemplateInvalidName.as_str(),
+            GearBotString::ChannelTemplateLimitReached.as_str(),
+            GearBotString::ChannelTemplateUnsupported.as_str(),
+            GearBotString::ChannelTemplateListEmpty.as_str(),
+            GearBotString::ChannelTemplateListHeader.as_str(),
+            GearBotString::WhereisTitle.as_str(),
+            GearBotString::WhereisCluster.as_str(),
+            GearBotString::WhereisSummary.as_str(),
+            GearBotString::WhereisMissing.as_str(),
         ];
     }
 
@@ -334,4 +1850,60 @@ mod tests {
         // Make sure we exhausted everything
         assert_eq!(covered, ALL_TRANSLATION_STR_KEYS.len())
     }
+
+    #[test]
+    fn log_templates_select_style() {
+        let translations = super::load_translations();
+
+        let render = |style: &'static str| {
+            let args = FluArgs::with_capacity(5)
+                .add("name", "Someone#0001")
+                .add("user_id", "1")
+                .add("channel_id", "2")
+                .add("command", "!ping")
+                .add("style", style)
+                .generate();
+            translations
+                .get_text_with_args(&DEFAULT_LANG, GearBotString::CommandUsed, &args)
+                .to_string()
+        };
+
+        assert!(render("text").starts_with("Someone#0001"));
+        assert!(render("embed").starts_with("Used a command"));
+    }
+
+    #[test]
+    fn durations_are_translated() {
+        let translations = super::load_translations();
+
+        assert_eq!(
+            translations.format_duration(&DEFAULT_LANG, 90_061, 3),
+            "1 day 1 hour 1 minute"
+        );
+        assert_eq!(translations.format_duration(&DEFAULT_LANG, 7_200, 3), "2 hours");
+    }
+
+    #[test]
+    fn unknown_languages_fall_back() {
+        let translations = super::load_translations();
+
+        assert_eq!(
+            translations.get_text_plain(&langid!("en_GB"), GearBotString::PingPong),
+            translations.get_text_plain(&DEFAULT_LANG, GearBotString::PingPong)
+        );
+        assert_eq!(
+            translations.get_text_plain(&langid!("xx"), GearBotString::PingPong),
+            translations.get_text_plain(&DEFAULT_LANG, GearBotString::PingPong)
+        );
+    }
+
+    #[test]
+    fn default_language_is_fully_covered() {
+        let translations = super::load_translations();
+        let coverage = translations.coverage();
+        let default = coverage.iter().find(|c| c.language == DEFAULT_LANG).unwrap();
+
+        assert!(default.missing.is_empty());
+        assert_eq!(default.translated, ALL_TRANSLATION_STR_KEYS.len());
+    }
 }
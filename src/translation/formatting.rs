@@ -0,0 +1,217 @@
+//! Formatting of numbers and dates the way a language writes them.
+//!
+//! These are registered as functions on every translation bundle, so translations can use ``NUMBER($count)``,
+//! ``DATE($timestamp)`` and ``DATETIME($timestamp)`` themselves. Timestamps are unix timestamps and always shown
+//! in UTC. Durations are built from a translation per unit, see [`Translations::format_duration`].
+//!
+//! [`Translations::format_duration`]: super::Translations::format_duration
+
+use chrono::{DateTime, NaiveDateTime};
+use chrono_tz::Tz;
+use fluent_bundle::FluentValue;
+use unic_langid::LanguageIdentifier;
+
+use super::{FluentBundle, GearBotString};
+
+/// How a language writes numbers and dates.
+struct LocaleFormat {
+    group_separator: &'static str,
+    decimal_separator: &'static str,
+    date: &'static str,
+    time: &'static str,
+}
+
+fn locale_format(lang: &LanguageIdentifier) -> LocaleFormat {
+    let language = lang.language.as_str();
+    let (group_separator, decimal_separator) = match language {
+        "de" | "nl" | "es" | "it" | "pt" | "tr" | "id" | "da" | "el" | "ro" | "hr" | "sl" => (".", ","),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "uk" | "hu" | "bg" | "lt" | "lv" | "et" => {
+            ("\u{a0}", ",")
+        }
+        _ => (",", "."),
+    };
+
+    let us = language == "en" && lang.region.map_or(true, |region| region.as_str() == "US");
+    let date = match language {
+        _ if us => "%m/%d/%Y",
+        "en" | "fr" | "es" | "it" | "pt" | "el" => "%d/%m/%Y",
+        "de" | "ru" | "pl" | "cs" | "fi" | "nb" | "uk" | "tr" | "da" | "ro" => "%d.%m.%Y",
+        "nl" => "%d-%m-%Y",
+        _ => "%Y-%m-%d",
+    };
+    let time = if us { "%-I:%M %p" } else { "%H:%M" };
+
+    LocaleFormat {
+        group_separator,
+        decimal_separator,
+        date,
+        time,
+    }
+}
+
+/// Groups the thousands and shows at most two decimals.
+pub fn format_number(value: f64, lang: &LanguageIdentifier) -> String {
+    let format = locale_format(lang);
+    let rounded = format!("{:.2}", value.abs());
+    let (whole, fraction) = rounded.split_at(rounded.len() - 3);
+    let fraction = fraction[1..].trim_end_matches('0');
+
+    let mut output = String::with_capacity(rounded.len() + whole.len() / 3);
+    if value < 0.0 && rounded.chars().any(|c| c != '0' && c != '.') {
+        output.push('-');
+    }
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            output += format.group_separator;
+        }
+        output.push(digit);
+    }
+    if !fraction.is_empty() {
+        output += format.decimal_separator;
+        output += fraction;
+    }
+    output
+}
+
+pub fn format_date(timestamp: i64, lang: &LanguageIdentifier) -> String {
+    NaiveDateTime::from_timestamp(timestamp, 0)
+        .format(locale_format(lang).date)
+        .to_string()
+}
+
+pub fn format_datetime(timestamp: i64, lang: &LanguageIdentifier) -> String {
+    let format = locale_format(lang);
+    NaiveDateTime::from_timestamp(timestamp, 0)
+        .format(&format!("{} {} UTC", format.date, format.time))
+        .to_string()
+}
+
+/// Like [`format_datetime`], but in the timezone of the time instead of UTC.
+pub fn format_zoned_datetime(time: DateTime<Tz>, lang: &LanguageIdentifier) -> String {
+    let format = locale_format(lang);
+    time.format(&format!("{} {} %Z", format.date, format.time)).to_string()
+}
+
+/// The units a duration is split into, with their length in seconds.
+const DURATION_UNITS: [(GearBotString, i64); 7] = [
+    (GearBotString::DurationYears, 31_557_600),
+    (GearBotString::DurationMonths, 2_592_000),
+    (GearBotString::DurationWeeks, 604_800),
+    (GearBotString::DurationDays, 86_400),
+    (GearBotString::DurationHours, 3_600),
+    (GearBotString::DurationMinutes, 60),
+    (GearBotString::DurationSeconds, 1),
+];
+
+/// Splits a duration into at most `max_parts` units, largest first. Units that would be 0 are left out.
+pub(super) fn duration_parts(seconds: i64, max_parts: usize) -> Vec<(GearBotString, i64)> {
+    let mut remaining = seconds.max(0);
+    let mut parts = Vec::with_capacity(max_parts);
+    for (unit, length) in DURATION_UNITS.iter() {
+        if parts.len() == max_parts {
+            break;
+        }
+        let amount = remaining / length;
+        if amount > 0 {
+            remaining -= amount * length;
+            parts.push((*unit, amount));
+        }
+    }
+
+    if parts.is_empty() {
+        parts.push((GearBotString::DurationSeconds, 0));
+    }
+    parts
+}
+
+/// Makes the formatters available to the translations in the bundle.
+pub(super) fn register_functions(bundle: &mut FluentBundle, lang: &LanguageIdentifier) {
+    let number_lang = lang.clone();
+    bundle
+        .add_function("NUMBER", move |positional, _named| {
+            format_argument(positional, |value| format_number(value, &number_lang))
+        })
+        .expect("NUMBER was registered twice");
+
+    let date_lang = lang.clone();
+    bundle
+        .add_function("DATE", move |positional, _named| {
+            format_argument(positional, |timestamp| format_date(timestamp as i64, &date_lang))
+        })
+        .expect("DATE was registered twice");
+
+    let datetime_lang = lang.clone();
+    bundle
+        .add_function("DATETIME", move |positional, _named| {
+            format_argument(positional, |timestamp| {
+                format_datetime(timestamp as i64, &datetime_lang)
+            })
+        })
+        .expect("DATETIME was registered twice");
+}
+
+/// Formats the first argument if it is a number, anything else (like "unknown") is shown as is.
+fn format_argument<'a>(positional: &[FluentValue<'a>], format: impl Fn(f64) -> String) -> FluentValue<'a> {
+    let value = match positional.first() {
+        Some(value) => value,
+        None => return FluentValue::Error,
+    };
+    let number = match value {
+        FluentValue::Number(number) => Some(number.value),
+        FluentValue::String(string) => string.parse().ok(),
+        _ => None,
+    };
+
+    match number {
+        Some(number) => FluentValue::from(format(number)),
+        None => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use unic_langid::langid;
+
+    #[test]
+    fn numbers_use_the_separators_of_the_language() {
+        assert_eq!(format_number(1234567.0, &langid!("en_US")), "1,234,567");
+        assert_eq!(format_number(1234.5, &langid!("de")), "1.234,5");
+        assert_eq!(format_number(-1234.567, &langid!("fr")), "-1\u{a0}234,57");
+        assert_eq!(format_number(999.0, &langid!("en_US")), "999");
+        assert_eq!(format_number(0.001, &langid!("en_US")), "0");
+    }
+
+    #[test]
+    fn dates_follow_the_language() {
+        // 2021-03-04 05:06:07 UTC
+        let timestamp = 1614834367;
+
+        assert_eq!(format_date(timestamp, &langid!("en_US")), "03/04/2021");
+        assert_eq!(format_date(timestamp, &langid!("en_GB")), "04/03/2021");
+        assert_eq!(format_date(timestamp, &langid!("de")), "04.03.2021");
+        assert_eq!(format_date(timestamp, &langid!("ja")), "2021-03-04");
+        assert_eq!(format_datetime(timestamp, &langid!("en_US")), "03/04/2021 5:06 AM UTC");
+        assert_eq!(format_datetime(timestamp, &langid!("nl")), "04-03-2021 05:06 UTC");
+
+        let brussels = chrono_tz::Europe::Brussels.timestamp(timestamp, 0);
+        assert_eq!(format_zoned_datetime(brussels, &langid!("nl")), "04-03-2021 06:06 CET");
+    }
+
+    #[test]
+    fn durations_keep_the_largest_units() {
+        let amounts = |seconds, max_parts| {
+            duration_parts(seconds, max_parts)
+                .into_iter()
+                .map(|(_, amount)| amount)
+                .collect::<Vec<_>>()
+        };
+
+        // 1 day, 2 hours and 5 seconds
+        assert_eq!(amounts(93_605, 4), vec![1, 2, 5]);
+        assert_eq!(amounts(93_605, 2), vec![1, 2]);
+        assert_eq!(amounts(0, 2), vec![0]);
+        assert_eq!(amounts(-5, 2), vec![0]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use rand::Rng;
+use regex::Regex;
+
+const MAX_DICE: u32 = 100;
+const MAX_SIDES: u32 = 1000;
+const MAX_MODIFIER: i64 = 10_000;
+
+lazy_static! {
+    static ref DICE_MATCHER: Regex = Regex::new(r"^(\d{0,3})[dD](\d{1,4})(?:([+-])(\d{1,5}))?$").unwrap();
+}
+
+/// A parsed dice expression in the common `NdS+M` notation, for example `2d20+3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiceRoll {
+    pub count: u32,
+    pub sides: u32,
+    pub modifier: i64,
+}
+
+pub struct DiceOutcome {
+    pub rolls: Vec<u32>,
+    pub total: i64,
+}
+
+impl DiceRoll {
+    /// Parses a dice expression, returning `None` when it is malformed or out of bounds.
+    pub fn parse(input: &str) -> Option<Self> {
+        let captures = DICE_MATCHER.captures(input.trim())?;
+
+        let count = match captures.get(1).map(|m| m.as_str()) {
+            Some("") | None => 1,
+            Some(count) => count.parse().ok()?,
+        };
+        let sides = captures.get(2)?.as_str().parse().ok()?;
+        let modifier = match (captures.get(3), captures.get(4)) {
+            (Some(sign), Some(amount)) => {
+                let amount: i64 = amount.as_str().parse().ok()?;
+                if sign.as_str() == "-" {
+                    -amount
+                } else {
+                    amount
+                }
+            }
+            _ => 0,
+        };
+
+        if !(1..=MAX_DICE).contains(&count) || !(2..=MAX_SIDES).contains(&sides) || modifier.abs() > MAX_MODIFIER {
+            return None;
+        }
+
+        Some(DiceRoll { count, sides, modifier })
+    }
+
+    pub fn roll(&self) -> DiceOutcome {
+        let mut rng = rand::thread_rng();
+        let rolls: Vec<u32> = (0..self.count).map(|_| rng.gen_range(1..=self.sides)).collect();
+        let total = rolls.iter().map(|r| *r as i64).sum::<i64>() + self.modifier;
+
+        DiceOutcome { rolls, total }
+    }
+}
+
+impl Default for DiceRoll {
+    fn default() -> Self {
+        DiceRoll {
+            count: 1,
+            sides: 6,
+            modifier: 0,
+        }
+    }
+}
+
+impl fmt::Display for DiceRoll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.modifier.cmp(&0) {
+            std::cmp::Ordering::Greater => write!(f, "+{}", self.modifier),
+            std::cmp::Ordering::Less => write!(f, "{}", self.modifier),
+            std::cmp::Ordering::Equal => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_expressions() {
+        assert_eq!(
+            DiceRoll::parse("2d20+3"),
+            Some(DiceRoll {
+                count: 2,
+                sides: 20,
+                modifier: 3
+            })
+        );
+        assert_eq!(
+            DiceRoll::parse("4D6-1"),
+            Some(DiceRoll {
+                count: 4,
+                sides: 6,
+                modifier: -1
+            })
+        );
+    }
+
+    #[test]
+    fn count_defaults_to_one() {
+        assert_eq!(
+            DiceRoll::parse("d8"),
+            Some(DiceRoll {
+                count: 1,
+                sides: 8,
+                modifier: 0
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert_eq!(DiceRoll::parse("hello"), None);
+        assert_eq!(DiceRoll::parse("2d"), None);
+        assert_eq!(DiceRoll::parse("0d6"), None);
+        assert_eq!(DiceRoll::parse("2d1"), None);
+        assert_eq!(DiceRoll::parse("500d6"), None);
+        assert_eq!(DiceRoll::parse("2d20+"), None);
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        for expression in &["2d20+3", "1d6", "3d8-2"] {
+            assert_eq!(DiceRoll::parse(expression).unwrap().to_string(), *expression);
+        }
+    }
+
+    #[test]
+    fn rolls_stay_in_bounds() {
+        let dice = DiceRoll::parse("50d6+2").unwrap();
+        let outcome = dice.roll();
+        assert_eq!(outcome.rolls.len(), 50);
+        assert!(outcome.rolls.iter().all(|r| (1..=6).contains(r)));
+        assert_eq!(outcome.total, outcome.rolls.iter().map(|r| *r as i64).sum::<i64>() + 2);
+    }
+}
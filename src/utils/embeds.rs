@@ -0,0 +1,347 @@
+use twilight_embed_builder::{EmbedAuthorBuilder, EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource};
+use twilight_model::channel::embed::Embed;
+
+use crate::error::MessageError;
+
+pub const TITLE_LIMIT: usize = 256;
+pub const DESCRIPTION_LIMIT: usize = 2048;
+pub const FIELD_NAME_LIMIT: usize = 256;
+pub const FIELD_VALUE_LIMIT: usize = 1024;
+pub const FIELD_COUNT_LIMIT: usize = 25;
+pub const FOOTER_LIMIT: usize = 2048;
+pub const AUTHOR_NAME_LIMIT: usize = 256;
+pub const TOTAL_LIMIT: usize = 6000;
+/// When content needs more embeds than this it gets attached as a file instead.
+pub const MAX_EMBEDS: usize = 5;
+
+// Discord doesn't accept empty descriptions or field values
+const EMPTY: &str = "\u{200b}";
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    value: String,
+    inline: bool,
+}
+
+#[derive(Debug, Default)]
+struct Page {
+    description: Option<String>,
+    fields: Vec<Field>,
+    size: usize,
+}
+
+/// Collects everything that should go in an embed, and only splits it up into actual embeds once it's done.
+///
+/// Descriptions and field values that are too long are split over multiple embeds or fields instead of being rejected
+/// by the api. The author, title, url and image go on the first embed, the footer and timestamp on the last one.
+#[derive(Debug, Clone, Default)]
+pub struct SafeEmbed {
+    title: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+    color: Option<u32>,
+    author: Option<(String, Option<String>)>,
+    footer: Option<String>,
+    timestamp: Option<String>,
+    image: Option<String>,
+    fields: Vec<Field>,
+}
+
+impl SafeEmbed {
+    pub fn new() -> Self {
+        SafeEmbed::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Where the title links to.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the color, 0 (like roles without a color have) is treated as no color at all.
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = if color == 0 { None } else { Some(color) };
+        self
+    }
+
+    pub fn author(mut self, name: impl Into<String>, icon_url: Option<String>) -> Self {
+        self.author = Some((name.into(), icon_url));
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.timestamp = Some(timestamp.into());
+        self
+    }
+
+    pub fn image(mut self, url: impl Into<String>) -> Self {
+        self.image = Some(url.into());
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            value: value.into(),
+            inline: false,
+        });
+        self
+    }
+
+    pub fn inline_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            value: value.into(),
+            inline: true,
+        });
+        self
+    }
+
+    /// Builds as many embeds as are needed to fit everything, this is always at least one.
+    pub fn build(&self) -> Result<Vec<Embed>, MessageError> {
+        let pages = self.pages();
+        let last = pages.len() - 1;
+
+        let mut embeds = Vec::with_capacity(pages.len());
+        for (index, page) in pages.into_iter().enumerate() {
+            let mut builder = EmbedBuilder::new();
+            if let Some(color) = self.color {
+                builder = builder.color(color)?;
+            }
+
+            if index == 0 {
+                if let Some(title) = &self.title {
+                    builder = builder.title(truncate(title, TITLE_LIMIT))?;
+                }
+                if let Some(url) = &self.url {
+                    builder = builder.url(url.clone());
+                }
+                if let Some((name, icon_url)) = &self.author {
+                    let mut author = EmbedAuthorBuilder::new().name(truncate(name, AUTHOR_NAME_LIMIT))?;
+                    if let Some(icon_url) = icon_url {
+                        author = author.icon_url(ImageSource::url(icon_url.clone())?);
+                    }
+                    builder = builder.author(author.build());
+                }
+                if let Some(image) = &self.image {
+                    builder = builder.image(ImageSource::url(image.clone())?);
+                }
+            }
+
+            if let Some(description) = page.description {
+                builder = builder.description(description)?;
+            }
+
+            for field in page.fields {
+                let mut field_builder = EmbedFieldBuilder::new(field.name, field.value)?;
+                if field.inline {
+                    field_builder = field_builder.inline();
+                }
+                builder = builder.field(field_builder.build());
+            }
+
+            if index == last {
+                if let Some(footer) = &self.footer {
+                    builder = builder.footer(EmbedFooterBuilder::new(truncate(footer, FOOTER_LIMIT))?.build());
+                }
+                if let Some(timestamp) = &self.timestamp {
+                    builder = builder.timestamp(timestamp.clone());
+                }
+            }
+
+            embeds.push(builder.build()?);
+        }
+
+        Ok(embeds)
+    }
+
+    /// Plain text version of the embed, for when it's too big to send as embeds.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some((name, _)) = &self.author {
+            out += &format!("{}\n\n", name);
+        }
+        if let Some(title) = &self.title {
+            out += &format!("{}\n\n", title);
+        }
+        if let Some(description) = &self.description {
+            out += &format!("{}\n\n", description);
+        }
+        for field in &self.fields {
+            out += &format!("{}:\n{}\n\n", field.name, field.value);
+        }
+        if let Some(footer) = &self.footer {
+            out += footer;
+        }
+        out.trim_end().to_string()
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        // Reserve room for the parts that don't get split so every page fits no matter where they end up
+        let fixed = self
+            .title
+            .as_ref()
+            .map_or(0, |title| title.chars().count().min(TITLE_LIMIT))
+            + self
+                .author
+                .as_ref()
+                .map_or(0, |(name, _)| name.chars().count().min(AUTHOR_NAME_LIMIT))
+            + self
+                .footer
+                .as_ref()
+                .map_or(0, |footer| footer.chars().count().min(FOOTER_LIMIT));
+        let budget = TOTAL_LIMIT - fixed;
+
+        let mut pages = vec![];
+        let mut current = Page::default();
+
+        if let Some(description) = &self.description {
+            for chunk in split(description, DESCRIPTION_LIMIT) {
+                if current.description.is_some() {
+                    pages.push(std::mem::take(&mut current));
+                }
+                current.size += chunk.chars().count();
+                current.description = Some(chunk);
+            }
+        }
+
+        for field in &self.fields {
+            let name = truncate(&field.name, FIELD_NAME_LIMIT);
+            for value in split(&field.value, FIELD_VALUE_LIMIT) {
+                let size = name.chars().count() + value.chars().count();
+                if current.fields.len() == FIELD_COUNT_LIMIT || current.size + size > budget {
+                    pages.push(std::mem::take(&mut current));
+                }
+                current.size += size;
+                current.fields.push(Field {
+                    name: name.clone(),
+                    value,
+                    inline: field.inline,
+                });
+            }
+        }
+
+        pages.push(current);
+        pages
+    }
+}
+
+/// Cuts the content down to the limit (in characters), marking it with "..." when anything was cut off.
+pub fn truncate(content: &str, limit: usize) -> String {
+    if content.is_empty() {
+        return String::from(EMPTY);
+    }
+
+    if content.chars().count() <= limit {
+        content.to_string()
+    } else {
+        let mut out = content.chars().take(limit - 3).collect::<String>();
+        out += "...";
+        out
+    }
+}
+
+/// Splits the content into chunks that fit within the limit, breaking on newlines where possible.
+pub fn split(content: &str, limit: usize) -> Vec<String> {
+    if content.is_empty() {
+        return vec![String::from(EMPTY)];
+    }
+
+    let mut chunks = vec![];
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in content.split_inclusive('\n') {
+        let mut line_len = line.chars().count();
+        if current_len + line_len > limit && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        // A single line that doesn't fit by itself gets cut up wherever
+        let mut rest = line;
+        while line_len > limit {
+            let cut = rest.char_indices().nth(limit).map_or(rest.len(), |(index, _)| index);
+            chunks.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+            line_len -= limit;
+        }
+
+        current += rest;
+        current_len += line_len;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_prefers_newlines() {
+        let content = format!("{}\n{}\n", "a".repeat(6), "b".repeat(6));
+        assert_eq!(split(&content, 10), vec!["aaaaaa\n", "bbbbbb\n"]);
+        assert_eq!(split("ab\ncd", 10), vec!["ab\ncd"]);
+    }
+
+    #[test]
+    fn split_cuts_long_lines() {
+        let chunks = split(&"é".repeat(25), 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 10));
+        assert_eq!(chunks.concat(), "é".repeat(25));
+    }
+
+    #[test]
+    fn truncate_marks_cut_content() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("something longer", 10), "somethi...");
+        assert_eq!(truncate("", 10), EMPTY);
+    }
+
+    #[test]
+    fn pages_respect_limits() {
+        let mut embed = SafeEmbed::new().description("x".repeat(DESCRIPTION_LIMIT + 10));
+        for i in 0..40 {
+            embed = embed.field(format!("field {}", i), "y".repeat(FIELD_VALUE_LIMIT));
+        }
+
+        let pages = embed.pages();
+        assert!(pages.len() > 2);
+        assert!(pages[0].fields.is_empty());
+        for page in &pages {
+            assert!(page.fields.len() <= FIELD_COUNT_LIMIT);
+            assert!(page.size <= TOTAL_LIMIT);
+        }
+        assert_eq!(pages.iter().map(|page| page.fields.len()).sum::<usize>(), 40);
+    }
+
+    #[test]
+    fn long_field_values_are_split() {
+        let pages = SafeEmbed::new()
+            .field("name", "z".repeat(FIELD_VALUE_LIMIT * 2 + 1))
+            .pages();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].fields.len(), 3);
+        assert!(pages[0].fields.iter().all(|field| field.name == "name"));
+    }
+}
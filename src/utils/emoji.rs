@@ -27,6 +27,8 @@ define_emoji!(
     Left => "⬅️",
     Right => "➡️",
     Online => "🟢",
+    Trash => "🗑️",
+    Maybe => "❔",
 
     StaffBadge => "",
     PartnerBadge => "",
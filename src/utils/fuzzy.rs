@@ -0,0 +1,69 @@
+use std::cmp;
+
+/// The amount of single character insertions, deletions or substitutions to get from one string to the other.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = cmp::min(substitution, cmp::min(previous[j + 1], current[j]) + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Finds the candidates closest to the target, best match first.
+///
+/// Anything further away than a third of the target's length (with a minimum of one) isn't considered a match.
+pub fn closest_matches<'a>(
+    target: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<(&'a str, usize)> {
+    let target = target.to_lowercase();
+    let max_distance = cmp::max(1, target.chars().count() / 3);
+
+    let mut matches: Vec<(&'a str, usize)> = candidates
+        .into_iter()
+        .map(|candidate| (candidate, levenshtein(&target, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect();
+    matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculates_distance() {
+        assert_eq!(levenshtein("ban", "ban"), 0);
+        assert_eq!(levenshtein("bna", "ban"), 2);
+        assert_eq!(levenshtein("pnig", "ping"), 2);
+        assert_eq!(levenshtein("rol", "roll"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn finds_closest_matches() {
+        let candidates = ["ping", "roll", "rank", "role", "leaderboard"];
+        assert_eq!(
+            closest_matches("rol", candidates.iter().copied(), 3),
+            vec![("role", 1), ("roll", 1)]
+        );
+        assert_eq!(
+            closest_matches("leaderbord", candidates.iter().copied(), 3),
+            vec![("leaderboard", 1)]
+        );
+        assert!(closest_matches("xyz", candidates.iter().copied(), 3).is_empty());
+    }
+}
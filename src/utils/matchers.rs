@@ -36,6 +36,19 @@ pub fn get_mention(msg: &str) -> Option<u64> {
         .and_then(|m| m.as_str().parse().ok())
 }
 
+/// Extracts a snowflake from either a raw id or a user, role or channel mention.
+pub fn get_snowflake(msg: &str) -> Option<u64> {
+    SNOWFLAKE_MATCHER
+        .captures(msg)
+        .and_then(|c| c.get(1).or_else(|| c.get(2)))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+/// Extracts a user id from either a user mention or a raw id, anything else (like small numbers) isn't a user.
+pub fn get_user_snowflake(msg: &str) -> Option<u64> {
+    get_mention(msg).or_else(|| get_snowflake(msg).filter(|_| !msg.starts_with('<')))
+}
+
 pub fn contains_url(msg: &str) -> bool {
     URL_MATCHER.is_match(msg)
 }
@@ -56,6 +69,21 @@ pub fn contains_jump_link(msg: &str) -> bool {
     JUMP_LINK_MATCHER.is_match(msg)
 }
 
+/// Extracts the `(channel id, message id)` out of a message jump link.
+pub fn get_jump_link_parts(msg: &str) -> Option<(u64, u64)> {
+    JUMP_LINK_MATCHER.captures(msg).and_then(|c| {
+        let channel_id = c.get(1)?.as_str().parse().ok()?;
+        let message_id = c.get(2)?.as_str().parse().ok()?;
+        Some((channel_id, message_id))
+    })
+}
+
+/// Replaces anything that looks like a credential (bot tokens, webhook urls) so it can be safely logged.
+pub fn redact_sensitive(msg: &str) -> String {
+    let msg = WEBHOOK_URL_MATCHER.replace_all(msg, "[redacted webhook]");
+    TOKEN_MATCHER.replace_all(&msg, "[redacted token]").into_owned()
+}
+
 pub fn starts_with_number(msg: &str) -> bool {
     msg.chars().next().map(|c| c.is_numeric()).unwrap_or(false)
 }
@@ -113,10 +141,14 @@ lazy_static! {
     static ref CHANNEL_ID_MATCHER: Regex = Regex::new(r"<#([0-9]+)>").unwrap();
     static ref MENTION_MATCHER: Regex = Regex::new(r"<@!?\d+>").unwrap();
     static ref MENTION_MATCHER_SOLO: Regex = Regex::new(r"^<@!?(\d+)>$").unwrap();
+    static ref SNOWFLAKE_MATCHER: Regex = Regex::new(r"^(?:<(?:@[!&]?|#)(\d{15,21})>|(\d{15,21}))$").unwrap();
     static ref EMOJI_MATCHER: Regex = Regex::new(r"<(a?):([^:\n]+):([0-9]+)>").unwrap();
     static ref USERNAME_WITH_DISCRIMINATOR: Regex = Regex::new(r"([!#]*)#(\d{4})").unwrap();
     static ref JUMP_LINK_MATCHER: Regex =
-        Regex::new(r"https://(?:canary|ptb)?\.?discordapp.com/channels/\d*/(\d*)/(\d*)").unwrap();
+        Regex::new(r"https://(?:canary|ptb)?\.?discord(?:app)?\.com/channels/(?:\d+|@me)/(\d+)/(\d+)").unwrap();
+    static ref TOKEN_MATCHER: Regex = Regex::new(r"[\w-]{23,28}\.[\w-]{6,7}\.[\w-]{27,}").unwrap();
+    static ref WEBHOOK_URL_MATCHER: Regex =
+        Regex::new(r"https://(?:canary|ptb)?\.?discord(?:app)?\.com/api/(?:v\d+/)?webhooks/\d+/[\w-]+").unwrap();
     static ref URL_MATCHER: Regex = {
         RegexBuilder::new(r"((?:https?://)[a-z0-9]+(?:[-._][a-z0-9]+)*\.[a-z]{2,5}(?::[0-9]{1,5})?(?:/[^ \n<>]*)?)")
             .case_insensitive(true)
@@ -215,6 +247,20 @@ mod tests {
         assert_eq!(contains_jump_link(control), false);
     }
 
+    #[test]
+    fn jump_link_parts_work() {
+        let msg = "https://discord.com/channels/365498559174410241/365498559723732993/606145193766551552";
+        let msg2 = "look: https://ptb.discordapp.com/channels/365498559174410241/365498559723732993/606145193766551552";
+        let control = "https://discord.com/channels/365498559174410241";
+
+        assert_eq!(get_jump_link_parts(msg), Some((365498559723732993, 606145193766551552)));
+        assert_eq!(
+            get_jump_link_parts(msg2),
+            Some((365498559723732993, 606145193766551552))
+        );
+        assert_eq!(get_jump_link_parts(control), None);
+    }
+
     #[test]
     fn starts_with_number_works() {
         let msg = "1 birthday a year, only!";
@@ -244,4 +290,30 @@ mod tests {
 
         assert_eq!(contains_invite_link(control), false);
     }
+
+    #[test]
+    fn snowflakes_work() {
+        assert_eq!(get_snowflake("106354106196570112"), Some(106354106196570112));
+        assert_eq!(get_snowflake("<@!106354106196570112>"), Some(106354106196570112));
+        assert_eq!(get_snowflake("<@&365498559174410241>"), Some(365498559174410241));
+        assert_eq!(get_snowflake("<#365498559174410241>"), Some(365498559174410241));
+        assert_eq!(get_snowflake("12345"), None);
+        assert_eq!(get_snowflake("106354106196570112>"), None);
+        assert_eq!(get_snowflake("hello"), None);
+
+        assert_eq!(get_user_snowflake("<@106354106196570112>"), Some(106354106196570112));
+        assert_eq!(get_user_snowflake("106354106196570112"), Some(106354106196570112));
+        assert_eq!(get_user_snowflake("<#365498559174410241>"), None);
+        assert_eq!(get_user_snowflake("50"), None);
+    }
+
+    #[test]
+    fn redaction_works() {
+        let token = "redact NzA5NDMyMTIzNDU2Nzg5MDEy.XsUqNA.abcdefghijklmnopqrstuvwxyz0 please";
+        let webhook = "https://discord.com/api/webhooks/709432123456789012/aBcD-eFgH_iJkL";
+
+        assert_eq!(redact_sensitive(token), "redact [redacted token] please");
+        assert_eq!(redact_sensitive(webhook), "[redacted webhook]");
+        assert_eq!(redact_sensitive("<@123456789> spamming"), "<@123456789> spamming");
+    }
 }
@@ -1,9 +1,14 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use twilight_model::guild::Permissions;
 
 pub use emoji::*;
+pub mod dice;
+pub mod embeds;
 pub mod emoji;
+pub mod fuzzy;
 pub mod matchers;
 pub mod pattern;
+pub mod transcript;
 
 const MARKDOWN_REPALCEMENTS: &[&str; 7] = &["\\", "*", "_", "~", "|", "{", ">"];
 const DISCORD_EPOCH: i64 = 1_420_070_400_000;
@@ -62,6 +67,29 @@ pub fn clean(msg: &str, markdown: bool, links: bool, emotes: bool, lookalikes: b
     msg
 }
 
+/// Readable names for a set of permissions, like ``Manage Roles``.
+pub fn permission_names(permissions: Permissions) -> Vec<String> {
+    if permissions.is_empty() {
+        return vec![];
+    }
+
+    format!("{:?}", permissions)
+        .split(" | ")
+        .map(|flag| {
+            flag.split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect()
+}
+
 pub fn snowflake_timestamp(snowflake: u64) -> DateTime<Utc> {
     DateTime::from_utc(
         NaiveDateTime::from_timestamp(((snowflake as i64 >> 22) + DISCORD_EPOCH) / 1000, 0),
@@ -69,73 +97,74 @@ pub fn snowflake_timestamp(snowflake: u64) -> DateTime<Utc> {
     )
 }
 
-pub fn age(old: DateTime<Utc>, new: DateTime<Utc>, max_parts: i8) -> String {
-    let mut seconds = new.signed_duration_since(old).num_seconds();
-    let mut parts = 0;
-    let mut output = String::with_capacity(20);
-
-    let years = (seconds as f64 / (60.0 * 60.0 * 24.0 * 365.25)) as i64;
-    if years > 0 {
-        seconds -= (years as f64 * 60.0 * 60.0 * 24.0 * 365.25) as i64;
-        output += &format!("{} years ", years);
-        parts += 1;
+/// The lowest snowflake that could have been created at this time, handy for searching by id.
+pub fn snowflake_at(time: DateTime<Utc>) -> u64 {
+    ((time.timestamp_millis() - DISCORD_EPOCH).max(0) as u64) << 22
+}
 
-        if parts == max_parts {
-            return output;
+/// Parses a duration like ``30m``, ``12h`` or ``1w2d``.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
         }
-    }
 
-    let months = seconds / (60 * 60 * 24 * 30);
-    if months > 0 {
-        seconds -= months * 60 * 60 * 24 * 30;
-        output += &format!("{} months ", months);
-        parts += 1;
-
-        if parts == max_parts {
-            return output;
-        }
+        let amount = number.parse::<i64>().ok()?;
+        number.clear();
+        total = total
+            + match c.to_ascii_lowercase() {
+                's' => Duration::seconds(amount),
+                'm' => Duration::minutes(amount),
+                'h' => Duration::hours(amount),
+                'd' => Duration::days(amount),
+                'w' => Duration::weeks(amount),
+                _ => return None,
+            };
     }
 
-    let weeks = seconds / (60 * 60 * 24 * 7);
-    if weeks > 0 {
-        seconds -= weeks * 60 * 60 * 24 * 7;
-        output += &format!("{} weeks ", weeks);
-        parts += 1;
-        if parts == max_parts {
-            return output;
-        }
+    // A number without a unit at the end is most likely a mistake
+    if !number.is_empty() || total.is_zero() {
+        return None;
     }
+    Some(total)
+}
 
-    let days = seconds / (60 * 60 * 24);
-    if days > 0 {
-        seconds -= days * 60 * 60 * 24;
-        output += &format!("{} days ", days);
-        parts += 1;
-        if parts == max_parts {
-            return output;
-        }
-    }
+/// The internal fields packed into a discord snowflake next to its timestamp.
+pub struct SnowflakeParts {
+    pub worker_id: u64,
+    pub process_id: u64,
+    pub increment: u64,
+}
 
-    let hours = seconds / (60 * 60);
-    if hours > 0 {
-        seconds -= hours * 60 * 60;
-        output += &format!("{} hours ", hours);
-        parts += 1;
-        if parts == max_parts {
-            return output;
-        }
+pub fn snowflake_parts(snowflake: u64) -> SnowflakeParts {
+    SnowflakeParts {
+        worker_id: (snowflake & 0x3E_0000) >> 17,
+        process_id: (snowflake & 0x1_F000) >> 12,
+        increment: snowflake & 0xFFF,
     }
+}
 
-    let minutes = seconds / 60;
-    if minutes > 0 {
-        seconds -= minutes * 60;
-        output += &format!("{} minutes ", minutes);
-        parts += 1;
-        if parts == max_parts {
-            return output;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("1w2D"), Some(Duration::days(9)));
+        assert_eq!(parse_duration("12"), None);
+        assert_eq!(parse_duration("5y"), None);
+        assert_eq!(parse_duration(""), None);
     }
 
-    output += &format!("{} seconds", seconds);
-    output
+    #[test]
+    fn snowflakes_round_trip() {
+        let snowflake = 175_928_847_299_117_063;
+        let time = snowflake_timestamp(snowflake);
+        assert_eq!(snowflake_timestamp(snowflake_at(time)), time);
+        assert!(snowflake_at(time) <= snowflake);
+    }
 }
@@ -0,0 +1,196 @@
+use std::fmt::Write;
+
+use chrono_tz::Tz;
+use serde::Serialize;
+use twilight_model::id::{ChannelId, MessageId, UserId};
+
+use super::snowflake_timestamp;
+
+/// A single message to be included in a transcript.
+pub struct TranscriptEntry<'a> {
+    pub id: MessageId,
+    /// How the author should be shown, usually their full name with their id.
+    pub author: &'a str,
+    pub content: &'a str,
+}
+
+/// Renders a plain text transcript of messages that were deleted from a channel.
+///
+/// Entries are written in the order they are given, continuation lines of multi-line messages are
+/// indented so every message still starts with its timestamp. Timestamps are shown in the timezone of the guild.
+pub fn render_transcript(
+    channel_name: &str,
+    channel_id: ChannelId,
+    deleted: usize,
+    entries: &[TranscriptEntry],
+    timezone: Tz,
+) -> String {
+    let mut out = format!(
+        "Deleted messages from #{} ({})\n{} deleted, {} recovered from the message cache\n\n",
+        channel_name,
+        channel_id,
+        deleted,
+        entries.len()
+    );
+
+    for entry in entries {
+        let timestamp = snowflake_timestamp(entry.id.0)
+            .with_timezone(&timezone)
+            .format("%Y-%m-%d %H:%M:%S %Z");
+        let content = entry.content.replace('\n', "\n    ");
+        // Writing to a string can't fail.
+        let _ = writeln!(out, "[{}] {}: {}", timestamp, entry.author, content);
+    }
+
+    out
+}
+
+/// A message as it ends up in a channel export.
+#[derive(Debug, Serialize)]
+pub struct ExportedMessage {
+    pub id: MessageId,
+    pub author_id: UserId,
+    pub author: String,
+    pub content: String,
+    pub timestamp: String,
+    /// Links to the attachments, these stop working once the message is deleted.
+    pub attachments: Vec<String>,
+    pub embeds: usize,
+}
+
+#[derive(Serialize)]
+struct ChannelExport<'a> {
+    channel_name: &'a str,
+    channel_id: ChannelId,
+    messages: &'a [ExportedMessage],
+}
+
+/// Renders an export of a channel as json, messages are expected from oldest to newest.
+pub fn render_json(channel_name: &str, channel_id: ChannelId, messages: &[ExportedMessage]) -> String {
+    let export = ChannelExport {
+        channel_name,
+        channel_id,
+        messages,
+    };
+    // Only plain strings and numbers in there, this can't fail.
+    serde_json::to_string_pretty(&export).unwrap()
+}
+
+/// Renders an export of a channel as a standalone html page, messages are expected from oldest to newest.
+pub fn render_html(channel_name: &str, channel_id: ChannelId, messages: &[ExportedMessage]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>#{name}</title>\n\
+        <style>body{{font-family:sans-serif;background:#36393f;color:#dcddde}}.message{{margin:8px 0}}\
+        .author{{font-weight:bold;color:#fff}}.time{{font-size:0.8em;color:#72767d}}.content{{white-space:pre-wrap}}</style>\n\
+        </head>\n<body>\n<h1>#{name} ({id})</h1>\n",
+        name = escape_html(channel_name),
+        id = channel_id
+    );
+
+    for message in messages {
+        let _ = write!(
+            out,
+            "<div class=\"message\" id=\"{}\">\n<span class=\"author\" title=\"{}\">{}</span> \
+            <span class=\"time\">{}</span>\n<div class=\"content\">{}</div>\n",
+            message.id,
+            message.author_id,
+            escape_html(&message.author),
+            escape_html(&message.timestamp),
+            escape_html(&message.content)
+        );
+        for attachment in &message.attachments {
+            let attachment = escape_html(attachment);
+            let _ = writeln!(
+                out,
+                "<div class=\"attachment\"><a href=\"{0}\">{0}</a></div>",
+                attachment
+            );
+        }
+        out += "</div>\n";
+    }
+
+    out += "</body>\n</html>\n";
+    out
+}
+
+fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out += "&amp;",
+            '<' => out += "&lt;",
+            '>' => out += "&gt;",
+            '"' => out += "&quot;",
+            '\'' => out += "&#39;",
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_entries_in_order() {
+        let entries = [
+            TranscriptEntry {
+                id: MessageId(175928847299117063),
+                author: "Someone#0001 (1)",
+                content: "first",
+            },
+            TranscriptEntry {
+                id: MessageId(175928847299117064),
+                author: "Someone Else#0002 (2)",
+                content: "second\nline",
+            },
+        ];
+
+        let transcript = render_transcript("general", ChannelId(3), 5, &entries, Tz::UTC);
+        let lines: Vec<&str> = transcript.lines().collect();
+
+        assert_eq!(lines[0], "Deleted messages from #general (3)");
+        assert_eq!(lines[1], "5 deleted, 2 recovered from the message cache");
+        assert_eq!(lines[3], "[2016-04-30 11:18:25 UTC] Someone#0001 (1): first");
+        assert_eq!(lines[4], "[2016-04-30 11:18:25 UTC] Someone Else#0002 (2): second");
+        assert_eq!(lines[5], "    line");
+
+        let transcript = render_transcript("general", ChannelId(3), 5, &entries, Tz::Europe__Brussels);
+        assert_eq!(
+            transcript.lines().nth(3),
+            Some("[2016-04-30 13:18:25 CEST] Someone#0001 (1): first")
+        );
+    }
+
+    fn exported(content: &str) -> ExportedMessage {
+        ExportedMessage {
+            id: MessageId(175928847299117063),
+            author_id: UserId(1),
+            author: String::from("<Someone>#0001"),
+            content: content.to_string(),
+            timestamp: String::from("2016-04-30T11:18:25+00:00"),
+            attachments: vec![],
+            embeds: 0,
+        }
+    }
+
+    #[test]
+    fn html_exports_are_escaped() {
+        let html = render_html("general", ChannelId(3), &[exported("<script>alert('hi')</script>")]);
+
+        assert!(html.contains("&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;"));
+        assert!(html.contains("&lt;Someone&gt;#0001"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn json_exports_keep_the_content() {
+        let json = render_json("general", ChannelId(3), &[exported("a \"quote\"")]);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["channel_name"], "general");
+        assert_eq!(parsed["messages"][0]["content"], "a \"quote\"");
+        assert_eq!(parsed["messages"][0]["author"], "<Someone>#0001");
+    }
+}